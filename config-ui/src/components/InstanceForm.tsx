@@ -243,6 +243,7 @@ export function InstanceForm({
     const sanitizedDisabledPacks = disabledPacks.map((pack) => pack.trim()).filter(Boolean);
     const existingDefaults = instanceData?.toolConfig?.defaults;
     const existingAllowlist = instanceData?.toolConfig?.executeAllowlist;
+    const existingAllowedTools = instanceData?.toolConfig?.allowedTools;
     const nextToolConfig: NonNullable<InstanceDetails['toolConfig']> = {};
     if (sanitizedDisabledTools.length > 0) {
       nextToolConfig.disabledTools = sanitizedDisabledTools;
@@ -250,6 +251,9 @@ export function InstanceForm({
     if (sanitizedDisabledPacks.length > 0) {
       nextToolConfig.disabledPacks = sanitizedDisabledPacks;
     }
+    if (existingAllowedTools && existingAllowedTools.length > 0) {
+      nextToolConfig.allowedTools = existingAllowedTools;
+    }
     if (existingDefaults && Object.keys(existingDefaults).length > 0) {
       nextToolConfig.defaults = existingDefaults;
     }
@@ -261,7 +265,7 @@ export function InstanceForm({
     } else {
       delete data.toolConfig;
     }
-    // readOnly is JSON-managed; keep whatever was spread from instanceData.
+    // readOnly, headers, proxyAuth, lang and translateErrors are JSON-managed; keep whatever was spread from instanceData.
 
     onSave(name.trim(), data);
   };
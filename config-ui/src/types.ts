@@ -5,6 +5,8 @@ export interface ExecuteAllowlistEntry {
 
 export interface InstanceToolConfig {
   disabledTools?: string[];
+  /** When non-empty, the only tools permitted (`*` wildcards allowed); edited in JSON. */
+  allowedTools?: string[];
   /** Pack names disabled for this instance; edited in JSON until a UI is warranted. */
   disabledPacks?: string[];
   defaults?: Record<string, Record<string, unknown>>;
@@ -22,6 +24,14 @@ export interface InstanceDetails {
   tags?: string[];
   /** When true, mutating tools are denied for this instance even if write env is set. */
   readOnly?: boolean;
+  /** Extra HTTP headers sent with every request to this instance (e.g. gateway tokens). */
+  headers?: Record<string, string>;
+  /** Basic auth for a proxy in front of Odoo, sent before the Odoo login. */
+  proxyAuth?: { username: string; password: string; header?: 'authorization' | 'proxy-authorization' };
+  /** Language sent as context.lang with every request, e.g. en_US. */
+  lang?: string;
+  /** Rewrite well-known localized Odoo errors into English. */
+  translateErrors?: boolean;
   toolConfig?: InstanceToolConfig;
   [key: string]: unknown;
 }
@@ -1,13 +1,15 @@
-use serde::Serialize;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{error, info, warn};
 
-use crate::odoo::config::OdooInstanceConfig;
+use crate::mcp::auth_tokens::{self, ApiToken, TokenFile, TokenScope};
+use crate::odoo::config::{OdooInstanceConfig, tool_name_matches};
 
 const DEFAULT_TOOLS_JSON: &str = include_str!("../../config-defaults/tools.json");
 
@@ -33,6 +35,24 @@ pub struct ToolCatalogImportResult {
     pub drift: ToolCatalogDrift,
 }
 
+/// One entry of a token save request from the config UI.
+///
+/// `token` is normally omitted: the existing secret of the same name is kept, and new names
+/// get a generated secret that is returned once.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenUpdate {
+    pub name: String,
+    #[serde(default)]
+    pub token: Option<String>,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub scopes: BTreeSet<TokenScope>,
+    #[serde(default)]
+    pub tenant: Option<String>,
+}
+
 /// Result type for config operations that may need to notify the UI
 #[derive(Debug, Clone)]
 pub struct ConfigResult {
@@ -709,6 +729,91 @@ impl ConfigManager {
         Ok(ConfigResult::ok("Server configuration saved successfully"))
     }
 
+    /// Resolve the tokens.json path, preferring MCP_AUTH_TOKENS_JSON like the HTTP server does.
+    pub fn tokens_path(&self) -> PathBuf {
+        match std::env::var(auth_tokens::TOKENS_PATH_ENV) {
+            Ok(p) if !p.trim().is_empty() => PathBuf::from(p.trim()),
+            _ => self.config_dir.join("tokens.json"),
+        }
+    }
+
+    pub async fn load_tokens(&self) -> anyhow::Result<Vec<ApiToken>> {
+        auth_tokens::load_tokens(&self.tokens_path())
+    }
+
+    /// Replace the token list. Returns the secrets generated for new tokens by name.
+    pub async fn save_tokens(
+        &self,
+        updates: Vec<TokenUpdate>,
+    ) -> anyhow::Result<(ConfigResult, BTreeMap<String, String>)> {
+        let path = self.tokens_path();
+        let existing = match auth_tokens::load_tokens(&path) {
+            Ok(tokens) => tokens,
+            Err(e) => {
+                return Ok((
+                    ConfigResult::error(format!("Existing tokens.json is invalid: {e}")),
+                    BTreeMap::new(),
+                ));
+            }
+        };
+
+        let mut generated = BTreeMap::new();
+        let tokens: Vec<ApiToken> = updates
+            .into_iter()
+            .map(|update| {
+                let name = update.name.trim().to_string();
+                let token = match update.token.filter(|t| !t.trim().is_empty()) {
+                    Some(token) => token,
+                    None => match existing.iter().find(|t| t.name == name) {
+                        Some(previous) => previous.token.clone(),
+                        None => {
+                            let secret = auth_tokens::generate_secret();
+                            generated.insert(name.clone(), secret.clone());
+                            secret
+                        }
+                    },
+                };
+                ApiToken {
+                    name,
+                    token,
+                    expires_at: update.expires_at,
+                    scopes: update.scopes,
+                    tenant: update.tenant.filter(|t| !t.trim().is_empty()),
+                }
+            })
+            .collect();
+        if let Err(e) = auth_tokens::validate_tokens(&tokens) {
+            return Ok((ConfigResult::error(e), BTreeMap::new()));
+        }
+
+        if let Some(parent) = path.parent()
+            && let Err(e) = fs::create_dir_all(parent)
+        {
+            return Ok((
+                ConfigResult::error(format!("Failed to create config directory: {}", e)),
+                BTreeMap::new(),
+            ));
+        }
+        let backup = self.backup_file(&path);
+        let json_str = serde_json::to_string_pretty(&TokenFile { tokens })?;
+        if let Err(e) = fs::write(&path, &json_str) {
+            if let Some(ref backup_content) = backup {
+                self.restore_backup(&path, backup_content);
+                return Ok((
+                    ConfigResult::error(format!("Failed to save tokens: {}", e)).with_rollback(),
+                    BTreeMap::new(),
+                ));
+            }
+            return Ok((
+                ConfigResult::error(format!("Failed to save tokens: {}", e)),
+                BTreeMap::new(),
+            ));
+        }
+
+        info!("Saved API tokens to {:?}", path);
+        Ok((ConfigResult::ok("API tokens saved successfully"), generated))
+    }
+
     pub fn config_dir(&self) -> &PathBuf {
         &self.config_dir
     }
@@ -729,12 +834,21 @@ impl ConfigManager {
                 continue;
             };
 
-            for tool_name in &tool_config.disabled_tools {
-                if !tool_names.contains(tool_name) {
-                    return Err(format!(
-                        "Instance '{}' references unknown disabled tool '{}'",
-                        instance_name, tool_name
-                    ));
+            let lists = [
+                ("disabled", &tool_config.disabled_tools),
+                ("allowed", &tool_config.allowed_tools),
+            ];
+            for (list, patterns) in lists {
+                for pattern in patterns {
+                    if !tool_names
+                        .iter()
+                        .any(|name| tool_name_matches(pattern, name))
+                    {
+                        return Err(format!(
+                            "Instance '{}' references unknown {} tool '{}'",
+                            instance_name, list, pattern
+                        ));
+                    }
                 }
             }
 
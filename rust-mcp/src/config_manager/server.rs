@@ -4,7 +4,7 @@ use axum::{
     http::{HeaderMap, StatusCode},
     middleware::{self, Next},
     response::{IntoResponse, Response},
-    routing::{get, post},
+    routing::{delete, get, post},
 };
 use rand::Rng;
 use serde::{Deserialize, Serialize};
@@ -20,8 +20,11 @@ use tower_http::{
 };
 use tracing::{error, info, warn};
 
+use super::manager::TokenUpdate;
 use super::{ConfigManager, ConfigWatcher};
+use crate::mcp::auth_tokens::{TOKENS_PATH_ENV, redact_tokens};
 use crate::mcp::http::AuthConfig as HttpAuthConfig;
+use crate::mcp::session_directory::SessionDirectory;
 use crate::mcp::tools::OdooClientPool;
 use crate::odoo::config::{
     OdooInstanceConfig, RuntimeInstancesSourceKind, detect_runtime_instances_source,
@@ -107,6 +110,8 @@ struct AppState {
     http_auth_config: Option<HttpAuthConfig>,
     /// MCP client pool for hot-reload when instances.json changes (optional)
     pool: Option<OdooClientPool>,
+    /// Live MCP sessions for the admin sessions API (optional - only when HTTP transport is used)
+    mcp_sessions: Option<SessionDirectory>,
 }
 
 // Session token validity duration (24 hours)
@@ -170,6 +175,7 @@ pub async fn start_config_server(
     config_dir: std::path::PathBuf,
     http_auth_config: Option<HttpAuthConfig>,
     pool: Option<OdooClientPool>,
+    mcp_sessions: Option<SessionDirectory>,
 ) -> anyhow::Result<()> {
     let config_manager = ConfigManager::new(config_dir.clone());
     let config_watcher = Arc::new(ConfigWatcher::new(config_dir.clone())?);
@@ -190,6 +196,7 @@ pub async fn start_config_server(
         env_file_path,
         http_auth_config,
         pool,
+        mcp_sessions,
     };
 
     // Serve static files from dist directory (React app)
@@ -243,6 +250,10 @@ pub async fn start_config_server(
         .route("/api/config/prompts", post(update_prompts))
         .route("/api/config/server", get(get_server))
         .route("/api/config/server", post(update_server))
+        .route("/api/config/tokens", get(get_tokens))
+        .route("/api/config/tokens", post(update_tokens))
+        .route("/api/sessions", get(list_mcp_sessions))
+        .route("/api/sessions/{id}", delete(terminate_mcp_session))
         // Auth management endpoints (protected)
         .route("/api/auth/change-password", post(change_password))
         .route("/api/auth/mcp-auth-status", get(mcp_token_status))
@@ -1518,14 +1529,132 @@ async fn update_server(
     }
 }
 
+/// List MCP API tokens with their secrets redacted.
+async fn get_tokens(State(state): State<AppState>) -> impl IntoResponse {
+    match state.config_manager.load_tokens().await {
+        Ok(tokens) => (
+            StatusCode::OK,
+            Json(json!({
+                "path": state.config_manager.tokens_path(),
+                "tokens": redact_tokens(&tokens, chrono::Utc::now())
+            })),
+        )
+            .into_response(),
+        Err(e) => {
+            error!("Failed to load tokens: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": e.to_string() })),
+            )
+                .into_response()
+        }
+    }
+}
+
+fn mcp_sessions_unavailable() -> Response {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(json!({ "error": "MCP sessions are only tracked by the HTTP transport" })),
+    )
+        .into_response()
+}
+
+/// List live MCP sessions with their age, protocol version, instances and recent tools.
+async fn list_mcp_sessions(State(state): State<AppState>) -> Response {
+    let Some(directory) = &state.mcp_sessions else {
+        return mcp_sessions_unavailable();
+    };
+    (
+        StatusCode::OK,
+        Json(json!({ "sessions": directory.list() })),
+    )
+        .into_response()
+}
+
+/// Terminate a live MCP session; its SSE streams receive a final expiry event and close.
+async fn terminate_mcp_session(State(state): State<AppState>, Path(id): Path<String>) -> Response {
+    let Some(directory) = &state.mcp_sessions else {
+        return mcp_sessions_unavailable();
+    };
+    if !directory.terminate(&id) {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": format!("Unknown session '{id}'") })),
+        )
+            .into_response();
+    }
+    info!("Terminated MCP session {} from the config server", id);
+    (
+        StatusCode::OK,
+        Json(json!({ "success": true, "terminated": id })),
+    )
+        .into_response()
+}
+
+#[derive(Deserialize)]
+struct UpdateTokensRequest {
+    tokens: Vec<TokenUpdate>,
+}
+
+/// Replace the MCP API token list and hot-reload HTTP auth.
+///
+/// Secrets generated for new tokens are returned once in `generated` and never shown again.
+async fn update_tokens(
+    State(state): State<AppState>,
+    Json(payload): Json<UpdateTokensRequest>,
+) -> impl IntoResponse {
+    match state.config_manager.save_tokens(payload.tokens).await {
+        Ok((result, generated)) if result.success => {
+            if std::env::var(TOKENS_PATH_ENV).is_err() {
+                // SAFETY: Same single-writer pattern as MCP_AUTH_TOKEN updates above.
+                unsafe {
+                    std::env::set_var(TOKENS_PATH_ENV, state.config_manager.tokens_path());
+                }
+            }
+            if let Some(ref http_auth) = state.http_auth_config {
+                http_auth.reload().await;
+            }
+            (
+                StatusCode::OK,
+                Json(json!({
+                    "status": "saved",
+                    "message": result.message,
+                    "generated": generated
+                })),
+            )
+                .into_response()
+        }
+        Ok((result, _)) => {
+            error!("Failed to save tokens: {}", result.message);
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": result.message,
+                    "rollback": result.rollback_performed
+                })),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            error!("Unexpected error saving tokens: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": format!("Unexpected error: {}", e) })),
+            )
+                .into_response()
+        }
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::await_holding_lock)] // The process environment requires serialized tests.
 mod tests {
     use super::{
         AppState, AuthConfig, DynamicAuthConfig, InstanceEnvSyncState, build_instances_sync_status,
-        deactivate_env_var, find_docs_dir_from, read_active_env_vars, sync_instances_to_env,
-        test_instance_connection, update_env_var,
+        deactivate_env_var, find_docs_dir_from, list_mcp_sessions, read_active_env_vars,
+        sync_instances_to_env, terminate_mcp_session, test_instance_connection, update_env_var,
     };
+    use crate::mcp::session_directory::SessionDirectory;
     use crate::{
         TEST_ENV_MUTEX,
         config_manager::{ConfigManager, ConfigWatcher},
@@ -1612,6 +1741,7 @@ mod tests {
             env_file_path: env_file.to_path_buf(),
             http_auth_config: None,
             pool: None,
+            mcp_sessions: None,
         }
     }
 
@@ -1904,4 +2034,37 @@ mod tests {
         assert!(!env_text.contains("\"aliases\""));
         assert!(env_text.contains("\"tags\""));
     }
+
+    #[tokio::test]
+    async fn sessions_api_lists_and_terminates_mcp_sessions() {
+        let temp_dir = TempDir::new().unwrap();
+        let env_file = temp_dir.path().join("env");
+        let directory = SessionDirectory::default();
+        let mut terminations = directory.subscribe_terminations();
+        directory.open("abc", "2025-11-25", None, Some("ci"));
+        directory.record_tool("abc", "odoo_search", Some("prod"));
+
+        let mut state = make_test_state(temp_dir.path(), &env_file);
+        let unavailable = list_mcp_sessions(State(state.clone())).await;
+        assert_eq!(unavailable.status(), StatusCode::SERVICE_UNAVAILABLE);
+        state.mcp_sessions = Some(directory.clone());
+
+        let response = list_mcp_sessions(State(state.clone())).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value =
+            serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await.unwrap())
+                .unwrap();
+        let session = &body["sessions"][0];
+        assert_eq!(session["id"], "abc");
+        assert_eq!(session["protocolVersion"], "2025-11-25");
+        assert_eq!(session["token"], "ci");
+        assert_eq!(session["instances"], json!(["prod"]));
+        assert_eq!(session["recentTools"][0]["tool"], "odoo_search");
+
+        let response = terminate_mcp_session(State(state.clone()), Path("abc".to_string())).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(terminations.recv().await.unwrap(), "abc");
+        let response = terminate_mcp_session(State(state), Path("abc".to_string())).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
 }
@@ -6,6 +6,8 @@ pub mod cleanup;
 pub mod config_manager;
 pub mod mcp;
 pub mod odoo;
+pub mod packs;
+pub mod telemetry;
 
 #[cfg(test)]
 pub static TEST_ENV_MUTEX: std::sync::Mutex<()> = std::sync::Mutex::new(());
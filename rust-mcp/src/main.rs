@@ -9,14 +9,24 @@ use mcp_rust_sdk::transport::websocket::WebSocketTransport;
 use tokio::net::TcpListener;
 use tokio_tungstenite::accept_async;
 use tracing::{error, info, warn};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer, fmt};
 
 use rust_mcp::config_manager::start_config_server;
 use rust_mcp::mcp::McpOdooHandler;
+use rust_mcp::mcp::audit::audit_log_from_env;
+use rust_mcp::mcp::audit_export::{AuditExporter, ExportConfig};
+use rust_mcp::mcp::config_check::{self, CheckOptions};
 use rust_mcp::mcp::cursor_stdio::CursorStdioTransport;
+use rust_mcp::mcp::event_sink::{EventSink, EventSinkConfig};
 use rust_mcp::mcp::http as mcp_http;
 use rust_mcp::mcp::registry::Registry;
+use rust_mcp::mcp::relay::{self, RelayConfig};
+use rust_mcp::mcp::retention;
 use rust_mcp::mcp::runtime::ServerCompat;
 use rust_mcp::mcp::tools::OdooClientPool;
+use rust_mcp::telemetry;
 
 /// Get config directory based on context:
 /// - If running as root/systemd service: /etc/odoo-rust-mcp
@@ -293,6 +303,11 @@ fn setup_user_config() {
         );
     }
 
+    // API tokens and tenants are per installation, so they always live in the user config
+    // directory.
+    set_default_env("MCP_AUTH_TOKENS_JSON", config_dir.join("tokens.json"));
+    set_default_env("MCP_TENANTS_JSON", config_dir.join("tenants.json"));
+
     // Set default MCP config paths if not already set
     // Priority: 1) Already set in env, 2) Homebrew/APT share dir, 3) User config dir
     if let Some(share_dir) = get_share_dir() {
@@ -627,6 +642,10 @@ enum TransportMode {
     Stdio,
     Ws,
     Http,
+    /// stdio for the local editor plus HTTP on --listen, sharing one handler
+    StdioHttp,
+    /// stdio for the local editor, forwarded to a remote server at MCP_RELAY_URL
+    Relay,
 }
 
 #[derive(Debug, Parser)]
@@ -635,11 +654,12 @@ struct Cli {
     #[command(subcommand)]
     command: Option<Command>,
 
-    /// Transport mode (stdio for Claude Desktop, ws for standalone server)
+    /// Transport mode (stdio for Claude Desktop, ws/http for standalone server, stdio-http for
+    /// both, relay for stdio backed by a remote server)
     #[arg(long, value_enum, default_value_t = TransportMode::Stdio)]
     transport: TransportMode,
 
-    /// Listen address for ws mode, e.g. 0.0.0.0:8787
+    /// Listen address for ws/http modes, e.g. 0.0.0.0:8787
     #[arg(long, default_value = "127.0.0.1:8787")]
     listen: String,
 
@@ -647,6 +667,10 @@ struct Cli {
     #[arg(long, env = "ODOO_ENABLE_CLEANUP_TOOLS", default_value_t = false)]
     enable_cleanup_tools: bool,
 
+    /// Enable module management tools (install/upgrade/uninstall; off by default)
+    #[arg(long, env = "ODOO_ENABLE_ADMIN_TOOLS", default_value_t = false)]
+    enable_admin_tools: bool,
+
     /// Enable config server on separate port (default: 3008, inspired by Peugeot 3008)
     #[arg(long, env = "ODOO_CONFIG_SERVER_PORT", default_value = "3008")]
     config_server_port: u16,
@@ -665,6 +689,14 @@ enum Command {
         #[arg(long)]
         env_file: Option<PathBuf>,
     },
+    /// Lint env vars, instances, tools.json, prompts.json, server.json and auth/TLS settings,
+    /// printing JSON diagnostics. Exits 1 on errors (2 on warnings with --strict).
+    #[command(about = "Lint the configuration and print JSON diagnostics")]
+    Check {
+        /// Also fail (exit code 2) when there are warnings
+        #[arg(long)]
+        strict: bool,
+    },
 }
 
 #[tokio::main]
@@ -675,41 +707,47 @@ async fn main() -> anyhow::Result<()> {
     // Initialize tracing - for stdio mode, we must use stderr only
     // because stdout is reserved for JSON-RPC messages
     let json_logs = std::env::var("ODOO_LOG_FORMAT").is_ok_and(|value| value == "json");
-    let stdio = matches!(&cli.transport, TransportMode::Stdio);
-    match (stdio, json_logs) {
-        (true, true) => {
-            tracing_subscriber::fmt()
-                .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+    let stdio = matches!(
+        &cli.transport,
+        TransportMode::Stdio | TransportMode::StdioHttp | TransportMode::Relay
+    );
+    // The env filter applies to log output only, so OTLP export still sees request spans.
+    let log_layer: Box<dyn Layer<tracing_subscriber::Registry> + Send + Sync> =
+        match (stdio, json_logs) {
+            (true, true) => fmt::layer()
                 .with_writer(std::io::stderr)
                 .with_ansi(false)
                 .json()
-                .init();
-        }
-        (false, true) => {
-            tracing_subscriber::fmt()
-                .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-                .json()
-                .init();
-        }
-        (true, false) => {
+                .boxed(),
+            (false, true) => fmt::layer().json().boxed(),
             // Stdio mode: log to stderr only, no ANSI colors to avoid issues
-            tracing_subscriber::fmt()
-                .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+            (true, false) => fmt::layer()
                 .with_writer(std::io::stderr)
                 .with_ansi(false)
-                .init();
-        }
-        (false, false) => {
+                .boxed(),
             // HTTP/WS modes: normal logging to stdout with colors
-            tracing_subscriber::fmt()
-                .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-                .init();
-        }
-    }
+            (false, false) => fmt::layer().boxed(),
+        };
+    tracing_subscriber::registry()
+        .with(log_layer.with_filter(EnvFilter::from_default_env()))
+        .with(telemetry::layer_from_env())
+        .init();
 
     // Auto-load user config from ~/.config/odoo-rust-mcp/
     setup_user_config();
 
+    // `check` lints the configuration as found, so it runs before anything is synced back.
+    if let Some(Command::Check { strict }) = &cli.command {
+        let listen = matches!(
+            cli.transport,
+            TransportMode::Http | TransportMode::Ws | TransportMode::StdioHttp
+        )
+        .then(|| cli.listen.clone());
+        let report = config_check::run(&CheckOptions::from_env(listen));
+        println!("{}", serde_json::to_string_pretty(&report.to_json())?);
+        std::process::exit(report.exit_code(*strict));
+    }
+
     // Direction 2: If ODOO_INSTANCES env var has instances not yet in instances.json,
     // merge them in so they are visible and editable via the Config UI.
     sync_env_instances_to_file();
@@ -720,9 +758,15 @@ async fn main() -> anyhow::Result<()> {
             Command::ValidateConfig { env_file } => {
                 return validate_config(env_file).await;
             }
+            Command::Check { .. } => unreachable!("check exits above"),
         }
     }
 
+    // The relay only forwards to a remote server: no Odoo instances or local config needed
+    if matches!(cli.transport, TransportMode::Relay) {
+        return relay::run_stdio(RelayConfig::from_env()?).await;
+    }
+
     // Otherwise, start the server
     let pool = OdooClientPool::from_env()?;
     let registry = Arc::new(Registry::from_env());
@@ -731,10 +775,31 @@ async fn main() -> anyhow::Result<()> {
 
     // Clone pool for the config server before moving it into the handler
     let pool_for_config_server = pool.clone();
+    pool.spawn_health_monitor();
+    let event_sink = EventSinkConfig::from_env()?.map(EventSink::spawn);
+    if let Some(sink) = &event_sink {
+        sink.forward_pool_events(&pool);
+    }
 
     // Cleanup tool gating is handled via tool guards (e.g. requiresEnvTrue=ODOO_ENABLE_CLEANUP_TOOLS).
     // We keep the CLI flag for compatibility, but it only affects the env var via clap env binding.
-    let handler = Arc::new(McpOdooHandler::new(pool, registry));
+    let mut handler = McpOdooHandler::new(pool, registry.clone());
+    if let Some(log) = audit_log_from_env()? {
+        info!("Recording tool calls in the {} audit log", log.backend());
+        retention::spawn_janitor(registry, log.clone());
+        if let Some(config) = ExportConfig::from_env()? {
+            info!("Exporting audit entries to {}", config.dir.display());
+            let exporter = AuditExporter::new(log.clone(), config);
+            exporter.clone().spawn();
+            handler = handler.with_audit_exporter(exporter);
+        }
+        handler = handler.with_audit_log(log);
+    }
+    if let Some(sink) = event_sink {
+        handler = handler.with_event_sink(sink);
+    }
+    let handler = Arc::new(handler);
+    handler.spawn_list_changed_notifier();
 
     // Create shared HTTP auth config (supports hot-reload)
     let http_auth_config = mcp_http::AuthConfig::from_env();
@@ -747,12 +812,14 @@ async fn main() -> anyhow::Result<()> {
     // Clone auth config for config server to trigger reloads
     let auth_config_for_config_server = http_auth_config.clone();
     let config_server_port = cli.config_server_port;
+    let sessions_for_config_server = handler.sessions().clone();
     tokio::spawn(async move {
         if let Err(e) = start_config_server(
             config_server_port,
             config_dir,
             Some(auth_config_for_config_server),
             Some(pool_for_config_server),
+            Some(sessions_for_config_server),
         )
         .await
         {
@@ -776,6 +843,10 @@ async fn main() -> anyhow::Result<()> {
         TransportMode::Stdio => run_stdio(handler).await?,
         TransportMode::Ws => run_ws(handler, &cli.listen).await?,
         TransportMode::Http => run_http_with_auth(handler, &cli.listen, http_auth_config).await?,
+        TransportMode::StdioHttp => {
+            run_stdio_and_http(handler, &cli.listen, http_auth_config).await?
+        }
+        TransportMode::Relay => unreachable!("relay mode returns before the handler is built"),
     }
 
     Ok(())
@@ -842,6 +913,7 @@ async fn validate_config(_env_file: Option<PathBuf>) -> anyhow::Result<()> {
 
 async fn run_stdio(handler: Arc<McpOdooHandler>) -> anyhow::Result<()> {
     let (transport, _sender) = CursorStdioTransport::new();
+    handler.server_info().note_transport("stdio");
     let server = ServerCompat::new(Arc::new(transport), handler);
 
     info!("MCP server starting (stdio)");
@@ -851,9 +923,27 @@ async fn run_stdio(handler: Arc<McpOdooHandler>) -> anyhow::Result<()> {
         .map_err(|e| anyhow::anyhow!(e.to_string()))
 }
 
+/// Serve stdio and HTTP from one process and one handler (so one client pool and cache).
+///
+/// The editor owns the process: when stdin closes, the HTTP listener shuts down with it.
+async fn run_stdio_and_http(
+    handler: Arc<McpOdooHandler>,
+    listen: &str,
+    auth: mcp_http::AuthConfig,
+) -> anyhow::Result<()> {
+    tokio::select! {
+        result = run_stdio(handler.clone()) => {
+            info!("stdio closed; stopping HTTP transport");
+            result
+        }
+        result = run_http_with_auth(handler, listen, auth) => result,
+    }
+}
+
 async fn run_ws(handler: Arc<McpOdooHandler>, listen: &str) -> anyhow::Result<()> {
     let listener = TcpListener::bind(listen).await?;
     info!("MCP server listening (ws) on {}", listen);
+    handler.server_info().note_transport("ws");
 
     loop {
         let (stream, addr) = listener.accept().await?;
@@ -885,7 +975,8 @@ async fn run_http_with_auth(
 
 #[cfg(test)]
 mod tests {
-    use super::{env_value_for_log, should_auto_set_instances_json};
+    use super::{Cli, TransportMode, env_value_for_log, should_auto_set_instances_json};
+    use clap::Parser;
     use std::sync::Mutex;
 
     static ENV_LOCK: Mutex<()> = Mutex::new(());
@@ -927,6 +1018,14 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parses_combined_stdio_http_transport() {
+        let cli = Cli::try_parse_from(["odoo-rust-mcp", "--transport", "stdio-http"]).unwrap();
+        assert!(matches!(cli.transport, TransportMode::StdioHttp));
+        let cli = Cli::try_parse_from(["odoo-rust-mcp", "--transport", "relay"]).unwrap();
+        assert!(matches!(cli.transport, TransportMode::Relay));
+    }
+
     #[test]
     fn auto_sets_instances_json_when_no_env_source_is_present() {
         let _lock = ENV_LOCK.lock().expect("env test lock poisoned");
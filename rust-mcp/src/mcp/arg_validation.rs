@@ -0,0 +1,109 @@
+//! Tool arguments checked against the tool's `inputSchema` before any op runs.
+//!
+//! Every violation is reported at once, each prefixed with the JSON path of the offending
+//! value (`$.domain[0]`), instead of the single serde error the typed op arguments would give.
+
+use serde_json::Value;
+
+/// Compile `schema` once to catch schemas the validator cannot use (checked at registry load).
+pub fn check_schema(schema: &Value) -> Result<(), String> {
+    jsonschema::validator_for(schema)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// All violations of `schema` by `args`, as `"<path>: <message>"`, ordered by path.
+pub fn validate_args(schema: &Value, args: &Value) -> Result<(), Vec<String>> {
+    let validator = jsonschema::validator_for(schema)
+        .map_err(|e| vec![format!("$: inputSchema is not usable: {e}")])?;
+    let mut violations: Vec<(String, String)> = validator
+        .iter_errors(args)
+        .map(|error| (json_path(error.instance_path.as_str()), error.to_string()))
+        .collect();
+    if violations.is_empty() {
+        return Ok(());
+    }
+    violations.sort();
+    Err(violations
+        .into_iter()
+        .map(|(path, message)| format!("{path}: {message}"))
+        .collect())
+}
+
+/// `/domain/0/field~1x` as `$.domain[0]["field/x"]`.
+fn json_path(pointer: &str) -> String {
+    let mut path = String::from("$");
+    for segment in pointer.split('/').skip(1) {
+        let segment = segment.replace("~1", "/").replace("~0", "~");
+        if !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_digit()) {
+            path.push_str(&format!("[{segment}]"));
+        } else if is_identifier(&segment) {
+            path.push('.');
+            path.push_str(&segment);
+        } else {
+            path.push_str(&format!("[{}]", Value::String(segment)));
+        }
+    }
+    path
+}
+
+fn is_identifier(segment: &str) -> bool {
+    let mut chars = segment.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "instance": {"type": "string"},
+                "model": {"type": "string"},
+                "ids": {"type": "array", "items": {"type": "integer"}},
+                "limit": {"type": "integer", "minimum": 1}
+            },
+            "required": ["instance", "model"],
+            "additionalProperties": false
+        })
+    }
+
+    #[test]
+    fn valid_args_pass() {
+        let args = json!({"instance": "default", "model": "res.partner", "ids": [1, 2]});
+        assert!(validate_args(&schema(), &args).is_ok());
+    }
+
+    #[test]
+    fn every_violation_is_reported_with_its_path() {
+        let args = json!({"instance": "default", "ids": [1, "two"], "limit": 0, "extra": true});
+        let violations = validate_args(&schema(), &args).unwrap_err();
+        assert_eq!(violations.len(), 4, "{violations:?}");
+        assert!(violations[0].starts_with("$: "));
+        assert!(
+            violations
+                .iter()
+                .any(|v| v.contains("\"model\" is a required property"))
+        );
+        assert!(violations.iter().any(|v| v.contains("extra")));
+        assert!(violations.iter().any(|v| v.starts_with("$.ids[1]: ")));
+        assert!(violations.iter().any(|v| v.starts_with("$.limit: ")));
+    }
+
+    #[test]
+    fn json_path_escapes_odd_keys() {
+        assert_eq!(json_path(""), "$");
+        assert_eq!(json_path("/domain/0/2"), "$.domain[0][2]");
+        assert_eq!(
+            json_path("/values/x_studio~1field"),
+            "$.values[\"x_studio/field\"]"
+        );
+        assert_eq!(json_path("/values/ a"), "$.values[\" a\"]");
+    }
+}
@@ -0,0 +1,732 @@
+//! Durable audit trail of tool calls.
+//!
+//! Every `tools/call` (including unknown tools and denied calls) is recorded with the session,
+//! the API token name, the target instance/model, the record ids it touched and its outcome.
+//! Backends are selected with `MCP_AUDIT_LOG`:
+//! - unset / `off` (default): no audit trail beyond the tracing log
+//! - `jsonl`: one JSON object per line at `MCP_AUDIT_LOG_PATH` (default `mcp-audit.jsonl`),
+//!   rotated to `<path>.1` .. `<path>.N` past `MCP_AUDIT_LOG_MAX_BYTES` (default 10 MiB,
+//!   `N` = `MCP_AUDIT_LOG_MAX_FILES`, default 5)
+//! - `sqlite`: table `mcp_audit` in `MCP_AUDIT_LOG_PATH` (default `mcp-audit.db`)
+//!
+//! Entries are queried over HTTP with `GET /audit` (admin scope) and pruned by the retention
+//! janitor (see [`crate::mcp::retention`]).
+
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::mcp::canonical;
+
+const DEFAULT_MAX_BYTES: u64 = 10 * 1024 * 1024;
+const DEFAULT_MAX_FILES: usize = 5;
+const DEFAULT_QUERY_LIMIT: usize = 100;
+const MAX_QUERY_LIMIT: usize = 1000;
+
+/// One tool call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    /// RFC 3339 UTC with milliseconds; sorts lexicographically.
+    pub timestamp: String,
+    /// HTTP session id or "stdio"; None for transports without sessions.
+    #[serde(default)]
+    pub session: Option<String>,
+    /// Name of the API token the call authenticated with.
+    #[serde(default)]
+    pub token: Option<String>,
+    /// Tenant of that token (see [`crate::mcp::tenants`]).
+    #[serde(default)]
+    pub tenant: Option<String>,
+    pub tool: String,
+    #[serde(default)]
+    pub instance: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Record ids named in the arguments plus ids reported back (e.g. created records).
+    #[serde(default)]
+    pub ids: Vec<i64>,
+    /// "success", "error", "cancelled" or "unknown_tool".
+    pub outcome: String,
+    #[serde(default)]
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    /// Canonical digest of the arguments, to spot retries of the same call.
+    pub args_digest: String,
+}
+
+impl AuditEntry {
+    /// Entry for a call starting now; complete it with [`AuditEntry::finish`].
+    pub fn begin(session: &str, token: Option<&str>, tool: &str, args: &Value) -> Self {
+        let text = |key: &str| args.get(key).and_then(Value::as_str).map(str::to_string);
+        let mut ids = Vec::new();
+        collect_ids(args, &mut ids);
+        Self {
+            timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            session: (!session.is_empty()).then(|| session.to_string()),
+            token: token.map(str::to_string),
+            tenant: None,
+            tool: tool.to_string(),
+            instance: text("instance"),
+            model: text("model"),
+            ids,
+            outcome: String::new(),
+            error: None,
+            duration_ms: 0,
+            args_digest: canonical::digest(args),
+        }
+    }
+
+    /// Record the outcome. `result` is the tool result on success, whose text payload may
+    /// report further ids (`{"id": 7}` from create, `{"ids": [..]}` from batch creates).
+    pub fn finish(
+        mut self,
+        outcome: &str,
+        result: Option<&Value>,
+        error: Option<String>,
+        elapsed: Duration,
+    ) -> Self {
+        if let Some(payload) = result.and_then(result_payload) {
+            collect_ids(&payload, &mut self.ids);
+        }
+        self.ids.sort_unstable();
+        self.ids.dedup();
+        self.outcome = outcome.to_string();
+        self.error = error;
+        self.duration_ms = elapsed.as_millis() as u64;
+        self
+    }
+
+    fn matches(&self, query: &AuditQuery) -> bool {
+        let eq = |want: &Option<String>, have: Option<&str>| {
+            want.as_deref().is_none_or(|want| have == Some(want))
+        };
+        query
+            .since
+            .as_deref()
+            .is_none_or(|since| self.timestamp.as_str() >= since)
+            && query
+                .until
+                .as_deref()
+                .is_none_or(|until| self.timestamp.as_str() < until)
+            && eq(&query.tool, Some(&self.tool))
+            && eq(&query.instance, self.instance.as_deref())
+            && eq(&query.model, self.model.as_deref())
+            && eq(&query.token, self.token.as_deref())
+            && eq(&query.tenant, self.tenant.as_deref())
+            && eq(&query.session, self.session.as_deref())
+            && eq(&query.outcome, Some(&self.outcome))
+    }
+}
+
+/// `id`, `ids` and `record_id`/`res_id` members of an argument or result object.
+fn collect_ids(value: &Value, ids: &mut Vec<i64>) {
+    for key in ["id", "record_id", "res_id"] {
+        if let Some(id) = value.get(key).and_then(Value::as_i64) {
+            ids.push(id);
+        }
+    }
+    if let Some(list) = value.get("ids").and_then(Value::as_array) {
+        ids.extend(list.iter().filter_map(Value::as_i64));
+    }
+}
+
+/// JSON payload of a text tool result.
+fn result_payload(result: &Value) -> Option<Value> {
+    let text = result.pointer("/content/0/text")?.as_str()?;
+    serde_json::from_str(text).ok()
+}
+
+/// Filters for [`AuditLog::query`]; all optional and combined with AND.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditQuery {
+    /// Inclusive lower bound, RFC 3339 UTC (e.g. `2026-01-31T00:00:00Z`).
+    pub since: Option<String>,
+    /// Exclusive upper bound, RFC 3339 UTC.
+    pub until: Option<String>,
+    pub tool: Option<String>,
+    pub instance: Option<String>,
+    pub model: Option<String>,
+    pub token: Option<String>,
+    pub tenant: Option<String>,
+    pub session: Option<String>,
+    pub outcome: Option<String>,
+    /// Newest entries returned (default 100, at most 1000).
+    pub limit: Option<usize>,
+}
+
+impl AuditQuery {
+    fn limit(&self) -> usize {
+        self.limit
+            .unwrap_or(DEFAULT_QUERY_LIMIT)
+            .clamp(1, MAX_QUERY_LIMIT)
+    }
+}
+
+#[async_trait]
+pub trait AuditLog: Send + Sync {
+    /// Short backend name for logs ("jsonl", "sqlite").
+    fn backend(&self) -> &'static str;
+
+    async fn record(&self, entry: &AuditEntry) -> anyhow::Result<()>;
+
+    /// Matching entries, newest first.
+    async fn query(&self, query: &AuditQuery) -> anyhow::Result<Vec<AuditEntry>>;
+
+    /// Delete entries stamped before `before` (RFC 3339) and all but the newest `keep`;
+    /// returns how many were removed.
+    async fn prune(&self, before: Option<&str>, keep: Option<u64>) -> anyhow::Result<u64>;
+}
+
+/// Append-only JSONL file with size-based rotation.
+#[derive(Clone)]
+pub struct JsonlAuditLog {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    lock: Arc<Mutex<()>>,
+}
+
+impl JsonlAuditLog {
+    pub fn new(path: impl Into<PathBuf>, max_bytes: u64, max_files: usize) -> Self {
+        Self {
+            path: path.into(),
+            max_bytes,
+            max_files: max_files.max(1),
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// `path` for generation 0, `path.N` for rotated generations.
+    fn generation(&self, n: usize) -> PathBuf {
+        if n == 0 {
+            return self.path.clone();
+        }
+        let mut name = self.path.as_os_str().to_os_string();
+        name.push(format!(".{n}"));
+        PathBuf::from(name)
+    }
+
+    fn append(&self, line: &str) -> std::io::Result<()> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let size = fs::metadata(&self.path).map(|m| m.len()).unwrap_or(0);
+        if size > 0 && size + line.len() as u64 + 1 > self.max_bytes {
+            // The oldest generation is overwritten by the rename chain.
+            for n in (1..self.max_files).rev() {
+                let from = self.generation(n);
+                if from.exists() {
+                    fs::rename(&from, self.generation(n + 1))?;
+                }
+            }
+            fs::rename(&self.path, self.generation(1))?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{line}")
+    }
+
+    fn scan(&self, query: &AuditQuery) -> std::io::Result<Vec<AuditEntry>> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let limit = query.limit();
+        let mut found = Vec::new();
+        for n in 0..=self.max_files {
+            let entries = match read_entries(&self.generation(n)) {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+            for entry in entries.into_iter().rev() {
+                if entry.matches(query) {
+                    found.push(entry);
+                    if found.len() == limit {
+                        return Ok(found);
+                    }
+                }
+            }
+        }
+        Ok(found)
+    }
+
+    fn prune_files(&self, before: Option<&str>, keep: Option<u64>) -> std::io::Result<u64> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut kept = 0u64;
+        let mut removed = 0u64;
+        for n in 0..=self.max_files {
+            let path = self.generation(n);
+            let entries = match read_entries(&path) {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+            let total = entries.len();
+            // Newest generations come first and entries are appended, so walk each file backwards.
+            let mut retained: Vec<AuditEntry> = entries
+                .into_iter()
+                .rev()
+                .filter(|entry| {
+                    let fresh = before.is_none_or(|before| entry.timestamp.as_str() >= before);
+                    let room = keep.is_none_or(|keep| kept < keep);
+                    kept += u64::from(fresh && room);
+                    fresh && room
+                })
+                .collect();
+            if retained.len() == total {
+                continue;
+            }
+            removed += (total - retained.len()) as u64;
+            if retained.is_empty() && n > 0 {
+                fs::remove_file(&path)?;
+                continue;
+            }
+            retained.reverse();
+            let mut file = fs::File::create(&path)?;
+            for entry in &retained {
+                writeln!(file, "{}", serde_json::to_string(entry)?)?;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+/// Entries of one JSONL file in write order; unparsable lines (e.g. a torn write) are skipped.
+fn read_entries(path: &Path) -> std::io::Result<Vec<AuditEntry>> {
+    let file = fs::File::open(path)?;
+    let mut entries = Vec::new();
+    for line in BufReader::new(file).lines() {
+        if let Ok(entry) = serde_json::from_str(&line?) {
+            entries.push(entry);
+        }
+    }
+    Ok(entries)
+}
+
+#[async_trait]
+impl AuditLog for JsonlAuditLog {
+    fn backend(&self) -> &'static str {
+        "jsonl"
+    }
+
+    async fn record(&self, entry: &AuditEntry) -> anyhow::Result<()> {
+        let line = serde_json::to_string(entry)?;
+        let log = self.clone();
+        tokio::task::spawn_blocking(move || log.append(&line)).await??;
+        Ok(())
+    }
+
+    async fn query(&self, query: &AuditQuery) -> anyhow::Result<Vec<AuditEntry>> {
+        let log = self.clone();
+        let query = query.clone();
+        Ok(tokio::task::spawn_blocking(move || log.scan(&query)).await??)
+    }
+
+    async fn prune(&self, before: Option<&str>, keep: Option<u64>) -> anyhow::Result<u64> {
+        let log = self.clone();
+        let before = before.map(str::to_string);
+        Ok(tokio::task::spawn_blocking(move || log.prune_files(before.as_deref(), keep)).await??)
+    }
+}
+
+/// SQLite-backed audit log; suits long retention and ad-hoc SQL by auditors.
+#[derive(Clone)]
+pub struct SqliteAuditLog {
+    conn: Arc<Mutex<rusqlite::Connection>>,
+}
+
+impl SqliteAuditLog {
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        Self::init(rusqlite::Connection::open(path.as_ref())?)
+    }
+
+    pub fn open_in_memory() -> anyhow::Result<Self> {
+        Self::init(rusqlite::Connection::open_in_memory()?)
+    }
+
+    fn init(conn: rusqlite::Connection) -> anyhow::Result<Self> {
+        conn.busy_timeout(Duration::from_secs(5))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS mcp_audit (
+                seq INTEGER PRIMARY KEY AUTOINCREMENT,
+                ts TEXT NOT NULL,
+                session TEXT,
+                token TEXT,
+                tool TEXT NOT NULL,
+                instance TEXT,
+                model TEXT,
+                ids TEXT NOT NULL,
+                outcome TEXT NOT NULL,
+                error TEXT,
+                duration_ms INTEGER NOT NULL,
+                args_digest TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS mcp_audit_ts ON mcp_audit (ts);",
+        )?;
+        // Added with tenants; older databases gain the column in place.
+        let has_tenant = conn
+            .prepare("SELECT 1 FROM pragma_table_info('mcp_audit') WHERE name = 'tenant'")?
+            .exists([])?;
+        if !has_tenant {
+            conn.execute_batch("ALTER TABLE mcp_audit ADD COLUMN tenant TEXT;")?;
+        }
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    async fn with_conn<T, F>(&self, f: F) -> anyhow::Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut rusqlite::Connection) -> rusqlite::Result<T> + Send + 'static,
+    {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut guard = conn
+                .lock()
+                .map_err(|_| anyhow::anyhow!("audit log lock poisoned"))?;
+            f(&mut guard).map_err(anyhow::Error::from)
+        })
+        .await?
+    }
+}
+
+#[async_trait]
+impl AuditLog for SqliteAuditLog {
+    fn backend(&self) -> &'static str {
+        "sqlite"
+    }
+
+    async fn record(&self, entry: &AuditEntry) -> anyhow::Result<()> {
+        let entry = entry.clone();
+        let ids = serde_json::to_string(&entry.ids)?;
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT INTO mcp_audit (ts, session, token, tool, instance, model, ids, outcome,
+                    error, duration_ms, args_digest, tenant)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                rusqlite::params![
+                    entry.timestamp,
+                    entry.session,
+                    entry.token,
+                    entry.tool,
+                    entry.instance,
+                    entry.model,
+                    ids,
+                    entry.outcome,
+                    entry.error,
+                    entry.duration_ms as i64,
+                    entry.args_digest,
+                    entry.tenant,
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn query(&self, query: &AuditQuery) -> anyhow::Result<Vec<AuditEntry>> {
+        let q = query.clone();
+        let limit = query.limit() as i64;
+        self.with_conn(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT ts, session, token, tool, instance, model, ids, outcome, error,
+                    duration_ms, args_digest, tenant
+                 FROM mcp_audit
+                 WHERE (?1 IS NULL OR ts >= ?1) AND (?2 IS NULL OR ts < ?2)
+                   AND (?3 IS NULL OR tool = ?3) AND (?4 IS NULL OR instance = ?4)
+                   AND (?5 IS NULL OR model = ?5) AND (?6 IS NULL OR token = ?6)
+                   AND (?7 IS NULL OR session = ?7) AND (?8 IS NULL OR outcome = ?8)
+                   AND (?9 IS NULL OR tenant = ?9)
+                 ORDER BY seq DESC LIMIT ?10",
+            )?;
+            stmt.query_map(
+                rusqlite::params![
+                    q.since, q.until, q.tool, q.instance, q.model, q.token, q.session, q.outcome,
+                    q.tenant, limit,
+                ],
+                |row| {
+                    let ids: String = row.get(6)?;
+                    Ok(AuditEntry {
+                        timestamp: row.get(0)?,
+                        session: row.get(1)?,
+                        token: row.get(2)?,
+                        tenant: row.get(11)?,
+                        tool: row.get(3)?,
+                        instance: row.get(4)?,
+                        model: row.get(5)?,
+                        ids: serde_json::from_str(&ids).unwrap_or_default(),
+                        outcome: row.get(7)?,
+                        error: row.get(8)?,
+                        duration_ms: row.get::<_, i64>(9)? as u64,
+                        args_digest: row.get(10)?,
+                    })
+                },
+            )?
+            .collect()
+        })
+        .await
+    }
+
+    async fn prune(&self, before: Option<&str>, keep: Option<u64>) -> anyhow::Result<u64> {
+        let before = before.map(str::to_string);
+        self.with_conn(move |conn| {
+            let mut removed = 0;
+            if let Some(before) = before {
+                removed += conn.execute("DELETE FROM mcp_audit WHERE ts < ?1", [before])?;
+            }
+            if let Some(keep) = keep {
+                removed += conn.execute(
+                    "DELETE FROM mcp_audit WHERE seq <= (
+                        SELECT seq FROM mcp_audit ORDER BY seq DESC LIMIT 1 OFFSET ?1)",
+                    [keep as i64],
+                )?;
+            }
+            Ok(removed as u64)
+        })
+        .await
+    }
+}
+
+/// Build the audit log selected by `MCP_AUDIT_LOG`; None when auditing is off.
+pub fn audit_log_from_env() -> anyhow::Result<Option<Arc<dyn AuditLog>>> {
+    let var = |name: &str| {
+        std::env::var(name)
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    let backend = var("MCP_AUDIT_LOG")
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match backend.as_str() {
+        "" | "off" => Ok(None),
+        "jsonl" => {
+            let path = var("MCP_AUDIT_LOG_PATH").unwrap_or_else(|| "mcp-audit.jsonl".into());
+            let max_bytes = var("MCP_AUDIT_LOG_MAX_BYTES")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_MAX_BYTES);
+            let max_files = var("MCP_AUDIT_LOG_MAX_FILES")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_MAX_FILES);
+            Ok(Some(Arc::new(JsonlAuditLog::new(
+                path, max_bytes, max_files,
+            ))))
+        }
+        "sqlite" => {
+            let path = var("MCP_AUDIT_LOG_PATH").unwrap_or_else(|| "mcp-audit.db".into());
+            Ok(Some(Arc::new(SqliteAuditLog::open(path)?)))
+        }
+        other => anyhow::bail!("Unknown MCP_AUDIT_LOG '{other}' (expected off, jsonl, or sqlite)"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entry(tool: &str, instance: &str, outcome: &str) -> AuditEntry {
+        AuditEntry::begin(
+            "s1",
+            Some("ops"),
+            tool,
+            &json!({"instance": instance, "model": "res.partner", "ids": [3, 1]}),
+        )
+        .finish(outcome, None, None, Duration::from_millis(5))
+    }
+
+    async fn exercise(log: &dyn AuditLog) {
+        log.record(&entry("odoo_read", "prod", "success"))
+            .await
+            .unwrap();
+        log.record(&entry("odoo_update", "prod", "error"))
+            .await
+            .unwrap();
+        log.record(&entry("odoo_update", "staging", "success"))
+            .await
+            .unwrap();
+
+        let all = log.query(&AuditQuery::default()).await.unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].instance.as_deref(), Some("staging"), "newest first");
+        assert_eq!(all[2].ids, vec![1, 3]);
+
+        let updates = log
+            .query(&AuditQuery {
+                tool: Some("odoo_update".into()),
+                instance: Some("prod".into()),
+                ..AuditQuery::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].outcome, "error");
+
+        let mut tenant_entry = entry("odoo_read", "prod", "success");
+        tenant_entry.tenant = Some("finance".into());
+        log.record(&tenant_entry).await.unwrap();
+        let finance = log
+            .query(&AuditQuery {
+                tenant: Some("finance".into()),
+                ..AuditQuery::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(finance, vec![tenant_entry]);
+
+        let limited = log
+            .query(&AuditQuery {
+                limit: Some(2),
+                ..AuditQuery::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(limited.len(), 2);
+
+        let future = log
+            .query(&AuditQuery {
+                since: Some("2999-01-01T00:00:00Z".into()),
+                ..AuditQuery::default()
+            })
+            .await
+            .unwrap();
+        assert!(future.is_empty());
+    }
+
+    #[tokio::test]
+    async fn sqlite_log_records_and_filters() {
+        exercise(&SqliteAuditLog::open_in_memory().unwrap()).await;
+    }
+
+    #[tokio::test]
+    async fn sqlite_log_adds_the_tenant_column_to_older_databases() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.db");
+        rusqlite::Connection::open(&path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE mcp_audit (seq INTEGER PRIMARY KEY AUTOINCREMENT, ts TEXT NOT NULL,
+                    session TEXT, token TEXT, tool TEXT NOT NULL, instance TEXT, model TEXT,
+                    ids TEXT NOT NULL, outcome TEXT NOT NULL, error TEXT,
+                    duration_ms INTEGER NOT NULL, args_digest TEXT NOT NULL);
+                 INSERT INTO mcp_audit (ts, tool, ids, outcome, duration_ms, args_digest)
+                    VALUES ('2026-01-01T00:00:00.000Z', 'odoo_read', '[]', 'success', 1, 'x');",
+            )
+            .unwrap();
+        let log = SqliteAuditLog::open(&path).unwrap();
+        let entries = log.query(&AuditQuery::default()).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].tenant, None);
+
+        let mut entry = entry("odoo_read", "prod", "success");
+        entry.tenant = Some("finance".into());
+        log.record(&entry).await.unwrap();
+        let query = AuditQuery {
+            tenant: Some("finance".into()),
+            ..AuditQuery::default()
+        };
+        assert_eq!(log.query(&query).await.unwrap(), vec![entry]);
+    }
+
+    #[tokio::test]
+    async fn jsonl_log_records_and_filters() {
+        let dir = tempfile::tempdir().unwrap();
+        exercise(&JsonlAuditLog::new(
+            dir.path().join("audit.jsonl"),
+            1 << 20,
+            3,
+        ))
+        .await;
+    }
+
+    #[tokio::test]
+    async fn jsonl_log_rotates_and_queries_across_generations() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        // Room for roughly two entries per file.
+        let line_len = serde_json::to_string(&entry("tool_0", "prod", "success"))
+            .unwrap()
+            .len() as u64;
+        let log = JsonlAuditLog::new(&path, line_len * 2 + 2, 2);
+        for n in 0..7 {
+            log.record(&entry(&format!("tool_{n}"), "prod", "success"))
+                .await
+                .unwrap();
+        }
+        assert!(log.generation(1).exists());
+        assert!(log.generation(2).exists());
+        assert!(!log.generation(3).exists());
+
+        let tools: Vec<String> = log
+            .query(&AuditQuery::default())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| e.tool)
+            .collect();
+        // Oldest entries fell off the last generation.
+        assert_eq!(tools, ["tool_6", "tool_5", "tool_4", "tool_3", "tool_2"]);
+    }
+
+    async fn exercise_prune(log: &dyn AuditLog) {
+        let mut old = entry("tool_old", "prod", "success");
+        old.timestamp = "2020-01-01T00:00:00.000Z".into();
+        log.record(&old).await.unwrap();
+        for n in 0..4 {
+            log.record(&entry(&format!("tool_{n}"), "prod", "success"))
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            log.prune(Some("2021-01-01T00:00:00Z"), None).await.unwrap(),
+            1
+        );
+        assert_eq!(log.prune(None, Some(2)).await.unwrap(), 2);
+        assert_eq!(log.prune(None, Some(2)).await.unwrap(), 0);
+        let tools: Vec<String> = log
+            .query(&AuditQuery::default())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| e.tool)
+            .collect();
+        assert_eq!(tools, ["tool_3", "tool_2"]);
+    }
+
+    #[tokio::test]
+    async fn sqlite_log_prunes_by_age_and_count() {
+        exercise_prune(&SqliteAuditLog::open_in_memory().unwrap()).await;
+    }
+
+    #[tokio::test]
+    async fn jsonl_log_prunes_across_generations() {
+        let dir = tempfile::tempdir().unwrap();
+        let line_len = serde_json::to_string(&entry("tool_0", "prod", "success"))
+            .unwrap()
+            .len() as u64;
+        let log = JsonlAuditLog::new(dir.path().join("audit.jsonl"), line_len * 2 + 2, 3);
+        exercise_prune(&log).await;
+        // Generations emptied by the prune are removed.
+        assert!(!log.generation(2).exists());
+    }
+
+    #[test]
+    fn created_ids_come_from_the_result_payload() {
+        let result =
+            json!({"content": [{"type": "text", "text": "{\"id\": 42, \"success\": true}"}]});
+        let entry = AuditEntry::begin("", None, "odoo_create", &json!({"model": "res.partner"}))
+            .finish("success", Some(&result), None, Duration::ZERO);
+        assert_eq!(entry.ids, vec![42]);
+        assert_eq!(entry.session, None);
+        assert!(entry.args_digest.starts_with("sha256:"));
+    }
+}
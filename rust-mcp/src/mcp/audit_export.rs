@@ -0,0 +1,385 @@
+//! Batch export of the audit log to CSV files for analytics.
+//!
+//! With `MCP_AUDIT_EXPORT_DIR` set (and an audit log enabled), entries are written to
+//! `<dir>/audit-<from>-<until>.csv` every `MCP_AUDIT_EXPORT_INTERVAL_SECS` (default one hour;
+//! `0` exports only on demand through `POST /audit/export`, admin scope). Each batch covers the
+//! window from the previous batch up to now minus `MCP_AUDIT_EXPORT_LAG_SECS` (default 300):
+//! entries are stamped when a call starts but written when it ends, so the lag leaves slow calls
+//! time to land before their window closes. The window boundary is kept in `<dir>/.cursor`, so a
+//! restart carries on without gaps or duplicates. Windows without entries write no file.
+//!
+//! Only CSV is produced; `MCP_AUDIT_EXPORT_FORMAT=parquet` is rejected at startup since the
+//! build carries no Parquet encoder. BI tools load the CSV files directly.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::mcp::audit::{AuditEntry, AuditLog, AuditQuery};
+use crate::mcp::csv_export::encode_rows;
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(3600);
+const DEFAULT_LAG: Duration = Duration::from_secs(300);
+/// Entries fetched per audit query while paging through a window.
+const PAGE: usize = 1000;
+const CURSOR_FILE: &str = ".cursor";
+
+const COLUMNS: &[&str] = &[
+    "timestamp",
+    "session",
+    "token",
+    "tenant",
+    "tool",
+    "instance",
+    "model",
+    "ids",
+    "outcome",
+    "error",
+    "duration_ms",
+    "args_digest",
+];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExportConfig {
+    pub dir: PathBuf,
+    /// None exports only on demand.
+    pub interval: Option<Duration>,
+    pub lag: Duration,
+}
+
+impl ExportConfig {
+    /// Settings from `MCP_AUDIT_EXPORT_*`; None when `MCP_AUDIT_EXPORT_DIR` is unset.
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let var = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let Some(dir) = var("MCP_AUDIT_EXPORT_DIR") else {
+            return Ok(None);
+        };
+        match var("MCP_AUDIT_EXPORT_FORMAT")
+            .unwrap_or_else(|| "csv".into())
+            .to_ascii_lowercase()
+            .as_str()
+        {
+            "csv" => {}
+            "parquet" => anyhow::bail!(
+                "MCP_AUDIT_EXPORT_FORMAT=parquet is not supported by this build; use csv"
+            ),
+            other => anyhow::bail!("Unknown MCP_AUDIT_EXPORT_FORMAT '{other}' (expected csv)"),
+        }
+        let secs = |name: &str| var(name).and_then(|v| v.parse::<u64>().ok());
+        let interval = match secs("MCP_AUDIT_EXPORT_INTERVAL_SECS") {
+            Some(0) => None,
+            Some(secs) => Some(Duration::from_secs(secs)),
+            None => Some(DEFAULT_INTERVAL),
+        };
+        Ok(Some(Self {
+            dir: PathBuf::from(dir),
+            interval,
+            lag: secs("MCP_AUDIT_EXPORT_LAG_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_LAG),
+        }))
+    }
+}
+
+/// Outcome of one export run.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportBatch {
+    /// Start of the window (inclusive); None for the first export, which takes everything.
+    pub from: Option<String>,
+    /// End of the window (exclusive).
+    pub until: String,
+    pub entries: usize,
+    /// File written, None when the window was empty.
+    pub file: Option<PathBuf>,
+}
+
+/// Exports the audit log in windows; runs are serialized so a manual export and the schedule
+/// never overlap.
+#[derive(Clone)]
+pub struct AuditExporter {
+    log: Arc<dyn AuditLog>,
+    config: ExportConfig,
+    running: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl AuditExporter {
+    pub fn new(log: Arc<dyn AuditLog>, config: ExportConfig) -> Self {
+        Self {
+            log,
+            config,
+            running: Arc::new(tokio::sync::Mutex::new(())),
+        }
+    }
+
+    pub fn config(&self) -> &ExportConfig {
+        &self.config
+    }
+
+    /// Export the window ending at now minus the lag.
+    pub async fn export_now(&self) -> anyhow::Result<ExportBatch> {
+        let lag = chrono::Duration::from_std(self.config.lag)?;
+        self.export_until(Utc::now() - lag).await
+    }
+
+    /// Export entries stamped before `until` that no earlier batch covered.
+    pub async fn export_until(&self, until: DateTime<Utc>) -> anyhow::Result<ExportBatch> {
+        let _running = self.running.lock().await;
+        tokio::fs::create_dir_all(&self.config.dir).await?;
+        let cursor_path = self.config.dir.join(CURSOR_FILE);
+        let from = match tokio::fs::read_to_string(&cursor_path).await {
+            Ok(text) => Some(text.trim().to_string()).filter(|t| !t.is_empty()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        let until = until.to_rfc3339_opts(SecondsFormat::Millis, true);
+        if from.as_deref().is_some_and(|from| from >= until.as_str()) {
+            return Ok(ExportBatch {
+                from: from.clone(),
+                until: from.unwrap_or_default(),
+                ..ExportBatch::default()
+            });
+        }
+
+        let entries = self.window(from.as_deref(), &until).await?;
+        let file = if entries.is_empty() {
+            None
+        } else {
+            let path = self.config.dir.join(file_name(from.as_deref(), &until));
+            write_atomically(&path, to_csv(&entries).into_bytes()).await?;
+            Some(path)
+        };
+        write_atomically(&cursor_path, until.clone().into_bytes()).await?;
+        Ok(ExportBatch {
+            from,
+            until,
+            entries: entries.len(),
+            file,
+        })
+    }
+
+    /// Every entry stamped in `[from, until)`, oldest first.
+    ///
+    /// The log returns entries in the order they were written, which is not timestamp order, so
+    /// a full page cannot be continued from its oldest timestamp; instead a window that fills a
+    /// page is split in half until each part fits.
+    async fn window(&self, from: Option<&str>, until: &str) -> anyhow::Result<Vec<AuditEntry>> {
+        let parse = |ts: &str| DateTime::parse_from_rfc3339(ts).map(|t| t.with_timezone(&Utc));
+        let lo = match from {
+            Some(from) => parse(from)?,
+            None => DateTime::UNIX_EPOCH,
+        };
+        let mut pending = vec![(lo, parse(until)?)];
+        let mut all = Vec::new();
+        while let Some((lo, hi)) = pending.pop() {
+            let page = self
+                .log
+                .query(&AuditQuery {
+                    since: Some(lo.to_rfc3339_opts(SecondsFormat::Millis, true)),
+                    until: Some(hi.to_rfc3339_opts(SecondsFormat::Millis, true)),
+                    limit: Some(PAGE),
+                    ..AuditQuery::default()
+                })
+                .await?;
+            let span = (hi - lo).num_milliseconds();
+            if page.len() < PAGE || span <= 1 {
+                all.extend(page);
+                continue;
+            }
+            let mid = lo + chrono::Duration::milliseconds(span / 2);
+            pending.push((lo, mid));
+            pending.push((mid, hi));
+        }
+        all.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        Ok(all)
+    }
+
+    /// Export on the configured interval; a no-op when exports are on demand only.
+    pub fn spawn(self) {
+        let Some(interval) = self.config.interval else {
+            return;
+        };
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                match self.export_now().await {
+                    Ok(ExportBatch { entries: 0, .. }) => {}
+                    Ok(batch) => info!(
+                        entries = batch.entries,
+                        file = ?batch.file,
+                        "Exported audit entries"
+                    ),
+                    Err(e) => warn!(error = %e, "Audit export failed"),
+                }
+            }
+        });
+    }
+}
+
+/// `audit-<from>-<until>.csv` with compact UTC times, e.g. `audit-20260301T080000Z-...`.
+fn file_name(from: Option<&str>, until: &str) -> String {
+    let compact = |ts: &str| {
+        DateTime::parse_from_rfc3339(ts)
+            .map(|t| {
+                t.with_timezone(&Utc)
+                    .format("%Y%m%dT%H%M%S%.3fZ")
+                    .to_string()
+            })
+            .unwrap_or_else(|_| ts.replace([':', '-', '.'], ""))
+            .replace('.', "")
+    };
+    format!(
+        "audit-{}-{}.csv",
+        from.map(compact).unwrap_or_else(|| "start".into()),
+        compact(until)
+    )
+}
+
+/// Entries (oldest first) as CSV with a header row; ids are joined with `;`.
+pub fn to_csv(entries: &[AuditEntry]) -> String {
+    let text = |value: &Option<String>| value.clone().unwrap_or_default();
+    let mut rows = Vec::with_capacity(entries.len() + 1);
+    rows.push(COLUMNS.iter().map(|c| c.to_string()).collect());
+    rows.extend(entries.iter().map(|entry| {
+        vec![
+            entry.timestamp.clone(),
+            text(&entry.session),
+            text(&entry.token),
+            text(&entry.tenant),
+            entry.tool.clone(),
+            text(&entry.instance),
+            text(&entry.model),
+            entry
+                .ids
+                .iter()
+                .map(i64::to_string)
+                .collect::<Vec<_>>()
+                .join(";"),
+            entry.outcome.clone(),
+            text(&entry.error),
+            entry.duration_ms.to_string(),
+            entry.args_digest.clone(),
+        ]
+    }));
+    encode_rows(&rows)
+}
+
+async fn write_atomically(path: &Path, contents: Vec<u8>) -> std::io::Result<()> {
+    let mut tmp = path.as_os_str().to_os_string();
+    tmp.push(".tmp");
+    tokio::fs::write(&tmp, contents).await?;
+    tokio::fs::rename(&tmp, path).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::audit::SqliteAuditLog;
+    use serde_json::json;
+
+    fn entry(tool: &str, timestamp: &str) -> AuditEntry {
+        let mut entry = AuditEntry::begin(
+            "s1",
+            Some("ops"),
+            tool,
+            &json!({"instance": "prod", "model": "res.partner", "ids": [3, 1]}),
+        )
+        .finish("error", None, Some("boom, \"bad\"".into()), Duration::ZERO);
+        entry.timestamp = timestamp.into();
+        entry
+    }
+
+    fn at(ts: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(ts)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn entries_render_as_csv() {
+        let csv = to_csv(&[entry("odoo_read", "2026-03-01T08:00:00.000Z")]);
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            "timestamp,session,token,tenant,tool,instance,model,ids,outcome,error,duration_ms,args_digest"
+        );
+        let row = lines.next().unwrap();
+        assert!(row.starts_with(
+            "2026-03-01T08:00:00.000Z,s1,ops,,odoo_read,prod,res.partner,1;3,error,\"boom, \"\"bad\"\"\",0,sha256:"
+        ));
+    }
+
+    #[test]
+    fn file_names_use_compact_window_bounds() {
+        assert_eq!(
+            file_name(Some("2026-03-01T08:00:00.000Z"), "2026-03-01T09:00:00.250Z"),
+            "audit-20260301T080000000Z-20260301T090000250Z.csv"
+        );
+        assert_eq!(
+            file_name(None, "2026-03-01T09:00:00.000Z"),
+            "audit-start-20260301T090000000Z.csv"
+        );
+    }
+
+    #[tokio::test]
+    async fn batches_cover_consecutive_windows_once() {
+        let log = Arc::new(SqliteAuditLog::open_in_memory().unwrap());
+        for (tool, ts) in [
+            ("a", "2026-03-01T08:00:00.000Z"),
+            ("b", "2026-03-01T08:30:00.000Z"),
+            ("c", "2026-03-01T09:10:00.000Z"),
+        ] {
+            log.record(&entry(tool, ts)).await.unwrap();
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let exporter = AuditExporter::new(
+            log.clone(),
+            ExportConfig {
+                dir: dir.path().to_path_buf(),
+                interval: None,
+                lag: DEFAULT_LAG,
+            },
+        );
+
+        let first = exporter
+            .export_until(at("2026-03-01T09:00:00Z"))
+            .await
+            .unwrap();
+        assert_eq!(first.from, None);
+        assert_eq!(first.entries, 2);
+        let csv = std::fs::read_to_string(first.file.unwrap()).unwrap();
+        let tools: Vec<&str> = csv
+            .lines()
+            .skip(1)
+            .map(|line| line.split(',').nth(4).unwrap())
+            .collect();
+        assert_eq!(tools, ["a", "b"], "oldest first");
+
+        let empty = exporter
+            .export_until(at("2026-03-01T09:05:00Z"))
+            .await
+            .unwrap();
+        assert_eq!(empty.from.as_deref(), Some("2026-03-01T09:00:00.000Z"));
+        assert_eq!((empty.entries, empty.file), (0, None));
+
+        // A fresh exporter picks the window up from the cursor file.
+        let exporter = AuditExporter::new(log, exporter.config().clone());
+        let second = exporter
+            .export_until(at("2026-03-01T10:00:00Z"))
+            .await
+            .unwrap();
+        assert_eq!(second.from.as_deref(), Some("2026-03-01T09:05:00.000Z"));
+        assert_eq!(second.entries, 1);
+    }
+}
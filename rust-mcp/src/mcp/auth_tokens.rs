@@ -0,0 +1,357 @@
+//! Named API tokens for the HTTP transport.
+//!
+//! Tokens live in `tokens.json` (path from `MCP_AUTH_TOKENS_JSON`) and are managed through the
+//! config UI. Each token carries an optional expiry and a scope set; `tools/call` checks the
+//! scope an op requires against the token the request authenticated with. The legacy
+//! `MCP_AUTH_TOKEN` keeps working and is treated as an `admin` token. A token may also name a
+//! tenant (see [`crate::mcp::tenants`]), which limits the instances it can reach.
+
+use std::collections::{BTreeSet, HashSet};
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::mcp::registry::is_mutating_op;
+use crate::mcp::tenants::{Tenant, find_tenant};
+
+pub const TOKENS_PATH_ENV: &str = "MCP_AUTH_TOKENS_JSON";
+
+/// Name reported for requests authenticated with `MCP_AUTH_TOKEN`.
+pub const LEGACY_TOKEN_NAME: &str = "MCP_AUTH_TOKEN";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenScope {
+    Read,
+    Write,
+    Cleanup,
+    /// Implies every other scope.
+    Admin,
+}
+
+impl TokenScope {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TokenScope::Read => "read",
+            TokenScope::Write => "write",
+            TokenScope::Cleanup => "cleanup",
+            TokenScope::Admin => "admin",
+        }
+    }
+
+    /// Scope a tool op needs: cleanup ops need `cleanup`, other mutating ops `write`, and
+    /// `server_info`, which describes every instance, module management, running or
+    /// toggling scheduled actions or server actions (which may run Python code) and managing
+    /// users `admin`.
+    pub fn for_op(op_type: &str) -> Self {
+        if matches!(
+            op_type,
+            "server_info"
+                | "module_update_list"
+                | "module_install"
+                | "module_upgrade"
+                | "module_uninstall"
+                | "cron_control"
+                | "server_action_run"
+                | "user_create"
+                | "user_reset_password"
+                | "user_set_active"
+                | "user_groups"
+        ) {
+            TokenScope::Admin
+        } else if matches!(
+            op_type,
+            "database_cleanup" | "deep_cleanup" | "stock_inventory_reversal_cleanup"
+        ) {
+            TokenScope::Cleanup
+        } else if is_mutating_op(op_type) {
+            TokenScope::Write
+        } else {
+            TokenScope::Read
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiToken {
+    pub name: String,
+    pub token: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub scopes: BTreeSet<TokenScope>,
+    /// Tenant the token belongs to; `None` sees every instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+}
+
+impl ApiToken {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TokenFile {
+    #[serde(default)]
+    pub tokens: Vec<ApiToken>,
+}
+
+/// The token a request authenticated with.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenGrant {
+    pub name: String,
+    pub scopes: BTreeSet<TokenScope>,
+    pub tenant: Option<Tenant>,
+}
+
+impl TokenGrant {
+    pub fn admin(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            scopes: BTreeSet::from([TokenScope::Admin]),
+            tenant: None,
+        }
+    }
+
+    /// Grant of `token` with its tenant looked up in `tenants`.
+    pub fn for_token(token: &ApiToken, tenants: &[Tenant]) -> Self {
+        Self {
+            tenant: token
+                .tenant
+                .as_deref()
+                .map(|name| find_tenant(tenants, name)),
+            ..Self::from(token)
+        }
+    }
+
+    pub fn allows(&self, scope: TokenScope) -> bool {
+        self.scopes.contains(&TokenScope::Admin) || self.scopes.contains(&scope)
+    }
+}
+
+/// Without the tenant list a tenant token keeps its tenant but reaches no instances.
+impl From<&ApiToken> for TokenGrant {
+    fn from(token: &ApiToken) -> Self {
+        Self {
+            name: token.name.clone(),
+            scopes: token.scopes.clone(),
+            tenant: token.tenant.as_deref().map(Tenant::undefined),
+        }
+    }
+}
+
+/// Random 256-bit secret, hex encoded.
+pub fn generate_secret() -> String {
+    use rand::Rng;
+    let bytes: [u8; 32] = rand::rng().random();
+    hex::encode(bytes)
+}
+
+/// Load tokens from `path`; a missing file means no named tokens.
+pub fn load_tokens(path: &Path) -> anyhow::Result<Vec<ApiToken>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(path)?;
+    let file: TokenFile = serde_json::from_str(&content)?;
+    validate_tokens(&file.tokens).map_err(|e| anyhow::anyhow!(e))?;
+    Ok(file.tokens)
+}
+
+/// Load tokens from the file named by `MCP_AUTH_TOKENS_JSON`, if set.
+pub fn load_tokens_from_env() -> anyhow::Result<Vec<ApiToken>> {
+    match std::env::var(TOKENS_PATH_ENV) {
+        Ok(path) if !path.trim().is_empty() => load_tokens(Path::new(path.trim())),
+        _ => Ok(Vec::new()),
+    }
+}
+
+pub fn validate_tokens(tokens: &[ApiToken]) -> Result<(), String> {
+    let mut names = HashSet::new();
+    let mut secrets = HashSet::new();
+    for token in tokens {
+        let name = token.name.trim();
+        if name.is_empty() {
+            return Err("Every token needs a name".to_string());
+        }
+        if !names.insert(name) {
+            return Err(format!("Duplicate token name '{name}'"));
+        }
+        if token.token.trim().is_empty() {
+            return Err(format!("Token '{name}' has an empty secret"));
+        }
+        if !secrets.insert(token.token.as_str()) {
+            return Err(format!("Token '{name}' reuses the secret of another token"));
+        }
+        if token.scopes.is_empty() {
+            return Err(format!("Token '{name}' has no scopes"));
+        }
+    }
+    Ok(())
+}
+
+/// Token list as shown in the config UI: secrets are replaced by their last four characters.
+pub fn redact_tokens(tokens: &[ApiToken], now: DateTime<Utc>) -> Value {
+    Value::Array(
+        tokens
+            .iter()
+            .map(|token| {
+                let tail: String = token
+                    .token
+                    .chars()
+                    .rev()
+                    .take(4)
+                    .collect::<Vec<_>>()
+                    .into_iter()
+                    .rev()
+                    .collect();
+                json!({
+                    "name": token.name,
+                    "tokenHint": format!("…{tail}"),
+                    "expiresAt": token.expires_at,
+                    "expired": token.is_expired(now),
+                    "scopes": token.scopes,
+                    "tenant": token.tenant,
+                })
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(name: &str, secret: &str, scopes: &[TokenScope]) -> ApiToken {
+        ApiToken {
+            name: name.to_string(),
+            token: secret.to_string(),
+            expires_at: None,
+            scopes: scopes.iter().copied().collect(),
+            tenant: None,
+        }
+    }
+
+    #[test]
+    fn scope_for_op_separates_cleanup_and_writes() {
+        assert_eq!(TokenScope::for_op("search_read"), TokenScope::Read);
+        assert_eq!(TokenScope::for_op("create"), TokenScope::Write);
+        assert_eq!(TokenScope::for_op("deep_cleanup"), TokenScope::Cleanup);
+        assert_eq!(TokenScope::for_op("server_info"), TokenScope::Admin);
+        assert_eq!(TokenScope::for_op("module_uninstall"), TokenScope::Admin);
+        assert_eq!(TokenScope::for_op("module_list"), TokenScope::Read);
+        assert_eq!(TokenScope::for_op("module_update_list"), TokenScope::Admin);
+        assert_eq!(TokenScope::for_op("settings_apply"), TokenScope::Write);
+        assert_eq!(TokenScope::for_op("cron_control"), TokenScope::Admin);
+        assert_eq!(TokenScope::for_op("cron_list"), TokenScope::Read);
+        assert_eq!(TokenScope::for_op("server_action_run"), TokenScope::Admin);
+        assert_eq!(
+            TokenScope::for_op("automation_set_active"),
+            TokenScope::Write
+        );
+        assert_eq!(TokenScope::for_op("automation_list"), TokenScope::Read);
+        assert_eq!(TokenScope::for_op("user_groups"), TokenScope::Admin);
+    }
+
+    #[test]
+    fn write_guarded_tools_need_more_than_read() {
+        let tools: Value =
+            serde_json::from_str(include_str!("../../config-defaults/tools.json")).unwrap();
+        for tool in tools["tools"].as_array().unwrap() {
+            let guards = &tool["guards"];
+            let write_guarded = guards["requiresEnvTrue"] == "ODOO_ENABLE_WRITE_TOOLS"
+                || guards["requiresEnvTrueAll"]
+                    .as_array()
+                    .is_some_and(|vars| vars.contains(&json!("ODOO_ENABLE_WRITE_TOOLS")));
+            let op_type = tool["op"]["type"].as_str().unwrap();
+            if write_guarded {
+                assert_ne!(
+                    TokenScope::for_op(op_type),
+                    TokenScope::Read,
+                    "{} ({op_type}) is write-guarded but needs only the read scope",
+                    tool["name"]
+                );
+            }
+            let admin_write_guarded = guards["requiresEnvTrueAll"]
+                .as_array()
+                .is_some_and(|vars| vars.contains(&json!("ODOO_ENABLE_ADMIN_TOOLS")));
+            if write_guarded && admin_write_guarded {
+                assert_eq!(
+                    TokenScope::for_op(op_type),
+                    TokenScope::Admin,
+                    "{} ({op_type}) is admin-guarded but does not need the admin scope",
+                    tool["name"]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn admin_grant_allows_everything() {
+        let grant = TokenGrant::admin(LEGACY_TOKEN_NAME);
+        assert!(grant.allows(TokenScope::Cleanup));
+
+        let reader = TokenGrant::from(&token("bi", "s1", &[TokenScope::Read]));
+        assert!(reader.allows(TokenScope::Read));
+        assert!(!reader.allows(TokenScope::Write));
+    }
+
+    #[test]
+    fn tenant_tokens_resolve_their_tenant() {
+        let mut scoped = token("team-a", "s1", &[TokenScope::Read]);
+        scoped.tenant = Some("finance".into());
+        let tenants = vec![Tenant {
+            name: "finance".into(),
+            instances: vec!["prod".into()],
+            calls_per_minute: None,
+        }];
+        let grant = TokenGrant::for_token(&scoped, &tenants);
+        assert!(grant.tenant.as_ref().unwrap().allows_instance("prod"));
+        // Without the tenant list the token fails closed.
+        let grant = TokenGrant::from(&scoped);
+        assert!(!grant.tenant.as_ref().unwrap().allows_instance("prod"));
+        assert_eq!(TokenGrant::admin("ops").tenant, None);
+    }
+
+    #[test]
+    fn validate_tokens_rejects_duplicates_and_empty_scopes() {
+        let ok = token("bi", "s1", &[TokenScope::Read]);
+        assert!(validate_tokens(std::slice::from_ref(&ok)).is_ok());
+        assert!(validate_tokens(&[ok.clone(), token("bi", "s2", &[TokenScope::Read])]).is_err());
+        assert!(validate_tokens(&[ok.clone(), token("etl", "s1", &[TokenScope::Write])]).is_err());
+        assert!(validate_tokens(&[token("etl", "s2", &[])]).is_err());
+    }
+
+    #[test]
+    fn expiry_and_redaction() {
+        let now = Utc::now();
+        let mut expired = token("old", "abcdef123456", &[TokenScope::Read]);
+        expired.expires_at = Some(now - chrono::Duration::hours(1));
+        assert!(expired.is_expired(now));
+
+        let redacted = redact_tokens(&[expired], now);
+        assert_eq!(redacted[0]["tokenHint"], "…3456");
+        assert_eq!(redacted[0]["expired"], true);
+        assert!(redacted[0].get("token").is_none());
+    }
+
+    #[test]
+    fn load_tokens_parses_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("tokens.json");
+        assert!(load_tokens(&path).unwrap().is_empty());
+        std::fs::write(
+            &path,
+            r#"{"tokens": [{"name": "bi", "token": "s1", "scopes": ["read"],
+                "expiresAt": "2030-01-01T00:00:00Z"}]}"#,
+        )
+        .unwrap();
+        let tokens = load_tokens(&path).unwrap();
+        assert_eq!(tokens[0].scopes, BTreeSet::from([TokenScope::Read]));
+        assert!(tokens[0].expires_at.is_some());
+    }
+}
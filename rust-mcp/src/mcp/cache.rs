@@ -85,10 +85,132 @@ impl Default for MetadataCache {
     }
 }
 
+/// Entries kept per (instance, model) before expired ones are pruned and, failing that, the
+/// oldest half is dropped.
+const MAX_RESULTS_PER_MODEL: usize = 256;
+
+/// TTL cache for read tool results (`ODOO_READ_CACHE_TTL_SECS`, off by default).
+///
+/// Entries are grouped by (instance, model) so a successful write can drop everything it may
+/// have made stale; writes without a known model drop the whole instance.
+#[derive(Clone, Default)]
+pub struct ResultCache {
+    ttl: Option<Duration>,
+    entries: std::sync::Arc<RwLock<HashMap<CacheKey, HashMap<String, CacheEntry>>>>,
+}
+
+impl ResultCache {
+    pub fn with_ttl(ttl: Option<Duration>) -> Self {
+        Self {
+            ttl,
+            ..Self::default()
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let ttl = std::env::var("ODOO_READ_CACHE_TTL_SECS")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs);
+        Self::with_ttl(ttl)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.ttl.is_some()
+    }
+
+    pub fn ttl(&self) -> Option<Duration> {
+        self.ttl
+    }
+
+    pub async fn get(&self, instance: &str, model: &str, key: &str) -> Option<Value> {
+        let guard = self.entries.read().await;
+        let (value, expiration) = guard
+            .get(&(instance.to_string(), model.to_string()))?
+            .get(key)?;
+        (Instant::now() < *expiration).then(|| value.clone())
+    }
+
+    pub async fn insert(&self, instance: &str, model: &str, key: String, value: Value) {
+        let Some(ttl) = self.ttl else {
+            return;
+        };
+        let now = Instant::now();
+        let mut guard = self.entries.write().await;
+        let results = guard
+            .entry((instance.to_string(), model.to_string()))
+            .or_default();
+        if results.len() >= MAX_RESULTS_PER_MODEL {
+            results.retain(|_, (_, expiration)| now < *expiration);
+        }
+        if results.len() >= MAX_RESULTS_PER_MODEL {
+            let mut by_age: Vec<(String, Instant)> =
+                results.iter().map(|(k, (_, e))| (k.clone(), *e)).collect();
+            by_age.sort_by_key(|(_, expiration)| *expiration);
+            for (k, _) in by_age.into_iter().take(MAX_RESULTS_PER_MODEL / 2) {
+                results.remove(&k);
+            }
+        }
+        results.insert(key, (value, now + ttl));
+    }
+
+    /// Drop cached results for `model` on `instance`, or for the whole instance when the
+    /// model is unknown. Returns the number of entries removed.
+    pub async fn invalidate(&self, instance: &str, model: Option<&str>) -> usize {
+        let mut guard = self.entries.write().await;
+        let mut removed = 0;
+        guard.retain(|(i, m), results| {
+            let stale = i == instance && model.is_none_or(|model| model == m);
+            if stale {
+                removed += results.len();
+            }
+            !stale
+        });
+        removed
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn test_result_cache_invalidates_by_model_and_instance() {
+        let cache = ResultCache::with_ttl(Some(Duration::from_secs(60)));
+        let value = serde_json::json!({"records": []});
+        cache
+            .insert("prod", "res.partner", "a".into(), value.clone())
+            .await;
+        cache
+            .insert("prod", "sale.order", "b".into(), value.clone())
+            .await;
+        cache
+            .insert("staging", "res.partner", "a".into(), value.clone())
+            .await;
+
+        assert_eq!(cache.invalidate("prod", Some("res.partner")).await, 1);
+        assert_eq!(cache.get("prod", "res.partner", "a").await, None);
+        assert_eq!(
+            cache.get("prod", "sale.order", "b").await,
+            Some(value.clone())
+        );
+        assert_eq!(cache.get("staging", "res.partner", "a").await, Some(value));
+
+        assert_eq!(cache.invalidate("prod", None).await, 1);
+        assert_eq!(cache.get("prod", "sale.order", "b").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_result_cache_disabled_without_ttl() {
+        let cache = ResultCache::with_ttl(None);
+        assert!(!cache.is_enabled());
+        cache
+            .insert("prod", "res.partner", "a".into(), serde_json::json!(1))
+            .await;
+        assert_eq!(cache.get("prod", "res.partner", "a").await, None);
+    }
+
     #[tokio::test]
     async fn test_cache_insert_and_get() {
         let cache = MetadataCache::new();
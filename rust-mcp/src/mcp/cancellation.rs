@@ -0,0 +1,110 @@
+//! In-flight request tracking for `notifications/cancelled`.
+//!
+//! Each cancellable request registers a [`CancellationToken`] under a key scoped to its
+//! transport session, so a cancel notification only reaches requests from the same client.
+//! Cancelling drops the pending tool future, which aborts the in-flight reqwest call and
+//! any retry backoff in the Odoo client.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use serde_json::Value;
+use tokio_util::sync::CancellationToken;
+
+#[derive(Clone, Default)]
+pub struct CancellationRegistry {
+    inner: Arc<Mutex<HashMap<String, CancellationToken>>>,
+}
+
+/// Registration for one in-flight request; deregisters itself on drop.
+pub struct InFlight {
+    key: String,
+    token: CancellationToken,
+    registry: CancellationRegistry,
+}
+
+impl InFlight {
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        if let Ok(mut guard) = self.registry.inner.lock() {
+            guard.remove(&self.key);
+        }
+    }
+}
+
+/// Build the registry key for a JSON-RPC request id within a session scope.
+pub fn request_key(scope: &str, request_id: &Value) -> String {
+    format!("{scope}:{request_id}")
+}
+
+impl CancellationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&self, key: String) -> InFlight {
+        let token = CancellationToken::new();
+        if let Ok(mut guard) = self.inner.lock() {
+            guard.insert(key.clone(), token.clone());
+        }
+        InFlight {
+            key,
+            token,
+            registry: self.clone(),
+        }
+    }
+
+    /// Cancel the request registered under `key`. Returns false if it already finished.
+    pub fn cancel(&self, key: &str) -> bool {
+        let token = self
+            .inner
+            .lock()
+            .ok()
+            .and_then(|guard| guard.get(key).cloned());
+        match token {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.inner.lock().map(|guard| guard.len()).unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn cancel_reaches_registered_request_and_drop_deregisters() {
+        let registry = CancellationRegistry::new();
+        let key = request_key("session-a", &json!(7));
+        let in_flight = registry.register(key.clone());
+        assert_eq!(registry.in_flight(), 1);
+
+        assert!(!registry.cancel(&request_key("session-b", &json!(7))));
+        assert!(!in_flight.token().is_cancelled());
+
+        assert!(registry.cancel(&key));
+        assert!(in_flight.token().is_cancelled());
+
+        drop(in_flight);
+        assert_eq!(registry.in_flight(), 0);
+        assert!(!registry.cancel(&key));
+    }
+
+    #[test]
+    fn request_keys_distinguish_string_and_number_ids() {
+        assert_ne!(request_key("s", &json!(1)), request_key("s", &json!("1")));
+    }
+}
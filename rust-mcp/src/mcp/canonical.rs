@@ -0,0 +1,159 @@
+//! Canonical JSON for keys derived from tool arguments.
+//!
+//! Two argument objects that mean the same call must produce the same key: object keys are
+//! sorted, integral floats become integers (`1.0`, `1e0` and `1` are one value, `-0.0` is `0`),
+//! `null` members are dropped (absent and `null` are equivalent for every op), and members equal
+//! to their `inputSchema` default can be stripped. Array order is significant and kept.
+//!
+//! Capability payload digests are fixed by an external contract and do not use this module.
+
+use serde_json::{Map, Number, Value};
+use sha2::{Digest, Sha256};
+
+/// Largest integer an f64 represents exactly; integral floats beyond it stay floats.
+const MAX_EXACT_F64: f64 = 9_007_199_254_740_992.0;
+
+pub fn canonicalize(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            // Inserting in sorted order keeps the result sorted even with `preserve_order`.
+            let mut out = Map::new();
+            for key in keys {
+                let member = &map[key];
+                if !member.is_null() {
+                    out.insert(key.clone(), canonicalize(member));
+                }
+            }
+            Value::Object(out)
+        }
+        Value::Array(items) => Value::Array(items.iter().map(canonicalize).collect()),
+        Value::Number(number) => Value::Number(normalize_number(number)),
+        other => other.clone(),
+    }
+}
+
+fn normalize_number(number: &Number) -> Number {
+    match number.as_f64() {
+        Some(f) if number.is_f64() && f.fract() == 0.0 && f.abs() <= MAX_EXACT_F64 => {
+            Number::from(f as i64)
+        }
+        _ => number.clone(),
+    }
+}
+
+/// Drop object members equal to the `default` declared for them in `schema`, recursing into
+/// nested object properties.
+pub fn strip_defaults(value: &Value, schema: &Value) -> Value {
+    let (Value::Object(map), Some(properties)) =
+        (value, schema.get("properties").and_then(Value::as_object))
+    else {
+        return value.clone();
+    };
+    let mut out = Map::new();
+    for (key, member) in map {
+        let Some(property) = properties.get(key) else {
+            out.insert(key.clone(), member.clone());
+            continue;
+        };
+        let is_default = property
+            .get("default")
+            .is_some_and(|default| canonicalize(default) == canonicalize(member));
+        if !is_default {
+            out.insert(key.clone(), strip_defaults(member, property));
+        }
+    }
+    Value::Object(out)
+}
+
+/// Compact canonical encoding.
+pub fn to_canonical_string(value: &Value) -> String {
+    canonicalize(value).to_string()
+}
+
+/// `sha256:<hex>` of the canonical encoding; short and safe to use as a map key or file name.
+pub fn digest(value: &Value) -> String {
+    format!(
+        "sha256:{:x}",
+        Sha256::digest(to_canonical_string(value).as_bytes())
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn same(a: &str, b: &str) -> bool {
+        let a: Value = serde_json::from_str(a).unwrap();
+        let b: Value = serde_json::from_str(b).unwrap();
+        digest(&a) == digest(&b)
+    }
+
+    #[test]
+    fn key_order_and_whitespace_do_not_matter() {
+        assert!(same(
+            r#"{"model": "res.partner", "domain": [["id", "=", 1]]}"#,
+            r#"{ "domain":[["id","=",1]],"model":"res.partner" }"#
+        ));
+        assert!(same(
+            r#"{"a": {"y": 1, "x": 2}}"#,
+            r#"{"a": {"x": 2, "y": 1}}"#
+        ));
+    }
+
+    #[test]
+    fn numbers_are_normalized() {
+        assert!(same("[1, 1.0, 1e0, 100e-2]", "[1, 1, 1, 1]"));
+        assert!(same("-0.0", "0"));
+        assert!(!same("0.1", "0.10000001"));
+        assert!(same("0.1", "0.10"));
+        // Past 2^53 floats are not exact integers; they stay floats.
+        assert!(canonicalize(&json!(1e300)).is_f64());
+        assert!(canonicalize(&json!(4.0)).is_i64());
+        assert_eq!(to_canonical_string(&json!(u64::MAX)), u64::MAX.to_string());
+    }
+
+    #[test]
+    fn null_members_equal_absent_but_null_items_are_kept() {
+        assert!(same(
+            r#"{"limit": null, "model": "x"}"#,
+            r#"{"model": "x"}"#
+        ));
+        assert!(!same("[null]", "[]"));
+        assert!(!same("[1, 2]", "[2, 1]"));
+        assert!(!same(r#"{"a": 0}"#, r#"{}"#));
+    }
+
+    #[test]
+    fn string_escapes_are_equivalent() {
+        assert!(same(r#""Caf\u00e9""#, r#""Café""#));
+        assert!(!same(r#""1""#, "1"));
+    }
+
+    #[test]
+    fn strip_defaults_uses_schema_defaults() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "limit": {"type": "integer", "default": 80},
+                "options": {
+                    "type": "object",
+                    "properties": {"lazy": {"type": "boolean", "default": true}}
+                }
+            }
+        });
+        let explicit = json!({"model": "x", "limit": 80.0, "options": {"lazy": true}});
+        let implicit = json!({"model": "x", "options": {}});
+        assert_eq!(
+            digest(&strip_defaults(&explicit, &schema)),
+            digest(&strip_defaults(&implicit, &schema))
+        );
+        let other = json!({"model": "x", "limit": 10});
+        assert_ne!(
+            digest(&strip_defaults(&other, &schema)),
+            digest(&strip_defaults(&implicit, &schema))
+        );
+    }
+}
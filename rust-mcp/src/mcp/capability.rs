@@ -1072,6 +1072,11 @@ mod tests {
                 tool_config: None,
                 read_only: false,
                 tags: vec![],
+                headers: HashMap::new(),
+                proxy_auth: None,
+                lang: None,
+                translate_errors: false,
+                health_depth: None,
                 aliases: vec![],
                 extra,
             },
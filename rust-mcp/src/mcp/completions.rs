@@ -0,0 +1,257 @@
+//! MCP argument completion (`completion/complete`).
+//!
+//! Completion is driven by the argument name rather than the reference: `instance` completes
+//! configured instances, `model` completes `ir.model` names, and `fields`/`field`/`groupby`
+//! complete `fields_get` keys of the model given in `context.arguments.model`. This covers
+//! prompt and resource-template references as well as clients that send `ref/tool`.
+
+use mcp_rust_sdk::error::{Error, ErrorCode};
+use serde_json::{Value, json};
+
+use crate::mcp::cache::MetadataCache;
+use crate::mcp::tools::OdooClientPool;
+
+/// MCP caps a completion response at 100 values.
+const MAX_VALUES: usize = 100;
+
+const DEFAULT_TTL_SECS: u64 = 300;
+
+/// Cache key used for the model list of an instance; real model names are never empty.
+const MODELS_KEY: &str = "";
+
+fn completion_err(message: impl Into<String>) -> Error {
+    Error::protocol(ErrorCode::InvalidRequest, message)
+}
+
+fn cache_ttl() -> u64 {
+    std::env::var("ODOO_METADATA_CACHE_TTL_SECS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_TTL_SECS)
+}
+
+#[derive(Clone)]
+pub struct Completions {
+    cache: MetadataCache,
+}
+
+impl Default for Completions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Completions {
+    pub fn new() -> Self {
+        Self {
+            cache: MetadataCache::new(),
+        }
+    }
+
+    /// Handle `completion/complete` params and build the MCP result.
+    pub async fn complete(&self, pool: &OdooClientPool, params: &Value) -> Result<Value, Error> {
+        if params.get("ref").and_then(|r| r.get("type")).is_none() {
+            return Err(completion_err("completion/complete missing 'ref'"));
+        }
+        let argument = params
+            .get("argument")
+            .ok_or_else(|| completion_err("completion/complete missing 'argument'"))?;
+        let name = argument
+            .get("name")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| completion_err("completion argument missing 'name'"))?;
+        let value = argument.get("value").and_then(|v| v.as_str()).unwrap_or("");
+        let arguments = params
+            .get("context")
+            .and_then(|c| c.get("arguments"))
+            .cloned()
+            .unwrap_or_else(|| json!({}));
+        let context_arg = |key: &str| arguments.get(key).and_then(|v| v.as_str());
+
+        let instances = pool.instance_names();
+        // Fall back to the only configured instance when the client has not filled one in.
+        let instance = context_arg("instance")
+            .map(str::to_string)
+            .or_else(|| (instances.len() == 1).then(|| instances[0].clone()));
+
+        let candidates = match name {
+            "instance" => instances,
+            "model" => match instance {
+                Some(instance) => self.models(pool, &instance).await,
+                None => Vec::new(),
+            },
+            "fields" | "field" | "groupby" => match (instance, context_arg("model")) {
+                (Some(instance), Some(model)) => self.fields(pool, &instance, model).await,
+                _ => Vec::new(),
+            },
+            _ => Vec::new(),
+        };
+        Ok(completion_result(rank(candidates, value)))
+    }
+
+    pub(crate) async fn models(&self, pool: &OdooClientPool, instance: &str) -> Vec<String> {
+        self.cached(&pool.cache_key(instance), MODELS_KEY, || async {
+            let client = pool.get(instance).await.ok()?;
+            let rows = client
+                .search_read(
+                    "ir.model",
+                    Some(json!([])),
+                    Some(vec!["model".to_string()]),
+                    None,
+                    None,
+                    Some("model asc".to_string()),
+                    None,
+                )
+                .await
+                .ok()?;
+            Some(Value::Array(
+                rows.as_array()?
+                    .iter()
+                    .filter_map(|row| row.get("model").cloned())
+                    .collect(),
+            ))
+        })
+        .await
+    }
+
+    pub(crate) async fn fields(
+        &self,
+        pool: &OdooClientPool,
+        instance: &str,
+        model: &str,
+    ) -> Vec<String> {
+        self.cached(&pool.cache_key(instance), model, || async {
+            let client = pool.get(instance).await.ok()?;
+            let fields = client.fields_get(model, None).await.ok()?;
+            let mut names: Vec<Value> = fields.as_object()?.keys().map(|k| json!(k)).collect();
+            names.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+            Some(Value::Array(names))
+        })
+        .await
+    }
+
+    /// Read a name list from the per-instance cache, loading it on a miss.
+    ///
+    /// Failed loads are not cached so an unreachable instance is retried on the next keystroke.
+    async fn cached<F, Fut>(&self, instance: &str, key: &str, load: F) -> Vec<String>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Option<Value>>,
+    {
+        let ttl = cache_ttl();
+        let value = match self.cache.get(instance, key).await {
+            Some(value) if ttl > 0 => Some(value),
+            _ => {
+                let loaded = load().await;
+                if let Some(value) = loaded.as_ref()
+                    && ttl > 0
+                {
+                    self.cache.insert(instance, key, value.clone(), ttl).await;
+                }
+                loaded
+            }
+        };
+        value
+            .as_ref()
+            .and_then(|v| v.as_array())
+            .map(|names| {
+                names
+                    .iter()
+                    .filter_map(|n| n.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Case-insensitive matches: prefix matches first, then substring matches, each sorted.
+fn rank(candidates: Vec<String>, value: &str) -> Vec<String> {
+    let needle = value.to_lowercase();
+    let (mut prefix, mut contains): (Vec<String>, Vec<String>) = candidates
+        .into_iter()
+        .filter(|c| c.to_lowercase().contains(&needle))
+        .partition(|c| c.to_lowercase().starts_with(&needle));
+    prefix.sort();
+    contains.sort();
+    prefix.extend(contains);
+    prefix
+}
+
+fn completion_result(matches: Vec<String>) -> Value {
+    let total = matches.len();
+    let values: Vec<String> = matches.into_iter().take(MAX_VALUES).collect();
+    json!({
+        "completion": {
+            "values": values,
+            "total": total,
+            "hasMore": total > MAX_VALUES
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn rank_puts_prefix_matches_first() {
+        let ranked = rank(
+            names(&[
+                "sale.order.line",
+                "res.partner",
+                "sale.order",
+                "crm.lead.sale",
+            ]),
+            "SALE",
+        );
+        assert_eq!(
+            ranked,
+            names(&["sale.order", "sale.order.line", "crm.lead.sale"])
+        );
+        assert_eq!(rank(names(&["b", "a"]), "").len(), 2);
+    }
+
+    #[test]
+    fn completion_result_caps_values() {
+        let many: Vec<String> = (0..150).map(|i| format!("x_model_{i:03}")).collect();
+        let result = completion_result(many);
+        assert_eq!(
+            result["completion"]["values"].as_array().unwrap().len(),
+            100
+        );
+        assert_eq!(result["completion"]["total"], 150);
+        assert_eq!(result["completion"]["hasMore"], true);
+    }
+
+    #[tokio::test]
+    async fn complete_instances_without_odoo_access() {
+        use crate::odoo::config::OdooEnvConfig;
+        use std::collections::HashMap;
+
+        let pool = OdooClientPool::from_config(OdooEnvConfig {
+            instances: HashMap::new(),
+        });
+        let completions = Completions::new();
+        let result = completions
+            .complete(
+                &pool,
+                &json!({
+                    "ref": {"type": "ref/prompt", "name": "explore"},
+                    "argument": {"name": "model", "value": "sa"}
+                }),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result["completion"]["total"], 0);
+        assert!(
+            completions
+                .complete(&pool, &json!({"argument": {"name": "model"}}))
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,741 @@
+//! `odoo-rust-mcp check`: lint the configuration without starting the server.
+//!
+//! Every check runs, so one pass lists every problem: environment variables, the Odoo
+//! instances, tools.json, prompts.json, server.json, and the auth / TLS combination. Each
+//! [`Diagnostic`] has a severity, a stable `code` to filter on in CI, the `source` it came from
+//! and, where there is one, the tool, instance or variable it is about. [`Report::exit_code`] is
+//! 0 without errors, 1 with errors, and 2 when `--strict` is given and there are warnings.
+//!
+//! Nothing is contacted or written: instances are checked by building their clients, not by
+//! connecting (use `validate-config` for that).
+
+use std::collections::HashSet;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::mcp::audit_export::ExportConfig;
+use crate::mcp::auth_tokens::load_tokens_from_env;
+use crate::mcp::event_sink::EventSinkConfig;
+use crate::mcp::registry::{load_prompts_file, load_server_file, load_tools_file, validate_tool};
+use crate::mcp::renderers;
+use crate::mcp::request_signing::RequestVerifier;
+use crate::mcp::tenants::load_tenants_from_env;
+use crate::mcp::tls::TlsSettings;
+use crate::odoo::config::load_odoo_env;
+use crate::odoo::health::HealthDepth;
+use crate::odoo::unified_client::OdooClient;
+
+/// Env vars tool guards may name; anything else is most likely a typo or a stale name.
+const GUARD_VARS: &[&str] = &[
+    "ODOO_ENABLE_WRITE_TOOLS",
+    "ODOO_ENABLE_CLEANUP_TOOLS",
+    "ODOO_ENABLE_EXECUTE_TOOL",
+    "ODOO_ENABLE_ADMIN_TOOLS",
+    "ODOO_ALLOW_SESSION_INSTANCES",
+    "ODOO_CAPABILITY_CONTROLLED_MODE",
+];
+
+/// Boolean env vars read with the guard rules (`1`, `true`, `yes`, `y`, `on`).
+const FLAG_VARS: &[&str] = &[
+    "ODOO_ENABLE_WRITE_TOOLS",
+    "ODOO_ENABLE_CLEANUP_TOOLS",
+    "ODOO_ENABLE_EXECUTE_TOOL",
+    "ODOO_ENABLE_ADMIN_TOOLS",
+    "ODOO_ALLOW_SESSION_INSTANCES",
+    "ODOO_CAPABILITY_CONTROLLED_MODE",
+    "ODOO_CONFIRM_DESTRUCTIVE",
+    "ODOO_READ_ONLY",
+    "ODOO_TRANSLATE_ERRORS",
+];
+
+/// Numeric env vars that fall back to their default when they do not parse.
+const NUMBER_VARS: &[&str] = &[
+    "ODOO_TIMEOUT_MS",
+    "ODOO_MAX_RETRIES",
+    "ODOO_READ_CACHE_TTL_SECS",
+    "ODOO_MODULE_SNAPSHOT_TTL_SECS",
+    "ODOO_RESOURCE_POLL_SECS",
+    "ODOO_CONFIRM_TTL_SECS",
+    "ODOO_MAX_ATTACHMENT_BYTES",
+    "MCP_MAX_BODY_BYTES",
+    "MCP_REQUEST_TIMEOUT_SECS",
+    "MCP_SESSION_IDLE_TTL_SECS",
+    "MCP_SSE_DISCONNECT_GRACE_SECS",
+    "MCP_INSPECTOR_REQUESTS",
+    "MCP_AUDIT_LOG_MAX_BYTES",
+    "MCP_AUDIT_LOG_MAX_FILES",
+];
+
+/// Instance keys kept in `extra` on purpose; other unknown keys are reported.
+const EXTRA_INSTANCE_KEYS: &[&str] = &["edition", "databaseRef"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Stable identifier such as `tools.unknown_guard`.
+    pub code: &'static str,
+    /// `env`, `instances`, `tools.json`, `prompts.json`, `server.json`, `auth` or `tls`.
+    pub source: &'static str,
+    /// Tool, prompt, instance or env var the diagnostic is about.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    pub message: String,
+}
+
+/// Where to find the config and how the server would be started.
+#[derive(Debug, Clone)]
+pub struct CheckOptions {
+    pub tools_path: PathBuf,
+    pub prompts_path: PathBuf,
+    pub server_path: PathBuf,
+    /// Listen address of the HTTP/WebSocket transport; None for stdio.
+    pub listen: Option<String>,
+}
+
+impl CheckOptions {
+    /// Config paths from `MCP_TOOLS_JSON` / `MCP_PROMPTS_JSON` / `MCP_SERVER_JSON`, as the
+    /// registry resolves them.
+    pub fn from_env(listen: Option<String>) -> Self {
+        let path = |var: &str, default: &str| {
+            PathBuf::from(std::env::var(var).unwrap_or_else(|_| default.to_string()))
+        };
+        Self {
+            tools_path: path("MCP_TOOLS_JSON", "config/tools.json"),
+            prompts_path: path("MCP_PROMPTS_JSON", "config/prompts.json"),
+            server_path: path("MCP_SERVER_JSON", "config/server.json"),
+            listen,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct Report {
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Report {
+    fn push(
+        &mut self,
+        severity: Severity,
+        code: &'static str,
+        source: &'static str,
+        subject: Option<&str>,
+        message: impl Into<String>,
+    ) {
+        self.diagnostics.push(Diagnostic {
+            severity,
+            code,
+            source,
+            subject: subject.map(str::to_string),
+            message: message.into(),
+        });
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.severity == severity)
+            .count()
+    }
+
+    pub fn exit_code(&self, strict: bool) -> i32 {
+        if self.count(Severity::Error) > 0 {
+            1
+        } else if strict && self.count(Severity::Warning) > 0 {
+            2
+        } else {
+            0
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "ok": self.count(Severity::Error) == 0,
+            "errors": self.count(Severity::Error),
+            "warnings": self.count(Severity::Warning),
+            "diagnostics": self.diagnostics,
+        })
+    }
+}
+
+/// Run every check.
+pub fn run(options: &CheckOptions) -> Report {
+    let mut report = Report::default();
+    check_env(&mut report);
+    check_instances(&mut report);
+    check_tools(&mut report, &options.tools_path);
+    check_prompts(&mut report, &options.prompts_path);
+    let server_tls = check_server(&mut report, &options.server_path);
+    check_auth_and_tls(&mut report, options.listen.as_deref(), server_tls);
+    report
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+pub fn check_env(report: &mut Report) {
+    for var in FLAG_VARS {
+        if let Some(value) = env_var(var)
+            && !matches!(
+                value.to_ascii_lowercase().as_str(),
+                "1" | "true" | "yes" | "y" | "on" | "0" | "false" | "no" | "n" | "off"
+            )
+        {
+            report.push(
+                Severity::Warning,
+                "env.not_boolean",
+                "env",
+                Some(var),
+                format!("'{value}' is not a boolean and is read as false"),
+            );
+        }
+    }
+    if let Some(value) = env_var("MCP_AUTH_ENABLED")
+        && !matches!(
+            value.to_ascii_lowercase().as_str(),
+            "1" | "true" | "0" | "false"
+        )
+    {
+        report.push(
+            Severity::Warning,
+            "env.not_boolean",
+            "env",
+            Some("MCP_AUTH_ENABLED"),
+            format!("'{value}' disables authentication; only 'true' or '1' enable it"),
+        );
+    }
+    for var in NUMBER_VARS {
+        if let Some(value) = env_var(var)
+            && value.parse::<u64>().is_err()
+        {
+            report.push(
+                Severity::Warning,
+                "env.not_a_number",
+                "env",
+                Some(var),
+                format!("'{value}' is not a non-negative integer; the default applies"),
+            );
+        }
+    }
+    for var in ["ODOO_HEALTH_DEPTH", "ODOO_READYZ_DEPTH"] {
+        if let Some(Err(e)) = env_var(var).map(|v| v.parse::<HealthDepth>()) {
+            report.push(
+                Severity::Warning,
+                "env.invalid_value",
+                "env",
+                Some(var),
+                format!("{e}; the default applies"),
+            );
+        }
+    }
+    if let Some(format) = env_var("MCP_RESULT_FORMAT")
+        && !renderers::names().contains(&format.to_ascii_lowercase().as_str())
+    {
+        report.push(
+            Severity::Warning,
+            "env.invalid_value",
+            "env",
+            Some("MCP_RESULT_FORMAT"),
+            format!(
+                "Unknown format '{format}' (known: {}); json applies",
+                renderers::names().join(", ")
+            ),
+        );
+    }
+    if let Some(backend) = env_var("MCP_AUDIT_LOG")
+        && !matches!(
+            backend.to_ascii_lowercase().as_str(),
+            "off" | "jsonl" | "sqlite"
+        )
+    {
+        report.push(
+            Severity::Error,
+            "env.invalid_value",
+            "env",
+            Some("MCP_AUDIT_LOG"),
+            format!("Unknown audit log '{backend}' (expected off, jsonl or sqlite)"),
+        );
+    }
+    // These fail startup when set wrong.
+    let startup: [(&str, anyhow::Result<()>); 3] = [
+        ("MCP_AUDIT_EXPORT_*", ExportConfig::from_env().map(|_| ())),
+        ("MCP_EVENT_SINK*", EventSinkConfig::from_env().map(|_| ())),
+        (
+            "MCP_REQUEST_SIGNING_*",
+            RequestVerifier::from_env().map(|_| ()),
+        ),
+    ];
+    for (vars, result) in startup {
+        if let Err(e) = result {
+            report.push(
+                Severity::Error,
+                "env.invalid_value",
+                "env",
+                Some(vars),
+                e.to_string(),
+            );
+        }
+    }
+}
+
+pub fn check_instances(report: &mut Report) {
+    let env = match load_odoo_env() {
+        Ok(env) => env,
+        Err(e) => {
+            report.push(
+                Severity::Error,
+                "instances.invalid",
+                "instances",
+                None,
+                e.to_string(),
+            );
+            return;
+        }
+    };
+    let mut names: Vec<&String> = env.instances.keys().collect();
+    names.sort();
+    for name in names {
+        let cfg = &env.instances[name];
+        if let Err(e) = OdooClient::new(cfg) {
+            report.push(
+                Severity::Error,
+                "instances.invalid",
+                "instances",
+                Some(name),
+                e.to_string(),
+            );
+        }
+        if cfg.username_looks_like_url() {
+            report.push(
+                Severity::Warning,
+                "instances.username_is_url",
+                "instances",
+                Some(name),
+                "username looks like a URL; url and username are probably swapped",
+            );
+        }
+        let mut unknown: Vec<&String> = cfg
+            .extra
+            .keys()
+            .filter(|key| !EXTRA_INSTANCE_KEYS.contains(&key.as_str()))
+            .collect();
+        unknown.sort();
+        for key in unknown {
+            report.push(
+                Severity::Warning,
+                "instances.unknown_key",
+                "instances",
+                Some(name),
+                format!("Unknown key '{key}' is ignored"),
+            );
+        }
+    }
+}
+
+pub fn check_tools(report: &mut Report, path: &Path) {
+    const SOURCE: &str = "tools.json";
+    if !path.exists() {
+        report.push(
+            Severity::Info,
+            "tools.missing",
+            SOURCE,
+            None,
+            format!(
+                "{} does not exist; defaults are written on start",
+                path.display()
+            ),
+        );
+        return;
+    }
+    let tools = match load_tools_file(path) {
+        Ok(tools) => tools,
+        Err(e) => {
+            report.push(
+                Severity::Error,
+                "tools.invalid",
+                SOURCE,
+                None,
+                e.to_string(),
+            );
+            return;
+        }
+    };
+    let mut seen = HashSet::new();
+    for tool in &tools {
+        let name = Some(tool.name.as_str());
+        if !seen.insert(tool.name.as_str()) {
+            report.push(
+                Severity::Error,
+                "tools.duplicate",
+                SOURCE,
+                name,
+                "Duplicate tool name",
+            );
+        }
+        if let Err(e) = validate_tool(tool) {
+            report.push(Severity::Error, "tools.schema", SOURCE, name, e.to_string());
+        }
+        let guards = tool.guards.iter().flat_map(|g| {
+            g.requires_env_true
+                .iter()
+                .chain(g.requires_env_true_all.iter())
+        });
+        for var in guards {
+            if !GUARD_VARS.contains(&var.as_str()) {
+                report.push(
+                    Severity::Warning,
+                    "tools.unknown_guard",
+                    SOURCE,
+                    name,
+                    format!(
+                        "Guard '{var}' is not one of {}; the tool stays hidden unless it is set",
+                        GUARD_VARS.join(", ")
+                    ),
+                );
+            }
+        }
+        let Some(properties) = tool
+            .input_schema
+            .get("properties")
+            .and_then(Value::as_object)
+        else {
+            continue;
+        };
+        let required = tool
+            .input_schema
+            .get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str);
+        for key in required {
+            if !properties.contains_key(key) {
+                report.push(
+                    Severity::Warning,
+                    "tools.required_not_defined",
+                    SOURCE,
+                    name,
+                    format!("'{key}' is required but not a property of inputSchema"),
+                );
+            }
+        }
+        let mut mapped: Vec<&String> = Vec::new();
+        for (key, pointer) in &tool.op.map {
+            if let Some(arg) = pointer.strip_prefix('/')
+                && !arg.contains('/')
+                && !properties.contains_key(arg)
+                && !tool.op.pinned.contains_key(key)
+            {
+                mapped.push(key);
+            }
+        }
+        mapped.sort();
+        for key in mapped {
+            report.push(
+                Severity::Warning,
+                "tools.map_unknown_property",
+                SOURCE,
+                name,
+                format!(
+                    "op.map '{key}' points at '{}', which inputSchema does not define",
+                    tool.op.map[key]
+                ),
+            );
+        }
+    }
+}
+
+pub fn check_prompts(report: &mut Report, path: &Path) {
+    const SOURCE: &str = "prompts.json";
+    if !path.exists() {
+        report.push(
+            Severity::Info,
+            "prompts.missing",
+            SOURCE,
+            None,
+            format!(
+                "{} does not exist; defaults are written on start",
+                path.display()
+            ),
+        );
+        return;
+    }
+    match load_prompts_file(path) {
+        Ok(prompts) => {
+            let mut seen = HashSet::new();
+            for prompt in &prompts {
+                if !seen.insert(prompt.name.as_str()) {
+                    report.push(
+                        Severity::Error,
+                        "prompts.duplicate",
+                        SOURCE,
+                        Some(&prompt.name),
+                        "Duplicate prompt name",
+                    );
+                }
+                if prompt.content.trim().is_empty() {
+                    report.push(
+                        Severity::Warning,
+                        "prompts.empty",
+                        SOURCE,
+                        Some(&prompt.name),
+                        "Prompt has no content",
+                    );
+                }
+            }
+        }
+        Err(e) => report.push(
+            Severity::Error,
+            "prompts.invalid",
+            SOURCE,
+            None,
+            e.to_string(),
+        ),
+    }
+}
+
+/// Check server.json and return its TLS settings.
+pub fn check_server(report: &mut Report, path: &Path) -> Option<TlsSettings> {
+    const SOURCE: &str = "server.json";
+    if !path.exists() {
+        report.push(
+            Severity::Info,
+            "server.missing",
+            SOURCE,
+            None,
+            format!(
+                "{} does not exist; defaults are written on start",
+                path.display()
+            ),
+        );
+        return None;
+    }
+    match load_server_file(path) {
+        Ok(server) => server.tls,
+        Err(e) => {
+            report.push(
+                Severity::Error,
+                "server.invalid",
+                SOURCE,
+                None,
+                e.to_string(),
+            );
+            None
+        }
+    }
+}
+
+fn is_loopback(listen: &str) -> bool {
+    match listen.parse::<SocketAddr>() {
+        Ok(addr) => addr.ip().is_loopback(),
+        Err(_) => listen
+            .rsplit_once(':')
+            .map(|(host, _)| host.trim_matches(['[', ']']))
+            .is_some_and(|host| {
+                host == "localhost" || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+            }),
+    }
+}
+
+pub fn check_auth_and_tls(
+    report: &mut Report,
+    listen: Option<&str>,
+    server_tls: Option<TlsSettings>,
+) {
+    let tls = match TlsSettings::from_env() {
+        Ok(env_tls) => env_tls.or(server_tls),
+        Err(e) => {
+            report.push(
+                Severity::Error,
+                "tls.incomplete",
+                "tls",
+                None,
+                e.to_string(),
+            );
+            None
+        }
+    };
+    if let Some(settings) = &tls
+        && let Err(e) = settings.server_config()
+    {
+        report.push(Severity::Error, "tls.invalid", "tls", None, e.to_string());
+    }
+    if tls.is_none() && env_var("MCP_TLS_CLIENT_CA").is_some() {
+        report.push(
+            Severity::Warning,
+            "tls.client_ca_unused",
+            "tls",
+            Some("MCP_TLS_CLIENT_CA"),
+            "Client certificates need MCP_TLS_CERT and MCP_TLS_KEY; it is ignored",
+        );
+    }
+
+    let enabled = env_var("MCP_AUTH_ENABLED")
+        .is_some_and(|v| matches!(v.to_ascii_lowercase().as_str(), "true" | "1"));
+    let bearer = env_var("MCP_AUTH_TOKEN").is_some();
+    let tokens = load_tokens_from_env().unwrap_or_else(|e| {
+        report.push(
+            Severity::Error,
+            "auth.tokens_invalid",
+            "auth",
+            Some("MCP_AUTH_TOKENS_JSON"),
+            format!("{e}; the server would ignore tokens.json"),
+        );
+        Vec::new()
+    });
+    let tenants = load_tenants_from_env().unwrap_or_else(|e| {
+        report.push(
+            Severity::Error,
+            "auth.tenants_invalid",
+            "auth",
+            Some("MCP_TENANTS_JSON"),
+            format!("{e}; the server would ignore tenants.json"),
+        );
+        Vec::new()
+    });
+    for token in &tokens {
+        if let Some(tenant) = &token.tenant
+            && !tenants.iter().any(|t| &t.name == tenant)
+        {
+            report.push(
+                Severity::Warning,
+                "auth.unknown_tenant",
+                "auth",
+                Some(&token.name),
+                format!("Token names undefined tenant '{tenant}'; it can reach no instances"),
+            );
+        }
+    }
+    if enabled && !bearer && tokens.is_empty() {
+        report.push(
+            Severity::Error,
+            "auth.no_credentials",
+            "auth",
+            Some("MCP_AUTH_ENABLED"),
+            "Authentication is enabled but neither MCP_AUTH_TOKEN nor tokens.json is set",
+        );
+    }
+    if !enabled && (bearer || !tokens.is_empty()) {
+        report.push(
+            Severity::Warning,
+            "auth.credentials_unused",
+            "auth",
+            Some("MCP_AUTH_ENABLED"),
+            "Tokens are configured but MCP_AUTH_ENABLED is not true; they are not checked",
+        );
+    }
+    if let Some(listen) = listen
+        && !is_loopback(listen)
+    {
+        if !enabled {
+            report.push(
+                Severity::Warning,
+                "auth.open_listener",
+                "auth",
+                Some(listen),
+                "Listening beyond localhost without authentication",
+            );
+        } else if tls.is_none() {
+            report.push(
+                Severity::Warning,
+                "tls.plaintext_credentials",
+                "tls",
+                Some(listen),
+                "Bearer tokens travel unencrypted: listening beyond localhost without TLS",
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tools_are_linted_for_guards_mapping_and_schema() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tools.json");
+        std::fs::write(
+            &path,
+            json!({
+                "tools": [
+                    {
+                        "name": "odoo_thing",
+                        "description": "d",
+                        "guards": { "requiresEnvTrue": "ODOO_ENABLE_WRITE_OPERATIONS" },
+                        "inputSchema": {
+                            "type": "object",
+                            "properties": { "instance": { "type": "string" } },
+                            "required": ["instance", "model"]
+                        },
+                        "op": { "type": "search", "map": { "instance": "/instance", "model": "/model" } }
+                    },
+                    {
+                        "name": "odoo_thing",
+                        "description": "d",
+                        "inputSchema": { "type": ["object", "null"] },
+                        "op": { "type": "search", "map": {} }
+                    }
+                ]
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let mut report = Report::default();
+        check_tools(&mut report, &path);
+        let codes: Vec<&str> = report.diagnostics.iter().map(|d| d.code).collect();
+        assert_eq!(
+            codes,
+            [
+                "tools.unknown_guard",
+                "tools.required_not_defined",
+                "tools.map_unknown_property",
+                "tools.duplicate",
+                "tools.schema",
+            ]
+        );
+        assert_eq!(report.exit_code(false), 1);
+        assert_eq!(report.to_json()["errors"], 2);
+    }
+
+    #[test]
+    fn shipped_config_is_clean() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("config");
+        let mut report = Report::default();
+        check_tools(&mut report, &root.join("tools.json"));
+        check_prompts(&mut report, &root.join("prompts.json"));
+        check_server(&mut report, &root.join("server.json"));
+        assert!(report.diagnostics.is_empty(), "{:#?}", report.diagnostics);
+    }
+
+    #[test]
+    fn exit_codes_follow_the_worst_severity() {
+        let mut report = Report::default();
+        assert_eq!(report.exit_code(true), 0);
+        report.push(Severity::Warning, "x", "env", None, "w");
+        assert_eq!(report.exit_code(false), 0);
+        assert_eq!(report.exit_code(true), 2);
+        report.push(Severity::Error, "y", "env", None, "e");
+        assert_eq!(report.exit_code(true), 1);
+
+        assert!(is_loopback("127.0.0.1:8787"));
+        assert!(is_loopback("localhost:8787"));
+        assert!(!is_loopback("0.0.0.0:8787"));
+    }
+}
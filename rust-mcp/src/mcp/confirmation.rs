@@ -0,0 +1,104 @@
+//! Two-phase confirmation for destructive tool calls.
+//!
+//! With `ODOO_CONFIRM_DESTRUCTIVE=true`, `unlink` and non-dry-run `database_cleanup` /
+//! `deep_cleanup` calls do not run on the first request. `call_tool` answers with a preview and
+//! a single-use `confirmationToken`; repeating the call with the same arguments plus that token
+//! within `ODOO_CONFIRM_TTL_SECS` (default 300) executes it. A token is bound to the client scope,
+//! tool, instance and arguments, so it cannot confirm a different call.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::mcp::registry::env_truthy;
+
+/// Argument carrying the token on the confirming call.
+pub const TOKEN_ARG: &str = "confirmationToken";
+
+const DEFAULT_TTL_SECS: u64 = 300;
+
+pub fn enabled() -> bool {
+    env_truthy("ODOO_CONFIRM_DESTRUCTIVE")
+}
+
+pub fn ttl() -> Duration {
+    let secs = std::env::var("ODOO_CONFIRM_TTL_SECS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_TTL_SECS);
+    Duration::from_secs(secs.max(1))
+}
+
+struct Pending {
+    scope: Option<String>,
+    call_digest: String,
+    expires: Instant,
+}
+
+/// Outstanding confirmation tokens.
+#[derive(Clone, Default)]
+pub struct ConfirmationStore {
+    pending: Arc<Mutex<HashMap<String, Pending>>>,
+}
+
+impl ConfirmationStore {
+    /// A new token for the call identified by `call_digest`, valid for `ttl`.
+    pub fn issue(&self, scope: Option<&str>, call_digest: &str, ttl: Duration) -> String {
+        let token = uuid::Uuid::new_v4().simple().to_string();
+        if let Ok(mut pending) = self.pending.lock() {
+            let now = Instant::now();
+            pending.retain(|_, entry| entry.expires > now);
+            pending.insert(
+                token.clone(),
+                Pending {
+                    scope: scope.map(str::to_string),
+                    call_digest: call_digest.to_string(),
+                    expires: now + ttl,
+                },
+            );
+        }
+        token
+    }
+
+    /// Consume `token` if it was issued to `scope` for the same call and has not expired.
+    ///
+    /// A token presented for a different call is left in place for the call it belongs to.
+    pub fn redeem(&self, token: &str, scope: Option<&str>, call_digest: &str) -> bool {
+        let Ok(mut pending) = self.pending.lock() else {
+            return false;
+        };
+        let matches = pending.get(token).is_some_and(|entry| {
+            entry.scope.as_deref() == scope
+                && entry.call_digest == call_digest
+                && entry.expires > Instant::now()
+        });
+        if matches {
+            pending.remove(token);
+        }
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_are_single_use_and_bound_to_the_call() {
+        let store = ConfirmationStore::default();
+        let token = store.issue(Some("s1"), "sha256:a", Duration::from_secs(60));
+
+        assert!(!store.redeem(&token, Some("s2"), "sha256:a"));
+        assert!(!store.redeem(&token, Some("s1"), "sha256:b"));
+        assert!(!store.redeem("other", Some("s1"), "sha256:a"));
+        assert!(store.redeem(&token, Some("s1"), "sha256:a"));
+        assert!(!store.redeem(&token, Some("s1"), "sha256:a"));
+    }
+
+    #[test]
+    fn expired_tokens_are_rejected() {
+        let store = ConfirmationStore::default();
+        let token = store.issue(None, "sha256:a", Duration::ZERO);
+        assert!(!store.redeem(&token, None, "sha256:a"));
+    }
+}
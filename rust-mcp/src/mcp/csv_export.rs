@@ -0,0 +1,120 @@
+//! CSV rendering for `odoo_export_records`: `search_read` rows flattened into
+//! spreadsheet-ready cells using the field types from `fields_get`. `odoo_import_records`
+//! reuses the encoder for the file it hands Odoo's import wizard on a dry run.
+
+use serde_json::Value;
+
+/// Separator for the ids of one2many/many2many cells.
+const X2MANY_SEPARATOR: &str = ";";
+
+/// Column header: the field name, or its label from `fields_get` when `labels` is set.
+pub fn header(field: &str, fields: &Value, labels: bool) -> String {
+    if !labels {
+        return field.to_string();
+    }
+    fields[field]["string"]
+        .as_str()
+        .map(str::to_string)
+        .unwrap_or_else(|| field.to_string())
+}
+
+/// One cell. Many2one `[id, display_name]` pairs become the display name, x2many id lists
+/// are joined with `;`, and Odoo's `false` for an empty non-boolean field becomes empty.
+pub fn cell(value: &Value, field_type: Option<&str>) -> String {
+    match (value, field_type) {
+        (Value::Bool(b), Some("boolean")) => b.to_string(),
+        (Value::Bool(false) | Value::Null, _) => String::new(),
+        (Value::Array(pair), Some("many2one") | None)
+            if pair.len() == 2 && pair[0].is_i64() && pair[1].is_string() =>
+        {
+            pair[1].as_str().unwrap_or_default().to_string()
+        }
+        (Value::Array(items), Some("one2many" | "many2many")) => items
+            .iter()
+            .map(|item| match item {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(X2MANY_SEPARATOR),
+        (Value::String(s), _) => s.clone(),
+        (Value::Number(n), _) => n.to_string(),
+        (other, _) => other.to_string(),
+    }
+}
+
+/// Quote a cell per RFC 4180 when it holds a comma, quote or line break.
+fn escape(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+/// Encode rows of cells as CSV (CRLF line endings).
+pub fn encode_rows(rows: &[Vec<String>]) -> String {
+    let mut out = String::new();
+    for row in rows {
+        let cells: Vec<String> = row.iter().map(|c| escape(c)).collect();
+        out.push_str(&cells.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// Render `records` as CSV with one column per entry of `columns`, in that order.
+pub fn to_csv(records: &[Value], columns: &[String], fields: &Value, labels: bool) -> String {
+    let mut rows = Vec::with_capacity(records.len() + 1);
+    rows.push(
+        columns
+            .iter()
+            .map(|column| header(column, fields, labels))
+            .collect(),
+    );
+    rows.extend(records.iter().map(|record| {
+        columns
+            .iter()
+            .map(|column| cell(&record[column], fields[column]["type"].as_str()))
+            .collect()
+    }));
+    encode_rows(&rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn flattens_relational_and_empty_values() {
+        assert_eq!(
+            cell(&json!([7, "Azure Interior"]), Some("many2one")),
+            "Azure Interior"
+        );
+        assert_eq!(cell(&json!(false), Some("many2one")), "");
+        assert_eq!(cell(&json!(false), Some("char")), "");
+        assert_eq!(cell(&json!(false), Some("boolean")), "false");
+        assert_eq!(cell(&json!([1, 2, 3]), Some("many2many")), "1;2;3");
+        assert_eq!(cell(&json!(12.5), Some("monetary")), "12.5");
+        assert_eq!(cell(&json!([3, "Mitchell Admin"]), None), "Mitchell Admin");
+    }
+
+    #[test]
+    fn renders_rfc4180_csv_with_labels() {
+        let fields = json!({
+            "name": {"type": "char", "string": "Name"},
+            "partner_id": {"type": "many2one", "string": "Customer"},
+        });
+        let records = [
+            json!({"id": 1, "name": "Desk, large", "partner_id": [7, "Azure \"AZ\" Interior"]}),
+            json!({"id": 2, "name": "Chair", "partner_id": false}),
+        ];
+        let columns = ["name".to_string(), "partner_id".to_string()];
+        assert_eq!(
+            to_csv(&records, &columns, &fields, true),
+            "Name,Customer\r\n\"Desk, large\",\"Azure \"\"AZ\"\" Interior\"\r\nChair,\r\n"
+        );
+        assert!(to_csv(&[], &columns, &fields, false).starts_with("name,partner_id\r\n"));
+    }
+}
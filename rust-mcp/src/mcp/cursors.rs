@@ -0,0 +1,172 @@
+//! Opaque pagination cursors for `search` and `search_read` results.
+//!
+//! When a page comes back full (as many records as `limit`), the result carries `nextCursor`:
+//! the call's instance, model, domain, fields, order, limit and context plus the next offset,
+//! as base64url JSON. Passing that cursor as the only argument of the next call to the same
+//! tool fetches the following page. [`crate::mcp::tools::call_tool`] expands it back into
+//! arguments before anything else looks at them, so scopes, tenants, validation and the read
+//! cache treat the page like any explicit call.
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde_json::{Map, Value, json};
+
+use crate::mcp::registry::OpSpec;
+
+pub const CURSOR_ARG: &str = "cursor";
+const VERSION: i64 = 1;
+
+/// Ops whose results are paged with cursors.
+pub const CURSOR_OPS: &[&str] = &["search", "search_read"];
+
+/// Arguments a cursor carries, besides `offset`.
+const CARRIED: &[&str] = &[
+    "instance", "model", "domain", "fields", "order", "limit", "context",
+];
+
+/// Cursor for the page after the one `args` fetched, when that page held `returned` records
+/// and so may not be the last.
+pub fn next_cursor(op: &OpSpec, args: &Value, returned: usize) -> Option<String> {
+    let limit = arg(op, args, "limit")?
+        .as_i64()
+        .filter(|limit| *limit > 0)?;
+    if (returned as i64) < limit {
+        return None;
+    }
+    let offset = arg(op, args, "offset").and_then(Value::as_i64).unwrap_or(0);
+    let mut carried = Map::new();
+    for key in CARRIED {
+        if let Some(value) = arg(op, args, key).filter(|v| !v.is_null()) {
+            carried.insert((*key).to_string(), value.clone());
+        }
+    }
+    carried.insert("offset".into(), json!(offset + limit));
+    let cursor = json!({ "v": VERSION, "op": op.op_type, "args": carried });
+    Some(URL_SAFE_NO_PAD.encode(cursor.to_string()))
+}
+
+/// Replace a `cursor` argument with the arguments it encodes. Other arguments are refused
+/// alongside it so a page can't silently mix two queries.
+pub fn expand(op: &OpSpec, args: &mut Value) -> Result<(), String> {
+    if !CURSOR_OPS.contains(&op.op_type.as_str()) {
+        return Ok(());
+    }
+    let Some(map) = args.as_object_mut() else {
+        return Ok(());
+    };
+    let Some(cursor) = map.remove(CURSOR_ARG) else {
+        return Ok(());
+    };
+    if !map.is_empty() {
+        let mut extra: Vec<&String> = map.keys().collect();
+        extra.sort();
+        return Err(format!(
+            "'{CURSOR_ARG}' must be the only argument (also got {})",
+            extra
+                .iter()
+                .map(|k| k.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    let invalid = || format!("'{CURSOR_ARG}' is not a cursor returned by this tool");
+    let decoded = cursor
+        .as_str()
+        .and_then(|text| URL_SAFE_NO_PAD.decode(text).ok())
+        .and_then(|bytes| serde_json::from_slice::<Value>(&bytes).ok())
+        .ok_or_else(invalid)?;
+    if decoded.get("v").and_then(Value::as_i64) != Some(VERSION)
+        || decoded.get("op").and_then(Value::as_str) != Some(op.op_type.as_str())
+    {
+        return Err(invalid());
+    }
+    let carried = decoded
+        .get("args")
+        .and_then(Value::as_object)
+        .ok_or_else(invalid)?;
+    // Arguments the tool pins itself (a per-model tool's `model`) are not the caller's to pass.
+    for (key, value) in carried
+        .iter()
+        .filter(|(key, _)| !op.pinned.contains_key(*key))
+    {
+        // Arguments live where the tool's op map points; tools.json maps them flat.
+        let Some(name) = op
+            .map
+            .get(key)
+            .and_then(|pointer| pointer.strip_prefix('/'))
+            .filter(|name| !name.contains('/'))
+        else {
+            return Err(invalid());
+        };
+        map.insert(name.to_string(), value.clone());
+    }
+    Ok(())
+}
+
+fn arg<'a>(op: &OpSpec, args: &'a Value, key: &str) -> Option<&'a Value> {
+    op.map.get(key).and_then(|pointer| args.pointer(pointer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn op(op_type: &str) -> OpSpec {
+        let mut op: OpSpec = serde_json::from_value(json!({"type": op_type, "map": {}})).unwrap();
+        for key in CARRIED.iter().chain(&["offset"]) {
+            op.map.insert((*key).to_string(), format!("/{key}"));
+        }
+        op
+    }
+
+    #[test]
+    fn full_pages_carry_a_cursor_to_the_next_page() {
+        let op = op("search_read");
+        let args = json!({
+            "instance": "prod",
+            "model": "res.partner",
+            "domain": [["is_company", "=", true]],
+            "limit": 2,
+            "offset": 4,
+            "order": "name asc"
+        });
+        assert_eq!(next_cursor(&op, &args, 1), None, "short page is the last");
+        assert_eq!(next_cursor(&op, &json!({"model": "res.partner"}), 80), None);
+
+        let cursor = next_cursor(&op, &args, 2).unwrap();
+        let mut next = json!({ "cursor": cursor });
+        expand(&op, &mut next).unwrap();
+        assert_eq!(
+            next,
+            json!({
+                "instance": "prod",
+                "model": "res.partner",
+                "domain": [["is_company", "=", true]],
+                "limit": 2,
+                "offset": 6,
+                "order": "name asc"
+            })
+        );
+    }
+
+    #[test]
+    fn cursors_are_checked_before_use() {
+        let op = op("search_read");
+        let cursor = next_cursor(&op, &json!({"model": "res.partner", "limit": 1}), 1).unwrap();
+
+        let mut mixed = json!({"cursor": cursor, "limit": 5});
+        assert!(
+            expand(&op, &mut mixed)
+                .unwrap_err()
+                .contains("only argument (also got limit)")
+        );
+        let mut other_tool = json!({ "cursor": cursor });
+        assert!(expand(&self::op("search"), &mut other_tool).is_err());
+        let mut garbage = json!({"cursor": "not-a-cursor"});
+        assert!(expand(&op, &mut garbage).is_err());
+
+        let mut untouched = json!({"cursor": "x"});
+        expand(&self::op("read"), &mut untouched).unwrap();
+        assert_eq!(untouched, json!({"cursor": "x"}));
+    }
+}
@@ -0,0 +1,282 @@
+//! Deprecation warnings: machine-readable notices for clients still using something the
+//! server plans to drop.
+//!
+//! A response to a request that used a deprecated feature carries the matching warnings under
+//! `_meta.deprecations` of its result (or `data.deprecations` of its error), each with a stable
+//! `code`, a human `message` and the `replacement` to migrate to. Every use is also counted so
+//! operators can see what is still in use before removing it: `GET /deprecations` (admin scope)
+//! returns the counts with the first and last time each was seen.
+//!
+//! To deprecate something, add a [`Deprecation`] below and report it where the feature is
+//! handled: [`for_request`] for request shapes, the transports for transport-level features.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+use tracing::warn;
+
+use crate::mcp::resources::ResourceUri;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Deprecation {
+    pub code: &'static str,
+    pub message: &'static str,
+    pub replacement: &'static str,
+}
+
+impl Deprecation {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "code": self.code,
+            "message": self.message,
+            "replacement": self.replacement,
+        })
+    }
+}
+
+pub const LEGACY_SSE_TRANSPORT: Deprecation = Deprecation {
+    code: "legacy-sse-transport",
+    message: "The HTTP+SSE transport (GET /sse, POST /messages) is deprecated",
+    replacement: "Streamable HTTP: POST and GET /mcp",
+};
+
+pub const LEGACY_PROTOCOL_VERSION: Deprecation = Deprecation {
+    code: "legacy-protocol-version",
+    message: "MCP protocol version 2024-11-05 is deprecated",
+    replacement: "protocolVersion 2025-03-26 or later",
+};
+
+pub const LEGACY_METADATA_URI: Deprecation = Deprecation {
+    code: "legacy-metadata-uri",
+    message: "Resource URIs of the form odoo://{instance}/metadata/{model} are deprecated",
+    replacement: "odoo://{instance}/models/{model}/metadata",
+};
+
+/// Protocol versions a client may still negotiate but should move off.
+const DEPRECATED_PROTOCOL_VERSIONS: &[&str] = &["2024-11-05"];
+
+/// The deprecation a negotiated protocol version falls under, if any.
+pub fn for_protocol_version(version: &str) -> Option<&'static Deprecation> {
+    DEPRECATED_PROTOCOL_VERSIONS
+        .contains(&version)
+        .then_some(&LEGACY_PROTOCOL_VERSION)
+}
+
+/// Deprecated request shapes used by a `method` call with `params`.
+pub fn for_request(method: &str, params: Option<&Value>) -> Vec<&'static Deprecation> {
+    let mut found = Vec::new();
+    if method == "resources/read"
+        && let Some(uri) = params.and_then(|p| p.get("uri")).and_then(Value::as_str)
+        && matches!(ResourceUri::parse(uri), Ok(ResourceUri::Metadata { .. }))
+    {
+        found.push(&LEGACY_METADATA_URI);
+    }
+    found
+}
+
+fn append(target: &mut Map<String, Value>, key: &str, warnings: &[&Deprecation]) {
+    let Value::Object(entry) = target
+        .entry(key)
+        .or_insert_with(|| Value::Object(Map::new()))
+    else {
+        return;
+    };
+    let Value::Array(list) = entry
+        .entry("deprecations")
+        .or_insert_with(|| Value::Array(Vec::new()))
+    else {
+        return;
+    };
+    for warning in warnings {
+        let warning = warning.to_json();
+        if !list.contains(&warning) {
+            list.push(warning);
+        }
+    }
+}
+
+/// Add `warnings` to a method result under `_meta.deprecations`.
+pub fn attach(result: &mut Value, warnings: &[&Deprecation]) {
+    if warnings.is_empty() {
+        return;
+    }
+    if let Value::Object(result) = result {
+        append(result, "_meta", warnings);
+    }
+}
+
+/// Add `warnings` to a JSON-RPC response, or to each response of a batch: under
+/// `result._meta.deprecations`, or `error.data.deprecations` for errors.
+pub fn attach_to_response(response: &mut Value, warnings: &[&Deprecation]) {
+    if warnings.is_empty() {
+        return;
+    }
+    match response {
+        Value::Array(responses) => {
+            for response in responses {
+                attach_to_response(response, warnings);
+            }
+        }
+        Value::Object(response) => {
+            if let Some(result) = response.get_mut("result") {
+                attach(result, warnings);
+            } else if let Some(Value::Object(error)) = response.get_mut("error") {
+                if error.get("data").is_some_and(Value::is_null) {
+                    error.remove("data");
+                }
+                append(error, "data", warnings);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[derive(Debug)]
+struct Usage {
+    deprecation: Deprecation,
+    count: u64,
+    first_seen: DateTime<Utc>,
+    last_seen: DateTime<Utc>,
+    last_session: Option<String>,
+}
+
+/// One deprecation as reported by `GET /deprecations`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeprecationUsage {
+    #[serde(flatten)]
+    pub deprecation: Deprecation,
+    pub count: u64,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    /// Session that used it last; absent for sessionless requests.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_session: Option<String>,
+}
+
+/// Use counts of each deprecation since the server started.
+#[derive(Clone, Default)]
+pub struct DeprecationTracker {
+    usage: Arc<Mutex<BTreeMap<&'static str, Usage>>>,
+}
+
+impl DeprecationTracker {
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<&'static str, Usage>> {
+        self.usage.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Count one use of `deprecation` from `scope` (empty for sessionless requests). The first
+    /// use is logged.
+    pub fn record(&self, deprecation: &Deprecation, scope: &str) {
+        let now = Utc::now();
+        let session = (!scope.is_empty()).then(|| scope.to_string());
+        let mut usage = self.lock();
+        match usage.get_mut(deprecation.code) {
+            Some(usage) => {
+                usage.count += 1;
+                usage.last_seen = now;
+                usage.last_session = session;
+            }
+            None => {
+                warn!(
+                    code = deprecation.code,
+                    replacement = deprecation.replacement,
+                    "A client used a deprecated feature: {}",
+                    deprecation.message
+                );
+                usage.insert(
+                    deprecation.code,
+                    Usage {
+                        deprecation: *deprecation,
+                        count: 1,
+                        first_seen: now,
+                        last_seen: now,
+                        last_session: session,
+                    },
+                );
+            }
+        }
+    }
+
+    pub fn record_all(&self, deprecations: &[&Deprecation], scope: &str) {
+        for deprecation in deprecations {
+            self.record(deprecation, scope);
+        }
+    }
+
+    /// Deprecations used so far, by code.
+    pub fn summary(&self) -> Vec<DeprecationUsage> {
+        self.lock()
+            .values()
+            .map(|usage| DeprecationUsage {
+                deprecation: usage.deprecation,
+                count: usage.count,
+                first_seen: usage.first_seen,
+                last_seen: usage.last_seen,
+                last_session: usage.last_session.clone(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_deprecated_request_shapes() {
+        let legacy = json!({"uri": "odoo://prod/metadata/sale.order"});
+        assert_eq!(
+            for_request("resources/read", Some(&legacy)),
+            [&LEGACY_METADATA_URI]
+        );
+        let current = json!({"uri": "odoo://prod/models/sale.order/metadata"});
+        assert!(for_request("resources/read", Some(&current)).is_empty());
+        assert!(for_request("tools/list", None).is_empty());
+        assert_eq!(
+            for_protocol_version("2024-11-05"),
+            Some(&LEGACY_PROTOCOL_VERSION)
+        );
+        assert_eq!(for_protocol_version("2025-11-05"), None);
+    }
+
+    #[test]
+    fn attaches_warnings_to_results_and_errors() {
+        let mut responses = json!([
+            {"jsonrpc": "2.0", "id": 1, "result": {"tools": [], "_meta": {"trace": "x"}}},
+            {"jsonrpc": "2.0", "id": 2, "error": {"code": -32601, "message": "nope", "data": null}},
+        ]);
+        attach_to_response(&mut responses, &[&LEGACY_SSE_TRANSPORT]);
+        attach_to_response(&mut responses, &[&LEGACY_SSE_TRANSPORT]);
+
+        let meta = &responses[0]["result"]["_meta"];
+        assert_eq!(meta["trace"], "x");
+        assert_eq!(
+            meta["deprecations"],
+            json!([LEGACY_SSE_TRANSPORT.to_json()])
+        );
+        assert_eq!(
+            responses[1]["error"]["data"]["deprecations"][0]["code"],
+            "legacy-sse-transport"
+        );
+    }
+
+    #[test]
+    fn summarizes_use_counts() {
+        let tracker = DeprecationTracker::default();
+        tracker.record(&LEGACY_SSE_TRANSPORT, "s1");
+        tracker.record_all(&[&LEGACY_SSE_TRANSPORT, &LEGACY_METADATA_URI], "");
+
+        let summary = tracker.summary();
+        assert_eq!(summary.len(), 2);
+        assert_eq!(summary[0].deprecation.code, "legacy-metadata-uri");
+        assert_eq!(summary[1].count, 2);
+        assert_eq!(summary[1].last_session, None);
+        let json = serde_json::to_value(&summary[1]).unwrap();
+        assert_eq!(json["code"], "legacy-sse-transport");
+        assert!(json["firstSeen"].is_string());
+    }
+}
@@ -9,32 +9,52 @@
 //! - Session management with resumability support
 //! - Protocol version header handling
 
-use std::collections::{HashMap, VecDeque};
+use std::collections::HashMap;
 use std::convert::Infallible;
 use std::net::SocketAddr;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use axum::extract::{Query, State};
+use axum::extract::{DefaultBodyLimit, Query, State};
 use axum::http::{HeaderMap, HeaderName, HeaderValue, StatusCode};
+use axum::middleware;
 use axum::response::IntoResponse;
 use axum::response::sse::{Event, Sse};
 use axum::routing::{get, post};
 use axum::{Json, Router};
 use mcp_rust_sdk::error::{Error as McpError, ErrorCode};
 use mcp_rust_sdk::protocol::{RequestId, Response};
-use mcp_rust_sdk::server::ServerHandler;
 use serde::Deserialize;
 use serde_json::{Value, json};
-use tokio::sync::{Mutex, RwLock, broadcast};
-use tokio_stream::wrappers::{BroadcastStream, IntervalStream};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{RwLock, mpsc};
+use tokio_stream::wrappers::{IntervalStream, UnboundedReceiverStream};
 use tokio_stream::{StreamExt, iter};
 use tower_http::cors::CorsLayer;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
 use crate::mcp::McpOdooHandler;
+use crate::mcp::audit::AuditQuery;
+use crate::mcp::auth_tokens::{
+    ApiToken, LEGACY_TOKEN_NAME, TokenGrant, TokenScope, load_tokens_from_env,
+};
+use crate::mcp::deprecations::{self, LEGACY_SSE_TRANSPORT};
+use crate::mcp::progress;
+use crate::mcp::rate_limit::{RateLimitConfig, RateLimiter, rate_limit_layer};
+use crate::mcp::request_signing::{DEFAULT_BODY_LIMIT, RequestVerifier, signature_layer};
+use crate::mcp::sampling::is_response;
+use crate::mcp::session_idle::{SessionActivity, idle_ttl_from_env, sweep_interval};
+use crate::mcp::session_store::{
+    MemorySessionStore, SessionRecord, SessionStore, StoredEvent, session_store_from_env,
+};
+use crate::mcp::sse_channels::{SseChannels, SseItem, disconnect_grace_from_env, resync_message};
+use crate::mcp::tenants::{Tenant, load_tenants_from_env};
+use crate::mcp::tls::{TlsSettings, serve_tls};
+use crate::odoo::deadline;
+use crate::odoo::health::{self, HealthDepth, HealthEndpoint};
+use crate::odoo::types::OdooError;
+use crate::telemetry;
 
 // Header names per MCP spec
 static MCP_SESSION_ID: HeaderName = HeaderName::from_static("mcp-session-id");
@@ -43,100 +63,34 @@ static AUTHORIZATION: HeaderName = HeaderName::from_static("authorization");
 static ORIGIN: HeaderName = HeaderName::from_static("origin");
 static LAST_EVENT_ID: HeaderName = HeaderName::from_static("last-event-id");
 
-/// Default protocol version for backwards compatibility
-const DEFAULT_PROTOCOL_VERSION: &str = "2025-03-26";
-
 /// Current supported protocol version
 const CURRENT_PROTOCOL_VERSION: &str = "2025-11-05";
 
-/// Maximum number of events to buffer for resumability per session
-const MAX_EVENT_BUFFER_SIZE: usize = 100;
-
 /// SSE keepalive interval in seconds
 const SSE_KEEPALIVE_SECS: u64 = 15;
 
 /// Default retry interval for SSE reconnection (milliseconds)
 const SSE_RETRY_MS: u64 = 3000;
 
-/// Stored SSE event for resumability
-#[derive(Clone, Debug)]
-struct StoredEvent {
-    id: String,
-    data: Value,
-}
-
-/// Session state with enhanced tracking for Streamable HTTP
-#[derive(Clone)]
-struct SessionState {
-    initialized: bool,
-    protocol_version: String,
-    event_counter: Arc<AtomicU64>,
-    /// Circular buffer of recent events for resumability (placeholder for full implementation)
-    #[allow(dead_code)]
-    event_buffer: Arc<RwLock<VecDeque<StoredEvent>>>,
-}
-
-impl Default for SessionState {
-    fn default() -> Self {
-        Self {
-            initialized: false,
-            protocol_version: DEFAULT_PROTOCOL_VERSION.to_string(),
-            event_counter: Arc::new(AtomicU64::new(0)),
-            event_buffer: Arc::new(RwLock::new(VecDeque::with_capacity(MAX_EVENT_BUFFER_SIZE))),
-        }
-    }
-}
-
-impl SessionState {
-    fn new(protocol_version: String) -> Self {
-        Self {
-            initialized: true,
-            protocol_version,
-            event_counter: Arc::new(AtomicU64::new(0)),
-            event_buffer: Arc::new(RwLock::new(VecDeque::with_capacity(MAX_EVENT_BUFFER_SIZE))),
-        }
-    }
-
-    /// Generate next event ID for this session
-    fn next_event_id(&self, session_id: &str) -> String {
-        let counter = self.event_counter.fetch_add(1, Ordering::SeqCst);
-        format!("{}:{}", session_id, counter)
-    }
-
-    /// Store an event for potential replay (placeholder for full resumability implementation)
-    #[allow(dead_code)]
-    async fn store_event(&self, event: StoredEvent) {
-        let mut buffer = self.event_buffer.write().await;
-        if buffer.len() >= MAX_EVENT_BUFFER_SIZE {
-            buffer.pop_front();
-        }
-        buffer.push_back(event);
-    }
-
-    /// Get events after a given event ID for replay (placeholder for full resumability implementation)
-    #[allow(dead_code)]
-    async fn get_events_after(&self, last_event_id: &str) -> Vec<StoredEvent> {
-        let buffer = self.event_buffer.read().await;
-        let mut found = false;
-        let mut result = Vec::new();
-
-        for event in buffer.iter() {
-            if found {
-                result.push(event.clone());
-            } else if event.id == last_event_id {
-                found = true;
-            }
-        }
-
-        result
-    }
-}
-
-/// Security configuration for Origin validation
+/// Security configuration for Origin validation and request limits
 #[derive(Clone, Debug, Default)]
 pub struct SecurityConfig {
     /// Allowed origins. None = allow all (default), Some([]) = localhost only
     pub allowed_origins: Option<Vec<String>>,
+    /// Per-token / per-IP request limits for the MCP endpoints
+    pub rate_limit: RateLimitConfig,
+    /// Maximum request body size in bytes (MCP_MAX_BODY_BYTES). None = axum default (2 MiB)
+    pub max_body_bytes: Option<usize>,
+    /// Deadline for a single JSON-RPC request (MCP_REQUEST_TIMEOUT_SECS). None = no deadline
+    pub request_timeout: Option<Duration>,
+    /// Expire sessions idle this long (MCP_SESSION_IDLE_TTL_SECS). None = never
+    pub session_idle_ttl: Option<Duration>,
+    /// End sessions whose SSE streams have been disconnected this long
+    /// (MCP_SSE_DISCONNECT_GRACE_SECS). None = never
+    pub sse_disconnect_grace: Option<Duration>,
+    /// Require signed requests on the transport endpoints (MCP_REQUEST_SIGNING_SECRET).
+    /// Loaded by `serve_with_config`, like TLS, since a bad secret must stop startup.
+    pub request_signing: Option<RequestVerifier>,
 }
 
 impl SecurityConfig {
@@ -160,7 +114,32 @@ impl SecurityConfig {
             debug!("MCP Origin validation disabled (set MCP_ALLOWED_ORIGINS to enable)");
         }
 
-        Self { allowed_origins }
+        let max_body_bytes = std::env::var("MCP_MAX_BODY_BYTES")
+            .ok()
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .filter(|v| *v > 0);
+        let request_timeout = std::env::var("MCP_REQUEST_TIMEOUT_SECS")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|v| *v > 0)
+            .map(Duration::from_secs);
+        if let Some(bytes) = max_body_bytes {
+            info!("MCP max request body: {} bytes", bytes);
+        }
+        if let Some(timeout) = request_timeout {
+            info!("MCP request timeout: {:?}", timeout);
+        }
+        let session_idle_ttl = idle_ttl_from_env();
+
+        Self {
+            allowed_origins,
+            rate_limit: RateLimitConfig::from_env(),
+            max_body_bytes,
+            request_timeout,
+            session_idle_ttl,
+            sse_disconnect_grace: disconnect_grace_from_env(),
+            request_signing: None,
+        }
     }
 }
 
@@ -171,6 +150,10 @@ pub struct AuthConfigData {
     pub bearer_token: Option<String>,
     /// Whether authentication is enabled (MCP_AUTH_ENABLED)
     pub enabled: bool,
+    /// Named, scoped tokens from tokens.json (MCP_AUTH_TOKENS_JSON)
+    pub tokens: Vec<ApiToken>,
+    /// Tenants from tenants.json (MCP_TENANTS_JSON)
+    pub tenants: Vec<Tenant>,
 }
 
 impl AuthConfigData {
@@ -185,9 +168,30 @@ impl AuthConfigData {
             .ok()
             .filter(|s| !s.is_empty());
 
+        let tokens = load_tokens_from_env().unwrap_or_else(|e| {
+            warn!("Failed to load MCP API tokens, ignoring tokens.json: {e}");
+            Vec::new()
+        });
+        let tenants = load_tenants_from_env().unwrap_or_else(|e| {
+            warn!("Failed to load MCP tenants, ignoring tenants.json: {e}");
+            Vec::new()
+        });
+        for token in &tokens {
+            if let Some(tenant) = &token.tenant
+                && !tenants.iter().any(|t| &t.name == tenant)
+            {
+                warn!(
+                    "Token '{}' names undefined tenant '{}'; it can reach no instances",
+                    token.name, tenant
+                );
+            }
+        }
+
         Self {
             bearer_token,
             enabled,
+            tokens,
+            tenants,
         }
     }
 
@@ -196,8 +200,14 @@ impl AuthConfigData {
         Self {
             bearer_token: None,
             enabled: false,
+            tokens: Vec::new(),
+            tenants: Vec::new(),
         }
     }
+
+    fn has_credentials(&self) -> bool {
+        self.bearer_token.is_some() || !self.tokens.is_empty()
+    }
 }
 
 /// Authentication configuration wrapper with hot-reload support
@@ -211,10 +221,15 @@ impl AuthConfig {
     pub fn from_env() -> Self {
         let data = AuthConfigData::from_env();
         if data.enabled {
-            if data.bearer_token.is_some() {
-                info!("MCP HTTP authentication enabled (Bearer token)");
+            if data.has_credentials() {
+                info!(
+                    "MCP HTTP authentication enabled (Bearer token, {} named token(s))",
+                    data.tokens.len()
+                );
             } else {
-                warn!("MCP HTTP authentication enabled but MCP_AUTH_TOKEN not set!");
+                warn!(
+                    "MCP HTTP authentication enabled but neither MCP_AUTH_TOKEN nor tokens.json is set!"
+                );
             }
         } else {
             debug!("MCP HTTP authentication disabled (set MCP_AUTH_ENABLED=true to enable)");
@@ -237,6 +252,32 @@ impl AuthConfig {
             inner: Arc::new(RwLock::new(AuthConfigData {
                 enabled,
                 bearer_token,
+                tokens: Vec::new(),
+                tenants: Vec::new(),
+            })),
+        }
+    }
+
+    /// Create auth config with named tokens only (for testing)
+    pub fn with_tokens(tokens: Vec<ApiToken>) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(AuthConfigData {
+                enabled: true,
+                bearer_token: None,
+                tokens,
+                tenants: Vec::new(),
+            })),
+        }
+    }
+
+    /// Create auth config with named tokens and tenants (for testing)
+    pub fn with_tenants(tokens: Vec<ApiToken>, tenants: Vec<Tenant>) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(AuthConfigData {
+                enabled: true,
+                bearer_token: None,
+                tokens,
+                tenants,
             })),
         }
     }
@@ -247,6 +288,8 @@ impl AuthConfig {
         let mut guard = self.inner.write().await;
         let old_enabled = guard.enabled;
         let old_token = guard.bearer_token.clone();
+        let old_tokens = guard.tokens.clone();
+        let old_tenants = guard.tenants.clone();
         *guard = new_data.clone();
 
         // Log changes
@@ -260,6 +303,18 @@ impl AuthConfig {
         if old_token != new_data.bearer_token {
             info!("MCP_AUTH_TOKEN updated (hot-reload)");
         }
+        if old_tokens != new_data.tokens {
+            info!(
+                "MCP API tokens updated (hot-reload, {} token(s))",
+                new_data.tokens.len()
+            );
+        }
+        if old_tenants != new_data.tenants {
+            info!(
+                "MCP tenants updated (hot-reload, {} tenant(s))",
+                new_data.tenants.len()
+            );
+        }
     }
 
     /// Get current config snapshot
@@ -271,8 +326,9 @@ impl AuthConfig {
 #[derive(Clone)]
 struct AppState {
     handler: Arc<McpOdooHandler>,
-    sessions: Arc<Mutex<HashMap<String, SessionState>>>,
-    sse_channels: Arc<Mutex<HashMap<String, broadcast::Sender<Value>>>>,
+    sessions: Arc<dyn SessionStore>,
+    sse_channels: SseChannels,
+    activity: SessionActivity,
     auth: AuthConfig,
     security: SecurityConfig,
 }
@@ -299,50 +355,110 @@ pub async fn serve_with_config(
     handler: Arc<McpOdooHandler>,
     listen: &str,
     auth: AuthConfig,
-    security: SecurityConfig,
+    mut security: SecurityConfig,
 ) -> anyhow::Result<()> {
-    let app = create_app_with_security(handler, auth, security);
+    if security.request_signing.is_none() {
+        security.request_signing = RequestVerifier::from_env()?;
+    }
+    let sessions = session_store_from_env().await?;
+    info!("HTTP session store: {}", sessions.backend());
+    // MCP_TLS_* env vars take precedence over the `tls` object in server.json
+    let tls = match TlsSettings::from_env()? {
+        Some(tls) => Some(tls),
+        None => handler.tls_settings().await,
+    };
+    let server_info = handler.server_info().clone();
+    let app = create_app_with_store(handler, auth, security, sessions);
     let addr: SocketAddr = listen.parse()?;
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    if let Some(tls) = tls {
+        server_info.note_tls(&tls);
+        return serve_tls(listener, app, &tls).await;
+    }
+    // Connection info feeds the per-IP rate limiter
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
     Ok(())
 }
 
 /// Health check handler: returns server status and per-instance Odoo reachability
 async fn health_check(State(state): State<AppState>) -> impl IntoResponse {
+    Json(instance_health(&state, HealthEndpoint::Health).await)
+}
+
+/// Readiness probe: `/health` at the readiness depth, answering 503 when no instance is
+/// reachable so orchestrators stop routing to this server.
+async fn readyz(State(state): State<AppState>) -> impl IntoResponse {
+    let report = instance_health(&state, HealthEndpoint::Readyz).await;
+    let status = if report["status"] == "unhealthy" {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+    (status, Json(report))
+}
+
+/// Probe every instance at the depth configured for `endpoint` (see `odoo::health`).
+async fn instance_health(state: &AppState, endpoint: HealthEndpoint) -> Value {
     let pool = &state.handler.pool;
     let instances = pool.instance_names();
+    let depths: HashMap<String, HealthDepth> = pool
+        .instance_configs()
+        .into_iter()
+        .map(|(name, cfg)| (name, health::depth_for(cfg.health_depth.as_ref(), endpoint)))
+        .collect();
 
     let mut instance_health = serde_json::Map::new();
     let mut any_reachable = false;
     let mut any_unreachable = false;
+    let mut any_throttled = false;
 
     for instance in instances {
-        match pool.get(&instance).await {
-            Ok(client) => match client.health_probe().await {
-                Ok(()) => {
-                    instance_health.insert(instance.clone(), json!({"reachable": true}));
-                    any_reachable = true;
-                }
-                Err(e) => {
-                    instance_health.insert(
-                        instance.clone(),
-                        json!({"reachable": false, "error": e.to_string()}),
-                    );
-                    any_unreachable = true;
-                }
-            },
+        let client = match pool.get(&instance).await {
+            Ok(client) => client,
             Err(e) => {
                 instance_health.insert(
                     instance.clone(),
                     json!({"reachable": false, "error": e.to_string()}),
                 );
                 any_unreachable = true;
+                continue;
             }
+        };
+        // An instance still cooling down after a 429 is not probed: it answered, and another
+        // request would only extend the throttling.
+        let depth = depths
+            .get(&instance)
+            .copied()
+            .unwrap_or_else(|| endpoint.default_depth());
+        let probe = if client.throttle().is_cooling_down(Instant::now()) {
+            Ok(None)
+        } else {
+            client.health_probe_at(depth).await
+        };
+        let throttle = client.throttle().status(Instant::now());
+        let mut entry = match probe {
+            Ok(Some(version)) => json!({"reachable": true, "serverVersion": version}),
+            Ok(None) | Err(OdooError::UpstreamThrottled { .. }) => json!({"reachable": true}),
+            Err(e) => json!({"reachable": false, "error": e.to_string()}),
+        };
+        entry["depth"] = json!(depth.as_str());
+        if entry["reachable"] == json!(true) {
+            any_reachable = true;
+        } else {
+            any_unreachable = true;
+        }
+        if throttle.throttled_total > 0 {
+            any_throttled |= throttle.throttled;
+            entry["upstream"] = json!(throttle);
         }
+        instance_health.insert(instance.clone(), entry);
     }
 
-    let status = if !any_unreachable {
+    let status = if !any_unreachable && !any_throttled {
         "ok"
     } else if any_reachable {
         "degraded"
@@ -350,13 +466,196 @@ async fn health_check(State(state): State<AppState>) -> impl IntoResponse {
         "unhealthy"
     };
 
-    let response = json!({
+    json!({
         "status": status,
         "version": env!("CARGO_PKG_VERSION"),
         "instances": instance_health
-    });
+    })
+}
 
-    Json(response)
+/// Query the tool-call audit log; needs the `admin` scope when auth is enabled.
+async fn audit_query(
+    State(state): State<AppState>,
+    Query(mut query): Query<AuditQuery>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(err) = validate_origin(&headers, &state.security) {
+        return err.into_response();
+    }
+    let grant = match validate_auth_async(&headers, &state.auth).await {
+        Ok(grant) => grant,
+        Err(err) => return err.into_response(),
+    };
+    if grant
+        .as_ref()
+        .is_some_and(|grant| !grant.allows(TokenScope::Admin))
+    {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "The audit log requires the 'admin' scope"})),
+        )
+            .into_response();
+    }
+    let Some(log) = state.handler.audit_log() else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Audit log is disabled (set MCP_AUDIT_LOG)"})),
+        )
+            .into_response();
+    };
+    // A tenant's admin only ever sees the tenant's own entries.
+    if let Some(tenant) = grant.as_ref().and_then(|grant| grant.tenant.as_ref()) {
+        query.tenant = Some(tenant.name.clone());
+    }
+    match log.query(&query).await {
+        Ok(entries) => Json(json!({ "entries": entries })).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+/// POST /audit/export - export the audit entries no batch has covered yet (admin scope).
+async fn audit_export(State(state): State<AppState>, headers: HeaderMap) -> impl IntoResponse {
+    if let Err(err) = validate_origin(&headers, &state.security) {
+        return err.into_response();
+    }
+    let grant = match validate_auth_async(&headers, &state.auth).await {
+        Ok(grant) => grant,
+        Err(err) => return err.into_response(),
+    };
+    // Batches span every tenant, so tenant admins cannot trigger them.
+    if grant
+        .as_ref()
+        .is_some_and(|grant| !grant.allows(TokenScope::Admin) || grant.tenant.is_some())
+    {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "Audit exports require the 'admin' scope"})),
+        )
+            .into_response();
+    }
+    let Some(exporter) = state.handler.audit_exporter() else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Audit export is disabled (set MCP_AUDIT_LOG and MCP_AUDIT_EXPORT_DIR)"})),
+        )
+            .into_response();
+    };
+    match exporter.export_now().await {
+        Ok(batch) => Json(json!(batch)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct InspectorQuery {
+    session: String,
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+/// GET /inspector?session=<id> - the session's recent requests and responses, newest first.
+async fn inspector_query(
+    State(state): State<AppState>,
+    Query(query): Query<InspectorQuery>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(err) = validate_origin(&headers, &state.security) {
+        return err.into_response();
+    }
+    let grant = match validate_auth_async(&headers, &state.auth).await {
+        Ok(grant) => grant,
+        Err(err) => return err.into_response(),
+    };
+    if grant
+        .as_ref()
+        .is_some_and(|grant| !grant.allows(TokenScope::Admin))
+    {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "The request inspector requires the 'admin' scope"})),
+        )
+            .into_response();
+    }
+    let inspector = state.handler.inspector();
+    if !inspector.is_enabled() {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Request inspector is disabled (MCP_INSPECTOR_REQUESTS=0)"})),
+        )
+            .into_response();
+    }
+    // A tenant's admin only ever sees the tenant's own sessions.
+    if let Some(tenant) = grant.as_ref().and_then(|grant| grant.tenant.as_ref())
+        && inspector.tenant(&query.session).as_deref() != Some(tenant.name.as_str())
+    {
+        return Json(json!({ "session": query.session, "requests": [] })).into_response();
+    }
+    let requests = inspector.recent(&query.session, query.limit.unwrap_or(usize::MAX));
+    Json(json!({ "session": query.session, "requests": requests })).into_response()
+}
+
+/// GET /deprecations - deprecated features clients have used since startup, with use counts.
+async fn deprecations_summary(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(err) = validate_origin(&headers, &state.security) {
+        return err.into_response();
+    }
+    let grant = match validate_auth_async(&headers, &state.auth).await {
+        Ok(grant) => grant,
+        Err(err) => return err.into_response(),
+    };
+    if grant
+        .as_ref()
+        .is_some_and(|grant| !grant.allows(TokenScope::Admin))
+    {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "The deprecation summary requires the 'admin' scope"})),
+        )
+            .into_response();
+    }
+    Json(json!({ "deprecations": state.handler.deprecations().summary() })).into_response()
+}
+
+/// Effective configuration (admin scope), the same report as `odoo_server_info`.
+async fn server_info_report(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(err) = validate_origin(&headers, &state.security) {
+        return err.into_response();
+    }
+    let grant = match validate_auth_async(&headers, &state.auth).await {
+        Ok(grant) => grant,
+        Err(err) => return err.into_response(),
+    };
+    if grant
+        .as_ref()
+        .is_some_and(|grant| !grant.allows(TokenScope::Admin))
+    {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "The server info report requires the 'admin' scope"})),
+        )
+            .into_response();
+    }
+    let handler = &state.handler;
+    // A tenant's admin only sees the tenant's instances.
+    let pool = match grant.as_ref().and_then(|grant| grant.tenant.as_ref()) {
+        Some(tenant) => handler.pool.for_tenant(tenant),
+        None => handler.pool.clone(),
+    };
+    Json(handler.server_info().report(&pool).await).into_response()
 }
 
 /// OpenAPI specification handler
@@ -373,28 +672,81 @@ pub fn create_app(handler: Arc<McpOdooHandler>, auth: AuthConfig) -> Router {
     create_app_with_security(handler, auth, SecurityConfig::default())
 }
 
-/// Create the Axum Router with full configuration
+/// Create the Axum Router with full configuration and an in-memory session store
 pub fn create_app_with_security(
     handler: Arc<McpOdooHandler>,
     auth: AuthConfig,
     security: SecurityConfig,
 ) -> Router {
+    create_app_with_store(handler, auth, security, Arc::new(MemorySessionStore::new()))
+}
+
+/// Create the Axum Router backed by the given session store
+pub fn create_app_with_store(
+    handler: Arc<McpOdooHandler>,
+    auth: AuthConfig,
+    security: SecurityConfig,
+    sessions: Arc<dyn SessionStore>,
+) -> Router {
+    let limiter = security
+        .rate_limit
+        .is_enabled()
+        .then(|| Arc::new(RateLimiter::new(security.rate_limit.clone())));
+    handler
+        .server_info()
+        .note_http(auth.clone(), &security, sessions.backend());
     let state = AppState {
         handler,
-        sessions: Arc::new(Mutex::new(HashMap::new())),
-        sse_channels: Arc::new(Mutex::new(HashMap::new())),
-        auth,
+        sessions,
+        sse_channels: SseChannels::from_env(),
+        activity: SessionActivity::new(),
+        auth: auth.clone(),
         security,
     };
+    if let Some(ttl) = state.security.session_idle_ttl {
+        spawn_idle_sweeper(state.clone(), ttl);
+    }
+    if let Some(grace) = state.security.sse_disconnect_grace {
+        spawn_disconnect_sweeper(state.clone(), grace);
+    }
+    spawn_termination_listener(state.clone());
 
-    Router::new()
+    let max_body_bytes = state.security.max_body_bytes;
+    let mut transport_routes = Router::new()
         // Streamable HTTP (MCP 2025-11-25 spec)
         .route("/mcp", post(mcp_post).get(mcp_get).delete(mcp_delete))
         // Legacy SSE transport (Cursor supports `SSE` transport option)
         .route("/sse", get(legacy_sse))
-        .route("/messages", post(legacy_messages))
+        .route("/messages", post(legacy_messages));
+    if let Some(verifier) = state.security.request_signing.clone() {
+        let limit = max_body_bytes.unwrap_or(DEFAULT_BODY_LIMIT);
+        transport_routes = transport_routes.route_layer(middleware::from_fn_with_state(
+            (verifier, limit),
+            signature_layer,
+        ));
+    }
+    let mut mcp_routes = transport_routes
+        .route("/audit", get(audit_query))
+        .route("/audit/export", post(audit_export))
+        .route("/inspector", get(inspector_query))
+        .route("/deprecations", get(deprecations_summary))
+        .route("/server-info", get(server_info_report));
+    if let Some(bytes) = max_body_bytes {
+        mcp_routes = mcp_routes.layer(DefaultBodyLimit::max(bytes));
+    }
+    if let Some(limiter) = limiter {
+        let limit = max_body_bytes.unwrap_or(DEFAULT_BODY_LIMIT);
+        mcp_routes = mcp_routes.route_layer(middleware::from_fn_with_state(
+            (limiter, auth, limit),
+            rate_limit_layer,
+        ));
+    }
+
+    Router::new()
+        .merge(mcp_routes)
         // Health check endpoint (no auth required for monitoring)
         .route("/health", get(health_check))
+        .route("/readyz", get(readyz))
         // OpenAPI specification (no auth required)
         .route("/openapi.json", get(openapi_spec))
         .layer(CorsLayer::permissive())
@@ -441,7 +793,8 @@ fn cursor_initialize_result(
         "capabilities": {
             "tools": { "listChanged": true },
             "prompts": { "listChanged": true },
-            "resources": {},
+            "resources": { "subscribe": true },
+            "completions": {},
             "experimental": {
                 "odooInstances": { "available": odoo_instances }
             }
@@ -508,12 +861,14 @@ fn validate_origin(
     }
 }
 
-/// Validate Bearer token authentication
 /// Validate Bearer token authentication (async version for hot-reload support)
+///
+/// Returns the token the request authenticated with, or `None` when authentication is
+/// disabled (the request is then unrestricted).
 async fn validate_auth_async(
     headers: &HeaderMap,
     auth: &AuthConfig,
-) -> Result<(), (StatusCode, Json<Value>)> {
+) -> Result<Option<TokenGrant>, (StatusCode, Json<Value>)> {
     let auth_data = auth.get().await;
     validate_auth_data(headers, &auth_data)
 }
@@ -522,13 +877,13 @@ async fn validate_auth_async(
 fn validate_auth_data(
     headers: &HeaderMap,
     auth: &AuthConfigData,
-) -> Result<(), (StatusCode, Json<Value>)> {
+) -> Result<Option<TokenGrant>, (StatusCode, Json<Value>)> {
     // Check if auth is enabled
     if !auth.enabled {
-        return Ok(());
+        return Ok(None);
     }
 
-    let Some(expected_token) = &auth.bearer_token else {
+    if !auth.has_credentials() {
         // Auth enabled but no token configured - deny all
         return Err((
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -537,23 +892,32 @@ fn validate_auth_data(
                 "error_description": "Authentication enabled but no token configured"
             })),
         ));
-    };
+    }
 
     let auth_header = headers.get(&AUTHORIZATION).and_then(|v| v.to_str().ok());
 
     match auth_header {
         Some(header) if header.starts_with("Bearer ") => {
             let token = &header[7..];
-            if token == expected_token {
-                Ok(())
-            } else {
-                Err((
+            if auth.bearer_token.as_deref() == Some(token) {
+                return Ok(Some(TokenGrant::admin(LEGACY_TOKEN_NAME)));
+            }
+            match auth.tokens.iter().find(|t| t.token == token) {
+                Some(api_token) if api_token.is_expired(chrono::Utc::now()) => Err((
+                    StatusCode::UNAUTHORIZED,
+                    Json(json!({
+                        "error": "invalid_token",
+                        "error_description": "The access token has expired"
+                    })),
+                )),
+                Some(api_token) => Ok(Some(TokenGrant::for_token(api_token, &auth.tenants))),
+                None => Err((
                     StatusCode::UNAUTHORIZED,
                     Json(json!({
                         "error": "invalid_token",
                         "error_description": "The access token is invalid"
                     })),
-                ))
+                )),
             }
         }
         Some(_) => Err((
@@ -576,7 +940,7 @@ fn validate_auth_data(
 /// Validate MCP-Protocol-Version header
 fn validate_protocol_version(
     headers: &HeaderMap,
-    session: Option<&SessionState>,
+    session: Option<&SessionRecord>,
 ) -> Result<(), (StatusCode, Json<Value>)> {
     let version = headers
         .get(&MCP_PROTOCOL_VERSION)
@@ -615,14 +979,26 @@ fn validate_protocol_version(
     }
 }
 
+/// Map a session store failure to an HTTP 500 JSON-RPC error
+fn session_store_err(err: anyhow::Error) -> (StatusCode, Json<Value>) {
+    warn!("Session store error: {}", err);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(jsonrpc_err_no_id(
+            ErrorCode::InternalError,
+            "Session store unavailable",
+        )),
+    )
+}
+
 /// Validate session exists and is not expired
-fn validate_session(
+async fn validate_session(
     session_id: Option<&str>,
-    sessions: &HashMap<String, SessionState>,
-) -> Result<Option<SessionState>, (StatusCode, Json<Value>)> {
+    sessions: &dyn SessionStore,
+) -> Result<Option<SessionRecord>, (StatusCode, Json<Value>)> {
     match session_id {
-        Some(id) => match sessions.get(id) {
-            Some(state) => Ok(Some(state.clone())),
+        Some(id) => match sessions.get(id).await.map_err(session_store_err)? {
+            Some(record) => Ok(Some(record)),
             None => {
                 // Session not found - per spec, return 404
                 Err((
@@ -641,11 +1017,21 @@ fn validate_session(
 async fn handle_jsonrpc(
     state: &AppState,
     session_id: Option<String>,
+    grant: Option<&TokenGrant>,
     v: Value,
 ) -> Result<(Option<String>, Option<Value>, StatusCode, Option<String>), (StatusCode, Value)> {
     let obj = v
         .as_object()
         .ok_or((StatusCode::BAD_REQUEST, json!({"error":"expected object"})))?;
+
+    // Client answers to server-to-client requests (sampling) carry no method.
+    if is_response(&v) {
+        if !state.handler.client_requests().resolve(&v) {
+            debug!("Ignoring response for unknown server request");
+        }
+        return Ok((None, None, StatusCode::ACCEPTED, None));
+    }
+
     let method = obj
         .get("method")
         .and_then(|m| m.as_str())
@@ -670,7 +1056,7 @@ async fn handle_jsonrpc(
         let protocol_default = state.handler.protocol_version_default().await;
         let server_name = state.handler.server_name().await;
         let instructions = state.handler.instructions().await;
-        let (result, negotiated_version) = cursor_initialize_result(
+        let (mut result, negotiated_version) = cursor_initialize_result(
             &params,
             state.handler.instance_names(),
             protocol_default,
@@ -679,18 +1065,44 @@ async fn handle_jsonrpc(
         )
         .map_err(|e| (StatusCode::BAD_REQUEST, json!({"error": e.to_string()})))?;
 
-        let sess = Uuid::new_v4().to_string();
+        // `/sse` issues its session id up front and leaves the record waiting for `initialize`;
+        // otherwise initialize starts a new session.
+        let pending = match &effective_session {
+            Some(sess) => matches!(
+                state.sessions.get(sess).await,
+                Ok(Some(record)) if !record.initialized
+            ),
+            None => false,
+        };
+        let sess = match (&effective_session, pending) {
+            (Some(sess), true) => sess.clone(),
+            _ => Uuid::new_v4().to_string(),
+        };
+        if let Some(deprecation) = deprecations::for_protocol_version(&negotiated_version) {
+            state.handler.deprecations().record(deprecation, &sess);
+            deprecations::attach(&mut result, &[deprecation]);
+        }
         state
             .sessions
-            .lock()
+            .put(&sess, &SessionRecord::new(negotiated_version.clone()))
             .await
-            .insert(sess.clone(), SessionState::new(negotiated_version.clone()));
+            .map_err(|e| {
+                let (sc, Json(v)) = session_store_err(e);
+                (sc, v)
+            })?;
+        state.sse_channels.ensure(&sess);
+        state.activity.touch(&sess);
+        state.handler.sessions().open(
+            &sess,
+            &negotiated_version,
+            params.get("clientInfo").cloned(),
+            grant.map(|grant| grant.name.as_str()),
+        );
         state
-            .sse_channels
-            .lock()
-            .await
-            .entry(sess.clone())
-            .or_insert_with(|| broadcast::channel(256).0);
+            .handler
+            .client_requests()
+            .set_client_capabilities(&sess, params.get("capabilities"));
+        attach_client_requests(state, &sess);
 
         let resp = Response::success(id, Some(result));
         return Ok((
@@ -704,17 +1116,21 @@ async fn handle_jsonrpc(
     // initialized notification toggles gating for the session (if provided)
     if method == "initialized" {
         if let Some(sess) = &effective_session
-            && let Some(st) = state.sessions.lock().await.get_mut(sess)
+            && let Ok(Some(mut record)) = state.sessions.get(sess).await
+            && !record.initialized
         {
-            st.initialized = true;
+            record.initialized = true;
+            if let Err(e) = state.sessions.put(sess, &record).await {
+                warn!("Failed to persist session {}: {}", sess, e);
+            }
         }
         return Ok((None, None, StatusCode::ACCEPTED, None));
     }
 
     // For other methods, if we have a known session and it's not initialized, reject.
     if let Some(sess) = &effective_session
-        && let Some(st) = state.sessions.lock().await.get(sess)
-        && !st.initialized
+        && let Ok(Some(record)) = state.sessions.get(sess).await
+        && !record.initialized
     {
         // Cursor typically sends initialized quickly; if not, still allow read-only ops?
         // We'll follow MCP gating to match stdio behavior.
@@ -735,25 +1151,54 @@ async fn handle_jsonrpc(
         ));
     }
 
-    // Notifications: best-effort handle_method, return 202.
-    if id_val.is_none() {
-        let _ = state.handler.handle_method(&method, params).await;
+    // Cancellation is scoped to the client session.
+    let scope = effective_session.as_deref().unwrap_or("");
+
+    // Notifications: best-effort handle_request, return 202.
+    let Some(id_val) = id_val else {
+        let _ = state
+            .handler
+            .handle_request(scope, grant, None, &method, params)
+            .await;
         return Ok((None, None, StatusCode::ACCEPTED, None));
-    }
+    };
 
-    let id: RequestId = serde_json::from_value(id_val.unwrap())
+    let id: RequestId = serde_json::from_value(id_val.clone())
         .map_err(|e| (StatusCode::BAD_REQUEST, json!({"error": e.to_string()})))?;
 
-    let result = state
+    let call = state
         .handler
-        .handle_method(&method, params)
-        .await
-        .map_err(|e| {
-            (
-                StatusCode::OK,
-                jsonrpc_err(id.clone(), ErrorCode::InternalError, e.to_string()).to_value(),
-            )
-        })?;
+        .handle_request(scope, grant, Some(&id_val), &method, params);
+    // Dropping the call on timeout also drops its cancellation registration. The Odoo client
+    // sees the same limit as a deadline, so it stops retrying before this backstop fires.
+    let outcome = match state.security.request_timeout {
+        Some(limit) => {
+            match tokio::time::timeout(limit, deadline::scope(Some(Instant::now() + limit), call))
+                .await
+            {
+                Ok(outcome) => outcome,
+                Err(_) => {
+                    warn!("MCP request '{}' timed out after {:?}", method, limit);
+                    return Err((
+                        StatusCode::OK,
+                        jsonrpc_err(
+                            id,
+                            ErrorCode::RequestFailed,
+                            format!("Request timed out after {}s", limit.as_secs()),
+                        )
+                        .to_value(),
+                    ));
+                }
+            }
+        }
+        None => call.await,
+    };
+    let result = outcome.map_err(|e| {
+        (
+            StatusCode::OK,
+            jsonrpc_err(id.clone(), ErrorCode::InternalError, e.to_string()).to_value(),
+        )
+    })?;
     let resp = Response::success(id, Some(result));
     Ok((
         None,
@@ -763,6 +1208,65 @@ async fn handle_jsonrpc(
     ))
 }
 
+/// Handle a JSON-RPC batch (array body) by dispatching each element in order.
+///
+/// Returns the array of responses, or `None` when the batch held only notifications.
+/// `initialize` must be sent on its own and is rejected inside a batch.
+async fn handle_jsonrpc_batch(
+    state: &AppState,
+    session_id: Option<String>,
+    grant: Option<&TokenGrant>,
+    items: Vec<Value>,
+) -> Result<Option<Value>, (StatusCode, Value)> {
+    if items.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            jsonrpc_err_no_id(ErrorCode::InvalidRequest, "Empty batch"),
+        ));
+    }
+
+    let mut responses = Vec::new();
+    for item in items {
+        let item_id = item
+            .get("id")
+            .cloned()
+            .and_then(|x| serde_json::from_value::<RequestId>(x).ok());
+        let is_initialize = item
+            .get("method")
+            .and_then(|m| m.as_str())
+            .is_some_and(|m| m == "initialize");
+
+        let outcome = if is_initialize {
+            Err((
+                StatusCode::BAD_REQUEST,
+                json!({"error": "initialize cannot be part of a batch"}),
+            ))
+        } else {
+            handle_jsonrpc(state, session_id.clone(), grant, item).await
+        };
+
+        match outcome {
+            Ok((_, Some(resp), _, _)) => responses.push(resp),
+            Ok((_, None, _, _)) => {}
+            // Method errors already come back as complete JSON-RPC responses.
+            Err((_, v)) if v.get("jsonrpc").is_some() => responses.push(v),
+            Err((_, v)) => {
+                let message = v
+                    .get("error")
+                    .and_then(|e| e.as_str())
+                    .unwrap_or("Invalid request")
+                    .to_string();
+                responses.push(match item_id {
+                    Some(id) => jsonrpc_err(id, ErrorCode::InvalidRequest, message).to_value(),
+                    None => jsonrpc_err_no_id(ErrorCode::InvalidRequest, message),
+                });
+            }
+        }
+    }
+
+    Ok((!responses.is_empty()).then_some(Value::Array(responses)))
+}
+
 /// POST /mcp - Send JSON-RPC messages
 ///
 /// Per MCP spec:
@@ -781,10 +1285,12 @@ async fn mcp_post(
     }
 
     // Validate authentication (async for hot-reload support)
-    if let Err(err) = validate_auth_async(&headers, &state.auth).await {
-        return err.into_response();
-    }
+    let grant = match validate_auth_async(&headers, &state.auth).await {
+        Ok(grant) => grant,
+        Err(err) => return err.into_response(),
+    };
 
+    let request_id = telemetry::request_id_from_headers(&headers);
     let session_id = headers
         .get(&MCP_SESSION_ID)
         .and_then(|v| v.to_str().ok())
@@ -797,22 +1303,43 @@ async fn mcp_post(
         .is_some_and(|m| m == "initialize");
 
     if !is_initialize && let Some(ref sid) = session_id {
-        let sessions = state.sessions.lock().await;
-        if let Err(err) = validate_session(Some(sid), &sessions) {
+        let record = match validate_session(Some(sid), state.sessions.as_ref()).await {
+            Ok(record) => record,
+            Err(err) => return err.into_response(),
+        };
+        if let Err(err) = validate_protocol_version(&headers, record.as_ref()) {
             return err.into_response();
         }
-        let session_state = sessions.get(sid);
-        if let Err(err) = validate_protocol_version(&headers, session_state) {
-            return err.into_response();
+        if record.is_some() {
+            state.activity.touch(sid);
         }
     }
 
-    // Handle the JSON-RPC message
-    let (new_sess, maybe_resp, status, protocol_version) =
-        match handle_jsonrpc(&state, session_id.clone(), body).await {
-            Ok(v) => v,
-            Err((sc, v)) => return (sc, Json(v)).into_response(),
+    // JSON-RPC batch: one array of responses, or 202 if it only carried notifications
+    if let Value::Array(items) = body {
+        let call = handle_jsonrpc_batch(&state, session_id, grant.as_ref(), items);
+        return match telemetry::scope(request_id, call).await {
+            Ok(Some(v)) => (StatusCode::OK, Json(v)).into_response(),
+            Ok(None) => StatusCode::ACCEPTED.into_response(),
+            Err((sc, v)) => (sc, Json(v)).into_response(),
         };
+    }
+
+    // Tool calls asking for progress answer as an SSE stream when the client accepts one.
+    if accepts_event_stream(&headers) && wants_progress_stream(&body) {
+        return stream_jsonrpc(state, session_id, grant, request_id, body);
+    }
+
+    // Handle the JSON-RPC message
+    let (new_sess, maybe_resp, status, protocol_version) = match telemetry::scope(
+        request_id,
+        handle_jsonrpc(&state, session_id.clone(), grant.as_ref(), body),
+    )
+    .await
+    {
+        Ok(v) => v,
+        Err((sc, v)) => return (sc, Json(v)).into_response(),
+    };
 
     // Build response headers
     let mut out_headers = HeaderMap::new();
@@ -832,6 +1359,50 @@ async fn mcp_post(
     }
 }
 
+fn accepts_event_stream(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(axum::http::header::ACCEPT)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .any(|v| v.contains("text/event-stream"))
+}
+
+/// A `tools/call` request (not a notification) carrying `_meta.progressToken`.
+fn wants_progress_stream(body: &Value) -> bool {
+    body.get("id").is_some()
+        && body.get("method").and_then(Value::as_str) == Some("tools/call")
+        && body.get("params").and_then(progress::token).is_some()
+}
+
+/// Answer a request with an SSE stream: the `notifications/progress` it reports while running,
+/// then its JSON-RPC response, after which the stream ends.
+///
+/// The call runs in its own task, so a client that drops the stream does not cancel it; as on
+/// the JSON path, cancellation goes through `notifications/cancelled`.
+fn stream_jsonrpc(
+    state: AppState,
+    session_id: Option<String>,
+    grant: Option<TokenGrant>,
+    request_id: Option<String>,
+    body: Value,
+) -> axum::response::Response {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let sink = tx.clone();
+    tokio::spawn(async move {
+        let call = handle_jsonrpc(&state, session_id, grant.as_ref(), body);
+        let outcome = progress::with_sink(sink, telemetry::scope(request_id, call)).await;
+        if let Ok((_, Some(response), _, _)) | Err((_, response)) = outcome {
+            let _ = tx.send(response);
+        }
+    });
+    let events = UnboundedReceiverStream::new(rx).map(|message| {
+        Ok::<Event, Infallible>(Event::default().event("message").data(message.to_string()))
+    });
+    Sse::new(events)
+        .keep_alive(axum::response::sse::KeepAlive::default())
+        .into_response()
+}
+
 /// GET /mcp - Open SSE stream for server-to-client messages
 ///
 /// Per MCP spec:
@@ -855,47 +1426,49 @@ async fn mcp_get(State(state): State<AppState>, headers: HeaderMap) -> axum::res
         .and_then(|v| v.to_str().ok())
         .map(|s| s.to_string())
         .unwrap_or_else(|| "default".to_string());
+    state.activity.touch(&session_id);
 
     // Check for Last-Event-ID for resumability
-    let _last_event_id = headers
+    let last_event_id = headers
         .get(&LAST_EVENT_ID)
         .and_then(|v| v.to_str().ok())
         .map(|s| s.to_string());
 
-    // Get or create the broadcast channel
-    let (tx, session_state) = {
-        let mut chans = state.sse_channels.lock().await;
-        let tx = chans
-            .entry(session_id.clone())
-            .or_insert_with(|| broadcast::channel(256).0)
-            .clone();
-
-        let sessions = state.sessions.lock().await;
-        let session_state = sessions.get(&session_id).cloned();
-        (tx, session_state)
-    };
-
-    // Build the SSE stream
-    let session_for_events = session_id.clone();
+    // Subscribe before reading the replay buffer so nothing published in between is lost.
+    let live = state.sse_channels.subscribe_live(&session_id);
 
     // Initial event with retry field to prime reconnection
-    let initial_event_id = session_state
-        .as_ref()
-        .map(|s| s.next_event_id(&session_for_events))
-        .unwrap_or_else(|| format!("{}:0", session_for_events));
+    let initial_event_id = match state.sessions.next_event_id(&session_id).await {
+        Ok(id) => id,
+        Err(e) => {
+            warn!("Session store error: {}", e);
+            format!("{}:0", session_id)
+        }
+    };
 
     let initial_events = iter(vec![Ok::<Event, Infallible>(
         Event::default()
-            .id(initial_event_id)
+            .id(initial_event_id.clone())
             .retry(Duration::from_millis(SSE_RETRY_MS))
             .comment("connected"),
     )]);
 
-    // Replay events if Last-Event-ID was provided
-    // Note: For full resumability, we'd fetch events from session_state.get_events_after(_last_event_id).
-    // This is left as a placeholder - in production, you'd spawn a task to fetch and replay events.
-    let replay_events: Vec<StoredEvent> = vec![];
+    // Replay buffered events after Last-Event-ID, if provided
+    let replay_events: Vec<StoredEvent> = match last_event_id {
+        Some(last) => state
+            .sessions
+            .events_after(&session_id, &last)
+            .await
+            .unwrap_or_else(|e| {
+                warn!("Failed to load replay events for {}: {}", session_id, e);
+                Vec::new()
+            }),
+        None => Vec::new(),
+    };
 
+    let last_delivered = replay_events
+        .last()
+        .map_or(initial_event_id, |e| e.id.clone());
     let replay_stream = iter(replay_events.into_iter().map(|e: StoredEvent| {
         Ok::<Event, Infallible>(
             Event::default()
@@ -909,37 +1482,168 @@ async fn mcp_get(State(state): State<AppState>, headers: HeaderMap) -> axum::res
     let keepalive = IntervalStream::new(tokio::time::interval(Duration::from_secs(
         SSE_KEEPALIVE_SECS,
     )))
-    .map(|_| Ok::<Event, Infallible>(Event::default().comment("keepalive")));
-
-    // Message stream from broadcast channel
-    let session_for_stream = session_id.clone();
-    let session_state_for_stream = session_state.clone();
-
-    let stream = BroadcastStream::new(tx.subscribe()).filter_map(move |msg| {
-        match msg {
-            Ok(v) => {
-                let event_id = session_state_for_stream
-                    .as_ref()
-                    .map(|s| s.next_event_id(&session_for_stream))
-                    .unwrap_or_else(|| Uuid::new_v4().to_string());
-
-                Some(Ok(Event::default()
-                    .id(event_id)
-                    .event("message")
-                    .data(v.to_string())))
+    .map(|_| Event::default().comment("keepalive"));
+
+    // Message stream from broadcast channel; `None` marks the channel closing (session ended)
+    let stream = live
+        .into_stream(Some(last_delivered))
+        .map(|item| Some(sse_event(item, true)))
+        .chain(iter([None]));
+
+    // Combine all streams; the keepalive would otherwise hold an ended session's stream open
+    let live = keepalive
+        .map(Some)
+        .merge(stream)
+        .take_while(Option::is_some)
+        .filter_map(|event| event.map(Ok::<Event, Infallible>));
+    Sse::new(initial_events.chain(replay_stream).chain(live))
+        .keep_alive(axum::response::sse::KeepAlive::default())
+        .into_response()
+}
+
+/// SSE frame for a live stream item. Resync notices carry no id, so a reconnecting client still
+/// resumes after the last event it really received.
+fn sse_event(item: SseItem, with_id: bool) -> Event {
+    match item {
+        SseItem::Event(e) => {
+            let event = Event::default().event("message").data(e.data.to_string());
+            if with_id { event.id(e.id) } else { event }
+        }
+        SseItem::Resync {
+            last_event_id,
+            missed,
+        } => Event::default()
+            .event("message")
+            .data(resync_message(last_event_id.as_deref(), missed).to_string()),
+    }
+}
+
+/// Record a server-to-client message in the session's replay buffer and
+/// broadcast it to any open SSE streams for that session.
+async fn publish_event(state: &AppState, session_id: &str, data: Value) {
+    let id = match state.sessions.next_event_id(session_id).await {
+        Ok(id) => id,
+        Err(e) => {
+            warn!("Session store error: {}", e);
+            Uuid::new_v4().to_string()
+        }
+    };
+    let event = StoredEvent { id, data };
+    if let Err(e) = state.sessions.append_event(session_id, &event).await {
+        warn!("Failed to buffer event for {}: {}", session_id, e);
+    }
+    if let Some(tx) = state.sse_channels.sender(session_id) {
+        let _ = tx.send(event);
+    }
+}
+
+/// Deliver server-to-client requests for a session over its SSE stream.
+fn attach_client_requests(state: &AppState, session_id: &str) {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    state.handler.client_requests().attach(session_id, tx);
+    let state = state.clone();
+    let session_id = session_id.to_string();
+    tokio::spawn(async move {
+        while let Some(message) = rx.recv().await {
+            publish_event(&state, &session_id, message).await;
+        }
+    });
+}
+
+/// Periodically expire sessions that have not been used for `ttl`.
+fn spawn_idle_sweeper(state: AppState, ttl: Duration) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(sweep_interval(ttl));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            for session_id in state.activity.take_idle(ttl, Instant::now()) {
+                expire_session(&state, &session_id, ttl).await;
             }
-            Err(_) => None, // Channel lagged, skip
         }
     });
+}
 
-    // Combine all streams
-    Sse::new(
-        initial_events
-            .chain(replay_stream)
-            .chain(keepalive.merge(stream)),
-    )
-    .keep_alive(axum::response::sse::KeepAlive::default())
-    .into_response()
+/// End an idle session: tell open SSE streams why, then drop it like DELETE /mcp would.
+async fn expire_session(state: &AppState, session_id: &str, ttl: Duration) {
+    // Dropping the sender closes the session's streams after this last event.
+    if let Some(tx) = state.sse_channels.remove(session_id) {
+        let _ = tx.send(StoredEvent {
+            id: format!("{session_id}:expired"),
+            data: json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/session/expired",
+                "params": { "reason": "idle", "idleTimeoutSecs": ttl.as_secs() }
+            }),
+        });
+    }
+    if let Err(e) = state.sessions.remove(session_id).await {
+        warn!("Failed to remove idle session {}: {}", session_id, e);
+    }
+    state.handler.end_session(session_id);
+    info!("Session expired after idle timeout: {}", session_id);
+}
+
+/// End sessions an admin terminated through the config server's `DELETE /api/sessions/{id}`.
+fn spawn_termination_listener(state: AppState) {
+    let mut terminations = state.handler.sessions().subscribe_terminations();
+    tokio::spawn(async move {
+        loop {
+            let session_id = match terminations.recv().await {
+                Ok(session_id) => session_id,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            };
+            if let Some(tx) = state.sse_channels.remove(&session_id) {
+                let _ = tx.send(StoredEvent {
+                    id: format!("{session_id}:terminated"),
+                    data: json!({
+                        "jsonrpc": "2.0",
+                        "method": "notifications/session/expired",
+                        "params": { "reason": "terminated" }
+                    }),
+                });
+            }
+            if let Err(e) = state.sessions.remove(&session_id).await {
+                warn!("Failed to remove terminated session {}: {}", session_id, e);
+            }
+            state.activity.forget(&session_id);
+            state.handler.end_session(&session_id);
+            info!("Session terminated by an administrator: {}", session_id);
+        }
+    });
+}
+
+/// Periodically end sessions whose SSE streams disconnected more than `grace` ago and that
+/// have not sent a request since.
+fn spawn_disconnect_sweeper(state: AppState, grace: Duration) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(sweep_interval(grace));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let now = Instant::now();
+            let gone = state
+                .sse_channels
+                .take_disconnected(grace, now, |session_id| {
+                    state.activity.active_within(session_id, grace, now)
+                });
+            for session_id in gone {
+                if let Err(e) = state.sessions.remove(&session_id).await {
+                    warn!(
+                        "Failed to remove disconnected session {}: {}",
+                        session_id, e
+                    );
+                }
+                state.activity.forget(&session_id);
+                state.handler.end_session(&session_id);
+                info!(
+                    "Session ended after its SSE stream disconnected: {}",
+                    session_id
+                );
+            }
+        }
+    });
 }
 
 /// DELETE /mcp - Explicitly terminate a session
@@ -975,15 +1679,14 @@ async fn mcp_delete(State(state): State<AppState>, headers: HeaderMap) -> impl I
     };
 
     // Remove session and its SSE channel
-    let removed = {
-        let mut sessions = state.sessions.lock().await;
-        sessions.remove(&session_id).is_some()
+    let removed = match state.sessions.remove(&session_id).await {
+        Ok(removed) => removed,
+        Err(e) => return session_store_err(e).into_response(),
     };
 
-    {
-        let mut channels = state.sse_channels.lock().await;
-        channels.remove(&session_id);
-    }
+    state.sse_channels.remove(&session_id);
+    state.activity.forget(&session_id);
+    state.handler.end_session(&session_id);
 
     if removed {
         info!("Session terminated: {}", session_id);
@@ -1006,6 +1709,11 @@ struct LegacyQuery {
     session_id: Option<String>,
 }
 
+/// GET /sse - Legacy HTTP+SSE transport: open a session and its event stream
+///
+/// The first event names the `/messages` URL to POST to. The session is stored like a
+/// Streamable HTTP one but refuses requests until the client sends `initialize`, and it ends
+/// as soon as the stream disconnects.
 async fn legacy_sse(State(state): State<AppState>, headers: HeaderMap) -> axum::response::Response {
     // Validate Origin (security)
     if let Err(err) = validate_origin(&headers, &state.security) {
@@ -1018,12 +1726,18 @@ async fn legacy_sse(State(state): State<AppState>, headers: HeaderMap) -> axum::
     }
 
     let session_id = Uuid::new_v4().to_string();
-    let tx = {
-        let mut chans = state.sse_channels.lock().await;
-        chans
-            .entry(session_id.clone())
-            .or_insert_with(|| broadcast::channel(256).0)
-            .clone()
+    let record = SessionRecord {
+        initialized: false,
+        protocol_version: state.handler.protocol_version_default().await,
+    };
+    if let Err(e) = state.sessions.put(&session_id, &record).await {
+        return session_store_err(e).into_response();
+    }
+    state.activity.touch(&session_id);
+    let live = state.sse_channels.subscribe_live(&session_id);
+    let guard = LegacySessionGuard {
+        state: state.clone(),
+        session_id: session_id.clone(),
     };
 
     // First event tells the client where to POST messages (legacy spec).
@@ -1033,16 +1747,57 @@ async fn legacy_sse(State(state): State<AppState>, headers: HeaderMap) -> axum::
             .data(format!("/messages?sessionId={session_id}")),
     )]);
 
-    let stream = BroadcastStream::new(tx.subscribe()).filter_map(|msg| match msg {
-        Ok(v) => Some(Ok(Event::default().event("message").data(v.to_string()))),
-        Err(_) => None,
-    });
-
-    Sse::new(endpoint_event.chain(stream))
+    let keepalive = IntervalStream::new(tokio::time::interval(Duration::from_secs(
+        SSE_KEEPALIVE_SECS,
+    )))
+    .map(|_| Event::default().comment("keepalive"));
+
+    // `None` marks the channel closing (session ended elsewhere).
+    let stream = live
+        .into_stream(None)
+        .map(|item| Some(sse_event(item, false)))
+        .chain(iter([None]));
+
+    // The guard lives as long as the stream, which is dropped when the client disconnects.
+    let live = keepalive
+        .map(Some)
+        .merge(stream)
+        .take_while(Option::is_some)
+        .filter_map(move |event| {
+            let _ = &guard;
+            event.map(Ok::<Event, Infallible>)
+        });
+    Sse::new(endpoint_event.chain(live))
         .keep_alive(axum::response::sse::KeepAlive::default())
         .into_response()
 }
 
+/// Ends a legacy `/sse` session once its stream is dropped: the stream is the client's only
+/// way to receive responses, and the legacy transport has no resumption.
+struct LegacySessionGuard {
+    state: AppState,
+    session_id: String,
+}
+
+impl Drop for LegacySessionGuard {
+    fn drop(&mut self) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let state = self.state.clone();
+        let session_id = std::mem::take(&mut self.session_id);
+        runtime.spawn(async move {
+            state.sse_channels.remove(&session_id);
+            if let Err(e) = state.sessions.remove(&session_id).await {
+                warn!("Failed to remove legacy session {}: {}", session_id, e);
+            }
+            state.activity.forget(&session_id);
+            state.handler.end_session(&session_id);
+            info!("Legacy SSE session ended on disconnect: {}", session_id);
+        });
+    }
+}
+
 async fn legacy_messages(
     State(state): State<AppState>,
     Query(q): Query<LegacyQuery>,
@@ -1055,28 +1810,58 @@ async fn legacy_messages(
     }
 
     // Validate authentication (async for hot-reload support)
-    if let Err(err) = validate_auth_async(&headers, &state.auth).await {
-        return err.into_response();
-    }
+    let grant = match validate_auth_async(&headers, &state.auth).await {
+        Ok(grant) => grant,
+        Err(err) => return err.into_response(),
+    };
 
+    let request_id = telemetry::request_id_from_headers(&headers);
     let session = q.session_id.or_else(|| {
         headers
             .get(&MCP_SESSION_ID)
             .and_then(|v| v.to_str().ok())
             .map(|s| s.to_string())
     });
+    // Responses only reach the client through its /sse stream, so the session must be live.
+    let Some(sess) = session else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(jsonrpc_err_no_id(
+                ErrorCode::InvalidRequest,
+                "Missing sessionId query parameter",
+            )),
+        )
+            .into_response();
+    };
+    if let Err(err) = validate_session(Some(&sess), state.sessions.as_ref()).await {
+        return err.into_response();
+    }
+    state.activity.touch(&sess);
 
     // Legacy transport: responses are delivered on SSE stream, not in HTTP response.
-    let (_new_sess, maybe_resp, _status, _) =
-        match handle_jsonrpc(&state, session.clone(), body).await {
-            Ok(v) => v,
-            Err((_sc, _v)) => return StatusCode::BAD_REQUEST.into_response(),
-        };
+    let outcome = if let Value::Array(items) = body {
+        let call = handle_jsonrpc_batch(&state, Some(sess.clone()), grant.as_ref(), items);
+        telemetry::scope(request_id, call).await
+    } else {
+        let call = handle_jsonrpc(&state, Some(sess.clone()), grant.as_ref(), body);
+        telemetry::scope(request_id, call)
+            .await
+            .map(|(_, maybe_resp, _, _)| maybe_resp)
+    };
+    let maybe_resp = match outcome {
+        Ok(maybe_resp) => maybe_resp,
+        // JSON-RPC errors (e.g. request timeouts) are answered on the stream like on /mcp.
+        Err((StatusCode::OK, resp)) => Some(resp),
+        Err((sc, v)) => return (sc, Json(v)).into_response(),
+    };
 
-    if let (Some(sess), Some(resp)) = (session, maybe_resp)
-        && let Some(tx) = state.sse_channels.lock().await.get(&sess).cloned()
-    {
-        let _ = tx.send(resp);
+    state
+        .handler
+        .deprecations()
+        .record(&LEGACY_SSE_TRANSPORT, &sess);
+    if let Some(mut resp) = maybe_resp {
+        deprecations::attach_to_response(&mut resp, &[&LEGACY_SSE_TRANSPORT]);
+        publish_event(&state, &sess, resp).await;
     }
 
     StatusCode::ACCEPTED.into_response()
@@ -1100,6 +1885,7 @@ mod tests {
     fn test_origin_validation_disabled() {
         let security = SecurityConfig {
             allowed_origins: None,
+            ..Default::default()
         };
         let headers = HeaderMap::new();
         assert!(validate_origin(&headers, &security).is_ok());
@@ -1109,6 +1895,7 @@ mod tests {
     fn test_origin_validation_localhost_only() {
         let security = SecurityConfig {
             allowed_origins: Some(vec![]),
+            ..Default::default()
         };
 
         // No origin - should pass
@@ -1135,6 +1922,7 @@ mod tests {
     fn test_origin_validation_with_allowed_list() {
         let security = SecurityConfig {
             allowed_origins: Some(vec!["https://example.com".to_string()]),
+            ..Default::default()
         };
 
         // Allowed origin - should pass
@@ -1176,49 +1964,19 @@ mod tests {
         assert!(validate_protocol_version(&headers, None).is_ok());
     }
 
-    #[test]
-    fn test_session_state_event_id() {
-        let state = SessionState::new("2025-03-26".to_string());
-        let id1 = state.next_event_id("session123");
-        let id2 = state.next_event_id("session123");
-
-        assert!(id1.starts_with("session123:"));
-        assert!(id2.starts_with("session123:"));
-        assert_ne!(id1, id2);
-    }
-
     #[tokio::test]
-    async fn test_session_state_event_buffer() {
-        let state = SessionState::new("2025-03-26".to_string());
-
-        // Store some events
-        state
-            .store_event(StoredEvent {
-                id: "s:0".to_string(),
-                data: json!({"msg": "first"}),
-            })
-            .await;
-        state
-            .store_event(StoredEvent {
-                id: "s:1".to_string(),
-                data: json!({"msg": "second"}),
-            })
-            .await;
-        state
-            .store_event(StoredEvent {
-                id: "s:2".to_string(),
-                data: json!({"msg": "third"}),
-            })
-            .await;
+    async fn test_validate_session_uses_store() {
+        let store = MemorySessionStore::new();
+        store
+            .put("session123", &SessionRecord::new("2025-03-26"))
+            .await
+            .unwrap();
 
-        // Get events after s:0
-        let events = state.get_events_after("s:0").await;
-        assert_eq!(events.len(), 2);
-        assert_eq!(events[0].id, "s:1");
-        assert_eq!(events[1].id, "s:2");
+        let record = validate_session(Some("session123"), &store).await.unwrap();
+        assert_eq!(record.unwrap().protocol_version, "2025-03-26");
+        assert!(validate_session(None, &store).await.unwrap().is_none());
 
-        // Get events after non-existent ID
-        let events = state.get_events_after("s:999").await;
-        assert!(events.is_empty());
+        let (status, _) = validate_session(Some("missing"), &store).await.unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
     }
 }
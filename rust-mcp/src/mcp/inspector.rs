@@ -0,0 +1,284 @@
+//! Request inspector: the last JSON-RPC exchanges of each session, for debugging.
+//!
+//! `McpOdooHandler::handle_request` records every request with its response or error, keyed
+//! by the transport scope (HTTP session id, or the stdio stream). Arguments and results are
+//! redacted before they are stored: values under secret-looking keys are masked and long
+//! strings (attachment content, HTML bodies) are cut short. `MCP_INSPECTOR_REQUESTS` sets how
+//! many exchanges are kept per session (default 20, `0` disables the inspector).
+//!
+//! Admins read a session's exchanges from `GET /inspector?session=<id>`; the
+//! `odoo_debug_requests` tool returns the caller's own.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+pub const INSPECTOR_REQUESTS_ENV: &str = "MCP_INSPECTOR_REQUESTS";
+const DEFAULT_CAPACITY: usize = 20;
+/// Strings longer than this are truncated in recorded params and results.
+const MAX_STRING_CHARS: usize = 500;
+const REDACTED: &str = "[redacted]";
+
+/// Keys whose values are masked wherever they appear.
+const SECRET_KEYS: &[&str] = &[
+    "password",
+    "passwd",
+    "apikey",
+    "api_key",
+    "token",
+    "secret",
+    "authorization",
+    "credentials",
+];
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    SECRET_KEYS.iter().any(|secret| key.contains(secret))
+}
+
+/// `value` with secrets masked and long strings truncated.
+pub fn redact(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| {
+                    let value = if is_secret_key(key) && !value.is_null() {
+                        Value::String(REDACTED.to_string())
+                    } else {
+                        redact(value)
+                    };
+                    (key.clone(), value)
+                })
+                .collect::<Map<_, _>>(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(redact).collect()),
+        Value::String(s) if s.chars().count() > MAX_STRING_CHARS => {
+            let kept: String = s.chars().take(MAX_STRING_CHARS).collect();
+            let dropped = s.chars().count() - MAX_STRING_CHARS;
+            Value::String(format!("{kept}… [{dropped} more chars]"))
+        }
+        other => other.clone(),
+    }
+}
+
+/// One recorded request and its outcome.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Exchange {
+    pub at: DateTime<Utc>,
+    /// JSON-RPC id; absent for notifications.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Value>,
+    pub method: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+impl Exchange {
+    /// A failed request, or a tool call that returned `isError`.
+    pub fn is_error(&self) -> bool {
+        self.error.is_some()
+            || self
+                .result
+                .as_ref()
+                .and_then(|result| result.get("isError"))
+                .and_then(Value::as_bool)
+                == Some(true)
+    }
+}
+
+#[derive(Debug, Default)]
+struct SessionLog {
+    tenant: Option<String>,
+    exchanges: VecDeque<Exchange>,
+}
+
+#[derive(Clone)]
+pub struct RequestInspector {
+    sessions: Arc<Mutex<HashMap<String, SessionLog>>>,
+    capacity: usize,
+}
+
+impl Default for RequestInspector {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl RequestInspector {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            sessions: Arc::default(),
+            capacity,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let capacity = std::env::var(INSPECTOR_REQUESTS_ENV)
+            .ok()
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .unwrap_or(DEFAULT_CAPACITY);
+        Self::new(capacity)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    /// Exchanges kept per session.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, SessionLog>> {
+        self.sessions.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Record one exchange for `scope`, dropping the oldest past the capacity.
+    pub fn record(
+        &self,
+        scope: &str,
+        tenant: Option<&str>,
+        id: Option<&Value>,
+        method: &str,
+        params: Option<&Value>,
+        outcome: Result<&Value, String>,
+        elapsed: Duration,
+    ) {
+        if !self.is_enabled() {
+            return;
+        }
+        let (result, error) = match outcome {
+            Ok(result) => (Some(redact(result)), None),
+            Err(error) => (None, Some(error)),
+        };
+        let exchange = Exchange {
+            at: Utc::now(),
+            id: id.cloned(),
+            method: method.to_string(),
+            params: params.map(redact),
+            result,
+            error,
+            duration_ms: elapsed.as_millis() as u64,
+        };
+        let mut sessions = self.lock();
+        let log = sessions.entry(scope.to_string()).or_default();
+        if tenant.is_some() {
+            log.tenant = tenant.map(str::to_string);
+        }
+        if log.exchanges.len() == self.capacity {
+            log.exchanges.pop_front();
+        }
+        log.exchanges.push_back(exchange);
+    }
+
+    /// The latest `limit` exchanges of `scope`, newest first.
+    pub fn recent(&self, scope: &str, limit: usize) -> Vec<Exchange> {
+        self.lock()
+            .get(scope)
+            .map(|log| log.exchanges.iter().rev().take(limit).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Tenant of the token that last called in `scope`, if any.
+    pub fn tenant(&self, scope: &str) -> Option<String> {
+        self.lock().get(scope).and_then(|log| log.tenant.clone())
+    }
+
+    pub fn forget(&self, scope: &str) {
+        self.lock().remove(scope);
+    }
+
+    /// `scope`'s exchanges as seen from one tool call.
+    pub fn for_scope(&self, scope: &str) -> Option<SessionRequests> {
+        self.is_enabled().then(|| SessionRequests {
+            inspector: self.clone(),
+            scope: scope.to_string(),
+        })
+    }
+}
+
+/// The calling session's recorded exchanges, handed to `odoo_debug_requests`.
+#[derive(Clone)]
+pub struct SessionRequests {
+    inspector: RequestInspector,
+    scope: String,
+}
+
+impl SessionRequests {
+    pub fn recent(&self, limit: usize) -> Vec<Exchange> {
+        self.inspector.recent(&self.scope, limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn redacts_secrets_and_long_strings() {
+        let redacted = redact(&json!({
+            "name": "prod",
+            "apiKey": "abc",
+            "config": {"password": "hunter2", "db": "odoo"},
+            "datas": "x".repeat(MAX_STRING_CHARS + 3),
+        }));
+        assert_eq!(redacted["name"], "prod");
+        assert_eq!(redacted["apiKey"], REDACTED);
+        assert_eq!(redacted["config"]["password"], REDACTED);
+        assert_eq!(redacted["config"]["db"], "odoo");
+        assert!(
+            redacted["datas"]
+                .as_str()
+                .unwrap()
+                .ends_with("… [3 more chars]")
+        );
+    }
+
+    #[test]
+    fn keeps_the_last_exchanges_per_session() {
+        let inspector = RequestInspector::new(2);
+        for n in 0..3 {
+            inspector.record(
+                "s1",
+                Some("finance"),
+                Some(&json!(n)),
+                "tools/call",
+                None,
+                Ok(&json!({"isError": n == 2})),
+                Duration::from_millis(5),
+            );
+        }
+        inspector.record(
+            "s2",
+            None,
+            None,
+            "ping",
+            None,
+            Err("boom".into()),
+            Duration::ZERO,
+        );
+
+        let recent = inspector.recent("s1", 10);
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].id, Some(json!(2)));
+        assert!(recent[0].is_error());
+        assert!(!recent[1].is_error());
+        assert_eq!(inspector.tenant("s1").as_deref(), Some("finance"));
+        assert!(inspector.recent("s2", 10)[0].is_error());
+
+        inspector.forget("s1");
+        assert!(inspector.recent("s1", 10).is_empty());
+        assert!(RequestInspector::new(0).for_scope("s1").is_none());
+    }
+}
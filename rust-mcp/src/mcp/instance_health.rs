@@ -0,0 +1,138 @@
+//! Instance reachability tracked by a background probe.
+//!
+//! With `MCP_HEALTH_GATED_TOOLS=true`, `OdooClientPool::spawn_health_monitor` probes every
+//! configured instance each `MCP_HEALTH_CHECK_SECS` (default 30). An instance whose probe fails
+//! is degraded: `tools/list` treats it like a read-only instance and hides mutating tools until a
+//! probe succeeds again. Each flip is announced through `notifications/tools/list_changed`.
+//! Calls are not blocked; they fail (or succeed) against Odoo as usual.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use tokio::sync::broadcast;
+
+use crate::mcp::registry::env_truthy;
+
+const DEFAULT_INTERVAL_SECS: u64 = 30;
+
+pub fn gating_enabled() -> bool {
+    env_truthy("MCP_HEALTH_GATED_TOOLS")
+}
+
+pub fn probe_interval() -> Duration {
+    let secs = std::env::var("MCP_HEALTH_CHECK_SECS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_INTERVAL_SECS);
+    Duration::from_secs(secs.max(1))
+}
+
+/// An instance turning degraded (`error` set) or recovering.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HealthChange {
+    pub instance: String,
+    pub error: Option<String>,
+}
+
+/// Degraded instances and the error of their last failed probe.
+#[derive(Clone)]
+pub struct InstanceHealth {
+    degraded: Arc<RwLock<HashMap<String, String>>>,
+    changes: broadcast::Sender<HealthChange>,
+}
+
+impl Default for InstanceHealth {
+    fn default() -> Self {
+        Self {
+            degraded: Arc::default(),
+            changes: broadcast::channel(16).0,
+        }
+    }
+}
+
+impl InstanceHealth {
+    pub fn is_degraded(&self, instance: &str) -> bool {
+        self.degraded
+            .read()
+            .is_ok_and(|degraded| degraded.contains_key(instance))
+    }
+
+    pub fn last_error(&self, instance: &str) -> Option<String> {
+        self.degraded.read().ok()?.get(instance).cloned()
+    }
+
+    /// Record a probe outcome; returns whether the instance changed between healthy and degraded.
+    pub fn record(&self, instance: &str, outcome: Result<(), String>) -> bool {
+        let Ok(mut degraded) = self.degraded.write() else {
+            return false;
+        };
+        let error = outcome.as_ref().err().cloned();
+        let changed = match outcome {
+            Ok(()) => degraded.remove(instance).is_some(),
+            Err(error) => degraded.insert(instance.to_string(), error).is_none(),
+        };
+        if changed {
+            let _ = self.changes.send(HealthChange {
+                instance: instance.to_string(),
+                error,
+            });
+        }
+        changed
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<HealthChange> {
+        self.changes.subscribe()
+    }
+
+    /// Forget instances that are no longer configured; returns whether any were degraded.
+    pub fn retain(&self, instances: &[String]) -> bool {
+        let Ok(mut degraded) = self.degraded.write() else {
+            return false;
+        };
+        let before = degraded.len();
+        degraded.retain(|name, _| instances.contains(name));
+        degraded.len() != before
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_reports_only_transitions() {
+        let health = InstanceHealth::default();
+        let mut changes = health.subscribe();
+        assert!(!health.record("prod", Ok(())));
+        assert!(health.record("prod", Err("connection refused".into())));
+        assert!(!health.record("prod", Err("timed out".into())));
+        assert!(health.is_degraded("prod"));
+        assert_eq!(health.last_error("prod").as_deref(), Some("timed out"));
+        assert!(health.record("prod", Ok(())));
+        assert!(!health.is_degraded("prod"));
+        let flips: Vec<_> = std::iter::from_fn(|| changes.try_recv().ok()).collect();
+        assert_eq!(
+            flips,
+            vec![
+                HealthChange {
+                    instance: "prod".into(),
+                    error: Some("connection refused".into())
+                },
+                HealthChange {
+                    instance: "prod".into(),
+                    error: None
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn retain_drops_removed_instances() {
+        let health = InstanceHealth::default();
+        health.record("old", Err("down".into()));
+        assert!(!health.retain(&["old".to_string()]));
+        assert!(health.retain(&["new".to_string()]));
+        assert!(!health.is_degraded("old"));
+    }
+}
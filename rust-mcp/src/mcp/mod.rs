@@ -1,13 +1,49 @@
+pub mod arg_validation;
+pub mod audit;
+pub mod audit_export;
+pub mod auth_tokens;
 pub mod cache;
+pub mod cancellation;
+pub mod canonical;
 pub mod capability;
+pub mod completions;
+pub mod config_check;
+pub mod confirmation;
+pub mod csv_export;
 pub mod cursor_stdio;
+pub mod cursors;
+pub mod deprecations;
+pub mod event_sink;
 pub mod http;
+pub mod inspector;
+pub mod instance_health;
+pub mod model_tools;
 pub mod module_snapshot;
+pub mod orchestrator;
+pub mod progress;
 pub mod prompts;
+pub mod rate_limit;
 pub mod registry;
+pub mod relay;
+pub mod renderers;
+pub mod request_signing;
 pub mod resources;
+pub mod retention;
 pub mod runtime;
+pub mod sampling;
+pub mod server_info;
+pub mod session_directory;
+pub mod session_idle;
+pub mod session_store;
+pub mod smart_search;
+pub mod snapshot;
+pub mod sse_channels;
+pub mod subscriptions;
+pub mod tenants;
+pub mod tls;
+pub mod tool_examples;
 pub mod tools;
+pub mod view_arch;
 
 use async_trait::async_trait;
 use futures::future::join_all;
@@ -18,21 +54,269 @@ use serde_json::{Value, json};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Instant;
-use tracing::{info, warn};
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
+use tracing::{Instrument, info, info_span, warn};
 
+use crate::mcp::audit::{AuditEntry, AuditLog};
+use crate::mcp::audit_export::AuditExporter;
+use crate::mcp::auth_tokens::TokenGrant;
+use crate::mcp::cancellation::{CancellationRegistry, request_key};
+use crate::mcp::completions::Completions;
+use crate::mcp::deprecations::DeprecationTracker;
+use crate::mcp::event_sink::{EventKind, EventSink};
+use crate::mcp::inspector::RequestInspector;
+use crate::mcp::model_tools::FieldDescriptions;
+use crate::mcp::progress::Progress;
 use crate::mcp::prompts::{get_prompt_result, list_prompts_result};
-use crate::mcp::registry::{Registry, ToolCapabilityContext};
-use crate::mcp::tools::{OdooClientPool, call_tool};
+use crate::mcp::rate_limit::{RateLimitConfig, RateLimiter};
+use crate::mcp::registry::{ListChanged, Registry, ToolCapabilityContext};
+use crate::mcp::sampling::{ClientRequests, Sampler};
+use crate::mcp::server_info::ServerInfo;
+use crate::mcp::session_directory::SessionDirectory;
+use crate::mcp::subscriptions::ResourceSubscriptions;
+use crate::mcp::tenants::Tenant;
+use crate::mcp::tls::TlsSettings;
+use crate::mcp::tools::{CallContext, OdooClientPool, call_tool};
+use crate::odoo::deadline;
+use crate::odoo::types::OdooError;
+use crate::telemetry;
 
 #[derive(Clone)]
 pub struct McpOdooHandler {
     pool: OdooClientPool,
     registry: Arc<Registry>,
+    cancellations: CancellationRegistry,
+    client_requests: ClientRequests,
+    subscriptions: ResourceSubscriptions,
+    completions: Completions,
+    field_descriptions: FieldDescriptions,
+    audit: Option<Arc<dyn AuditLog>>,
+    audit_export: Option<AuditExporter>,
+    events: Option<EventSink>,
+    tenant_quotas: Arc<RateLimiter>,
+    sessions: SessionDirectory,
+    inspector: RequestInspector,
+    deprecations: DeprecationTracker,
+    server_info: ServerInfo,
 }
 
 impl McpOdooHandler {
     pub fn new(pool: OdooClientPool, registry: Arc<Registry>) -> Self {
-        Self { pool, registry }
+        let inspector = RequestInspector::from_env();
+        let server_info = ServerInfo::new(inspector.capacity());
+        Self {
+            pool,
+            registry,
+            cancellations: CancellationRegistry::new(),
+            client_requests: ClientRequests::new(),
+            subscriptions: ResourceSubscriptions::new(),
+            completions: Completions::new(),
+            field_descriptions: FieldDescriptions::new(),
+            audit: None,
+            audit_export: None,
+            events: None,
+            tenant_quotas: Arc::new(RateLimiter::new(RateLimitConfig::default())),
+            sessions: SessionDirectory::default(),
+            inspector,
+            deprecations: DeprecationTracker::default(),
+            server_info,
+        }
+    }
+
+    /// Record every tool call in `log` (see [`audit`]).
+    pub fn with_audit_log(mut self, log: Arc<dyn AuditLog>) -> Self {
+        self.server_info.note_audit_log(log.backend());
+        self.audit = Some(log);
+        self
+    }
+
+    /// Export the audit log in batches with `exporter` (see [`audit_export`]).
+    pub fn with_audit_exporter(mut self, exporter: AuditExporter) -> Self {
+        self.audit_export = Some(exporter);
+        self
+    }
+
+    /// Mirror tool calls to `sink` (see [`event_sink`]).
+    pub fn with_event_sink(mut self, sink: EventSink) -> Self {
+        self.server_info.note_event_sink();
+        self.events = Some(sink);
+        self
+    }
+
+    pub fn audit_log(&self) -> Option<&Arc<dyn AuditLog>> {
+        self.audit.as_ref()
+    }
+
+    pub fn audit_exporter(&self) -> Option<&AuditExporter> {
+        self.audit_export.as_ref()
+    }
+
+    /// Live HTTP sessions (see [`session_directory`]).
+    pub fn sessions(&self) -> &SessionDirectory {
+        &self.sessions
+    }
+
+    /// Recent requests per session (see [`inspector`]).
+    pub fn inspector(&self) -> &RequestInspector {
+        &self.inspector
+    }
+
+    /// Deprecated features clients have used (see [`deprecations`]).
+    pub fn deprecations(&self) -> &DeprecationTracker {
+        &self.deprecations
+    }
+
+    /// Effective configuration for `odoo_server_info` (see [`server_info`]).
+    pub fn server_info(&self) -> &ServerInfo {
+        &self.server_info
+    }
+
+    /// Take one tool call from the tenant's `callsPerMinute` quota.
+    fn charge_tenant(&self, tenant: Option<&Tenant>) -> Result<(), OdooError> {
+        let Some((tenant, quota)) = tenant.and_then(|t| Some((t, t.quota()?))) else {
+            return Ok(());
+        };
+        self.tenant_quotas
+            .check(&format!("tenant:{}", tenant.name), quota, Instant::now())
+            .map_err(|wait| {
+                OdooError::InvalidResponse(format!(
+                    "Tenant '{}' exceeded its quota of {} tool calls per minute; retry in {}s",
+                    tenant.name,
+                    quota.per_minute,
+                    wait.as_secs_f64().ceil().max(1.0) as u64
+                ))
+            })
+    }
+
+    /// Handle a JSON-RPC message that may be cancelled or may cancel another request.
+    ///
+    /// `scope` identifies the client connection (HTTP session id, or the stdio stream) so
+    /// `notifications/cancelled` only affects that client's requests. `grant` is the API token
+    /// the request authenticated with; `None` means unrestricted (stdio, or auth disabled).
+    /// `request_id` is the JSON-RPC id, or `None` for notifications.
+    pub async fn handle_request(
+        &self,
+        scope: &str,
+        grant: Option<&TokenGrant>,
+        request_id: Option<&Value>,
+        method: &str,
+        params: Option<Value>,
+    ) -> Result<Value, Error> {
+        if method == "notifications/cancelled" {
+            if let Some(target) = params.as_ref().and_then(|p| p.get("requestId")) {
+                let cancelled = self.cancellations.cancel(&request_key(scope, target));
+                let reason = params
+                    .as_ref()
+                    .and_then(|p| p.get("reason"))
+                    .and_then(|r| r.as_str())
+                    .unwrap_or("");
+                info!(
+                    request_id = %target,
+                    cancelled,
+                    reason,
+                    "MCP request cancellation received"
+                );
+            }
+            return Ok(json!({}));
+        }
+        // HTTP transports may already have scoped the caller's X-Request-Id.
+        let trace_id = telemetry::current_request_id().unwrap_or_else(telemetry::new_request_id);
+        let span = info_span!(
+            "mcp.request",
+            rpc.method = method,
+            request_id = %trace_id,
+            error = tracing::field::Empty
+        );
+        let deadline = deadline::from_params(params.as_ref());
+        // Sessionless HTTP requests share the empty scope; there is nothing to inspect there.
+        let inspected = (self.inspector.is_enabled() && !scope.is_empty()).then(|| params.clone());
+        let deprecated = deprecations::for_request(method, params.as_ref());
+        let started = Instant::now();
+        let call = async {
+            // Boxed: the dispatch future spans every method and tool op, and keeping it inline
+            // made each transport's handler future large enough to overflow the stack in debug
+            // builds.
+            let outcome = match request_id {
+                Some(id) => {
+                    let in_flight = self.cancellations.register(request_key(scope, id));
+                    Box::pin(self.dispatch(scope, grant, method, params, in_flight.token())).await
+                }
+                None => {
+                    Box::pin(self.dispatch(scope, grant, method, params, &CancellationToken::new()))
+                        .await
+                }
+            };
+            if let Err(err) = &outcome {
+                tracing::Span::current().record("error", err.to_string());
+            }
+            outcome
+        };
+        let call = telemetry::with_session(scope, deadline::scope(deadline, call));
+        let mut outcome = telemetry::scope(Some(trace_id), call.instrument(span)).await;
+        if !deprecated.is_empty() {
+            self.deprecations.record_all(&deprecated, scope);
+            if let Ok(result) = &mut outcome {
+                deprecations::attach(result, &deprecated);
+            }
+        }
+        if let Some(params) = inspected {
+            let tenant = grant
+                .and_then(|grant| grant.tenant.as_ref())
+                .map(|tenant| tenant.name.as_str());
+            self.inspector.record(
+                scope,
+                tenant,
+                request_id,
+                method,
+                params.as_ref(),
+                outcome.as_ref().map_err(ToString::to_string),
+                started.elapsed(),
+            );
+        }
+        outcome
+    }
+
+    /// Forward registry reloads to every connected client as `notifications/*/list_changed`.
+    /// Instance reloads that add or remove names also refresh the tool list, whose `instance`
+    /// enums list the configured instances.
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub fn spawn_list_changed_notifier(&self) {
+        let mut changes = self.registry.subscribe_changes();
+        let mut instances = self.pool.subscribe_instance_changes();
+        let requests = self.client_requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let change = tokio::select! {
+                    change = changes.recv() => change,
+                    changed = instances.recv() => changed.map(|()| ListChanged::Tools),
+                };
+                match change {
+                    Ok(change) => requests.notify_all(change.notification_method(), json!({})),
+                    Err(RecvError::Lagged(_)) => {
+                        // Some changes were missed; refreshing both lists is always safe.
+                        for change in [ListChanged::Tools, ListChanged::Prompts] {
+                            requests.notify_all(change.notification_method(), json!({}));
+                        }
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+
+    /// Server-to-client request channels; transports attach one per client scope.
+    pub fn client_requests(&self) -> &ClientRequests {
+        &self.client_requests
+    }
+
+    /// Release per-session state once a transport scope ends (stdio EOF or HTTP DELETE).
+    pub fn end_session(&self, scope: &str) {
+        self.client_requests.detach(scope);
+        self.pool.disconnect_session(scope);
+        self.sessions.close(scope);
+        self.inspector.forget(scope);
     }
 
     pub fn instance_names(&self) -> Vec<String> {
@@ -47,11 +331,37 @@ impl McpOdooHandler {
         self.registry.instructions().await
     }
 
+    pub async fn tls_settings(&self) -> Option<TlsSettings> {
+        self.registry.tls_settings().await
+    }
+
     pub async fn protocol_version_default(&self) -> String {
         self.registry.protocol_version_default().await
     }
 }
 
+/// Audit failures are logged, never surfaced to the caller whose call already ran.
+async fn record_audit(log: &dyn AuditLog, entry: &AuditEntry) {
+    if let Err(e) = log.record(entry).await {
+        warn!(
+            backend = log.backend(),
+            tool = entry.tool,
+            "Failed to write audit entry: {}",
+            e
+        );
+    }
+}
+
+/// Outcome label of a failed tool call in logs and the audit trail.
+fn error_outcome(error: &OdooError) -> &'static str {
+    match error {
+        OdooError::Cancelled => "cancelled",
+        OdooError::DeadlineExceeded { .. } => "deadline_exceeded",
+        OdooError::UpstreamThrottled { .. } => "throttled",
+        _ => "error",
+    }
+}
+
 fn protocol_err(message: impl Into<String>) -> Error {
     Error::protocol(ErrorCode::InvalidRequest, message)
 }
@@ -68,6 +378,7 @@ impl ServerHandler for McpOdooHandler {
         custom.insert("tools".to_string(), json!({}));
         custom.insert("prompts".to_string(), json!({}));
         custom.insert("resources".to_string(), json!({}));
+        custom.insert("completions".to_string(), json!({}));
         custom.insert(
             "odooInstances".to_string(),
             json!({ "available": self.pool.instance_names() }),
@@ -82,6 +393,28 @@ impl ServerHandler for McpOdooHandler {
     }
 
     async fn handle_method(&self, method: &str, params: Option<Value>) -> Result<Value, Error> {
+        self.dispatch("", None, method, params, &CancellationToken::new())
+            .await
+    }
+}
+
+impl McpOdooHandler {
+    async fn dispatch(
+        &self,
+        scope: &str,
+        grant: Option<&TokenGrant>,
+        method: &str,
+        params: Option<Value>,
+        cancel: &CancellationToken,
+    ) -> Result<Value, Error> {
+        // Sees the instances this session registered with odoo_connect, and of the configured
+        // ones only the token's tenant's.
+        let pool = self.pool.scoped(scope);
+        let tenant = grant.and_then(|grant| grant.tenant.as_ref());
+        let pool = match tenant {
+            Some(tenant) => pool.for_tenant(tenant),
+            None => pool,
+        };
         match method {
             "tools/list" => {
                 // Fully declarative: tools are served from tools.json (registry).
@@ -91,24 +424,50 @@ impl ServerHandler for McpOdooHandler {
                     .and_then(|params| params.get("instance"))
                     .and_then(Value::as_str)
                     .map(str::to_string);
-                let read_only = instance
-                    .as_deref()
-                    .map(|instance| self.pool.instance_is_read_only(instance))
-                    .unwrap_or_else(|| self.pool.all_instances_read_only());
+                let read_only = pool.hides_write_tools(instance.as_deref());
+                let instance_names = pool.instance_names();
+                // Field lists and examples come from the requested instance, else the first
+                // one by name.
+                let described_instance = instance
+                    .clone()
+                    .or_else(|| instance_names.iter().min().cloned());
+                let scoped = instance.is_some();
                 let instances = if let Some(instance) = instance {
                     vec![instance]
                 } else {
-                    self.pool.instance_names()
+                    instance_names.clone()
                 };
+                let pool = &pool;
                 let capabilities = join_all(instances.into_iter().map(|instance| async move {
                     ToolCapabilityContext {
-                        snapshot: self.pool.module_snapshot(&instance).await,
-                        disabled_packs: self.pool.disabled_packs(&instance),
+                        snapshot: pool.module_snapshot(&instance).await,
+                        disabled_packs: pool.disabled_packs(&instance),
+                        // Allow/deny lists only shape lists bound to their instance; calls
+                        // are checked against them either way.
+                        tool_config: if scoped {
+                            pool.tool_config(&instance)
+                        } else {
+                            None
+                        },
                         instance,
                     }
                 }))
                 .await;
-                let tools = self.registry.list_tools(read_only, &capabilities).await;
+                let mut tools = self
+                    .registry
+                    .list_tools(read_only, &capabilities, &instance_names)
+                    .await;
+                let described = self.registry.described_tools().await;
+                if let Some(instance) = described_instance {
+                    if !described.is_empty() {
+                        self.field_descriptions
+                            .describe(pool, &instance, &mut tools, &described)
+                            .await;
+                    }
+                    if tool_examples::enabled() {
+                        tool_examples::enrich(&self.completions, pool, &instance, &mut tools).await;
+                    }
+                }
                 Ok(json!({ "tools": tools }))
             }
             "tools/call" => {
@@ -136,8 +495,21 @@ impl ServerHandler for McpOdooHandler {
                     .map(Vec::len)
                     .or_else(|| args.get("id").map(|_| 1));
 
+                self.sessions
+                    .record_tool(scope, name, instance_name.as_deref());
+                let mut audit = self.audit.as_ref().map(|log| {
+                    let token = grant.map(|grant| grant.name.as_str());
+                    let mut entry = AuditEntry::begin(scope, token, name, &args);
+                    entry.tenant = tenant.map(|tenant| tenant.name.clone());
+                    (log, entry)
+                });
+
                 let Some(tool) = self.registry.get_tool(name, instance_name.as_deref()).await
                 else {
+                    if let Some((log, entry)) = audit {
+                        let entry = entry.finish("unknown_tool", None, None, started.elapsed());
+                        record_audit(log.as_ref(), &entry).await;
+                    }
                     warn!(
                         service = "odoo-rust-mcp",
                         tool = name,
@@ -160,7 +532,59 @@ impl ServerHandler for McpOdooHandler {
                     }));
                 };
 
-                match call_tool(&self.pool, &tool, args).await {
+                let model = model.or_else(|| {
+                    tool.op
+                        .pinned
+                        .get("model")
+                        .and_then(Value::as_str)
+                        .map(str::to_owned)
+                });
+                if let Some((_, entry)) = audit.as_mut()
+                    && entry.model.is_none()
+                {
+                    entry.model = model.clone();
+                }
+                let ctx = CallContext {
+                    cancel: cancel.clone(),
+                    sampler: Sampler::for_scope(&self.client_requests, scope),
+                    grant: grant.cloned(),
+                    progress: Progress::for_request(&params, &self.client_requests, scope),
+                    requests: self.inspector.for_scope(scope),
+                    server: Some(self.server_info.clone()),
+                };
+                let outcome = match self.charge_tenant(tenant) {
+                    Ok(()) => {
+                        telemetry::with_tool(&tool.name, call_tool(&pool, &tool, args, &ctx)).await
+                    }
+                    Err(e) => Err(e),
+                };
+                if let Some((log, entry)) = audit {
+                    let entry = match &outcome {
+                        Ok(v) => entry.finish("success", Some(v), None, started.elapsed()),
+                        Err(e) => entry.finish(
+                            error_outcome(e),
+                            None,
+                            Some(e.to_string()),
+                            started.elapsed(),
+                        ),
+                    };
+                    record_audit(log.as_ref(), &entry).await;
+                }
+                if let Some(sink) = &self.events {
+                    let mut event = json!({
+                        "session": telemetry::session_tag(scope),
+                        "tool": name,
+                        "instance": instance_name,
+                        "model": model,
+                        "outcome": outcome.as_ref().map_or_else(error_outcome, |_| "success"),
+                        "durationMs": started.elapsed().as_millis() as u64,
+                    });
+                    if let Err(e) = &outcome {
+                        event["error"] = json!(e.to_string());
+                    }
+                    sink.publish(EventKind::ToolCall, event);
+                }
+                match outcome {
                     Ok(v) => {
                         info!(
                             service = "odoo-rust-mcp",
@@ -181,7 +605,7 @@ impl ServerHandler for McpOdooHandler {
                             instance = instance_name.as_deref().unwrap_or("unknown"),
                             model = model.as_deref().unwrap_or("unknown"),
                             record_id_count = record_id_count.unwrap_or(0),
-                            outcome = "error",
+                            outcome = error_outcome(&e),
                             duration_ms = started.elapsed().as_millis(),
                             "MCP tool call completed"
                         );
@@ -192,6 +616,16 @@ impl ServerHandler for McpOdooHandler {
                         if let Some(instance) = instance_name {
                             error_payload["instance"] = json!(instance);
                         }
+                        if let OdooError::InvalidArguments(violations) = &e {
+                            error_payload["violations"] = json!(violations);
+                        }
+                        if matches!(e, OdooError::DeadlineExceeded { .. }) {
+                            error_payload["deadlineExceeded"] = json!(true);
+                        }
+                        if let OdooError::UpstreamThrottled { retry_after_secs } = &e {
+                            error_payload["upstreamThrottled"] = json!(true);
+                            error_payload["retryAfterSecs"] = json!(retry_after_secs);
+                        }
 
                         Ok(json!({
                             "content": [{
@@ -222,7 +656,8 @@ impl ServerHandler for McpOdooHandler {
                     .ok_or_else(|| protocol_err(format!("Unknown prompt: {name}")))?;
                 Ok(get_prompt_result(&p))
             }
-            "resources/list" => resources::list_resources(&self.pool).await,
+            "resources/list" => resources::list_resources(&pool).await,
+            "resources/templates/list" => Ok(resources::list_resource_templates()),
             "resources/read" => {
                 let params =
                     params.ok_or_else(|| protocol_err("Missing params for resources/read"))?;
@@ -230,7 +665,27 @@ impl ServerHandler for McpOdooHandler {
                     .get("uri")
                     .and_then(|v| v.as_str())
                     .ok_or_else(|| protocol_err("resources/read missing 'uri'"))?;
-                resources::read_resource(&self.pool, uri).await
+                resources::read_resource(&pool, uri).await
+            }
+            "resources/subscribe" | "resources/unsubscribe" => {
+                let uri = params
+                    .as_ref()
+                    .and_then(|p| p.get("uri"))
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| protocol_err(format!("{method} missing 'uri'")))?;
+                if method == "resources/subscribe" {
+                    self.subscriptions
+                        .subscribe(scope, uri, pool.clone(), self.client_requests.clone())
+                        .map_err(protocol_err)?;
+                } else {
+                    self.subscriptions.unsubscribe(scope, uri);
+                }
+                Ok(json!({}))
+            }
+            "completion/complete" => {
+                let params =
+                    params.ok_or_else(|| protocol_err("Missing params for completion/complete"))?;
+                self.completions.complete(&pool, &params).await
             }
             // MCP ping method for health check / keep-alive
             "ping" => Ok(json!({})),
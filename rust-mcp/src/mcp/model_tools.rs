@@ -0,0 +1,459 @@
+//! Per-model tools generated from the `modelTools` section of tools.json.
+//!
+//! Each entry pins one Odoo model and expands into specialized tools (`search_sale_orders`,
+//! `create_sale_order`, ...) that run the generic ops with `model` fixed, so on servers with many
+//! tools the LLM picks a tool by what it is about instead of filling in a model name. When tools
+//! are listed, the `fields`, `values` and `domain` descriptions of these tools are filled in
+//! from `fields_get`.
+
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+
+use crate::mcp::cache::MetadataCache;
+use crate::mcp::registry::{OpSpec, ToolDef, ToolGuards};
+use crate::mcp::tools::OdooClientPool;
+
+const DEFAULT_TTL_SECS: u64 = 300;
+
+/// Fields listed per description when the entry does not name them.
+const MAX_LISTED_FIELDS: usize = 40;
+
+/// Env guard shared with the generic write tools.
+const WRITE_GUARD: &str = "ODOO_ENABLE_WRITE_TOOLS";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModelToolSpec {
+    pub model: String,
+    /// Singular slug used in tool names; defaults to the model with dots replaced (`sale_order`).
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Plural slug; defaults to `name` followed by `s`.
+    #[serde(default)]
+    pub plural: Option<String>,
+    /// Ops to generate, out of `search`, `count`, `read`, `create`, `update` and `delete`.
+    #[serde(default = "default_ops")]
+    pub ops: Vec<String>,
+    /// Fields listed in the descriptions, in this order; empty lists stored fields by name.
+    #[serde(default)]
+    pub fields: Vec<String>,
+    #[serde(default)]
+    pub pack: Option<String>,
+    #[serde(default, rename = "requiredModules")]
+    pub required_modules: Vec<String>,
+}
+
+fn default_ops() -> Vec<String> {
+    ["search", "count", "read"].map(String::from).to_vec()
+}
+
+impl ModelToolSpec {
+    fn singular(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| self.model.replace('.', "_"))
+    }
+
+    fn plural(&self) -> String {
+        self.plural
+            .clone()
+            .unwrap_or_else(|| format!("{}s", self.singular()))
+    }
+
+    /// The tools this entry stands for, in `ops` order.
+    pub fn expand(&self) -> anyhow::Result<Vec<ToolDef>> {
+        if self.model.trim().is_empty() {
+            anyhow::bail!("modelTools entry has an empty model");
+        }
+        let (singular, plural, model) = (self.singular(), self.plural(), &self.model);
+        self.ops
+            .iter()
+            .map(|op| {
+                let (name, op_type, description, properties, required) = match op.as_str() {
+                    "search" => (
+                        format!("search_{plural}"),
+                        "search_read",
+                        format!("Search {model} records and return their field values."),
+                        vec![
+                            "domain",
+                            "fields",
+                            "limit",
+                            "offset",
+                            "order",
+                            "stream",
+                            "batchSize",
+                        ],
+                        vec![],
+                    ),
+                    "count" => (
+                        format!("count_{plural}"),
+                        "search_count",
+                        format!("Count {model} records matching a domain."),
+                        vec!["domain"],
+                        vec![],
+                    ),
+                    "read" => (
+                        format!("read_{plural}"),
+                        "read",
+                        format!("Read {model} records by ID."),
+                        vec!["ids", "fields"],
+                        vec!["ids"],
+                    ),
+                    "create" => (
+                        format!("create_{singular}"),
+                        "create",
+                        format!("Create a {model} record. Returns the new ID."),
+                        vec!["values"],
+                        vec!["values"],
+                    ),
+                    "update" => (
+                        format!("update_{plural}"),
+                        "write",
+                        format!("Update {model} records. Returns true on success."),
+                        vec!["ids", "values"],
+                        vec!["ids", "values"],
+                    ),
+                    "delete" => (
+                        format!("delete_{plural}"),
+                        "unlink",
+                        format!("Delete {model} records. Returns true on success."),
+                        vec!["ids", "confirmationToken"],
+                        vec!["ids"],
+                    ),
+                    other => anyhow::bail!("modelTools entry for {model} has unknown op '{other}'"),
+                };
+                Ok(self.tool(name, op_type, description, &properties, &required))
+            })
+            .collect()
+    }
+
+    fn tool(
+        &self,
+        name: String,
+        op_type: &str,
+        description: String,
+        properties: &[&str],
+        required: &[&str],
+    ) -> ToolDef {
+        let mut schema_properties = Map::new();
+        schema_properties.insert("instance".into(), json!({"type": "string"}));
+        for property in properties.iter().copied().chain(["context"]) {
+            schema_properties.insert(property.into(), property_schema(property));
+        }
+        let mut map: std::collections::HashMap<String, String> = schema_properties
+            .keys()
+            .map(|key| (key.clone(), format!("/{key}")))
+            .collect();
+        map.insert("model".into(), "/model".into());
+        let writes = matches!(op_type, "create" | "write" | "unlink");
+        let required: Vec<&str> = ["instance"]
+            .into_iter()
+            .chain(required.iter().copied())
+            .collect();
+        ToolDef {
+            name,
+            description,
+            pack: self.pack.clone(),
+            required_modules: self.required_modules.clone(),
+            input_schema: json!({
+                "type": "object",
+                "properties": schema_properties,
+                "required": required,
+                "additionalProperties": false
+            }),
+            op: OpSpec {
+                op_type: op_type.to_string(),
+                map,
+                steps: Vec::new(),
+                pinned: Map::from_iter([("model".to_string(), json!(self.model))]),
+            },
+            guards: writes.then(|| ToolGuards {
+                requires_env_true: Some(WRITE_GUARD.to_string()),
+                requires_env_true_all: Vec::new(),
+            }),
+            annotations: Default::default(),
+            describe_fields: Some(self.fields.clone()),
+        }
+    }
+}
+
+fn property_schema(property: &str) -> Value {
+    match property {
+        "domain" => json!({"type": "array", "items": {}, "description": "Odoo domain filter."}),
+        "fields" => json!({
+            "type": "array",
+            "items": {"type": "string"},
+            "description": "Field names to return."
+        }),
+        "ids" => json!({"type": "array", "items": {"type": "integer"}}),
+        "values" => json!({"type": "object", "description": "Field values keyed by field name."}),
+        "limit" | "offset" => json!({"type": "integer"}),
+        "order" => json!({"type": "string"}),
+        "stream" => json!({
+            "type": "boolean",
+            "description": "Send records in batches as notifications/progress (needs a progressToken)."
+        }),
+        "batchSize" => json!({"type": "integer", "minimum": 1, "maximum": 1000}),
+        "confirmationToken" => json!({
+            "type": "string",
+            "description": "Token from the preview returned when ODOO_CONFIRM_DESTRUCTIVE is on."
+        }),
+        _ => json!({"type": "object"}),
+    }
+}
+
+/// A listed tool whose descriptions should name the fields of `model`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DescribedTool {
+    pub name: String,
+    pub model: String,
+    pub fields: Vec<String>,
+}
+
+fn cache_ttl() -> u64 {
+    std::env::var("ODOO_METADATA_CACHE_TTL_SECS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_TTL_SECS)
+}
+
+/// `fields_get` summaries used to describe per-model tools, cached per instance and model.
+#[derive(Clone)]
+pub struct FieldDescriptions {
+    cache: MetadataCache,
+}
+
+impl Default for FieldDescriptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FieldDescriptions {
+    pub fn new() -> Self {
+        Self {
+            cache: MetadataCache::new(),
+        }
+    }
+
+    /// Add field lists from `instance` to the listed `tools` named in `described`.
+    ///
+    /// A model whose fields cannot be loaded keeps its static descriptions.
+    pub async fn describe(
+        &self,
+        pool: &OdooClientPool,
+        instance: &str,
+        tools: &mut [Value],
+        described: &[DescribedTool],
+    ) {
+        for tool in tools.iter_mut() {
+            let Some(spec) = tool
+                .get("name")
+                .and_then(Value::as_str)
+                .and_then(|name| described.iter().find(|d| d.name == name))
+            else {
+                continue;
+            };
+            let Some(fields) = self.fields(pool, instance, &spec.model).await else {
+                continue;
+            };
+            let summary = summarize(&spec.model, &fields, &spec.fields);
+            if let Some(properties) = tool
+                .pointer_mut("/inputSchema/properties")
+                .and_then(Value::as_object_mut)
+            {
+                for key in ["fields", "values", "domain"] {
+                    if let Some(property) = properties.get_mut(key) {
+                        let base = property
+                            .get("description")
+                            .and_then(Value::as_str)
+                            .unwrap_or_default();
+                        property["description"] = json!(format!("{base} {summary}").trim());
+                    }
+                }
+            }
+        }
+    }
+
+    async fn fields(&self, pool: &OdooClientPool, instance: &str, model: &str) -> Option<Value> {
+        let key = pool.cache_key(instance);
+        let ttl = cache_ttl();
+        if ttl > 0
+            && let Some(cached) = self.cache.get(&key, model).await
+        {
+            return Some(cached);
+        }
+        let client = pool.get(instance).await.ok()?;
+        let fields = client.fields_get(model, None).await.ok()?;
+        let trimmed: Map<String, Value> = fields
+            .as_object()?
+            .iter()
+            .map(|(name, attrs)| {
+                let keep = ["type", "string", "store", "required"];
+                let attrs: Map<String, Value> = keep
+                    .iter()
+                    .filter_map(|k| attrs.get(*k).map(|v| (k.to_string(), v.clone())))
+                    .collect();
+                (name.clone(), Value::Object(attrs))
+            })
+            .collect();
+        let value = Value::Object(trimmed);
+        if ttl > 0 {
+            self.cache.insert(&key, model, value.clone(), ttl).await;
+        }
+        Some(value)
+    }
+}
+
+/// `Fields of sale.order: name (char, Order Reference, required), ...`.
+///
+/// `only` picks and orders the fields; otherwise stored fields are listed by name, capped at
+/// [`MAX_LISTED_FIELDS`].
+fn summarize(model: &str, fields: &Value, only: &[String]) -> String {
+    let empty = Map::new();
+    let fields = fields.as_object().unwrap_or(&empty);
+    let mut names: Vec<&String> = if only.is_empty() {
+        let mut names: Vec<&String> = fields
+            .iter()
+            .filter(|(name, attrs)| {
+                !name.starts_with("__")
+                    && attrs.get("store").and_then(Value::as_bool) != Some(false)
+            })
+            .map(|(name, _)| name)
+            .collect();
+        names.sort();
+        names
+    } else {
+        only.iter()
+            .filter(|name| fields.contains_key(*name))
+            .collect()
+    };
+    let hidden = names.len().saturating_sub(MAX_LISTED_FIELDS);
+    if only.is_empty() {
+        names.truncate(MAX_LISTED_FIELDS);
+    }
+    let listed: Vec<String> = names
+        .into_iter()
+        .map(|name| {
+            let attrs = &fields[name];
+            let mut parts = Vec::new();
+            if let Some(kind) = attrs.get("type").and_then(Value::as_str) {
+                parts.push(kind.to_string());
+            }
+            if let Some(label) = attrs.get("string").and_then(Value::as_str) {
+                parts.push(label.to_string());
+            }
+            if attrs.get("required").and_then(Value::as_bool) == Some(true) {
+                parts.push("required".to_string());
+            }
+            if parts.is_empty() {
+                name.clone()
+            } else {
+                format!("{name} ({})", parts.join(", "))
+            }
+        })
+        .collect();
+    let mut summary = format!("Fields of {model}: {}", listed.join(", "));
+    if only.is_empty() && hidden > 0 {
+        summary.push_str(&format!(" and {hidden} more"));
+    }
+    summary.push('.');
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(raw: Value) -> ModelToolSpec {
+        serde_json::from_value(raw).unwrap()
+    }
+
+    #[test]
+    fn default_entry_generates_read_tools_with_model_pinned() {
+        let tools = spec(json!({"model": "sale.order"})).expand().unwrap();
+        let names: Vec<&str> = tools.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "search_sale_orders",
+                "count_sale_orders",
+                "read_sale_orders"
+            ]
+        );
+        let search = &tools[0];
+        assert_eq!(search.op.op_type, "search_read");
+        assert_eq!(search.op.pinned["model"], "sale.order");
+        assert_eq!(search.op.map["model"], "/model");
+        assert!(search.input_schema["properties"].get("model").is_none());
+        assert_eq!(search.input_schema["required"], json!(["instance"]));
+        assert!(search.guards.is_none());
+        crate::mcp::arg_validation::check_schema(&search.input_schema).unwrap();
+    }
+
+    #[test]
+    fn slugs_and_write_ops() {
+        let tools = spec(json!({
+            "model": "res.partner",
+            "name": "partner",
+            "plural": "partners",
+            "ops": ["create", "update", "delete"]
+        }))
+        .expand()
+        .unwrap();
+        let names: Vec<&str> = tools.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["create_partner", "update_partners", "delete_partners"]
+        );
+        for tool in &tools {
+            assert_eq!(
+                tool.guards.as_ref().unwrap().requires_env_true.as_deref(),
+                Some(WRITE_GUARD)
+            );
+        }
+        assert_eq!(
+            tools[1].input_schema["required"],
+            json!(["instance", "ids", "values"])
+        );
+    }
+
+    #[test]
+    fn unknown_op_is_rejected() {
+        let error = spec(json!({"model": "res.partner", "ops": ["archive"]}))
+            .expand()
+            .unwrap_err();
+        assert!(error.to_string().contains("archive"));
+        assert!(spec(json!({"model": " "})).expand().is_err());
+    }
+
+    #[test]
+    fn summary_lists_stored_fields_or_the_chosen_ones() {
+        let fields = json!({
+            "name": {"type": "char", "string": "Name", "required": true},
+            "email": {"type": "char", "string": "Email"},
+            "display_name": {"type": "char", "string": "Display Name", "store": false},
+            "__last_update": {"type": "datetime"}
+        });
+        assert_eq!(
+            summarize("res.partner", &fields, &[]),
+            "Fields of res.partner: email (char, Email), name (char, Name, required)."
+        );
+        assert_eq!(
+            summarize(
+                "res.partner",
+                &fields,
+                &["display_name".into(), "missing".into(), "name".into()]
+            ),
+            "Fields of res.partner: display_name (char, Display Name), name (char, Name, required)."
+        );
+    }
+
+    #[test]
+    fn summary_caps_long_field_lists() {
+        let fields: Map<String, Value> = (0..MAX_LISTED_FIELDS + 5)
+            .map(|i| (format!("f{i:03}"), json!({"type": "integer"})))
+            .collect();
+        let summary = summarize("x.y", &Value::Object(fields), &[]);
+        assert!(summary.ends_with(" and 5 more."), "{summary}");
+    }
+}
@@ -0,0 +1,416 @@
+//! Composite tools: a tool whose op is a DAG of read-only sub-queries declared in tools.json.
+//!
+//! Each step names a model and a method; string values in its `domain`, `ids`, `limit` and
+//! `context` may reference tool arguments (`${args.partnerId}`) or earlier results
+//! (`${partner.0.commercial_partner_id.0}`, `${orders.*.id}`). Dependencies are inferred from
+//! those references, and every step runs as soon as the steps it references have finished.
+
+use std::collections::{BTreeSet, HashMap};
+
+use futures::StreamExt;
+use futures::stream::FuturesUnordered;
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+
+use crate::mcp::progress::Progress;
+use crate::odoo::types::{OdooError, OdooResult};
+use crate::odoo::unified_client::OdooClient;
+
+/// Variable name holding the tool arguments.
+const ARGS: &str = "args";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepMethod {
+    SearchRead,
+    Read,
+    SearchCount,
+    ReadGroup,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompositeStep {
+    pub id: String,
+    pub model: String,
+    pub method: StepMethod,
+    #[serde(default)]
+    pub domain: Option<Value>,
+    /// Record ids for `read`: a list, a single id, or a reference resolving to either.
+    #[serde(default)]
+    pub ids: Option<Value>,
+    #[serde(default)]
+    pub fields: Option<Vec<String>>,
+    #[serde(default)]
+    pub groupby: Vec<String>,
+    #[serde(default)]
+    pub limit: Option<Value>,
+    #[serde(default)]
+    pub order: Option<String>,
+    #[serde(default)]
+    pub context: Option<Value>,
+    /// A failing optional step is reported in its slot instead of failing the tool.
+    #[serde(default)]
+    pub optional: bool,
+}
+
+impl CompositeStep {
+    /// Templated parts of the step, substituted together before the call.
+    fn template(&self) -> Value {
+        json!({
+            "domain": self.domain,
+            "ids": self.ids,
+            "limit": self.limit,
+            "context": self.context,
+        })
+    }
+
+    /// Steps (and `args`) this step references.
+    fn dependencies(&self) -> BTreeSet<String> {
+        let mut deps = BTreeSet::new();
+        collect_references(&self.template(), &mut deps);
+        deps
+    }
+}
+
+/// Check step ids are unique, references resolve to earlier-declared or later steps, and the
+/// graph has no cycle. Run when tools.json is loaded.
+pub fn validate_steps(steps: &[CompositeStep]) -> anyhow::Result<()> {
+    if steps.is_empty() {
+        anyhow::bail!("composite op needs at least one step");
+    }
+    let mut deps = HashMap::new();
+    for step in steps {
+        if step.id == ARGS || step.id.contains('.') {
+            anyhow::bail!("invalid step id '{}'", step.id);
+        }
+        if deps.insert(step.id.as_str(), step.dependencies()).is_some() {
+            anyhow::bail!("duplicate step id '{}'", step.id);
+        }
+    }
+    for (id, refs) in &deps {
+        if let Some(unknown) = refs
+            .iter()
+            .find(|r| r.as_str() != ARGS && !deps.contains_key(r.as_str()))
+        {
+            anyhow::bail!("step '{id}' references unknown step '{unknown}'");
+        }
+    }
+    // Kahn's algorithm: whatever cannot be ordered is on a cycle.
+    let mut done: BTreeSet<&str> = BTreeSet::from([ARGS]);
+    while done.len() <= deps.len() {
+        let ready: Vec<&str> = deps
+            .iter()
+            .filter(|(id, refs)| {
+                !done.contains(*id) && refs.iter().all(|r| done.contains(r.as_str()))
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        if ready.is_empty() {
+            let mut stuck: Vec<&str> = deps
+                .keys()
+                .filter(|id| !done.contains(*id))
+                .copied()
+                .collect();
+            stuck.sort_unstable();
+            anyhow::bail!("steps form a cycle: {}", stuck.join(", "));
+        }
+        done.extend(ready);
+    }
+    Ok(())
+}
+
+/// Run the steps against one instance, returning an object keyed by step id.
+///
+/// With `progress`, each finished step is reported as `<done>/<steps>`.
+pub async fn run(
+    client: &OdooClient,
+    steps: &[CompositeStep],
+    args: &Value,
+    progress: Option<&Progress>,
+) -> OdooResult<Value> {
+    let mut vars = Map::new();
+    vars.insert(ARGS.to_string(), args.clone());
+    let mut output = Map::new();
+    let mut failed: BTreeSet<String> = BTreeSet::new();
+    let mut pending: Vec<(&CompositeStep, BTreeSet<String>)> =
+        steps.iter().map(|s| (s, s.dependencies())).collect();
+    let mut running = FuturesUnordered::new();
+
+    loop {
+        let mut waiting = Vec::new();
+        for (step, deps) in pending {
+            if let Some(dep) = deps.iter().find(|d| failed.contains(*d)) {
+                failed.insert(step.id.clone());
+                output.insert(
+                    step.id.clone(),
+                    json!({ "error": format!("skipped: step '{dep}' failed") }),
+                );
+            } else if deps.iter().all(|d| vars.contains_key(d)) {
+                let params = substitute(&step.template(), &vars);
+                running.push(async move {
+                    let result = match params {
+                        Ok(params) => run_step(client, step, params).await,
+                        Err(e) => Err(OdooError::InvalidResponse(e)),
+                    };
+                    (step, result)
+                });
+            } else {
+                waiting.push((step, deps));
+            }
+        }
+        pending = waiting;
+
+        let Some((step, result)) = running.next().await else {
+            // Only reachable for unvalidated steps referencing unknown names.
+            for (step, _) in pending {
+                output.insert(
+                    step.id.clone(),
+                    json!({ "error": "skipped: unresolved dependencies" }),
+                );
+            }
+            break;
+        };
+        match result {
+            Ok(value) => {
+                vars.insert(step.id.clone(), value.clone());
+                output.insert(step.id.clone(), value);
+            }
+            Err(e) if step.optional => {
+                failed.insert(step.id.clone());
+                output.insert(step.id.clone(), json!({ "error": e.to_string() }));
+            }
+            Err(e) => {
+                return Err(OdooError::InvalidResponse(format!(
+                    "Step '{}' failed: {e}",
+                    step.id
+                )));
+            }
+        }
+        if let Some(progress) = progress {
+            let message = format!("Step '{}' finished", step.id);
+            progress.report(
+                output.len() as u64,
+                Some(steps.len() as u64),
+                Some(&message),
+            );
+        }
+    }
+    Ok(Value::Object(output))
+}
+
+async fn run_step(client: &OdooClient, step: &CompositeStep, params: Value) -> OdooResult<Value> {
+    let take = |key: &str| params.get(key).filter(|v| !v.is_null()).cloned();
+    let domain = take("domain");
+    let limit = take("limit").and_then(|v| v.as_i64());
+    let context = take("context");
+    match step.method {
+        StepMethod::SearchRead => {
+            client
+                .search_read(
+                    &step.model,
+                    domain,
+                    step.fields.clone(),
+                    limit,
+                    None,
+                    step.order.clone(),
+                    context,
+                )
+                .await
+        }
+        StepMethod::Read => {
+            let ids = match take("ids") {
+                Some(Value::Array(ids)) => ids.iter().filter_map(Value::as_i64).collect(),
+                Some(id) => id.as_i64().into_iter().collect(),
+                None => Vec::new(),
+            };
+            if ids.is_empty() {
+                return Ok(json!([]));
+            }
+            client
+                .read(&step.model, ids, step.fields.clone(), context)
+                .await
+        }
+        StepMethod::SearchCount => client
+            .search_count(&step.model, domain, context)
+            .await
+            .map(|count| json!(count)),
+        StepMethod::ReadGroup => {
+            client
+                .read_group(
+                    &step.model,
+                    domain,
+                    step.fields.clone().unwrap_or_default(),
+                    step.groupby.clone(),
+                    None,
+                    limit,
+                    step.order.clone(),
+                    Some(false),
+                    context,
+                )
+                .await
+        }
+    }
+}
+
+/// `${...}` references in string values, in order of appearance.
+fn references(text: &str) -> impl Iterator<Item = &str> {
+    text.split("${")
+        .skip(1)
+        .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+}
+
+fn collect_references(value: &Value, out: &mut BTreeSet<String>) {
+    match value {
+        Value::String(text) => {
+            for reference in references(text) {
+                let root = reference.split('.').next().unwrap_or_default();
+                out.insert(root.to_string());
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|v| collect_references(v, out)),
+        Value::Object(map) => map.values().for_each(|v| collect_references(v, out)),
+        _ => {}
+    }
+}
+
+/// Follow a dotted path: object keys, array indexes, and `*` to map over an array.
+fn lookup(value: &Value, path: &[&str]) -> Option<Value> {
+    let Some((segment, rest)) = path.split_first() else {
+        return Some(value.clone());
+    };
+    if *segment == "*" {
+        let items = value.as_array()?;
+        return Some(Value::Array(
+            items.iter().filter_map(|item| lookup(item, rest)).collect(),
+        ));
+    }
+    let next = match value {
+        Value::Object(map) => map.get(*segment),
+        Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+        _ => None,
+    }?;
+    lookup(next, rest)
+}
+
+fn resolve(reference: &str, vars: &Map<String, Value>) -> Result<Value, String> {
+    let path: Vec<&str> = reference.split('.').collect();
+    vars.get(path[0])
+        .and_then(|root| lookup(root, &path[1..]))
+        .filter(|v| !v.is_null())
+        .ok_or_else(|| format!("Unresolved variable ${{{reference}}}"))
+}
+
+/// Replace references in `template`. A string that is exactly one reference takes the
+/// referenced JSON value (number, list, ...); otherwise values are spliced in as text.
+fn substitute(template: &Value, vars: &Map<String, Value>) -> Result<Value, String> {
+    match template {
+        Value::String(text) => {
+            if let Some(reference) = text
+                .strip_prefix("${")
+                .and_then(|rest| rest.strip_suffix('}'))
+                .filter(|r| !r.contains("${"))
+            {
+                return resolve(reference, vars);
+            }
+            let mut out = String::new();
+            let mut rest = text.as_str();
+            while let Some(start) = rest.find("${") {
+                let Some(len) = rest[start..].find('}') else {
+                    break;
+                };
+                out.push_str(&rest[..start]);
+                match resolve(&rest[start + 2..start + len], vars)? {
+                    Value::String(s) => out.push_str(&s),
+                    other => out.push_str(&other.to_string()),
+                }
+                rest = &rest[start + len + 1..];
+            }
+            out.push_str(rest);
+            Ok(Value::String(out))
+        }
+        Value::Array(items) => items.iter().map(|v| substitute(v, vars)).collect(),
+        Value::Object(map) => map
+            .iter()
+            .map(|(k, v)| Ok((k.clone(), substitute(v, vars)?)))
+            .collect::<Result<Map<_, _>, String>>()
+            .map(Value::Object),
+        other => Ok(other.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(value: Value) -> CompositeStep {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn vars() -> Map<String, Value> {
+        let mut vars = Map::new();
+        vars.insert("args".into(), json!({"partnerId": 7, "name": "Acme"}));
+        vars.insert(
+            "orders".into(),
+            json!([
+                {"id": 11, "partner_id": [3, "Acme"]},
+                {"id": 12, "partner_id": [3, "Acme"]}
+            ]),
+        );
+        vars
+    }
+
+    #[test]
+    fn substitute_keeps_json_types_and_splices_text() {
+        let template = json!({
+            "domain": [
+                ["order_id", "in", "${orders.*.id}"],
+                ["partner_id", "=", "${orders.0.partner_id.0}"],
+                ["name", "ilike", "${args.name} Corp"]
+            ],
+            "limit": 5
+        });
+        assert_eq!(
+            substitute(&template, &vars()).unwrap(),
+            json!({
+                "domain": [
+                    ["order_id", "in", [11, 12]],
+                    ["partner_id", "=", 3],
+                    ["name", "ilike", "Acme Corp"]
+                ],
+                "limit": 5
+            })
+        );
+        let err = substitute(&json!("${args.missing}"), &vars()).unwrap_err();
+        assert!(err.contains("args.missing"), "{err}");
+    }
+
+    #[test]
+    fn dependencies_come_from_references() {
+        let lines = step(json!({
+            "id": "lines", "model": "sale.order.line", "method": "search_read",
+            "domain": [["order_id", "in", "${orders.*.id}"]],
+            "context": {"lang": "${args.lang}"}
+        }));
+        assert_eq!(
+            lines.dependencies(),
+            BTreeSet::from(["args".to_string(), "orders".to_string()])
+        );
+    }
+
+    #[test]
+    fn validate_steps_rejects_unknown_references_and_cycles() {
+        let a = json!({"id": "a", "model": "res.partner", "method": "read", "ids": "${b.0.id}"});
+        let b = json!({"id": "b", "model": "res.partner", "method": "read", "ids": "${a.0.id}"});
+        let root =
+            json!({"id": "root", "model": "res.partner", "method": "read", "ids": "${args.id}"});
+
+        assert!(validate_steps(&[step(root.clone()), step(a.clone())]).is_err());
+        let err = validate_steps(&[step(a), step(b), step(root.clone())])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("cycle: a, b"), "{err}");
+        assert!(validate_steps(&[step(root.clone()), step(root)]).is_err());
+        assert!(validate_steps(&[]).is_err());
+    }
+}
@@ -0,0 +1,140 @@
+//! `notifications/progress` for long-running tool calls.
+//!
+//! A client opts in by sending `_meta.progressToken` with `tools/call`. Notifications go to the
+//! sink of the current request when the transport installed one with [`with_sink`] (the SSE
+//! response of `POST /mcp`), otherwise to the client's outbound channel (stdout for stdio, the
+//! session's `GET /mcp` stream for HTTP).
+
+use std::future::Future;
+
+use serde_json::{Map, Value, json};
+use tokio::sync::mpsc;
+
+use crate::mcp::sampling::ClientRequests;
+
+tokio::task_local! {
+    static REQUEST_SINK: mpsc::UnboundedSender<Value>;
+}
+
+/// Run `fut` with `sink` receiving the server-to-client messages of the request it serves.
+pub async fn with_sink<F: Future>(sink: mpsc::UnboundedSender<Value>, fut: F) -> F::Output {
+    REQUEST_SINK.scope(sink, fut).await
+}
+
+/// The `progressToken` of a request's params, if the client asked for progress.
+pub fn token(params: &Value) -> Option<Value> {
+    params
+        .pointer("/_meta/progressToken")
+        .filter(|token| token.is_string() || token.is_number())
+        .cloned()
+}
+
+#[derive(Clone)]
+enum Sink {
+    Request(mpsc::UnboundedSender<Value>),
+    Client {
+        requests: ClientRequests,
+        scope: String,
+    },
+}
+
+/// Reports progress of one request under the client's token.
+#[derive(Clone)]
+pub struct Progress {
+    token: Value,
+    sink: Sink,
+}
+
+impl Progress {
+    /// A reporter for a request with `params`, or `None` when the client sent no token.
+    pub fn for_request(params: &Value, requests: &ClientRequests, scope: &str) -> Option<Self> {
+        let token = token(params)?;
+        let sink = match REQUEST_SINK.try_with(Clone::clone) {
+            Ok(sink) => Sink::Request(sink),
+            Err(_) => Sink::Client {
+                requests: requests.clone(),
+                scope: scope.to_string(),
+            },
+        };
+        Some(Self { token, sink })
+    }
+
+    /// Send a progress notification; a client that went away is not an error.
+    pub fn report(&self, progress: u64, total: Option<u64>, message: Option<&str>) {
+        self.report_with(progress, total, message, Map::new());
+    }
+
+    /// [`Progress::report`] with extra params, such as a batch of partial results.
+    pub fn report_with(
+        &self,
+        progress: u64,
+        total: Option<u64>,
+        message: Option<&str>,
+        extra: Map<String, Value>,
+    ) {
+        let mut params = json!({ "progressToken": self.token, "progress": progress });
+        if let Some(total) = total {
+            params["total"] = json!(total);
+        }
+        if let Some(message) = message {
+            params["message"] = json!(message);
+        }
+        if let Value::Object(map) = &mut params {
+            map.extend(extra);
+        }
+        match &self.sink {
+            Sink::Request(sink) => {
+                let _ = sink.send(json!({
+                    "jsonrpc": "2.0",
+                    "method": "notifications/progress",
+                    "params": params,
+                }));
+            }
+            Sink::Client { requests, scope } => {
+                requests.notify(scope, "notifications/progress", params);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn reports_go_to_the_request_sink_when_one_is_installed() {
+        let requests = ClientRequests::new();
+        let params = json!({"name": "x", "_meta": {"progressToken": "p1"}});
+        assert!(Progress::for_request(&json!({"name": "x"}), &requests, "s").is_none());
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let progress = with_sink(tx, async { Progress::for_request(&params, &requests, "s") })
+            .await
+            .unwrap();
+        progress.report(1, Some(3), Some("Step 'a' finished"));
+        assert_eq!(
+            rx.recv().await.unwrap(),
+            json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/progress",
+                "params": {
+                    "progressToken": "p1",
+                    "progress": 1,
+                    "total": 3,
+                    "message": "Step 'a' finished"
+                }
+            })
+        );
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        requests.attach("s", tx);
+        Progress::for_request(&params, &requests, "s")
+            .unwrap()
+            .report(2, None, None);
+        let sent = rx.recv().await.unwrap();
+        assert_eq!(
+            sent["params"],
+            json!({"progressToken": "p1", "progress": 2})
+        );
+    }
+}
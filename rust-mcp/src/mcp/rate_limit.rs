@@ -0,0 +1,320 @@
+//! Token-bucket rate limiting for the HTTP transport.
+//!
+//! Requests are charged against a bucket per authenticated token (`MCP_RATE_LIMIT_PER_TOKEN`)
+//! and a bucket per client IP (`MCP_RATE_LIMIT_PER_IP`), both in requests per minute. A bucket
+//! holds `MCP_RATE_LIMIT_BURST` requests (default: one minute's worth) and refills continuously.
+//! A JSON-RPC batch costs one request per element. An empty bucket answers 429 with
+//! `Retry-After`.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::Json;
+use axum::body::{Body, to_bytes};
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::{HeaderValue, StatusCode, header};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use serde_json::{Value, json};
+use tracing::{debug, info};
+
+use crate::mcp::auth_tokens::LEGACY_TOKEN_NAME;
+use crate::mcp::http::AuthConfig;
+
+/// Buckets kept before idle, fully refilled ones are pruned.
+const MAX_BUCKETS: usize = 10_000;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quota {
+    pub per_minute: u32,
+    pub burst: u32,
+}
+
+impl Quota {
+    fn refill_per_sec(&self) -> f64 {
+        f64::from(self.per_minute) / 60.0
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RateLimitConfig {
+    pub per_token: Option<Quota>,
+    pub per_ip: Option<Quota>,
+}
+
+impl RateLimitConfig {
+    pub fn from_env() -> Self {
+        let burst = env_u32("MCP_RATE_LIMIT_BURST");
+        let quota = |name: &str| {
+            env_u32(name).map(|per_minute| Quota {
+                per_minute,
+                burst: burst.unwrap_or(per_minute).max(1),
+            })
+        };
+        let config = Self {
+            per_token: quota("MCP_RATE_LIMIT_PER_TOKEN"),
+            per_ip: quota("MCP_RATE_LIMIT_PER_IP"),
+        };
+        if config.is_enabled() {
+            info!(
+                "MCP rate limiting enabled: per token {:?}, per IP {:?}",
+                config.per_token, config.per_ip
+            );
+        } else {
+            debug!(
+                "MCP rate limiting disabled (set MCP_RATE_LIMIT_PER_TOKEN / MCP_RATE_LIMIT_PER_IP)"
+            );
+        }
+        config
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.per_token.is_some() || self.per_ip.is_some()
+    }
+}
+
+/// Unset, unparsable and zero values all mean "no limit".
+fn env_u32(name: &str) -> Option<u32> {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.trim().parse::<u32>().ok())
+        .filter(|v| *v > 0)
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    quota: Quota,
+}
+
+impl Bucket {
+    fn is_full(&self, now: Instant) -> bool {
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        self.tokens + elapsed * self.quota.refill_per_sec() >= f64::from(self.quota.burst)
+    }
+}
+
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Take one request from the `key` bucket, or return how long until one is available.
+    pub fn check(&self, key: &str, quota: Quota, now: Instant) -> Result<(), Duration> {
+        self.check_n(key, quota, 1, now)
+    }
+
+    /// Take `cost` requests from the `key` bucket at once, or none and return how long until
+    /// they are available. A cost above the burst never fits; callers reject it up front.
+    pub fn check_n(
+        &self,
+        key: &str,
+        quota: Quota,
+        cost: u32,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        let cost = f64::from(cost);
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        if buckets.len() >= MAX_BUCKETS && !buckets.contains_key(key) {
+            // A refilled bucket is indistinguishable from a new one, so dropping it is lossless.
+            buckets.retain(|_, b| !b.is_full(now));
+        }
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+            tokens: f64::from(quota.burst),
+            updated: now,
+            quota,
+        });
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens =
+            (bucket.tokens + elapsed * quota.refill_per_sec()).min(f64::from(quota.burst));
+        bucket.updated = now;
+        if bucket.tokens >= cost {
+            bucket.tokens -= cost;
+            Ok(())
+        } else {
+            let wait = (cost - bucket.tokens) / quota.refill_per_sec();
+            Err(Duration::from_secs_f64(wait))
+        }
+    }
+}
+
+/// Name of the token the request presents, if it is a known one.
+///
+/// Unknown tokens are left to authentication to reject; they only count against the IP bucket.
+async fn token_name(token: Option<String>, auth: &AuthConfig) -> Option<String> {
+    let token = token?;
+    let data = auth.get().await;
+    if data.bearer_token.as_deref() == Some(token.as_str()) {
+        return Some(LEGACY_TOKEN_NAME.to_string());
+    }
+    data.tokens
+        .iter()
+        .find(|t| t.token == token)
+        .map(|t| t.name.clone())
+}
+
+fn too_many_requests(retry_after: Duration) -> Response {
+    let secs = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+    let mut response = (
+        StatusCode::TOO_MANY_REQUESTS,
+        Json(json!({
+            "error": "rate_limited",
+            "error_description": format!("Rate limit exceeded; retry in {secs}s")
+        })),
+    )
+        .into_response();
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, HeaderValue::from(secs));
+    response
+}
+
+fn batch_too_large(cost: u32, burst: u32) -> Response {
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        Json(json!({
+            "error": "rate_limited",
+            "error_description": format!(
+                "Batch of {cost} requests exceeds the rate limit burst of {burst}"
+            )
+        })),
+    )
+        .into_response()
+}
+
+/// Number of JSON-RPC messages in `body`: the length of a batch, otherwise 1.
+fn message_count(body: &[u8]) -> u32 {
+    match serde_json::from_slice::<Value>(body) {
+        Ok(Value::Array(items)) => u32::try_from(items.len()).unwrap_or(u32::MAX).max(1),
+        _ => 1,
+    }
+}
+
+/// Charge `cost` against the `key` bucket, rejecting costs that can never fit.
+fn charge(
+    limiter: &RateLimiter,
+    key: &str,
+    quota: Quota,
+    cost: u32,
+    now: Instant,
+) -> Option<Response> {
+    if cost > quota.burst {
+        return Some(batch_too_large(cost, quota.burst));
+    }
+    limiter
+        .check_n(key, quota, cost, now)
+        .err()
+        .map(too_many_requests)
+}
+
+/// Axum middleware charging each request against its token and IP buckets, one request per
+/// JSON-RPC message so batches cannot bypass the limits.
+pub async fn rate_limit_layer(
+    State((limiter, auth, limit)): State<(Arc<RateLimiter>, AuthConfig, usize)>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let (parts, body) = request.into_parts();
+    let bytes = match to_bytes(body, limit).await {
+        Ok(bytes) => bytes,
+        Err(_) => return StatusCode::PAYLOAD_TOO_LARGE.into_response(),
+    };
+    let cost = message_count(&bytes);
+    let request = Request::from_parts(parts, Body::from(bytes));
+
+    let now = Instant::now();
+    if let Some(quota) = limiter.config.per_ip
+        && let Some(ConnectInfo(addr)) = request.extensions().get::<ConnectInfo<SocketAddr>>()
+        && let Some(rejected) = charge(&limiter, &format!("ip:{}", addr.ip()), quota, cost, now)
+    {
+        return rejected;
+    }
+    // Read the header up front: `&Request` is not `Send` across the await below.
+    let bearer = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::to_string);
+    if let Some(quota) = limiter.config.per_token
+        && let Some(name) = token_name(bearer, &auth).await
+        && let Some(rejected) = charge(&limiter, &format!("token:{name}"), quota, cost, now)
+    {
+        return rejected;
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quota(per_minute: u32, burst: u32) -> Quota {
+        Quota { per_minute, burst }
+    }
+
+    #[test]
+    fn bucket_allows_burst_then_refills() {
+        let limiter = RateLimiter::new(RateLimitConfig::default());
+        let start = Instant::now();
+        let q = quota(60, 2);
+        assert!(limiter.check("a", q, start).is_ok());
+        assert!(limiter.check("a", q, start).is_ok());
+        let wait = limiter.check("a", q, start).unwrap_err();
+        assert!(wait <= Duration::from_secs(1) && wait > Duration::from_millis(900));
+
+        // Other keys have their own bucket.
+        assert!(limiter.check("b", q, start).is_ok());
+
+        // One request per second refills.
+        assert!(
+            limiter
+                .check("a", q, start + Duration::from_secs(1))
+                .is_ok()
+        );
+        assert!(
+            limiter
+                .check("a", q, start + Duration::from_secs(1))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn batches_take_one_request_per_element() {
+        let limiter = RateLimiter::new(RateLimitConfig::default());
+        let start = Instant::now();
+        let q = quota(60, 5);
+        assert_eq!(message_count(br#"[{"id":1},{"id":2},{"id":3}]"#), 3);
+        assert_eq!(message_count(br#"{"id":1}"#), 1);
+        assert_eq!(message_count(b""), 1);
+
+        assert!(limiter.check_n("a", q, 3, start).is_ok());
+        // Two left: a batch of three is refused whole and takes nothing.
+        let wait = limiter.check_n("a", q, 3, start).unwrap_err();
+        assert!(wait > Duration::from_millis(900));
+        assert!(limiter.check_n("a", q, 2, start).is_ok());
+
+        let rejected = charge(&limiter, "b", q, 6, start).unwrap();
+        assert_eq!(rejected.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(rejected.headers().get(header::RETRY_AFTER).is_none());
+    }
+
+    #[test]
+    fn retry_after_is_rounded_up() {
+        let response = too_many_requests(Duration::from_millis(1500));
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "2");
+    }
+}
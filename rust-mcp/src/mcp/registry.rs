@@ -3,13 +3,20 @@ use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
-use serde::Deserialize;
-use serde_json::Value;
-use tokio::sync::RwLock;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tokio::sync::{RwLock, broadcast};
 use tracing::{debug, info, warn};
 
+use crate::mcp::arg_validation::check_schema;
+use crate::mcp::model_tools::{DescribedTool, ModelToolSpec};
 use crate::mcp::module_snapshot::ModuleSnapshot;
+use crate::mcp::orchestrator::{CompositeStep, validate_steps};
 use crate::mcp::prompts::Prompt;
+use crate::mcp::renderers;
+use crate::mcp::retention::RetentionPolicy;
+use crate::mcp::tls::TlsSettings;
+use crate::odoo::config::InstanceToolConfig;
 
 // Embedded seed defaults (used when target files are missing).
 const DEFAULT_TOOLS_JSON: &str = include_str!("../../config-defaults/tools.json");
@@ -19,6 +26,9 @@ const DEFAULT_SERVER_JSON: &str = include_str!("../../config-defaults/server.jso
 #[derive(Debug, Clone, Deserialize)]
 struct ToolsConfigFile {
     tools: Vec<ToolDef>,
+    /// Models to generate specialized tools for (see [`crate::mcp::model_tools`]).
+    #[serde(default, rename = "modelTools")]
+    model_tools: Vec<ModelToolSpec>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -27,12 +37,18 @@ struct PromptsConfigFile {
 }
 
 #[derive(Debug, Clone, Deserialize)]
-struct ServerConfigFile {
+pub(crate) struct ServerConfigFile {
     #[serde(rename = "serverName")]
     server_name: String,
     instructions: String,
     #[serde(rename = "protocolVersionDefault")]
     protocol_version_default: Option<String>,
+    /// HTTPS settings; `MCP_TLS_*` env vars take precedence.
+    #[serde(default)]
+    pub(crate) tls: Option<TlsSettings>,
+    /// Retention limits for server-side stores; `MCP_RETENTION_*` env vars take precedence.
+    #[serde(default)]
+    retention: RetentionPolicy,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -48,6 +64,64 @@ pub struct ToolDef {
     pub op: OpSpec,
     #[serde(default)]
     pub guards: Option<ToolGuards>,
+    /// Explicit MCP annotations; unset hints are derived from the op type.
+    #[serde(default)]
+    pub annotations: ToolAnnotations,
+    /// Fields of the pinned `model` to list in the schema descriptions when tools are listed;
+    /// an empty list names stored fields.
+    #[serde(default, rename = "describeFields")]
+    pub describe_fields: Option<Vec<String>>,
+}
+
+/// MCP tool annotations (behaviour hints clients use to gate confirmation prompts).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolAnnotations {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_only_hint: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destructive_hint: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotent_hint: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub open_world_hint: Option<bool>,
+}
+
+impl ToolAnnotations {
+    /// Hints implied by an op type: non-mutating ops are read-only, deletes and cleanups are
+    /// destructive, and only ops that converge on the same state when repeated are idempotent.
+    pub fn for_op(op_type: &str) -> Self {
+        if !is_mutating_op(op_type) {
+            return Self {
+                read_only_hint: Some(true),
+                ..Self::default()
+            };
+        }
+        Self {
+            read_only_hint: Some(false),
+            destructive_hint: Some(is_destructive_op(op_type)),
+            idempotent_hint: Some(matches!(op_type, "write" | "unlink")),
+            open_world_hint: None,
+        }
+    }
+
+    fn or(self, fallback: Self) -> Self {
+        Self {
+            read_only_hint: self.read_only_hint.or(fallback.read_only_hint),
+            destructive_hint: self.destructive_hint.or(fallback.destructive_hint),
+            idempotent_hint: self.idempotent_hint.or(fallback.idempotent_hint),
+            open_world_hint: self.open_world_hint.or(fallback.open_world_hint),
+        }
+    }
+}
+
+impl ToolDef {
+    /// Annotations advertised in `tools/list`: explicit values win over derived ones.
+    pub fn effective_annotations(&self) -> ToolAnnotations {
+        self.annotations
+            .clone()
+            .or(ToolAnnotations::for_op(&self.op.op_type))
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -56,6 +130,12 @@ pub struct OpSpec {
     pub op_type: String,
     #[serde(default)]
     pub map: HashMap<String, String>,
+    /// Sub-queries of a `composite` op.
+    #[serde(default)]
+    pub steps: Vec<CompositeStep>,
+    /// Arguments fixed by the tool itself (e.g. `model`); they override the caller's.
+    #[serde(default)]
+    pub pinned: Map<String, Value>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -71,6 +151,8 @@ pub struct ToolCapabilityContext {
     pub instance: String,
     pub snapshot: ModuleSnapshot,
     pub disabled_packs: Vec<String>,
+    /// Per-instance `allowedTools`/`disabledTools`; only set for lists scoped to one instance.
+    pub tool_config: Option<InstanceToolConfig>,
 }
 
 #[derive(Debug, Clone)]
@@ -80,6 +162,9 @@ struct RegistryState {
     prompts_by_name: HashMap<String, Prompt>,
     prompt_order: Vec<String>,
     server: ServerConfigFile,
+    /// Parsed tools.json / prompts.json as last loaded; `None` before the first load.
+    tools_fingerprint: Option<Value>,
+    prompts_fingerprint: Option<Value>,
 }
 
 impl RegistryState {
@@ -93,7 +178,28 @@ impl RegistryState {
                 server_name: "odoo-rust-mcp".to_string(),
                 instructions: "Odoo MCP server".to_string(),
                 protocol_version_default: Some("2025-11-05".to_string()),
+                tls: None,
+                retention: RetentionPolicy::default(),
             },
+            tools_fingerprint: None,
+            prompts_fingerprint: None,
+        }
+    }
+}
+
+/// A tool or prompt list that changed on reload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListChanged {
+    Tools,
+    Prompts,
+}
+
+impl ListChanged {
+    /// The MCP notification clients expect for this change.
+    pub fn notification_method(self) -> &'static str {
+        match self {
+            ListChanged::Tools => "notifications/tools/list_changed",
+            ListChanged::Prompts => "notifications/prompts/list_changed",
         }
     }
 }
@@ -104,6 +210,7 @@ pub struct Registry {
     server_path: PathBuf,
     state: RwLock<RegistryState>,
     watchers: Mutex<Option<WatchGuards>>,
+    changes: broadcast::Sender<ListChanged>,
 }
 
 struct WatchGuards {
@@ -119,15 +226,29 @@ impl Registry {
         let server_path =
             std::env::var("MCP_SERVER_JSON").unwrap_or_else(|_| "config/server.json".to_string());
 
+        Self::from_paths(tools_path, prompts_path, server_path)
+    }
+
+    pub fn from_paths(
+        tools_path: impl Into<PathBuf>,
+        prompts_path: impl Into<PathBuf>,
+        server_path: impl Into<PathBuf>,
+    ) -> Self {
         Self {
-            tools_path: PathBuf::from(tools_path),
-            prompts_path: PathBuf::from(prompts_path),
-            server_path: PathBuf::from(server_path),
+            tools_path: tools_path.into(),
+            prompts_path: prompts_path.into(),
+            server_path: server_path.into(),
             state: RwLock::new(RegistryState::empty()),
             watchers: Mutex::new(None),
+            changes: broadcast::channel(16).0,
         }
     }
 
+    /// Receive a [`ListChanged`] whenever a reload changes tools.json or prompts.json.
+    pub fn subscribe_changes(&self) -> broadcast::Receiver<ListChanged> {
+        self.changes.subscribe()
+    }
+
     /// Ensure JSON files exist (seed defaults on first start), then load into memory.
     pub async fn initial_load(&self) -> anyhow::Result<()> {
         self.ensure_default_files_exist()?;
@@ -206,6 +327,16 @@ impl Registry {
         self.state.read().await.server.instructions.clone()
     }
 
+    pub async fn tls_settings(&self) -> Option<TlsSettings> {
+        self.state.read().await.server.tls.clone()
+    }
+
+    /// Retention policy from server.json with env overrides applied.
+    pub async fn retention(&self) -> RetentionPolicy {
+        let policy = self.state.read().await.server.retention.clone();
+        policy.with_env_overrides()
+    }
+
     pub async fn protocol_version_default(&self) -> String {
         self.state
             .read()
@@ -220,6 +351,7 @@ impl Registry {
         &self,
         read_only: bool,
         capabilities: &[ToolCapabilityContext],
+        instance_names: &[String],
     ) -> Vec<Value> {
         let st = self.state.read().await;
         st.tools
@@ -259,6 +391,15 @@ impl Registry {
                             denial = Some(reason);
                             break;
                         }
+                        if capability
+                            .tool_config
+                            .as_ref()
+                            .is_some_and(|config| config.is_tool_disabled(&tool.name))
+                        {
+                            denial_instance = &capability.instance;
+                            denial = Some(("tool_disabled", String::new()));
+                            break;
+                        }
                     }
                 }
                 if let Some((reason, detail)) = denial {
@@ -272,7 +413,11 @@ impl Registry {
                 serde_json::json!({
                     "name": t.name,
                     "description": t.description,
-                    "inputSchema": t.input_schema
+                    "inputSchema": renderers::with_format_arg(&with_instance_enum(
+                        &t.input_schema,
+                        instance_names
+                    )),
+                    "annotations": t.effective_annotations()
                 })
             })
             .collect()
@@ -289,6 +434,21 @@ impl Registry {
         }
     }
 
+    /// Tools whose listed schemas name the fields of their pinned model.
+    pub async fn described_tools(&self) -> Vec<DescribedTool> {
+        let st = self.state.read().await;
+        st.tools
+            .iter()
+            .filter_map(|tool| {
+                Some(DescribedTool {
+                    name: tool.name.clone(),
+                    model: tool.op.pinned.get("model")?.as_str()?.to_string(),
+                    fields: tool.describe_fields.clone()?,
+                })
+            })
+            .collect()
+    }
+
     pub async fn list_prompts(&self) -> Vec<(String, String)> {
         let st = self.state.read().await;
         st.prompt_order
@@ -316,9 +476,7 @@ impl Registry {
         // Validate and build maps.
         let mut tool_by_name = HashMap::new();
         for t in &tools {
-            validate_cursor_schema(&t.input_schema).map_err(|e| {
-                anyhow::anyhow!("tools.json tool '{}' has invalid inputSchema: {e}", t.name)
-            })?;
+            validate_tool(t)?;
             if tool_by_name.insert(t.name.clone(), t.clone()).is_some() {
                 return Err(anyhow::anyhow!(
                     "Duplicate tool name in tools.json: {}",
@@ -339,12 +497,30 @@ impl Registry {
             prompt_order.push(p.name.clone());
         }
 
+        let tools_fingerprint = file_fingerprint(&self.tools_path);
+        let prompts_fingerprint = file_fingerprint(&self.prompts_path);
+
         let mut st = self.state.write().await;
+        let mut changed = Vec::new();
+        if st.tools_fingerprint.is_some() && st.tools_fingerprint != tools_fingerprint {
+            changed.push(ListChanged::Tools);
+        }
+        if st.prompts_fingerprint.is_some() && st.prompts_fingerprint != prompts_fingerprint {
+            changed.push(ListChanged::Prompts);
+        }
         st.tools = tools;
         st.tool_by_name = tool_by_name;
         st.prompts_by_name = prompts_by_name;
         st.prompt_order = prompt_order;
         st.server = server;
+        st.tools_fingerprint = tools_fingerprint;
+        st.prompts_fingerprint = prompts_fingerprint;
+        drop(st);
+
+        for change in changed {
+            // No receivers simply means no transport is listening yet.
+            let _ = self.changes.send(change);
+        }
 
         info!(path = %self.tools_path.display(), "tools config loaded");
         info!(path = %self.prompts_path.display(), "prompts config loaded");
@@ -360,7 +536,7 @@ impl Registry {
     }
 }
 
-fn is_mutating_op(op_type: &str) -> bool {
+pub(crate) fn is_mutating_op(op_type: &str) -> bool {
     matches!(
         op_type,
         "create"
@@ -373,7 +549,53 @@ fn is_mutating_op(op_type: &str) -> bool {
             | "stock_inventory_reversal_cleanup"
             | "copy"
             | "create_batch"
+            | "update_batch"
+            | "upsert"
+            | "xmlid_assign"
             | "execute_capability"
+            | "helpdesk_create_ticket"
+            | "helpdesk_assign_ticket"
+            | "helpdesk_reply"
+            | "attachment_upload"
+            | "message_post"
+            | "activity_schedule"
+            | "send_email"
+            | "import_records"
+            | "documents_upload"
+            | "documents_request_signature"
+            | "subscription_renew_invoice"
+            | "maintenance_request_create"
+            | "quality_check_create"
+            | "quality_alert_create"
+            | "fleet_log_odometer"
+            | "fleet_create_service"
+            | "event_register"
+            | "delivery_send_to_shipper"
+            | "bank_reconcile_confirm"
+            | "user_create"
+            | "user_reset_password"
+            | "user_set_active"
+            | "user_groups"
+            | "module_update_list"
+            | "module_install"
+            | "module_upgrade"
+            | "module_uninstall"
+            | "settings_apply"
+            | "cron_control"
+            | "server_action_run"
+            | "automation_set_active"
+    )
+}
+
+fn is_destructive_op(op_type: &str) -> bool {
+    matches!(
+        op_type,
+        "unlink"
+            | "database_cleanup"
+            | "deep_cleanup"
+            | "stock_inventory_reversal_cleanup"
+            | "module_uninstall"
+            | "server_action_run"
     )
 }
 
@@ -428,7 +650,7 @@ pub(crate) fn audit_tool_denial(instance: &str, tool: &ToolDef, reason_code: &st
     );
 }
 
-fn env_truthy(var: &str) -> bool {
+pub(crate) fn env_truthy(var: &str) -> bool {
     match std::env::var(var) {
         Ok(v) => {
             let s = v.trim().to_ascii_lowercase();
@@ -462,21 +684,68 @@ fn ensure_file_exists_with_seed(path: &Path, seed_contents: &str) -> anyhow::Res
     Ok(())
 }
 
-fn load_tools_file(path: &Path) -> anyhow::Result<Vec<ToolDef>> {
+/// `schema` with its string `instance` property restricted to `names`, so clients can offer a
+/// picker. Only the advertised schema changes: calls are validated against the file schema, so
+/// aliases and case-insensitive names keep working.
+fn with_instance_enum(schema: &Value, names: &[String]) -> Value {
+    let mut schema = schema.clone();
+    if names.is_empty() {
+        return schema;
+    }
+    if let Some(instance) = schema.pointer_mut("/properties/instance")
+        && instance.get("type").and_then(Value::as_str) == Some("string")
+        && instance.get("enum").is_none()
+    {
+        let mut names = names.to_vec();
+        names.sort();
+        names.dedup();
+        instance["enum"] = serde_json::json!(names);
+    }
+    schema
+}
+
+/// Schema and composite-step checks `reload` applies to every tool.
+pub(crate) fn validate_tool(t: &ToolDef) -> anyhow::Result<()> {
+    validate_cursor_schema(&t.input_schema)
+        .and_then(|()| check_schema(&t.input_schema).map_err(anyhow::Error::msg))
+        .map_err(|e| {
+            anyhow::anyhow!("tools.json tool '{}' has invalid inputSchema: {e}", t.name)
+        })?;
+    if t.op.op_type == "composite" {
+        validate_steps(&t.op.steps)
+            .map_err(|e| anyhow::anyhow!("tools.json tool '{}' has invalid steps: {e}", t.name))?;
+    }
+    Ok(())
+}
+
+pub(crate) fn load_tools_file(path: &Path) -> anyhow::Result<Vec<ToolDef>> {
     let raw = std::fs::read_to_string(path).map_err(|e| anyhow::anyhow!(e))?;
     let parsed: ToolsConfigFile =
         serde_json::from_str(&raw).map_err(|e| anyhow::anyhow!("Invalid tools.json: {e}"))?;
-    Ok(parsed.tools)
+    let mut tools = parsed.tools;
+    for spec in &parsed.model_tools {
+        tools.extend(
+            spec.expand()
+                .map_err(|e| anyhow::anyhow!("Invalid tools.json: {e}"))?,
+        );
+    }
+    Ok(tools)
 }
 
-fn load_prompts_file(path: &Path) -> anyhow::Result<Vec<Prompt>> {
+pub(crate) fn load_prompts_file(path: &Path) -> anyhow::Result<Vec<Prompt>> {
     let raw = std::fs::read_to_string(path).map_err(|e| anyhow::anyhow!(e))?;
     let parsed: PromptsConfigFile =
         serde_json::from_str(&raw).map_err(|e| anyhow::anyhow!("Invalid prompts.json: {e}"))?;
     Ok(parsed.prompts)
 }
 
-fn load_server_file(path: &Path) -> anyhow::Result<ServerConfigFile> {
+/// Whitespace-insensitive snapshot of a config file used to detect real changes.
+fn file_fingerprint(path: &Path) -> Option<Value> {
+    let raw = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+pub(crate) fn load_server_file(path: &Path) -> anyhow::Result<ServerConfigFile> {
     let raw = std::fs::read_to_string(path).map_err(|e| anyhow::anyhow!(e))?;
     let parsed: ServerConfigFile =
         serde_json::from_str(&raw).map_err(|e| anyhow::anyhow!("Invalid server.json: {e}"))?;
@@ -591,6 +860,7 @@ mod tests {
             server_path: "server.json".into(),
             state: RwLock::new(state),
             watchers: Mutex::new(None),
+            changes: broadcast::channel(16).0,
         };
         let missing = ModuleSnapshot {
             instance: "dev".into(),
@@ -603,7 +873,7 @@ mod tests {
             last_error: None,
         };
 
-        assert_eq!(registry.list_tools(false, &[]).await.len(), 1);
+        assert_eq!(registry.list_tools(false, &[], &[]).await.len(), 1);
         assert!(
             registry
                 .list_tools(
@@ -612,13 +882,65 @@ mod tests {
                         instance: "dev".into(),
                         snapshot: missing,
                         disabled_packs: Vec::new(),
+                        tool_config: None,
                     }],
+                    &[],
                 )
                 .await
                 .is_empty()
         );
     }
 
+    #[test]
+    fn instance_enum_lists_configured_instances() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "instance": {"type": "string"},
+                "model": {"type": "string"}
+            }
+        });
+        let names = vec!["staging".to_string(), "prod".to_string()];
+        let listed = with_instance_enum(&schema, &names);
+        assert_eq!(
+            listed["properties"]["instance"]["enum"],
+            json!(["prod", "staging"])
+        );
+        assert!(listed["properties"]["model"].get("enum").is_none());
+        // No instances configured (yet): leave the schema open.
+        assert_eq!(with_instance_enum(&schema, &[]), schema);
+        // Tools without an instance argument are untouched.
+        let plain = json!({"type": "object", "properties": {}});
+        assert_eq!(with_instance_enum(&plain, &names), plain);
+    }
+
+    #[test]
+    fn model_tools_are_appended_to_the_file_tools() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tools.json");
+        std::fs::write(
+            &path,
+            json!({
+                "tools": [],
+                "modelTools": [{"model": "sale.order", "ops": ["search"], "fields": ["name"]}]
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let tools = load_tools_file(&path).unwrap();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name, "search_sale_orders");
+        assert_eq!(tools[0].describe_fields, Some(vec!["name".to_string()]));
+
+        std::fs::write(
+            &path,
+            json!({"tools": [], "modelTools": [{"model": "sale.order", "ops": ["merge"]}]})
+                .to_string(),
+        )
+        .unwrap();
+        assert!(load_tools_file(&path).is_err());
+    }
+
     #[test]
     fn test_validate_cursor_schema_rejects_anyof() {
         let schema = json!({
@@ -812,4 +1134,32 @@ mod tests {
         assert!(state.prompt_order.is_empty());
         assert_eq!(state.server.server_name, "odoo-rust-mcp");
     }
+
+    #[tokio::test]
+    async fn test_reload_broadcasts_list_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let tools = dir.path().join("tools.json");
+        let prompts = dir.path().join("prompts.json");
+        std::fs::write(&tools, r#"{"tools": []}"#).unwrap();
+        std::fs::write(&prompts, r#"{"prompts": []}"#).unwrap();
+        let registry = Registry::from_paths(&tools, &prompts, dir.path().join("server.json"));
+        let mut changes = registry.subscribe_changes();
+
+        registry.initial_load().await.unwrap();
+        assert!(changes.try_recv().is_err(), "first load is not a change");
+
+        // Reformatting alone is not a change.
+        std::fs::write(&tools, "{ \"tools\": [ ] }").unwrap();
+        registry.reload().await.unwrap();
+        assert!(changes.try_recv().is_err());
+
+        std::fs::write(
+            &prompts,
+            r#"{"prompts": [{"name": "p", "description": "d", "content": "c"}]}"#,
+        )
+        .unwrap();
+        registry.reload().await.unwrap();
+        assert_eq!(changes.try_recv().unwrap(), ListChanged::Prompts);
+        assert!(changes.try_recv().is_err());
+    }
 }
@@ -0,0 +1,504 @@
+//! Relay transport: a local stdio MCP server forwarding every message to a remote instance of
+//! this server over Streamable HTTP.
+//!
+//! Desktop clients that can only launch stdio servers use a centrally hosted deployment by
+//! running `odoo-rust-mcp --transport relay` with `MCP_RELAY_URL` set to the remote `/mcp`
+//! endpoint. Each line read from stdin is POSTed with the `MCP_RELAY_TOKEN` bearer token and the
+//! session id the remote assigned at `initialize`; responses (JSON or SSE) and the messages of
+//! the session's GET stream are written to stdout. With `MCP_RELAY_SIGNING_SECRET` every
+//! request is also signed (see [`crate::mcp::request_signing`]) for servers configured with the
+//! same value as `MCP_REQUEST_SIGNING_SECRET`.
+//!
+//! The URL must use `https://` unless it points at this machine. When stdin closes, the relay
+//! waits for pending requests and ends the remote session with `DELETE`.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Context, bail};
+use mcp_rust_sdk::error::ErrorCode;
+use reqwest::header::{ACCEPT, CONTENT_TYPE};
+use reqwest::{Method, StatusCode};
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+use url::{Host, Url};
+
+use crate::mcp::request_signing::{RequestSigner, SignedRequest};
+
+pub const RELAY_URL_ENV: &str = "MCP_RELAY_URL";
+pub const RELAY_SIGNING_SECRET_ENV: &str = "MCP_RELAY_SIGNING_SECRET";
+const MCP_SESSION_ID: &str = "mcp-session-id";
+const MCP_PROTOCOL_VERSION: &str = "mcp-protocol-version";
+const LAST_EVENT_ID: &str = "last-event-id";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const END_SESSION_TIMEOUT: Duration = Duration::from_secs(5);
+/// Wait before reopening the remote notification stream.
+const STREAM_RETRY: Duration = Duration::from_secs(3);
+
+#[derive(Clone, Debug)]
+pub struct RelayConfig {
+    pub url: Url,
+    pub token: Option<String>,
+    pub signer: Option<RequestSigner>,
+}
+
+impl RelayConfig {
+    /// Configuration from `MCP_RELAY_*`; `MCP_RELAY_URL` is required.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let var = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let url = var(RELAY_URL_ENV)
+            .with_context(|| format!("{RELAY_URL_ENV} is required for the relay transport"))?;
+        let signer = var(RELAY_SIGNING_SECRET_ENV)
+            .map(|secret| RequestSigner::new(&secret, RELAY_SIGNING_SECRET_ENV))
+            .transpose()?;
+        Self::new(&url, var("MCP_RELAY_TOKEN"), signer)
+    }
+
+    pub fn new(
+        url: &str,
+        token: Option<String>,
+        signer: Option<RequestSigner>,
+    ) -> anyhow::Result<Self> {
+        let url = Url::parse(url).with_context(|| format!("Invalid {RELAY_URL_ENV} '{url}'"))?;
+        match url.scheme() {
+            "https" => {}
+            "http" if is_loopback(&url) => {}
+            "http" => bail!("{RELAY_URL_ENV} must use https:// unless it points at this machine"),
+            other => bail!("Unsupported {RELAY_URL_ENV} scheme '{other}' (expected https)"),
+        }
+        Ok(Self { url, token, signer })
+    }
+}
+
+fn is_loopback(url: &Url) -> bool {
+    match url.host() {
+        Some(Host::Domain(host)) => host.eq_ignore_ascii_case("localhost"),
+        Some(Host::Ipv4(ip)) => ip.is_loopback(),
+        Some(Host::Ipv6(ip)) => ip.is_loopback(),
+        None => false,
+    }
+}
+
+/// One event of a `text/event-stream` body.
+#[derive(Debug, PartialEq)]
+struct SseEvent {
+    id: Option<String>,
+    data: String,
+}
+
+/// Incremental `text/event-stream` parser; chunks may split lines anywhere.
+#[derive(Default)]
+struct SseParser {
+    buffer: Vec<u8>,
+    id: Option<String>,
+    data: Vec<String>,
+}
+
+impl SseParser {
+    fn push(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\n', '\r']);
+            if line.is_empty() {
+                if !self.data.is_empty() {
+                    events.push(SseEvent {
+                        id: self.id.take(),
+                        data: std::mem::take(&mut self.data).join("\n"),
+                    });
+                }
+                self.id = None;
+                continue;
+            }
+            if line.starts_with(':') {
+                continue;
+            }
+            let (field, value) = line.split_once(':').unwrap_or((line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "data" => self.data.push(value.to_string()),
+                "id" => self.id = Some(value.to_string()),
+                _ => {}
+            }
+        }
+        events
+    }
+}
+
+/// Ids of the requests in a message or batch; notifications and responses have none.
+fn request_ids(message: &Value) -> Vec<Value> {
+    match message {
+        Value::Array(items) => items.iter().flat_map(request_ids).collect(),
+        Value::Object(map) if map.contains_key("method") => {
+            map.get("id").cloned().into_iter().collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn is_jsonrpc(value: &Value) -> bool {
+    match value {
+        Value::Array(items) => !items.is_empty() && items.iter().all(is_jsonrpc),
+        Value::Object(map) => map.contains_key("jsonrpc"),
+        _ => false,
+    }
+}
+
+struct Relay {
+    config: RelayConfig,
+    client: reqwest::Client,
+    session: Mutex<Option<String>>,
+    protocol_version: Mutex<Option<String>>,
+    output: mpsc::UnboundedSender<String>,
+}
+
+impl Relay {
+    fn session(&self) -> Option<String> {
+        self.session
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// A request to the remote endpoint with credentials, session headers and signature.
+    fn request(&self, method: Method, body: Vec<u8>) -> reqwest::RequestBuilder {
+        let mut builder = self.client.request(method.clone(), self.config.url.clone());
+        if let Some(token) = &self.config.token {
+            builder = builder.bearer_auth(token);
+        }
+        if let Some(session) = self.session() {
+            builder = builder.header(MCP_SESSION_ID, session);
+        }
+        let protocol_version = self
+            .protocol_version
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        if let Some(version) = protocol_version {
+            builder = builder.header(MCP_PROTOCOL_VERSION, version);
+        }
+        if let Some(signer) = &self.config.signer {
+            let url = &self.config.url;
+            let target = match url.query() {
+                Some(query) => format!("{}?{query}", url.path()),
+                None => url.path().to_string(),
+            };
+            let request = SignedRequest {
+                method: method.as_str(),
+                target: &target,
+                body: &body,
+            };
+            for (name, value) in signer.headers(request) {
+                builder = builder.header(name, value);
+            }
+        }
+        builder.body(body)
+    }
+
+    fn remember_session(&self, response: &reqwest::Response) {
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        if let Some(session) = header(MCP_SESSION_ID) {
+            *self.session.lock().unwrap_or_else(|e| e.into_inner()) = Some(session);
+        }
+        if let Some(version) = header(MCP_PROTOCOL_VERSION) {
+            *self
+                .protocol_version
+                .lock()
+                .unwrap_or_else(|e| e.into_inner()) = Some(version);
+        }
+    }
+
+    fn emit(&self, message: String) {
+        let _ = self.output.send(message);
+    }
+
+    /// Answer each request in `ids` with a JSON-RPC error, since the remote could not.
+    fn emit_error(&self, ids: &[Value], code: ErrorCode, message: &str) {
+        if ids.is_empty() {
+            warn!("{message}");
+        }
+        for id in ids {
+            self.emit(
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": {"code": i32::from(code), "message": message}
+                })
+                .to_string(),
+            );
+        }
+    }
+
+    /// POST one stdin message and write whatever the remote answers.
+    async fn forward(&self, message: Value) {
+        let ids = request_ids(&message);
+        let body = serde_json::to_vec(&message).unwrap_or_default();
+        let sent = self
+            .request(Method::POST, body)
+            .header(CONTENT_TYPE, "application/json")
+            .header(ACCEPT, "application/json, text/event-stream")
+            .send()
+            .await;
+        let response = match sent {
+            Ok(response) => response,
+            Err(e) => {
+                let message = format!("Relay could not reach {}: {e}", self.config.url);
+                return self.emit_error(&ids, ErrorCode::InternalError, &message);
+            }
+        };
+        self.remember_session(&response);
+        let status = response.status();
+        if status == StatusCode::ACCEPTED {
+            return;
+        }
+        if is_event_stream(&response) {
+            self.pump(response).await;
+            return;
+        }
+        let text = response.text().await.unwrap_or_default();
+        match serde_json::from_str::<Value>(&text) {
+            Ok(value) if is_jsonrpc(&value) => self.emit(value.to_string()),
+            _ => {
+                let message = format!("Remote server answered HTTP {status}: {}", text.trim());
+                self.emit_error(&ids, ErrorCode::InternalError, &message);
+            }
+        }
+    }
+
+    /// Write the data of each event of an SSE response; returns the last event id seen.
+    async fn pump(&self, mut response: reqwest::Response) -> Option<String> {
+        let mut parser = SseParser::default();
+        let mut last_event_id = None;
+        while let Ok(Some(chunk)) = response.chunk().await {
+            for event in parser.push(&chunk) {
+                if event.id.is_some() {
+                    last_event_id = event.id;
+                }
+                self.emit(event.data);
+            }
+        }
+        last_event_id
+    }
+
+    /// Relay the session's GET stream (server notifications and requests) until shutdown,
+    /// reopening it with `Last-Event-ID` when it drops.
+    async fn listen(self: Arc<Self>, shutdown: CancellationToken) {
+        let mut last_event_id: Option<String> = None;
+        loop {
+            let mut request = self
+                .request(Method::GET, Vec::new())
+                .header(ACCEPT, "text/event-stream");
+            if let Some(id) = &last_event_id {
+                request = request.header(LAST_EVENT_ID, id);
+            }
+            let sent = tokio::select! {
+                _ = shutdown.cancelled() => return,
+                sent = request.send() => sent,
+            };
+            match sent {
+                Ok(response) if response.status().is_success() => {
+                    tokio::select! {
+                        _ = shutdown.cancelled() => return,
+                        id = self.pump(response) => {
+                            if id.is_some() {
+                                last_event_id = id;
+                            }
+                        }
+                    }
+                    debug!("Remote notification stream closed; reopening");
+                }
+                Ok(response)
+                    if matches!(
+                        response.status(),
+                        StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED
+                    ) =>
+                {
+                    warn!(
+                        status = %response.status(),
+                        "Remote server has no notification stream for this session"
+                    );
+                    return;
+                }
+                Ok(response) => warn!(
+                    status = %response.status(),
+                    "Remote server refused the notification stream"
+                ),
+                Err(e) => warn!("Remote notification stream failed: {e}"),
+            }
+            tokio::select! {
+                _ = shutdown.cancelled() => return,
+                _ = tokio::time::sleep(STREAM_RETRY) => {}
+            }
+        }
+    }
+
+    async fn end_session(&self) {
+        if self.session().is_none() {
+            return;
+        }
+        let request = self
+            .request(Method::DELETE, Vec::new())
+            .timeout(END_SESSION_TIMEOUT);
+        if let Err(e) = request.send().await {
+            debug!("Could not end the remote session: {e}");
+        }
+    }
+}
+
+fn is_event_stream(response: &reqwest::Response) -> bool {
+    response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/event-stream"))
+}
+
+fn is_initialize(message: &Value) -> bool {
+    message.get("method").and_then(Value::as_str) == Some("initialize")
+}
+
+/// Relay newline-delimited JSON-RPC messages from `input` to the remote server, writing its
+/// messages to `output`, until `input` ends.
+pub async fn run<R, W>(config: RelayConfig, input: R, mut output: W) -> anyhow::Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin + Send + 'static,
+{
+    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+    let writer = tokio::spawn(async move {
+        while let Some(line) = rx.recv().await {
+            output.write_all(line.as_bytes()).await?;
+            output.write_all(b"\n").await?;
+            output.flush().await?;
+        }
+        std::io::Result::Ok(())
+    });
+
+    let client = reqwest::Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .build()?;
+    let relay = Arc::new(Relay {
+        config,
+        client,
+        session: Mutex::new(None),
+        protocol_version: Mutex::new(None),
+        output: tx,
+    });
+    info!(url = %relay.config.url, "MCP relay starting (stdio)");
+
+    let shutdown = CancellationToken::new();
+    let mut listener = None;
+    let mut in_flight = JoinSet::new();
+    let mut lines = BufReader::new(input).lines();
+    while let Some(line) = lines.next_line().await? {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let message: Value = match serde_json::from_str(line) {
+            Ok(message) => message,
+            Err(e) => {
+                relay.emit_error(
+                    &[Value::Null],
+                    ErrorCode::ParseError,
+                    &format!("Parse error: {e}"),
+                );
+                continue;
+            }
+        };
+        if is_initialize(&message) {
+            // Later messages need the session id the remote assigns here.
+            relay.forward(message).await;
+            if listener.is_none() && relay.session().is_some() {
+                listener = Some(tokio::spawn(relay.clone().listen(shutdown.clone())));
+            }
+        } else {
+            let relay = relay.clone();
+            in_flight.spawn(async move { relay.forward(message).await });
+        }
+        while in_flight.try_join_next().is_some() {}
+    }
+
+    while in_flight.join_next().await.is_some() {}
+    shutdown.cancel();
+    if let Some(listener) = listener {
+        let _ = listener.await;
+    }
+    relay.end_session().await;
+    info!("stdin closed; relay stopped");
+    drop(relay);
+    writer.await??;
+    Ok(())
+}
+
+/// Relay between this process's stdin/stdout and the remote server.
+pub async fn run_stdio(config: RelayConfig) -> anyhow::Result<()> {
+    run(config, tokio::io::stdin(), tokio::io::stdout()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requires_https_for_remote_hosts() {
+        assert!(RelayConfig::new("https://mcp.example.com/mcp", None, None).is_ok());
+        assert!(RelayConfig::new("http://127.0.0.1:8787/mcp", None, None).is_ok());
+        assert!(RelayConfig::new("http://localhost:8787/mcp", None, None).is_ok());
+        assert!(RelayConfig::new("http://[::1]:8787/mcp", None, None).is_ok());
+        let err = RelayConfig::new("http://mcp.example.com/mcp", None, None).unwrap_err();
+        assert!(err.to_string().contains("https://"));
+        assert!(RelayConfig::new("ws://localhost:8787", None, None).is_err());
+    }
+
+    #[test]
+    fn parses_events_split_across_chunks() {
+        let mut parser = SseParser::default();
+        assert!(parser.push(b": keepalive\n\nid: 4\nevent: mess").is_empty());
+        let events = parser.push(b"age\r\ndata: {\"a\":\ndata: 1}\n\nretry: 3000\n\ndata: x\n\n");
+        assert_eq!(
+            events,
+            [
+                SseEvent {
+                    id: Some("4".into()),
+                    data: "{\"a\":\n1}".into()
+                },
+                SseEvent {
+                    id: None,
+                    data: "x".into()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_request_ids_in_messages_and_batches() {
+        let batch = json!([
+            {"jsonrpc": "2.0", "id": 1, "method": "ping"},
+            {"jsonrpc": "2.0", "method": "notifications/initialized"},
+            {"jsonrpc": "2.0", "id": "s1", "result": {}},
+        ]);
+        assert_eq!(request_ids(&batch), [json!(1)]);
+        assert!(is_jsonrpc(
+            &json!({"jsonrpc": "2.0", "id": 1, "result": {}})
+        ));
+        assert!(!is_jsonrpc(&json!({"error": "invalid_token"})));
+    }
+}
@@ -0,0 +1,393 @@
+//! Renderers for the text of tool results.
+//!
+//! Tools build their result as JSON and [`crate::mcp::tools::call_tool`] hands the payload to a
+//! renderer picked by the call's `format` argument, falling back to `MCP_RESULT_FORMAT`
+//! (default `json`). Built in:
+//! - `json`: pretty-printed JSON, the historical output
+//! - `compact`: single-line JSON
+//! - `markdown`: tables for lists of records, `key: value` lines for the remaining fields;
+//!   renders well in chat UIs
+//! - `csv`: the records of the result as CSV
+//!
+//! `format` is advertised on every tool that does not define its own `format` argument (such
+//! as `odoo_export_records`); those tools keep their meaning and use the server default. Only
+//! results made of a single JSON text block are rendered; embedded resources, blobs and text
+//! that is not JSON pass through untouched, as do results a renderer cannot express (`csv`
+//! without records). More renderers can be added at startup with [`register`].
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use serde_json::{Map, Value, json};
+
+use crate::mcp::csv_export;
+
+/// Per-call argument naming the renderer.
+pub const FORMAT_ARG: &str = "format";
+const DEFAULT_FORMAT: &str = "json";
+
+pub trait Renderer: Send + Sync {
+    /// Value of the `format` argument that selects this renderer.
+    fn name(&self) -> &'static str;
+
+    /// `payload` as result text, or None when it cannot be expressed in this format.
+    fn render(&self, payload: &Value) -> Option<String>;
+}
+
+struct Json;
+
+impl Renderer for Json {
+    fn name(&self) -> &'static str {
+        "json"
+    }
+
+    fn render(&self, payload: &Value) -> Option<String> {
+        serde_json::to_string_pretty(payload).ok()
+    }
+}
+
+struct Compact;
+
+impl Renderer for Compact {
+    fn name(&self) -> &'static str {
+        "compact"
+    }
+
+    fn render(&self, payload: &Value) -> Option<String> {
+        serde_json::to_string(payload).ok()
+    }
+}
+
+struct Markdown;
+
+impl Renderer for Markdown {
+    fn name(&self) -> &'static str {
+        "markdown"
+    }
+
+    fn render(&self, payload: &Value) -> Option<String> {
+        Some(markdown(payload))
+    }
+}
+
+struct Csv;
+
+impl Renderer for Csv {
+    fn name(&self) -> &'static str {
+        "csv"
+    }
+
+    fn render(&self, payload: &Value) -> Option<String> {
+        let (_, rows) = tables(payload)
+            .into_iter()
+            .max_by_key(|(_, rows)| rows.len())?;
+        let columns = columns(rows);
+        let mut out = Vec::with_capacity(rows.len() + 1);
+        out.push(columns.clone());
+        out.extend(rows.iter().map(|row| {
+            columns
+                .iter()
+                .map(|column| csv_export::cell(&row[column.as_str()], None))
+                .collect()
+        }));
+        Some(csv_export::encode_rows(&out))
+    }
+}
+
+fn registry() -> &'static RwLock<BTreeMap<&'static str, Arc<dyn Renderer>>> {
+    static REGISTRY: OnceLock<RwLock<BTreeMap<&'static str, Arc<dyn Renderer>>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let builtins: [Arc<dyn Renderer>; 4] = [
+            Arc::new(Json),
+            Arc::new(Compact),
+            Arc::new(Markdown),
+            Arc::new(Csv),
+        ];
+        RwLock::new(builtins.into_iter().map(|r| (r.name(), r)).collect())
+    })
+}
+
+/// Add `renderer`, replacing a renderer of the same name.
+pub fn register(renderer: Arc<dyn Renderer>) {
+    registry()
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(renderer.name(), renderer);
+}
+
+/// Names accepted by the `format` argument.
+pub fn names() -> Vec<&'static str> {
+    registry()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .keys()
+        .copied()
+        .collect()
+}
+
+fn find(name: &str) -> Option<Arc<dyn Renderer>> {
+    registry()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(name)
+        .cloned()
+}
+
+/// `MCP_RESULT_FORMAT` when it names a renderer, else `json`.
+pub fn default_format() -> String {
+    std::env::var("MCP_RESULT_FORMAT")
+        .ok()
+        .map(|v| v.trim().to_ascii_lowercase())
+        .filter(|v| find(v).is_some())
+        .unwrap_or_else(|| DEFAULT_FORMAT.to_string())
+}
+
+/// Whether the tool's own schema defines `format`, leaving it out of the renderer's reach.
+fn owns_format(schema: &Value) -> bool {
+    schema
+        .pointer(&format!("/properties/{FORMAT_ARG}"))
+        .is_some()
+}
+
+/// Remove the renderer's `format` from `args` unless the tool defines it; returns the name.
+pub fn take_format(schema: &Value, args: &mut Value) -> Result<Option<String>, String> {
+    if owns_format(schema) {
+        return Ok(None);
+    }
+    let Some(value) = args.as_object_mut().and_then(|map| map.remove(FORMAT_ARG)) else {
+        return Ok(None);
+    };
+    match value.as_str().filter(|name| find(name).is_some()) {
+        Some(name) => Ok(Some(name.to_string())),
+        None => Err(format!(
+            "'{FORMAT_ARG}' must be one of {}, got {value}",
+            names().join(", ")
+        )),
+    }
+}
+
+/// `schema` with the `format` argument advertised, unless the tool defines its own.
+pub fn with_format_arg(schema: &Value) -> Value {
+    let mut schema = schema.clone();
+    if owns_format(&schema) {
+        return schema;
+    }
+    if let Some(properties) = schema.get_mut("properties").and_then(Value::as_object_mut) {
+        properties.insert(
+            FORMAT_ARG.to_string(),
+            json!({
+                "type": "string",
+                "enum": names(),
+                "description": "How the result text is rendered (server default applies when omitted)"
+            }),
+        );
+    }
+    schema
+}
+
+/// `result` with its JSON text rendered in `format` (the server default when None).
+pub fn render_result(mut result: Value, format: Option<&str>) -> Value {
+    let format = format.map(str::to_string).unwrap_or_else(default_format);
+    if format == DEFAULT_FORMAT {
+        return result;
+    }
+    let Some(renderer) = find(&format) else {
+        return result;
+    };
+    let Some([block]) = result
+        .get_mut("content")
+        .and_then(Value::as_array_mut)
+        .map(Vec::as_mut_slice)
+    else {
+        return result;
+    };
+    if block.get("type").and_then(Value::as_str) != Some("text") {
+        return result;
+    }
+    let Some(payload) = block
+        .get("text")
+        .and_then(Value::as_str)
+        .and_then(|text| serde_json::from_str::<Value>(text).ok())
+    else {
+        return result;
+    };
+    if let Some(text) = renderer.render(&payload) {
+        block["text"] = Value::String(text);
+    }
+    result
+}
+
+/// Lists of records in `payload`: the payload itself or its top-level members.
+fn tables(payload: &Value) -> Vec<(Option<&str>, &[Value])> {
+    let is_table = |value: &Value| {
+        value
+            .as_array()
+            .filter(|rows| !rows.is_empty() && rows.iter().all(Value::is_object))
+            .is_some()
+    };
+    match payload {
+        Value::Array(rows) if is_table(payload) => vec![(None, rows.as_slice())],
+        Value::Object(map) => map
+            .iter()
+            .filter(|(_, value)| is_table(value))
+            .filter_map(|(key, value)| Some((Some(key.as_str()), value.as_array()?.as_slice())))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Column names in order of first appearance, `id` first.
+fn columns(rows: &[Value]) -> Vec<String> {
+    let mut columns: Vec<String> = Vec::new();
+    for key in rows.iter().filter_map(Value::as_object).flat_map(Map::keys) {
+        if !columns.contains(key) {
+            columns.push(key.clone());
+        }
+    }
+    if let Some(at) = columns.iter().position(|column| column == "id") {
+        let id = columns.remove(at);
+        columns.insert(0, id);
+    }
+    columns
+}
+
+/// A cell or scalar value as inline markdown text.
+fn markdown_cell(value: &Value) -> String {
+    let text = match value {
+        // Many2one pairs show their display name, other lists of scalars their items.
+        Value::Array(pair) if pair.len() == 2 && pair[0].is_i64() && pair[1].is_string() => {
+            csv_export::cell(value, None)
+        }
+        Value::Array(items) if items.iter().all(|v| !v.is_object() && !v.is_array()) => items
+            .iter()
+            .map(|item| csv_export::cell(item, None))
+            .collect::<Vec<_>>()
+            .join(", "),
+        Value::Array(_) | Value::Object(_) => value.to_string(),
+        other => csv_export::cell(other, None),
+    };
+    text.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+fn markdown_table(rows: &[Value]) -> String {
+    let columns = columns(rows);
+    let mut out = format!("| {} |\n", columns.join(" | "));
+    out.push_str(&format!("|{}\n", " --- |".repeat(columns.len())));
+    for row in rows {
+        let cells: Vec<String> = columns
+            .iter()
+            .map(|column| markdown_cell(&row[column.as_str()]))
+            .collect();
+        out.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+    out
+}
+
+fn markdown(payload: &Value) -> String {
+    let tables = tables(payload);
+    if tables.is_empty() {
+        return match payload {
+            Value::Object(map) if map.values().all(|v| !v.is_object()) => map
+                .iter()
+                .map(|(key, value)| format!("- **{key}**: {}\n", markdown_cell(value)))
+                .collect(),
+            _ => format!(
+                "```json\n{}\n```\n",
+                serde_json::to_string_pretty(payload).unwrap_or_default()
+            ),
+        };
+    }
+    let mut sections = Vec::new();
+    if let Value::Object(map) = payload {
+        let scalars: String = map
+            .iter()
+            .filter(|(key, _)| !tables.iter().any(|(name, _)| *name == Some(key.as_str())))
+            .map(|(key, value)| format!("- **{key}**: {}\n", markdown_cell(value)))
+            .collect();
+        if !scalars.is_empty() {
+            sections.push(scalars);
+        }
+    }
+    for (name, rows) in &tables {
+        let heading = name
+            .map(|name| format!("### {name}\n\n"))
+            .unwrap_or_default();
+        sections.push(format!("{heading}{}", markdown_table(rows)));
+    }
+    sections.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_result(payload: Value) -> Value {
+        json!({"content": [{"type": "text", "text": serde_json::to_string_pretty(&payload).unwrap()}]})
+    }
+
+    #[test]
+    fn records_render_as_markdown_tables() {
+        let payload = json!({
+            "count": 2,
+            "records": [
+                {"id": 1, "name": "Desk | large", "partner_id": [7, "Azure Interior"]},
+                {"id": 2, "name": "Chair", "partner_id": false, "tag_ids": [1, 2]}
+            ]
+        });
+        assert_eq!(
+            markdown(&payload),
+            "- **count**: 2\n\n### records\n\n| id | name | partner_id | tag_ids |\n| --- | --- | --- | --- |\n| 1 | Desk \\| large | Azure Interior |  |\n| 2 | Chair |  | 1, 2 |\n"
+        );
+        assert_eq!(
+            markdown(&json!({"success": true, "id": 5})),
+            "- **id**: 5\n- **success**: true\n"
+        );
+    }
+
+    #[test]
+    fn csv_takes_the_largest_record_list() {
+        let payload = json!([{"id": 1, "name": "A, B"}, {"id": 2, "name": "C"}]);
+        assert_eq!(
+            Csv.render(&payload).unwrap(),
+            "id,name\r\n1,\"A, B\"\r\n2,C\r\n"
+        );
+        assert_eq!(Csv.render(&json!({"id": 5})), None);
+    }
+
+    #[test]
+    fn results_are_rendered_only_when_they_are_json_text() {
+        let result = text_result(json!({"id": 5}));
+        assert_eq!(
+            render_result(result.clone(), Some("compact"))["content"][0]["text"],
+            "{\"id\":5}"
+        );
+        // Nothing to tabulate: the JSON stays.
+        assert_eq!(render_result(result.clone(), Some("csv")), result);
+        let resource = json!({"content": [{"type": "resource", "resource": {"text": "{}"}}]});
+        assert_eq!(render_result(resource.clone(), Some("compact")), resource);
+    }
+
+    #[test]
+    fn format_is_taken_only_from_tools_without_their_own() {
+        let plain = json!({"type": "object", "properties": {"instance": {"type": "string"}}});
+        let mut args = json!({"instance": "prod", "format": "markdown"});
+        assert_eq!(
+            take_format(&plain, &mut args).unwrap().as_deref(),
+            Some("markdown")
+        );
+        assert_eq!(args, json!({"instance": "prod"}));
+        assert!(take_format(&plain, &mut json!({"format": "yaml"})).is_err());
+        assert!(
+            with_format_arg(&plain)["properties"]["format"]["enum"]
+                .as_array()
+                .unwrap()
+                .contains(&json!("markdown"))
+        );
+
+        let owned = json!({"type": "object", "properties": {"format": {"enum": ["json", "csv"]}}});
+        let mut args = json!({"format": "csv"});
+        assert_eq!(take_format(&owned, &mut args).unwrap(), None);
+        assert_eq!(args, json!({"format": "csv"}));
+        assert_eq!(with_format_arg(&owned), owned);
+    }
+}
@@ -0,0 +1,398 @@
+//! Signed HTTP requests to the MCP transport endpoints.
+//!
+//! With `MCP_REQUEST_SIGNING_SECRET` set (at least 32 bytes), every request to `/mcp`, `/sse`
+//! and `/messages` must carry an HMAC-SHA256 signature made with the same secret, on top of
+//! the bearer token. The relay transport (`--transport relay`) signs its requests this way.
+//!
+//! A signed request has three headers:
+//!
+//! - `X-MCP-Timestamp`: Unix time in seconds when it was signed;
+//! - `X-MCP-Nonce`: a value unique to the request;
+//! - `X-MCP-Signature`: `v1=` and the lowercase hex HMAC of
+//!   `<timestamp>.<nonce>.<HTTP method>.<path and query>.<body>`.
+//!
+//! The path and query are the request target as the server receives it (`/mcp`,
+//! `/messages?sessionId=...`), so a signature is only good for the route it was made for. A
+//! reverse proxy in front of the server must not rewrite them.
+//!
+//! Timestamps further than `MCP_REQUEST_SIGNING_TOLERANCE_SECS` (default 300) from the server
+//! clock are rejected, and so is a nonce seen again within that window.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::bail;
+use axum::Json;
+use axum::body::{Body, to_bytes};
+use axum::extract::{Request, State};
+use axum::http::{HeaderMap, StatusCode, Uri};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use hmac::{Hmac, Mac};
+use serde_json::json;
+use sha2::Sha256;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub const SIGNING_SECRET_ENV: &str = "MCP_REQUEST_SIGNING_SECRET";
+pub const TIMESTAMP_HEADER: &str = "x-mcp-timestamp";
+pub const NONCE_HEADER: &str = "x-mcp-nonce";
+pub const SIGNATURE_HEADER: &str = "x-mcp-signature";
+const SIGNATURE_VERSION: &str = "v1=";
+const MIN_SECRET_BYTES: usize = 32;
+const DEFAULT_TOLERANCE: Duration = Duration::from_secs(300);
+/// Body size read for verification when `MCP_MAX_BODY_BYTES` is unset (axum's default).
+pub const DEFAULT_BODY_LIMIT: usize = 2 * 1024 * 1024;
+
+fn check_secret(secret: &str, env: &str) -> anyhow::Result<()> {
+    if secret.len() < MIN_SECRET_BYTES {
+        bail!("{env} must be at least {MIN_SECRET_BYTES} bytes");
+    }
+    Ok(())
+}
+
+/// What a signature covers besides its timestamp and nonce.
+#[derive(Clone, Copy, Debug)]
+pub struct SignedRequest<'a> {
+    pub method: &'a str,
+    /// Path and query, e.g. `/messages?sessionId=abc`.
+    pub target: &'a str,
+    pub body: &'a [u8],
+}
+
+fn mac(secret: &[u8], timestamp: i64, nonce: &str, request: SignedRequest<'_>) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    let SignedRequest {
+        method,
+        target,
+        body,
+    } = request;
+    mac.update(format!("{timestamp}.{nonce}.{method}.{target}.").as_bytes());
+    mac.update(body);
+    mac
+}
+
+/// `X-MCP-Signature` value for one request.
+pub fn sign(secret: &str, timestamp: i64, nonce: &str, request: SignedRequest<'_>) -> String {
+    let digest = mac(secret.as_bytes(), timestamp, nonce, request).finalize();
+    format!("{SIGNATURE_VERSION}{}", hex::encode(digest.into_bytes()))
+}
+
+/// Path and query of `uri`, as signed; `/` when it has neither.
+pub fn request_target(uri: &Uri) -> &str {
+    uri.path_and_query().map_or("/", |target| target.as_str())
+}
+
+/// Signs outgoing requests with a shared secret.
+#[derive(Clone)]
+pub struct RequestSigner {
+    secret: Arc<str>,
+}
+
+impl fmt::Debug for RequestSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestSigner").finish_non_exhaustive()
+    }
+}
+
+impl RequestSigner {
+    pub fn new(secret: &str, env: &str) -> anyhow::Result<Self> {
+        check_secret(secret, env)?;
+        Ok(Self {
+            secret: secret.into(),
+        })
+    }
+
+    /// The signature headers for `request`, signed now.
+    pub fn headers(&self, request: SignedRequest<'_>) -> [(&'static str, String); 3] {
+        let timestamp = chrono::Utc::now().timestamp();
+        let nonce = Uuid::new_v4().simple().to_string();
+        let signature = sign(&self.secret, timestamp, &nonce, request);
+        [
+            (TIMESTAMP_HEADER, timestamp.to_string()),
+            (NONCE_HEADER, nonce),
+            (SIGNATURE_HEADER, signature),
+        ]
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum SignatureError {
+    #[error("Missing {0} header")]
+    Missing(&'static str),
+    #[error("Malformed {0} header")]
+    Malformed(&'static str),
+    #[error("Request timestamp is outside the accepted window")]
+    Stale,
+    #[error("Request nonce was already used")]
+    Replayed,
+    #[error("Request signature does not match")]
+    Mismatch,
+}
+
+/// Checks incoming request signatures and remembers recent nonces.
+#[derive(Clone)]
+pub struct RequestVerifier {
+    secret: Arc<str>,
+    tolerance: Duration,
+    /// Nonces seen within the tolerance window, with their request timestamps.
+    seen: Arc<Mutex<HashMap<String, i64>>>,
+}
+
+impl fmt::Debug for RequestVerifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestVerifier")
+            .field("tolerance", &self.tolerance)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RequestVerifier {
+    pub fn new(secret: &str, tolerance: Duration) -> anyhow::Result<Self> {
+        check_secret(secret, SIGNING_SECRET_ENV)?;
+        Ok(Self {
+            secret: secret.into(),
+            tolerance,
+            seen: Arc::default(),
+        })
+    }
+
+    /// The verifier configured by `MCP_REQUEST_SIGNING_*`, or `None` when no secret is set.
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let Some(secret) = std::env::var(SIGNING_SECRET_ENV)
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+        else {
+            return Ok(None);
+        };
+        let tolerance = std::env::var("MCP_REQUEST_SIGNING_TOLERANCE_SECS")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|v| *v > 0)
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_TOLERANCE);
+        info!(
+            tolerance_secs = tolerance.as_secs(),
+            "MCP request signing required"
+        );
+        Self::new(&secret, tolerance).map(Some)
+    }
+
+    /// Check the signature headers of `request`, received at `now` (Unix seconds). A valid
+    /// nonce is consumed.
+    pub fn verify(
+        &self,
+        headers: &HeaderMap,
+        request: SignedRequest<'_>,
+        now: i64,
+    ) -> Result<(), SignatureError> {
+        let header = |name: &'static str| {
+            headers
+                .get(name)
+                .ok_or(SignatureError::Missing(name))?
+                .to_str()
+                .map_err(|_| SignatureError::Malformed(name))
+        };
+        let timestamp: i64 = header(TIMESTAMP_HEADER)?
+            .trim()
+            .parse()
+            .map_err(|_| SignatureError::Malformed(TIMESTAMP_HEADER))?;
+        let nonce = header(NONCE_HEADER)?.trim();
+        if nonce.is_empty() {
+            return Err(SignatureError::Malformed(NONCE_HEADER));
+        }
+        let signature = header(SIGNATURE_HEADER)?
+            .trim()
+            .strip_prefix(SIGNATURE_VERSION)
+            .and_then(|hex_digest| hex::decode(hex_digest).ok())
+            .ok_or(SignatureError::Malformed(SIGNATURE_HEADER))?;
+
+        let window = self.tolerance.as_secs() as i64;
+        if (now - timestamp).abs() > window {
+            return Err(SignatureError::Stale);
+        }
+        mac(self.secret.as_bytes(), timestamp, nonce, request)
+            .verify_slice(&signature)
+            .map_err(|_| SignatureError::Mismatch)?;
+
+        let mut seen = self.seen.lock().unwrap_or_else(|e| e.into_inner());
+        seen.retain(|_, at| (now - *at).abs() <= window);
+        if seen.insert(nonce.to_string(), timestamp).is_some() {
+            return Err(SignatureError::Replayed);
+        }
+        Ok(())
+    }
+}
+
+/// Middleware rejecting transport requests without a valid signature. The body is read up to
+/// `limit` bytes to check it and handed on unchanged.
+pub async fn signature_layer(
+    State((verifier, limit)): State<(RequestVerifier, usize)>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let (parts, body) = request.into_parts();
+    let bytes = match to_bytes(body, limit).await {
+        Ok(bytes) => bytes,
+        Err(_) => return StatusCode::PAYLOAD_TOO_LARGE.into_response(),
+    };
+    let now = chrono::Utc::now().timestamp();
+    let request = SignedRequest {
+        method: parts.method.as_str(),
+        target: request_target(&parts.uri),
+        body: &bytes,
+    };
+    if let Err(err) = verifier.verify(&parts.headers, request, now) {
+        warn!(method = %parts.method, path = %parts.uri.path(), "Rejected request: {err}");
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({
+                "error": "invalid_signature",
+                "error_description": err.to_string()
+            })),
+        )
+            .into_response();
+    }
+    next.run(Request::from_parts(parts, Body::from(bytes)))
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    const SECRET: &str = "0123456789abcdef0123456789abcdef";
+
+    fn request<'a>(method: &'a str, target: &'a str, body: &'a [u8]) -> SignedRequest<'a> {
+        SignedRequest {
+            method,
+            target,
+            body,
+        }
+    }
+
+    fn signed(signer: &RequestSigner, request: SignedRequest<'_>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in signer.headers(request) {
+            headers.insert(name, HeaderValue::from_str(&value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn rejects_short_secrets() {
+        assert!(RequestSigner::new("short", "MCP_RELAY_SIGNING_SECRET").is_err());
+        assert!(RequestVerifier::new("short", DEFAULT_TOLERANCE).is_err());
+    }
+
+    #[test]
+    fn verifies_signed_requests_once() {
+        let signer = RequestSigner::new(SECRET, SIGNING_SECRET_ENV).unwrap();
+        let verifier = RequestVerifier::new(SECRET, DEFAULT_TOLERANCE).unwrap();
+        let now = chrono::Utc::now().timestamp();
+        let body = br#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#;
+
+        let headers = signed(&signer, request("POST", "/mcp", body));
+        assert_eq!(
+            verifier.verify(&headers, request("POST", "/mcp", body), now),
+            Ok(())
+        );
+        assert_eq!(
+            verifier.verify(&headers, request("POST", "/mcp", body), now),
+            Err(SignatureError::Replayed)
+        );
+
+        let headers = signed(&signer, request("POST", "/mcp", body));
+        assert_eq!(
+            verifier.verify(&headers, request("POST", "/mcp", b"{}"), now),
+            Err(SignatureError::Mismatch)
+        );
+        assert_eq!(
+            verifier.verify(&headers, request("DELETE", "/mcp", body), now),
+            Err(SignatureError::Mismatch)
+        );
+        assert_eq!(
+            verifier.verify(&headers, request("POST", "/mcp", body), now + 301),
+            Err(SignatureError::Stale)
+        );
+
+        let other = RequestVerifier::new(&SECRET.repeat(2), DEFAULT_TOLERANCE).unwrap();
+        assert_eq!(
+            other.verify(&headers, request("POST", "/mcp", body), now),
+            Err(SignatureError::Mismatch)
+        );
+    }
+
+    #[test]
+    fn signatures_are_bound_to_the_path_and_query() {
+        let signer = RequestSigner::new(SECRET, SIGNING_SECRET_ENV).unwrap();
+        let verifier = RequestVerifier::new(SECRET, DEFAULT_TOLERANCE).unwrap();
+        let now = chrono::Utc::now().timestamp();
+        let body = br#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#;
+
+        let headers = signed(&signer, request("POST", "/mcp", body));
+        assert_eq!(
+            verifier.verify(
+                &headers,
+                request("POST", "/messages?sessionId=s1", body),
+                now
+            ),
+            Err(SignatureError::Mismatch)
+        );
+        let headers = signed(&signer, request("POST", "/messages?sessionId=s1", body));
+        assert_eq!(
+            verifier.verify(
+                &headers,
+                request("POST", "/messages?sessionId=s2", body),
+                now
+            ),
+            Err(SignatureError::Mismatch)
+        );
+        assert_eq!(
+            verifier.verify(
+                &headers,
+                request("POST", "/messages?sessionId=s1", body),
+                now
+            ),
+            Ok(())
+        );
+
+        let uri: Uri = "http://localhost:8787/messages?sessionId=s1"
+            .parse()
+            .unwrap();
+        assert_eq!(request_target(&uri), "/messages?sessionId=s1");
+        assert_eq!(request_target(&Uri::from_static("http://localhost")), "/");
+    }
+
+    #[test]
+    fn reports_missing_and_malformed_headers() {
+        let verifier = RequestVerifier::new(SECRET, DEFAULT_TOLERANCE).unwrap();
+        let now = chrono::Utc::now().timestamp();
+        assert_eq!(
+            verifier.verify(&HeaderMap::new(), request("POST", "/mcp", b""), now),
+            Err(SignatureError::Missing(TIMESTAMP_HEADER))
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert(TIMESTAMP_HEADER, HeaderValue::from(now));
+        headers.insert(NONCE_HEADER, HeaderValue::from_static("n1"));
+        headers.insert(SIGNATURE_HEADER, HeaderValue::from_static("sha1=abc"));
+        assert_eq!(
+            verifier.verify(&headers, request("POST", "/mcp", b""), now),
+            Err(SignatureError::Malformed(SIGNATURE_HEADER))
+        );
+
+        let signature = sign(SECRET, now, "n1", request("POST", "/mcp", b""));
+        headers.insert(SIGNATURE_HEADER, HeaderValue::from_str(&signature).unwrap());
+        assert_eq!(
+            verifier.verify(&headers, request("POST", "/mcp", b""), now),
+            Ok(())
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use mcp_rust_sdk::error::{Error, ErrorCode};
 use serde_json::{Value, json};
+use tracing::warn;
 
 use crate::mcp::tools::OdooClientPool;
 
@@ -11,12 +12,38 @@ fn resource_err(message: impl Into<String>) -> Error {
 /// Supports odoo:// URI scheme with the following formats:
 /// - odoo://instances - List all configured instances
 /// - odoo://{instance}/models - List models for an instance
-/// - odoo://{instance}/metadata/{model} - Get model metadata
+/// - odoo://{instance}/models/{model} - Get model fields (fields_get)
+/// - odoo://{instance}/models/{model}/metadata - Get model metadata
+/// - odoo://{instance}/metadata/{model} - Get model metadata (legacy form)
+/// - odoo://{instance}/record/{model}/{id} - Read a single record
+/// - odoo://{instance}/model/{model}/fields - Get model fields (template form)
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ResourceUri {
     Instances,
-    Models { instance: String },
-    Metadata { instance: String, model: String },
+    Models {
+        instance: String,
+    },
+    Model {
+        instance: String,
+        model: String,
+    },
+    ModelMetadata {
+        instance: String,
+        model: String,
+    },
+    Metadata {
+        instance: String,
+        model: String,
+    },
+    Record {
+        instance: String,
+        model: String,
+        id: i64,
+    },
+    ModelFields {
+        instance: String,
+        model: String,
+    },
 }
 
 impl ResourceUri {
@@ -45,13 +72,53 @@ impl ResourceUri {
             }
             [instance, rest] => {
                 // Parse the rest part
-                if rest.starts_with("models") {
-                    if *rest == "models" {
-                        Ok(ResourceUri::Models {
-                            instance: instance.to_string(),
-                        })
+                if *rest == "models" {
+                    Ok(ResourceUri::Models {
+                        instance: instance.to_string(),
+                    })
+                } else if let Some(model_path) = rest.strip_prefix("models/") {
+                    let (model, metadata) = match model_path.strip_suffix("/metadata") {
+                        Some(model) => (model, true),
+                        None => (model_path, false),
+                    };
+                    // Model names are dotted identifiers and never contain '/'.
+                    if model.is_empty() || model.contains('/') {
+                        return Err(format!("Invalid models URI: {}", uri));
+                    }
+                    let (instance, model) = (instance.to_string(), model.to_string());
+                    Ok(if metadata {
+                        ResourceUri::ModelMetadata { instance, model }
                     } else {
-                        Err(format!("Invalid models URI: {}", uri))
+                        ResourceUri::Model { instance, model }
+                    })
+                } else if rest.starts_with("models") {
+                    Err(format!("Invalid models URI: {}", uri))
+                } else if let Some(record_path) = rest.strip_prefix("record/") {
+                    let (model, id) = record_path
+                        .split_once('/')
+                        .ok_or_else(|| format!("Invalid record URI: {}", uri))?;
+                    let id = id
+                        .parse::<i64>()
+                        .ok()
+                        .filter(|id| *id > 0)
+                        .ok_or_else(|| format!("Invalid record id in URI: {}", uri))?;
+                    if model.is_empty() || model.contains('/') {
+                        return Err(format!("Invalid record URI: {}", uri));
+                    }
+                    Ok(ResourceUri::Record {
+                        instance: instance.to_string(),
+                        model: model.to_string(),
+                        id,
+                    })
+                } else if let Some(model_path) = rest.strip_prefix("model/") {
+                    match model_path.strip_suffix("/fields") {
+                        Some(model) if !model.is_empty() && !model.contains('/') => {
+                            Ok(ResourceUri::ModelFields {
+                                instance: instance.to_string(),
+                                model: model.to_string(),
+                            })
+                        }
+                        _ => Err(format!("Invalid model fields URI: {}", uri)),
                     }
                 } else if let Some(model) = rest.strip_prefix("metadata/") {
                     if model.is_empty() {
@@ -75,14 +142,31 @@ impl ResourceUri {
         match self {
             ResourceUri::Instances => "odoo://instances".to_string(),
             ResourceUri::Models { instance } => format!("odoo://{}/models", instance),
+            ResourceUri::Model { instance, model } => {
+                format!("odoo://{}/models/{}", instance, model)
+            }
+            ResourceUri::ModelMetadata { instance, model } => {
+                format!("odoo://{}/models/{}/metadata", instance, model)
+            }
             ResourceUri::Metadata { instance, model } => {
                 format!("odoo://{}/metadata/{}", instance, model)
             }
+            ResourceUri::Record {
+                instance,
+                model,
+                id,
+            } => format!("odoo://{}/record/{}/{}", instance, model, id),
+            ResourceUri::ModelFields { instance, model } => {
+                format!("odoo://{}/model/{}/fields", instance, model)
+            }
         }
     }
 }
 
-/// List all available resources
+/// List all available resources.
+///
+/// Installed models are listed per instance as `odoo://{instance}/models/{model}`; an
+/// instance that cannot be reached is logged and contributes only its models index.
 pub async fn list_resources(pool: &OdooClientPool) -> Result<Value, Error> {
     let mut resources = vec![];
 
@@ -102,6 +186,27 @@ pub async fn list_resources(pool: &OdooClientPool) -> Result<Value, Error> {
             "description": format!("List of accessible models in Odoo instance '{}'", instance),
             "mimeType": "application/json"
         }));
+
+        match installed_models(pool, &instance).await {
+            Ok(models) => {
+                for (model, name) in models {
+                    resources.push(json!({
+                        "uri": ResourceUri::Model {
+                            instance: instance.clone(),
+                            model: model.clone(),
+                        }
+                        .to_uri(),
+                        "name": format!("{} ({})", name, model),
+                        "description": format!("Fields of {} in Odoo instance '{}'", model, instance),
+                        "mimeType": "application/json"
+                    }));
+                }
+            }
+            Err(e) => warn!(
+                "Skipping model resources for instance '{}': {}",
+                instance, e
+            ),
+        }
     }
 
     Ok(json!({
@@ -109,6 +214,32 @@ pub async fn list_resources(pool: &OdooClientPool) -> Result<Value, Error> {
     }))
 }
 
+/// List the URI templates clients can fill in and pass to `resources/read`
+pub fn list_resource_templates() -> Value {
+    json!({
+        "resourceTemplates": [
+            {
+                "uriTemplate": "odoo://{instance}/record/{model}/{id}",
+                "name": "Odoo record",
+                "description": "A single record, all fields, read by model and id",
+                "mimeType": "application/json"
+            },
+            {
+                "uriTemplate": "odoo://{instance}/model/{model}/fields",
+                "name": "Odoo model fields",
+                "description": "Field definitions (fields_get) of a model",
+                "mimeType": "application/json"
+            },
+            {
+                "uriTemplate": "odoo://{instance}/models/{model}/metadata",
+                "name": "Odoo model metadata",
+                "description": "Model description together with its field definitions",
+                "mimeType": "application/json"
+            }
+        ]
+    })
+}
+
 /// Read a specific resource by URI
 pub async fn read_resource(pool: &OdooClientPool, uri: &str) -> Result<Value, Error> {
     let resource = ResourceUri::parse(uri).map_err(resource_err)?;
@@ -116,10 +247,59 @@ pub async fn read_resource(pool: &OdooClientPool, uri: &str) -> Result<Value, Er
     match resource {
         ResourceUri::Instances => read_instances(pool).await,
         ResourceUri::Models { instance } => read_models(pool, &instance).await,
-        ResourceUri::Metadata { instance, model } => read_metadata(pool, &instance, &model).await,
+        ResourceUri::Model { .. } | ResourceUri::ModelFields { .. } => {
+            read_fields(pool, &resource).await
+        }
+        ResourceUri::Record {
+            instance,
+            model,
+            id,
+        } => read_record(pool, &instance, &model, id).await,
+        ResourceUri::ModelMetadata { .. } | ResourceUri::Metadata { .. } => {
+            read_metadata(pool, &resource).await
+        }
     }
 }
 
+/// Non-transient models installed on an instance, as `(model, display name)` pairs.
+async fn installed_models(
+    pool: &OdooClientPool,
+    instance: &str,
+) -> Result<Vec<(String, String)>, String> {
+    let client = pool.get(instance).await.map_err(|e| e.to_string())?;
+    let rows = client
+        .search_read(
+            "ir.model",
+            Some(json!([["transient", "=", false]])),
+            Some(vec!["model".to_string(), "name".to_string()]),
+            None,
+            None,
+            Some("model asc".to_string()),
+            None,
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(parse_installed_models(&rows))
+}
+
+fn parse_installed_models(rows: &Value) -> Vec<(String, String)> {
+    rows.as_array()
+        .map(|rows| {
+            rows.iter()
+                .filter_map(|row| {
+                    let model = row.get("model")?.as_str()?.to_string();
+                    let name = row
+                        .get("name")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or(&model)
+                        .to_string();
+                    Some((model, name))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 /// Read the list of instances
 async fn read_instances(pool: &OdooClientPool) -> Result<Value, Error> {
     let instances = pool.instance_names();
@@ -173,8 +353,85 @@ async fn read_models(pool: &OdooClientPool, instance: &str) -> Result<Value, Err
     }))
 }
 
-/// Read metadata for a specific model
-async fn read_metadata(pool: &OdooClientPool, instance: &str, model: &str) -> Result<Value, Error> {
+/// Read the fields_get output for a specific model; `resource` is either fields URI form
+async fn read_fields(pool: &OdooClientPool, resource: &ResourceUri) -> Result<Value, Error> {
+    let (instance, model) = match resource {
+        ResourceUri::Model { instance, model } | ResourceUri::ModelFields { instance, model } => {
+            (instance.as_str(), model.as_str())
+        }
+        _ => {
+            return Err(resource_err(format!(
+                "Not a fields URI: {}",
+                resource.to_uri()
+            )));
+        }
+    };
+    let client = pool
+        .get(instance)
+        .await
+        .map_err(|e| resource_err(e.to_string()))?;
+
+    let fields = client
+        .fields_get(model, None)
+        .await
+        .map_err(|e| resource_err(e.to_string()))?;
+
+    let uri = resource.to_uri();
+    Ok(json!({
+        "contents": [{
+            "uri": uri,
+            "mimeType": "application/json",
+            "text": serde_json::to_string_pretty(&fields)
+                .unwrap_or_else(|_| "{}".to_string())
+        }]
+    }))
+}
+
+/// Read a single record by id
+async fn read_record(
+    pool: &OdooClientPool,
+    instance: &str,
+    model: &str,
+    id: i64,
+) -> Result<Value, Error> {
+    let client = pool
+        .get(instance)
+        .await
+        .map_err(|e| resource_err(e.to_string()))?;
+
+    let rows = client
+        .read(model, vec![id], None, None)
+        .await
+        .map_err(|e| resource_err(e.to_string()))?;
+    let record = rows
+        .as_array()
+        .and_then(|arr| arr.first())
+        .cloned()
+        .ok_or_else(|| resource_err(format!("Record {}({}) not found", model, id)))?;
+
+    let uri = format!("odoo://{}/record/{}/{}", instance, model, id);
+    Ok(json!({
+        "contents": [{
+            "uri": uri,
+            "mimeType": "application/json",
+            "text": serde_json::to_string_pretty(&record)
+                .unwrap_or_else(|_| "{}".to_string())
+        }]
+    }))
+}
+
+/// Read metadata for a specific model; `resource` is either metadata URI form
+async fn read_metadata(pool: &OdooClientPool, resource: &ResourceUri) -> Result<Value, Error> {
+    let (instance, model) = match resource {
+        ResourceUri::ModelMetadata { instance, model }
+        | ResourceUri::Metadata { instance, model } => (instance.as_str(), model.as_str()),
+        _ => {
+            return Err(resource_err(format!(
+                "Not a metadata URI: {}",
+                resource.to_uri()
+            )));
+        }
+    };
     let client = pool
         .get(instance)
         .await
@@ -215,7 +472,7 @@ async fn read_metadata(pool: &OdooClientPool, instance: &str, model: &str) -> Re
         }
     });
 
-    let uri = format!("odoo://{}/metadata/{}", instance, model);
+    let uri = resource.to_uri();
     Ok(json!({
         "contents": [{
             "uri": uri,
@@ -259,6 +516,115 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_model_fields_uri() {
+        let uri = ResourceUri::parse("odoo://prod/models/sale.order").unwrap();
+        assert_eq!(
+            uri,
+            ResourceUri::Model {
+                instance: "prod".to_string(),
+                model: "sale.order".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_model_metadata_uri() {
+        let uri = ResourceUri::parse("odoo://prod/models/sale.order/metadata").unwrap();
+        assert_eq!(
+            uri,
+            ResourceUri::ModelMetadata {
+                instance: "prod".to_string(),
+                model: "sale.order".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_model_uri_rejects_extra_segments() {
+        assert!(ResourceUri::parse("odoo://prod/models/sale.order/fields").is_err());
+        assert!(ResourceUri::parse("odoo://prod/models//metadata").is_err());
+        assert!(ResourceUri::parse("odoo://prod/modelsx").is_err());
+    }
+
+    #[test]
+    fn test_roundtrip_model_uris() {
+        for original in [
+            "odoo://prod/models/res.partner",
+            "odoo://prod/models/res.partner/metadata",
+        ] {
+            assert_eq!(ResourceUri::parse(original).unwrap().to_uri(), original);
+        }
+    }
+
+    #[test]
+    fn test_parse_record_uri() {
+        let uri = ResourceUri::parse("odoo://prod/record/sale.order/42").unwrap();
+        assert_eq!(
+            uri,
+            ResourceUri::Record {
+                instance: "prod".to_string(),
+                model: "sale.order".to_string(),
+                id: 42
+            }
+        );
+        assert_eq!(uri.to_uri(), "odoo://prod/record/sale.order/42");
+    }
+
+    #[test]
+    fn test_parse_record_uri_rejects_bad_ids() {
+        assert!(ResourceUri::parse("odoo://prod/record/sale.order/abc").is_err());
+        assert!(ResourceUri::parse("odoo://prod/record/sale.order/0").is_err());
+        assert!(ResourceUri::parse("odoo://prod/record/sale.order").is_err());
+        assert!(ResourceUri::parse("odoo://prod/record//7").is_err());
+    }
+
+    #[test]
+    fn test_parse_model_fields_template_uri() {
+        let uri = ResourceUri::parse("odoo://prod/model/res.partner/fields").unwrap();
+        assert_eq!(
+            uri,
+            ResourceUri::ModelFields {
+                instance: "prod".to_string(),
+                model: "res.partner".to_string()
+            }
+        );
+        assert_eq!(uri.to_uri(), "odoo://prod/model/res.partner/fields");
+        assert!(ResourceUri::parse("odoo://prod/model/res.partner").is_err());
+    }
+
+    #[test]
+    fn test_resource_templates_parse_when_filled() {
+        let templates = list_resource_templates();
+        let templates = templates["resourceTemplates"].as_array().unwrap();
+        assert_eq!(templates.len(), 3);
+        for template in templates {
+            let filled = template["uriTemplate"]
+                .as_str()
+                .unwrap()
+                .replace("{instance}", "prod")
+                .replace("{model}", "res.partner")
+                .replace("{id}", "7");
+            assert!(ResourceUri::parse(&filled).is_ok(), "{filled}");
+        }
+    }
+
+    #[test]
+    fn test_parse_installed_models() {
+        let rows = json!([
+            {"id": 1, "model": "res.partner", "name": "Contact"},
+            {"id": 2, "model": "sale.order"},
+            {"id": 3, "name": "broken"}
+        ]);
+        assert_eq!(
+            parse_installed_models(&rows),
+            vec![
+                ("res.partner".to_string(), "Contact".to_string()),
+                ("sale.order".to_string(), "sale.order".to_string())
+            ]
+        );
+    }
+
     #[test]
     fn test_parse_invalid_scheme() {
         let result = ResourceUri::parse("http://example.com");
@@ -0,0 +1,137 @@
+//! Retention limits for the server-side stores, enforced by a background janitor.
+//!
+//! The policy comes from the `retention` object in server.json, so it can be changed through
+//! `POST /api/config/server` and is picked up on the next sweep without a restart:
+//!
+//! ```json
+//! { "retention": { "auditDays": 90, "auditMaxEntries": 100000 } }
+//! ```
+//!
+//! `MCP_RETENTION_AUDIT_DAYS` and `MCP_RETENTION_AUDIT_MAX_ENTRIES` take precedence. The janitor
+//! sweeps at startup and every `MCP_RETENTION_INTERVAL_SECS` (default one hour). The audit log
+//! is the only store that grows without bound; SSE replay buffers are already capped per session
+//! and dropped with the session.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{SecondsFormat, Utc};
+use serde::Deserialize;
+use tracing::{info, warn};
+
+use crate::mcp::audit::AuditLog;
+use crate::mcp::registry::Registry;
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(3600);
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionPolicy {
+    /// Drop audit entries older than this many days.
+    #[serde(default)]
+    pub audit_days: Option<u32>,
+    /// Keep at most this many audit entries, newest first.
+    #[serde(default)]
+    pub audit_max_entries: Option<u64>,
+}
+
+impl RetentionPolicy {
+    /// This policy with `MCP_RETENTION_*` overrides applied.
+    pub fn with_env_overrides(mut self) -> Self {
+        let var = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.trim().parse::<u64>().ok())
+        };
+        if let Some(days) = var("MCP_RETENTION_AUDIT_DAYS") {
+            self.audit_days = Some(days.min(u32::MAX as u64) as u32);
+        }
+        if let Some(max) = var("MCP_RETENTION_AUDIT_MAX_ENTRIES") {
+            self.audit_max_entries = Some(max);
+        }
+        self
+    }
+
+    /// Cut-off timestamp for audit entries, in the format the audit log stores.
+    fn audit_cutoff(&self) -> Option<String> {
+        let days = self.audit_days.filter(|days| *days > 0)?;
+        let cutoff = Utc::now() - chrono::Duration::days(days as i64);
+        Some(cutoff.to_rfc3339_opts(SecondsFormat::Millis, true))
+    }
+
+    /// Apply the policy to `log`; returns how many entries were removed.
+    pub async fn sweep(&self, log: &dyn AuditLog) -> anyhow::Result<u64> {
+        let cutoff = self.audit_cutoff();
+        if cutoff.is_none() && self.audit_max_entries.is_none() {
+            return Ok(0);
+        }
+        log.prune(cutoff.as_deref(), self.audit_max_entries).await
+    }
+}
+
+fn interval_from_env() -> Duration {
+    std::env::var("MCP_RETENTION_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_INTERVAL)
+}
+
+/// Sweep `audit` with the registry's current policy now and then on every interval.
+pub fn spawn_janitor(registry: Arc<Registry>, audit: Arc<dyn AuditLog>) {
+    let interval = interval_from_env();
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let policy = registry.retention().await;
+            match policy.sweep(audit.as_ref()).await {
+                Ok(0) => {}
+                Ok(removed) => info!(
+                    removed,
+                    backend = audit.backend(),
+                    "Pruned audit entries past retention"
+                ),
+                Err(e) => warn!(error = %e, "Audit retention sweep failed"),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::audit::{AuditEntry, AuditQuery, SqliteAuditLog};
+    use serde_json::json;
+
+    #[test]
+    fn policy_parses_from_server_json() {
+        let policy: RetentionPolicy =
+            serde_json::from_value(json!({"auditDays": 30, "auditMaxEntries": 500})).unwrap();
+        assert_eq!(policy.audit_days, Some(30));
+        assert_eq!(policy.audit_max_entries, Some(500));
+        assert_eq!(RetentionPolicy::default().audit_cutoff(), None);
+    }
+
+    #[tokio::test]
+    async fn sweep_drops_entries_past_the_age_limit() {
+        let log = SqliteAuditLog::open_in_memory().unwrap();
+        let mut old = AuditEntry::begin("", None, "odoo_read", &json!({}));
+        old.timestamp = "2020-01-01T00:00:00.000Z".into();
+        log.record(&old).await.unwrap();
+        log.record(&AuditEntry::begin("", None, "odoo_search", &json!({})))
+            .await
+            .unwrap();
+
+        assert_eq!(RetentionPolicy::default().sweep(&log).await.unwrap(), 0);
+        let policy = RetentionPolicy {
+            audit_days: Some(30),
+            ..RetentionPolicy::default()
+        };
+        assert_eq!(policy.sweep(&log).await.unwrap(), 1);
+        let left = log.query(&AuditQuery::default()).await.unwrap();
+        assert_eq!(left.len(), 1);
+        assert_eq!(left[0].tool, "odoo_search");
+    }
+}
@@ -2,15 +2,20 @@ use std::sync::Arc;
 
 use futures::StreamExt;
 use serde_json::json;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, mpsc};
 
 use mcp_rust_sdk::error::{Error, ErrorCode};
-use mcp_rust_sdk::protocol::{Request, Response, ResponseError};
+use mcp_rust_sdk::protocol::{Notification, Request, Response, ResponseError};
 use mcp_rust_sdk::server::ServerHandler;
 use mcp_rust_sdk::transport::{Message, Transport};
 
 use super::McpOdooHandler;
+use super::deprecations;
 
+/// Cancellation scope for the single stdio client.
+const STDIO_SCOPE: &str = "stdio";
+
+#[derive(Clone)]
 pub struct ServerCompat {
     transport: Arc<dyn Transport>,
     handler: Arc<McpOdooHandler>,
@@ -27,34 +32,92 @@ impl ServerCompat {
     }
 
     pub async fn start(&self) -> Result<(), Error> {
+        self.attach_client_requests();
         let mut stream = self.transport.receive();
         while let Some(message) = stream.next().await {
             match message? {
                 Message::Request(request) => {
-                    let response = match self.handle_request(request.clone()).await {
-                        Ok(resp) => resp,
-                        Err(err) => Response::error(request.id, ResponseError::from(err)),
-                    };
-                    self.transport.send(Message::Response(response)).await?;
+                    // Lifecycle requests run inline so ordering with `initialized` holds;
+                    // everything else runs concurrently so `notifications/cancelled`
+                    // can reach it while it is in flight.
+                    if matches!(request.method.as_str(), "initialize" | "shutdown") {
+                        let response = self.respond(request).await;
+                        self.transport.send(Message::Response(response)).await?;
+                    } else {
+                        let this = self.clone();
+                        tokio::spawn(async move {
+                            let response = this.respond(request).await;
+                            if let Err(err) = this.transport.send(Message::Response(response)).await
+                            {
+                                tracing::warn!("Failed to send stdio response: {}", err);
+                            }
+                        });
+                    }
                 }
                 Message::Notification(notification) => match notification.method.as_str() {
                     "exit" => break,
                     "initialized" | "notifications/initialized" => {
                         *self.initialized.write().await = true;
                     }
+                    "notifications/cancelled" => {
+                        let _ = self
+                            .handler
+                            .handle_request(
+                                STDIO_SCOPE,
+                                None,
+                                None,
+                                &notification.method,
+                                notification.params,
+                            )
+                            .await;
+                    }
                     _ => {}
                 },
-                Message::Response(_) => {
-                    return Err(Error::protocol(
-                        ErrorCode::InvalidRequest,
-                        "Server received unexpected response",
-                    ));
+                Message::Response(response) => {
+                    // Answers to server-to-client requests (e.g. sampling).
+                    let resolved = serde_json::to_value(&response)
+                        .is_ok_and(|v| self.handler.client_requests().resolve(&v));
+                    if !resolved {
+                        tracing::warn!("Ignoring unexpected response from stdio client");
+                    }
                 }
             }
         }
+        self.handler.end_session(STDIO_SCOPE);
         Ok(())
     }
 
+    /// Forward server-to-client requests and notifications onto stdout.
+    fn attach_client_requests(&self) {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        self.handler.client_requests().attach(STDIO_SCOPE, tx);
+        let transport = self.transport.clone();
+        tokio::spawn(async move {
+            while let Some(message) = rx.recv().await {
+                let outgoing = if message.get("id").is_some() {
+                    serde_json::from_value::<Request>(message).map(Message::Request)
+                } else {
+                    serde_json::from_value::<Notification>(message).map(Message::Notification)
+                };
+                let sent = match outgoing {
+                    Ok(outgoing) => transport.send(outgoing).await,
+                    Err(err) => Err(err.into()),
+                };
+                if let Err(err) = sent {
+                    tracing::warn!("Failed to send stdio message to client: {}", err);
+                }
+            }
+        });
+    }
+
+    async fn respond(&self, request: Request) -> Response {
+        let id = request.id.clone();
+        match self.handle_request(request).await {
+            Ok(resp) => resp,
+            Err(err) => Response::error(id, ResponseError::from(err)),
+        }
+    }
+
     async fn handle_request(&self, request: Request) -> Result<Response, Error> {
         let initialized = *self.initialized.read().await;
 
@@ -68,6 +131,9 @@ impl ServerCompat {
                 }
 
                 let params = request.params.unwrap_or(serde_json::json!({}));
+                self.handler
+                    .client_requests()
+                    .set_client_capabilities(STDIO_SCOPE, params.get("capabilities"));
 
                 // Get protocol version from client or use default
                 let default_protocol = self.handler.protocol_version_default().await;
@@ -82,12 +148,13 @@ impl ServerCompat {
                 let odoo_instances = self.handler.instance_names();
 
                 // Build MCP-compliant initialize response with protocolVersion, capabilities, serverInfo
-                let result = json!({
+                let mut result = json!({
                     "protocolVersion": protocol_version,
                     "capabilities": {
                         "tools": { "listChanged": true },
                         "prompts": { "listChanged": true },
-                        "resources": {},
+                        "resources": { "subscribe": true },
+                        "completions": {},
                         "experimental": {
                             "odooInstances": { "available": odoo_instances }
                         }
@@ -99,6 +166,11 @@ impl ServerCompat {
                     "instructions": instructions
                 });
 
+                if let Some(deprecation) = deprecations::for_protocol_version(&protocol_version) {
+                    self.handler.deprecations().record(deprecation, STDIO_SCOPE);
+                    deprecations::attach(&mut result, &[deprecation]);
+                }
+
                 Ok(Response::success(request.id, Some(result)))
             }
             "shutdown" => {
@@ -116,7 +188,7 @@ impl ServerCompat {
                 // Some clients (like Cursor) may not send initialized before listing
                 let allow_without_init = matches!(
                     request.method.as_str(),
-                    "tools/list" | "prompts/list" | "resources/list"
+                    "tools/list" | "prompts/list" | "resources/list" | "resources/templates/list"
                 );
                 if !initialized && !allow_without_init {
                     return Err(Error::protocol(
@@ -124,9 +196,16 @@ impl ServerCompat {
                         "Server not initialized",
                     ));
                 }
+                let request_id = serde_json::to_value(&request.id).ok();
                 let result = self
                     .handler
-                    .handle_method(&request.method, request.params)
+                    .handle_request(
+                        STDIO_SCOPE,
+                        None,
+                        request_id.as_ref(),
+                        &request.method,
+                        request.params,
+                    )
                     .await?;
                 Ok(Response::success(request.id, Some(result)))
             }
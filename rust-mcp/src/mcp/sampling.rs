@@ -0,0 +1,360 @@
+//! Server-to-client requests, used for MCP sampling (`sampling/createMessage`), and
+//! server-initiated notifications such as `notifications/resources/updated`.
+//!
+//! Each transport attaches an outbound channel per client scope (the stdio stream, or an
+//! HTTP session) and forwards the JSON-RPC request objects it receives to the wire: stdout
+//! for stdio, the session's SSE stream for Streamable HTTP. When the client's response
+//! arrives, the transport hands it to [`ClientRequests::resolve`], which completes the
+//! waiting call by JSON-RPC id.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde_json::{Value, json};
+use thiserror::Error;
+use tokio::sync::{mpsc, oneshot};
+
+/// How long a tool waits for the client to answer a sampling request.
+pub const SAMPLING_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Error)]
+pub enum ClientRequestError {
+    #[error("client did not advertise the sampling capability")]
+    Unsupported,
+    #[error("client connection is not available for server-to-client requests")]
+    Disconnected,
+    #[error("client did not answer within {0:?}")]
+    Timeout(Duration),
+    #[error("client rejected the request: {0}")]
+    Rejected(String),
+}
+
+type Reply = Result<Value, Value>;
+
+#[derive(Default)]
+struct Inner {
+    channels: HashMap<String, mpsc::UnboundedSender<Value>>,
+    sampling: HashSet<String>,
+    pending: HashMap<String, oneshot::Sender<Reply>>,
+    next_id: u64,
+}
+
+#[derive(Clone, Default)]
+pub struct ClientRequests {
+    inner: Arc<Mutex<Inner>>,
+}
+
+/// Removes a pending request if the caller gives up (timeout or cancellation).
+struct PendingGuard<'a> {
+    requests: &'a ClientRequests,
+    id: String,
+}
+
+impl Drop for PendingGuard<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut inner) = self.requests.inner.lock() {
+            inner.pending.remove(&self.id);
+        }
+    }
+}
+
+impl ClientRequests {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the channel that delivers server-to-client requests for `scope`.
+    pub fn attach(&self, scope: &str, outbound: mpsc::UnboundedSender<Value>) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.channels.insert(scope.to_string(), outbound);
+        }
+    }
+
+    /// Forget a client scope; its forwarder task ends once the sender is dropped.
+    pub fn detach(&self, scope: &str) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.channels.remove(scope);
+            inner.sampling.remove(scope);
+        }
+    }
+
+    /// Record the client capabilities sent with `initialize`.
+    pub fn set_client_capabilities(&self, scope: &str, capabilities: Option<&Value>) {
+        let sampling = capabilities.and_then(|c| c.get("sampling")).is_some();
+        if let Ok(mut inner) = self.inner.lock() {
+            if sampling {
+                inner.sampling.insert(scope.to_string());
+            } else {
+                inner.sampling.remove(scope);
+            }
+        }
+    }
+
+    pub fn supports_sampling(&self, scope: &str) -> bool {
+        self.inner
+            .lock()
+            .map(|inner| inner.sampling.contains(scope) && inner.channels.contains_key(scope))
+            .unwrap_or(false)
+    }
+
+    /// Whether an outbound channel is attached for `scope`.
+    pub fn is_attached(&self, scope: &str) -> bool {
+        self.inner
+            .lock()
+            .map(|inner| inner.channels.contains_key(scope))
+            .unwrap_or(false)
+    }
+
+    /// Send a JSON-RPC notification to the client; returns `false` if it could not be queued.
+    pub fn notify(&self, scope: &str, method: &str, params: Value) -> bool {
+        let Some(outbound) = self
+            .inner
+            .lock()
+            .ok()
+            .and_then(|inner| inner.channels.get(scope).cloned())
+        else {
+            return false;
+        };
+        outbound
+            .send(json!({
+                "jsonrpc": "2.0",
+                "method": method,
+                "params": params,
+            }))
+            .is_ok()
+    }
+
+    /// Send a JSON-RPC notification to every attached client.
+    pub fn notify_all(&self, method: &str, params: Value) {
+        let scopes: Vec<String> = self
+            .inner
+            .lock()
+            .map(|inner| inner.channels.keys().cloned().collect())
+            .unwrap_or_default();
+        for scope in scopes {
+            self.notify(&scope, method, params.clone());
+        }
+    }
+
+    /// Send a JSON-RPC request to the client and wait for its response.
+    pub async fn request(
+        &self,
+        scope: &str,
+        method: &str,
+        params: Value,
+        timeout: Duration,
+    ) -> Result<Value, ClientRequestError> {
+        let (tx, rx) = oneshot::channel();
+        let id = {
+            let mut inner = self
+                .inner
+                .lock()
+                .map_err(|_| ClientRequestError::Disconnected)?;
+            let outbound = inner
+                .channels
+                .get(scope)
+                .cloned()
+                .ok_or(ClientRequestError::Disconnected)?;
+            inner.next_id += 1;
+            // Prefixed so server ids never collide with ids the client picks.
+            let id = format!("srv-{}", inner.next_id);
+            let message = json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": method,
+                "params": params,
+            });
+            outbound
+                .send(message)
+                .map_err(|_| ClientRequestError::Disconnected)?;
+            inner.pending.insert(id.clone(), tx);
+            id
+        };
+        let _guard = PendingGuard { requests: self, id };
+
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(Ok(result))) => Ok(result),
+            Ok(Ok(Err(error))) => Err(ClientRequestError::Rejected(
+                error
+                    .get("message")
+                    .and_then(|m| m.as_str())
+                    .map(String::from)
+                    .unwrap_or_else(|| error.to_string()),
+            )),
+            Ok(Err(_)) => Err(ClientRequestError::Disconnected),
+            Err(_) => Err(ClientRequestError::Timeout(timeout)),
+        }
+    }
+
+    /// Complete a pending request from a client JSON-RPC response.
+    ///
+    /// Returns `false` when the response does not match any pending request.
+    pub fn resolve(&self, response: &Value) -> bool {
+        let Some(id) = response.get("id").and_then(|id| id.as_str()) else {
+            return false;
+        };
+        let Some(tx) = self
+            .inner
+            .lock()
+            .ok()
+            .and_then(|mut inner| inner.pending.remove(id))
+        else {
+            return false;
+        };
+        let reply = match response.get("error") {
+            Some(error) if !error.is_null() => Err(error.clone()),
+            _ => Ok(response.get("result").cloned().unwrap_or(Value::Null)),
+        };
+        tx.send(reply).is_ok()
+    }
+}
+
+/// Whether a JSON-RPC message is a response (has an id and no method).
+pub fn is_response(message: &Value) -> bool {
+    message.get("method").is_none()
+        && message.get("id").is_some()
+        && (message.get("result").is_some() || message.get("error").is_some())
+}
+
+/// Handle for issuing sampling requests on behalf of one client.
+#[derive(Clone)]
+pub struct Sampler {
+    requests: ClientRequests,
+    scope: String,
+}
+
+impl Sampler {
+    /// A sampler for `scope`, or `None` when that client cannot be sampled.
+    pub fn for_scope(requests: &ClientRequests, scope: &str) -> Option<Self> {
+        requests.supports_sampling(scope).then(|| Self {
+            requests: requests.clone(),
+            scope: scope.to_string(),
+        })
+    }
+
+    /// Ask the client LLM for a single text completion.
+    pub async fn create_message(
+        &self,
+        system_prompt: &str,
+        prompt: &str,
+        max_tokens: u32,
+    ) -> Result<String, ClientRequestError> {
+        let params = json!({
+            "messages": [{
+                "role": "user",
+                "content": { "type": "text", "text": prompt }
+            }],
+            "systemPrompt": system_prompt,
+            "includeContext": "none",
+            "temperature": 0,
+            "maxTokens": max_tokens,
+        });
+        let result = self
+            .requests
+            .request(
+                &self.scope,
+                "sampling/createMessage",
+                params,
+                SAMPLING_TIMEOUT,
+            )
+            .await?;
+        result
+            .get("content")
+            .and_then(|c| c.get("text"))
+            .and_then(|t| t.as_str())
+            .map(String::from)
+            .ok_or_else(|| {
+                ClientRequestError::Rejected("sampling result has no text content".to_string())
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn request_round_trips_through_resolve() {
+        let requests = ClientRequests::new();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        requests.attach("s1", tx);
+        requests.set_client_capabilities("s1", Some(&json!({"sampling": {}})));
+        assert!(requests.supports_sampling("s1"));
+
+        let responder = requests.clone();
+        tokio::spawn(async move {
+            let outgoing = rx.recv().await.unwrap();
+            assert_eq!(outgoing["method"], "sampling/createMessage");
+            let reply = json!({
+                "jsonrpc": "2.0",
+                "id": outgoing["id"],
+                "result": {"role": "assistant", "content": {"type": "text", "text": "[]"}}
+            });
+            assert!(is_response(&reply));
+            assert!(responder.resolve(&reply));
+        });
+
+        let sampler = Sampler::for_scope(&requests, "s1").unwrap();
+        let text = sampler.create_message("sys", "prompt", 100).await.unwrap();
+        assert_eq!(text, "[]");
+    }
+
+    #[tokio::test]
+    async fn request_times_out_and_cleans_up() {
+        let requests = ClientRequests::new();
+        let (tx, _rx) = mpsc::unbounded_channel();
+        requests.attach("s1", tx);
+        let err = requests
+            .request("s1", "ping", json!({}), Duration::from_millis(10))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ClientRequestError::Timeout(_)));
+        assert!(!requests.resolve(&json!({"jsonrpc": "2.0", "id": "srv-1", "result": {}})));
+    }
+
+    #[test]
+    fn notify_requires_an_attached_channel() {
+        let requests = ClientRequests::new();
+        assert!(!requests.notify("s1", "notifications/resources/updated", json!({})));
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        requests.attach("s1", tx);
+        assert!(requests.is_attached("s1"));
+        assert!(requests.notify("s1", "notifications/resources/updated", json!({"uri": "x"})));
+        let sent = rx.try_recv().unwrap();
+        assert_eq!(sent["method"], "notifications/resources/updated");
+        assert!(sent.get("id").is_none());
+        requests.detach("s1");
+        assert!(!requests.is_attached("s1"));
+    }
+
+    #[test]
+    fn notify_all_reaches_every_scope() {
+        let requests = ClientRequests::new();
+        let (tx1, mut rx1) = mpsc::unbounded_channel();
+        let (tx2, mut rx2) = mpsc::unbounded_channel();
+        requests.attach("s1", tx1);
+        requests.attach("s2", tx2);
+        requests.notify_all("notifications/tools/list_changed", json!({}));
+        assert_eq!(
+            rx1.try_recv().unwrap()["method"],
+            "notifications/tools/list_changed"
+        );
+        assert_eq!(
+            rx2.try_recv().unwrap()["method"],
+            "notifications/tools/list_changed"
+        );
+    }
+
+    #[test]
+    fn sampler_requires_capability_and_channel() {
+        let requests = ClientRequests::new();
+        requests.set_client_capabilities("s1", Some(&json!({"sampling": {}})));
+        assert!(Sampler::for_scope(&requests, "s1").is_none());
+        let (tx, _rx) = mpsc::unbounded_channel();
+        requests.attach("s1", tx);
+        assert!(Sampler::for_scope(&requests, "s1").is_some());
+        requests.set_client_capabilities("s1", Some(&json!({"roots": {}})));
+        assert!(Sampler::for_scope(&requests, "s1").is_none());
+    }
+}
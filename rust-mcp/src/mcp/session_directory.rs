@@ -0,0 +1,205 @@
+//! Live HTTP sessions, for the config server's admin API.
+//!
+//! The HTTP transport opens an entry when a session initializes and the handler closes it when
+//! the session ends; every tool call is recorded against its session. `GET /api/sessions`
+//! lists the entries and `DELETE /api/sessions/{id}` asks the transport to terminate one, which
+//! drops it from the session store and closes its SSE channel like an idle expiry.
+
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::broadcast;
+
+/// Tool calls kept per session.
+const RECENT_TOOLS: usize = 10;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentTool {
+    pub tool: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    pub at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    created_at: DateTime<Utc>,
+    last_activity: DateTime<Utc>,
+    protocol_version: String,
+    client_info: Option<Value>,
+    token: Option<String>,
+    instances: BTreeSet<String>,
+    recent_tools: VecDeque<RecentTool>,
+}
+
+/// One session as listed by `GET /api/sessions`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSummary {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub age_secs: i64,
+    pub last_activity: DateTime<Utc>,
+    pub protocol_version: String,
+    /// `clientInfo` the client sent with `initialize`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_info: Option<Value>,
+    /// Name of the API token the session initialized with.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// Instances the session has called tools on.
+    pub instances: Vec<String>,
+    /// Latest tool calls, newest first.
+    pub recent_tools: Vec<RecentTool>,
+}
+
+#[derive(Clone)]
+pub struct SessionDirectory {
+    entries: Arc<Mutex<HashMap<String, Entry>>>,
+    terminations: broadcast::Sender<String>,
+}
+
+impl Default for SessionDirectory {
+    fn default() -> Self {
+        Self {
+            entries: Arc::default(),
+            terminations: broadcast::channel(16).0,
+        }
+    }
+}
+
+impl SessionDirectory {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Entry>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn open(
+        &self,
+        id: &str,
+        protocol_version: &str,
+        client_info: Option<Value>,
+        token: Option<&str>,
+    ) {
+        let now = Utc::now();
+        self.lock().insert(
+            id.to_string(),
+            Entry {
+                created_at: now,
+                last_activity: now,
+                protocol_version: protocol_version.to_string(),
+                client_info,
+                token: token.map(str::to_string),
+                instances: BTreeSet::new(),
+                recent_tools: VecDeque::new(),
+            },
+        );
+    }
+
+    pub fn close(&self, id: &str) {
+        self.lock().remove(id);
+    }
+
+    /// Record a tool call; sessions that were never opened (stdio) are ignored.
+    pub fn record_tool(&self, id: &str, tool: &str, instance: Option<&str>) {
+        let mut entries = self.lock();
+        let Some(entry) = entries.get_mut(id) else {
+            return;
+        };
+        let now = Utc::now();
+        entry.last_activity = now;
+        if let Some(instance) = instance {
+            entry.instances.insert(instance.to_string());
+        }
+        if entry.recent_tools.len() == RECENT_TOOLS {
+            entry.recent_tools.pop_back();
+        }
+        entry.recent_tools.push_front(RecentTool {
+            tool: tool.to_string(),
+            instance: instance.map(str::to_string),
+            at: now,
+        });
+    }
+
+    /// Sessions, oldest first.
+    pub fn list(&self) -> Vec<SessionSummary> {
+        let now = Utc::now();
+        let mut sessions: Vec<SessionSummary> = self
+            .lock()
+            .iter()
+            .map(|(id, entry)| SessionSummary {
+                id: id.clone(),
+                created_at: entry.created_at,
+                age_secs: (now - entry.created_at).num_seconds(),
+                last_activity: entry.last_activity,
+                protocol_version: entry.protocol_version.clone(),
+                client_info: entry.client_info.clone(),
+                token: entry.token.clone(),
+                instances: entry.instances.iter().cloned().collect(),
+                recent_tools: entry.recent_tools.iter().cloned().collect(),
+            })
+            .collect();
+        sessions.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+        sessions
+    }
+
+    /// Ask the transport to end `id`. Returns whether the session was live.
+    pub fn terminate(&self, id: &str) -> bool {
+        if self.lock().remove(id).is_none() {
+            return false;
+        }
+        let _ = self.terminations.send(id.to_string());
+        true
+    }
+
+    /// Session ids passed to [`SessionDirectory::terminate`].
+    pub fn subscribe_terminations(&self) -> broadcast::Receiver<String> {
+        self.terminations.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn records_recent_tools_per_session() {
+        let directory = SessionDirectory::default();
+        directory.open(
+            "s1",
+            "2025-11-25",
+            Some(json!({"name": "cursor", "version": "1.0"})),
+            Some("ops"),
+        );
+        directory.record_tool("stdio", "odoo_read", Some("prod"));
+        for n in 0..12 {
+            directory.record_tool("s1", &format!("tool_{n}"), Some("prod"));
+        }
+        directory.record_tool("s1", "odoo_connect", None);
+
+        let sessions = directory.list();
+        assert_eq!(sessions.len(), 1);
+        let session = &sessions[0];
+        assert_eq!(session.token.as_deref(), Some("ops"));
+        assert_eq!(session.instances, ["prod"]);
+        assert_eq!(session.recent_tools.len(), RECENT_TOOLS);
+        assert_eq!(session.recent_tools[0].tool, "odoo_connect");
+        assert_eq!(session.recent_tools[1].tool, "tool_11");
+    }
+
+    #[tokio::test]
+    async fn terminate_notifies_the_transport_once() {
+        let directory = SessionDirectory::default();
+        let mut terminations = directory.subscribe_terminations();
+        directory.open("s1", "2025-11-25", None, None);
+
+        assert!(directory.terminate("s1"));
+        assert!(!directory.terminate("s1"));
+        assert_eq!(terminations.recv().await.unwrap(), "s1");
+        assert!(directory.list().is_empty());
+    }
+}
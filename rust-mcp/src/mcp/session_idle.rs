@@ -0,0 +1,117 @@
+//! Idle tracking for HTTP sessions.
+//!
+//! Every request naming a session marks it active; a background sweeper in the HTTP transport
+//! expires sessions idle for longer than `MCP_SESSION_IDLE_TTL_SECS` (default 3600, `0`
+//! disables) so their SSE channels and per-session state do not pile up.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const DEFAULT_IDLE_TTL_SECS: u64 = 3600;
+
+/// Idle TTL from `MCP_SESSION_IDLE_TTL_SECS`; None disables expiry.
+pub fn idle_ttl_from_env() -> Option<Duration> {
+    let secs = std::env::var("MCP_SESSION_IDLE_TTL_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_IDLE_TTL_SECS);
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+/// How often the sweeper looks for idle sessions: a quarter of the TTL, within 100ms..60s.
+pub fn sweep_interval(ttl: Duration) -> Duration {
+    (ttl / 4).clamp(Duration::from_millis(100), Duration::from_secs(60))
+}
+
+/// Last activity per session id.
+#[derive(Clone, Default)]
+pub struct SessionActivity {
+    last_seen: Arc<Mutex<HashMap<String, Instant>>>,
+}
+
+impl SessionActivity {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn touch(&self, session_id: &str) {
+        self.touch_at(session_id, Instant::now());
+    }
+
+    fn touch_at(&self, session_id: &str, now: Instant) {
+        self.lock().insert(session_id.to_string(), now);
+    }
+
+    pub fn forget(&self, session_id: &str) {
+        self.lock().remove(session_id);
+    }
+
+    /// Whether the session made a request within `window` before `now`.
+    pub fn active_within(&self, session_id: &str, window: Duration, now: Instant) -> bool {
+        self.lock()
+            .get(session_id)
+            .is_some_and(|seen| now.saturating_duration_since(*seen) <= window)
+    }
+
+    /// Remove and return the sessions idle for longer than `ttl`.
+    pub fn take_idle(&self, ttl: Duration, now: Instant) -> Vec<String> {
+        let mut last_seen = self.lock();
+        let idle: Vec<String> = last_seen
+            .iter()
+            .filter(|(_, seen)| now.saturating_duration_since(**seen) > ttl)
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in &idle {
+            last_seen.remove(id);
+        }
+        idle
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Instant>> {
+        self.last_seen.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn take_idle_returns_only_stale_sessions_once() {
+        let activity = SessionActivity::new();
+        let start = Instant::now();
+        activity.touch_at("old", start);
+        activity.touch_at("fresh", start + Duration::from_secs(50));
+
+        let now = start + Duration::from_secs(70);
+        assert_eq!(activity.take_idle(Duration::from_secs(60), now), ["old"]);
+        assert!(activity.take_idle(Duration::from_secs(60), now).is_empty());
+
+        assert!(activity.active_within("fresh", Duration::from_secs(30), now));
+        assert!(!activity.active_within("fresh", Duration::from_secs(10), now));
+        activity.forget("fresh");
+        let later = now + Duration::from_secs(600);
+        assert!(
+            activity
+                .take_idle(Duration::from_secs(60), later)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn sweep_interval_is_bounded() {
+        assert_eq!(
+            sweep_interval(Duration::from_secs(3600)),
+            Duration::from_secs(60)
+        );
+        assert_eq!(
+            sweep_interval(Duration::from_secs(8)),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            sweep_interval(Duration::from_millis(1)),
+            Duration::from_millis(100)
+        );
+    }
+}
@@ -0,0 +1,565 @@
+//! Pluggable storage for Streamable HTTP sessions.
+//!
+//! Sessions (negotiated protocol version, `initialized` flag, SSE event counter and
+//! the replay buffer used for `Last-Event-ID` resumability) live behind the
+//! [`SessionStore`] trait so they can survive restarts and be shared across replicas.
+//!
+//! Backends are selected with `MCP_SESSION_STORE`:
+//! - `memory` (default): process-local, lost on restart
+//! - `sqlite`: file at `MCP_SESSION_STORE_PATH` (default `mcp-sessions.db`)
+//! - `redis`: server at `MCP_SESSION_STORE_URL` (requires the `redis-sessions` feature)
+
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::Mutex;
+
+/// Maximum number of events to buffer for resumability per session
+pub const MAX_EVENT_BUFFER_SIZE: usize = 100;
+
+/// Stored SSE event for resumability
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StoredEvent {
+    pub id: String,
+    pub data: Value,
+}
+
+/// Persisted per-session state.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SessionRecord {
+    pub initialized: bool,
+    pub protocol_version: String,
+}
+
+impl SessionRecord {
+    pub fn new(protocol_version: impl Into<String>) -> Self {
+        Self {
+            initialized: true,
+            protocol_version: protocol_version.into(),
+        }
+    }
+}
+
+#[async_trait]
+pub trait SessionStore: Send + Sync {
+    /// Short backend name for logs ("memory", "sqlite", "redis").
+    fn backend(&self) -> &'static str;
+
+    async fn get(&self, session_id: &str) -> anyhow::Result<Option<SessionRecord>>;
+
+    async fn put(&self, session_id: &str, record: &SessionRecord) -> anyhow::Result<()>;
+
+    /// Remove a session and its event buffer. Returns whether it existed.
+    async fn remove(&self, session_id: &str) -> anyhow::Result<bool>;
+
+    /// Allocate the next monotonically increasing event sequence number for a session.
+    async fn next_event_seq(&self, session_id: &str) -> anyhow::Result<u64>;
+
+    /// Append an event to the session's replay buffer, keeping at most
+    /// [`MAX_EVENT_BUFFER_SIZE`] entries.
+    async fn append_event(&self, session_id: &str, event: &StoredEvent) -> anyhow::Result<()>;
+
+    /// Events recorded after `last_event_id`. Empty when the id is unknown.
+    async fn events_after(
+        &self,
+        session_id: &str,
+        last_event_id: &str,
+    ) -> anyhow::Result<Vec<StoredEvent>>;
+
+    /// Generate next event ID for this session
+    async fn next_event_id(&self, session_id: &str) -> anyhow::Result<String> {
+        let seq = self.next_event_seq(session_id).await?;
+        Ok(format!("{}:{}", session_id, seq))
+    }
+}
+
+fn events_after_in(buffer: &VecDeque<StoredEvent>, last_event_id: &str) -> Vec<StoredEvent> {
+    let mut found = false;
+    let mut result = Vec::new();
+    for event in buffer.iter() {
+        if found {
+            result.push(event.clone());
+        } else if event.id == last_event_id {
+            found = true;
+        }
+    }
+    result
+}
+
+#[derive(Default)]
+struct MemoryEntry {
+    record: Option<SessionRecord>,
+    counter: u64,
+    events: VecDeque<StoredEvent>,
+}
+
+/// Process-local session store (previous default behavior).
+#[derive(Default, Clone)]
+pub struct MemorySessionStore {
+    inner: Arc<Mutex<HashMap<String, MemoryEntry>>>,
+}
+
+impl MemorySessionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl SessionStore for MemorySessionStore {
+    fn backend(&self) -> &'static str {
+        "memory"
+    }
+
+    async fn get(&self, session_id: &str) -> anyhow::Result<Option<SessionRecord>> {
+        let guard = self.inner.lock().await;
+        Ok(guard.get(session_id).and_then(|e| e.record.clone()))
+    }
+
+    async fn put(&self, session_id: &str, record: &SessionRecord) -> anyhow::Result<()> {
+        let mut guard = self.inner.lock().await;
+        guard.entry(session_id.to_string()).or_default().record = Some(record.clone());
+        Ok(())
+    }
+
+    async fn remove(&self, session_id: &str) -> anyhow::Result<bool> {
+        let mut guard = self.inner.lock().await;
+        Ok(guard
+            .remove(session_id)
+            .is_some_and(|entry| entry.record.is_some()))
+    }
+
+    async fn next_event_seq(&self, session_id: &str) -> anyhow::Result<u64> {
+        let mut guard = self.inner.lock().await;
+        let entry = guard.entry(session_id.to_string()).or_default();
+        let seq = entry.counter;
+        entry.counter += 1;
+        Ok(seq)
+    }
+
+    async fn append_event(&self, session_id: &str, event: &StoredEvent) -> anyhow::Result<()> {
+        let mut guard = self.inner.lock().await;
+        let buffer = &mut guard.entry(session_id.to_string()).or_default().events;
+        if buffer.len() >= MAX_EVENT_BUFFER_SIZE {
+            buffer.pop_front();
+        }
+        buffer.push_back(event.clone());
+        Ok(())
+    }
+
+    async fn events_after(
+        &self,
+        session_id: &str,
+        last_event_id: &str,
+    ) -> anyhow::Result<Vec<StoredEvent>> {
+        let guard = self.inner.lock().await;
+        Ok(guard
+            .get(session_id)
+            .map(|e| events_after_in(&e.events, last_event_id))
+            .unwrap_or_default())
+    }
+}
+
+/// SQLite-backed session store. Survives restarts; can be shared by replicas on the
+/// same host (or a shared volume).
+#[derive(Clone)]
+pub struct SqliteSessionStore {
+    conn: Arc<std::sync::Mutex<rusqlite::Connection>>,
+}
+
+impl SqliteSessionStore {
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let conn = rusqlite::Connection::open(path.as_ref())?;
+        Self::init(conn)
+    }
+
+    pub fn open_in_memory() -> anyhow::Result<Self> {
+        Self::init(rusqlite::Connection::open_in_memory()?)
+    }
+
+    fn init(conn: rusqlite::Connection) -> anyhow::Result<Self> {
+        conn.busy_timeout(std::time::Duration::from_secs(5))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS mcp_sessions (
+                id TEXT PRIMARY KEY,
+                record TEXT,
+                event_counter INTEGER NOT NULL DEFAULT 0
+            );
+            CREATE TABLE IF NOT EXISTS mcp_session_events (
+                seq INTEGER PRIMARY KEY AUTOINCREMENT,
+                session_id TEXT NOT NULL,
+                event_id TEXT NOT NULL,
+                data TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS mcp_session_events_session
+                ON mcp_session_events (session_id, seq);",
+        )?;
+        Ok(Self {
+            conn: Arc::new(std::sync::Mutex::new(conn)),
+        })
+    }
+
+    async fn with_conn<T, F>(&self, f: F) -> anyhow::Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut rusqlite::Connection) -> rusqlite::Result<T> + Send + 'static,
+    {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut guard = conn
+                .lock()
+                .map_err(|_| anyhow::anyhow!("session store lock poisoned"))?;
+            f(&mut guard).map_err(anyhow::Error::from)
+        })
+        .await?
+    }
+}
+
+#[async_trait]
+impl SessionStore for SqliteSessionStore {
+    fn backend(&self) -> &'static str {
+        "sqlite"
+    }
+
+    async fn get(&self, session_id: &str) -> anyhow::Result<Option<SessionRecord>> {
+        let id = session_id.to_string();
+        let raw: Option<String> = self
+            .with_conn(move |conn| {
+                let mut stmt = conn.prepare("SELECT record FROM mcp_sessions WHERE id = ?1")?;
+                let mut rows = stmt.query([id])?;
+                match rows.next()? {
+                    Some(row) => row.get(0),
+                    None => Ok(None),
+                }
+            })
+            .await?;
+        raw.map(|s| serde_json::from_str(&s).map_err(anyhow::Error::from))
+            .transpose()
+    }
+
+    async fn put(&self, session_id: &str, record: &SessionRecord) -> anyhow::Result<()> {
+        let id = session_id.to_string();
+        let raw = serde_json::to_string(record)?;
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT INTO mcp_sessions (id, record) VALUES (?1, ?2)
+                 ON CONFLICT(id) DO UPDATE SET record = excluded.record",
+                rusqlite::params![id, raw],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn remove(&self, session_id: &str) -> anyhow::Result<bool> {
+        let id = session_id.to_string();
+        self.with_conn(move |conn| {
+            let tx = conn.transaction()?;
+            tx.execute(
+                "DELETE FROM mcp_session_events WHERE session_id = ?1",
+                [&id],
+            )?;
+            let existed: bool = tx
+                .query_row(
+                    "SELECT record IS NOT NULL FROM mcp_sessions WHERE id = ?1",
+                    [&id],
+                    |row| row.get(0),
+                )
+                .unwrap_or(false);
+            tx.execute("DELETE FROM mcp_sessions WHERE id = ?1", [&id])?;
+            tx.commit()?;
+            Ok(existed)
+        })
+        .await
+    }
+
+    async fn next_event_seq(&self, session_id: &str) -> anyhow::Result<u64> {
+        let id = session_id.to_string();
+        self.with_conn(move |conn| {
+            let next: i64 = conn.query_row(
+                "INSERT INTO mcp_sessions (id, event_counter) VALUES (?1, 1)
+                 ON CONFLICT(id) DO UPDATE SET event_counter = event_counter + 1
+                 RETURNING event_counter",
+                [id],
+                |row| row.get(0),
+            )?;
+            Ok((next - 1).max(0) as u64)
+        })
+        .await
+    }
+
+    async fn append_event(&self, session_id: &str, event: &StoredEvent) -> anyhow::Result<()> {
+        let id = session_id.to_string();
+        let event_id = event.id.clone();
+        let data = event.data.to_string();
+        self.with_conn(move |conn| {
+            let tx = conn.transaction()?;
+            tx.execute(
+                "INSERT INTO mcp_session_events (session_id, event_id, data) VALUES (?1, ?2, ?3)",
+                rusqlite::params![id, event_id, data],
+            )?;
+            tx.execute(
+                "DELETE FROM mcp_session_events WHERE session_id = ?1 AND seq NOT IN (
+                    SELECT seq FROM mcp_session_events WHERE session_id = ?1
+                    ORDER BY seq DESC LIMIT ?2
+                )",
+                rusqlite::params![id, MAX_EVENT_BUFFER_SIZE as i64],
+            )?;
+            tx.commit()
+        })
+        .await
+    }
+
+    async fn events_after(
+        &self,
+        session_id: &str,
+        last_event_id: &str,
+    ) -> anyhow::Result<Vec<StoredEvent>> {
+        let id = session_id.to_string();
+        let rows: Vec<(String, String)> = self
+            .with_conn(move |conn| {
+                let mut stmt = conn.prepare(
+                    "SELECT event_id, data FROM mcp_session_events
+                     WHERE session_id = ?1 ORDER BY seq ASC",
+                )?;
+                stmt.query_map([id], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .collect()
+            })
+            .await?;
+        let buffer: VecDeque<StoredEvent> = rows
+            .into_iter()
+            .map(|(id, data)| StoredEvent {
+                id,
+                data: serde_json::from_str(&data).unwrap_or(Value::Null),
+            })
+            .collect();
+        Ok(events_after_in(&buffer, last_event_id))
+    }
+}
+
+/// Redis-backed session store for sharing sessions across replicas.
+#[cfg(feature = "redis-sessions")]
+#[derive(Clone)]
+pub struct RedisSessionStore {
+    conn: redis::aio::MultiplexedConnection,
+    prefix: String,
+}
+
+#[cfg(feature = "redis-sessions")]
+impl RedisSessionStore {
+    pub async fn connect(url: &str) -> anyhow::Result<Self> {
+        let client = redis::Client::open(url)?;
+        let conn = client.get_multiplexed_async_connection().await?;
+        Ok(Self {
+            conn,
+            prefix: "odoo-mcp:session".to_string(),
+        })
+    }
+
+    fn key(&self, session_id: &str, suffix: &str) -> String {
+        format!("{}:{}:{}", self.prefix, session_id, suffix)
+    }
+}
+
+#[cfg(feature = "redis-sessions")]
+#[async_trait]
+impl SessionStore for RedisSessionStore {
+    fn backend(&self) -> &'static str {
+        "redis"
+    }
+
+    async fn get(&self, session_id: &str) -> anyhow::Result<Option<SessionRecord>> {
+        use redis::AsyncCommands;
+        let mut conn = self.conn.clone();
+        let raw: Option<String> = conn.get(self.key(session_id, "record")).await?;
+        raw.map(|s| serde_json::from_str(&s).map_err(anyhow::Error::from))
+            .transpose()
+    }
+
+    async fn put(&self, session_id: &str, record: &SessionRecord) -> anyhow::Result<()> {
+        use redis::AsyncCommands;
+        let mut conn = self.conn.clone();
+        let raw = serde_json::to_string(record)?;
+        let _: () = conn.set(self.key(session_id, "record"), raw).await?;
+        Ok(())
+    }
+
+    async fn remove(&self, session_id: &str) -> anyhow::Result<bool> {
+        use redis::AsyncCommands;
+        let mut conn = self.conn.clone();
+        let removed: u64 = conn.del(self.key(session_id, "record")).await?;
+        let _: () = conn
+            .del(&[
+                self.key(session_id, "counter"),
+                self.key(session_id, "events"),
+            ])
+            .await?;
+        Ok(removed > 0)
+    }
+
+    async fn next_event_seq(&self, session_id: &str) -> anyhow::Result<u64> {
+        use redis::AsyncCommands;
+        let mut conn = self.conn.clone();
+        let next: u64 = conn.incr(self.key(session_id, "counter"), 1u64).await?;
+        Ok(next.saturating_sub(1))
+    }
+
+    async fn append_event(&self, session_id: &str, event: &StoredEvent) -> anyhow::Result<()> {
+        let mut conn = self.conn.clone();
+        let key = self.key(session_id, "events");
+        let raw = serde_json::to_string(event)?;
+        let _: () = redis::pipe()
+            .atomic()
+            .rpush(&key, raw)
+            .ignore()
+            .ltrim(&key, -(MAX_EVENT_BUFFER_SIZE as isize), -1)
+            .ignore()
+            .query_async(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn events_after(
+        &self,
+        session_id: &str,
+        last_event_id: &str,
+    ) -> anyhow::Result<Vec<StoredEvent>> {
+        use redis::AsyncCommands;
+        let mut conn = self.conn.clone();
+        let raw: Vec<String> = conn.lrange(self.key(session_id, "events"), 0, -1).await?;
+        let buffer: VecDeque<StoredEvent> = raw
+            .iter()
+            .filter_map(|s| serde_json::from_str(s).ok())
+            .collect();
+        Ok(events_after_in(&buffer, last_event_id))
+    }
+}
+
+/// Build the session store selected by `MCP_SESSION_STORE`.
+pub async fn session_store_from_env() -> anyhow::Result<Arc<dyn SessionStore>> {
+    let backend = std::env::var("MCP_SESSION_STORE")
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    match backend.as_str() {
+        "" | "memory" => Ok(Arc::new(MemorySessionStore::new())),
+        "sqlite" => {
+            let path = std::env::var("MCP_SESSION_STORE_PATH")
+                .ok()
+                .filter(|s| !s.trim().is_empty())
+                .unwrap_or_else(|| "mcp-sessions.db".to_string());
+            Ok(Arc::new(SqliteSessionStore::open(path)?))
+        }
+        #[cfg(feature = "redis-sessions")]
+        "redis" => {
+            let url = std::env::var("MCP_SESSION_STORE_URL")
+                .unwrap_or_else(|_| "redis://127.0.0.1/".to_string());
+            Ok(Arc::new(RedisSessionStore::connect(&url).await?))
+        }
+        #[cfg(not(feature = "redis-sessions"))]
+        "redis" => anyhow::bail!(
+            "MCP_SESSION_STORE=redis requires building with the `redis-sessions` feature"
+        ),
+        other => {
+            anyhow::bail!("Unknown MCP_SESSION_STORE '{other}' (expected memory, sqlite, or redis)")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    async fn exercise(store: &dyn SessionStore) {
+        assert!(store.get("s").await.unwrap().is_none());
+        store
+            .put("s", &SessionRecord::new("2025-03-26"))
+            .await
+            .unwrap();
+        let rec = store.get("s").await.unwrap().unwrap();
+        assert!(rec.initialized);
+        assert_eq!(rec.protocol_version, "2025-03-26");
+
+        let id1 = store.next_event_id("s").await.unwrap();
+        let id2 = store.next_event_id("s").await.unwrap();
+        assert_eq!(id1, "s:0");
+        assert_eq!(id2, "s:1");
+
+        for (i, msg) in ["first", "second", "third"].iter().enumerate() {
+            store
+                .append_event(
+                    "s",
+                    &StoredEvent {
+                        id: format!("s:{i}"),
+                        data: json!({"msg": msg}),
+                    },
+                )
+                .await
+                .unwrap();
+        }
+        let events = store.events_after("s", "s:0").await.unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].id, "s:1");
+        assert_eq!(events[1].data, json!({"msg": "third"}));
+        assert!(store.events_after("s", "s:999").await.unwrap().is_empty());
+
+        assert!(store.remove("s").await.unwrap());
+        assert!(!store.remove("s").await.unwrap());
+        assert!(store.get("s").await.unwrap().is_none());
+        assert!(store.events_after("s", "s:0").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_roundtrip() {
+        exercise(&MemorySessionStore::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_store_roundtrip() {
+        exercise(&SqliteSessionStore::open_in_memory().unwrap()).await;
+    }
+
+    #[tokio::test]
+    async fn test_event_buffer_is_bounded() {
+        let store = SqliteSessionStore::open_in_memory().unwrap();
+        for i in 0..(MAX_EVENT_BUFFER_SIZE + 5) {
+            store
+                .append_event(
+                    "s",
+                    &StoredEvent {
+                        id: format!("s:{i}"),
+                        data: json!(i),
+                    },
+                )
+                .await
+                .unwrap();
+        }
+        // The oldest events were evicted, so they can no longer anchor a replay.
+        assert!(store.events_after("s", "s:0").await.unwrap().is_empty());
+        let tail = store.events_after("s", "s:5").await.unwrap();
+        assert_eq!(tail.len(), MAX_EVENT_BUFFER_SIZE - 1);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_store_survives_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sessions.db");
+        {
+            let store = SqliteSessionStore::open(&path).unwrap();
+            store
+                .put("abc", &SessionRecord::new("2025-11-05"))
+                .await
+                .unwrap();
+            store.next_event_seq("abc").await.unwrap();
+        }
+        let store = SqliteSessionStore::open(&path).unwrap();
+        let rec = store.get("abc").await.unwrap().unwrap();
+        assert_eq!(rec.protocol_version, "2025-11-05");
+        assert_eq!(store.next_event_seq("abc").await.unwrap(), 1);
+    }
+}
@@ -0,0 +1,188 @@
+//! Natural-language search: the client LLM (via MCP sampling) turns a filter description
+//! into an Odoo domain, which is validated against the model's fields before it is run.
+
+use serde_json::Value;
+
+/// Fields offered to the LLM; large models are truncated to keep the prompt small.
+const MAX_PROMPT_FIELDS: usize = 200;
+
+pub const SYSTEM_PROMPT: &str = "You translate natural-language filters into Odoo search domains. \
+Reply with ONLY a JSON array in Odoo domain syntax (prefix notation, e.g. \
+[[\"state\", \"=\", \"sale\"], [\"amount_total\", \">\", 1000]]), using only the listed fields. \
+Use ISO dates (YYYY-MM-DD). Reply [] when the request has no filter.";
+
+const OPERATORS: &[&str] = &[
+    "=",
+    "!=",
+    ">",
+    ">=",
+    "<",
+    "<=",
+    "=?",
+    "=like",
+    "=ilike",
+    "like",
+    "not like",
+    "ilike",
+    "not ilike",
+    "in",
+    "not in",
+    "child_of",
+    "parent_of",
+    "any",
+    "not any",
+];
+
+/// One line per searchable field: `name (type): label`.
+pub fn field_catalog(fields: &Value) -> String {
+    let mut lines: Vec<String> = fields
+        .as_object()
+        .map(|fields| {
+            fields
+                .iter()
+                .filter(|(_, meta)| meta.get("searchable").and_then(|v| v.as_bool()) != Some(false))
+                .map(|(name, meta)| {
+                    let ty = meta.get("type").and_then(|v| v.as_str()).unwrap_or("?");
+                    let label = meta.get("string").and_then(|v| v.as_str()).unwrap_or("");
+                    match meta.get("relation").and_then(|v| v.as_str()) {
+                        Some(relation) => format!("{name} ({ty} -> {relation}): {label}"),
+                        None => format!("{name} ({ty}): {label}"),
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    lines.sort();
+    lines.truncate(MAX_PROMPT_FIELDS);
+    lines.join("\n")
+}
+
+pub fn domain_prompt(model: &str, catalog: &str, query: &str) -> String {
+    format!("Model: {model}\nFields:\n{catalog}\n\nFilter: {query}")
+}
+
+/// Pull the JSON array out of an LLM reply, tolerating code fences and surrounding prose.
+pub fn extract_domain(text: &str) -> Result<Value, String> {
+    let start = text.find('[');
+    let end = text.rfind(']');
+    let (Some(start), Some(end)) = (start, end) else {
+        return Err("model reply does not contain a JSON array".to_string());
+    };
+    if end < start {
+        return Err("model reply does not contain a JSON array".to_string());
+    }
+    serde_json::from_str(&text[start..=end])
+        .map_err(|e| format!("model reply is not valid JSON: {e}"))
+}
+
+/// Check that `domain` is a well-formed prefix-notation domain over fields of the model.
+///
+/// Only the first segment of dotted paths (`partner_id.country_id`) is checked.
+pub fn validate_domain(domain: &Value, fields: &Value) -> Result<(), String> {
+    let items = domain
+        .as_array()
+        .ok_or_else(|| "domain must be a JSON array".to_string())?;
+
+    // Walk right-to-left counting operands; `&`/`|` consume two, `!` consumes one.
+    let mut operands = 0usize;
+    for item in items.iter().rev() {
+        match item {
+            Value::String(op) if op == "&" || op == "|" => {
+                if operands < 2 {
+                    return Err(format!("operator '{op}' is missing operands"));
+                }
+                operands -= 1;
+            }
+            Value::String(op) if op == "!" => {
+                if operands < 1 {
+                    return Err("operator '!' is missing an operand".to_string());
+                }
+            }
+            Value::Array(leaf) => {
+                validate_leaf(leaf, fields)?;
+                operands += 1;
+            }
+            other => return Err(format!("unexpected domain element {other}")),
+        }
+    }
+    Ok(())
+}
+
+fn validate_leaf(leaf: &[Value], fields: &Value) -> Result<(), String> {
+    let [field, operator, _value] = leaf else {
+        return Err(format!(
+            "domain condition must have 3 elements, got {}",
+            leaf.len()
+        ));
+    };
+    let field = field
+        .as_str()
+        .ok_or_else(|| "domain field name must be a string".to_string())?;
+    let operator = operator
+        .as_str()
+        .ok_or_else(|| "domain operator must be a string".to_string())?;
+    let root = field.split('.').next().unwrap_or(field);
+    if root != "id" && fields.get(root).is_none() {
+        return Err(format!("unknown field '{root}'"));
+    }
+    if !OPERATORS.contains(&operator.to_ascii_lowercase().as_str()) {
+        return Err(format!("unsupported operator '{operator}'"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fields() -> Value {
+        json!({
+            "name": {"type": "char", "string": "Name"},
+            "state": {"type": "selection", "string": "Status"},
+            "partner_id": {"type": "many2one", "string": "Customer", "relation": "res.partner"},
+            "message_ids": {"type": "one2many", "string": "Messages", "searchable": false}
+        })
+    }
+
+    #[test]
+    fn field_catalog_lists_searchable_fields_with_relations() {
+        let catalog = field_catalog(&fields());
+        assert!(catalog.contains("partner_id (many2one -> res.partner): Customer"));
+        assert!(catalog.contains("state (selection): Status"));
+        assert!(!catalog.contains("message_ids"));
+    }
+
+    #[test]
+    fn extract_domain_strips_fences_and_prose() {
+        let reply = "Here you go:\n```json\n[[\"state\", \"=\", \"sale\"]]\n```";
+        assert_eq!(
+            extract_domain(reply).unwrap(),
+            json!([["state", "=", "sale"]])
+        );
+        assert!(extract_domain("no domain here").is_err());
+    }
+
+    #[test]
+    fn validate_domain_accepts_prefix_notation() {
+        let domain = json!([
+            "|",
+            ["state", "=", "sale"],
+            "!",
+            ["partner_id.name", "ilike", "acme"],
+            ["id", ">", 5]
+        ]);
+        assert!(validate_domain(&domain, &fields()).is_ok());
+        assert!(validate_domain(&json!([]), &fields()).is_ok());
+    }
+
+    #[test]
+    fn validate_domain_rejects_bad_input() {
+        let f = fields();
+        assert!(validate_domain(&json!({"state": "sale"}), &f).is_err());
+        assert!(validate_domain(&json!([["bogus", "=", 1]]), &f).is_err());
+        assert!(validate_domain(&json!([["state", "~", 1]]), &f).is_err());
+        assert!(validate_domain(&json!([["state", "="]]), &f).is_err());
+        assert!(validate_domain(&json!(["|", ["state", "=", "sale"]]), &f).is_err());
+    }
+}
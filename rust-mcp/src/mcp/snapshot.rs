@@ -0,0 +1,180 @@
+//! Time-boxed read-only snapshot sessions (`odoo_snapshot`).
+//!
+//! A session can pin its reads to a logical time T for a limited period, so a multi-step
+//! analysis keeps seeing the same data while others keep working in Odoo. While the snapshot is
+//! active, `search`, `search_read`, `search_count` and `read_group` calls get
+//! `["write_date", "<=", T]` added to their domain on every model that has `write_date`, so
+//! records created or changed after T are left out, and mutating tools are refused. Records
+//! deleted after T are not brought back, and reads by id are not filtered.
+//!
+//! Snapshots belong to the MCP session (the pool's scope) and end after their time box, when
+//! the session closes, or with `action: "stop"`.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use serde_json::{Value, json};
+
+pub const DEFAULT_MINUTES: i64 = 30;
+pub const MAX_MINUTES: i64 = 240;
+
+/// Ops whose domain is pinned to the snapshot time.
+pub const PINNED_OPS: &[&str] = &["search", "search_read", "search_count", "read_group"];
+
+#[derive(Clone, Debug)]
+pub struct Snapshot {
+    pub at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// Whether each `instance/model` seen so far has `write_date` and can be pinned.
+    pinnable: HashMap<String, bool>,
+}
+
+impl Snapshot {
+    /// T in Odoo's datetime format (UTC).
+    pub fn odoo_at(&self) -> String {
+        self.at.format("%Y-%m-%d %H:%M:%S").to_string()
+    }
+
+    pub fn describe(&self) -> Value {
+        json!({
+            "active": true,
+            "at": self.at.to_rfc3339(),
+            "expiresAt": self.expires_at.to_rfc3339(),
+            "remainingSecs": (self.expires_at - Utc::now()).num_seconds().max(0),
+            "pinnedOps": PINNED_OPS,
+        })
+    }
+}
+
+/// Active snapshots by session scope.
+#[derive(Clone, Default)]
+pub struct SnapshotStore {
+    sessions: Arc<Mutex<HashMap<String, Snapshot>>>,
+}
+
+impl SnapshotStore {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Snapshot>> {
+        self.sessions.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Start (or restart) `scope`'s snapshot at `at` for `minutes`.
+    pub fn start(&self, scope: &str, at: DateTime<Utc>, minutes: i64) -> Snapshot {
+        let snapshot = Snapshot {
+            at,
+            expires_at: Utc::now() + Duration::minutes(minutes),
+            pinnable: HashMap::new(),
+        };
+        self.lock().insert(scope.to_string(), snapshot.clone());
+        snapshot
+    }
+
+    pub fn stop(&self, scope: &str) -> Option<Snapshot> {
+        self.lock().remove(scope)
+    }
+
+    /// `scope`'s snapshot unless it has run out, which also ends it.
+    pub fn active(&self, scope: &str) -> Option<Snapshot> {
+        let mut sessions = self.lock();
+        match sessions.get(scope) {
+            Some(snapshot) if snapshot.expires_at > Utc::now() => Some(snapshot.clone()),
+            Some(_) => {
+                sessions.remove(scope);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn pinnable(&self, scope: &str, key: &str) -> Option<bool> {
+        self.lock().get(scope)?.pinnable.get(key).copied()
+    }
+
+    pub fn set_pinnable(&self, scope: &str, key: &str, pinnable: bool) {
+        if let Some(snapshot) = self.lock().get_mut(scope) {
+            snapshot.pinnable.insert(key.to_string(), pinnable);
+        }
+    }
+}
+
+/// A snapshot time from RFC 3339 or Odoo's `YYYY-MM-DD HH:MM:SS` (UTC); `None` means now.
+/// Times in the future are rejected, since they would not hold still.
+pub fn parse_at(value: Option<&str>) -> Result<DateTime<Utc>, String> {
+    let now = Utc::now();
+    let Some(value) = value.map(str::trim).filter(|v| !v.is_empty()) else {
+        return Ok(now);
+    };
+    let at = DateTime::parse_from_rfc3339(value)
+        .map(|at| at.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").map(|at| at.and_utc())
+        })
+        .map_err(|_| {
+            format!("'at' must be an RFC 3339 or 'YYYY-MM-DD HH:MM:SS' UTC time, got '{value}'")
+        })?;
+    if at > now {
+        return Err(format!("'at' ({value}) is in the future"));
+    }
+    Ok(at)
+}
+
+/// `domain` with `write_date <= at` added.
+pub fn pin_domain(domain: Option<&Value>, at: &str) -> Value {
+    let mut terms = domain
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    terms.push(json!(["write_date", "<=", at]));
+    Value::Array(terms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn domains_gain_the_write_date_bound() {
+        let domain = json!(["|", ["state", "=", "sale"], ["state", "=", "done"]]);
+        assert_eq!(
+            pin_domain(Some(&domain), "2026-03-01 08:00:00"),
+            json!([
+                "|",
+                ["state", "=", "sale"],
+                ["state", "=", "done"],
+                ["write_date", "<=", "2026-03-01 08:00:00"]
+            ])
+        );
+        assert_eq!(
+            pin_domain(None, "2026-03-01 08:00:00"),
+            json!([["write_date", "<=", "2026-03-01 08:00:00"]])
+        );
+    }
+
+    #[test]
+    fn snapshot_times_are_parsed_and_bounded() {
+        let at = parse_at(Some("2026-03-01 08:00:00")).unwrap();
+        assert_eq!(at.to_rfc3339(), "2026-03-01T08:00:00+00:00");
+        let at = parse_at(Some("2026-03-01T10:00:00+02:00")).unwrap();
+        assert_eq!(at.to_rfc3339(), "2026-03-01T08:00:00+00:00");
+        assert!(parse_at(Some("yesterday")).is_err());
+        assert!(
+            parse_at(Some("2999-01-01 00:00:00"))
+                .unwrap_err()
+                .contains("future")
+        );
+        assert!(parse_at(None).is_ok());
+    }
+
+    #[test]
+    fn expired_snapshots_end() {
+        let store = SnapshotStore::default();
+        store.start("s1", Utc::now(), 0);
+        assert!(store.active("s1").is_none());
+        store.start("s1", Utc::now(), 5);
+        store.set_pinnable("s1", "prod/sale.order", true);
+        assert_eq!(store.pinnable("s1", "prod/sale.order"), Some(true));
+        assert!(store.active("s2").is_none());
+        assert!(store.stop("s1").is_some());
+        assert!(store.active("s1").is_none());
+    }
+}
@@ -0,0 +1,444 @@
+//! Per-session SSE broadcast channels with lag recovery.
+//!
+//! Each HTTP session publishes server-to-client messages on a broadcast channel holding
+//! `MCP_SSE_BUFFER` events (default 256). A stream that falls further behind is not silently
+//! skipped ahead: it yields [`SseItem::Resync`] carrying the last event id it delivered, so the
+//! client can reconnect with `Last-Event-ID` and replay the gap, and the session's channel is
+//! replaced by one twice as large, up to `MCP_SSE_BUFFER_MAX` (default 4096). Other streams of
+//! the session move to the new channel and get a resync as well, since they may miss events
+//! published during the switch.
+//!
+//! Channels also count their open streams. A stream is dropped when its client disconnects (at
+//! the latest when a keepalive write fails), and a session whose streams have all been gone for
+//! longer than `MCP_SSE_DISCONNECT_GRACE_SECS` (default 300, `0` disables) is reported by
+//! [`SseChannels::take_disconnected`] so the transport can end it. Sessions that never opened a
+//! stream are left to idle expiry.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures::Stream;
+use serde_json::{Value, json};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::mcp::session_store::StoredEvent;
+
+const DEFAULT_BUFFER: usize = 256;
+const DEFAULT_BUFFER_MAX: usize = 4096;
+const DEFAULT_DISCONNECT_GRACE_SECS: u64 = 300;
+
+/// Initial and maximum channel capacity from `MCP_SSE_BUFFER` / `MCP_SSE_BUFFER_MAX`.
+pub fn buffer_limits_from_env() -> (usize, usize) {
+    let read = |name: &str, default: usize| {
+        std::env::var(name)
+            .ok()
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(default)
+    };
+    let initial = read("MCP_SSE_BUFFER", DEFAULT_BUFFER);
+    (
+        initial,
+        read("MCP_SSE_BUFFER_MAX", DEFAULT_BUFFER_MAX).max(initial),
+    )
+}
+
+/// Grace period from `MCP_SSE_DISCONNECT_GRACE_SECS`; None disables dead-stream cleanup.
+pub fn disconnect_grace_from_env() -> Option<Duration> {
+    let secs = std::env::var("MCP_SSE_DISCONNECT_GRACE_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_DISCONNECT_GRACE_SECS);
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+/// What an SSE stream delivers to its client.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SseItem {
+    Event(StoredEvent),
+    /// Events were dropped after `last_event_id`; `missed` is unknown after a channel switch.
+    Resync {
+        last_event_id: Option<String>,
+        missed: Option<u64>,
+    },
+}
+
+/// The `notifications/stream/resync_required` message announcing a gap.
+pub fn resync_message(last_event_id: Option<&str>, missed: Option<u64>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/stream/resync_required",
+        "params": { "lastEventId": last_event_id, "missed": missed }
+    })
+}
+
+struct SessionChannel {
+    tx: broadcast::Sender<StoredEvent>,
+    capacity: usize,
+    generation: u64,
+    open_streams: usize,
+    /// When the last open stream went away; None while one is open or before the first.
+    disconnected_at: Option<Instant>,
+}
+
+struct Subscription {
+    rx: broadcast::Receiver<StoredEvent>,
+    generation: u64,
+}
+
+/// Broadcast channels keyed by session id.
+#[derive(Clone)]
+pub struct SseChannels {
+    inner: Arc<Mutex<HashMap<String, SessionChannel>>>,
+    initial: usize,
+    max: usize,
+}
+
+impl SseChannels {
+    pub fn new(initial: usize, max: usize) -> Self {
+        Self {
+            inner: Arc::default(),
+            initial: initial.max(1),
+            max: max.max(initial).max(1),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let (initial, max) = buffer_limits_from_env();
+        Self::new(initial, max)
+    }
+
+    /// Create the session's channel if it does not exist yet.
+    pub fn ensure(&self, session_id: &str) {
+        self.subscribe(session_id);
+    }
+
+    /// Current sender for the session, if it has a channel.
+    pub fn sender(&self, session_id: &str) -> Option<broadcast::Sender<StoredEvent>> {
+        self.lock()
+            .get(session_id)
+            .map(|channel| channel.tx.clone())
+    }
+
+    /// Drop the session's channel; its streams end after the events already sent.
+    pub fn remove(&self, session_id: &str) -> Option<broadcast::Sender<StoredEvent>> {
+        self.lock().remove(session_id).map(|channel| channel.tx)
+    }
+
+    pub fn capacity(&self, session_id: &str) -> Option<usize> {
+        self.lock().get(session_id).map(|channel| channel.capacity)
+    }
+
+    pub fn open_streams(&self, session_id: &str) -> usize {
+        self.lock()
+            .get(session_id)
+            .map_or(0, |channel| channel.open_streams)
+    }
+
+    /// Remove and return the sessions whose last stream disconnected more than `grace` ago,
+    /// except those `still_active` vouches for (e.g. clients still POSTing without a stream).
+    pub fn take_disconnected(
+        &self,
+        grace: Duration,
+        now: Instant,
+        still_active: impl Fn(&str) -> bool,
+    ) -> Vec<String> {
+        let mut channels = self.lock();
+        let gone: Vec<String> = channels
+            .iter()
+            .filter(|(id, channel)| {
+                channel
+                    .disconnected_at
+                    .is_some_and(|at| now.saturating_duration_since(at) > grace)
+                    && !still_active(id)
+            })
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in &gone {
+            channels.remove(id);
+        }
+        gone
+    }
+
+    /// Live events for the session, starting now; see [`SseSubscription::into_stream`].
+    pub fn stream(
+        &self,
+        session_id: &str,
+        last_event_id: Option<String>,
+    ) -> impl Stream<Item = SseItem> + Send + 'static {
+        self.subscribe_live(session_id).into_stream(last_event_id)
+    }
+
+    /// Start receiving the session's events now, creating its channel if needed.
+    pub fn subscribe_live(&self, session_id: &str) -> SseSubscription {
+        let subscription = self.subscribe(session_id);
+        if let Some(channel) = self.lock().get_mut(session_id) {
+            channel.open_streams += 1;
+            channel.disconnected_at = None;
+        }
+        SseSubscription {
+            subscription,
+            channels: self.clone(),
+            session_id: session_id.to_string(),
+        }
+    }
+
+    fn subscribe(&self, session_id: &str) -> Subscription {
+        let mut channels = self.lock();
+        let channel = channels
+            .entry(session_id.to_string())
+            .or_insert_with(|| SessionChannel {
+                tx: broadcast::channel(self.initial).0,
+                capacity: self.initial,
+                generation: 0,
+                open_streams: 0,
+                disconnected_at: None,
+            });
+        Subscription {
+            rx: channel.tx.subscribe(),
+            generation: channel.generation,
+        }
+    }
+
+    /// Replace a lagging session's channel with a larger one, unless it is already at the
+    /// maximum or was replaced since `generation`; returns the subscription to switch to.
+    fn grow(&self, session_id: &str, generation: u64) -> Option<Subscription> {
+        let mut channels = self.lock();
+        let channel = channels.get_mut(session_id)?;
+        if channel.generation == generation {
+            if channel.capacity >= self.max {
+                return None;
+            }
+            channel.capacity = (channel.capacity * 2).min(self.max);
+            channel.tx = broadcast::channel(channel.capacity).0;
+            channel.generation += 1;
+            tracing::debug!(
+                session_id,
+                capacity = channel.capacity,
+                "Grew SSE buffer after lag"
+            );
+        }
+        Some(Subscription {
+            rx: channel.tx.subscribe(),
+            generation: channel.generation,
+        })
+    }
+
+    /// Subscription to a channel that replaced the one at `generation`; None once the session
+    /// is gone.
+    fn resubscribe(&self, session_id: &str, generation: u64) -> Option<Subscription> {
+        let channels = self.lock();
+        let channel = channels.get(session_id)?;
+        (channel.generation != generation).then(|| Subscription {
+            rx: channel.tx.subscribe(),
+            generation: channel.generation,
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, SessionChannel>> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// A session subscription that buffers events until it is turned into a stream.
+pub struct SseSubscription {
+    subscription: Subscription,
+    channels: SseChannels,
+    session_id: String,
+}
+
+impl Drop for SseSubscription {
+    fn drop(&mut self) {
+        if let Some(channel) = self.channels.lock().get_mut(&self.session_id) {
+            channel.open_streams = channel.open_streams.saturating_sub(1);
+            if channel.open_streams == 0 {
+                channel.disconnected_at = Some(Instant::now());
+            }
+        }
+    }
+}
+
+impl SseSubscription {
+    /// Events received since subscribing. `last_event_id` is the last id the client already has
+    /// (e.g. from a replay); the stream ends when the session's channel is removed.
+    pub fn into_stream(
+        self,
+        last_event_id: Option<String>,
+    ) -> impl Stream<Item = SseItem> + Send + 'static {
+        let state = StreamState {
+            live: self,
+            last_event_id,
+        };
+        futures::stream::unfold(state, |mut state| async move {
+            let live = &mut state.live;
+            let item = match live.subscription.rx.recv().await {
+                Ok(event) => {
+                    state.last_event_id = Some(event.id.clone());
+                    SseItem::Event(event)
+                }
+                Err(RecvError::Lagged(missed)) => {
+                    if let Some(subscription) = live
+                        .channels
+                        .grow(&live.session_id, live.subscription.generation)
+                    {
+                        live.subscription = subscription;
+                    }
+                    SseItem::Resync {
+                        last_event_id: state.last_event_id.clone(),
+                        missed: Some(missed),
+                    }
+                }
+                Err(RecvError::Closed) => {
+                    live.subscription = live
+                        .channels
+                        .resubscribe(&live.session_id, live.subscription.generation)?;
+                    SseItem::Resync {
+                        last_event_id: state.last_event_id.clone(),
+                        missed: None,
+                    }
+                }
+            };
+            Some((item, state))
+        })
+    }
+}
+
+struct StreamState {
+    live: SseSubscription,
+    last_event_id: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    fn event(id: usize) -> StoredEvent {
+        StoredEvent {
+            id: format!("s:{id}"),
+            data: json!({ "n": id }),
+        }
+    }
+
+    #[tokio::test]
+    async fn lagging_stream_gets_resync_and_a_larger_buffer() {
+        let channels = SseChannels::new(2, 8);
+        let mut stream = Box::pin(channels.stream("s", Some("s:0".into())));
+        let tx = channels.sender("s").unwrap();
+        tx.send(event(1)).unwrap();
+        assert_eq!(stream.next().await, Some(SseItem::Event(event(1))));
+
+        for id in 2..=5 {
+            tx.send(event(id)).unwrap();
+        }
+        assert_eq!(
+            stream.next().await,
+            Some(SseItem::Resync {
+                last_event_id: Some("s:1".into()),
+                missed: Some(2),
+            })
+        );
+        assert_eq!(channels.capacity("s"), Some(4));
+
+        let tx = channels.sender("s").unwrap();
+        for id in 6..=9 {
+            tx.send(event(id)).unwrap();
+        }
+        for id in 6..=9 {
+            assert_eq!(stream.next().await, Some(SseItem::Event(event(id))));
+        }
+    }
+
+    #[tokio::test]
+    async fn growth_stops_at_the_maximum() {
+        let channels = SseChannels::new(2, 2);
+        let mut stream = Box::pin(channels.stream("s", None));
+        let tx = channels.sender("s").unwrap();
+        for id in 1..=3 {
+            tx.send(event(id)).unwrap();
+        }
+        assert!(matches!(
+            stream.next().await,
+            Some(SseItem::Resync {
+                last_event_id: None,
+                missed: Some(1)
+            })
+        ));
+        assert_eq!(channels.capacity("s"), Some(2));
+        assert_eq!(stream.next().await, Some(SseItem::Event(event(2))));
+    }
+
+    #[tokio::test]
+    async fn other_streams_follow_the_new_channel_and_end_with_the_session() {
+        let channels = SseChannels::new(1, 4);
+        let mut lagging = Box::pin(channels.stream("s", None));
+        let mut other = Box::pin(channels.stream("s", Some("s:0".into())));
+        let tx = channels.sender("s").unwrap();
+        tx.send(event(1)).unwrap();
+        assert_eq!(other.next().await, Some(SseItem::Event(event(1))));
+        tx.send(event(2)).unwrap();
+        drop(tx);
+        assert!(matches!(lagging.next().await, Some(SseItem::Resync { .. })));
+
+        assert_eq!(other.next().await, Some(SseItem::Event(event(2))));
+        assert_eq!(
+            other.next().await,
+            Some(SseItem::Resync {
+                last_event_id: Some("s:2".into()),
+                missed: None,
+            })
+        );
+        channels.sender("s").unwrap().send(event(3)).unwrap();
+        assert_eq!(other.next().await, Some(SseItem::Event(event(3))));
+
+        channels.remove("s");
+        assert_eq!(other.next().await, None);
+        assert_eq!(lagging.next().await, Some(SseItem::Event(event(3))));
+        assert_eq!(lagging.next().await, None);
+    }
+
+    #[tokio::test]
+    async fn sessions_are_reported_after_their_streams_are_gone_for_the_grace_period() {
+        let channels = SseChannels::new(4, 4);
+        channels.ensure("never-streamed");
+        let first = channels.stream("s", None);
+        let second = channels.stream("s", None);
+        assert_eq!(channels.open_streams("s"), 2);
+        let grace = Duration::from_secs(60);
+
+        drop(first);
+        let later = Instant::now() + Duration::from_secs(120);
+        assert!(
+            channels
+                .take_disconnected(grace, later, |_| false)
+                .is_empty()
+        );
+
+        drop(second);
+        assert_eq!(channels.open_streams("s"), 0);
+        assert!(
+            channels
+                .take_disconnected(grace, Instant::now(), |_| false)
+                .is_empty()
+        );
+
+        // A reconnect within the grace period keeps the session.
+        let reconnected = channels.stream("s", None);
+        assert!(
+            channels
+                .take_disconnected(grace, later, |_| false)
+                .is_empty()
+        );
+        drop(reconnected);
+
+        assert!(
+            channels
+                .take_disconnected(grace, later, |id| id == "s")
+                .is_empty()
+        );
+        assert_eq!(channels.take_disconnected(grace, later, |_| false), ["s"]);
+        assert!(channels.sender("s").is_none());
+        assert!(channels.sender("never-streamed").is_some());
+    }
+}
@@ -0,0 +1,340 @@
+//! `resources/subscribe` for record resources.
+//!
+//! Each subscription polls the record's `write_date` and pushes
+//! `notifications/resources/updated` through the client's outbound channel (see
+//! [`crate::mcp::sampling`]) when it changes. Odoo's bus only carries channels that modules
+//! publish to explicitly, so polling is the one mechanism that works for any model. Writes made
+//! through this server's own tools on the same instance and model trigger an immediate poll.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde_json::{Value, json};
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+use tracing::debug;
+
+use crate::mcp::resources::ResourceUri;
+use crate::mcp::sampling::ClientRequests;
+use crate::mcp::tools::{ModelWrite, OdooClientPool};
+
+const DEFAULT_POLL_SECS: u64 = 30;
+
+fn poll_interval() -> Duration {
+    let secs = std::env::var("ODOO_RESOURCE_POLL_SECS")
+        .ok()
+        .and_then(|value| value.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_POLL_SECS);
+    Duration::from_secs(secs.max(1))
+}
+
+/// What one poll saw compared to the previous one.
+#[derive(Debug, PartialEq, Eq)]
+enum Observation {
+    Unchanged,
+    Updated,
+    Deleted,
+}
+
+fn observe(previous: Option<&str>, current: Option<&str>) -> Observation {
+    match current {
+        None => Observation::Deleted,
+        Some(current) if previous.is_some_and(|p| p != current) => Observation::Updated,
+        Some(_) => Observation::Unchanged,
+    }
+}
+
+type Key = (String, String);
+
+#[derive(Default)]
+struct Inner {
+    watchers: HashMap<Key, (u64, CancellationToken)>,
+    next_generation: u64,
+}
+
+#[derive(Clone, Default)]
+pub struct ResourceSubscriptions {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl ResourceSubscriptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start watching `uri` for `scope`. Subscribing twice to the same URI is a no-op.
+    pub fn subscribe(
+        &self,
+        scope: &str,
+        uri: &str,
+        pool: OdooClientPool,
+        requests: ClientRequests,
+    ) -> Result<(), String> {
+        let ResourceUri::Record {
+            instance,
+            model,
+            id,
+        } = ResourceUri::parse(uri)?
+        else {
+            return Err(format!(
+                "Only record resources (odoo://{{instance}}/record/{{model}}/{{id}}) support subscriptions: {uri}"
+            ));
+        };
+        if !requests.is_attached(scope) {
+            return Err("This connection cannot receive resource update notifications".to_string());
+        }
+
+        let key = (scope.to_string(), uri.to_string());
+        let cancel = CancellationToken::new();
+        let generation = {
+            let mut inner = self.inner.lock().map_err(|e| e.to_string())?;
+            if inner.watchers.contains_key(&key) {
+                return Ok(());
+            }
+            inner.next_generation += 1;
+            let generation = inner.next_generation;
+            inner
+                .watchers
+                .insert(key.clone(), (generation, cancel.clone()));
+            generation
+        };
+
+        let this = self.clone();
+        tokio::spawn(async move {
+            let watch = RecordWatch {
+                pool,
+                requests,
+                scope: key.0.clone(),
+                uri: key.1.clone(),
+                instance,
+                model,
+                id,
+            };
+            watch.run(cancel, poll_interval()).await;
+            this.remove(&key, generation);
+        });
+        Ok(())
+    }
+
+    /// Stop watching `uri` for `scope`; returns whether a subscription existed.
+    pub fn unsubscribe(&self, scope: &str, uri: &str) -> bool {
+        let removed = self
+            .inner
+            .lock()
+            .ok()
+            .and_then(|mut inner| inner.watchers.remove(&(scope.to_string(), uri.to_string())));
+        match removed {
+            Some((_, cancel)) => {
+                cancel.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn count(&self, scope: &str) -> usize {
+        self.inner
+            .lock()
+            .map(|inner| inner.watchers.keys().filter(|(s, _)| s == scope).count())
+            .unwrap_or(0)
+    }
+
+    /// Drop a finished watcher unless it was already replaced by a newer subscription.
+    fn remove(&self, key: &Key, generation: u64) {
+        if let Ok(mut inner) = self.inner.lock()
+            && inner
+                .watchers
+                .get(key)
+                .is_some_and(|(g, _)| *g == generation)
+        {
+            inner.watchers.remove(key);
+        }
+    }
+}
+
+struct RecordWatch {
+    pool: OdooClientPool,
+    requests: ClientRequests,
+    scope: String,
+    uri: String,
+    instance: String,
+    model: String,
+    id: i64,
+}
+
+impl RecordWatch {
+    /// `Ok(None)` when the record no longer exists.
+    async fn write_date(&self) -> Result<Option<String>, String> {
+        let client = self
+            .pool
+            .get(&self.instance)
+            .await
+            .map_err(|e| e.to_string())?;
+        let rows = client
+            .read(
+                &self.model,
+                vec![self.id],
+                Some(vec!["write_date".to_string()]),
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(rows.as_array().and_then(|rows| rows.first()).map(|row| {
+            row.get("write_date")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string()
+        }))
+    }
+
+    /// Whether a write reported by the pool may have touched this record.
+    fn affected_by(&self, write: &ModelWrite) -> bool {
+        let instance = self
+            .pool
+            .resolve_instance_name(&self.instance)
+            .map(|canonical| self.pool.cache_key(&canonical))
+            .unwrap_or_else(|_| self.instance.clone());
+        write.instance == instance && write.model.as_ref().is_none_or(|m| *m == self.model)
+    }
+
+    async fn run(&self, cancel: CancellationToken, interval: Duration) {
+        let mut writes = self.pool.subscribe_writes();
+        let mut last = self.write_date().await.ok().flatten();
+        loop {
+            let sleep = tokio::time::sleep(interval);
+            tokio::pin!(sleep);
+            loop {
+                tokio::select! {
+                    _ = cancel.cancelled() => return,
+                    _ = &mut sleep => break,
+                    write = writes.recv() => match write {
+                        Ok(write) if self.affected_by(&write) => break,
+                        Ok(_) => {}
+                        // Missed writes may include ours; polling now is always safe.
+                        Err(broadcast::error::RecvError::Lagged(_)) => break,
+                        // Unreachable while the pool (which owns the sender) is alive.
+                        Err(broadcast::error::RecvError::Closed) => {
+                            (&mut sleep).await;
+                            break;
+                        }
+                    },
+                }
+            }
+            // The transport detached (stdio closed or HTTP session deleted).
+            if !self.requests.is_attached(&self.scope) {
+                return;
+            }
+            let current = match self.write_date().await {
+                Ok(current) => current,
+                Err(err) => {
+                    debug!(uri = %self.uri, error = %err, "Resource poll failed");
+                    continue;
+                }
+            };
+            let observation = observe(last.as_deref(), current.as_deref());
+            if observation != Observation::Unchanged {
+                self.requests.notify(
+                    &self.scope,
+                    "notifications/resources/updated",
+                    json!({ "uri": self.uri }),
+                );
+            }
+            if observation == Observation::Deleted {
+                return;
+            }
+            last = current;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn observe_reports_changes_after_a_baseline() {
+        assert_eq!(
+            observe(None, Some("2026-01-01 10:00:00")),
+            Observation::Unchanged
+        );
+        assert_eq!(
+            observe(Some("2026-01-01 10:00:00"), Some("2026-01-01 10:00:00")),
+            Observation::Unchanged
+        );
+        assert_eq!(
+            observe(Some("2026-01-01 10:00:00"), Some("2026-01-01 10:05:00")),
+            Observation::Updated
+        );
+        assert_eq!(
+            observe(Some("2026-01-01 10:00:00"), None),
+            Observation::Deleted
+        );
+    }
+
+    #[tokio::test]
+    async fn subscribe_requires_record_uri_and_attached_client() {
+        use crate::odoo::config::OdooEnvConfig;
+
+        let pool = OdooClientPool::from_config(OdooEnvConfig {
+            instances: HashMap::new(),
+        });
+        let requests = ClientRequests::new();
+        let subscriptions = ResourceSubscriptions::new();
+        let record = "odoo://prod/record/res.partner/1";
+
+        let err = subscriptions
+            .subscribe("s1", "odoo://prod/models", pool.clone(), requests.clone())
+            .unwrap_err();
+        assert!(err.contains("Only record resources"));
+        assert!(
+            subscriptions
+                .subscribe("s1", record, pool.clone(), requests.clone())
+                .is_err()
+        );
+
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        requests.attach("s1", tx);
+        subscriptions
+            .subscribe("s1", record, pool.clone(), requests.clone())
+            .unwrap();
+        subscriptions
+            .subscribe("s1", record, pool, requests)
+            .unwrap();
+        assert_eq!(subscriptions.count("s1"), 1);
+        assert!(subscriptions.unsubscribe("s1", record));
+        assert_eq!(subscriptions.count("s1"), 0);
+    }
+
+    #[test]
+    fn writes_to_same_instance_and_model_affect_watch() {
+        use crate::odoo::config::OdooEnvConfig;
+
+        let watch = RecordWatch {
+            pool: OdooClientPool::from_config(OdooEnvConfig {
+                instances: HashMap::new(),
+            }),
+            requests: ClientRequests::new(),
+            scope: "s1".into(),
+            uri: "odoo://prod/record/res.partner/1".into(),
+            instance: "prod".into(),
+            model: "res.partner".into(),
+            id: 1,
+        };
+        let write = |instance: &str, model: Option<&str>| ModelWrite {
+            instance: instance.into(),
+            model: model.map(str::to_string),
+        };
+        assert!(watch.affected_by(&write("prod", Some("res.partner"))));
+        assert!(watch.affected_by(&write("prod", None)));
+        assert!(!watch.affected_by(&write("prod", Some("sale.order"))));
+        assert!(!watch.affected_by(&write("staging", Some("res.partner"))));
+    }
+
+    #[test]
+    fn unsubscribe_without_subscription_is_false() {
+        let subscriptions = ResourceSubscriptions::new();
+        assert!(!subscriptions.unsubscribe("s1", "odoo://prod/record/res.partner/1"));
+        assert_eq!(subscriptions.count("s1"), 0);
+    }
+}
@@ -0,0 +1,145 @@
+//! Tenants: teams sharing one hosted server.
+//!
+//! Tenants live in `tenants.json` (path from `MCP_TENANTS_JSON`). A named token with a `tenant`
+//! only sees that tenant's instances in tools, resources and completions; calls naming any
+//! other instance fail as if it did not exist. Tool calls are charged against the tenant's
+//! `callsPerMinute`, and audit entries carry the tenant so `GET /audit` with a tenant token
+//! only returns that tenant's entries. Tokens without a tenant keep seeing everything.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::mcp::rate_limit::Quota;
+
+pub const TENANTS_PATH_ENV: &str = "MCP_TENANTS_JSON";
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Tenant {
+    pub name: String,
+    /// Configured instance names the tenant may use (case-insensitive).
+    #[serde(default)]
+    pub instances: Vec<String>,
+    /// Tool calls per minute across all of the tenant's tokens; unset is unlimited.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calls_per_minute: Option<u32>,
+}
+
+impl Tenant {
+    /// Stand-in for a tenant a token names but tenants.json does not define: no instances.
+    pub fn undefined(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            instances: Vec::new(),
+            calls_per_minute: None,
+        }
+    }
+
+    pub fn allows_instance(&self, instance: &str) -> bool {
+        let instance = instance.trim();
+        self.instances
+            .iter()
+            .any(|allowed| allowed.trim().eq_ignore_ascii_case(instance))
+    }
+
+    pub fn quota(&self) -> Option<Quota> {
+        self.calls_per_minute
+            .filter(|per_minute| *per_minute > 0)
+            .map(|per_minute| Quota {
+                per_minute,
+                burst: per_minute,
+            })
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TenantFile {
+    #[serde(default)]
+    pub tenants: Vec<Tenant>,
+}
+
+/// Load tenants from `path`; a missing file means no tenants.
+pub fn load_tenants(path: &Path) -> anyhow::Result<Vec<Tenant>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(path)?;
+    let file: TenantFile = serde_json::from_str(&content)?;
+    validate_tenants(&file.tenants).map_err(|e| anyhow::anyhow!(e))?;
+    Ok(file.tenants)
+}
+
+/// Load tenants from the file named by `MCP_TENANTS_JSON`, if set.
+pub fn load_tenants_from_env() -> anyhow::Result<Vec<Tenant>> {
+    match std::env::var(TENANTS_PATH_ENV) {
+        Ok(path) if !path.trim().is_empty() => load_tenants(Path::new(path.trim())),
+        _ => Ok(Vec::new()),
+    }
+}
+
+pub fn validate_tenants(tenants: &[Tenant]) -> Result<(), String> {
+    let mut names = HashSet::new();
+    for tenant in tenants {
+        let name = tenant.name.trim();
+        if name.is_empty() {
+            return Err("Every tenant needs a name".to_string());
+        }
+        if !names.insert(name) {
+            return Err(format!("Duplicate tenant name '{name}'"));
+        }
+    }
+    Ok(())
+}
+
+/// The tenant called `name`, or one without instances when it is not defined.
+pub fn find_tenant(tenants: &[Tenant], name: &str) -> Tenant {
+    tenants
+        .iter()
+        .find(|tenant| tenant.name == name)
+        .cloned()
+        .unwrap_or_else(|| Tenant::undefined(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tenant_matches_instances_case_insensitively() {
+        let tenant: Tenant = serde_json::from_str(
+            r#"{"name": "finance", "instances": ["Prod-EU"], "callsPerMinute": 120}"#,
+        )
+        .unwrap();
+        assert!(tenant.allows_instance("prod-eu"));
+        assert!(!tenant.allows_instance("prod-us"));
+        assert_eq!(tenant.quota().unwrap().per_minute, 120);
+        assert_eq!(Tenant::undefined("x").quota(), None);
+    }
+
+    #[test]
+    fn unknown_tenants_get_no_instances() {
+        let tenants = vec![Tenant {
+            name: "finance".into(),
+            instances: vec!["prod".into()],
+            calls_per_minute: None,
+        }];
+        assert!(find_tenant(&tenants, "finance").allows_instance("prod"));
+        assert!(!find_tenant(&tenants, "ops").allows_instance("prod"));
+        assert!(validate_tenants(&[tenants[0].clone(), tenants[0].clone()]).is_err());
+    }
+
+    #[test]
+    fn load_tenants_parses_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("tenants.json");
+        assert!(load_tenants(&path).unwrap().is_empty());
+        std::fs::write(
+            &path,
+            r#"{"tenants": [{"name": "ops", "instances": ["staging"]}]}"#,
+        )
+        .unwrap();
+        assert_eq!(load_tenants(&path).unwrap()[0].instances, ["staging"]);
+    }
+}
@@ -0,0 +1,200 @@
+//! TLS termination for the HTTP transport.
+//!
+//! Enabled by `MCP_TLS_CERT` + `MCP_TLS_KEY` (PEM files), or by a `tls` object in server.json;
+//! the environment wins. `MCP_TLS_CLIENT_CA` / `clientCaPath` additionally requires every client
+//! to present a certificate signed by that CA (mutual TLS).
+
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::Router;
+use axum::extract::ConnectInfo;
+use hyper::body::Incoming;
+use hyper_util::rt::TokioIo;
+use rustls_pki_types::pem::PemObject;
+use rustls_pki_types::{CertificateDer, PrivateKeyDer};
+use serde::Deserialize;
+use tokio::net::TcpListener;
+use tokio_rustls::TlsAcceptor;
+use tokio_rustls::rustls::server::WebPkiClientVerifier;
+use tokio_rustls::rustls::{RootCertStore, ServerConfig};
+use tower_service::Service;
+use tracing::{debug, info, warn};
+
+/// Time a client gets to finish the TLS handshake.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TlsSettings {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+    /// CA bundle that client certificates must chain to. None = no client certificates.
+    #[serde(default)]
+    pub client_ca_path: Option<PathBuf>,
+}
+
+impl TlsSettings {
+    /// Settings from `MCP_TLS_CERT`, `MCP_TLS_KEY` and `MCP_TLS_CLIENT_CA`.
+    ///
+    /// Setting only one of cert/key is an error rather than a silent fallback to plain HTTP.
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let var = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        match (var("MCP_TLS_CERT"), var("MCP_TLS_KEY")) {
+            (Some(cert), Some(key)) => Ok(Some(Self {
+                cert_path: cert.into(),
+                key_path: key.into(),
+                client_ca_path: var("MCP_TLS_CLIENT_CA").map(PathBuf::from),
+            })),
+            (None, None) => Ok(None),
+            _ => anyhow::bail!("MCP_TLS_CERT and MCP_TLS_KEY must be set together"),
+        }
+    }
+
+    /// Build the rustls server config, loading and checking the PEM files.
+    pub fn server_config(&self) -> anyhow::Result<Arc<ServerConfig>> {
+        let certs = load_certs(&self.cert_path)?;
+        let key = PrivateKeyDer::from_pem_file(&self.key_path).map_err(|e| {
+            anyhow::anyhow!("Failed to read TLS key {}: {e}", self.key_path.display())
+        })?;
+
+        let builder = match &self.client_ca_path {
+            Some(ca_path) => {
+                let mut roots = RootCertStore::empty();
+                for cert in load_certs(ca_path)? {
+                    roots.add(cert)?;
+                }
+                let verifier = WebPkiClientVerifier::builder(Arc::new(roots)).build()?;
+                ServerConfig::builder().with_client_cert_verifier(verifier)
+            }
+            None => ServerConfig::builder().with_no_client_auth(),
+        };
+        let mut config = builder.with_single_cert(certs, key)?;
+        config.alpn_protocols = vec![b"http/1.1".to_vec()];
+        Ok(Arc::new(config))
+    }
+}
+
+fn load_certs(path: &Path) -> anyhow::Result<Vec<CertificateDer<'static>>> {
+    let certs = CertificateDer::pem_file_iter(path)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| anyhow::anyhow!("Failed to read certificates {}: {e}", path.display()))?;
+    if certs.is_empty() {
+        anyhow::bail!("No certificates found in {}", path.display());
+    }
+    Ok(certs)
+}
+
+/// Serve `app` over TLS on an already bound listener.
+///
+/// Requests carry `ConnectInfo<SocketAddr>` like `axum::serve` with connect info, so the per-IP
+/// rate limiter keeps working.
+pub async fn serve_tls(
+    listener: TcpListener,
+    app: Router,
+    settings: &TlsSettings,
+) -> anyhow::Result<()> {
+    let acceptor = TlsAcceptor::from(settings.server_config()?);
+    info!(
+        "MCP HTTPS enabled{}",
+        if settings.client_ca_path.is_some() {
+            " with client certificate verification"
+        } else {
+            ""
+        }
+    );
+
+    loop {
+        let (stream, remote) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                warn!("TLS accept error: {}", e);
+                continue;
+            }
+        };
+        let acceptor = acceptor.clone();
+        let app = app.clone();
+        tokio::spawn(async move {
+            let tls = match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                Ok(Ok(tls)) => tls,
+                Ok(Err(e)) => {
+                    debug!("TLS handshake with {} failed: {}", remote, e);
+                    return;
+                }
+                Err(_) => {
+                    debug!("TLS handshake with {} timed out", remote);
+                    return;
+                }
+            };
+            let service =
+                hyper::service::service_fn(move |mut request: hyper::Request<Incoming>| {
+                    request
+                        .extensions_mut()
+                        .insert(ConnectInfo::<SocketAddr>(remote));
+                    app.clone().call(request)
+                });
+            if let Err(e) = hyper::server::conn::http1::Builder::new()
+                .serve_connection(TokioIo::new(tls), service)
+                .await
+            {
+                debug!("HTTPS connection from {} ended: {}", remote, e);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/tls")
+            .join(name)
+    }
+
+    #[test]
+    fn server_config_loads_pem_files() {
+        let settings = TlsSettings {
+            cert_path: fixture("server.pem"),
+            key_path: fixture("server.key"),
+            client_ca_path: Some(fixture("ca.pem")),
+        };
+        assert!(settings.server_config().is_ok());
+    }
+
+    #[test]
+    fn server_config_reports_bad_paths() {
+        let settings = TlsSettings {
+            cert_path: fixture("missing.pem"),
+            key_path: fixture("server.key"),
+            client_ca_path: None,
+        };
+        let err = settings.server_config().unwrap_err().to_string();
+        assert!(err.contains("missing.pem"), "{err}");
+
+        // A key is not a certificate bundle.
+        let settings = TlsSettings {
+            cert_path: fixture("server.key"),
+            ..settings
+        };
+        assert!(settings.server_config().is_err());
+    }
+
+    #[test]
+    fn settings_deserialize_from_server_json() {
+        let settings: TlsSettings = serde_json::from_value(serde_json::json!({
+            "certPath": "/etc/mcp/cert.pem",
+            "keyPath": "/etc/mcp/key.pem"
+        }))
+        .unwrap();
+        assert_eq!(settings.client_ca_path, None);
+    }
+}
@@ -0,0 +1,158 @@
+//! Instance-derived examples appended to tool descriptions in `tools/list`.
+//!
+//! With `MCP_TOOL_EXAMPLES=true`, tools taking a `model` argument name a few models that exist
+//! on the instance, and tools that also take a `domain` show a domain built from real fields of
+//! one of them. Model and field names come from the completion cache, so listing costs at most
+//! one `ir.model` read and one `fields_get` per instance and TTL.
+
+use chrono::{Duration, Utc};
+use serde_json::{Value, json};
+
+use crate::mcp::completions::Completions;
+use crate::mcp::registry::env_truthy;
+use crate::mcp::tools::OdooClientPool;
+
+/// Common business models, preferred as examples in this order when installed.
+const PREFERRED_MODELS: &[&str] = &[
+    "res.partner",
+    "sale.order",
+    "account.move",
+    "product.product",
+    "crm.lead",
+    "project.task",
+    "stock.picking",
+    "hr.employee",
+];
+
+const MAX_EXAMPLE_MODELS: usize = 5;
+
+pub fn enabled() -> bool {
+    env_truthy("MCP_TOOL_EXAMPLES")
+}
+
+/// Append examples from `instance` to the descriptions of the listed `tools`.
+///
+/// Nothing is added when the instance cannot be reached.
+pub async fn enrich(
+    completions: &Completions,
+    pool: &OdooClientPool,
+    instance: &str,
+    tools: &mut [Value],
+) {
+    let takes = |tool: &Value, arg: &str| {
+        tool.pointer(&format!("/inputSchema/properties/{arg}"))
+            .is_some()
+    };
+    if !tools.iter().any(|tool| takes(tool, "model")) {
+        return;
+    }
+    let models = example_models(&completions.models(pool, instance).await);
+    let Some(example_model) = models.first() else {
+        return;
+    };
+    let domain = example_domain(&completions.fields(pool, instance, example_model).await);
+    let models_note = format!(
+        "Models on {instance} include {}.",
+        models
+            .iter()
+            .map(|model| format!("`{model}`"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    let domain_note =
+        domain.map(|domain| format!("Example: model `{example_model}` with domain `{domain}`."));
+    for tool in tools.iter_mut() {
+        if !takes(tool, "model") {
+            continue;
+        }
+        let mut notes = vec![models_note.as_str()];
+        if takes(tool, "domain")
+            && let Some(note) = domain_note.as_deref()
+        {
+            notes.push(note);
+        }
+        let base = tool
+            .get("description")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        tool["description"] = json!(format!("{base} {}", notes.join(" ")).trim());
+    }
+}
+
+/// Up to [`MAX_EXAMPLE_MODELS`] installed models, well-known ones first.
+fn example_models(installed: &[String]) -> Vec<String> {
+    let mut models: Vec<String> = PREFERRED_MODELS
+        .iter()
+        .filter(|model| installed.iter().any(|m| m == *model))
+        .map(|model| model.to_string())
+        .collect();
+    for model in installed {
+        if models.len() >= MAX_EXAMPLE_MODELS {
+            break;
+        }
+        let technical = ["ir.", "base", "bus.", "mail.", "res.config"]
+            .iter()
+            .any(|prefix| model.starts_with(prefix));
+        if !technical && !models.contains(model) {
+            models.push(model.clone());
+        }
+    }
+    models.truncate(MAX_EXAMPLE_MODELS);
+    models
+}
+
+/// A short domain over fields the model really has, or `None` if none of the usual ones exist.
+fn example_domain(fields: &[String]) -> Option<Value> {
+    let has = |name: &str| fields.iter().any(|f| f == name);
+    let mut domain = Vec::new();
+    if has("name") {
+        domain.push(json!(["name", "ilike", "acme"]));
+    }
+    if has("active") {
+        domain.push(json!(["active", "=", true]));
+    } else if has("create_date") {
+        let since = (Utc::now() - Duration::days(30))
+            .format("%Y-%m-%d")
+            .to_string();
+        domain.push(json!(["create_date", ">=", since]));
+    }
+    (!domain.is_empty()).then_some(Value::Array(domain))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn example_models_prefer_business_models() {
+        let installed = names(&[
+            "account.move",
+            "ir.model",
+            "mail.message",
+            "res.partner",
+            "school.student",
+        ]);
+        assert_eq!(
+            example_models(&installed),
+            names(&["res.partner", "account.move", "school.student"])
+        );
+        assert!(example_models(&[]).is_empty());
+        let many: Vec<String> = (0..10).map(|i| format!("x.model{i}")).collect();
+        assert_eq!(example_models(&many).len(), MAX_EXAMPLE_MODELS);
+    }
+
+    #[test]
+    fn example_domain_uses_real_fields() {
+        assert_eq!(
+            example_domain(&names(&["active", "email", "name"])),
+            Some(json!([["name", "ilike", "acme"], ["active", "=", true]]))
+        );
+        let dated = example_domain(&names(&["create_date", "amount"])).unwrap();
+        assert_eq!(dated[0][0], "create_date");
+        assert_eq!(example_domain(&names(&["amount"])), None);
+    }
+}
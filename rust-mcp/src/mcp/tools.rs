@@ -1,22 +1,47 @@
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::sync::{Arc, RwLock};
 
 use base64::Engine;
+use futures::future::join_all;
 use serde_json::{Map, Value, json};
-use tokio::sync::Mutex;
-use tracing::{info, warn};
+use tokio::sync::{Mutex, broadcast};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
 
 use crate::cleanup;
-use crate::mcp::cache::MetadataCache;
+use crate::mcp::arg_validation;
+use crate::mcp::auth_tokens::{TokenGrant, TokenScope};
+use crate::mcp::cache::{MetadataCache, ResultCache};
+use crate::mcp::canonical;
 use crate::mcp::capability;
+use crate::mcp::confirmation::{self, ConfirmationStore};
+use crate::mcp::csv_export;
+use crate::mcp::cursors;
+use crate::mcp::inspector::SessionRequests;
+use crate::mcp::instance_health::{self, HealthChange, InstanceHealth};
 use crate::mcp::module_snapshot::{ModuleSnapshot, ModuleSnapshotStore};
-use crate::mcp::registry::{OpSpec, ToolDef, audit_tool_denial, capability_denial};
-use crate::odoo::config::{OdooEnvConfig, load_odoo_env};
-use crate::odoo::types::OdooError;
+use crate::mcp::orchestrator;
+use crate::mcp::progress::Progress;
+use crate::mcp::registry::{OpSpec, ToolDef, audit_tool_denial, capability_denial, is_mutating_op};
+use crate::mcp::renderers;
+use crate::mcp::sampling::Sampler;
+use crate::mcp::server_info::ServerInfo;
+use crate::mcp::smart_search;
+use crate::mcp::snapshot::{self, Snapshot, SnapshotStore};
+use crate::mcp::tenants::Tenant;
+use crate::mcp::view_arch;
+use crate::odoo::config::{
+    InstanceToolConfig, OdooAuthMode, OdooEnvConfig, OdooInstanceConfig, load_odoo_env,
+};
+use crate::odoo::deadline;
+use crate::odoo::types::{OdooError, OdooResult, ReportFormat};
 use crate::odoo::unified_client::OdooClient;
+use crate::packs;
 
 const DEFAULT_MAX_REPORT_BYTES: usize = 10 * 1024 * 1024;
 const ABSOLUTE_MAX_REPORT_BYTES: usize = 50 * 1024 * 1024;
+const DEFAULT_MAX_ATTACHMENT_BYTES: usize = 25 * 1024 * 1024;
+const ABSOLUTE_MAX_ATTACHMENT_BYTES: usize = 100 * 1024 * 1024;
 
 fn max_report_bytes() -> Result<usize, OdooError> {
     let value = std::env::var("ODOO_MAX_REPORT_BYTES")
@@ -33,6 +58,23 @@ fn max_report_bytes() -> Result<usize, OdooError> {
     Ok(value)
 }
 
+fn max_attachment_bytes() -> Result<usize, OdooError> {
+    let value = std::env::var("ODOO_MAX_ATTACHMENT_BYTES")
+        .ok()
+        .map(|raw| raw.parse::<usize>())
+        .transpose()
+        .map_err(|_| {
+            OdooError::InvalidResponse("ODOO_MAX_ATTACHMENT_BYTES must be an integer".into())
+        })?
+        .unwrap_or(DEFAULT_MAX_ATTACHMENT_BYTES);
+    if !(1..=ABSOLUTE_MAX_ATTACHMENT_BYTES).contains(&value) {
+        return Err(OdooError::InvalidResponse(format!(
+            "ODOO_MAX_ATTACHMENT_BYTES must be between 1 and {ABSOLUTE_MAX_ATTACHMENT_BYTES}"
+        )));
+    }
+    Ok(value)
+}
+
 fn enforce_report_size(size: usize, limit: usize) -> Result<(), OdooError> {
     if size > limit {
         return Err(OdooError::InvalidResponse(format!(
@@ -42,15 +84,39 @@ fn enforce_report_size(size: usize, limit: usize) -> Result<(), OdooError> {
     Ok(())
 }
 
+/// Instances registered by `odoo_connect`, keyed by transport scope and then instance name.
+type SessionInstances = HashMap<String, HashMap<String, (OdooInstanceConfig, OdooClient)>>;
+
+/// A successful write tool call, published so subscriptions can re-check affected records.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModelWrite {
+    /// Instance cache key (see [`OdooClientPool::cache_key`]).
+    pub instance: String,
+    /// None when the tool does not say which model it wrote (pack tools, cleanups).
+    pub model: Option<String>,
+}
+
 /// Shared state: parsed env + instantiated clients per instance.
 /// Supports both Odoo 19+ (JSON-2 API) and Odoo < 19 (JSON-RPC).
 /// The env is wrapped in RwLock to support hot-reload when instances.json changes.
+///
+/// A pool returned by [`OdooClientPool::scoped`] additionally resolves the instances its
+/// session registered with `odoo_connect`; those are never visible to other sessions.
 #[derive(Clone)]
 pub struct OdooClientPool {
     env: Arc<RwLock<OdooEnvConfig>>,
     clients: Arc<Mutex<HashMap<String, OdooClient>>>,
     pub metadata_cache: MetadataCache,
+    pub result_cache: ResultCache,
+    writes: broadcast::Sender<ModelWrite>,
+    instances_changed: broadcast::Sender<()>,
+    health: InstanceHealth,
+    confirmations: ConfirmationStore,
     module_snapshots: ModuleSnapshotStore,
+    sessions: Arc<RwLock<SessionInstances>>,
+    snapshots: SnapshotStore,
+    scope: Option<String>,
+    tenant: Option<Arc<Tenant>>,
 }
 
 impl OdooClientPool {
@@ -60,7 +126,16 @@ impl OdooClientPool {
             env: Arc::new(RwLock::new(env)),
             clients: Arc::new(Mutex::new(HashMap::new())),
             metadata_cache: MetadataCache::new(),
+            result_cache: ResultCache::from_env(),
+            writes: broadcast::channel(64).0,
+            instances_changed: broadcast::channel(4).0,
+            health: InstanceHealth::default(),
+            confirmations: ConfirmationStore::default(),
             module_snapshots: ModuleSnapshotStore::from_env(),
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+            snapshots: SnapshotStore::default(),
+            scope: None,
+            tenant: None,
         })
     }
 
@@ -70,12 +145,199 @@ impl OdooClientPool {
             env: Arc::new(RwLock::new(env)),
             clients: Arc::new(Mutex::new(HashMap::new())),
             metadata_cache: MetadataCache::new(),
+            result_cache: ResultCache::default(),
+            writes: broadcast::channel(64).0,
+            instances_changed: broadcast::channel(4).0,
+            health: InstanceHealth::default(),
+            confirmations: ConfirmationStore::default(),
             module_snapshots: ModuleSnapshotStore::memory(),
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+            snapshots: SnapshotStore::default(),
+            scope: None,
+            tenant: None,
+        }
+    }
+
+    /// A view of the pool that also sees the instances registered by `scope`
+    /// ("stdio" or an HTTP session id).
+    pub fn scoped(&self, scope: &str) -> Self {
+        Self {
+            scope: Some(scope.to_string()),
+            ..self.clone()
+        }
+    }
+
+    /// A view of the pool limited to `tenant`'s configured instances (see [`crate::mcp::tenants`]).
+    /// Instances the session registered itself stay visible.
+    pub fn for_tenant(&self, tenant: &Tenant) -> Self {
+        Self {
+            tenant: Some(Arc::new(tenant.clone())),
+            ..self.clone()
+        }
+    }
+
+    /// Whether a configured instance is visible through this view.
+    fn visible(&self, name: &str) -> bool {
+        self.tenant
+            .as_ref()
+            .is_none_or(|tenant| tenant.allows_instance(name))
+    }
+
+    fn session_instance(&self, name: &str) -> Option<(OdooInstanceConfig, OdooClient)> {
+        let scope = self.scope.as_deref()?;
+        let sessions = self.sessions.read().ok()?;
+        let instances = sessions.get(scope)?;
+        instances.get(name).cloned().or_else(|| {
+            instances
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name.trim()))
+                .map(|(_, entry)| entry.clone())
+        })
+    }
+
+    fn session_instance_names(&self) -> Vec<String> {
+        let Some(scope) = self.scope.as_deref() else {
+            return Vec::new();
+        };
+        self.sessions
+            .read()
+            .ok()
+            .and_then(|sessions| sessions.get(scope).map(|i| i.keys().cloned().collect()))
+            .unwrap_or_default()
+    }
+
+    /// Config of a canonical instance name, preferring the session's own instances.
+    fn config_for(&self, canonical: &str) -> Option<OdooInstanceConfig> {
+        if let Some((cfg, _)) = self.session_instance(canonical) {
+            return Some(cfg);
+        }
+        self.env
+            .read()
+            .ok()
+            .and_then(|env| env.instances.get(canonical).cloned())
+    }
+
+    /// Key for per-instance caches, so equally named instances of two sessions never share
+    /// module snapshots or metadata.
+    pub(crate) fn cache_key(&self, canonical: &str) -> String {
+        match self.scope.as_deref() {
+            Some(scope) if self.session_instance(canonical).is_some() => {
+                format!("session:{scope}:{canonical}")
+            }
+            _ => canonical.to_string(),
+        }
+    }
+
+    /// Register a client-supplied instance for this pool's session after checking that it
+    /// authenticates. Reconnecting under the same name replaces the previous entry.
+    pub async fn connect_session(
+        &self,
+        name: &str,
+        config: OdooInstanceConfig,
+    ) -> Result<(), OdooError> {
+        // Sessionless HTTP requests share the empty scope, so it must not hold instances.
+        let scope = self
+            .scope
+            .clone()
+            .filter(|scope| !scope.is_empty())
+            .ok_or_else(|| {
+                OdooError::InvalidResponse("odoo_connect requires an MCP session".into())
+            })?;
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(OdooError::InvalidResponse(
+                "Instance name must not be empty".into(),
+            ));
+        }
+        let configured = self
+            .env
+            .read()
+            .map(|env| resolve_instance_name_from_env(&env, name).is_ok())
+            .unwrap_or(false);
+        if configured {
+            return Err(OdooError::InvalidResponse(format!(
+                "Instance name '{name}' is already used by a configured instance"
+            )));
+        }
+
+        let client = OdooClient::new(&config)
+            .map_err(|e| OdooError::InvalidResponse(format!("Invalid connection: {e}")))?;
+        client.health_probe().await?;
+
+        let mut sessions = self
+            .sessions
+            .write()
+            .map_err(|e| OdooError::InvalidResponse(format!("Session lock poisoned: {e}")))?;
+        let instances = sessions.entry(scope).or_default();
+        instances.retain(|key, _| !key.eq_ignore_ascii_case(name));
+        instances.insert(name.to_string(), (config, client));
+        Ok(())
+    }
+
+    /// Drop every instance registered by `scope`, and its snapshot; called when the session ends.
+    pub fn disconnect_session(&self, scope: &str) {
+        if let Ok(mut sessions) = self.sessions.write() {
+            sessions.remove(scope);
+        }
+        self.snapshots.stop(scope);
+    }
+
+    /// This session's active snapshot (see [`crate::mcp::snapshot`]).
+    pub fn session_snapshot(&self) -> Option<Snapshot> {
+        self.snapshots.active(self.scope.as_deref()?)
+    }
+
+    /// `args` with the domain pinned to the session's snapshot time, when a snapshot is active
+    /// and `model` has `write_date`. Whether it has is looked up once per snapshot.
+    async fn pin_to_snapshot(
+        &self,
+        instance: &str,
+        model: &str,
+        op: &OpSpec,
+        mut args: Value,
+    ) -> Result<Value, OdooError> {
+        let (Some(scope), Some(pointer)) = (self.scope.as_deref(), op.map.get("domain")) else {
+            return Ok(args);
+        };
+        let Some(snapshot) = self.snapshots.active(scope) else {
+            return Ok(args);
+        };
+        let key = format!("{}/{model}", self.cache_key(instance));
+        let pinnable = match self.snapshots.pinnable(scope, &key) {
+            Some(pinnable) => pinnable,
+            None => {
+                let client = self
+                    .get(instance)
+                    .await
+                    .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+                let pinnable = client
+                    .fields_get(model, None)
+                    .await?
+                    .get("write_date")
+                    .is_some();
+                self.snapshots.set_pinnable(scope, &key, pinnable);
+                pinnable
+            }
+        };
+        if !pinnable {
+            return Ok(args);
         }
+        let domain = snapshot::pin_domain(args.pointer(pointer), &snapshot.odoo_at());
+        if let Some(slot) = args.pointer_mut(pointer) {
+            *slot = domain;
+        } else if let (Some(key), Value::Object(map)) = (pointer.strip_prefix('/'), &mut args)
+            && !key.contains('/')
+        {
+            map.insert(key.to_string(), domain);
+        }
+        Ok(args)
     }
 
     pub async fn get(&self, instance: &str) -> anyhow::Result<OdooClient> {
         let canonical_name = self.resolve_instance_name(instance)?;
+        if let Some((_, client)) = self.session_instance(&canonical_name) {
+            return Ok(client);
+        }
 
         {
             let guard = self.clients.lock().await;
@@ -102,54 +364,122 @@ impl OdooClientPool {
         Ok(client)
     }
 
+    /// Configured instances visible through this view, by name. Session instances are left out.
+    pub fn instance_configs(&self) -> Vec<(String, OdooInstanceConfig)> {
+        let mut configs: Vec<(String, OdooInstanceConfig)> = self
+            .env
+            .read()
+            .map(|env| {
+                env.instances
+                    .iter()
+                    .filter(|(name, _)| self.visible(name))
+                    .map(|(name, cfg)| (name.clone(), cfg.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        configs.sort_by(|a, b| a.0.cmp(&b.0));
+        configs
+    }
+
     pub fn instance_names(&self) -> Vec<String> {
-        self.env
+        let mut names: Vec<String> = self
+            .env
             .read()
-            .map(|env| env.instances.keys().cloned().collect())
-            .unwrap_or_default()
+            .map(|env| {
+                env.instances
+                    .keys()
+                    .filter(|name| self.visible(name))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        names.extend(self.session_instance_names());
+        names
     }
 
     pub fn all_instances_read_only(&self) -> bool {
         self.env
             .read()
-            .map(|env| !env.instances.is_empty() && env.instances.values().all(|cfg| cfg.read_only))
+            .map(|env| {
+                let mut visible = env
+                    .instances
+                    .iter()
+                    .filter(|(name, _)| self.visible(name))
+                    .peekable();
+                visible.peek().is_some() && visible.all(|(_, cfg)| cfg.read_only)
+            })
             .unwrap_or(false)
     }
 
     pub fn instance_is_read_only(&self, instance: &str) -> bool {
         self.resolve_instance_name(instance)
             .ok()
-            .and_then(|name| {
-                self.env
-                    .read()
-                    .ok()
-                    .and_then(|env| env.instances.get(&name).map(|cfg| cfg.read_only))
-            })
-            .unwrap_or(false)
+            .and_then(|name| self.config_for(&name))
+            .is_some_and(|cfg| cfg.read_only)
     }
 
-    pub(crate) fn instance_config(
-        &self,
-        instance: &str,
-    ) -> Result<crate::odoo::config::OdooInstanceConfig, OdooError> {
+    pub fn instance_is_degraded(&self, instance: &str) -> bool {
+        self.resolve_instance_name(instance)
+            .is_ok_and(|name| self.health.is_degraded(&name))
+    }
+
+    /// Whether `tools/list` hides mutating tools: the instance is read-only or degraded (see
+    /// [`instance_health`]). Unscoped lists (`None`) hide them only when every instance does.
+    pub fn hides_write_tools(&self, instance: Option<&str>) -> bool {
+        match instance {
+            Some(instance) => {
+                self.instance_is_read_only(instance) || self.instance_is_degraded(instance)
+            }
+            None => self
+                .env
+                .read()
+                .map(|env| {
+                    let mut visible = env
+                        .instances
+                        .iter()
+                        .filter(|(name, _)| self.visible(name))
+                        .peekable();
+                    visible.peek().is_some()
+                        && visible.all(|(name, cfg)| cfg.read_only || self.health.is_degraded(name))
+                })
+                .unwrap_or(false),
+        }
+    }
+
+    pub(crate) fn instance_config(&self, instance: &str) -> Result<OdooInstanceConfig, OdooError> {
         let name = self
             .resolve_instance_name(instance)
             .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
-        self.env
-            .read()
-            .map_err(|e| OdooError::InvalidResponse(format!("Instance config lock poisoned: {e}")))?
-            .instances
-            .get(&name)
-            .cloned()
+        self.config_for(&name)
             .ok_or_else(|| OdooError::InvalidResponse(format!("Unknown Odoo instance '{name}'")))
     }
 
     pub fn resolve_instance_name(&self, requested: &str) -> anyhow::Result<String> {
+        if let Some(name) = self
+            .session_instance_names()
+            .into_iter()
+            .find(|name| name == requested || name.eq_ignore_ascii_case(requested.trim()))
+        {
+            return Ok(name);
+        }
         let env = self
             .env
             .read()
             .map_err(|e| anyhow::anyhow!("Instance config lock poisoned: {e}"))?;
-        resolve_instance_name_from_env(&env, requested)
+        if self.tenant.is_none() {
+            return resolve_instance_name_from_env(&env, requested);
+        }
+        // Resolve against the tenant's instances only, so errors and suggestions never
+        // name another tenant's instance.
+        let visible = OdooEnvConfig {
+            instances: env
+                .instances
+                .iter()
+                .filter(|(name, _)| self.visible(name))
+                .map(|(name, cfg)| (name.clone(), cfg.clone()))
+                .collect(),
+        };
+        resolve_instance_name_from_env(&visible, requested)
     }
 
     fn apply_instance_tool_config(
@@ -158,11 +488,7 @@ impl OdooClientPool {
         tool: &ToolDef,
         args: Value,
     ) -> Result<Value, OdooError> {
-        let instance_config = self
-            .env
-            .read()
-            .ok()
-            .and_then(|env| env.instances.get(instance).cloned());
+        let instance_config = self.config_for(instance);
 
         if instance_config
             .as_ref()
@@ -170,7 +496,7 @@ impl OdooClientPool {
         {
             audit_tool_denial(instance, tool, "read_only", "");
             return Err(OdooError::InvalidResponse(format!(
-                "Tool '{}' is disabled for read-only instance '{instance}'",
+                "Tool '{}' is disabled for read-only instance '{instance}' (readOnly is set in its instance config)",
                 tool.name
             )));
         }
@@ -206,12 +532,7 @@ impl OdooClientPool {
     fn execute_allowed(&self, instance: &str, model: &str, method: &str) -> bool {
         self.resolve_instance_name(instance)
             .ok()
-            .and_then(|name| {
-                self.env
-                    .read()
-                    .ok()
-                    .and_then(|env| env.instances.get(&name).cloned())
-            })
+            .and_then(|name| self.config_for(&name))
             .and_then(|cfg| cfg.tool_config)
             .is_some_and(|config| {
                 config.execute_allowlist.iter().any(|entry| {
@@ -220,16 +541,15 @@ impl OdooClientPool {
             })
     }
 
-    pub fn disabled_packs(&self, instance: &str) -> Vec<String> {
+    pub fn tool_config(&self, instance: &str) -> Option<InstanceToolConfig> {
         self.resolve_instance_name(instance)
             .ok()
-            .and_then(|name| {
-                self.env
-                    .read()
-                    .ok()
-                    .and_then(|env| env.instances.get(&name).cloned())
-            })
+            .and_then(|name| self.config_for(&name))
             .and_then(|config| config.tool_config)
+    }
+
+    pub fn disabled_packs(&self, instance: &str) -> Vec<String> {
+        self.tool_config(instance)
             .map(|config| config.disabled_packs)
             .unwrap_or_default()
     }
@@ -244,14 +564,15 @@ impl OdooClientPool {
                     .await;
             }
         };
-        if let Some(snapshot) = self.module_snapshots.fresh(&canonical).await {
+        let key = self.cache_key(&canonical);
+        if let Some(snapshot) = self.module_snapshots.fresh(&key).await {
             return snapshot;
         }
         if let Ok(snapshot) = self.refresh_module_snapshot(&canonical).await {
             return snapshot;
         }
         self.module_snapshots
-            .get(&canonical)
+            .get(&key)
             .await
             .expect("failed module refresh always records a stale snapshot")
     }
@@ -264,6 +585,7 @@ impl OdooClientPool {
             .resolve_instance_name(instance)
             .map_err(|error| OdooError::InvalidResponse(error.to_string()))?;
         let config = self.instance_config(&canonical)?;
+        let key = self.cache_key(&canonical);
         let result = async {
             let client = self
                 .get(&canonical)
@@ -303,7 +625,7 @@ impl OdooClientPool {
                     Ok(modules) => modules,
                     Err(error) => {
                         self.module_snapshots
-                            .failure(&canonical, &error.to_string())
+                            .failure(&key, &error.to_string())
                             .await;
                         return Err(error);
                     }
@@ -322,18 +644,97 @@ impl OdooClientPool {
                     });
                 Ok(self
                     .module_snapshots
-                    .success(&canonical, config.version, edition, modules)
+                    .success(&key, config.version, edition, modules)
                     .await)
             }
             Err(error) => {
                 self.module_snapshots
-                    .failure(&canonical, &error.to_string())
+                    .failure(&key, &error.to_string())
                     .await;
                 Err(error)
             }
         }
     }
 
+    /// Writes made through this pool (any scope), for cache and subscription invalidation.
+    pub fn subscribe_writes(&self) -> broadcast::Receiver<ModelWrite> {
+        self.writes.subscribe()
+    }
+
+    /// Instances turning degraded or recovering under the health monitor.
+    pub fn subscribe_health_changes(&self) -> broadcast::Receiver<HealthChange> {
+        self.health.subscribe()
+    }
+
+    /// Fires when a reload changes the configured instance names or read-only flags, or an
+    /// instance becomes degraded or recovers.
+    pub fn subscribe_instance_changes(&self) -> broadcast::Receiver<()> {
+        self.instances_changed.subscribe()
+    }
+
+    /// Probe configured instances in the background when health gating is enabled.
+    pub fn spawn_health_monitor(&self) {
+        if !instance_health::gating_enabled() {
+            return;
+        }
+        let pool = self.clone();
+        let mut ticker = tokio::time::interval(instance_health::probe_interval());
+        tokio::spawn(async move {
+            loop {
+                ticker.tick().await;
+                if pool.probe_health().await {
+                    let _ = pool.instances_changed.send(());
+                }
+            }
+        });
+    }
+
+    /// Probe every configured instance once; returns whether any became degraded or recovered.
+    pub async fn probe_health(&self) -> bool {
+        let names: Vec<String> = self
+            .env
+            .read()
+            .map(|env| env.instances.keys().cloned().collect())
+            .unwrap_or_default();
+        let mut changed = self.health.retain(&names);
+        let outcomes = join_all(names.iter().map(|name| async move {
+            let outcome = match self.get(name).await {
+                Ok(client) => client.health_probe().await.map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+            (name, outcome)
+        }))
+        .await;
+        for (name, outcome) in outcomes {
+            let error = outcome.as_ref().err().cloned();
+            if self.health.record(name, outcome) {
+                changed = true;
+                match error {
+                    Some(error) => warn!(instance = %name, %error, "Odoo instance degraded"),
+                    None => info!(instance = %name, "Odoo instance recovered"),
+                }
+            }
+        }
+        changed
+    }
+
+    /// Drop cached read results a write on `canonical` may have made stale and tell
+    /// subscribers about it.
+    pub async fn record_write(&self, canonical: &str, model: Option<&str>) {
+        let instance = self.cache_key(canonical);
+        let dropped = self.result_cache.invalidate(&instance, model).await;
+        debug!(
+            instance = %instance,
+            model = model.unwrap_or("*"),
+            dropped,
+            "Invalidated cached reads after write"
+        );
+        let _ = self.writes.send(ModelWrite {
+            instance,
+            model: model.map(str::to_string),
+        });
+    }
+
     /// Hot-reload instances from ODOO_INSTANCES_JSON.
     /// Called by the config server when instances.json is saved via the Config UI.
     /// Clears cached clients so next call creates fresh ones with the new config.
@@ -341,9 +742,18 @@ impl OdooClientPool {
         match load_odoo_env() {
             Ok(new_env) => {
                 let count = new_env.instances.len();
+                let mut listing_changed = false;
                 // Write lock scope — must NOT hold std::sync lock across .await
                 let write_ok = match self.env.write() {
                     Ok(mut env) => {
+                        // Names and read-only flags both shape tools/list.
+                        let listing = |env: &OdooEnvConfig| {
+                            env.instances
+                                .iter()
+                                .map(|(name, cfg)| (name.clone(), cfg.read_only))
+                                .collect::<BTreeMap<_, _>>()
+                        };
+                        listing_changed = listing(&env) != listing(&new_env);
                         *env = new_env;
                         true
                     }
@@ -357,6 +767,9 @@ impl OdooClientPool {
                     self.clients.lock().await.clear();
                     self.module_snapshots.mark_all_stale().await;
                     info!("OdooClientPool: hot-reloaded {} instance(s)", count);
+                    if listing_changed {
+                        let _ = self.instances_changed.send(());
+                    }
                 }
             }
             Err(e) => {
@@ -369,12 +782,83 @@ impl OdooClientPool {
     }
 }
 
+/// Per-call state supplied by the transport rather than the tool arguments.
+#[derive(Clone, Default)]
+pub struct CallContext {
+    pub cancel: CancellationToken,
+    /// Present when the calling client supports MCP sampling.
+    pub sampler: Option<Sampler>,
+    /// API token of the HTTP request; `None` is unrestricted.
+    pub grant: Option<TokenGrant>,
+    /// Present when the client sent a `progressToken` with the call.
+    pub progress: Option<Progress>,
+    /// The calling session's recorded requests, unless the inspector is disabled.
+    pub requests: Option<SessionRequests>,
+    /// Effective server configuration, when called through the MCP handler.
+    pub server: Option<ServerInfo>,
+}
+
+/// `args` with the op's pinned arguments written over the caller's.
+fn with_pinned_args(mut args: Value, op: &OpSpec) -> Value {
+    if let Value::Object(map) = &mut args {
+        for (key, value) in &op.pinned {
+            map.insert(key.clone(), value.clone());
+        }
+    }
+    args
+}
+
+/// Run a tool call until it completes or `cancel` fires.
+///
+/// On cancellation the pending operation future is dropped, which aborts the in-flight
+/// Odoo HTTP request (and any retry backoff) instead of letting it run to completion.
+#[tracing::instrument(
+    name = "call_tool",
+    skip_all,
+    fields(tool = %tool.name, op = %tool.op.op_type)
+)]
 pub async fn call_tool(
+    pool: &OdooClientPool,
+    tool: &ToolDef,
+    mut args: Value,
+    ctx: &CallContext,
+) -> Result<Value, OdooError> {
+    // Tools whose op reads `format` themselves keep it.
+    let format = if tool.op.map.contains_key(renderers::FORMAT_ARG) {
+        None
+    } else {
+        renderers::take_format(&tool.input_schema, &mut args)
+            .map_err(|e| OdooError::InvalidArguments(vec![e]))?
+    };
+    cursors::expand(&tool.op, &mut args).map_err(|e| OdooError::InvalidArguments(vec![e]))?;
+    let result = run_tool(pool, tool, args, ctx).await?;
+    Ok(renderers::render_result(result, format.as_deref()))
+}
+
+async fn run_tool(
     pool: &OdooClientPool,
     tool: &ToolDef,
     args: Value,
+    ctx: &CallContext,
 ) -> Result<Value, OdooError> {
     let requested_instance = instance_from_args(&args, &tool.op);
+    let required_scope = TokenScope::for_op(&tool.op.op_type);
+    if let Some(grant) = ctx.grant.as_ref()
+        && !grant.allows(required_scope)
+    {
+        audit_tool_denial(
+            requested_instance.as_deref().unwrap_or("unknown"),
+            tool,
+            "token_scope",
+            required_scope.as_str(),
+        );
+        return Err(OdooError::InvalidResponse(format!(
+            "Token '{}' lacks the '{}' scope required by tool '{}'",
+            grant.name,
+            required_scope.as_str(),
+            tool.name
+        )));
+    }
     if controlled_mode()
         && is_mutating_op(&tool.op.op_type)
         && tool.op.op_type != "execute_capability"
@@ -389,6 +873,26 @@ pub async fn call_tool(
             "generic mutation tools are disabled in controlled capability mode".into(),
         ));
     }
+    if is_mutating_op(&tool.op.op_type)
+        && let Some(snapshot) = pool.session_snapshot()
+    {
+        audit_tool_denial(
+            requested_instance.as_deref().unwrap_or("unknown"),
+            tool,
+            "snapshot",
+            "",
+        );
+        return Err(OdooError::InvalidResponse(format!(
+            "Tool '{}' is disabled while this session's read-only snapshot (pinned to {}) is active; end it with odoo_snapshot action 'stop'",
+            tool.name,
+            snapshot.at.to_rfc3339()
+        )));
+    }
+    arg_validation::validate_args(&tool.input_schema, &args)
+        .map_err(OdooError::InvalidArguments)?;
+    let args = with_pinned_args(args, &tool.op);
+    let requested_instance = requested_instance.or_else(|| instance_from_args(&args, &tool.op));
+    let mut canonical = None;
     let args = if let Some(instance) = requested_instance {
         let canonical_instance = pool
             .resolve_instance_name(&instance)
@@ -407,12 +911,226 @@ pub async fn call_tool(
                 tool.name, canonical_instance, reason, detail
             )));
         }
-        pool.apply_instance_tool_config(&canonical_instance, tool, args)?
+        let args = pool.apply_instance_tool_config(&canonical_instance, tool, args)?;
+        canonical = Some(canonical_instance);
+        args
     } else {
         args
     };
 
-    execute_op(pool, &tool.op, args).await
+    let args = match confirm_destructive(pool, tool, args, canonical.as_deref()).await? {
+        Confirmation::Proceed(args) => args,
+        Confirmation::Preview(preview) => return Ok(preview),
+    };
+    // Checked on the final arguments, so instance defaults cannot switch amounts on.
+    if let Some(grant) = ctx.grant.as_ref()
+        && reads_payroll_amounts(&tool.op, &args)
+        && !grant.allows(TokenScope::Admin)
+    {
+        audit_tool_denial(
+            canonical.as_deref().unwrap_or("unknown"),
+            tool,
+            "token_scope",
+            TokenScope::Admin.as_str(),
+        );
+        return Err(OdooError::InvalidResponse(format!(
+            "Token '{}' lacks the 'admin' scope required for payslip amounts",
+            grant.name
+        )));
+    }
+
+    let model = ptr(&args, &tool.op, "model")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    let args = match (&canonical, &model) {
+        (Some(instance), Some(model))
+            if snapshot::PINNED_OPS.contains(&tool.op.op_type.as_str()) =>
+        {
+            pool.pin_to_snapshot(instance, model, &tool.op, args)
+                .await?
+        }
+        _ => args,
+    };
+    // Cached read results, keyed per instance and model so writes can invalidate them.
+    let cache_slot = match (&canonical, &model) {
+        // A cached summary would skip the batches a streamed call exists to send.
+        (Some(instance), Some(model))
+            if pool.result_cache.is_enabled()
+                && is_cacheable_op(&tool.op.op_type)
+                && !streams_records(&args, &tool.op) =>
+        {
+            let args = canonical::strip_defaults(&args, &tool.input_schema);
+            let key = canonical::digest(&json!({ "op": tool.op.op_type, "args": args }));
+            Some((pool.cache_key(instance), model.clone(), key))
+        }
+        _ => None,
+    };
+    if let Some((instance, model, key)) = &cache_slot
+        && let Some(cached) = pool.result_cache.get(instance, model, key).await
+    {
+        return Ok(cached);
+    }
+
+    let run = async {
+        match tool.op.op_type.as_str() {
+            // Needs the calling client, so it is routed here rather than in execute_op.
+            "smart_search" => op_smart_search(pool, &tool.op, args, ctx.sampler.as_ref()).await,
+            "composite" => op_composite(pool, &tool.op, args, ctx.progress.as_ref()).await,
+            "debug_requests" => op_debug_requests(&tool.op, args, ctx.requests.as_ref()),
+            "server_info" => op_server_info(pool, ctx.server.as_ref()).await,
+            "search_read" if streams_records(&args, &tool.op) => {
+                op_search_read_streamed(pool, &tool.op, args, ctx.progress.as_ref()).await
+            }
+            _ => execute_op(pool, &tool.op, args).await,
+        }
+    };
+    let result = tokio::select! {
+        biased;
+        _ = ctx.cancel.cancelled() => Err(OdooError::Cancelled),
+        _ = deadline::expired() => Err(OdooError::DeadlineExceeded { last_error: None }),
+        result = run => result,
+    };
+
+    if let Ok(value) = &result {
+        if let Some((instance, model, key)) = cache_slot {
+            pool.result_cache
+                .insert(&instance, &model, key, value.clone())
+                .await;
+        } else if is_mutating_op(&tool.op.op_type)
+            && let Some(instance) = &canonical
+        {
+            pool.record_write(instance, model.as_deref()).await;
+        }
+    }
+    result
+}
+
+/// Salary amounts are only returned to `admin` tokens, on top of `ODOO_ENABLE_PAYROLL_AMOUNTS`.
+fn reads_payroll_amounts(op: &OpSpec, args: &Value) -> bool {
+    op.op_type == "payslip_summary"
+        && ptr(args, op, "includeAmounts").and_then(Value::as_bool) == Some(true)
+}
+
+enum Confirmation {
+    Proceed(Value),
+    Preview(Value),
+}
+
+/// Two-phase confirmation of destructive calls (see [`confirmation`]); strips the token
+/// argument from `args` either way.
+async fn confirm_destructive(
+    pool: &OdooClientPool,
+    tool: &ToolDef,
+    mut args: Value,
+    instance: Option<&str>,
+) -> Result<Confirmation, OdooError> {
+    let token = args
+        .as_object_mut()
+        .and_then(|map| map.remove(confirmation::TOKEN_ARG));
+    if !confirmation::enabled() || !needs_confirmation(&tool.op, &args) {
+        return Ok(Confirmation::Proceed(args));
+    }
+    let call_digest = canonical::digest(&json!({
+        "tool": tool.name,
+        "instance": instance,
+        "args": args,
+    }));
+    let scope = pool.scope.as_deref();
+    match token
+        .as_ref()
+        .map(|token| token.as_str().unwrap_or_default())
+    {
+        Some(token) if pool.confirmations.redeem(token, scope, &call_digest) => {
+            Ok(Confirmation::Proceed(args))
+        }
+        Some(_) => Err(OdooError::InvalidResponse(format!(
+            "Confirmation token for '{}' is invalid, expired, or was issued for different \
+             arguments; call again without it for a new preview",
+            tool.name
+        ))),
+        None => {
+            let preview = destructive_preview(pool, &tool.op, &args).await?;
+            let ttl = confirmation::ttl();
+            let token = pool.confirmations.issue(scope, &call_digest, ttl);
+            Ok(Confirmation::Preview(ok_text(json!({
+                "confirmationRequired": true,
+                "confirmationToken": token,
+                "expiresInSecs": ttl.as_secs(),
+                "preview": preview,
+                "message": format!(
+                    "Nothing was changed. Call '{}' again with the same arguments and \
+                     confirmationToken to proceed.",
+                    tool.name
+                ),
+            }))))
+        }
+    }
+}
+
+fn needs_confirmation(op: &OpSpec, args: &Value) -> bool {
+    match op.op_type.as_str() {
+        "unlink" => true,
+        // Cleanups default to a dry run.
+        "database_cleanup" | "deep_cleanup" => {
+            ptr(args, op, "dryRun").and_then(Value::as_bool) == Some(false)
+        }
+        _ => false,
+    }
+}
+
+/// What a destructive call would do: the records an `unlink` would delete, or the report of
+/// the same cleanup run as a dry run.
+async fn destructive_preview(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: &Value,
+) -> Result<Value, OdooError> {
+    if op.op_type == "unlink" {
+        let instance = req_str(args, op, "instance")?;
+        let model = req_str(args, op, "model")?;
+        let ids = req_vec_i64(args, op, "ids")?;
+        let client = pool
+            .get(&instance)
+            .await
+            .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+        let records = client
+            .read(
+                &model,
+                ids.clone(),
+                Some(vec!["display_name".to_string()]),
+                opt_value(args, op, "context"),
+            )
+            .await?;
+        return Ok(json!({ "model": model, "ids": ids, "records": records }));
+    }
+    let mut dry_run = args.clone();
+    if let Some(slot) = op
+        .map
+        .get("dryRun")
+        .and_then(|pointer| dry_run.pointer_mut(pointer))
+    {
+        *slot = json!(true);
+    }
+    let report = execute_op(pool, op, dry_run).await?;
+    Ok(report
+        .pointer("/content/0/text")
+        .and_then(Value::as_str)
+        .and_then(|text| serde_json::from_str(text).ok())
+        .unwrap_or(report))
+}
+
+/// Plain reads of one model whose results may be served from [`ResultCache`].
+fn is_cacheable_op(op_type: &str) -> bool {
+    matches!(
+        op_type,
+        "search"
+            | "search_read"
+            | "read"
+            | "search_count"
+            | "read_group"
+            | "name_search"
+            | "name_get"
+    )
 }
 
 pub async fn execute_op(
@@ -424,6 +1142,7 @@ pub async fn execute_op(
         "search" => op_search(pool, op, args).await,
         "search_read" => op_search_read(pool, op, args).await,
         "read" => op_read(pool, op, args).await,
+        "multi_read" => op_multi_read(pool, op, args).await,
         "create" => op_create(pool, op, args).await,
         "write" => op_write(pool, op, args).await,
         "unlink" => op_unlink(pool, op, args).await,
@@ -431,6 +1150,15 @@ pub async fn execute_op(
         "workflow_action" => op_workflow_action(pool, op, args).await,
         "execute" => op_execute(pool, op, args).await,
         "generate_report" => op_generate_report(pool, op, args).await,
+        "attachment_upload" => op_attachment_upload(pool, op, args).await,
+        "attachment_download" => op_attachment_download(pool, op, args).await,
+        "list_attachments" => op_list_attachments(pool, op, args).await,
+        "message_post" => op_message_post(pool, op, args).await,
+        "activity_schedule" => op_activity_schedule(pool, op, args).await,
+        "send_email" => op_send_email(pool, op, args).await,
+        "render_email_template" => op_render_email_template(pool, op, args).await,
+        "export_records" => op_export_records(pool, op, args).await,
+        "import_records" => op_import_records(pool, op, args).await,
         "get_model_metadata" => op_get_model_metadata(pool, op, args).await,
         "database_cleanup" => op_database_cleanup(pool, op, args).await,
         "deep_cleanup" => op_deep_cleanup(pool, op, args).await,
@@ -444,10 +1172,99 @@ pub async fn execute_op(
         "copy" => op_copy(pool, op, args).await,
         "onchange" => op_onchange(pool, op, args).await,
         "list_models" => op_list_models(pool, op, args).await,
+        "view_definition" => op_view_definition(pool, op, args).await,
+        "access_check" => op_access_check(pool, op, args).await,
+        "snapshot_session" => op_snapshot_session(pool, op, args),
+        "list_reports" => op_list_reports(pool, op, args).await,
         "check_access" => op_check_access(pool, op, args).await,
         "create_batch" => op_create_batch(pool, op, args).await,
+        "update_batch" => op_update_batch(pool, op, args).await,
+        "upsert" => op_upsert(pool, op, args).await,
+        "xmlid_lookup" => op_xmlid_lookup(pool, op, args).await,
+        "xmlid_assign" => op_xmlid_assign(pool, op, args).await,
+        "helpdesk_create_ticket" => run_pack(pool, op, args, packs::helpdesk::create_ticket).await,
+        "helpdesk_assign_ticket" => run_pack(pool, op, args, packs::helpdesk::assign_ticket).await,
+        "helpdesk_reply" => run_pack(pool, op, args, packs::helpdesk::reply).await,
+        "helpdesk_sla_status" => run_pack(pool, op, args, packs::helpdesk::sla_status).await,
         "execute_capability" => capability::execute(pool, args).await.map(ok_text),
         "refresh_capabilities" => op_refresh_capabilities(pool, op, args).await,
+        "module_list" => op_module_list(pool, op, args).await,
+        "module_update_list" => op_module_update_list(pool, op, args).await,
+        "settings_get" => op_settings_get(pool, op, args).await,
+        "settings_apply" => op_settings_apply(pool, op, args).await,
+        "cron_list" => op_cron_list(pool, op, args).await,
+        "cron_control" => op_cron_control(pool, op, args).await,
+        "server_action_run" => op_server_action_run(pool, op, args).await,
+        "module_install" | "module_upgrade" | "module_uninstall" => {
+            op_module_button(pool, op, args).await
+        }
+        "documents_list_workspaces" => {
+            run_pack(pool, op, args, packs::documents::list_workspaces).await
+        }
+        "documents_upload" => run_pack(pool, op, args, packs::documents::upload_document).await,
+        "documents_request_signature" => {
+            run_pack(pool, op, args, packs::documents::request_signature).await
+        }
+        "timesheet_summary" => run_pack(pool, op, args, packs::timesheet::timesheet_summary).await,
+        "payslip_summary" => run_pack(pool, op, args, packs::payroll::payslip_summary).await,
+        "subscription_list" => {
+            run_pack(pool, op, args, packs::subscription::list_subscriptions).await
+        }
+        "subscription_metrics" => {
+            run_pack(pool, op, args, packs::subscription::subscription_metrics).await
+        }
+        "subscription_renew_invoice" => {
+            run_pack(pool, op, args, packs::subscription::create_renewal_invoices).await
+        }
+        "equipment_lookup" => run_pack(pool, op, args, packs::quality::lookup_equipment).await,
+        "maintenance_request_create" => {
+            run_pack(pool, op, args, packs::quality::create_maintenance_request).await
+        }
+        "quality_check_create" => {
+            run_pack(pool, op, args, packs::quality::create_quality_check).await
+        }
+        "quality_alert_create" => {
+            run_pack(pool, op, args, packs::quality::create_quality_alert).await
+        }
+        "user_create" => run_pack(pool, op, args, packs::users::create_user).await,
+        "user_reset_password" => run_pack(pool, op, args, packs::users::reset_password).await,
+        "user_set_active" => run_pack(pool, op, args, packs::users::set_active).await,
+        "user_groups" => run_pack(pool, op, args, packs::users::update_groups).await,
+        "fleet_list_vehicles" => run_pack(pool, op, args, packs::fleet::list_vehicles).await,
+        "fleet_log_odometer" => run_pack(pool, op, args, packs::fleet::log_odometer).await,
+        "fleet_create_service" => run_pack(pool, op, args, packs::fleet::create_service).await,
+        "event_list" => run_pack(pool, op, args, packs::event::list_events).await,
+        "event_register" => run_pack(pool, op, args, packs::event::register_attendees).await,
+        "event_export_attendees" => run_pack(pool, op, args, packs::event::export_attendees).await,
+        "survey_results" => run_pack(pool, op, args, packs::survey::survey_results).await,
+        "mailing_stats" => run_pack(pool, op, args, packs::mailing::mailing_stats).await,
+        "mailing_list_sizes" => run_pack(pool, op, args, packs::mailing::mailing_list_sizes).await,
+        "automation_list" => run_pack(pool, op, args, packs::automation::list_rules).await,
+        "automation_inspect" => run_pack(pool, op, args, packs::automation::inspect_rule).await,
+        "automation_set_active" => {
+            run_pack(pool, op, args, packs::automation::set_rule_active).await
+        }
+        "delivery_tracking" => run_pack(pool, op, args, packs::delivery::delivery_tracking).await,
+        "delivery_send_to_shipper" => {
+            run_pack(pool, op, args, packs::delivery::send_to_shipper).await
+        }
+        "delivery_rate" => run_pack(pool, op, args, packs::delivery::shipping_rate).await,
+        "bank_reconcile_suggest" => {
+            run_pack(pool, op, args, packs::reconciliation::suggest_matches).await
+        }
+        "bank_reconcile_confirm" => {
+            run_pack(pool, op, args, packs::reconciliation::confirm_match).await
+        }
+        "connect" => op_connect(pool, op, args).await,
+        "analytic_rollup" => run_pack(pool, op, args, packs::analytic::analytic_rollup).await,
+        "inventory_valuation" => {
+            run_pack(pool, op, args, packs::inventory::inventory_valuation).await
+        }
+        "price_simulation" => run_pack(pool, op, args, packs::pricing::price_simulation).await,
+        "credit_exposure" => run_pack(pool, op, args, packs::credit::credit_exposure).await,
+        "partner_360" => run_pack(pool, op, args, packs::partner::partner_360).await,
+        "order_360" => run_pack(pool, op, args, packs::order::order_360).await,
+        "composite" => op_composite(pool, op, args, None).await,
         other => Err(OdooError::InvalidResponse(format!(
             "Unknown op.type: {other}"
         ))),
@@ -466,9 +1283,508 @@ async fn op_refresh_capabilities(
     )?))
 }
 
-fn controlled_mode() -> bool {
-    std::env::var("ODOO_CAPABILITY_CONTROLLED_MODE").is_ok_and(|value| {
-        matches!(
+const MODULE_FIELDS: &[&str] = &[
+    "name",
+    "shortdesc",
+    "state",
+    "installed_version",
+    "latest_version",
+    "application",
+    "author",
+];
+
+async fn op_module_list(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let mut domain = Vec::new();
+    if let Some(state) = opt_str(&args, op, "state")? {
+        domain.push(json!(["state", "=", state]));
+    }
+    if let Some(search) = opt_str(&args, op, "search")? {
+        domain.extend([
+            json!("|"),
+            json!(["name", "ilike", search]),
+            json!(["shortdesc", "ilike", search]),
+        ]);
+    }
+    let modules = client
+        .search_read(
+            "ir.module.module",
+            Some(Value::Array(domain)),
+            Some(MODULE_FIELDS.iter().map(|f| f.to_string()).collect()),
+            Some(opt_i64(&args, op, "limit")?.unwrap_or(200)),
+            None,
+            Some("name asc".to_string()),
+            None,
+        )
+        .await?;
+    Ok(ok_text(json!({ "modules": modules })))
+}
+
+/// `update_list`: rescan the addons path so new modules can be installed. Odoo returns the
+/// number of updated and added modules.
+async fn op_module_update_list(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let counts = client
+        .call_named("ir.module.module", "update_list", None, Map::new(), None)
+        .await?;
+    Ok(ok_text(json!({
+        "updated": counts.get(0).cloned().unwrap_or(Value::Null),
+        "added": counts.get(1).cloned().unwrap_or(Value::Null)
+    })))
+}
+
+/// `button_immediate_install` / `_upgrade` / `_uninstall` on the named modules. Modules already
+/// in the wanted state are skipped; unknown names fail the call before anything runs.
+async fn op_module_button(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let names = opt_vec_string(&args, op, "modules")?.unwrap_or_default();
+    if names.is_empty() {
+        return Err(OdooError::InvalidResponse(
+            "'modules' must name at least one module".to_string(),
+        ));
+    }
+    let (method, wanted) = match op.op_type.as_str() {
+        "module_install" => ("button_immediate_install", "uninstalled"),
+        "module_upgrade" => ("button_immediate_upgrade", "installed"),
+        _ => ("button_immediate_uninstall", "installed"),
+    };
+    if method == "button_immediate_uninstall" && !opt_bool(&args, op, "confirm")?.unwrap_or(false) {
+        return Err(OdooError::InvalidResponse(
+            "Refusing to uninstall modules without 'confirm': true; uninstalling drops their data"
+                .to_string(),
+        ));
+    }
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let found = client
+        .search_read(
+            "ir.module.module",
+            Some(json!([["name", "in", names]])),
+            Some(vec!["name".to_string(), "state".to_string()]),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await?;
+    let found = found.as_array().cloned().unwrap_or_default();
+    let state_of = |name: &str| {
+        found
+            .iter()
+            .find(|m| m.get("name").and_then(Value::as_str) == Some(name))
+            .map(|m| {
+                (
+                    m.get("id").and_then(Value::as_i64).unwrap_or_default(),
+                    m.get("state").and_then(Value::as_str).unwrap_or_default(),
+                )
+            })
+    };
+    let unknown: Vec<&String> = names.iter().filter(|n| state_of(n).is_none()).collect();
+    if !unknown.is_empty() {
+        return Err(OdooError::InvalidResponse(format!(
+            "Unknown modules: {} (run odoo_update_module_list to pick up new addons)",
+            unknown
+                .iter()
+                .map(|n| n.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )));
+    }
+    // Uninstallable modules count as candidates for install too.
+    let (run, skipped): (Vec<&String>, Vec<&String>) = names.iter().partition(|n| {
+        let state = state_of(n).map(|(_, state)| state).unwrap_or_default();
+        state == wanted || (wanted == "uninstalled" && state == "uninstallable")
+    });
+    let ids: Vec<i64> = run
+        .iter()
+        .filter_map(|n| state_of(n))
+        .map(|(id, _)| id)
+        .collect();
+    if !ids.is_empty() {
+        client
+            .call_named("ir.module.module", method, Some(ids), Map::new(), None)
+            .await?;
+        // Installed modules decide which packs and tools are offered.
+        let _ = pool.refresh_module_snapshot(&instance).await;
+    }
+    let states = client
+        .search_read(
+            "ir.module.module",
+            Some(json!([["name", "in", names]])),
+            Some(vec!["name".to_string(), "state".to_string()]),
+            None,
+            None,
+            Some("name asc".to_string()),
+            None,
+        )
+        .await?;
+    Ok(ok_text(json!({
+        "method": method,
+        "processed": run,
+        "skipped": skipped
+            .iter()
+            .map(|n| json!({"name": n, "state": state_of(n).map(|(_, s)| s)}))
+            .collect::<Vec<_>>(),
+        "modules": states,
+    })))
+}
+
+const CRON_FIELDS: &[&str] = &[
+    "cron_name",
+    "active",
+    "nextcall",
+    "lastcall",
+    "interval_number",
+    "interval_type",
+    "priority",
+    "user_id",
+    "model_id",
+];
+
+/// Scheduled actions include archived ones unless `active` says otherwise.
+fn cron_context(args: &Value, op: &OpSpec) -> Value {
+    let mut context = opt_value(args, op, "context")
+        .and_then(|c| c.as_object().cloned())
+        .unwrap_or_default();
+    context.insert("active_test".into(), json!(false));
+    Value::Object(context)
+}
+
+/// Active jobs whose `nextcall` (UTC, `YYYY-MM-DD HH:MM:SS`) has passed are `overdue`: the
+/// cron worker has not picked them up, usually because it is busy, stuck or not running.
+fn mark_overdue(crons: &mut Value) {
+    let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    for cron in crons.as_array_mut().into_iter().flatten() {
+        let overdue = cron.get("active").and_then(Value::as_bool) == Some(true)
+            && cron
+                .get("nextcall")
+                .and_then(Value::as_str)
+                .is_some_and(|next| next < now.as_str());
+        if let Some(cron) = cron.as_object_mut() {
+            cron.insert("overdue".into(), json!(overdue));
+        }
+    }
+}
+
+async fn op_cron_list(pool: &OdooClientPool, op: &OpSpec, args: Value) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let mut domain = Vec::new();
+    if let Some(active) = opt_bool(&args, op, "active")? {
+        domain.push(json!(["active", "=", active]));
+    }
+    if let Some(search) = opt_str(&args, op, "search")? {
+        domain.push(json!(["cron_name", "ilike", search]));
+    }
+    let mut crons = client
+        .search_read(
+            "ir.cron",
+            Some(Value::Array(domain)),
+            Some(CRON_FIELDS.iter().map(|f| f.to_string()).collect()),
+            Some(opt_i64(&args, op, "limit")?.unwrap_or(200)),
+            None,
+            Some("nextcall asc, id asc".to_string()),
+            Some(cron_context(&args, op)),
+        )
+        .await?;
+    mark_overdue(&mut crons);
+    let count = crons.as_array().map(Vec::len).unwrap_or_default();
+    Ok(ok_text(
+        json!({ "count": count, "scheduledActions": crons }),
+    ))
+}
+
+/// Run a scheduled action now (`method_direct_trigger`) or archive / unarchive it. The job is
+/// picked by `id` or by its exact name.
+async fn op_cron_control(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let action = req_str(&args, op, "action")?;
+    if !matches!(action.as_str(), "run" | "activate" | "deactivate") {
+        return Err(OdooError::InvalidResponse(format!(
+            "Unknown action '{action}' (expected run, activate or deactivate)"
+        )));
+    }
+    let context = cron_context(&args, op);
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let id = match (opt_i64(&args, op, "id")?, opt_str(&args, op, "name")?) {
+        (Some(id), _) => id,
+        (None, Some(name)) => {
+            let ids = client
+                .search(
+                    "ir.cron",
+                    Some(json!([["cron_name", "=ilike", name.trim()]])),
+                    Some(2),
+                    None,
+                    None,
+                    Some(context.clone()),
+                )
+                .await?;
+            match ids.as_slice() {
+                [id] => *id,
+                [] => {
+                    return Err(OdooError::InvalidResponse(format!(
+                        "No scheduled action named '{name}'"
+                    )));
+                }
+                _ => {
+                    return Err(OdooError::InvalidResponse(format!(
+                        "Several scheduled actions are named '{name}'; pass 'id' instead"
+                    )));
+                }
+            }
+        }
+        (None, None) => {
+            return Err(OdooError::InvalidResponse(
+                "Pass the scheduled action's 'id' or 'name'".to_string(),
+            ));
+        }
+    };
+    let fields = Some(
+        CRON_FIELDS
+            .iter()
+            .map(|f| f.to_string())
+            .collect::<Vec<_>>(),
+    );
+    let before = client
+        .read("ir.cron", vec![id], fields.clone(), Some(context.clone()))
+        .await?;
+    if before.as_array().is_none_or(|found| found.is_empty()) {
+        return Err(OdooError::InvalidResponse(format!(
+            "Scheduled action {id} not found"
+        )));
+    }
+    match action.as_str() {
+        // Runs synchronously as the job's user; the call returns when the job is done.
+        "run" => {
+            client
+                .call_named(
+                    "ir.cron",
+                    "method_direct_trigger",
+                    Some(vec![id]),
+                    Map::new(),
+                    Some(context.clone()),
+                )
+                .await?;
+        }
+        _ => {
+            client
+                .write(
+                    "ir.cron",
+                    vec![id],
+                    json!({ "active": action == "activate" }),
+                    Some(context.clone()),
+                )
+                .await?;
+        }
+    }
+    let mut after = client
+        .read("ir.cron", vec![id], fields, Some(context))
+        .await?;
+    mark_overdue(&mut after);
+    Ok(ok_text(json!({
+        "action": action,
+        "scheduledAction": after.get(0).cloned().unwrap_or(Value::Null),
+    })))
+}
+
+const SETTINGS_MODEL: &str = "res.config.settings";
+
+/// Settings fields from `fields_get`, without the ORM's bookkeeping fields.
+fn settings_fields(fields: &Value) -> Vec<(&String, &Value)> {
+    const SKIP: &[&str] = &[
+        "id",
+        "display_name",
+        "create_uid",
+        "create_date",
+        "write_uid",
+        "write_date",
+        "__last_update",
+    ];
+    fields
+        .as_object()
+        .map(|fields| {
+            fields
+                .iter()
+                .filter(|(name, _)| !SKIP.contains(&name.as_str()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Effective settings: `default_get` on `res.config.settings`, which reads them back from
+/// config parameters, company fields and group membership.
+async fn op_settings_get(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let wanted = opt_vec_string(&args, op, "fields")?;
+    let search = opt_str(&args, op, "search")?.map(|s| s.to_lowercase());
+    let context = opt_value(&args, op, "context");
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let fields = client.fields_get(SETTINGS_MODEL, context.clone()).await?;
+    let selected: Vec<(&String, &Value)> = settings_fields(&fields)
+        .into_iter()
+        .filter(|(name, _)| wanted.as_ref().is_none_or(|wanted| wanted.contains(name)))
+        .filter(|(name, meta)| {
+            search.as_ref().is_none_or(|search| {
+                let label = meta
+                    .get("string")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default();
+                name.contains(search.as_str()) || label.to_lowercase().contains(search.as_str())
+            })
+        })
+        .collect();
+    if let Some(wanted) = &wanted {
+        let unknown: Vec<&String> = wanted
+            .iter()
+            .filter(|name| !selected.iter().any(|(field, _)| field == name))
+            .collect();
+        if !unknown.is_empty() {
+            return Err(OdooError::InvalidResponse(format!(
+                "Unknown settings fields: {}",
+                unknown
+                    .iter()
+                    .map(|n| n.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )));
+        }
+    }
+    let names: Vec<String> = selected.iter().map(|(name, _)| name.to_string()).collect();
+    let values = if names.is_empty() {
+        json!({})
+    } else {
+        client.default_get(SETTINGS_MODEL, names, context).await?
+    };
+    let settings: Vec<Value> = selected
+        .iter()
+        .map(|(name, meta)| {
+            json!({
+                "field": name,
+                "label": meta.get("string"),
+                "type": meta.get("type"),
+                "value": values.get(name.as_str()).cloned().unwrap_or(Value::Bool(false)),
+            })
+        })
+        .collect();
+    Ok(ok_text(
+        json!({ "count": settings.len(), "settings": settings }),
+    ))
+}
+
+/// Apply settings the way the Settings screen does: create a `res.config.settings` record from
+/// the current values plus the changes, then `execute` it so every field's setter runs
+/// (config parameters, company fields, group toggles, module installs).
+async fn op_settings_apply(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let changes = opt_value(&args, op, "values")
+        .and_then(|v| v.as_object().cloned())
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| {
+            OdooError::InvalidResponse("'values' must set at least one setting".to_string())
+        })?;
+    let context = opt_value(&args, op, "context");
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let fields = client.fields_get(SETTINGS_MODEL, context.clone()).await?;
+    let known = settings_fields(&fields);
+    let mut problems = Vec::new();
+    for name in changes.keys() {
+        match known.iter().find(|(field, _)| *field == name) {
+            None => problems.push(format!("{name} is not a settings field")),
+            Some((_, meta)) if meta.get("readonly").and_then(Value::as_bool) == Some(true) => {
+                problems.push(format!("{name} is read-only"))
+            }
+            Some(_) => {}
+        }
+    }
+    if !problems.is_empty() {
+        return Err(OdooError::InvalidResponse(format!(
+            "Cannot apply settings: {}",
+            problems.join("; ")
+        )));
+    }
+    let id = client
+        .create(
+            SETTINGS_MODEL,
+            Value::Object(changes.clone()),
+            context.clone(),
+        )
+        .await?;
+    let action = client
+        .call_named(
+            SETTINGS_MODEL,
+            "execute",
+            Some(vec![id]),
+            Map::new(),
+            context.clone(),
+        )
+        .await?;
+    let names: Vec<String> = changes.keys().cloned().collect();
+    let effective = client.default_get(SETTINGS_MODEL, names, context).await?;
+    // Setters may normalize or refuse a value without raising; report those.
+    let differs: Vec<&String> = changes
+        .iter()
+        .filter(|(name, value)| effective.get(name.as_str()) != Some(*value))
+        .map(|(name, _)| name)
+        .collect();
+    let mut payload = json!({
+        "applied": effective,
+        "differs": differs,
+    });
+    if action.is_object() {
+        payload["action"] = action;
+    }
+    Ok(ok_text(payload))
+}
+
+fn controlled_mode() -> bool {
+    std::env::var("ODOO_CAPABILITY_CONTROLLED_MODE").is_ok_and(|value| {
+        matches!(
             value.trim().to_ascii_lowercase().as_str(),
             "1" | "true" | "yes" | "y" | "on"
         )
@@ -667,6 +1983,22 @@ fn req_vec_i64(args: &Value, op: &OpSpec, key: &str) -> Result<Vec<i64>, OdooErr
     Ok(out)
 }
 
+/// Collect every mapped argument except `instance` into one object and deserialize it
+/// into a pack options struct (camelCase keys, as in the tool schema).
+fn mapped_options<T: serde::de::DeserializeOwned>(
+    args: &Value,
+    op: &OpSpec,
+) -> Result<T, OdooError> {
+    let mut obj = Map::new();
+    for key in op.map.keys().filter(|key| key.as_str() != "instance") {
+        if let Some(v) = ptr(args, op, key).filter(|v| !v.is_null()) {
+            obj.insert(key.clone(), v.clone());
+        }
+    }
+    serde_json::from_value(Value::Object(obj))
+        .map_err(|e| OdooError::InvalidResponse(format!("Invalid arguments: {e}")))
+}
+
 fn ok_text(payload: Value) -> Value {
     json!({
         "content": [{
@@ -676,6 +2008,30 @@ fn ok_text(payload: Value) -> Value {
     })
 }
 
+/// Run a pack function (see [`packs`]): typed options from the op's mapped arguments, the
+/// instance's client, and the report serialized as the tool result.
+async fn run_pack<O, R>(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+    pack_fn: impl AsyncFnOnce(&OdooClient, O) -> OdooResult<R>,
+) -> Result<Value, OdooError>
+where
+    O: serde::de::DeserializeOwned,
+    R: serde::Serialize,
+{
+    let instance = req_str(&args, op, "instance")?;
+    let options = mapped_options(&args, op)?;
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let report = pack_fn(&client, options).await?;
+    Ok(ok_text(
+        serde_json::to_value(report).unwrap_or_else(|_| json!({})),
+    ))
+}
+
 async fn op_search(pool: &OdooClientPool, op: &OpSpec, args: Value) -> Result<Value, OdooError> {
     let instance = req_str(&args, op, "instance")?;
     let model = req_str(&args, op, "model")?;
@@ -693,7 +2049,11 @@ async fn op_search(pool: &OdooClientPool, op: &OpSpec, args: Value) -> Result<Va
     let ids = client
         .search(&model, domain, limit, offset, order, context)
         .await?;
-    Ok(ok_text(json!({ "ids": ids, "count": ids.len() })))
+    let mut payload = json!({ "ids": ids, "count": ids.len() });
+    if let Some(cursor) = cursors::next_cursor(op, &args, ids.len()) {
+        payload["nextCursor"] = json!(cursor);
+    }
+    Ok(ok_text(payload))
 }
 
 async fn op_search_read(
@@ -719,52 +2079,343 @@ async fn op_search_read(
         .search_read(&model, domain, fields, limit, offset, order, context)
         .await?;
     let count = records.as_array().map(|a| a.len()).unwrap_or(0);
-    Ok(ok_text(json!({ "records": records, "count": count })))
+    let mut payload = json!({ "records": records, "count": count });
+    if let Some(cursor) = cursors::next_cursor(op, &args, count) {
+        payload["nextCursor"] = json!(cursor);
+    }
+    Ok(ok_text(payload))
 }
 
-async fn op_read(pool: &OdooClientPool, op: &OpSpec, args: Value) -> Result<Value, OdooError> {
-    let instance = req_str(&args, op, "instance")?;
-    let model = req_str(&args, op, "model")?;
-    let ids = req_vec_i64(&args, op, "ids")?;
-    let fields = opt_vec_string(&args, op, "fields")?;
-    let context = opt_value(&args, op, "context");
+/// Records per batch of a streamed search_read unless `batchSize` says otherwise.
+const STREAM_BATCH_SIZE: i64 = 200;
+const MAX_STREAM_BATCH_SIZE: i64 = 1000;
 
-    let client = pool
-        .get(&instance)
-        .await
-        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
-    let records = client.read(&model, ids, fields, context).await?;
-    Ok(ok_text(json!({ "records": records })))
+fn streams_records(args: &Value, op: &OpSpec) -> bool {
+    ptr(args, op, "stream").and_then(Value::as_bool) == Some(true)
 }
 
-async fn op_create(pool: &OdooClientPool, op: &OpSpec, args: Value) -> Result<Value, OdooError> {
+/// `search_read` with `stream: true`: records are sent in batches as `notifications/progress`
+/// (each carrying a `records` array) and the result only summarizes them.
+async fn op_search_read_streamed(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+    progress: Option<&Progress>,
+) -> Result<Value, OdooError> {
+    let progress = progress.ok_or_else(|| {
+        OdooError::InvalidResponse(
+            "stream: true needs a progressToken in the request _meta to deliver batches; omit stream to get the records in the result".into(),
+        )
+    })?;
     let instance = req_str(&args, op, "instance")?;
     let model = req_str(&args, op, "model")?;
-    let values = req_value(&args, op, "values")?;
-    let context = opt_value(&args, op, "context");
-
     let client = pool
         .get(&instance)
         .await
         .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
-    let id = client.create(&model, values, context).await?;
-    Ok(ok_text(json!({ "id": id, "success": true })))
-}
 
-async fn op_write(pool: &OdooClientPool, op: &OpSpec, args: Value) -> Result<Value, OdooError> {
-    let instance = req_str(&args, op, "instance")?;
-    let model = req_str(&args, op, "model")?;
-    let ids = req_vec_i64(&args, op, "ids")?;
-    let values = req_value(&args, op, "values")?;
+    let domain = opt_value(&args, op, "domain");
+    let fields = opt_vec_string(&args, op, "fields")?;
+    let limit = opt_i64(&args, op, "limit")?;
+    let offset = opt_i64(&args, op, "offset")?.unwrap_or(0).max(0);
+    // Offset paging needs a stable order.
+    let order = opt_str(&args, op, "order")?.unwrap_or_else(|| "id".to_string());
     let context = opt_value(&args, op, "context");
+    let batch_size = opt_i64(&args, op, "batchSize")?
+        .unwrap_or(STREAM_BATCH_SIZE)
+        .clamp(1, MAX_STREAM_BATCH_SIZE);
 
-    let client = pool
-        .get(&instance)
-        .await
-        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
-    let ok = client.write(&model, ids.clone(), values, context).await?;
-    Ok(ok_text(
-        json!({ "success": ok, "updated_count": ids.len() }),
+    let matching = client
+        .search_count(&model, domain.clone(), context.clone())
+        .await?;
+    let available = (matching - offset).max(0);
+    let total = limit.map_or(available, |limit| limit.clamp(0, available));
+
+    let mut sent = 0;
+    let mut batches = 0;
+    while sent < total {
+        let records = client
+            .search_read(
+                &model,
+                domain.clone(),
+                fields.clone(),
+                Some(batch_size.min(total - sent)),
+                Some(offset + sent),
+                Some(order.clone()),
+                context.clone(),
+            )
+            .await?;
+        let count = records.as_array().map_or(0, Vec::len) as i64;
+        if count == 0 {
+            break;
+        }
+        batches += 1;
+        let message = format!("Records {}-{} of {total}", sent + 1, sent + count);
+        sent += count;
+        progress.report_with(
+            sent as u64,
+            Some(total as u64),
+            Some(&message),
+            Map::from_iter([("records".to_string(), records)]),
+        );
+    }
+    Ok(ok_text(json!({
+        "streamed": true,
+        "count": sent,
+        "total": total,
+        "batches": batches,
+        "batchSize": batch_size,
+    })))
+}
+
+async fn op_smart_search(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+    sampler: Option<&Sampler>,
+) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let model = req_str(&args, op, "model")?;
+    let query = req_str(&args, op, "query")?;
+    let sampler = sampler.ok_or_else(|| {
+        OdooError::InvalidResponse(
+            "odoo_smart_search requires a client that supports MCP sampling; use odoo_search_read with an explicit domain".into(),
+        )
+    })?;
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let context = opt_value(&args, op, "context");
+
+    let fields = client.fields_get(&model, context.clone()).await?;
+    let prompt = smart_search::domain_prompt(&model, &smart_search::field_catalog(&fields), &query);
+    let reply = sampler
+        .create_message(smart_search::SYSTEM_PROMPT, &prompt, 1024)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(format!("Sampling failed: {e}")))?;
+    let domain = smart_search::extract_domain(&reply)
+        .and_then(|domain| smart_search::validate_domain(&domain, &fields).map(|_| domain))
+        .map_err(|e| OdooError::InvalidResponse(format!("Generated domain rejected: {e}")))?;
+
+    let records = client
+        .search_read(
+            &model,
+            Some(domain.clone()),
+            opt_vec_string(&args, op, "fields")?,
+            Some(opt_i64(&args, op, "limit")?.unwrap_or(50)),
+            None,
+            opt_str(&args, op, "order")?,
+            context,
+        )
+        .await?;
+    let count = records.as_array().map(|a| a.len()).unwrap_or(0);
+    Ok(ok_text(json!({
+        "query": query,
+        "domain": domain,
+        "records": records,
+        "count": count,
+    })))
+}
+
+/// Records per call of `odoo_debug_requests` unless `limit` says otherwise.
+const DEBUG_REQUESTS_LIMIT: i64 = 10;
+
+/// The calling session's latest requests and responses from the request inspector, newest
+/// first, so an agent can see exactly what it sent and what came back.
+fn op_debug_requests(
+    op: &OpSpec,
+    args: Value,
+    requests: Option<&SessionRequests>,
+) -> Result<Value, OdooError> {
+    let requests = requests.ok_or_else(|| {
+        OdooError::InvalidResponse(
+            "The request inspector is disabled (MCP_INSPECTOR_REQUESTS=0)".to_string(),
+        )
+    })?;
+    let limit = opt_i64(&args, op, "limit")?
+        .unwrap_or(DEBUG_REQUESTS_LIMIT)
+        .max(0) as usize;
+    let errors_only = opt_bool(&args, op, "errorsOnly")?.unwrap_or(false);
+    let method = opt_str(&args, op, "method")?;
+    let exchanges: Vec<_> = requests
+        .recent(usize::MAX)
+        .into_iter()
+        .filter(|exchange| !errors_only || exchange.is_error())
+        .filter(|exchange| method.as_ref().is_none_or(|m| &exchange.method == m))
+        .take(limit)
+        .collect();
+    Ok(ok_text(json!({
+        "count": exchanges.len(),
+        "requests": exchanges,
+    })))
+}
+
+/// Effective configuration of this server (see [`crate::mcp::server_info`]).
+async fn op_server_info(
+    pool: &OdooClientPool,
+    server: Option<&ServerInfo>,
+) -> Result<Value, OdooError> {
+    let report = match server {
+        Some(server) => server.report(pool).await,
+        None => ServerInfo::default().report(pool).await,
+    };
+    Ok(ok_text(report))
+}
+
+/// Register a client-supplied Odoo connection for the calling session only.
+async fn op_connect(pool: &OdooClientPool, op: &OpSpec, args: Value) -> Result<Value, OdooError> {
+    let name = req_str(&args, op, "name")?;
+    let mut config = json!({ "url": req_str(&args, op, "url")? });
+    for key in ["db", "apiKey", "username", "password", "version"] {
+        if let Some(value) = opt_str(&args, op, key)? {
+            config[key] = json!(value);
+        }
+    }
+    let read_only = opt_bool(&args, op, "readOnly")?.unwrap_or(false);
+    config["readOnly"] = json!(read_only);
+    let config: OdooInstanceConfig = serde_json::from_value(config)
+        .map_err(|e| OdooError::InvalidResponse(format!("Invalid connection: {e}")))?;
+    let url = config.url.clone();
+    let legacy = config.auth_mode() == OdooAuthMode::Password;
+
+    pool.connect_session(&name, config).await?;
+    Ok(ok_text(json!({
+        "instance": name.trim(),
+        "url": url,
+        "protocol": if legacy { "jsonrpc" } else { "json2" },
+        "readOnly": read_only,
+    })))
+}
+
+async fn op_read(pool: &OdooClientPool, op: &OpSpec, args: Value) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let model = req_str(&args, op, "model")?;
+    let ids = req_vec_i64(&args, op, "ids")?;
+    let fields = opt_vec_string(&args, op, "fields")?;
+    let context = opt_value(&args, op, "context");
+
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let records = client.read(&model, ids, fields, context).await?;
+    Ok(ok_text(json!({ "records": records })))
+}
+
+/// Most specs one `multi_read` call may carry.
+const MAX_MULTI_READ_SPECS: usize = 10;
+
+/// One lookup of a `multi_read` call: `read` when `ids` is given, `search_read` otherwise.
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct ReadSpec {
+    model: String,
+    ids: Option<Vec<i64>>,
+    domain: Option<Value>,
+    fields: Option<Vec<String>>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+    order: Option<String>,
+    context: Option<Value>,
+}
+
+/// Several reads against one instance, run concurrently. A failing spec reports its error
+/// under its key without failing the others.
+async fn op_multi_read(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let requests = req_value(&args, op, "requests")?;
+    let context = opt_value(&args, op, "context");
+    let specs: BTreeMap<String, ReadSpec> = serde_json::from_value(requests)
+        .map_err(|e| OdooError::InvalidResponse(format!("Invalid 'requests': {e}")))?;
+    if specs.is_empty() || specs.len() > MAX_MULTI_READ_SPECS {
+        return Err(OdooError::InvalidResponse(format!(
+            "'requests' must hold between 1 and {MAX_MULTI_READ_SPECS} lookups, got {}",
+            specs.len()
+        )));
+    }
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+
+    let client = &client;
+    let outcomes = join_all(specs.into_iter().map(|(key, spec)| {
+        let context = spec.context.or_else(|| context.clone());
+        async move {
+            let model = spec.model;
+            let records = match spec.ids {
+                Some(ids) => client.read(&model, ids, spec.fields, context).await,
+                None => {
+                    client
+                        .search_read(
+                            &model,
+                            spec.domain,
+                            spec.fields,
+                            spec.limit,
+                            spec.offset,
+                            spec.order,
+                            context,
+                        )
+                        .await
+                }
+            };
+            let entry = match records {
+                Ok(records) => {
+                    let count = records.as_array().map(Vec::len).unwrap_or(0);
+                    json!({ "model": model, "records": records, "count": count })
+                }
+                Err(e) => json!({ "model": model, "error": e.to_string() }),
+            };
+            (key, entry)
+        }
+    }))
+    .await;
+
+    let failed = outcomes
+        .iter()
+        .filter(|(_, entry)| entry.get("error").is_some())
+        .count();
+    let results: Map<String, Value> = outcomes.into_iter().collect();
+    Ok(ok_text(json!({
+        "results": results,
+        "count": results.len(),
+        "failed": failed,
+    })))
+}
+
+async fn op_create(pool: &OdooClientPool, op: &OpSpec, args: Value) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let model = req_str(&args, op, "model")?;
+    let values = req_value(&args, op, "values")?;
+    let context = opt_value(&args, op, "context");
+
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let id = client.create(&model, values, context).await?;
+    Ok(ok_text(json!({ "id": id, "success": true })))
+}
+
+async fn op_write(pool: &OdooClientPool, op: &OpSpec, args: Value) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let model = req_str(&args, op, "model")?;
+    let ids = req_vec_i64(&args, op, "ids")?;
+    let values = req_value(&args, op, "values")?;
+    let context = opt_value(&args, op, "context");
+
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let ok = client.write(&model, ids.clone(), values, context).await?;
+    Ok(ok_text(
+        json!({ "success": ok, "updated_count": ids.len() }),
     ))
 }
 
@@ -889,23 +2540,6 @@ async fn op_execute(pool: &OdooClientPool, op: &OpSpec, args: Value) -> Result<V
     Ok(ok_text(json!({ "result": result })))
 }
 
-fn is_mutating_op(op_type: &str) -> bool {
-    matches!(
-        op_type,
-        "create"
-            | "write"
-            | "unlink"
-            | "workflow_action"
-            | "execute"
-            | "database_cleanup"
-            | "deep_cleanup"
-            | "stock_inventory_reversal_cleanup"
-            | "copy"
-            | "create_batch"
-            | "execute_capability"
-    )
-}
-
 async fn op_generate_report(
     pool: &OdooClientPool,
     op: &OpSpec,
@@ -914,1188 +2548,5658 @@ async fn op_generate_report(
     let instance = req_str(&args, op, "instance")?;
     let report_name = req_str(&args, op, "reportName")?;
     let ids = req_vec_i64(&args, op, "ids")?;
+    let format = match opt_str(&args, op, "reportType")? {
+        None => ReportFormat::Pdf,
+        Some(value) => ReportFormat::parse(&value).ok_or_else(|| {
+            OdooError::InvalidResponse(format!(
+                "Unsupported reportType '{value}' (expected qweb-pdf, qweb-html, qweb-text or xlsx)"
+            ))
+        })?,
+    };
     let client = pool
         .get(&instance)
         .await
         .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
 
-    let pdf_bytes = client.download_report_pdf(&report_name, &ids).await?;
-    enforce_report_size(pdf_bytes.len(), max_report_bytes()?)?;
-    let pdf_base64 = base64::engine::general_purpose::STANDARD.encode(pdf_bytes);
-    Ok(ok_text(json!({
-        "pdf_base64": pdf_base64,
+    let content = client.download_report(&report_name, &ids, format).await?;
+    enforce_report_size(content.bytes.len(), max_report_bytes()?)?;
+    let mut result = json!({
         "report_name": report_name,
+        "report_type": format.report_type(),
         "record_ids": ids
-    })))
+    });
+    let encoded = || base64::engine::general_purpose::STANDARD.encode(&content.bytes);
+    match format {
+        ReportFormat::Pdf => result["pdf_base64"] = json!(encoded()),
+        ReportFormat::Xlsx => result["xlsx_base64"] = json!(encoded()),
+        ReportFormat::Html | ReportFormat::Text => {
+            result["content"] = json!(String::from_utf8_lossy(&content.bytes))
+        }
+    }
+    if let Some(content_type) = &content.content_type {
+        result["content_type"] = json!(content_type);
+    }
+    Ok(ok_text(result))
 }
 
-async fn op_get_model_metadata(
+async fn op_attachment_upload(
     pool: &OdooClientPool,
     op: &OpSpec,
     args: Value,
 ) -> Result<Value, OdooError> {
     let instance = req_str(&args, op, "instance")?;
-    let model = req_str(&args, op, "model")?;
+    let name = req_str(&args, op, "name")?;
+    let content = req_str(&args, op, "contentBase64")?;
+    let res_model = req_str(&args, op, "resModel")?;
+    let res_id = opt_i64(&args, op, "resId")?.ok_or_else(|| {
+        OdooError::InvalidResponse("Missing required argument 'resId'".to_string())
+    })?;
+    let mimetype = opt_str(&args, op, "mimetype")?;
     let context = opt_value(&args, op, "context");
 
-    // Get cache TTL from environment (default: 300 seconds, 0 disables cache)
-    let cache_ttl_secs: u64 = std::env::var("ODOO_METADATA_CACHE_TTL_SECS")
-        .unwrap_or_else(|_| "300".to_string())
-        .parse()
-        .unwrap_or(300);
+    let content = content.trim();
+    let size_bytes = base64::engine::general_purpose::STANDARD
+        .decode(content)
+        .map_err(|e| {
+            OdooError::InvalidResponse(format!("'contentBase64' is not valid base64: {e}"))
+        })?
+        .len();
+    let limit = max_attachment_bytes()?;
+    if size_bytes > limit {
+        return Err(OdooError::InvalidResponse(format!(
+            "Attachment exceeds the configured {limit}-byte limit"
+        )));
+    }
 
-    // Check cache if TTL > 0
-    if cache_ttl_secs > 0
-        && let Some(cached) = pool.metadata_cache.get(&instance, &model).await
-    {
-        return Ok(ok_text(cached));
+    let mut values = json!({
+        "name": name,
+        "datas": content,
+        "res_model": res_model,
+        "res_id": res_id,
+    });
+    if let Some(mimetype) = mimetype {
+        values["mimetype"] = json!(mimetype);
     }
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let id = client.create("ir.attachment", values, context).await?;
+    Ok(ok_text(json!({
+        "id": id,
+        "name": name,
+        "resModel": res_model,
+        "resId": res_id,
+        "sizeBytes": size_bytes,
+    })))
+}
 
+/// Fetch an attachment's content as base64 text or, with `format: "blob"`, as an embedded MCP
+/// resource. The size recorded on the attachment is checked before downloading, and the
+/// download itself stops at `ODOO_MAX_ATTACHMENT_BYTES`.
+async fn op_attachment_download(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let id = opt_i64(&args, op, "id")?
+        .ok_or_else(|| OdooError::InvalidResponse("Missing required argument 'id'".to_string()))?;
+    let format = opt_str(&args, op, "format")?.unwrap_or_else(|| "base64".to_string());
+    if !matches!(format.as_str(), "base64" | "blob") {
+        return Err(OdooError::InvalidResponse(format!(
+            "Unknown format '{format}'; expected 'base64' or 'blob'"
+        )));
+    }
+    let limit = max_attachment_bytes()?;
     let client = pool
         .get(&instance)
         .await
         .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
-    let fields = client.fields_get(&model, context.clone()).await?;
 
-    let domain = json!([["model", "=", model]]);
-    let info = client
-        .search_read(
-            "ir.model",
-            Some(domain),
-            Some(vec!["name".to_string(), "model".to_string()]),
-            Some(1),
-            None,
-            None,
-            context,
-        )
+    let fields = ["name", "mimetype", "file_size", "res_model", "res_id"]
+        .map(String::from)
+        .to_vec();
+    let records = client
+        .read("ir.attachment", vec![id], Some(fields), None)
         .await?;
-
-    let description = info
+    let record = records
         .as_array()
-        .and_then(|arr| arr.first())
-        .and_then(|o| o.get("name"))
-        .and_then(|v| v.as_str())
-        .unwrap_or(&model)
-        .to_string();
+        .and_then(|records| records.first())
+        .cloned()
+        .ok_or_else(|| OdooError::InvalidResponse(format!("Attachment {id} not found")))?;
+    if record["file_size"]
+        .as_u64()
+        .is_some_and(|size| size > limit as u64)
+    {
+        return Err(OdooError::InvalidResponse(format!(
+            "Attachment exceeds the configured {limit}-byte limit"
+        )));
+    }
 
-    let metadata = json!({
-        "model": {
-            "name": model,
-            "description": description,
-            "fields": fields
-        }
+    let content = client.download_attachment(id, limit).await?;
+    let mimetype = record["mimetype"]
+        .as_str()
+        .map(str::to_owned)
+        .or(content.content_type)
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+    let encoded = base64::engine::general_purpose::STANDARD.encode(&content.bytes);
+    let mut summary = json!({
+        "id": id,
+        "name": record["name"],
+        "mimetype": mimetype,
+        "sizeBytes": content.bytes.len(),
+        "resModel": record["res_model"],
+        "resId": record["res_id"],
     });
-
-    // Insert into cache if TTL > 0
-    if cache_ttl_secs > 0 {
-        pool.metadata_cache
-            .insert(&instance, &model, metadata.clone(), cache_ttl_secs)
-            .await;
+    if format == "base64" {
+        summary["contentBase64"] = json!(encoded);
+        return Ok(ok_text(summary));
     }
-
-    Ok(ok_text(metadata))
+    let mut result = ok_text(summary);
+    result["content"]
+        .as_array_mut()
+        .expect("ok_text builds a content array")
+        .push(json!({
+            "type": "resource",
+            "resource": {
+                "uri": format!("odoo://{instance}/record/ir.attachment/{id}"),
+                "mimeType": mimetype,
+                "blob": encoded,
+            }
+        }));
+    Ok(result)
 }
 
-async fn op_database_cleanup(
+async fn op_list_attachments(
     pool: &OdooClientPool,
     op: &OpSpec,
     args: Value,
 ) -> Result<Value, OdooError> {
     let instance = req_str(&args, op, "instance")?;
+    let model = req_str(&args, op, "model")?;
+    let id = opt_i64(&args, op, "id")?
+        .ok_or_else(|| OdooError::InvalidResponse("Missing required argument 'id'".to_string()))?;
+    let limit = opt_i64(&args, op, "limit")?.unwrap_or(100);
     let client = pool
         .get(&instance)
         .await
         .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
-    let report = cleanup::database::execute_full_cleanup(
-        &client,
-        cleanup::database::CleanupOptions {
-            remove_test_data: opt_bool(&args, op, "removeTestData")?,
-            remove_inactive_records: opt_bool(&args, op, "removeInactivRecords")?,
-            cleanup_drafts: opt_bool(&args, op, "cleanupDrafts")?,
-            archive_old_records: opt_bool(&args, op, "archiveOldRecords")?,
-            optimize_database: opt_bool(&args, op, "optimizeDatabase")?,
-            days_threshold: opt_i64(&args, op, "daysThreshold")?,
-            dry_run: Some(opt_bool(&args, op, "dryRun")?.unwrap_or(true)),
-        },
-    )
-    .await?;
-    let v = serde_json::to_value(&report).unwrap_or_else(|_| json!({}));
-    Ok(ok_text(v))
+
+    let fields = [
+        "name",
+        "mimetype",
+        "file_size",
+        "checksum",
+        "type",
+        "url",
+        "create_date",
+    ]
+    .map(String::from)
+    .to_vec();
+    let records = client
+        .search_read(
+            "ir.attachment",
+            Some(json!([["res_model", "=", model], ["res_id", "=", id]])),
+            Some(fields),
+            Some(limit),
+            None,
+            Some("id".to_string()),
+            None,
+        )
+        .await?;
+    let attachments: Vec<Value> = records
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|record| {
+            let attachment_id = record["id"].as_i64().unwrap_or_default();
+            // Binary attachments have no url; point at the controller odoo_attachment_download uses.
+            let url = match record["url"].as_str() {
+                Some(url) if record["type"] == "url" => url.to_string(),
+                _ => format!("/web/content/{attachment_id}?download=true"),
+            };
+            json!({
+                "id": attachment_id,
+                "name": record["name"],
+                "mimetype": record["mimetype"],
+                "sizeBytes": record["file_size"],
+                "checksum": record["checksum"],
+                "type": record["type"],
+                "url": url,
+                "createDate": record["create_date"],
+            })
+        })
+        .collect();
+    Ok(ok_text(json!({
+        "model": model,
+        "id": id,
+        "count": attachments.len(),
+        "attachments": attachments,
+    })))
 }
 
-async fn op_deep_cleanup(
-    pool: &OdooClientPool,
-    op: &OpSpec,
-    args: Value,
-) -> Result<Value, OdooError> {
-    let instance = req_str(&args, op, "instance")?;
-    let client = pool
-        .get(&instance)
-        .await
-        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
-    let report = cleanup::deep::execute_deep_cleanup(
-        &client,
-        cleanup::deep::DeepCleanupOptions {
-            dry_run: Some(opt_bool(&args, op, "dryRun")?.unwrap_or(true)),
-            keep_company_defaults: opt_bool(&args, op, "keepCompanyDefaults")?,
-            keep_user_accounts: opt_bool(&args, op, "keepUserAccounts")?,
-            keep_menus: opt_bool(&args, op, "keepMenus")?,
-            keep_groups: opt_bool(&args, op, "keepGroups")?,
-        },
-    )
-    .await?;
-    let v = serde_json::to_value(&report).unwrap_or_else(|_| json!({}));
-    Ok(ok_text(v))
-}
-
-async fn op_stock_inventory_reversal_cleanup(
-    pool: &OdooClientPool,
-    op: &OpSpec,
-    args: Value,
-) -> Result<Value, OdooError> {
-    let instance = req_str(&args, op, "instance")?;
-    let client = pool
-        .get(&instance)
-        .await
-        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
-    let lines = req_value(&args, op, "lines")?;
-    let lines = serde_json::from_value(lines).map_err(|e| {
-        OdooError::InvalidResponse(format!(
-            "Argument 'lines' must be an array of stock inventory reversal lines: {e}"
-        ))
-    })?;
-    let report = cleanup::stock_inventory::execute_stock_inventory_reversal(
-        &client,
-        cleanup::stock_inventory::InventoryReversalOptions {
-            lines,
-            dry_run: Some(opt_bool(&args, op, "dryRun")?.unwrap_or(true)),
-            confirm: Some(opt_bool(&args, op, "confirm")?.unwrap_or(false)),
-            allow_negative: Some(opt_bool(&args, op, "allowNegative")?.unwrap_or(false)),
-            reference: opt_str(&args, op, "reference")?,
-        },
-    )
-    .await?;
-    let v = serde_json::to_value(&report).unwrap_or_else(|_| json!({}));
-    Ok(ok_text(v))
-}
-
-async fn op_read_group(
+/// Post on a record's chatter. Defaults to an internal note (`mail.mt_note`); pass
+/// `subtypeXmlid: "mail.mt_comment"` to send a message to the record's followers.
+async fn op_message_post(
     pool: &OdooClientPool,
     op: &OpSpec,
     args: Value,
 ) -> Result<Value, OdooError> {
     let instance = req_str(&args, op, "instance")?;
     let model = req_str(&args, op, "model")?;
-    let fields = opt_vec_string(&args, op, "fields")?.unwrap_or_default();
-    let groupby = opt_vec_string(&args, op, "groupby")?.unwrap_or_default();
-    let domain = opt_value(&args, op, "domain");
-    let offset = opt_i64(&args, op, "offset")?;
-    let limit = opt_i64(&args, op, "limit")?;
-    let orderby = opt_str(&args, op, "orderby")?;
-    let lazy = opt_bool(&args, op, "lazy")?;
+    let id = opt_i64(&args, op, "id")?
+        .ok_or_else(|| OdooError::InvalidResponse("Missing required argument 'id'".to_string()))?;
+    let body = req_str(&args, op, "body")?;
+    if body.trim().is_empty() {
+        return Err(OdooError::InvalidResponse(
+            "Message 'body' must not be empty".to_string(),
+        ));
+    }
+    let message_type = opt_str(&args, op, "messageType")?.unwrap_or_else(|| "comment".into());
+    if !matches!(message_type.as_str(), "comment" | "notification") {
+        return Err(OdooError::InvalidResponse(format!(
+            "Unknown messageType '{message_type}' (expected comment or notification)"
+        )));
+    }
+    let subtype = opt_str(&args, op, "subtypeXmlid")?.unwrap_or_else(|| "mail.mt_note".into());
     let context = opt_value(&args, op, "context");
 
+    let mut params = serde_json::Map::new();
+    params.insert("body".into(), json!(body));
+    params.insert("message_type".into(), json!(message_type));
+    params.insert("subtype_xmlid".into(), json!(subtype));
+    if let Some(subject) = opt_str(&args, op, "subject")? {
+        params.insert("subject".into(), json!(subject));
+    }
+    if let Some(partner_ids) = opt_vec_i64(&args, op, "partnerIds")? {
+        params.insert("partner_ids".into(), json!(partner_ids));
+    }
     let client = pool
         .get(&instance)
         .await
         .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
     let result = client
-        .read_group(
-            &model, domain, fields, groupby, offset, limit, orderby, lazy, context,
-        )
+        .call_named(&model, "message_post", Some(vec![id]), params, context)
         .await?;
-    Ok(ok_text(json!({ "groups": result })))
+    // message_post returns the new mail.message, as an id or a one-element id list.
+    let message_id = result
+        .as_i64()
+        .or_else(|| result.as_array()?.first()?.as_i64());
+    Ok(ok_text(json!({
+        "messageId": message_id,
+        "model": model,
+        "id": id,
+        "subtypeXmlid": subtype,
+    })))
 }
 
-async fn op_name_search(
+/// Schedule a `mail.activity` on a record (`action: "schedule"`, the default) through the
+/// record's `activity_schedule`, or mark one done with `action_feedback` (`action: "done"`).
+async fn op_activity_schedule(
     pool: &OdooClientPool,
     op: &OpSpec,
     args: Value,
 ) -> Result<Value, OdooError> {
     let instance = req_str(&args, op, "instance")?;
-    let model = req_str(&args, op, "model")?;
-    let name = opt_str(&args, op, "name")?;
-    let domain = opt_value(&args, op, "args");
-    let operator = opt_str(&args, op, "operator")?;
-    let limit = opt_i64(&args, op, "limit")?;
+    let action = opt_str(&args, op, "action")?.unwrap_or_else(|| "schedule".into());
     let context = opt_value(&args, op, "context");
-
     let client = pool
         .get(&instance)
         .await
         .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
-    let result = client
-        .name_search(&model, name, domain, operator, limit, context)
-        .await?;
-    Ok(ok_text(json!({ "results": result })))
-}
-
-async fn op_name_get(pool: &OdooClientPool, op: &OpSpec, args: Value) -> Result<Value, OdooError> {
-    let instance = req_str(&args, op, "instance")?;
-    let model = req_str(&args, op, "model")?;
-    let ids = req_vec_i64(&args, op, "ids")?;
-    let context = opt_value(&args, op, "context");
 
-    let client = pool
-        .get(&instance)
-        .await
-        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
-    let result = client.name_get(&model, ids, context).await?;
-    Ok(ok_text(json!({ "names": result })))
+    match action.as_str() {
+        "schedule" => {
+            let model = req_str(&args, op, "model")?;
+            let id = opt_i64(&args, op, "id")?.ok_or_else(|| {
+                OdooError::InvalidResponse("Missing required argument 'id'".to_string())
+            })?;
+            let mut params = serde_json::Map::new();
+            match opt_i64(&args, op, "activityTypeId")? {
+                Some(type_id) => {
+                    params.insert("activity_type_id".into(), json!(type_id));
+                }
+                None => {
+                    let xmlid = opt_str(&args, op, "activityType")?
+                        .unwrap_or_else(|| "mail.mail_activity_data_todo".into());
+                    params.insert("act_type_xmlid".into(), json!(xmlid));
+                }
+            }
+            for (arg, field) in [
+                ("summary", "summary"),
+                ("note", "note"),
+                ("dateDeadline", "date_deadline"),
+            ] {
+                if let Some(value) = opt_str(&args, op, arg)? {
+                    params.insert(field.into(), json!(value));
+                }
+            }
+            if let Some(user_id) = opt_i64(&args, op, "userId")? {
+                params.insert("user_id".into(), json!(user_id));
+            }
+            let result = client
+                .call_named(&model, "activity_schedule", Some(vec![id]), params, context)
+                .await?;
+            let activity_id = result
+                .as_i64()
+                .or_else(|| result.as_array()?.first()?.as_i64());
+            Ok(ok_text(json!({
+                "action": "schedule",
+                "activityId": activity_id,
+                "model": model,
+                "id": id,
+            })))
+        }
+        "done" => {
+            let activity_id = opt_i64(&args, op, "activityId")?.ok_or_else(|| {
+                OdooError::InvalidResponse("Missing required argument 'activityId'".to_string())
+            })?;
+            let mut params = serde_json::Map::new();
+            if let Some(feedback) = opt_str(&args, op, "feedback")? {
+                params.insert("feedback".into(), json!(feedback));
+            }
+            client
+                .call_named(
+                    "mail.activity",
+                    "action_feedback",
+                    Some(vec![activity_id]),
+                    params,
+                    context,
+                )
+                .await?;
+            Ok(ok_text(json!({
+                "action": "done",
+                "activityId": activity_id,
+            })))
+        }
+        other => Err(OdooError::InvalidResponse(format!(
+            "Unknown action '{other}' (expected schedule or done)"
+        ))),
+    }
 }
 
-async fn op_default_get(
+/// Queue an email about a record: rendered from a `mail.template` (`templateId`) through
+/// `send_mail`, or a raw `subject`/`body` as a `mail.mail` linked to the record so it shows in
+/// the chatter. `sendNow` pushes it out immediately instead of waiting for the mail queue cron.
+async fn op_send_email(
     pool: &OdooClientPool,
     op: &OpSpec,
     args: Value,
 ) -> Result<Value, OdooError> {
-    let instance = req_str(&args, op, "instance")?;
-    let model = req_str(&args, op, "model")?;
-    let fields_list = opt_vec_string(&args, op, "fields")?.unwrap_or_default();
-    let context = opt_value(&args, op, "context");
-
-    let client = pool
-        .get(&instance)
-        .await
-        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
-    let result = client.default_get(&model, fields_list, context).await?;
-    Ok(ok_text(json!({ "defaults": result })))
-}
-
-async fn op_copy(pool: &OdooClientPool, op: &OpSpec, args: Value) -> Result<Value, OdooError> {
     let instance = req_str(&args, op, "instance")?;
     let model = req_str(&args, op, "model")?;
     let id = opt_i64(&args, op, "id")?
         .ok_or_else(|| OdooError::InvalidResponse("Missing required argument 'id'".to_string()))?;
-    let default = opt_value(&args, op, "default");
+    let template_id = opt_i64(&args, op, "templateId")?;
+    let send_now = opt_bool(&args, op, "sendNow")?.unwrap_or(false);
     let context = opt_value(&args, op, "context");
-
     let client = pool
         .get(&instance)
         .await
         .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
-    let new_id = client.copy(&model, id, default, context).await?;
-    Ok(ok_text(json!({ "id": new_id, "success": true })))
-}
 
-async fn op_onchange(pool: &OdooClientPool, op: &OpSpec, args: Value) -> Result<Value, OdooError> {
-    let instance = req_str(&args, op, "instance")?;
-    let model = req_str(&args, op, "model")?;
-    let ids = req_vec_i64(&args, op, "ids")?;
-    let values = req_value(&args, op, "values")?;
-    let field_name = opt_vec_string(&args, op, "fieldName")?.unwrap_or_default();
-    let field_onchange = opt_value(&args, op, "fieldOnchange").unwrap_or(json!({}));
-    let context = opt_value(&args, op, "context");
+    let mail_id = match template_id {
+        Some(template_id) => {
+            let mut params = serde_json::Map::new();
+            params.insert("res_id".into(), json!(id));
+            params.insert("force_send".into(), json!(false));
+            let result = client
+                .call_named(
+                    "mail.template",
+                    "send_mail",
+                    Some(vec![template_id]),
+                    params,
+                    context.clone(),
+                )
+                .await?;
+            result
+                .as_i64()
+                .or_else(|| result.as_array()?.first()?.as_i64())
+                .ok_or_else(|| {
+                    OdooError::InvalidResponse(format!(
+                        "mail.template.send_mail returned no mail id: {result}"
+                    ))
+                })?
+        }
+        None => {
+            let subject = opt_str(&args, op, "subject")?;
+            let body = opt_str(&args, op, "body")?;
+            let (Some(subject), Some(body)) = (subject, body) else {
+                return Err(OdooError::InvalidResponse(
+                    "Provide 'templateId', or both 'subject' and 'body'".to_string(),
+                ));
+            };
+            let partner_ids = opt_vec_i64(&args, op, "partnerIds")?.unwrap_or_default();
+            let email_to = opt_str(&args, op, "emailTo")?;
+            if partner_ids.is_empty() && email_to.is_none() {
+                return Err(OdooError::InvalidResponse(
+                    "Provide 'partnerIds' or 'emailTo' for a raw email".to_string(),
+                ));
+            }
+            let mut values = json!({
+                "subject": subject,
+                "body_html": body,
+                "model": model,
+                "res_id": id,
+                "auto_delete": false,
+            });
+            if !partner_ids.is_empty() {
+                values["recipient_ids"] = json!([[6, 0, partner_ids]]);
+            }
+            if let Some(email_to) = email_to {
+                values["email_to"] = json!(email_to);
+            }
+            client.create("mail.mail", values, context.clone()).await?
+        }
+    };
 
-    let client = pool
-        .get(&instance)
-        .await
-        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
-    let result = client
-        .onchange(&model, ids, values, field_name, field_onchange, context)
+    if send_now {
+        client
+            .call_named(
+                "mail.mail",
+                "send",
+                Some(vec![mail_id]),
+                serde_json::Map::new(),
+                context.clone(),
+            )
+            .await?;
+    }
+    let read = client
+        .read(
+            "mail.mail",
+            vec![mail_id],
+            Some(vec!["state".to_string()]),
+            context,
+        )
         .await?;
-    Ok(ok_text(json!({ "result": result })))
+    let state = read
+        .as_array()
+        .and_then(|rows| rows.first())
+        .and_then(|row| row.get("state"))
+        .cloned()
+        .unwrap_or(Value::Null);
+    Ok(ok_text(json!({
+        "mailId": mail_id,
+        "state": state,
+        "model": model,
+        "id": id,
+    })))
 }
 
-async fn op_list_models(
+/// `search_read` rendered as CSV for spreadsheets. Cells are flattened with the field types
+/// from `fields_get` (see [`csv_export`]); with `format: "resource"` the CSV comes back as an
+/// embedded `text/csv` resource instead of inside the JSON summary.
+async fn op_export_records(
     pool: &OdooClientPool,
     op: &OpSpec,
     args: Value,
 ) -> Result<Value, OdooError> {
     let instance = req_str(&args, op, "instance")?;
-    let domain =
-        opt_value(&args, op, "domain").unwrap_or_else(|| json!([["transient", "=", false]]));
+    let model = req_str(&args, op, "model")?;
+    let columns = opt_vec_string(&args, op, "fields")?
+        .filter(|fields| !fields.is_empty())
+        .ok_or_else(|| {
+            OdooError::InvalidResponse("Missing required argument 'fields'".to_string())
+        })?;
+    let format = opt_str(&args, op, "format")?.unwrap_or_else(|| "text".to_string());
+    if !matches!(format.as_str(), "text" | "resource") {
+        return Err(OdooError::InvalidResponse(format!(
+            "Unknown format '{format}'; expected 'text' or 'resource'"
+        )));
+    }
+    let labels = opt_bool(&args, op, "labels")?.unwrap_or(false);
+    let domain = opt_value(&args, op, "domain");
     let limit = opt_i64(&args, op, "limit")?;
     let offset = opt_i64(&args, op, "offset")?;
+    let order = opt_str(&args, op, "order")?;
     let context = opt_value(&args, op, "context");
-
     let client = pool
         .get(&instance)
         .await
         .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
 
-    let models = client
+    let fields = client.fields_get(&model, context.clone()).await?;
+    if let Some(unknown) = columns
+        .iter()
+        .find(|column| column.as_str() != "id" && fields.get(column.as_str()).is_none())
+    {
+        return Err(OdooError::InvalidResponse(format!(
+            "Unknown field '{unknown}' on {model}"
+        )));
+    }
+    let records = client
         .search_read(
-            "ir.model",
-            Some(domain),
-            Some(vec!["model".to_string(), "name".to_string()]),
+            &model,
+            domain,
+            Some(columns.clone()),
             limit,
             offset,
-            None,
+            order,
             context,
         )
         .await?;
+    let records = records.as_array().cloned().unwrap_or_default();
+    let csv = csv_export::to_csv(&records, &columns, &fields, labels);
 
-    Ok(ok_text(json!({ "models": models })))
+    let mut summary = json!({
+        "model": model,
+        "count": records.len(),
+        "columns": columns,
+    });
+    if format == "text" {
+        summary["csv"] = json!(csv);
+        return Ok(ok_text(summary));
+    }
+    let mut result = ok_text(summary);
+    result["content"]
+        .as_array_mut()
+        .expect("ok_text builds a content array")
+        .push(json!({
+            "type": "resource",
+            "resource": {
+                "uri": format!("odoo://{instance}/export/{model}.csv"),
+                "mimeType": "text/csv",
+                "text": csv,
+            }
+        }));
+    Ok(result)
 }
 
-async fn op_check_access(
+/// A cell of an import row as the text Odoo's importer expects.
+fn import_cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Bulk import through the model's `load()`, the method behind Odoo's import wizard: `fields`
+/// are import paths (`id` for external ids, `partner_id/id`, `line_ids/product_id`, ...) and
+/// each row holds one cell per field. Errors come back per row in `messages`, as the wizard
+/// shows them. With `dryRun` the rows go through `base_import.import.execute_import`, which
+/// validates them the same way and rolls back.
+async fn op_import_records(
     pool: &OdooClientPool,
     op: &OpSpec,
     args: Value,
 ) -> Result<Value, OdooError> {
     let instance = req_str(&args, op, "instance")?;
     let model = req_str(&args, op, "model")?;
-    let operation = req_str(&args, op, "operation")?;
-    let ids = opt_vec_i64(&args, op, "ids")?;
+    let fields = opt_vec_string(&args, op, "fields")?
+        .filter(|fields| !fields.is_empty())
+        .ok_or_else(|| {
+            OdooError::InvalidResponse("Missing required argument 'fields'".to_string())
+        })?;
+    let rows = ptr(&args, op, "rows")
+        .and_then(Value::as_array)
+        .ok_or_else(|| {
+            OdooError::InvalidResponse("Missing required argument 'rows'".to_string())
+        })?;
+    let mut data = Vec::with_capacity(rows.len());
+    for (index, row) in rows.iter().enumerate() {
+        let cells = row
+            .as_array()
+            .filter(|cells| cells.len() == fields.len())
+            .ok_or_else(|| {
+                OdooError::InvalidResponse(format!(
+                    "Row {index} must be an array of {} cells, one per field",
+                    fields.len()
+                ))
+            })?;
+        data.push(cells.iter().map(import_cell).collect::<Vec<_>>());
+    }
+    let dry_run = opt_bool(&args, op, "dryRun")?.unwrap_or(false);
     let context = opt_value(&args, op, "context");
-
     let client = pool
         .get(&instance)
         .await
         .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
 
-    // Note: check_access() in Odoo 19+ is a private method and cannot be called remotely.
-    // We must use check_access_rights() and check_access_rule() for all Odoo versions,
-    // even though they are deprecated in Odoo 19+. They are still callable remotely.
-    let mut params = serde_json::Map::new();
-    params.insert("operation".to_string(), json!(operation));
-
-    let access_result = client
-        .call_named(
-            &model,
-            "check_access_rights",
-            None,
-            params.clone(),
-            context.clone(),
-        )
-        .await?;
-
-    // If IDs provided, also check record-level access rules
-    let record_result = if let Some(record_ids) = ids {
-        let ids_array: Vec<i64> = record_ids.clone();
+    let result = if dry_run {
+        let mut file = vec![fields.clone()];
+        file.extend(data);
+        let encoded =
+            base64::engine::general_purpose::STANDARD.encode(csv_export::encode_rows(&file));
+        let wizard_id = client
+            .create(
+                "base_import.import",
+                json!({
+                    "res_model": model,
+                    "file": encoded,
+                    "file_name": format!("{model}.csv"),
+                    "file_type": "text/csv",
+                }),
+                context.clone(),
+            )
+            .await?;
+        let mut params = Map::new();
+        params.insert("fields".into(), json!(fields));
+        params.insert("columns".into(), json!(fields));
+        params.insert(
+            "options".into(),
+            json!({
+                "has_headers": true,
+                "separator": ",",
+                "quoting": "\"",
+                "encoding": "utf-8",
+                "float_thousand_separator": ",",
+                "float_decimal_separator": ".",
+            }),
+        );
+        params.insert("dryrun".into(), json!(true));
         client
             .call_named(
-                &model,
-                "check_access_rule",
-                Some(ids_array),
+                "base_import.import",
+                "execute_import",
+                Some(vec![wizard_id]),
                 params,
                 context,
             )
-            .await
-            .ok()
+            .await?
     } else {
-        None
+        let mut params = Map::new();
+        params.insert("fields".into(), json!(fields));
+        params.insert("data".into(), json!(data));
+        client
+            .call_named(&model, "load", None, params, context)
+            .await?
     };
 
-    let result = json!({
-        "has_access": true,
+    let ids: Vec<Value> = result["ids"].as_array().cloned().unwrap_or_default();
+    let messages: Vec<Value> = result["messages"].as_array().cloned().unwrap_or_default();
+    let errors = messages
+        .iter()
+        .filter(|message| message["type"] == "error")
+        .count();
+    Ok(ok_text(json!({
         "model": model,
-        "operation": operation,
-        "model_level": access_result,
-        "record_level": record_result
-    });
-
-    Ok(ok_text(result))
+        "dryRun": dry_run,
+        "success": errors == 0,
+        "rows": rows.len(),
+        "ids": ids,
+        "errors": errors,
+        "messages": messages,
+    })))
 }
 
-async fn op_create_batch(
+/// Render a `mail.template` for one record without sending it. Odoo 17+ renders through a
+/// transient `mail.compose.message` (its `res_ids` field is text there); older versions through
+/// the public `mail.template.generate_email`.
+async fn op_render_email_template(
     pool: &OdooClientPool,
     op: &OpSpec,
     args: Value,
 ) -> Result<Value, OdooError> {
     let instance = req_str(&args, op, "instance")?;
-    let model = req_str(&args, op, "model")?;
-    let values_array = req_value(&args, op, "values")?;
+    let template_id = opt_i64(&args, op, "templateId")?.ok_or_else(|| {
+        OdooError::InvalidResponse("Missing required argument 'templateId'".to_string())
+    })?;
+    let id = opt_i64(&args, op, "id")?
+        .ok_or_else(|| OdooError::InvalidResponse("Missing required argument 'id'".to_string()))?;
     let context = opt_value(&args, op, "context");
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
 
-    // Validate values is an array
-    let values_list = values_array
+    let template = client
+        .read(
+            "mail.template",
+            vec![template_id],
+            Some(vec!["model".to_string(), "name".to_string()]),
+            context.clone(),
+        )
+        .await?;
+    let template = template
         .as_array()
-        .ok_or_else(|| OdooError::InvalidResponse("'values' must be an array".to_string()))?;
+        .and_then(|rows| rows.first())
+        .cloned()
+        .ok_or_else(|| {
+            OdooError::InvalidResponse(format!("mail.template {template_id} not found"))
+        })?;
+    let model = template
+        .get("model")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
 
-    // Limit batch size to 100 to prevent abuse
-    if values_list.len() > 100 {
-        return Err(OdooError::InvalidResponse(
-            "Batch size limited to 100 records".to_string(),
-        ));
+    let composer_fields = client
+        .fields_get("mail.compose.message", context.clone())
+        .await?;
+    let text_res_ids = composer_fields
+        .pointer("/res_ids/type")
+        .and_then(Value::as_str)
+        .is_some_and(|kind| matches!(kind, "text" | "char"));
+
+    let rendered = if text_res_ids {
+        let composer_id = client
+            .create(
+                "mail.compose.message",
+                json!({
+                    "template_id": template_id,
+                    "model": model,
+                    "res_ids": format!("[{id}]"),
+                    "composition_mode": "comment",
+                }),
+                context.clone(),
+            )
+            .await?;
+        let rows = client
+            .read(
+                "mail.compose.message",
+                vec![composer_id],
+                Some(vec![
+                    "subject".to_string(),
+                    "body".to_string(),
+                    "email_from".to_string(),
+                    "partner_ids".to_string(),
+                ]),
+                context,
+            )
+            .await?;
+        let row = rows
+            .as_array()
+            .and_then(|rows| rows.first())
+            .cloned()
+            .unwrap_or_default();
+        json!({
+            "subject": row.get("subject"),
+            "body": row.get("body"),
+            "emailFrom": row.get("email_from"),
+            "partnerIds": row.get("partner_ids"),
+        })
+    } else {
+        let mut params = serde_json::Map::new();
+        params.insert("res_ids".into(), json!([id]));
+        params.insert(
+            "fields".into(),
+            json!([
+                "subject",
+                "body_html",
+                "email_from",
+                "email_to",
+                "partner_to",
+                "email_cc"
+            ]),
+        );
+        let result = client
+            .call_named(
+                "mail.template",
+                "generate_email",
+                Some(vec![template_id]),
+                params,
+                context,
+            )
+            .await?;
+        // Keyed by record id when called with a list of ids.
+        let values = result.get(id.to_string()).unwrap_or(&result);
+        json!({
+            "subject": values.get("subject"),
+            "body": values.get("body_html"),
+            "emailFrom": values.get("email_from"),
+            "emailTo": values.get("email_to"),
+            "emailCc": values.get("email_cc"),
+            "partnerIds": values.get("partner_ids"),
+        })
+    };
+
+    let mut response = json!({
+        "templateId": template_id,
+        "template": template.get("name"),
+        "model": model,
+        "id": id,
+    });
+    if let (Value::Object(response), Value::Object(rendered)) = (&mut response, rendered) {
+        response.extend(rendered.into_iter().filter(|(_, v)| !v.is_null()));
+    }
+    Ok(ok_text(response))
+}
+
+async fn op_get_model_metadata(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let model = req_str(&args, op, "model")?;
+    let context = opt_value(&args, op, "context");
+
+    // Get cache TTL from environment (default: 300 seconds, 0 disables cache)
+    let cache_ttl_secs: u64 = std::env::var("ODOO_METADATA_CACHE_TTL_SECS")
+        .unwrap_or_else(|_| "300".to_string())
+        .parse()
+        .unwrap_or(300);
+
+    let cache_key = pool.cache_key(&instance);
+
+    // Check cache if TTL > 0
+    if cache_ttl_secs > 0
+        && let Some(cached) = pool.metadata_cache.get(&cache_key, &model).await
+    {
+        return Ok(ok_text(cached));
     }
 
     let client = pool
         .get(&instance)
         .await
         .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let fields = client.fields_get(&model, context.clone()).await?;
 
-    let mut created_ids = Vec::new();
-    for values in values_list {
-        let id = client
-            .create(&model, values.clone(), context.clone())
-            .await?;
-        created_ids.push(id);
+    let domain = json!([["model", "=", model]]);
+    let info = client
+        .search_read(
+            "ir.model",
+            Some(domain),
+            Some(vec!["name".to_string(), "model".to_string()]),
+            Some(1),
+            None,
+            None,
+            context,
+        )
+        .await?;
+
+    let description = info
+        .as_array()
+        .and_then(|arr| arr.first())
+        .and_then(|o| o.get("name"))
+        .and_then(|v| v.as_str())
+        .unwrap_or(&model)
+        .to_string();
+
+    let metadata = json!({
+        "model": {
+            "name": model,
+            "description": description,
+            "fields": fields
+        }
+    });
+
+    // Insert into cache if TTL > 0
+    if cache_ttl_secs > 0 {
+        pool.metadata_cache
+            .insert(&cache_key, &model, metadata.clone(), cache_ttl_secs)
+            .await;
     }
 
-    Ok(ok_text(json!({
-        "ids": created_ids,
-        "count": created_ids.len()
-    })))
+    Ok(ok_text(metadata))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::odoo::config::{InstanceToolConfig, OdooEnvConfig, OdooInstanceConfig};
-    use std::collections::HashMap;
-    use std::sync::{Arc, RwLock};
-    use tokio::sync::Mutex;
+async fn op_database_cleanup(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let report = cleanup::database::execute_full_cleanup(
+        &client,
+        cleanup::database::CleanupOptions {
+            remove_test_data: opt_bool(&args, op, "removeTestData")?,
+            remove_inactive_records: opt_bool(&args, op, "removeInactivRecords")?,
+            cleanup_drafts: opt_bool(&args, op, "cleanupDrafts")?,
+            archive_old_records: opt_bool(&args, op, "archiveOldRecords")?,
+            optimize_database: opt_bool(&args, op, "optimizeDatabase")?,
+            days_threshold: opt_i64(&args, op, "daysThreshold")?,
+            dry_run: Some(opt_bool(&args, op, "dryRun")?.unwrap_or(true)),
+        },
+    )
+    .await?;
+    let v = serde_json::to_value(&report).unwrap_or_else(|_| json!({}));
+    Ok(ok_text(v))
+}
 
-    fn make_pool(tool_config: Option<InstanceToolConfig>) -> OdooClientPool {
-        let mut instances = HashMap::new();
-        instances.insert(
-            "school-prod".to_string(),
-            OdooInstanceConfig {
-                url: "http://localhost:8069".to_string(),
-                db: Some("school".to_string()),
-                api_key: Some("secret".to_string()),
-                username: None,
-                password: None,
-                version: Some("19".to_string()),
-                protocol: Default::default(),
-                timeout_ms: None,
-                max_retries: None,
-                tool_config,
-                read_only: false,
-                tags: Vec::new(),
-                aliases: Vec::new(),
-                extra: HashMap::new(),
-            },
-        );
+async fn op_deep_cleanup(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let report = cleanup::deep::execute_deep_cleanup(
+        &client,
+        cleanup::deep::DeepCleanupOptions {
+            dry_run: Some(opt_bool(&args, op, "dryRun")?.unwrap_or(true)),
+            keep_company_defaults: opt_bool(&args, op, "keepCompanyDefaults")?,
+            keep_user_accounts: opt_bool(&args, op, "keepUserAccounts")?,
+            keep_menus: opt_bool(&args, op, "keepMenus")?,
+            keep_groups: opt_bool(&args, op, "keepGroups")?,
+        },
+    )
+    .await?;
+    let v = serde_json::to_value(&report).unwrap_or_else(|_| json!({}));
+    Ok(ok_text(v))
+}
 
-        OdooClientPool {
-            env: Arc::new(RwLock::new(OdooEnvConfig { instances })),
-            clients: Arc::new(Mutex::new(HashMap::new())),
-            metadata_cache: MetadataCache::new(),
-            module_snapshots: ModuleSnapshotStore::memory(),
-        }
-    }
+async fn op_stock_inventory_reversal_cleanup(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let lines = req_value(&args, op, "lines")?;
+    let lines = serde_json::from_value(lines).map_err(|e| {
+        OdooError::InvalidResponse(format!(
+            "Argument 'lines' must be an array of stock inventory reversal lines: {e}"
+        ))
+    })?;
+    let report = cleanup::stock_inventory::execute_stock_inventory_reversal(
+        &client,
+        cleanup::stock_inventory::InventoryReversalOptions {
+            lines,
+            dry_run: Some(opt_bool(&args, op, "dryRun")?.unwrap_or(true)),
+            confirm: Some(opt_bool(&args, op, "confirm")?.unwrap_or(false)),
+            allow_negative: Some(opt_bool(&args, op, "allowNegative")?.unwrap_or(false)),
+            reference: opt_str(&args, op, "reference")?,
+        },
+    )
+    .await?;
+    let v = serde_json::to_value(&report).unwrap_or_else(|_| json!({}));
+    Ok(ok_text(v))
+}
 
-    fn make_tool(name: &str, op_type: &str) -> ToolDef {
-        ToolDef {
-            name: name.to_string(),
-            description: String::new(),
-            pack: None,
-            required_modules: Vec::new(),
-            input_schema: json!({"type": "object"}),
-            op: OpSpec {
-                op_type: op_type.to_string(),
-                map: HashMap::new(),
-            },
-            guards: None,
-        }
-    }
+async fn op_read_group(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let model = req_str(&args, op, "model")?;
+    let fields = opt_vec_string(&args, op, "fields")?.unwrap_or_default();
+    let groupby = opt_vec_string(&args, op, "groupby")?.unwrap_or_default();
+    let domain = opt_value(&args, op, "domain");
+    let offset = opt_i64(&args, op, "offset")?;
+    let limit = opt_i64(&args, op, "limit")?;
+    let orderby = opt_str(&args, op, "orderby")?;
+    let lazy = opt_bool(&args, op, "lazy")?;
+    let context = opt_value(&args, op, "context");
 
-    #[test]
-    fn report_size_limit_rejects_oversized_response() {
-        assert!(enforce_report_size(10, 10).is_ok());
-        assert!(enforce_report_size(11, 10).is_err());
-    }
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let result = client
+        .read_group(
+            &model, domain, fields, groupby, offset, limit, orderby, lazy, context,
+        )
+        .await?;
+    Ok(ok_text(json!({ "groups": result })))
+}
+
+async fn op_name_search(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let model = req_str(&args, op, "model")?;
+    let name = opt_str(&args, op, "name")?;
+    let domain = opt_value(&args, op, "args");
+    let operator = opt_str(&args, op, "operator")?;
+    let limit = opt_i64(&args, op, "limit")?;
+    let context = opt_value(&args, op, "context");
+
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let result = client
+        .name_search(&model, name, domain, operator, limit, context)
+        .await?;
+    Ok(ok_text(json!({ "results": result })))
+}
+
+async fn op_name_get(pool: &OdooClientPool, op: &OpSpec, args: Value) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let model = req_str(&args, op, "model")?;
+    let ids = req_vec_i64(&args, op, "ids")?;
+    let context = opt_value(&args, op, "context");
+
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let result = client.name_get(&model, ids, context).await?;
+    Ok(ok_text(json!({ "names": result })))
+}
+
+async fn op_default_get(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let model = req_str(&args, op, "model")?;
+    let fields_list = opt_vec_string(&args, op, "fields")?.unwrap_or_default();
+    let context = opt_value(&args, op, "context");
+
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let result = client.default_get(&model, fields_list, context).await?;
+    Ok(ok_text(json!({ "defaults": result })))
+}
+
+async fn op_copy(pool: &OdooClientPool, op: &OpSpec, args: Value) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let model = req_str(&args, op, "model")?;
+    let id = opt_i64(&args, op, "id")?
+        .ok_or_else(|| OdooError::InvalidResponse("Missing required argument 'id'".to_string()))?;
+    let default = opt_value(&args, op, "default");
+    let context = opt_value(&args, op, "context");
+
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let new_id = client.copy(&model, id, default, context).await?;
+    Ok(ok_text(json!({ "id": new_id, "success": true })))
+}
+
+async fn op_onchange(pool: &OdooClientPool, op: &OpSpec, args: Value) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let model = req_str(&args, op, "model")?;
+    let ids = req_vec_i64(&args, op, "ids")?;
+    let values = req_value(&args, op, "values")?;
+    let field_name = opt_vec_string(&args, op, "fieldName")?.unwrap_or_default();
+    let field_onchange = opt_value(&args, op, "fieldOnchange").unwrap_or(json!({}));
+    let context = opt_value(&args, op, "context");
+
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let result = client
+        .onchange(&model, ids, values, field_name, field_onchange, context)
+        .await?;
+    Ok(ok_text(json!({ "result": result })))
+}
+
+/// Whether the domain `terms` already have a condition on `field`.
+fn domain_mentions(terms: &[Value], field: &str) -> bool {
+    terms
+        .iter()
+        .any(|term| term.get(0).and_then(Value::as_str) == Some(field))
+}
+
+async fn op_list_models(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let domain = opt_value(&args, op, "domain");
+    let keyword = opt_str(&args, op, "keyword")?.filter(|k| !k.trim().is_empty());
+    let include_transient = opt_bool(&args, op, "includeTransient")?.unwrap_or(false);
+    let limit = opt_i64(&args, op, "limit")?;
+    let offset = opt_i64(&args, op, "offset")?;
+    let context = opt_value(&args, op, "context");
+
+    // Terms of one list are ANDed, so each filter is appended as a complete domain.
+    let mut terms = match domain {
+        Some(Value::Array(terms)) => terms,
+        Some(_) => {
+            return Err(OdooError::InvalidResponse(
+                "Argument 'domain' must be array".into(),
+            ));
+        }
+        None => Vec::new(),
+    };
+    if !include_transient && !domain_mentions(&terms, "transient") {
+        terms.push(json!(["transient", "=", false]));
+    }
+    if let Some(keyword) = keyword {
+        let keyword = keyword.trim();
+        terms.push(json!("|"));
+        terms.push(json!(["model", "ilike", keyword]));
+        terms.push(json!(["name", "ilike", keyword]));
+    }
+
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+
+    let rows = client
+        .search_read(
+            "ir.model",
+            Some(Value::Array(terms)),
+            Some(
+                ["model", "name", "transient", "modules"]
+                    .map(String::from)
+                    .to_vec(),
+            ),
+            limit,
+            offset,
+            Some("model".to_string()),
+            context,
+        )
+        .await?;
+
+    // `modules` is Odoo's comma-separated list of the modules defining the model.
+    let models: Vec<Value> = rows
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .map(|row| {
+            let modules: Vec<&str> = row["modules"]
+                .as_str()
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|m| !m.is_empty())
+                .collect();
+            json!({
+                "model": row["model"],
+                "name": row["name"],
+                "transient": row["transient"].as_bool().unwrap_or(false),
+                "modules": modules,
+            })
+        })
+        .collect();
+
+    Ok(ok_text(json!({ "count": models.len(), "models": models })))
+}
+
+/// Report actions (`ir.actions.report`), so callers can find the `reportName` to pass to
+/// `odoo_generate_report`.
+async fn op_list_reports(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let model = opt_str(&args, op, "model")?.filter(|m| !m.trim().is_empty());
+    let keyword = opt_str(&args, op, "keyword")?.filter(|k| !k.trim().is_empty());
+    let report_type = opt_str(&args, op, "reportType")?.filter(|t| !t.trim().is_empty());
+    let limit = opt_i64(&args, op, "limit")?;
+    let context = opt_value(&args, op, "context");
+
+    let mut terms = Vec::new();
+    if let Some(model) = model {
+        terms.push(json!(["model", "=", model.trim()]));
+    }
+    if let Some(report_type) = report_type {
+        terms.push(json!(["report_type", "=", report_type.trim()]));
+    }
+    if let Some(keyword) = keyword {
+        let keyword = keyword.trim();
+        terms.push(json!("|"));
+        terms.push(json!(["name", "ilike", keyword]));
+        terms.push(json!(["report_name", "ilike", keyword]));
+    }
+
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+
+    let rows = client
+        .search_read(
+            "ir.actions.report",
+            Some(Value::Array(terms)),
+            Some(
+                [
+                    "name",
+                    "report_name",
+                    "model",
+                    "report_type",
+                    "binding_model_id",
+                ]
+                .map(String::from)
+                .to_vec(),
+            ),
+            limit,
+            None,
+            Some("model, name".to_string()),
+            context,
+        )
+        .await?;
+
+    let reports: Vec<Value> = rows
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .map(|row| {
+            json!({
+                "id": row["id"],
+                "name": row["name"],
+                "reportName": row["report_name"],
+                "model": row["model"],
+                "reportType": row["report_type"],
+                // Bound reports appear in the model's Print menu.
+                "printMenu": row["binding_model_id"].as_bool() != Some(false),
+            })
+        })
+        .collect();
+
+    Ok(ok_text(
+        json!({ "count": reports.len(), "reports": reports }),
+    ))
+}
+
+async fn op_check_access(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let model = req_str(&args, op, "model")?;
+    let operation = req_str(&args, op, "operation")?;
+    let ids = opt_vec_i64(&args, op, "ids")?;
+    let context = opt_value(&args, op, "context");
+
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+
+    // Note: check_access() in Odoo 19+ is a private method and cannot be called remotely.
+    // We must use check_access_rights() and check_access_rule() for all Odoo versions,
+    // even though they are deprecated in Odoo 19+. They are still callable remotely.
+    let mut params = serde_json::Map::new();
+    params.insert("operation".to_string(), json!(operation));
+
+    let access_result = client
+        .call_named(
+            &model,
+            "check_access_rights",
+            None,
+            params.clone(),
+            context.clone(),
+        )
+        .await?;
+
+    // If IDs provided, also check record-level access rules
+    let record_result = if let Some(record_ids) = ids {
+        let ids_array: Vec<i64> = record_ids.clone();
+        client
+            .call_named(
+                &model,
+                "check_access_rule",
+                Some(ids_array),
+                params,
+                context,
+            )
+            .await
+            .ok()
+    } else {
+        None
+    };
+
+    let result = json!({
+        "has_access": true,
+        "model": model,
+        "operation": operation,
+        "model_level": access_result,
+        "record_level": record_result
+    });
+
+    Ok(ok_text(result))
+}
+
+/// Records per `create(vals_list)` call of `odoo_create_batch` unless `chunkSize` says otherwise.
+const CREATE_BATCH_CHUNK: usize = 100;
+const MAX_CREATE_BATCH_CHUNK: usize = 500;
+/// Records one `odoo_create_batch` call may create.
+const MAX_CREATE_BATCH: usize = 5000;
+
+/// Create records in chunks, each chunk with one `create(vals_list)` call. A chunk is created
+/// or rejected as a whole; a failed chunk is reported in `errors` and the remaining chunks
+/// still run.
+async fn op_create_batch(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let model = req_str(&args, op, "model")?;
+    let values_array = req_value(&args, op, "values")?;
+    let context = opt_value(&args, op, "context");
+    let chunk_size = opt_i64(&args, op, "chunkSize")?
+        .map(|size| size.clamp(1, MAX_CREATE_BATCH_CHUNK as i64) as usize)
+        .unwrap_or(CREATE_BATCH_CHUNK);
+
+    // Validate values is an array
+    let values_list = values_array
+        .as_array()
+        .ok_or_else(|| OdooError::InvalidResponse("'values' must be an array".to_string()))?;
+
+    // Limit batch size to prevent abuse
+    if values_list.len() > MAX_CREATE_BATCH {
+        return Err(OdooError::InvalidResponse(format!(
+            "Batch size limited to {MAX_CREATE_BATCH} records"
+        )));
+    }
+
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+
+    let mut created_ids = Vec::new();
+    let mut errors = Vec::new();
+    let chunks = values_list.chunks(chunk_size);
+    let chunk_count = chunks.len();
+    for (index, chunk) in chunks.enumerate() {
+        let first = index * chunk_size;
+        match client
+            .create_many(&model, chunk.to_vec(), context.clone())
+            .await
+        {
+            Ok(ids) => created_ids.extend(ids),
+            Err(e) => errors.push(json!({
+                "chunk": index,
+                "fromIndex": first,
+                "toIndex": first + chunk.len() - 1,
+                "error": e.to_string(),
+            })),
+        }
+    }
+
+    Ok(ok_text(json!({
+        "success": errors.is_empty(),
+        "ids": created_ids,
+        "count": created_ids.len(),
+        "chunks": chunk_count,
+        "errors": errors,
+    })))
+}
+
+/// Records one `odoo_update_batch` call may update.
+const MAX_UPDATE_BATCH: usize = 5000;
+
+/// Update records that each get their own values. Entries with identical `values` share one
+/// `write(ids, values)` call (split every `chunkSize` ids); a failed call marks its records as
+/// failed and the other calls still run.
+async fn op_update_batch(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let model = req_str(&args, op, "model")?;
+    let updates = req_value(&args, op, "updates")?;
+    let context = opt_value(&args, op, "context");
+    let chunk_size = opt_i64(&args, op, "chunkSize")?
+        .map(|size| size.clamp(1, MAX_CREATE_BATCH_CHUNK as i64) as usize)
+        .unwrap_or(CREATE_BATCH_CHUNK);
+
+    let entries = updates
+        .as_array()
+        .ok_or_else(|| OdooError::InvalidResponse("'updates' must be an array".to_string()))?;
+    if entries.len() > MAX_UPDATE_BATCH {
+        return Err(OdooError::InvalidResponse(format!(
+            "Batch size limited to {MAX_UPDATE_BATCH} records"
+        )));
+    }
+
+    // Group ids by their values, in order of first appearance. serde_json objects keep their
+    // keys sorted, so equal value sets serialize identically.
+    let mut ids = Vec::with_capacity(entries.len());
+    let mut seen = BTreeSet::new();
+    let mut groups: Vec<(Value, Vec<i64>)> = Vec::new();
+    let mut group_of: HashMap<String, usize> = HashMap::new();
+    for (index, entry) in entries.iter().enumerate() {
+        let id = entry.get("id").and_then(Value::as_i64).ok_or_else(|| {
+            OdooError::InvalidResponse(format!("Update {index} needs an integer 'id'"))
+        })?;
+        let values = entry
+            .get("values")
+            .filter(|values| values.is_object())
+            .ok_or_else(|| {
+                OdooError::InvalidResponse(format!("Update {index} needs a 'values' object"))
+            })?;
+        if !seen.insert(id) {
+            return Err(OdooError::InvalidResponse(format!(
+                "Record {id} is updated more than once; merge its values into one entry"
+            )));
+        }
+        ids.push(id);
+        let group = *group_of.entry(values.to_string()).or_insert_with(|| {
+            groups.push((values.clone(), Vec::new()));
+            groups.len() - 1
+        });
+        groups[group].1.push(id);
+    }
+
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+
+    let mut failures: HashMap<i64, String> = HashMap::new();
+    let mut calls = 0;
+    for (values, group_ids) in &groups {
+        for chunk in group_ids.chunks(chunk_size) {
+            calls += 1;
+            let error = match client
+                .write(&model, chunk.to_vec(), values.clone(), context.clone())
+                .await
+            {
+                Ok(true) => continue,
+                Ok(false) => "write returned false".to_string(),
+                Err(e) => e.to_string(),
+            };
+            for id in chunk {
+                failures.insert(*id, error.clone());
+            }
+        }
+    }
+
+    let results: Vec<Value> = ids
+        .iter()
+        .map(|id| match failures.get(id) {
+            Some(error) => json!({ "id": id, "success": false, "error": error }),
+            None => json!({ "id": id, "success": true }),
+        })
+        .collect();
+    Ok(ok_text(json!({
+        "success": failures.is_empty(),
+        "updated": ids.len() - failures.len(),
+        "failed": failures.len(),
+        "writeCalls": calls,
+        "results": results,
+    })))
+}
+
+/// Update the record matching `domain` and/or `matchFields` (compared against `values`), or
+/// create one when none matches. More than one match is an error rather than a guess.
+async fn op_upsert(pool: &OdooClientPool, op: &OpSpec, args: Value) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let model = req_str(&args, op, "model")?;
+    let values = req_value(&args, op, "values")?;
+    let domain = opt_value(&args, op, "domain");
+    let match_fields = opt_vec_string(&args, op, "matchFields")?.unwrap_or_default();
+    let context = opt_value(&args, op, "context");
+
+    if !values.is_object() {
+        return Err(OdooError::InvalidResponse(
+            "'values' must be an object".to_string(),
+        ));
+    }
+    let mut conditions = match domain {
+        Some(Value::Array(domain)) => domain,
+        Some(Value::Null) | None => Vec::new(),
+        Some(_) => {
+            return Err(OdooError::InvalidResponse(
+                "'domain' must be an array".to_string(),
+            ));
+        }
+    };
+    for field in &match_fields {
+        let value = values.get(field).ok_or_else(|| {
+            OdooError::InvalidResponse(format!(
+                "Match field '{field}' must also be given in 'values'"
+            ))
+        })?;
+        conditions.push(json!([field, "=", value]));
+    }
+    if conditions.is_empty() {
+        return Err(OdooError::InvalidResponse(
+            "Give 'domain' or 'matchFields' to find the existing record".to_string(),
+        ));
+    }
+
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let matches = client
+        .search(
+            &model,
+            Some(Value::Array(conditions)),
+            Some(2),
+            None,
+            None,
+            context.clone(),
+        )
+        .await?;
+    match matches.as_slice() {
+        [] => {
+            let id = client.create(&model, values, context).await?;
+            Ok(ok_text(
+                json!({ "success": true, "action": "created", "id": id }),
+            ))
+        }
+        [id] => {
+            let ok = client.write(&model, vec![*id], values, context).await?;
+            Ok(ok_text(
+                json!({ "success": ok, "action": "updated", "id": id }),
+            ))
+        }
+        _ => Err(OdooError::InvalidResponse(format!(
+            "Several {model} records match (ids {matches:?} and possibly more); narrow the domain or matchFields"
+        ))),
+    }
+}
+
+/// Split an external id into its `ir.model.data` module and name.
+fn split_xmlid(xmlid: &str) -> Result<(&str, &str), OdooError> {
+    match xmlid.trim().split_once('.') {
+        Some((module, name)) if !module.is_empty() && !name.is_empty() => Ok((module, name)),
+        _ => Err(OdooError::InvalidResponse(format!(
+            "External id '{xmlid}' must look like 'module.name'"
+        ))),
+    }
+}
+
+/// `ir.model.data` rows for `xmlids`, keyed by `module.name`.
+async fn xmlid_rows(
+    client: &OdooClient,
+    xmlids: &[String],
+) -> Result<HashMap<String, Value>, OdooError> {
+    let mut domain: Vec<Value> =
+        std::iter::repeat_n(json!("|"), xmlids.len().saturating_sub(1)).collect();
+    for xmlid in xmlids {
+        let (module, name) = split_xmlid(xmlid)?;
+        domain.extend([
+            json!("&"),
+            json!(["module", "=", module]),
+            json!(["name", "=", name]),
+        ]);
+    }
+    let rows = client
+        .search_read(
+            "ir.model.data",
+            Some(Value::Array(domain)),
+            Some(vec![
+                "module".into(),
+                "name".into(),
+                "model".into(),
+                "res_id".into(),
+                "noupdate".into(),
+            ]),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await?;
+    Ok(rows
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|row| {
+            let xmlid = format!("{}.{}", row["module"].as_str()?, row["name"].as_str()?);
+            Some((xmlid, row.clone()))
+        })
+        .collect())
+}
+
+/// Resolve external ids (`ir.model.data`) to the records they name.
+async fn op_xmlid_lookup(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let xmlids = opt_vec_string(&args, op, "xmlids")?.unwrap_or_default();
+    if xmlids.is_empty() {
+        return Err(OdooError::InvalidResponse(
+            "'xmlids' must name at least one external id".to_string(),
+        ));
+    }
+
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let rows = xmlid_rows(&client, &xmlids).await?;
+    let mut records = Vec::new();
+    let mut missing = Vec::new();
+    for xmlid in &xmlids {
+        match rows.get(xmlid.trim()) {
+            Some(row) => records.push(json!({
+                "xmlid": xmlid.trim(),
+                "model": row["model"],
+                "id": row["res_id"],
+                "noupdate": row["noupdate"],
+            })),
+            None => missing.push(xmlid.trim().to_string()),
+        }
+    }
+    Ok(ok_text(json!({ "records": records, "missing": missing })))
+}
+
+/// Run an `ir.actions.server` on records the way the Action menu does: `active_model`,
+/// `active_id` and `active_ids` in the context. The action is picked by id or external id.
+async fn op_server_action_run(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+) -> Result<Value, OdooError> {
+    const MODEL: &str = "ir.actions.server";
+    let instance = req_str(&args, op, "instance")?;
+    let record_ids = opt_vec_i64(&args, op, "recordIds")?.unwrap_or_default();
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let id = match (opt_i64(&args, op, "id")?, opt_str(&args, op, "xmlid")?) {
+        (Some(id), _) => id,
+        (None, Some(xmlid)) => {
+            let xmlid = xmlid.trim().to_string();
+            let rows = xmlid_rows(&client, std::slice::from_ref(&xmlid)).await?;
+            match rows.get(&xmlid) {
+                Some(row) if row["model"] == MODEL => row["res_id"].as_i64().ok_or_else(|| {
+                    OdooError::InvalidResponse(format!("External id '{xmlid}' has no res_id"))
+                })?,
+                Some(row) => {
+                    return Err(OdooError::InvalidResponse(format!(
+                        "External id '{xmlid}' names a {} record, not a server action",
+                        row["model"].as_str().unwrap_or("?")
+                    )));
+                }
+                None => {
+                    return Err(OdooError::InvalidResponse(format!(
+                        "Unknown external id '{xmlid}'"
+                    )));
+                }
+            }
+        }
+        (None, None) => {
+            return Err(OdooError::InvalidResponse(
+                "Pass the server action's 'id' or 'xmlid'".to_string(),
+            ));
+        }
+    };
+    let found = client
+        .read(
+            MODEL,
+            vec![id],
+            Some(vec![
+                "name".to_string(),
+                "model_name".to_string(),
+                "state".to_string(),
+            ]),
+            None,
+        )
+        .await?;
+    let Some(action) = found.get(0).cloned() else {
+        return Err(OdooError::InvalidResponse(format!(
+            "Server action {id} not found"
+        )));
+    };
+    let model = action["model_name"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+
+    let mut context = opt_value(&args, op, "context")
+        .and_then(|c| c.as_object().cloned())
+        .unwrap_or_default();
+    context.insert("active_model".into(), json!(model));
+    context.insert("active_ids".into(), json!(record_ids));
+    if let Some(first) = record_ids.first() {
+        context.insert("active_id".into(), json!(first));
+    }
+    let result = client
+        .call_named(
+            MODEL,
+            "run",
+            Some(vec![id]),
+            Map::new(),
+            Some(Value::Object(context)),
+        )
+        .await?;
+    Ok(ok_text(json!({
+        "action": {
+            "id": id,
+            "name": action["name"],
+            "model": model,
+            "state": action["state"],
+        },
+        "recordIds": record_ids,
+        // Usually false, or a client action (a window to open, a notification) to show.
+        "result": result,
+    })))
+}
+
+/// Give a record an external id. Assigning the id it already has is a no-op; an id naming
+/// another record is refused.
+async fn op_xmlid_assign(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let model = req_str(&args, op, "model")?;
+    let id = opt_i64(&args, op, "id")?
+        .ok_or_else(|| OdooError::InvalidResponse("Missing required argument 'id'".to_string()))?;
+    let xmlid = req_str(&args, op, "xmlid")?;
+    let noupdate = opt_bool(&args, op, "noupdate")?.unwrap_or(true);
+    let (module, name) = split_xmlid(&xmlid)?;
+    let xmlid = format!("{module}.{name}");
+
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    if let Some(row) = xmlid_rows(&client, std::slice::from_ref(&xmlid))
+        .await?
+        .remove(&xmlid)
+    {
+        if row["model"].as_str() == Some(model.as_str()) && row["res_id"].as_i64() == Some(id) {
+            return Ok(ok_text(json!({
+                "success": true,
+                "xmlid": xmlid,
+                "model": model,
+                "id": id,
+                "created": false,
+            })));
+        }
+        return Err(OdooError::InvalidResponse(format!(
+            "External id '{xmlid}' already names {}({})",
+            row["model"].as_str().unwrap_or("?"),
+            row["res_id"]
+        )));
+    }
+    if client
+        .search(
+            &model,
+            Some(json!([["id", "=", id]])),
+            Some(1),
+            None,
+            None,
+            Some(json!({ "active_test": false })),
+        )
+        .await?
+        .is_empty()
+    {
+        return Err(OdooError::InvalidResponse(format!(
+            "{model}({id}) does not exist"
+        )));
+    }
+    client
+        .create(
+            "ir.model.data",
+            json!({
+                "module": module,
+                "name": name,
+                "model": model,
+                "res_id": id,
+                "noupdate": noupdate,
+            }),
+            None,
+        )
+        .await?;
+    Ok(ok_text(json!({
+        "success": true,
+        "xmlid": xmlid,
+        "model": model,
+        "id": id,
+        "created": true,
+    })))
+}
+
+/// View types `odoo_view_definition` accepts; `tree` is the pre-17 name of `list`.
+const VIEW_TYPES: &[&str] = &[
+    "form", "list", "tree", "kanban", "search", "calendar", "pivot", "graph", "activity",
+];
+
+/// The view type name `major` understands: Odoo 17 renamed `tree` views to `list` and 18
+/// dropped the old name.
+fn wire_view_type(view_type: &str, major: Option<u32>) -> &str {
+    match view_type {
+        "list" if major.is_some_and(|m| m < 17) => "tree",
+        other => other,
+    }
+}
+
+/// Major Odoo version from the instance's configured `version`, if any.
+fn configured_major(pool: &OdooClientPool, instance: &str) -> Result<Option<u32>, OdooError> {
+    Ok(pool.instance_config(instance)?.version.and_then(|v| {
+        v.split('.')
+            .next()
+            .and_then(|m| m.trim().parse::<u32>().ok())
+    }))
+}
+
+/// Arch and field definitions of each requested view, keyed by the requested view type.
+/// `get_views` exists from Odoo 16; older servers only have `fields_view_get`, which is also
+/// tried when the version is not configured and `get_views` fails.
+async fn fetch_views(
+    client: &OdooClient,
+    model: &str,
+    view_types: &[String],
+    view_id: Option<i64>,
+    major: Option<u32>,
+    context: Option<Value>,
+) -> Result<Vec<(String, Value, Value)>, OdooError> {
+    let fields_view_get = async || {
+        let mut views = Vec::new();
+        for view_type in view_types {
+            let mut params = Map::new();
+            params.insert("view_id".into(), json!(view_id.unwrap_or_default()));
+            params.insert(
+                "view_type".into(),
+                json!(if view_type == "list" {
+                    "tree"
+                } else {
+                    view_type
+                }),
+            );
+            let view = client
+                .call_named(model, "fields_view_get", None, params, context.clone())
+                .await?;
+            let fields = view["fields"].clone();
+            views.push((view_type.clone(), view, fields));
+        }
+        Ok::<_, OdooError>(views)
+    };
+    if major.is_some_and(|m| m < 16) {
+        return fields_view_get().await;
+    }
+
+    let requested: Vec<Value> = view_types
+        .iter()
+        .map(|t| {
+            json!([
+                view_id.map_or(json!(false), |id| json!(id)),
+                wire_view_type(t, major)
+            ])
+        })
+        .collect();
+    let mut params = Map::new();
+    params.insert("views".into(), json!(requested));
+    params.insert("options".into(), json!({}));
+    let result = match client
+        .call_named(model, "get_views", None, params, context.clone())
+        .await
+    {
+        Ok(result) => result,
+        Err(OdooError::Api { .. }) if major.is_none() => return fields_view_get().await,
+        Err(e) => return Err(e),
+    };
+    // Odoo 16 maps each model to its fields; 17 and later nest them under "fields".
+    let model_fields = &result["models"][model];
+    let fields = match model_fields.get("fields") {
+        Some(nested) if nested.is_object() && nested.get("type").is_none() => nested.clone(),
+        _ => model_fields.clone(),
+    };
+    Ok(view_types
+        .iter()
+        .map(|t| {
+            (
+                t.clone(),
+                result["views"][wire_view_type(t, major)].clone(),
+                fields.clone(),
+            )
+        })
+        .collect())
+}
+
+/// The arch of a model's form/list/... views with the required, read-only and invisible state
+/// of each field, so callers know what a create call must include.
+async fn op_view_definition(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let model = req_str(&args, op, "model")?;
+    let mut view_types = opt_vec_string(&args, op, "viewTypes")?
+        .unwrap_or_else(|| vec!["form".into(), "list".into()]);
+    for view_type in &mut view_types {
+        *view_type = view_type.trim().to_lowercase();
+        if !VIEW_TYPES.contains(&view_type.as_str()) {
+            return Err(OdooError::InvalidResponse(format!(
+                "Unknown view type '{view_type}'; expected one of {}",
+                VIEW_TYPES.join(", ")
+            )));
+        }
+        if view_type == "tree" {
+            *view_type = "list".into();
+        }
+    }
+    view_types.dedup();
+    if view_types.is_empty() {
+        return Err(OdooError::InvalidResponse(
+            "'viewTypes' must name at least one view type".to_string(),
+        ));
+    }
+    let view_id = opt_i64(&args, op, "viewId")?;
+    if view_id.is_some() && view_types.len() != 1 {
+        return Err(OdooError::InvalidResponse(
+            "'viewId' needs exactly one entry in 'viewTypes'".to_string(),
+        ));
+    }
+    let include_arch = opt_bool(&args, op, "includeArch")?.unwrap_or(true);
+    let context = opt_value(&args, op, "context");
+    let major = configured_major(pool, &instance)?;
+
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let views = fetch_views(&client, &model, &view_types, view_id, major, context).await?;
+
+    let mut out = Vec::new();
+    for (view_type, view, fields) in views {
+        let Some(arch) = view["arch"].as_str() else {
+            return Err(OdooError::InvalidResponse(format!(
+                "Odoo returned no {view_type} view for {model}"
+            )));
+        };
+        let summaries: Vec<Value> = view_arch::top_level_fields(arch)
+            .iter()
+            .map(|field| view_arch::field_summary(field, &fields))
+            .collect();
+        let mut entry = json!({
+            "type": view_type,
+            "viewId": view.get("id").or_else(|| view.get("view_id")).cloned().unwrap_or(Value::Null),
+            "requiredFields": view_arch::required_fields(&summaries),
+            "fields": summaries,
+        });
+        if include_arch {
+            entry["arch"] = json!(arch);
+        }
+        out.push(entry);
+    }
+    Ok(ok_text(json!({ "model": model, "views": out })))
+}
+
+/// Start, stop or report the session's read-only snapshot (see [`crate::mcp::snapshot`]).
+fn op_snapshot_session(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+) -> Result<Value, OdooError> {
+    let scope = pool.scope.clone().ok_or_else(|| {
+        OdooError::InvalidResponse("odoo_snapshot requires an MCP session".into())
+    })?;
+    let action = opt_str(&args, op, "action")?.unwrap_or_else(|| "status".into());
+    match action.as_str() {
+        "start" => {
+            let at = snapshot::parse_at(opt_str(&args, op, "at")?.as_deref())
+                .map_err(OdooError::InvalidResponse)?;
+            let minutes = opt_i64(&args, op, "minutes")?.unwrap_or(snapshot::DEFAULT_MINUTES);
+            if !(1..=snapshot::MAX_MINUTES).contains(&minutes) {
+                return Err(OdooError::InvalidResponse(format!(
+                    "'minutes' must be between 1 and {}",
+                    snapshot::MAX_MINUTES
+                )));
+            }
+            Ok(ok_text(
+                pool.snapshots.start(&scope, at, minutes).describe(),
+            ))
+        }
+        "stop" => Ok(ok_text(json!({
+            "active": false,
+            "stopped": pool.snapshots.stop(&scope).is_some(),
+        }))),
+        "status" => Ok(ok_text(
+            pool.snapshots
+                .active(&scope)
+                .map_or_else(|| json!({ "active": false }), |s| s.describe()),
+        )),
+        other => Err(OdooError::InvalidResponse(format!(
+            "Unknown action '{other}'; expected start, stop or status"
+        ))),
+    }
+}
+
+const ACCESS_OPERATIONS: &[&str] = &["read", "create", "write", "unlink"];
+
+/// `Some(message)` when `error` is Odoo refusing access, `None` for any other error.
+fn access_denial(error: &OdooError) -> Option<String> {
+    match error {
+        OdooError::Api {
+            message,
+            body: Some(body),
+            ..
+        } if body
+            .name
+            .as_deref()
+            .is_some_and(|name| name.ends_with("AccessError")) =>
+        {
+            Some(message.clone())
+        }
+        _ => None,
+    }
+}
+
+/// Whether the instance's credential may run `operation` on `model` (and on `ids`, through
+/// record rules). Odoo 18 and later answer with `check_access`; older versions with
+/// `check_access_rights` followed by `check_access_rule` on the records. With no configured
+/// version, the older pair is used when `check_access` is not there.
+async fn check_operation_access(
+    client: &OdooClient,
+    model: &str,
+    operation: &str,
+    ids: &[i64],
+    major: Option<u32>,
+    context: Option<Value>,
+) -> Result<Result<(), String>, OdooError> {
+    let ids = (!ids.is_empty()).then(|| ids.to_vec());
+    let mut params = Map::new();
+    params.insert("operation".into(), json!(operation));
+    if major.is_none_or(|m| m >= 18) {
+        match client
+            .call_named(
+                model,
+                "check_access",
+                ids.clone(),
+                params.clone(),
+                context.clone(),
+            )
+            .await
+        {
+            Ok(_) => return Ok(Ok(())),
+            Err(e) => match access_denial(&e) {
+                Some(reason) => return Ok(Err(reason)),
+                None if major.is_none() && matches!(e, OdooError::Api { .. }) => {}
+                None => return Err(e),
+            },
+        }
+    }
+
+    let mut rights = params.clone();
+    rights.insert("raise_exception".into(), json!(false));
+    let allowed = client
+        .call_named(model, "check_access_rights", None, rights, context.clone())
+        .await?;
+    if allowed != json!(true) {
+        return Ok(Err(format!(
+            "No access rights to {operation} on {model} (ir.model.access)"
+        )));
+    }
+    if ids.is_none() {
+        return Ok(Ok(()));
+    }
+    match client
+        .call_named(model, "check_access_rule", ids, params, context)
+        .await
+    {
+        Ok(_) => Ok(Ok(())),
+        Err(e) => access_denial(&e).map(Err).ok_or(e),
+    }
+}
+
+async fn op_access_check(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let model = req_str(&args, op, "model")?;
+    let operations = match opt_vec_string(&args, op, "operations")? {
+        Some(operations) if !operations.is_empty() => operations,
+        _ => ACCESS_OPERATIONS.iter().map(|o| o.to_string()).collect(),
+    };
+    if let Some(unknown) = operations
+        .iter()
+        .find(|o| !ACCESS_OPERATIONS.contains(&o.as_str()))
+    {
+        return Err(OdooError::InvalidResponse(format!(
+            "Unknown operation '{unknown}'; expected one of {}",
+            ACCESS_OPERATIONS.join(", ")
+        )));
+    }
+    let ids = opt_vec_i64(&args, op, "ids")?.unwrap_or_default();
+    let context = opt_value(&args, op, "context");
+    let major = configured_major(pool, &instance)?;
+
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let mut results = Vec::new();
+    for operation in &operations {
+        let entry =
+            match check_operation_access(&client, &model, operation, &ids, major, context.clone())
+                .await?
+            {
+                Ok(()) => json!({ "operation": operation, "allowed": true }),
+                Err(reason) => {
+                    json!({ "operation": operation, "allowed": false, "reason": reason })
+                }
+            };
+        results.push(entry);
+    }
+    let allowed = results.iter().all(|r| r["allowed"] == json!(true));
+    Ok(ok_text(json!({
+        "model": model,
+        "ids": ids,
+        "allowed": allowed,
+        "operations": results,
+    })))
+}
+
+/// Declarative composite tool: `op.steps` run by the orchestrator against one instance.
+async fn op_composite(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+    progress: Option<&Progress>,
+) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let client = pool
+        .get(&instance)
+        .await
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let result = orchestrator::run(&client, &op.steps, &args, progress).await?;
+    Ok(ok_text(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::odoo::config::{OdooEnvConfig, OdooInstanceConfig};
+    use std::collections::HashMap;
+    use std::sync::{Arc, RwLock};
+    use tokio::sync::Mutex;
+
+    fn make_pool(tool_config: Option<InstanceToolConfig>) -> OdooClientPool {
+        let mut instances = HashMap::new();
+        instances.insert(
+            "school-prod".to_string(),
+            OdooInstanceConfig {
+                url: "http://localhost:8069".to_string(),
+                db: Some("school".to_string()),
+                api_key: Some("secret".to_string()),
+                username: None,
+                password: None,
+                version: Some("19".to_string()),
+                protocol: Default::default(),
+                timeout_ms: None,
+                max_retries: None,
+                tool_config,
+                read_only: false,
+                tags: Vec::new(),
+                headers: HashMap::new(),
+                proxy_auth: None,
+                lang: None,
+                translate_errors: false,
+                health_depth: None,
+                aliases: Vec::new(),
+                extra: HashMap::new(),
+            },
+        );
+
+        OdooClientPool {
+            env: Arc::new(RwLock::new(OdooEnvConfig { instances })),
+            clients: Arc::new(Mutex::new(HashMap::new())),
+            metadata_cache: MetadataCache::new(),
+            result_cache: ResultCache::default(),
+            writes: broadcast::channel(64).0,
+            instances_changed: broadcast::channel(4).0,
+            health: InstanceHealth::default(),
+            confirmations: ConfirmationStore::default(),
+            module_snapshots: ModuleSnapshotStore::memory(),
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+            snapshots: SnapshotStore::default(),
+            scope: None,
+            tenant: None,
+        }
+    }
+
+    fn make_tool(name: &str, op_type: &str) -> ToolDef {
+        ToolDef {
+            name: name.to_string(),
+            description: String::new(),
+            pack: None,
+            required_modules: Vec::new(),
+            input_schema: json!({"type": "object"}),
+            op: OpSpec {
+                op_type: op_type.to_string(),
+                map: HashMap::new(),
+                steps: Vec::new(),
+                pinned: Default::default(),
+            },
+            guards: None,
+            annotations: Default::default(),
+            describe_fields: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_call_tool_returns_cancelled_when_token_fires() {
+        let pool = make_pool(None);
+        let mut tool = make_tool("odoo_search", "search");
+        tool.op.map.insert("instance".into(), "/instance".into());
+        tool.op.map.insert("model".into(), "/model".into());
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        let error = call_tool(
+            &pool,
+            &tool,
+            json!({"instance": "school-prod", "model": "res.partner"}),
+            &CallContext {
+                cancel,
+                ..CallContext::default()
+            },
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(error, OdooError::Cancelled));
+    }
+
+    #[test]
+    fn pinned_args_override_the_caller() {
+        let mut op = make_op(HashMap::new());
+        op.pinned.insert("model".into(), json!("sale.order"));
+        let args = with_pinned_args(json!({"instance": "dev", "model": "res.users"}), &op);
+        assert_eq!(args, json!({"instance": "dev", "model": "sale.order"}));
+    }
+
+    #[tokio::test]
+    async fn test_smart_search_requires_sampling_client() {
+        let pool = make_pool(None);
+        let mut tool = make_tool("odoo_smart_search", "smart_search");
+        for key in ["instance", "model", "query"] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+
+        let error = call_tool(
+            &pool,
+            &tool,
+            json!({"instance": "school-prod", "model": "sale.order", "query": "big orders"}),
+            &CallContext::default(),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(error.to_string().contains("MCP sampling"));
+    }
+
+    #[tokio::test]
+    async fn test_record_write_invalidates_cache_and_notifies() {
+        let mut pool = make_pool(None);
+        pool.result_cache = ResultCache::with_ttl(Some(std::time::Duration::from_secs(60)));
+        let mut writes = pool.subscribe_writes();
+        for model in ["res.partner", "sale.order"] {
+            pool.result_cache
+                .insert("school-prod", model, "k".into(), json!({"cached": true}))
+                .await;
+        }
+
+        pool.record_write("school-prod", Some("res.partner")).await;
+
+        assert!(
+            pool.result_cache
+                .get("school-prod", "res.partner", "k")
+                .await
+                .is_none()
+        );
+        assert!(
+            pool.result_cache
+                .get("school-prod", "sale.order", "k")
+                .await
+                .is_some()
+        );
+        assert_eq!(
+            writes.recv().await.unwrap(),
+            ModelWrite {
+                instance: "school-prod".into(),
+                model: Some("res.partner".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn report_size_limit_rejects_oversized_response() {
+        assert!(enforce_report_size(10, 10).is_ok());
+        assert!(enforce_report_size(11, 10).is_err());
+    }
+
+    fn make_op(map: HashMap<String, String>) -> OpSpec {
+        OpSpec {
+            op_type: "test".to_string(),
+            map,
+            steps: Vec::new(),
+            pinned: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_mapped_options_skips_instance_and_nulls() {
+        let op = make_op(HashMap::from([
+            ("instance".to_string(), "/instance".to_string()),
+            ("ticketId".to_string(), "/ticketId".to_string()),
+            ("team".to_string(), "/team".to_string()),
+            ("stage".to_string(), "/stage".to_string()),
+        ]));
+        let args =
+            json!({"instance": "school-prod", "ticketId": 4, "team": "Support", "stage": null});
+
+        let options: packs::helpdesk::AssignTicketOptions = mapped_options(&args, &op).unwrap();
+
+        assert_eq!(options.ticket_id, 4);
+        assert_eq!(options.team.as_deref(), Some("Support"));
+        assert!(options.stage.is_none());
+
+        let bad = json!({"instance": "school-prod", "ticketId": "four"});
+        assert!(mapped_options::<packs::helpdesk::AssignTicketOptions>(&bad, &op).is_err());
+    }
+
+    #[test]
+    fn test_resolve_instance_name_by_exact_canonical_name() {
+        let pool = make_pool(None);
+
+        let resolved = pool.resolve_instance_name("school-prod").unwrap();
+
+        assert_eq!(resolved, "school-prod");
+    }
+
+    #[test]
+    fn test_resolve_instance_name_by_case_insensitive_canonical_name() {
+        let pool = make_pool(None);
+
+        let resolved = pool.resolve_instance_name("SCHOOL-PROD").unwrap();
+
+        assert_eq!(resolved, "school-prod");
+    }
+
+    #[test]
+    fn test_resolve_instance_name_suggests_matching_canonical_name() {
+        let pool = make_pool(None);
+
+        let error = pool.resolve_instance_name("school prod").unwrap_err();
+
+        assert!(error.to_string().contains("Did you mean 'school-prod'?"));
+    }
+
+    #[test]
+    fn test_resolve_instance_name_rejects_legacy_alias_values() {
+        let pool = make_pool(None);
+
+        let error = pool.resolve_instance_name("school").unwrap_err();
+
+        assert!(error.to_string().contains("Unknown Odoo instance 'school'"));
+    }
+
+    #[test]
+    fn tenant_view_hides_other_instances() {
+        let pool = make_pool(None);
+        let finance = Tenant {
+            name: "finance".into(),
+            instances: vec!["School-Prod".into()],
+            calls_per_minute: None,
+        };
+        let view = pool.for_tenant(&finance);
+        assert_eq!(view.instance_names(), ["school-prod"]);
+        assert_eq!(
+            view.resolve_instance_name("school-prod").unwrap(),
+            "school-prod"
+        );
+
+        let hr = pool.for_tenant(&Tenant::undefined("hr"));
+        assert!(hr.instance_names().is_empty());
+        let error = hr.resolve_instance_name("school prod").unwrap_err();
+        // Neither the suggestion nor the available list names the hidden instance.
+        assert!(!error.to_string().contains("school-prod"), "{error}");
+        assert!(pool.resolve_instance_name("school-prod").is_ok());
+    }
+
+    #[test]
+    fn test_ptr_finds_value_by_json_pointer() {
+        let args = json!({
+            "data": {
+                "name": "test"
+            }
+        });
+        let mut map = HashMap::new();
+        map.insert("key".to_string(), "/data/name".to_string());
+        let op = make_op(map);
+
+        let result = ptr(&args, &op, "key");
+        assert_eq!(result, Some(&json!("test")));
+    }
+
+    #[test]
+    fn test_ptr_returns_none_for_missing_key() {
+        let args = json!({"data": "value"});
+        let op = make_op(HashMap::new());
+
+        let result = ptr(&args, &op, "nonexistent");
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_ptr_returns_none_for_invalid_pointer() {
+        let args = json!({"data": "value"});
+        let mut map = HashMap::new();
+        map.insert("key".to_string(), "/nonexistent/path".to_string());
+        let op = make_op(map);
+
+        let result = ptr(&args, &op, "key");
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_req_str_success() {
+        let args = json!({"name": "test"});
+        let mut map = HashMap::new();
+        map.insert("name".to_string(), "/name".to_string());
+        let op = make_op(map);
+
+        let result = req_str(&args, &op, "name").unwrap();
+        assert_eq!(result, "test");
+    }
+
+    #[test]
+    fn test_req_str_missing_returns_error() {
+        let args = json!({});
+        let op = make_op(HashMap::new());
+
+        let result = req_str(&args, &op, "name");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_req_str_non_string_returns_error() {
+        let args = json!({"name": 123});
+        let mut map = HashMap::new();
+        map.insert("name".to_string(), "/name".to_string());
+        let op = make_op(map);
+
+        let result = req_str(&args, &op, "name");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_opt_str_success() {
+        let args = json!({"name": "test"});
+        let mut map = HashMap::new();
+        map.insert("name".to_string(), "/name".to_string());
+        let op = make_op(map);
+
+        let result = opt_str(&args, &op, "name").unwrap();
+        assert_eq!(result, Some("test".to_string()));
+    }
+
+    #[test]
+    fn test_opt_str_missing_returns_none() {
+        let args = json!({});
+        let op = make_op(HashMap::new());
+
+        let result = opt_str(&args, &op, "name").unwrap();
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_opt_str_null_returns_none() {
+        let args = json!({"name": null});
+        let mut map = HashMap::new();
+        map.insert("name".to_string(), "/name".to_string());
+        let op = make_op(map);
+
+        let result = opt_str(&args, &op, "name").unwrap();
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_opt_i64_success() {
+        let args = json!({"count": 42});
+        let mut map = HashMap::new();
+        map.insert("count".to_string(), "/count".to_string());
+        let op = make_op(map);
+
+        let result = opt_i64(&args, &op, "count").unwrap();
+        assert_eq!(result, Some(42));
+    }
+
+    #[test]
+    fn test_opt_i64_missing_returns_none() {
+        let args = json!({});
+        let op = make_op(HashMap::new());
+
+        let result = opt_i64(&args, &op, "count").unwrap();
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_opt_i64_null_returns_none() {
+        let args = json!({"count": null});
+        let mut map = HashMap::new();
+        map.insert("count".to_string(), "/count".to_string());
+        let op = make_op(map);
+
+        let result = opt_i64(&args, &op, "count").unwrap();
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_opt_bool_success() {
+        let args = json!({"active": true});
+        let mut map = HashMap::new();
+        map.insert("active".to_string(), "/active".to_string());
+        let op = make_op(map);
+
+        let result = opt_bool(&args, &op, "active").unwrap();
+        assert_eq!(result, Some(true));
+    }
+
+    #[test]
+    fn test_opt_bool_missing_returns_none() {
+        let args = json!({});
+        let op = make_op(HashMap::new());
+
+        let result = opt_bool(&args, &op, "active").unwrap();
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_ok_text_format() {
+        let result = ok_text(json!({"data": "test"}));
+        assert!(result.is_object());
+        assert!(result.get("content").is_some());
+        let content = result["content"].as_array().unwrap();
+        assert_eq!(content.len(), 1);
+        assert_eq!(content[0]["type"], "text");
+    }
+
+    #[test]
+    fn test_ok_text_contains_json() {
+        let data = json!({"key": "value"});
+        let result = ok_text(data);
+        let text = result["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("key"));
+        assert!(text.contains("value"));
+    }
+
+    #[test]
+    fn test_opt_vec_i64_success() {
+        let args = json!({"ids": [1, 2, 3]});
+        let mut map = HashMap::new();
+        map.insert("ids".to_string(), "/ids".to_string());
+        let op = make_op(map);
+
+        let result = opt_vec_i64(&args, &op, "ids").unwrap();
+        assert_eq!(result, Some(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn test_opt_vec_i64_missing_returns_none() {
+        let args = json!({});
+        let op = make_op(HashMap::new());
+
+        let result = opt_vec_i64(&args, &op, "ids").unwrap();
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_opt_vec_i64_null_returns_none() {
+        let args = json!({"ids": null});
+        let mut map = HashMap::new();
+        map.insert("ids".to_string(), "/ids".to_string());
+        let op = make_op(map);
+
+        let result = opt_vec_i64(&args, &op, "ids").unwrap();
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_opt_vec_i64_invalid_item_returns_error() {
+        let args = json!({"ids": [1, "two", 3]});
+        let mut map = HashMap::new();
+        map.insert("ids".to_string(), "/ids".to_string());
+        let op = make_op(map);
+
+        let result = opt_vec_i64(&args, &op, "ids");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_opt_vec_i64_empty_array() {
+        let args = json!({"ids": []});
+        let mut map = HashMap::new();
+        map.insert("ids".to_string(), "/ids".to_string());
+        let op = make_op(map);
+
+        let result = opt_vec_i64(&args, &op, "ids").unwrap();
+        assert_eq!(result, Some(vec![]));
+    }
+
+    #[test]
+    fn test_opt_vec_i64_single_element() {
+        let args = json!({"ids": [42]});
+        let mut map = HashMap::new();
+        map.insert("ids".to_string(), "/ids".to_string());
+        let op = make_op(map);
+
+        let result = opt_vec_i64(&args, &op, "ids").unwrap();
+        assert_eq!(result, Some(vec![42]));
+    }
+
+    #[test]
+    fn test_opt_vec_i64_large_numbers() {
+        let args = json!({"ids": [9223372036854775807i64, -9223372036854775808i64]});
+        let mut map = HashMap::new();
+        map.insert("ids".to_string(), "/ids".to_string());
+        let op = make_op(map);
+
+        let result = opt_vec_i64(&args, &op, "ids").unwrap();
+        assert_eq!(
+            result,
+            Some(vec![9223372036854775807i64, -9223372036854775808i64])
+        );
+    }
+
+    #[test]
+    fn test_opt_vec_i64_negative_numbers() {
+        let args = json!({"ids": [-1, -100, -999]});
+        let mut map = HashMap::new();
+        map.insert("ids".to_string(), "/ids".to_string());
+        let op = make_op(map);
+
+        let result = opt_vec_i64(&args, &op, "ids").unwrap();
+        assert_eq!(result, Some(vec![-1, -100, -999]));
+    }
+
+    #[test]
+    fn test_opt_vec_i64_mixed_signs() {
+        let args = json!({"ids": [-5, 0, 10, -100, 100]});
+        let mut map = HashMap::new();
+        map.insert("ids".to_string(), "/ids".to_string());
+        let op = make_op(map);
+
+        let result = opt_vec_i64(&args, &op, "ids").unwrap();
+        assert_eq!(result, Some(vec![-5, 0, 10, -100, 100]));
+    }
+
+    #[test]
+    fn test_opt_vec_i64_float_item_error() {
+        let args = json!({"ids": [1, 2.5, 3]});
+        let mut map = HashMap::new();
+        map.insert("ids".to_string(), "/ids".to_string());
+        let op = make_op(map);
+
+        let result = opt_vec_i64(&args, &op, "ids");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_opt_vec_i64_boolean_item_error() {
+        let args = json!({"ids": [1, true, 3]});
+        let mut map = HashMap::new();
+        map.insert("ids".to_string(), "/ids".to_string());
+        let op = make_op(map);
+
+        let result = opt_vec_i64(&args, &op, "ids");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_opt_vec_i64_nested_array_error() {
+        let args = json!({"ids": [1, [2, 3], 4]});
+        let mut map = HashMap::new();
+        map.insert("ids".to_string(), "/ids".to_string());
+        let op = make_op(map);
+
+        let result = opt_vec_i64(&args, &op, "ids");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_opt_vec_i64_object_item_error() {
+        let args = json!({"ids": [1, {"id": 2}, 3]});
+        let mut map = HashMap::new();
+        map.insert("ids".to_string(), "/ids".to_string());
+        let op = make_op(map);
+
+        let result = opt_vec_i64(&args, &op, "ids");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_opt_vec_i64_large_array() {
+        let ids: Vec<i64> = (0..1000).collect();
+        let args = json!({"ids": ids.clone()});
+        let mut map = HashMap::new();
+        map.insert("ids".to_string(), "/ids".to_string());
+        let op = make_op(map);
+
+        let result = opt_vec_i64(&args, &op, "ids").unwrap();
+        assert_eq!(result, Some(ids));
+    }
+
+    #[test]
+    fn test_opt_vec_string_success() {
+        let args = json!({"names": ["Alice", "Bob", "Charlie"]});
+        let mut map = HashMap::new();
+        map.insert("names".to_string(), "/names".to_string());
+        let op = make_op(map);
+
+        let result = opt_vec_string(&args, &op, "names").unwrap();
+        assert_eq!(
+            result,
+            Some(vec![
+                "Alice".to_string(),
+                "Bob".to_string(),
+                "Charlie".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_opt_vec_string_missing() {
+        let args = json!({});
+        let op = make_op(HashMap::new());
+
+        let result = opt_vec_string(&args, &op, "names").unwrap();
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_opt_vec_string_null() {
+        let args = json!({"names": null});
+        let mut map = HashMap::new();
+        map.insert("names".to_string(), "/names".to_string());
+        let op = make_op(map);
+
+        let result = opt_vec_string(&args, &op, "names").unwrap();
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_opt_vec_string_with_empty_string() {
+        let args = json!({"names": ["", "test", ""]});
+        let mut map = HashMap::new();
+        map.insert("names".to_string(), "/names".to_string());
+        let op = make_op(map);
+
+        let result = opt_vec_string(&args, &op, "names").unwrap();
+        assert_eq!(
+            result,
+            Some(vec!["".to_string(), "test".to_string(), "".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_opt_vec_string_with_unicode() {
+        let args = json!({"names": ["Alice", "Müller", "日本", "🚀"]});
+        let mut map = HashMap::new();
+        map.insert("names".to_string(), "/names".to_string());
+        let op = make_op(map);
+
+        let result = opt_vec_string(&args, &op, "names").unwrap();
+        assert_eq!(
+            result,
+            Some(vec![
+                "Alice".to_string(),
+                "Müller".to_string(),
+                "日本".to_string(),
+                "🚀".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_opt_vec_string_integer_item_error() {
+        let args = json!({"names": ["Alice", 123, "Bob"]});
+        let mut map = HashMap::new();
+        map.insert("names".to_string(), "/names".to_string());
+        let op = make_op(map);
+
+        let result = opt_vec_string(&args, &op, "names");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_ok_text_with_complex_json() {
+        let data = json!({
+            "records": [
+                {"id": 1, "name": "Test"},
+                {"id": 2, "name": "Another"}
+            ],
+            "count": 2
+        });
+        let result = ok_text(data);
+        assert!(result.is_object());
+        assert!(result.get("content").is_some());
+        let content = result["content"].as_array().unwrap();
+        assert_eq!(content.len(), 1);
+        assert_eq!(content[0]["type"], "text");
+        let text = content[0]["text"].as_str().unwrap();
+        assert!(text.contains("records"));
+        assert!(text.contains("Test"));
+    }
+
+    #[test]
+    fn test_ok_text_with_nested_json() {
+        let data = json!({
+            "nested": {
+                "deeply": {
+                    "value": "found"
+                }
+            }
+        });
+        let result = ok_text(data);
+        let text = result["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("deeply"));
+    }
+
+    #[test]
+    fn test_ok_text_with_array() {
+        let data = json!([1, 2, 3, 4, 5]);
+        let result = ok_text(data);
+        let text = result["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("1"));
+    }
+
+    #[test]
+    fn test_ok_text_with_string_value() {
+        let data = json!("test string");
+        let result = ok_text(data);
+        let text = result["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("test string"));
+    }
+
+    #[test]
+    fn test_ok_text_with_number() {
+        let data = json!(42);
+        let result = ok_text(data);
+        let text = result["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("42"));
+    }
+
+    #[test]
+    fn test_ok_text_with_boolean() {
+        let data = json!(true);
+        let result = ok_text(data);
+        let text = result["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("true"));
+    }
+
+    #[test]
+    fn test_ok_text_with_null() {
+        let data = json!(null);
+        let result = ok_text(data);
+        let text = result["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("null"));
+    }
+
+    #[test]
+    fn test_req_str_with_empty_string() {
+        let args = json!({"name": ""});
+        let mut map = HashMap::new();
+        map.insert("name".to_string(), "/name".to_string());
+        let op = make_op(map);
+
+        let result = req_str(&args, &op, "name").unwrap();
+        assert_eq!(result, "");
+    }
+
+    #[test]
+    fn test_opt_i64_zero() {
+        let args = json!({"count": 0});
+        let mut map = HashMap::new();
+        map.insert("count".to_string(), "/count".to_string());
+        let op = make_op(map);
+
+        let result = opt_i64(&args, &op, "count").unwrap();
+        assert_eq!(result, Some(0));
+    }
+
+    #[test]
+    fn test_opt_i64_negative() {
+        let args = json!({"count": -100});
+        let mut map = HashMap::new();
+        map.insert("count".to_string(), "/count".to_string());
+        let op = make_op(map);
+
+        let result = opt_i64(&args, &op, "count").unwrap();
+        assert_eq!(result, Some(-100));
+    }
+
+    #[test]
+    fn test_opt_bool_true() {
+        let args = json!({"active": true});
+        let mut map = HashMap::new();
+        map.insert("active".to_string(), "/active".to_string());
+        let op = make_op(map);
+
+        let result = opt_bool(&args, &op, "active").unwrap();
+        assert_eq!(result, Some(true));
+    }
+
+    #[test]
+    fn test_opt_bool_false() {
+        let args = json!({"active": false});
+        let mut map = HashMap::new();
+        map.insert("active".to_string(), "/active".to_string());
+        let op = make_op(map);
+
+        let result = opt_bool(&args, &op, "active").unwrap();
+        assert_eq!(result, Some(false));
+    }
+
+    #[test]
+    fn test_ptr_with_nested_path() {
+        let args = json!({
+            "data": {
+                "user": {
+                    "profile": {
+                        "name": "John"
+                    }
+                }
+            }
+        });
+        let mut map = HashMap::new();
+        map.insert("key".to_string(), "/data/user/profile/name".to_string());
+        let op = make_op(map);
+
+        let result = ptr(&args, &op, "key");
+        assert_eq!(result, Some(&json!("John")));
+    }
+
+    #[test]
+    fn test_ptr_with_array_index() {
+        let args = json!({
+            "items": ["a", "b", "c"]
+        });
+        let mut map = HashMap::new();
+        map.insert("key".to_string(), "/items/1".to_string());
+        let op = make_op(map);
+
+        let result = ptr(&args, &op, "key");
+        assert_eq!(result, Some(&json!("b")));
+    }
+
+    #[test]
+    fn test_deep_merge_values_recursively_preserves_defaults() {
+        let defaults = json!({
+            "limit": 20,
+            "context": {
+                "allowed_company_ids": [1],
+                "lang": "en_US"
+            },
+            "fields": ["name"]
+        });
+        let args = json!({
+            "context": {
+                "lang": "id_ID"
+            },
+            "fields": ["display_name"],
+            "offset": 5
+        });
+
+        let merged = deep_merge_values(defaults, args);
 
-    fn make_op(map: HashMap<String, String>) -> OpSpec {
-        OpSpec {
-            op_type: "test".to_string(),
-            map,
-        }
+        assert_eq!(merged["limit"], json!(20));
+        assert_eq!(merged["offset"], json!(5));
+        assert_eq!(merged["context"]["allowed_company_ids"], json!([1]));
+        assert_eq!(merged["context"]["lang"], json!("id_ID"));
+        assert_eq!(merged["fields"], json!(["display_name"]));
     }
 
     #[test]
-    fn test_resolve_instance_name_by_exact_canonical_name() {
-        let pool = make_pool(None);
+    fn test_apply_instance_tool_config_rejects_disabled_tool() {
+        let pool = make_pool(Some(InstanceToolConfig {
+            disabled_tools: vec!["odoo_create".to_string()],
+            allowed_tools: Vec::new(),
+            disabled_packs: Vec::new(),
+            defaults: HashMap::new(),
+            execute_allowlist: Vec::new(),
+        }));
 
-        let resolved = pool.resolve_instance_name("school-prod").unwrap();
+        let error = pool
+            .apply_instance_tool_config(
+                "school-prod",
+                &make_tool("odoo_create", "create"),
+                json!({ "instance": "school-prod" }),
+            )
+            .unwrap_err();
 
-        assert_eq!(resolved, "school-prod");
+        assert!(
+            error
+                .to_string()
+                .contains("Tool 'odoo_create' is disabled for instance 'school-prod'")
+        );
     }
 
     #[test]
-    fn test_resolve_instance_name_by_case_insensitive_canonical_name() {
-        let pool = make_pool(None);
+    fn test_apply_instance_tool_config_merges_defaults_with_call_precedence() {
+        let mut defaults = HashMap::new();
+        defaults.insert(
+            "odoo_search_read".to_string(),
+            json!({
+                "limit": 20,
+                "context": {
+                    "allowed_company_ids": [1],
+                    "lang": "en_US"
+                },
+                "fields": ["name"]
+            }),
+        );
 
-        let resolved = pool.resolve_instance_name("SCHOOL-PROD").unwrap();
+        let pool = make_pool(Some(InstanceToolConfig {
+            disabled_tools: Vec::new(),
+            allowed_tools: Vec::new(),
+            disabled_packs: Vec::new(),
+            defaults,
+            execute_allowlist: Vec::new(),
+        }));
 
-        assert_eq!(resolved, "school-prod");
+        let merged = pool
+            .apply_instance_tool_config(
+                "school-prod",
+                &make_tool("odoo_search_read", "search_read"),
+                json!({
+                    "instance": "school-prod",
+                    "context": {
+                        "lang": "id_ID"
+                    },
+                    "fields": ["display_name"],
+                    "offset": 5
+                }),
+            )
+            .unwrap();
+
+        assert_eq!(merged["instance"], json!("school-prod"));
+        assert_eq!(merged["limit"], json!(20));
+        assert_eq!(merged["offset"], json!(5));
+        assert_eq!(merged["context"]["allowed_company_ids"], json!([1]));
+        assert_eq!(merged["context"]["lang"], json!("id_ID"));
+        assert_eq!(merged["fields"], json!(["display_name"]));
     }
 
-    #[test]
-    fn test_resolve_instance_name_suggests_matching_canonical_name() {
+    #[tokio::test]
+    async fn degraded_instance_hides_write_tools_until_it_recovers() {
         let pool = make_pool(None);
+        {
+            let mut env = pool.env.write().unwrap();
+            let config = env.instances.get_mut("school-prod").unwrap();
+            config.url = "http://127.0.0.1:1".to_string();
+            config.max_retries = Some(0);
+        }
+        assert!(!pool.hides_write_tools(None));
 
-        let error = pool.resolve_instance_name("school prod").unwrap_err();
+        assert!(pool.probe_health().await);
+        assert!(pool.instance_is_degraded("school-prod"));
+        assert!(pool.hides_write_tools(Some("school-prod")));
+        assert!(pool.hides_write_tools(None));
+        // A second failure is not a new transition.
+        assert!(!pool.probe_health().await);
 
-        assert!(error.to_string().contains("Did you mean 'school-prod'?"));
+        assert!(pool.health.record("school-prod", Ok(())));
+        assert!(!pool.hides_write_tools(None));
     }
 
     #[test]
-    fn test_resolve_instance_name_rejects_legacy_alias_values() {
+    fn read_only_instance_rejects_mutating_tools() {
         let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .read_only = true;
 
-        let error = pool.resolve_instance_name("school").unwrap_err();
+        let error = pool
+            .apply_instance_tool_config(
+                "school-prod",
+                &make_tool("odoo_create", "create"),
+                json!({}),
+            )
+            .unwrap_err();
 
-        assert!(error.to_string().contains("Unknown Odoo instance 'school'"));
+        assert!(error.to_string().contains("read-only instance"));
+        assert!(pool.all_instances_read_only());
+        assert!(pool.instance_is_read_only("school-prod"));
     }
 
     #[test]
-    fn test_ptr_finds_value_by_json_pointer() {
-        let args = json!({
-            "data": {
-                "name": "test"
-            }
-        });
-        let mut map = HashMap::new();
-        map.insert("key".to_string(), "/data/name".to_string());
-        let op = make_op(map);
+    fn execute_is_denied_unless_model_and_method_are_allowlisted() {
+        let pool = make_pool(Some(InstanceToolConfig {
+            disabled_tools: Vec::new(),
+            allowed_tools: Vec::new(),
+            disabled_packs: Vec::new(),
+            defaults: HashMap::new(),
+            execute_allowlist: vec![crate::odoo::config::ExecuteAllowlistEntry {
+                model: "sale.order".to_string(),
+                methods: vec!["action_confirm".to_string()],
+            }],
+        }));
 
-        let result = ptr(&args, &op, "key");
-        assert_eq!(result, Some(&json!("test")));
+        assert!(pool.execute_allowed("school-prod", "sale.order", "action_confirm"));
+        assert!(!pool.execute_allowed("school-prod", "sale.order", "unlink"));
+        assert!(!pool.execute_allowed("school-prod", "res.partner", "action_confirm"));
     }
 
     #[test]
-    fn test_ptr_returns_none_for_missing_key() {
-        let args = json!({"data": "value"});
-        let op = make_op(HashMap::new());
+    fn execute_is_denied_when_allowlist_is_empty() {
+        let without_tool_config = make_pool(None);
+        assert!(!without_tool_config.execute_allowed(
+            "school-prod",
+            "sale.order",
+            "action_confirm"
+        ));
 
-        let result = ptr(&args, &op, "nonexistent");
-        assert!(result.is_none());
+        let empty_allowlist = make_pool(Some(InstanceToolConfig {
+            disabled_tools: Vec::new(),
+            allowed_tools: Vec::new(),
+            disabled_packs: Vec::new(),
+            defaults: HashMap::new(),
+            execute_allowlist: Vec::new(),
+        }));
+        assert!(!empty_allowlist.execute_allowed("school-prod", "sale.order", "action_confirm"));
     }
 
-    #[test]
-    fn test_ptr_returns_none_for_invalid_pointer() {
-        let args = json!({"data": "value"});
-        let mut map = HashMap::new();
-        map.insert("key".to_string(), "/nonexistent/path".to_string());
-        let op = make_op(map);
+    #[tokio::test]
+    async fn direct_call_denies_tool_with_missing_module() {
+        let pool = make_pool(None);
+        pool.module_snapshots
+            .success(
+                "school-prod",
+                Some("19".into()),
+                "community".into(),
+                BTreeSet::from(["base".into()]),
+            )
+            .await;
+        let mut tool = make_tool("stock_tool", "search");
+        tool.required_modules = vec!["stock".into()];
+        tool.op.map.insert("instance".into(), "/instance".into());
 
-        let result = ptr(&args, &op, "key");
-        assert!(result.is_none());
+        let error = call_tool(
+            &pool,
+            &tool,
+            json!({"instance": "school-prod", "model": "stock.quant"}),
+            &CallContext::default(),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(error.to_string().contains("missing_modules (stock)"));
     }
 
-    #[test]
-    fn test_req_str_success() {
-        let args = json!({"name": "test"});
-        let mut map = HashMap::new();
-        map.insert("name".to_string(), "/name".to_string());
-        let op = make_op(map);
+    #[tokio::test]
+    async fn session_instances_are_private_to_their_scope() {
+        use wiremock::matchers::{method, path_regex};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/ir\.model/search_count"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(42))
+            .mount(&server)
+            .await;
 
-        let result = req_str(&args, &op, "name").unwrap();
-        assert_eq!(result, "test");
-    }
+        let pool = make_pool(None);
+        let mut tool = make_tool("odoo_connect", "connect");
+        for key in ["name", "url", "apiKey", "db"] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let args = json!({"name": "acme", "url": server.uri(), "apiKey": "k", "db": "acme"});
 
-    #[test]
-    fn test_req_str_missing_returns_error() {
-        let args = json!({});
-        let op = make_op(HashMap::new());
+        let error = call_tool(&pool, &tool, args.clone(), &CallContext::default())
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("requires an MCP session"));
 
-        let result = req_str(&args, &op, "name");
-        assert!(result.is_err());
+        let session = pool.scoped("s1");
+        call_tool(&session, &tool, args.clone(), &CallContext::default())
+            .await
+            .unwrap();
+        assert!(session.instance_names().contains(&"acme".to_string()));
+        assert!(session.get("ACME").await.is_ok());
+        assert!(pool.scoped("s2").get("acme").await.is_err());
+        assert!(pool.get("acme").await.is_err());
+        assert_eq!(session.cache_key("acme"), "session:s1:acme");
+
+        let clash = json!({"name": "school-prod", "url": server.uri(), "apiKey": "k"});
+        let error = call_tool(&session, &tool, clash, &CallContext::default())
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("already used"));
+
+        pool.disconnect_session("s1");
+        assert!(session.get("acme").await.is_err());
     }
 
-    #[test]
-    fn test_req_str_non_string_returns_error() {
-        let args = json!({"name": 123});
-        let mut map = HashMap::new();
-        map.insert("name".to_string(), "/name".to_string());
-        let op = make_op(map);
+    #[tokio::test]
+    #[allow(clippy::await_holding_lock)] // The process environment requires serialized tests.
+    async fn destructive_calls_need_a_confirmation_token() {
+        use wiremock::matchers::{method, path_regex};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let _env_lock = crate::TEST_ENV_MUTEX.lock().unwrap();
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/res\.partner/read"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!([{"id": 7, "display_name": "Acme"}])),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/res\.partner/unlink"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(true))
+            .expect(1)
+            .mount(&server)
+            .await;
 
-        let result = req_str(&args, &op, "name");
-        assert!(result.is_err());
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
+        let session = pool.scoped("s1");
+        let mut tool = make_tool("odoo_delete", "unlink");
+        for key in ["instance", "model", "ids"] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let args = json!({"instance": "school-prod", "model": "res.partner", "ids": [7]});
+        let with_token = |args: &Value, token: &str| {
+            let mut args = args.clone();
+            args["confirmationToken"] = json!(token);
+            args
+        };
+        // SAFETY: serialized by TEST_ENV_MUTEX.
+        unsafe { std::env::set_var("ODOO_CONFIRM_DESTRUCTIVE", "true") };
+
+        let preview = call_tool(&session, &tool, args.clone(), &CallContext::default())
+            .await
+            .unwrap();
+        let preview: Value =
+            serde_json::from_str(preview["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(preview["confirmationRequired"], json!(true));
+        assert_eq!(preview["preview"]["records"][0]["display_name"], "Acme");
+        let token = preview["confirmationToken"].as_str().unwrap().to_string();
+
+        let other_ids = json!({"instance": "school-prod", "model": "res.partner", "ids": [8]});
+        for (pool, args) in [
+            (&session, with_token(&args, "forged")),
+            (&session, with_token(&other_ids, &token)),
+            (&pool.scoped("s2"), with_token(&args, &token)),
+        ] {
+            let error = call_tool(pool, &tool, args, &CallContext::default())
+                .await
+                .unwrap_err();
+            assert!(error.to_string().contains("Confirmation token"), "{error}");
+        }
+
+        let result = call_tool(
+            &session,
+            &tool,
+            with_token(&args, &token),
+            &CallContext::default(),
+        )
+        .await;
+        let reused = call_tool(
+            &session,
+            &tool,
+            with_token(&args, &token),
+            &CallContext::default(),
+        )
+        .await;
+        unsafe { std::env::remove_var("ODOO_CONFIRM_DESTRUCTIVE") };
+        assert!(
+            result.unwrap()["content"][0]["text"]
+                .as_str()
+                .unwrap()
+                .contains("\"deleted_count\": 1")
+        );
+        assert!(reused.is_err());
+    }
+
+    #[tokio::test]
+    async fn composite_steps_report_progress() {
+        use wiremock::matchers::{method, path_regex};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/[a-z.]+/search_count"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(3))
+            .mount(&server)
+            .await;
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
+        let mut tool = make_tool("partner_overview", "composite");
+        tool.op.map.insert("instance".into(), "/instance".into());
+        tool.op.steps = serde_json::from_value(json!([
+            {"id": "partners", "model": "res.partner", "method": "search_count"},
+            {"id": "orders", "model": "sale.order", "method": "search_count"}
+        ]))
+        .unwrap();
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let params = json!({"_meta": {"progressToken": 9}});
+        let ctx = crate::mcp::progress::with_sink(tx, async {
+            CallContext {
+                progress: Progress::for_request(&params, &Default::default(), ""),
+                ..CallContext::default()
+            }
+        })
+        .await;
+        call_tool(&pool, &tool, json!({"instance": "school-prod"}), &ctx)
+            .await
+            .unwrap();
+        drop(ctx);
+
+        let mut reports = Vec::new();
+        while let Some(message) = rx.recv().await {
+            assert_eq!(message["params"]["progressToken"], 9);
+            reports.push((
+                message["params"]["progress"].clone(),
+                message["params"]["total"].clone(),
+            ));
+        }
+        assert_eq!(reports, [(json!(1), json!(2)), (json!(2), json!(2))]);
     }
 
-    #[test]
-    fn test_opt_str_success() {
-        let args = json!({"name": "test"});
-        let mut map = HashMap::new();
-        map.insert("name".to_string(), "/name".to_string());
-        let op = make_op(map);
+    #[tokio::test]
+    async fn streamed_search_read_sends_batches_as_progress() {
+        use wiremock::matchers::{body_partial_json, method, path_regex};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/res\.partner/search_count"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(5))
+            .mount(&server)
+            .await;
+        for (offset, ids) in [(1, vec![2, 3]), (3, vec![4])] {
+            let records: Vec<Value> = ids.iter().map(|id| json!({"id": id})).collect();
+            Mock::given(method("POST"))
+                .and(path_regex(r"/json/2/res\.partner/search_read"))
+                .and(body_partial_json(json!({"offset": offset, "order": "id"})))
+                .respond_with(ResponseTemplate::new(200).set_body_json(records))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
+        let mut tool = make_tool("odoo_search_read", "search_read");
+        for key in [
+            "instance",
+            "model",
+            "offset",
+            "limit",
+            "stream",
+            "batchSize",
+        ] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let args = json!({
+            "instance": "school-prod",
+            "model": "res.partner",
+            "offset": 1,
+            "limit": 3,
+            "stream": true,
+            "batchSize": 2
+        });
 
-        let result = opt_str(&args, &op, "name").unwrap();
-        assert_eq!(result, Some("test".to_string()));
+        let error = call_tool(&pool, &tool, args.clone(), &CallContext::default())
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("progressToken"), "{error}");
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let params = json!({"_meta": {"progressToken": "rows"}});
+        let ctx = crate::mcp::progress::with_sink(tx, async {
+            CallContext {
+                progress: Progress::for_request(&params, &Default::default(), ""),
+                ..CallContext::default()
+            }
+        })
+        .await;
+        let result = call_tool(&pool, &tool, args, &ctx).await.unwrap();
+        drop(ctx);
+        let summary: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(summary["count"], 3);
+        assert_eq!(summary["batches"], 2);
+
+        let mut batches = Vec::new();
+        while let Some(message) = rx.recv().await {
+            let params = &message["params"];
+            assert_eq!(params["total"], 3);
+            batches.push((params["progress"].clone(), params["records"].clone()));
+        }
+        assert_eq!(
+            batches,
+            [
+                (json!(2), json!([{"id": 2}, {"id": 3}])),
+                (json!(3), json!([{"id": 4}]))
+            ]
+        );
     }
 
-    #[test]
-    fn test_opt_str_missing_returns_none() {
-        let args = json!({});
-        let op = make_op(HashMap::new());
-
-        let result = opt_str(&args, &op, "name").unwrap();
-        assert!(result.is_none());
-    }
+    #[tokio::test]
+    async fn token_scopes_gate_tool_calls() {
+        use crate::mcp::auth_tokens::{TokenGrant, TokenScope};
+        use std::collections::BTreeSet;
 
-    #[test]
-    fn test_opt_str_null_returns_none() {
-        let args = json!({"name": null});
-        let mut map = HashMap::new();
-        map.insert("name".to_string(), "/name".to_string());
-        let op = make_op(map);
+        let pool = make_pool(None);
+        let tool = make_tool("odoo_create", "create");
+        let ctx = CallContext {
+            grant: Some(TokenGrant {
+                name: "bi".into(),
+                scopes: BTreeSet::from([TokenScope::Read]),
+                tenant: None,
+            }),
+            ..CallContext::default()
+        };
+        let error = call_tool(&pool, &tool, json!({}), &ctx).await.unwrap_err();
+        assert!(error.to_string().contains("lacks the 'write' scope"));
 
-        let result = opt_str(&args, &op, "name").unwrap();
-        assert!(result.is_none());
+        let ctx = CallContext {
+            grant: Some(TokenGrant::admin("ops")),
+            ..CallContext::default()
+        };
+        let error = call_tool(&pool, &tool, json!({}), &ctx).await.unwrap_err();
+        assert!(!error.to_string().contains("scope"));
     }
 
-    #[test]
-    fn test_opt_i64_success() {
-        let args = json!({"count": 42});
-        let mut map = HashMap::new();
-        map.insert("count".to_string(), "/count".to_string());
-        let op = make_op(map);
-
-        let result = opt_i64(&args, &op, "count").unwrap();
-        assert_eq!(result, Some(42));
+    #[tokio::test]
+    async fn call_tool_rejects_args_violating_input_schema() {
+        let pool = make_pool(None);
+        let mut tool = make_tool("odoo_search", "search");
+        tool.input_schema = json!({
+            "type": "object",
+            "properties": {
+                "instance": {"type": "string"},
+                "model": {"type": "string"},
+                "limit": {"type": "integer"}
+            },
+            "required": ["instance", "model"],
+            "additionalProperties": false
+        });
+        let args = json!({"instance": "default", "limit": "10", "domian": []});
+        let error = call_tool(&pool, &tool, args, &CallContext::default())
+            .await
+            .unwrap_err();
+        let OdooError::InvalidArguments(violations) = error else {
+            panic!("expected InvalidArguments, got {error}");
+        };
+        assert_eq!(violations.len(), 3, "{violations:?}");
+        assert!(violations.iter().any(|v| v.starts_with("$.limit: ")));
     }
 
-    #[test]
-    fn test_opt_i64_missing_returns_none() {
-        let args = json!({});
-        let op = make_op(HashMap::new());
+    #[tokio::test]
+    async fn attachments_upload_and_download_as_base64_or_blob() {
+        use wiremock::matchers::{body_partial_json, method, path, path_regex};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/ir\.attachment/create"))
+            .and(body_partial_json(json!({"vals_list": [{
+                "name": "note.txt",
+                "datas": "aGVsbG8=",
+                "res_model": "res.partner",
+                "res_id": 7
+            }]})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([42])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/ir\.attachment/read"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+                "id": 42,
+                "name": "note.txt",
+                "mimetype": "text/plain",
+                "file_size": 5,
+                "res_model": "res.partner",
+                "res_id": 7
+            }])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/web/content/42"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"hello".to_vec()))
+            .mount(&server)
+            .await;
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
 
-        let result = opt_i64(&args, &op, "count").unwrap();
-        assert!(result.is_none());
-    }
+        let mut upload = make_tool("odoo_attachment_upload", "attachment_upload");
+        for key in ["instance", "name", "contentBase64", "resModel", "resId"] {
+            upload.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let args = json!({
+            "instance": "school-prod",
+            "name": "note.txt",
+            "contentBase64": "aGVsbG8=",
+            "resModel": "res.partner",
+            "resId": 7
+        });
+        let created = call_tool(&pool, &upload, args, &CallContext::default())
+            .await
+            .unwrap();
+        let created: Value =
+            serde_json::from_str(created["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(created["id"], 42);
+        assert_eq!(created["sizeBytes"], 5);
+        let bad = json!({
+            "instance": "school-prod",
+            "name": "x",
+            "contentBase64": "not base64!",
+            "resModel": "res.partner",
+            "resId": 7
+        });
+        let error = call_tool(&pool, &upload, bad, &CallContext::default())
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("base64"), "{error}");
 
-    #[test]
-    fn test_opt_i64_null_returns_none() {
-        let args = json!({"count": null});
-        let mut map = HashMap::new();
-        map.insert("count".to_string(), "/count".to_string());
-        let op = make_op(map);
+        let mut download = make_tool("odoo_attachment_download", "attachment_download");
+        for key in ["instance", "id", "format"] {
+            download.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let args = json!({"instance": "school-prod", "id": 42});
+        let result = call_tool(&pool, &download, args, &CallContext::default())
+            .await
+            .unwrap();
+        let text: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(text["contentBase64"], "aGVsbG8=");
+        assert_eq!(text["mimetype"], "text/plain");
 
-        let result = opt_i64(&args, &op, "count").unwrap();
-        assert!(result.is_none());
+        let args = json!({"instance": "school-prod", "id": 42, "format": "blob"});
+        let result = call_tool(&pool, &download, args, &CallContext::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            result["content"][1],
+            json!({
+                "type": "resource",
+                "resource": {
+                    "uri": "odoo://school-prod/record/ir.attachment/42",
+                    "mimeType": "text/plain",
+                    "blob": "aGVsbG8="
+                }
+            })
+        );
+        let text: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert!(text.get("contentBase64").is_none());
     }
 
-    #[test]
-    fn test_opt_bool_success() {
-        let args = json!({"active": true});
-        let mut map = HashMap::new();
-        map.insert("active".to_string(), "/active".to_string());
-        let op = make_op(map);
+    #[tokio::test]
+    async fn list_attachments_filters_by_record() {
+        use wiremock::matchers::{body_partial_json, method, path_regex};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/ir\.attachment/search_read"))
+            .and(body_partial_json(json!({
+                "domain": [["res_model", "=", "sale.order"], ["res_id", "=", 9]]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {
+                    "id": 3,
+                    "name": "quote.pdf",
+                    "mimetype": "application/pdf",
+                    "file_size": 1200,
+                    "checksum": "abc",
+                    "type": "binary",
+                    "url": false,
+                    "create_date": "2026-01-02 10:00:00"
+                },
+                {
+                    "id": 4,
+                    "name": "spec",
+                    "mimetype": false,
+                    "file_size": 0,
+                    "checksum": false,
+                    "type": "url",
+                    "url": "https://example.com/spec",
+                    "create_date": "2026-01-03 10:00:00"
+                }
+            ])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
+        let mut tool = make_tool("odoo_list_attachments", "list_attachments");
+        for key in ["instance", "model", "id"] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let args = json!({"instance": "school-prod", "model": "sale.order", "id": 9});
 
-        let result = opt_bool(&args, &op, "active").unwrap();
-        assert_eq!(result, Some(true));
+        let result = call_tool(&pool, &tool, args, &CallContext::default())
+            .await
+            .unwrap();
+        let listed: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(listed["count"], 2);
+        assert_eq!(
+            listed["attachments"][0]["url"],
+            "/web/content/3?download=true"
+        );
+        assert_eq!(listed["attachments"][0]["sizeBytes"], 1200);
+        assert_eq!(listed["attachments"][1]["url"], "https://example.com/spec");
     }
 
-    #[test]
-    fn test_opt_bool_missing_returns_none() {
-        let args = json!({});
-        let op = make_op(HashMap::new());
-
-        let result = opt_bool(&args, &op, "active").unwrap();
-        assert!(result.is_none());
-    }
+    #[tokio::test]
+    async fn message_post_defaults_to_an_internal_note() {
+        use wiremock::matchers::{body_partial_json, method, path_regex};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/sale\.order/message_post"))
+            .and(body_partial_json(json!({
+                "ids": [5],
+                "body": "Customer called about delivery",
+                "message_type": "comment",
+                "subtype_xmlid": "mail.mt_note",
+                "partner_ids": [3]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(88)))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
+        let mut tool = make_tool("odoo_message_post", "message_post");
+        for key in [
+            "instance",
+            "model",
+            "id",
+            "body",
+            "messageType",
+            "partnerIds",
+        ] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let args = json!({
+            "instance": "school-prod",
+            "model": "sale.order",
+            "id": 5,
+            "body": "Customer called about delivery",
+            "partnerIds": [3]
+        });
 
-    #[test]
-    fn test_ok_text_format() {
-        let result = ok_text(json!({"data": "test"}));
-        assert!(result.is_object());
-        assert!(result.get("content").is_some());
-        let content = result["content"].as_array().unwrap();
-        assert_eq!(content.len(), 1);
-        assert_eq!(content[0]["type"], "text");
-    }
+        let result = call_tool(&pool, &tool, args.clone(), &CallContext::default())
+            .await
+            .unwrap();
+        let posted: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(posted["messageId"], 88);
 
-    #[test]
-    fn test_ok_text_contains_json() {
-        let data = json!({"key": "value"});
-        let result = ok_text(data);
-        let text = result["content"][0]["text"].as_str().unwrap();
-        assert!(text.contains("key"));
-        assert!(text.contains("value"));
+        let mut bad = args;
+        bad["messageType"] = json!("email");
+        let error = call_tool(&pool, &tool, bad, &CallContext::default())
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("messageType"), "{error}");
     }
 
-    #[test]
-    fn test_opt_vec_i64_success() {
-        let args = json!({"ids": [1, 2, 3]});
-        let mut map = HashMap::new();
-        map.insert("ids".to_string(), "/ids".to_string());
-        let op = make_op(map);
+    #[tokio::test]
+    async fn render_email_template_uses_the_composer_on_recent_odoo() {
+        use wiremock::matchers::{body_partial_json, method, path_regex};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/mail\.template/read"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(
+                json!([{"id": 12, "model": "sale.order", "name": "Sales: Send Quotation"}]),
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/mail\.compose\.message/fields_get"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"res_ids": {"type": "text"}})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/mail\.compose\.message/create"))
+            .and(body_partial_json(json!({"vals_list": [{
+                "template_id": 12,
+                "model": "sale.order",
+                "res_ids": "[5]",
+                "composition_mode": "comment"
+            }]})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([77])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/mail\.compose\.message/read"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+                "id": 77,
+                "subject": "Quotation S00005",
+                "body": "<p>Hello Azure Interior</p>",
+                "email_from": false,
+                "partner_ids": [3]
+            }])))
+            .mount(&server)
+            .await;
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
+        let mut tool = make_tool("odoo_render_email_template", "render_email_template");
+        for key in ["instance", "templateId", "id"] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
 
-        let result = opt_vec_i64(&args, &op, "ids").unwrap();
-        assert_eq!(result, Some(vec![1, 2, 3]));
+        let result = call_tool(
+            &pool,
+            &tool,
+            json!({"instance": "school-prod", "templateId": 12, "id": 5}),
+            &CallContext::default(),
+        )
+        .await
+        .unwrap();
+        let rendered: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(rendered["subject"], "Quotation S00005");
+        assert_eq!(rendered["body"], "<p>Hello Azure Interior</p>");
+        assert_eq!(rendered["model"], "sale.order");
+        assert_eq!(rendered["partnerIds"], json!([3]));
     }
 
-    #[test]
-    fn test_opt_vec_i64_missing_returns_none() {
-        let args = json!({});
-        let op = make_op(HashMap::new());
+    #[tokio::test]
+    async fn export_records_flattens_relations_into_csv() {
+        use wiremock::matchers::{body_partial_json, method, path_regex};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/sale\.order/fields_get"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "name": {"type": "char", "string": "Order Reference"},
+                "partner_id": {"type": "many2one", "string": "Customer"},
+                "tag_ids": {"type": "many2many", "string": "Tags"},
+                "amount_total": {"type": "monetary", "string": "Total"}
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/sale\.order/search_read"))
+            .and(body_partial_json(
+                json!({"fields": ["name", "partner_id", "tag_ids", "amount_total"]}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {"id": 5, "name": "S00005", "partner_id": [7, "Azure Interior"], "tag_ids": [1, 4], "amount_total": 1250.5},
+                {"id": 6, "name": "S00006", "partner_id": false, "tag_ids": [], "amount_total": 0}
+            ])))
+            .mount(&server)
+            .await;
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
+        let mut tool = make_tool("odoo_export_records", "export_records");
+        for key in ["instance", "model", "fields", "labels", "format"] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let fields = json!(["name", "partner_id", "tag_ids", "amount_total"]);
+        let expected = "Order Reference,Customer,Tags,Total\r\n\
+                        S00005,Azure Interior,1;4,1250.5\r\n\
+                        S00006,,,0\r\n";
 
-        let result = opt_vec_i64(&args, &op, "ids").unwrap();
-        assert!(result.is_none());
-    }
+        let result = call_tool(
+            &pool,
+            &tool,
+            json!({"instance": "school-prod", "model": "sale.order", "fields": fields, "labels": true}),
+            &CallContext::default(),
+        )
+        .await
+        .unwrap();
+        let summary: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(summary["count"], 2);
+        assert_eq!(summary["csv"], expected);
 
-    #[test]
-    fn test_opt_vec_i64_null_returns_none() {
-        let args = json!({"ids": null});
-        let mut map = HashMap::new();
-        map.insert("ids".to_string(), "/ids".to_string());
-        let op = make_op(map);
+        let result = call_tool(
+            &pool,
+            &tool,
+            json!({"instance": "school-prod", "model": "sale.order", "fields": fields, "labels": true, "format": "resource"}),
+            &CallContext::default(),
+        )
+        .await
+        .unwrap();
+        let resource = &result["content"][1]["resource"];
+        assert_eq!(resource["mimeType"], "text/csv");
+        assert_eq!(resource["uri"], "odoo://school-prod/export/sale.order.csv");
+        assert_eq!(resource["text"], expected);
 
-        let result = opt_vec_i64(&args, &op, "ids").unwrap();
-        assert!(result.is_none());
+        let err = call_tool(
+            &pool,
+            &tool,
+            json!({"instance": "school-prod", "model": "sale.order", "fields": ["nope"]}),
+            &CallContext::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("Unknown field 'nope'"));
     }
 
-    #[test]
-    fn test_opt_vec_i64_invalid_item_returns_error() {
-        let args = json!({"ids": [1, "two", 3]});
-        let mut map = HashMap::new();
-        map.insert("ids".to_string(), "/ids".to_string());
-        let op = make_op(map);
+    #[tokio::test]
+    async fn debug_requests_filters_the_sessions_exchanges() {
+        use crate::mcp::inspector::RequestInspector;
+        use std::time::Duration;
+
+        let inspector = RequestInspector::new(10);
+        inspector.record(
+            "s1",
+            None,
+            Some(&json!(1)),
+            "tools/list",
+            None,
+            Ok(&json!({"tools": []})),
+            Duration::ZERO,
+        );
+        inspector.record(
+            "s1",
+            None,
+            Some(&json!(2)),
+            "tools/call",
+            Some(&json!({"name": "odoo_read"})),
+            Ok(&json!({"isError": true})),
+            Duration::ZERO,
+        );
+        inspector.record(
+            "s2",
+            None,
+            Some(&json!(1)),
+            "ping",
+            None,
+            Err("boom".into()),
+            Duration::ZERO,
+        );
+        let pool = make_pool(None);
+        let mut tool = make_tool("odoo_debug_requests", "debug_requests");
+        for key in ["limit", "errorsOnly", "method"] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let ctx = CallContext {
+            requests: inspector.for_scope("s1"),
+            ..CallContext::default()
+        };
 
-        let result = opt_vec_i64(&args, &op, "ids");
-        assert!(result.is_err());
-    }
+        let result = call_tool(&pool, &tool, json!({}), &ctx).await.unwrap();
+        let body: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(body["count"], 2);
+        assert_eq!(body["requests"][0]["id"], 2);
 
-    #[test]
-    fn test_opt_vec_i64_empty_array() {
-        let args = json!({"ids": []});
-        let mut map = HashMap::new();
-        map.insert("ids".to_string(), "/ids".to_string());
-        let op = make_op(map);
+        let result = call_tool(&pool, &tool, json!({"errorsOnly": true}), &ctx)
+            .await
+            .unwrap();
+        let body: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(body["count"], 1);
+        assert_eq!(body["requests"][0]["params"]["name"], "odoo_read");
 
-        let result = opt_vec_i64(&args, &op, "ids").unwrap();
-        assert_eq!(result, Some(vec![]));
+        let error = call_tool(&pool, &tool, json!({}), &CallContext::default())
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("MCP_INSPECTOR_REQUESTS"));
     }
 
-    #[test]
-    fn test_opt_vec_i64_single_element() {
-        let args = json!({"ids": [42]});
-        let mut map = HashMap::new();
-        map.insert("ids".to_string(), "/ids".to_string());
-        let op = make_op(map);
-
-        let result = opt_vec_i64(&args, &op, "ids").unwrap();
-        assert_eq!(result, Some(vec![42]));
-    }
+    #[tokio::test]
+    async fn import_records_loads_rows_or_validates_them_in_the_wizard() {
+        use wiremock::matchers::{body_partial_json, method, path_regex};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/res\.partner/load"))
+            .and(body_partial_json(json!({
+                "fields": ["id", "name", "parent_id/id"],
+                "data": [["__import__.acme", "Acme", ""], ["__import__.bob", "Bob", "__import__.nope"]]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "ids": false,
+                "messages": [{
+                    "type": "error",
+                    "message": "No matching record found for external id '__import__.nope' in field 'Related Company'",
+                    "record": 1,
+                    "rows": {"from": 1, "to": 1},
+                    "field": "parent_id"
+                }],
+                "nextrow": 0
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/base_import\.import/create"))
+            .and(body_partial_json(json!({"vals_list": [{
+                "res_model": "res.partner",
+                "file_type": "text/csv",
+                // "id,name,parent_id/id\r\n__import__.acme,Acme,\r\n"
+                "file": "aWQsbmFtZSxwYXJlbnRfaWQvaWQNCl9faW1wb3J0X18uYWNtZSxBY21lLA0K"
+            }]})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([4])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/base_import\.import/execute_import"))
+            .and(body_partial_json(json!({"ids": [4], "dryrun": true})))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"ids": [51], "messages": []})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
+        let mut tool = make_tool("odoo_import_records", "import_records");
+        for key in ["instance", "model", "fields", "rows", "dryRun"] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let fields = json!(["id", "name", "parent_id/id"]);
 
-    #[test]
-    fn test_opt_vec_i64_large_numbers() {
-        let args = json!({"ids": [9223372036854775807i64, -9223372036854775808i64]});
-        let mut map = HashMap::new();
-        map.insert("ids".to_string(), "/ids".to_string());
-        let op = make_op(map);
+        let result = call_tool(
+            &pool,
+            &tool,
+            json!({
+                "instance": "school-prod",
+                "model": "res.partner",
+                "fields": fields,
+                "rows": [["__import__.acme", "Acme", null], ["__import__.bob", "Bob", "__import__.nope"]]
+            }),
+            &CallContext::default(),
+        )
+        .await
+        .unwrap();
+        let body: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(body["success"], false);
+        assert_eq!(body["errors"], 1);
+        assert_eq!(body["ids"], json!([]));
+        assert_eq!(body["messages"][0]["record"], 1);
+
+        let result = call_tool(
+            &pool,
+            &tool,
+            json!({
+                "instance": "school-prod",
+                "model": "res.partner",
+                "fields": fields,
+                "rows": [["__import__.acme", "Acme", ""]],
+                "dryRun": true
+            }),
+            &CallContext::default(),
+        )
+        .await
+        .unwrap();
+        let body: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(body["success"], true);
+        assert_eq!(body["dryRun"], true);
 
-        let result = opt_vec_i64(&args, &op, "ids").unwrap();
-        assert_eq!(
-            result,
-            Some(vec![9223372036854775807i64, -9223372036854775808i64])
+        let error = call_tool(
+            &pool,
+            &tool,
+            json!({"instance": "school-prod", "model": "res.partner", "fields": fields, "rows": [["x"]]}),
+            &CallContext::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Row 0 must be an array of 3 cells")
         );
     }
 
-    #[test]
-    fn test_opt_vec_i64_negative_numbers() {
-        let args = json!({"ids": [-1, -100, -999]});
-        let mut map = HashMap::new();
-        map.insert("ids".to_string(), "/ids".to_string());
-        let op = make_op(map);
+    #[tokio::test]
+    async fn create_batch_creates_in_chunks_and_reports_failed_ones() {
+        use wiremock::matchers::{body_partial_json, method, path_regex};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/res\.partner/create"))
+            .and(body_partial_json(
+                json!({"vals_list": [{"name": "a"}, {"name": "b"}]}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([10, 11])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/res\.partner/create"))
+            .and(body_partial_json(
+                json!({"vals_list": [{"name": "c"}, {"email": "x"}]}),
+            ))
+            .respond_with(ResponseTemplate::new(422).set_body_json(json!({
+                "name": "odoo.exceptions.ValidationError",
+                "message": "The partner name is required"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/res\.partner/create"))
+            .and(body_partial_json(json!({"vals_list": [{"name": "e"}]})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([12])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
+        let mut tool = make_tool("odoo_create_batch", "create_batch");
+        for key in ["instance", "model", "values", "chunkSize"] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
 
-        let result = opt_vec_i64(&args, &op, "ids").unwrap();
-        assert_eq!(result, Some(vec![-1, -100, -999]));
+        let result = call_tool(
+            &pool,
+            &tool,
+            json!({
+                "instance": "school-prod",
+                "model": "res.partner",
+                "values": [{"name": "a"}, {"name": "b"}, {"name": "c"}, {"email": "x"}, {"name": "e"}],
+                "chunkSize": 2
+            }),
+            &CallContext::default(),
+        )
+        .await
+        .unwrap();
+        let body: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(body["success"], false);
+        assert_eq!(body["ids"], json!([10, 11, 12]));
+        assert_eq!(body["chunks"], 3);
+        let errors = body["errors"].as_array().unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0]["chunk"], 1);
+        assert_eq!(errors[0]["fromIndex"], 2);
+        assert_eq!(errors[0]["toIndex"], 3);
+        assert!(
+            errors[0]["error"]
+                .as_str()
+                .unwrap()
+                .contains("partner name is required")
+        );
     }
 
-    #[test]
-    fn test_opt_vec_i64_mixed_signs() {
-        let args = json!({"ids": [-5, 0, 10, -100, 100]});
-        let mut map = HashMap::new();
-        map.insert("ids".to_string(), "/ids".to_string());
-        let op = make_op(map);
-
-        let result = opt_vec_i64(&args, &op, "ids").unwrap();
-        assert_eq!(result, Some(vec![-5, 0, 10, -100, 100]));
-    }
+    #[tokio::test]
+    async fn update_batch_groups_identical_values_and_reports_per_record() {
+        use wiremock::matchers::{body_partial_json, method, path_regex};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/res\.partner/write"))
+            .and(body_partial_json(
+                json!({"ids": [1, 3], "vals": {"active": false}}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(true)))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/res\.partner/write"))
+            .and(body_partial_json(
+                json!({"ids": [2], "vals": {"name": "B"}}),
+            ))
+            .respond_with(ResponseTemplate::new(403).set_body_json(json!({
+                "name": "odoo.exceptions.AccessError",
+                "message": "You are not allowed to modify this partner"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
+        let mut tool = make_tool("odoo_update_batch", "update_batch");
+        for key in ["instance", "model", "updates"] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
 
-    #[test]
-    fn test_opt_vec_i64_float_item_error() {
-        let args = json!({"ids": [1, 2.5, 3]});
-        let mut map = HashMap::new();
-        map.insert("ids".to_string(), "/ids".to_string());
-        let op = make_op(map);
+        let result = call_tool(
+            &pool,
+            &tool,
+            json!({
+                "instance": "school-prod",
+                "model": "res.partner",
+                "updates": [
+                    {"id": 1, "values": {"active": false}},
+                    {"id": 2, "values": {"name": "B"}},
+                    {"id": 3, "values": {"active": false}}
+                ]
+            }),
+            &CallContext::default(),
+        )
+        .await
+        .unwrap();
+        let body: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(body["success"], false);
+        assert_eq!(body["updated"], 2);
+        assert_eq!(body["failed"], 1);
+        assert_eq!(body["writeCalls"], 2);
+        let results = body["results"].as_array().unwrap();
+        assert_eq!(results[0], json!({"id": 1, "success": true}));
+        assert_eq!(results[1]["success"], false);
+        assert!(
+            results[1]["error"]
+                .as_str()
+                .unwrap()
+                .contains("not allowed to modify")
+        );
 
-        let result = opt_vec_i64(&args, &op, "ids");
-        assert!(result.is_err());
+        let error = call_tool(
+            &pool,
+            &tool,
+            json!({
+                "instance": "school-prod",
+                "model": "res.partner",
+                "updates": [{"id": 1, "values": {}}, {"id": 1, "values": {"name": "A"}}]
+            }),
+            &CallContext::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(error.to_string().contains("updated more than once"));
     }
 
-    #[test]
-    fn test_opt_vec_i64_boolean_item_error() {
-        let args = json!({"ids": [1, true, 3]});
-        let mut map = HashMap::new();
-        map.insert("ids".to_string(), "/ids".to_string());
-        let op = make_op(map);
+    #[tokio::test]
+    async fn upsert_updates_a_single_match_or_creates() {
+        use wiremock::matchers::{body_partial_json, method, path_regex};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/res\.partner/search"))
+            .and(body_partial_json(json!({
+                "domain": [["is_company", "=", true], ["ref", "=", "C-1"]],
+                "limit": 2
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([7])))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/res\.partner/search"))
+            .and(body_partial_json(json!({"domain": [["ref", "=", "C-2"]]})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/res\.partner/search"))
+            .and(body_partial_json(json!({"domain": [["ref", "=", "dup"]]})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([3, 4])))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/res\.partner/write"))
+            .and(body_partial_json(json!({"ids": [7]})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(true)))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/res\.partner/create"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([15])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
+        let mut tool = make_tool("odoo_upsert", "upsert");
+        for key in ["instance", "model", "values", "domain", "matchFields"] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let ctx = CallContext::default();
+        let upsert = |args: Value| call_tool(&pool, &tool, args, &ctx);
+        let body = |result: Value| -> Value {
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap()
+        };
 
-        let result = opt_vec_i64(&args, &op, "ids");
-        assert!(result.is_err());
-    }
+        let updated = upsert(json!({
+            "instance": "school-prod",
+            "model": "res.partner",
+            "domain": [["is_company", "=", true]],
+            "matchFields": ["ref"],
+            "values": {"ref": "C-1", "name": "Acme"}
+        }))
+        .await
+        .unwrap();
+        assert_eq!(
+            body(updated),
+            json!({"success": true, "action": "updated", "id": 7})
+        );
 
-    #[test]
-    fn test_opt_vec_i64_nested_array_error() {
-        let args = json!({"ids": [1, [2, 3], 4]});
-        let mut map = HashMap::new();
-        map.insert("ids".to_string(), "/ids".to_string());
-        let op = make_op(map);
+        let created = upsert(json!({
+            "instance": "school-prod",
+            "model": "res.partner",
+            "matchFields": ["ref"],
+            "values": {"ref": "C-2", "name": "Globex"}
+        }))
+        .await
+        .unwrap();
+        assert_eq!(
+            body(created),
+            json!({"success": true, "action": "created", "id": 15})
+        );
 
-        let result = opt_vec_i64(&args, &op, "ids");
-        assert!(result.is_err());
+        let ambiguous = upsert(json!({
+            "instance": "school-prod",
+            "model": "res.partner",
+            "matchFields": ["ref"],
+            "values": {"ref": "dup"}
+        }))
+        .await
+        .unwrap_err();
+        assert!(
+            ambiguous
+                .to_string()
+                .contains("Several res.partner records")
+        );
+        let unmatched = upsert(json!({
+            "instance": "school-prod",
+            "model": "res.partner",
+            "matchFields": ["email"],
+            "values": {"name": "x"}
+        }))
+        .await
+        .unwrap_err();
+        assert!(unmatched.to_string().contains("Match field 'email'"));
     }
 
-    #[test]
-    fn test_opt_vec_i64_object_item_error() {
-        let args = json!({"ids": [1, {"id": 2}, 3]});
-        let mut map = HashMap::new();
-        map.insert("ids".to_string(), "/ids".to_string());
-        let op = make_op(map);
+    #[tokio::test]
+    async fn xmlid_tools_resolve_and_assign_external_ids() {
+        use wiremock::matchers::{body_partial_json, method, path_regex};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/ir\.model\.data/search_read"))
+            .and(body_partial_json(json!({"domain": [
+                "|",
+                "&", ["module", "=", "base"], ["name", "=", "main_company"],
+                "&", ["module", "=", "base"], ["name", "=", "nope"]
+            ]})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+                "module": "base", "name": "main_company", "model": "res.company",
+                "res_id": 1, "noupdate": true
+            }])))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/ir\.model\.data/search_read"))
+            .and(body_partial_json(json!({"domain": [
+                "&", ["module", "=", "__agent__"], ["name", "=", "acme"]
+            ]})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/ir\.model\.data/search_read"))
+            .and(body_partial_json(json!({"domain": [
+                "&", ["module", "=", "base"], ["name", "=", "main_partner"]
+            ]})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+                "module": "base", "name": "main_partner", "model": "res.partner",
+                "res_id": 1, "noupdate": true
+            }])))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/res\.partner/search"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([42])))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/ir\.model\.data/create"))
+            .and(body_partial_json(json!({"vals_list": [{
+                "module": "__agent__", "name": "acme", "model": "res.partner",
+                "res_id": 42, "noupdate": true
+            }]})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([900])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
+        let mut lookup = make_tool("odoo_xmlid_lookup", "xmlid_lookup");
+        let mut assign = make_tool("odoo_xmlid_assign", "xmlid_assign");
+        for key in ["instance", "xmlids"] {
+            lookup.op.map.insert(key.into(), format!("/{key}"));
+        }
+        for key in ["instance", "model", "id", "xmlid"] {
+            assign.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let ctx = CallContext::default();
+        let body = |result: Value| -> Value {
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap()
+        };
 
-        let result = opt_vec_i64(&args, &op, "ids");
-        assert!(result.is_err());
-    }
+        let found = call_tool(
+            &pool,
+            &lookup,
+            json!({"instance": "school-prod", "xmlids": ["base.main_company", "base.nope"]}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            body(found),
+            json!({
+                "records": [{"xmlid": "base.main_company", "model": "res.company", "id": 1, "noupdate": true}],
+                "missing": ["base.nope"]
+            })
+        );
 
-    #[test]
-    fn test_opt_vec_i64_large_array() {
-        let ids: Vec<i64> = (0..1000).collect();
-        let args = json!({"ids": ids.clone()});
-        let mut map = HashMap::new();
-        map.insert("ids".to_string(), "/ids".to_string());
-        let op = make_op(map);
+        let assigned = call_tool(
+            &pool,
+            &assign,
+            json!({"instance": "school-prod", "model": "res.partner", "id": 42, "xmlid": "__agent__.acme"}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(body(assigned)["created"], true);
 
-        let result = opt_vec_i64(&args, &op, "ids").unwrap();
-        assert_eq!(result, Some(ids));
+        let taken = call_tool(
+            &pool,
+            &assign,
+            json!({"instance": "school-prod", "model": "res.partner", "id": 42, "xmlid": "base.main_partner"}),
+            &ctx,
+        )
+        .await
+        .unwrap_err();
+        assert!(taken.to_string().contains("already names res.partner(1)"));
+        let malformed = call_tool(
+            &pool,
+            &lookup,
+            json!({"instance": "school-prod", "xmlids": ["main_company"]}),
+            &ctx,
+        )
+        .await
+        .unwrap_err();
+        assert!(malformed.to_string().contains("module.name"));
     }
 
-    #[test]
-    fn test_opt_vec_string_success() {
-        let args = json!({"names": ["Alice", "Bob", "Charlie"]});
-        let mut map = HashMap::new();
-        map.insert("names".to_string(), "/names".to_string());
-        let op = make_op(map);
+    #[tokio::test]
+    async fn list_models_filters_by_keyword_and_reports_modules() {
+        use wiremock::matchers::{body_partial_json, method, path_regex};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/ir\.model/search_read"))
+            .and(body_partial_json(json!({
+                "domain": [
+                    ["transient", "=", false],
+                    "|", ["model", "ilike", "sale"], ["name", "ilike", "sale"]
+                ],
+                "fields": ["model", "name", "transient", "modules"],
+                "order": "model"
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+                "id": 1, "model": "sale.order", "name": "Sales Order",
+                "transient": false, "modules": "sale, sale_management"
+            }])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/ir\.model/search_read"))
+            .and(body_partial_json(
+                json!({"domain": [["transient", "=", true]]}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+                "id": 2, "model": "sale.advance.payment.inv", "name": "Down Payment",
+                "transient": true, "modules": false
+            }])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
+        let mut tool = make_tool("odoo_list_models", "list_models");
+        for key in ["instance", "domain", "keyword"] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let ctx = CallContext::default();
+        let body = |result: Value| -> Value {
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap()
+        };
 
-        let result = opt_vec_string(&args, &op, "names").unwrap();
+        let sales = call_tool(
+            &pool,
+            &tool,
+            json!({"instance": "school-prod", "keyword": " sale "}),
+            &ctx,
+        )
+        .await
+        .unwrap();
         assert_eq!(
-            result,
-            Some(vec![
-                "Alice".to_string(),
-                "Bob".to_string(),
-                "Charlie".to_string()
-            ])
+            body(sales),
+            json!({"count": 1, "models": [{
+                "model": "sale.order", "name": "Sales Order",
+                "transient": false, "modules": ["sale", "sale_management"]
+            }]})
         );
+
+        // An explicit transient filter replaces the default one.
+        let wizards = call_tool(
+            &pool,
+            &tool,
+            json!({"instance": "school-prod", "domain": [["transient", "=", true]]}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(body(wizards)["models"][0]["modules"], json!([]));
     }
 
-    #[test]
-    fn test_opt_vec_string_missing() {
-        let args = json!({});
-        let op = make_op(HashMap::new());
+    #[tokio::test]
+    async fn user_tools_refuse_write_tokens() {
+        use crate::mcp::auth_tokens::{TokenGrant, TokenScope};
+        use std::collections::BTreeSet;
 
-        let result = opt_vec_string(&args, &op, "names").unwrap();
-        assert!(result.is_none());
+        let pool = make_pool(None);
+        let tool = make_tool("odoo_user_groups", "user_groups");
+        let ctx = CallContext {
+            grant: Some(TokenGrant {
+                name: "etl".into(),
+                scopes: BTreeSet::from([TokenScope::Read, TokenScope::Write]),
+                tenant: None,
+            }),
+            ..CallContext::default()
+        };
+        let args = json!({
+            "instance": "school-prod",
+            "login": "marc",
+            "add": ["base.group_system"],
+            "confirm": true
+        });
+        let error = call_tool(&pool, &tool, args, &ctx).await.unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("lacks the 'admin' scope required by tool 'odoo_user_groups'")
+        );
     }
 
-    #[test]
-    fn test_opt_vec_string_null() {
-        let args = json!({"names": null});
-        let mut map = HashMap::new();
-        map.insert("names".to_string(), "/names".to_string());
-        let op = make_op(map);
+    #[tokio::test]
+    async fn server_info_needs_the_admin_scope_and_hides_credentials() {
+        use crate::mcp::auth_tokens::{TokenGrant, TokenScope};
+        use std::collections::BTreeSet;
 
-        let result = opt_vec_string(&args, &op, "names").unwrap();
-        assert!(result.is_none());
+        let pool = make_pool(None);
+        let tool = make_tool("odoo_server_info", "server_info");
+        let server = ServerInfo::new(5);
+        server.note_transport("stdio");
+
+        let ctx = CallContext {
+            grant: Some(TokenGrant {
+                name: "bi".into(),
+                scopes: BTreeSet::from([TokenScope::Read, TokenScope::Write]),
+                tenant: None,
+            }),
+            server: Some(server.clone()),
+            ..CallContext::default()
+        };
+        let error = call_tool(&pool, &tool, json!({}), &ctx).await.unwrap_err();
+        assert!(error.to_string().contains("lacks the 'admin' scope"));
+
+        let ctx = CallContext {
+            grant: Some(TokenGrant::admin("ops")),
+            server: Some(server),
+            ..CallContext::default()
+        };
+        let result = call_tool(&pool, &tool, json!({}), &ctx).await.unwrap();
+        let text = result["content"][0]["text"].as_str().unwrap();
+        let body: Value = serde_json::from_str(text).unwrap();
+        assert_eq!(body["transports"], json!(["stdio"]));
+        assert_eq!(body["inspectorRequests"], 5);
+        assert_eq!(body["instances"][0]["name"], "school-prod");
+        assert_eq!(body["instances"][0]["db"], "school");
+        assert!(!text.contains("secret"));
     }
 
-    #[test]
-    fn test_opt_vec_string_with_empty_string() {
-        let args = json!({"names": ["", "test", ""]});
-        let mut map = HashMap::new();
-        map.insert("names".to_string(), "/names".to_string());
-        let op = make_op(map);
+    #[tokio::test]
+    async fn view_definition_reports_required_and_invisible_fields() {
+        use wiremock::matchers::{body_partial_json, method, path_regex};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/sale\.order/get_views"))
+            .and(body_partial_json(json!({"views": [[false, "form"]]})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "views": {"form": {
+                    "id": 812,
+                    "model": "sale.order",
+                    "arch": "<form><field name=\"partner_id\" required=\"1\"/>\
+                        <field name=\"validity_date\" invisible=\"state != 'draft'\"/>\
+                        <field name=\"order_line\"><list><field name=\"name\"/></list></field>\
+                        <field name=\"company_id\" invisible=\"1\"/></form>"
+                }},
+                "models": {"sale.order": {"fields": {
+                    "partner_id": {"string": "Customer", "type": "many2one", "relation": "res.partner"},
+                    "validity_date": {"string": "Expiration", "type": "date"},
+                    "order_line": {"string": "Order Lines", "type": "one2many", "relation": "sale.order.line"},
+                    "company_id": {"string": "Company", "type": "many2one", "required": true}
+                }}}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
+        let mut tool = make_tool("odoo_view_definition", "view_definition");
+        for key in ["instance", "model", "viewTypes", "viewId", "includeArch"] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let ctx = CallContext::default();
 
-        let result = opt_vec_string(&args, &op, "names").unwrap();
-        assert_eq!(
-            result,
-            Some(vec!["".to_string(), "test".to_string(), "".to_string()])
-        );
+        let result = call_tool(
+            &pool,
+            &tool,
+            json!({
+                "instance": "school-prod",
+                "model": "sale.order",
+                "viewTypes": ["form"],
+                "includeArch": false
+            }),
+            &ctx,
+        )
+        .await
+        .unwrap();
+        let body: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        let view = &body["views"][0];
+        assert_eq!(view["type"], "form");
+        assert_eq!(view["viewId"], 812);
+        assert!(view.get("arch").is_none());
+        assert_eq!(view["requiredFields"], json!(["partner_id"]));
+        let fields = view["fields"].as_array().unwrap();
+        assert_eq!(fields.len(), 4);
+        assert_eq!(fields[0]["string"], "Customer");
+        assert_eq!(fields[1]["invisible"], "state != 'draft'");
+        assert_eq!(fields[3]["required"], true);
+        assert_eq!(fields[3]["invisible"], true);
+
+        let error = call_tool(
+            &pool,
+            &tool,
+            json!({"instance": "school-prod", "model": "sale.order", "viewTypes": ["gantt"]}),
+            &ctx,
+        )
+        .await
+        .unwrap_err();
+        assert!(error.to_string().contains("Unknown view type 'gantt'"));
+        let error = call_tool(
+            &pool,
+            &tool,
+            json!({"instance": "school-prod", "model": "sale.order", "viewId": 5}),
+            &ctx,
+        )
+        .await
+        .unwrap_err();
+        assert!(error.to_string().contains("exactly one"));
     }
 
-    #[test]
-    fn test_opt_vec_string_with_unicode() {
-        let args = json!({"names": ["Alice", "Müller", "日本", "🚀"]});
-        let mut map = HashMap::new();
-        map.insert("names".to_string(), "/names".to_string());
-        let op = make_op(map);
+    #[tokio::test]
+    async fn list_reports_filters_by_model_and_keyword() {
+        use wiremock::matchers::{body_partial_json, method, path_regex};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/ir\.actions\.report/search_read"))
+            .and(body_partial_json(json!({
+                "domain": [
+                    ["model", "=", "sale.order"],
+                    "|", ["name", "ilike", "quot"], ["report_name", "ilike", "quot"]
+                ],
+                "order": "model, name"
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {
+                    "id": 301, "name": "Quotation / Order", "report_name": "sale.report_saleorder",
+                    "model": "sale.order", "report_type": "qweb-pdf", "binding_model_id": [412, "Sales Order"]
+                },
+                {
+                    "id": 302, "name": "Quotation (raw)", "report_name": "sale.report_saleorder_raw",
+                    "model": "sale.order", "report_type": "qweb-html", "binding_model_id": false
+                }
+            ])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
+        let mut tool = make_tool("odoo_list_reports", "list_reports");
+        for key in ["instance", "model", "keyword", "reportType"] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let ctx = CallContext::default();
 
-        let result = opt_vec_string(&args, &op, "names").unwrap();
+        let result = call_tool(
+            &pool,
+            &tool,
+            json!({"instance": "school-prod", "model": "sale.order", "keyword": "quot"}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+        let body: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(body["count"], 2);
         assert_eq!(
-            result,
-            Some(vec![
-                "Alice".to_string(),
-                "Müller".to_string(),
-                "日本".to_string(),
-                "🚀".to_string()
-            ])
+            body["reports"][0],
+            json!({
+                "id": 301, "name": "Quotation / Order", "reportName": "sale.report_saleorder",
+                "model": "sale.order", "reportType": "qweb-pdf", "printMenu": true
+            })
         );
+        assert_eq!(body["reports"][1]["printMenu"], false);
     }
 
-    #[test]
-    fn test_opt_vec_string_integer_item_error() {
-        let args = json!({"names": ["Alice", 123, "Bob"]});
-        let mut map = HashMap::new();
-        map.insert("names".to_string(), "/names".to_string());
-        let op = make_op(map);
-
-        let result = opt_vec_string(&args, &op, "names");
-        assert!(result.is_err());
-    }
+    #[tokio::test]
+    async fn generate_report_downloads_the_requested_format() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/report/html/sale.report_saleorder/7,8"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_raw("<h2>Quotation S00007</h2>", "text/html; charset=utf-8"),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/report/xlsx/account.report_aged/3"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"PK\x03\x04".to_vec()))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
+        let mut tool = make_tool("odoo_generate_report", "generate_report");
+        for key in ["instance", "reportName", "ids", "reportType"] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let ctx = CallContext::default();
+        let call = |args: Value| {
+            let (pool, tool, ctx) = (&pool, &tool, &ctx);
+            async move {
+                let result = call_tool(pool, tool, args, ctx).await?;
+                Ok::<Value, OdooError>(
+                    serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap(),
+                )
+            }
+        };
 
-    #[test]
-    fn test_ok_text_with_complex_json() {
-        let data = json!({
-            "records": [
-                {"id": 1, "name": "Test"},
-                {"id": 2, "name": "Another"}
-            ],
-            "count": 2
-        });
-        let result = ok_text(data);
-        assert!(result.is_object());
-        assert!(result.get("content").is_some());
-        let content = result["content"].as_array().unwrap();
-        assert_eq!(content.len(), 1);
-        assert_eq!(content[0]["type"], "text");
-        let text = content[0]["text"].as_str().unwrap();
-        assert!(text.contains("records"));
-        assert!(text.contains("Test"));
-    }
+        let html = call(json!({
+            "instance": "school-prod", "reportName": "sale.report_saleorder",
+            "ids": [7, 8], "reportType": "qweb-html"
+        }))
+        .await
+        .unwrap();
+        assert_eq!(html["content"], "<h2>Quotation S00007</h2>");
+        assert_eq!(html["report_type"], "qweb-html");
+        assert_eq!(html["content_type"], "text/html; charset=utf-8");
+        assert!(html.get("pdf_base64").is_none());
+
+        let xlsx = call(json!({
+            "instance": "school-prod", "reportName": "account.report_aged",
+            "ids": [3], "reportType": "xlsx"
+        }))
+        .await
+        .unwrap();
+        assert_eq!(xlsx["xlsx_base64"], "UEsDBA==");
 
-    #[test]
-    fn test_ok_text_with_nested_json() {
-        let data = json!({
-            "nested": {
-                "deeply": {
-                    "value": "found"
-                }
-            }
-        });
-        let result = ok_text(data);
-        let text = result["content"][0]["text"].as_str().unwrap();
-        assert!(text.contains("deeply"));
+        let err = call(json!({
+            "instance": "school-prod", "reportName": "account.report_aged",
+            "ids": [3], "reportType": "docx"
+        }))
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("Unsupported reportType 'docx'"));
     }
 
-    #[test]
-    fn test_ok_text_with_array() {
-        let data = json!([1, 2, 3, 4, 5]);
-        let result = ok_text(data);
-        let text = result["content"][0]["text"].as_str().unwrap();
-        assert!(text.contains("1"));
-    }
+    #[tokio::test]
+    async fn access_check_reports_denials_with_their_reason() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/sale.order/check_access"))
+            .and(body_partial_json(json!({"ids": [5], "operation": "write"})))
+            .respond_with(ResponseTemplate::new(403).set_body_json(json!({
+                "name": "odoo.exceptions.AccessError",
+                "message": "Uh-oh! Looks like you have stumbled upon some top-secret records.",
+                "arguments": [],
+                "context": {},
+                "debug": ""
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/sale.order/check_access"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(Value::Null))
+            .mount(&server)
+            .await;
+        // Without a configured version, servers lacking check_access get the older pair.
+        Mock::given(method("POST"))
+            .and(path("/json/2/res.partner/check_access"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(json!({
+                "name": "werkzeug.exceptions.NotFound",
+                "message": "Method not found",
+                "arguments": [],
+                "context": {},
+                "debug": ""
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/res.partner/check_access_rights"))
+            .and(body_partial_json(json!({"operation": "unlink"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(false)))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/res.partner/check_access_rights"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(true)))
+            .mount(&server)
+            .await;
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
+        let mut tool = make_tool("odoo_access_check", "access_check");
+        for key in ["instance", "model", "operations", "ids"] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let ctx = CallContext::default();
 
-    #[test]
-    fn test_ok_text_with_string_value() {
-        let data = json!("test string");
-        let result = ok_text(data);
-        let text = result["content"][0]["text"].as_str().unwrap();
-        assert!(text.contains("test string"));
-    }
+        let result = call_tool(
+            &pool,
+            &tool,
+            json!({"instance": "school-prod", "model": "sale.order", "operations": ["read", "write"], "ids": [5]}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+        let body: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(body["allowed"], false);
+        assert_eq!(
+            body["operations"][0],
+            json!({"operation": "read", "allowed": true})
+        );
+        assert_eq!(body["operations"][1]["allowed"], false);
+        assert!(
+            body["operations"][1]["reason"]
+                .as_str()
+                .unwrap()
+                .contains("top-secret records")
+        );
 
-    #[test]
-    fn test_ok_text_with_number() {
-        let data = json!(42);
-        let result = ok_text(data);
-        let text = result["content"][0]["text"].as_str().unwrap();
-        assert!(text.contains("42"));
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .version = None;
+        let result = call_tool(
+            &pool,
+            &tool,
+            json!({"instance": "school-prod", "model": "res.partner", "operations": ["create", "unlink"]}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+        let body: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(body["operations"][0]["allowed"], true);
+        assert_eq!(body["operations"][1]["allowed"], false);
+        assert_eq!(
+            body["operations"][1]["reason"],
+            "No access rights to unlink on res.partner (ir.model.access)"
+        );
     }
 
-    #[test]
-    fn test_ok_text_with_boolean() {
-        let data = json!(true);
-        let result = ok_text(data);
-        let text = result["content"][0]["text"].as_str().unwrap();
-        assert!(text.contains("true"));
-    }
+    #[tokio::test]
+    async fn snapshot_pins_session_reads_and_refuses_writes() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/sale.order/fields_get"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "name": {"type": "char"},
+                "write_date": {"type": "datetime"}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/sale.order/search_count"))
+            .and(body_partial_json(json!({
+                "domain": [["state", "=", "sale"], ["write_date", "<=", "2026-03-01 08:00:00"]]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(12)))
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/sale.order/search_count"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(15)))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
+        let session = pool.scoped("s1");
+        let mut snapshot_tool = make_tool("odoo_snapshot", "snapshot_session");
+        for key in ["action", "at", "minutes"] {
+            snapshot_tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let mut count_tool = make_tool("odoo_count", "search_count");
+        for key in ["instance", "model", "domain"] {
+            count_tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let mut write_tool = make_tool("odoo_update", "write");
+        for key in ["instance", "model", "ids", "values"] {
+            write_tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let ctx = CallContext::default();
+        let count_args = json!({
+            "instance": "school-prod", "model": "sale.order", "domain": [["state", "=", "sale"]]
+        });
+        let text = |result: Value| -> Value {
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap()
+        };
 
-    #[test]
-    fn test_ok_text_with_null() {
-        let data = json!(null);
-        let result = ok_text(data);
-        let text = result["content"][0]["text"].as_str().unwrap();
-        assert!(text.contains("null"));
-    }
+        let started = call_tool(
+            &session,
+            &snapshot_tool,
+            json!({"action": "start", "at": "2026-03-01 08:00:00", "minutes": 15}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(text(started)["at"], "2026-03-01T08:00:00+00:00");
+        for _ in 0..2 {
+            let count = call_tool(&session, &count_tool, count_args.clone(), &ctx)
+                .await
+                .unwrap();
+            assert_eq!(text(count)["count"], 12);
+        }
+        // Other sessions read live data.
+        let count = call_tool(&pool.scoped("s2"), &count_tool, count_args.clone(), &ctx)
+            .await
+            .unwrap();
+        assert_eq!(text(count)["count"], 15);
 
-    #[test]
-    fn test_req_str_with_empty_string() {
-        let args = json!({"name": ""});
-        let mut map = HashMap::new();
-        map.insert("name".to_string(), "/name".to_string());
-        let op = make_op(map);
+        let err = call_tool(
+            &session,
+            &write_tool,
+            json!({"instance": "school-prod", "model": "sale.order", "ids": [1], "values": {}}),
+            &ctx,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("read-only snapshot"));
 
-        let result = req_str(&args, &op, "name").unwrap();
-        assert_eq!(result, "");
+        let stopped = call_tool(&session, &snapshot_tool, json!({"action": "stop"}), &ctx)
+            .await
+            .unwrap();
+        assert_eq!(text(stopped)["stopped"], true);
+        assert!(session.session_snapshot().is_none());
     }
 
-    #[test]
-    fn test_opt_i64_zero() {
-        let args = json!({"count": 0});
-        let mut map = HashMap::new();
-        map.insert("count".to_string(), "/count".to_string());
-        let op = make_op(map);
-
-        let result = opt_i64(&args, &op, "count").unwrap();
-        assert_eq!(result, Some(0));
-    }
+    #[tokio::test]
+    async fn user_groups_requires_confirm_and_links_resolved_groups() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/res.users/search_read"))
+            .and(body_partial_json(json!({
+                "domain": [["login", "=", "jane"]],
+                "context": {"active_test": false}
+            })))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!([{"id": 12, "login": "jane"}])),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/ir.model.data/search_read"))
+            .and(body_partial_json(json!({
+                "domain": [["module", "=", "base"], ["name", "=", "group_system"], ["model", "=", "res.groups"]]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{"id": 1, "res_id": 4}])))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/res.groups/search"))
+            .and(body_partial_json(json!({
+                "domain": [["name", "=ilike", "Administrator"], ["category_id.name", "=ilike", "Sales"]]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([23])))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/ir.model.fields/search_count"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(1)))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/res.users/write"))
+            .and(body_partial_json(json!({
+                "ids": [12],
+                "vals": {"group_ids": [[4, 23], [3, 4]]}
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(true)))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
+        let mut tool = make_tool("odoo_user_groups", "user_groups");
+        for key in ["instance", "userId", "login", "add", "remove", "confirm"] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let ctx = CallContext::default();
 
-    #[test]
-    fn test_opt_i64_negative() {
-        let args = json!({"count": -100});
-        let mut map = HashMap::new();
-        map.insert("count".to_string(), "/count".to_string());
-        let op = make_op(map);
+        let err = call_tool(
+            &pool,
+            &tool,
+            json!({"instance": "school-prod", "login": "jane", "add": ["Sales / Administrator"]}),
+            &ctx,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("'confirm': true"));
 
-        let result = opt_i64(&args, &op, "count").unwrap();
-        assert_eq!(result, Some(-100));
+        let result = call_tool(
+            &pool,
+            &tool,
+            json!({
+                "instance": "school-prod",
+                "login": "jane",
+                "add": ["Sales / Administrator"],
+                "remove": ["base.group_system"],
+                "confirm": true
+            }),
+            &ctx,
+        )
+        .await
+        .unwrap();
+        let body: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(body["id"], 12);
+        assert_eq!(body["login"], "jane");
+        assert_eq!(body["groupsAdded"], json!([23]));
+        assert_eq!(body["groupsRemoved"], json!([4]));
     }
 
-    #[test]
-    fn test_opt_bool_true() {
-        let args = json!({"active": true});
-        let mut map = HashMap::new();
-        map.insert("active".to_string(), "/active".to_string());
-        let op = make_op(map);
+    #[tokio::test]
+    async fn module_install_runs_only_for_uninstalled_modules() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/ir.module.module/search_read"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {"id": 1, "name": "sale", "state": "installed"},
+                {"id": 7, "name": "school_fees", "state": "uninstalled"}
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/ir.module.module/button_immediate_install"))
+            .and(body_partial_json(json!({"ids": [7]})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"tag": "reload"})))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
+        let ctx = CallContext::default();
 
-        let result = opt_bool(&args, &op, "active").unwrap();
-        assert_eq!(result, Some(true));
-    }
+        let mut tool = make_tool("odoo_install_modules", "module_install");
+        for key in ["instance", "modules"] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let result = call_tool(
+            &pool,
+            &tool,
+            json!({"instance": "school-prod", "modules": ["sale", "school_fees"]}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+        let body: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(body["method"], "button_immediate_install");
+        assert_eq!(body["processed"], json!(["school_fees"]));
+        assert_eq!(
+            body["skipped"],
+            json!([{"name": "sale", "state": "installed"}])
+        );
 
-    #[test]
-    fn test_opt_bool_false() {
-        let args = json!({"active": false});
-        let mut map = HashMap::new();
-        map.insert("active".to_string(), "/active".to_string());
-        let op = make_op(map);
+        let err = call_tool(
+            &pool,
+            &tool,
+            json!({"instance": "school-prod", "modules": ["school_feez"]}),
+            &ctx,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("Unknown modules: school_feez"));
 
-        let result = opt_bool(&args, &op, "active").unwrap();
-        assert_eq!(result, Some(false));
+        let mut tool = make_tool("odoo_uninstall_modules", "module_uninstall");
+        for key in ["instance", "modules", "confirm"] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let err = call_tool(
+            &pool,
+            &tool,
+            json!({"instance": "school-prod", "modules": ["sale"]}),
+            &ctx,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("'confirm': true"));
     }
 
-    #[test]
-    fn test_ptr_with_nested_path() {
-        let args = json!({
-            "data": {
-                "user": {
-                    "profile": {
-                        "name": "John"
-                    }
-                }
-            }
-        });
-        let mut map = HashMap::new();
-        map.insert("key".to_string(), "/data/user/profile/name".to_string());
-        let op = make_op(map);
+    #[tokio::test]
+    async fn module_update_list_is_an_admin_write() {
+        use crate::mcp::auth_tokens::{TokenGrant, TokenScope};
+        use std::collections::BTreeSet;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/ir.module.module/update_list"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([3, 1])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
+        let mut tool = make_tool("odoo_update_module_list", "module_update_list");
+        tool.op.map.insert("instance".into(), "/instance".into());
+        let args = json!({"instance": "school-prod"});
 
-        let result = ptr(&args, &op, "key");
-        assert_eq!(result, Some(&json!("John")));
-    }
+        let reader = CallContext {
+            grant: Some(TokenGrant {
+                name: "bi".into(),
+                scopes: BTreeSet::from([TokenScope::Read, TokenScope::Write]),
+                tenant: None,
+            }),
+            ..CallContext::default()
+        };
+        let error = call_tool(&pool, &tool, args.clone(), &reader)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("lacks the 'admin' scope"));
 
-    #[test]
-    fn test_ptr_with_array_index() {
-        let args = json!({
-            "items": ["a", "b", "c"]
-        });
-        let mut map = HashMap::new();
-        map.insert("key".to_string(), "/items/1".to_string());
-        let op = make_op(map);
+        let result = call_tool(&pool, &tool, args.clone(), &CallContext::default())
+            .await
+            .unwrap();
+        let body: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(body, json!({"updated": 3, "added": 1}));
 
-        let result = ptr(&args, &op, "key");
-        assert_eq!(result, Some(&json!("b")));
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .read_only = true;
+        assert!(
+            call_tool(&pool, &tool, args, &CallContext::default())
+                .await
+                .is_err()
+        );
     }
 
-    #[test]
-    fn test_deep_merge_values_recursively_preserves_defaults() {
-        let defaults = json!({
-            "limit": 20,
-            "context": {
-                "allowed_company_ids": [1],
-                "lang": "en_US"
-            },
-            "fields": ["name"]
-        });
-        let args = json!({
-            "context": {
-                "lang": "id_ID"
-            },
-            "fields": ["display_name"],
-            "offset": 5
-        });
+    #[tokio::test]
+    async fn scheduled_actions_flag_overdue_jobs_and_run_by_name() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let crons = json!([
+            {"id": 12, "cron_name": "Mail: Email Queue Manager", "active": true, "nextcall": "2020-01-01 00:00:00"},
+            {"id": 13, "cron_name": "Fees: Reminders", "active": false, "nextcall": "2020-01-01 00:00:00"},
+            {"id": 14, "cron_name": "Backup", "active": true, "nextcall": "2999-01-01 00:00:00"}
+        ]);
+        Mock::given(method("POST"))
+            .and(path("/json/2/ir.cron/search_read"))
+            .and(body_partial_json(
+                json!({"context": {"active_test": false}}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(crons.clone()))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/ir.cron/search"))
+            .and(body_partial_json(
+                json!({"domain": [["cron_name", "=ilike", "mail: email queue manager"]]}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([12])))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/ir.cron/read"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([crons[0].clone()])))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/ir.cron/method_direct_trigger"))
+            .and(body_partial_json(json!({"ids": [12]})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(true)))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
+        let ctx = CallContext::default();
+        let text = |result: Value| -> Value {
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap()
+        };
 
-        let merged = deep_merge_values(defaults, args);
+        let mut tool = make_tool("odoo_scheduled_actions", "cron_list");
+        tool.op.map.insert("instance".into(), "/instance".into());
+        let body = text(
+            call_tool(&pool, &tool, json!({"instance": "school-prod"}), &ctx)
+                .await
+                .unwrap(),
+        );
+        assert_eq!(body["count"], 3);
+        let overdue: Vec<bool> = body["scheduledActions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["overdue"].as_bool().unwrap())
+            .collect();
+        assert_eq!(overdue, [true, false, false]);
+
+        let mut tool = make_tool("odoo_scheduled_action_control", "cron_control");
+        for key in ["instance", "action", "id", "name"] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let body = text(
+            call_tool(
+                &pool,
+                &tool,
+                json!({"instance": "school-prod", "action": "run", "name": "mail: email queue manager"}),
+                &ctx,
+            )
+            .await
+            .unwrap(),
+        );
+        assert_eq!(body["action"], "run");
+        assert_eq!(body["scheduledAction"]["id"], 12);
 
-        assert_eq!(merged["limit"], json!(20));
-        assert_eq!(merged["offset"], json!(5));
-        assert_eq!(merged["context"]["allowed_company_ids"], json!([1]));
-        assert_eq!(merged["context"]["lang"], json!("id_ID"));
-        assert_eq!(merged["fields"], json!(["display_name"]));
+        let err = call_tool(
+            &pool,
+            &tool,
+            json!({"instance": "school-prod", "action": "pause", "id": 12}),
+            &ctx,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("Unknown action 'pause'"));
     }
 
-    #[test]
-    fn test_apply_instance_tool_config_rejects_disabled_tool() {
-        let pool = make_pool(Some(InstanceToolConfig {
-            disabled_tools: vec!["odoo_create".to_string()],
-            disabled_packs: Vec::new(),
-            defaults: HashMap::new(),
-            execute_allowlist: Vec::new(),
-        }));
+    #[tokio::test]
+    async fn server_action_runs_by_xmlid_on_active_records() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/ir.model.data/search_read"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {"module": "school", "name": "action_send_reminders", "model": "ir.actions.server", "res_id": 31, "noupdate": false},
+                {"module": "base", "name": "main_company", "model": "res.company", "res_id": 1, "noupdate": true}
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/ir.actions.server/read"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {"id": 31, "name": "Send fee reminders", "model_name": "school.fee", "state": "code"}
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/ir.actions.server/run"))
+            .and(body_partial_json(json!({
+                "ids": [31],
+                "context": {"active_model": "school.fee", "active_id": 4, "active_ids": [4, 5], "lang": "id_ID"}
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(false)))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
+        let ctx = CallContext::default();
+        let mut tool = make_tool("odoo_server_action_run", "server_action_run");
+        for key in ["instance", "id", "xmlid", "recordIds", "context"] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+
+        let result = call_tool(
+            &pool,
+            &tool,
+            json!({
+                "instance": "school-prod",
+                "xmlid": "school.action_send_reminders",
+                "recordIds": [4, 5],
+                "context": {"lang": "id_ID"}
+            }),
+            &ctx,
+        )
+        .await
+        .unwrap();
+        let body: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(body["action"]["model"], "school.fee");
+        assert_eq!(body["result"], false);
+
+        let err = call_tool(
+            &pool,
+            &tool,
+            json!({"instance": "school-prod", "xmlid": "base.main_company"}),
+            &ctx,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("not a server action"));
+    }
 
-        let error = pool
-            .apply_instance_tool_config(
-                "school-prod",
-                &make_tool("odoo_create", "create"),
-                json!({ "instance": "school-prod" }),
-            )
-            .unwrap_err();
+    #[tokio::test]
+    async fn server_action_run_needs_an_admin_token() {
+        use crate::mcp::auth_tokens::{TokenGrant, TokenScope};
+        use crate::mcp::registry::ToolAnnotations;
+        use std::collections::BTreeSet;
 
+        let pool = make_pool(None);
+        let tool = make_tool("odoo_server_action_run", "server_action_run");
+        let ctx = CallContext {
+            grant: Some(TokenGrant {
+                name: "etl".into(),
+                scopes: BTreeSet::from([TokenScope::Read, TokenScope::Write]),
+                tenant: None,
+            }),
+            ..CallContext::default()
+        };
+        let error = call_tool(&pool, &tool, json!({}), &ctx).await.unwrap_err();
         assert!(
-            error
-                .to_string()
-                .contains("Tool 'odoo_create' is disabled for instance 'school-prod'")
+            error.to_string().contains("lacks the 'admin' scope"),
+            "{error}"
         );
+
+        let annotations = ToolAnnotations::for_op("server_action_run");
+        assert_eq!(annotations.read_only_hint, Some(false));
+        assert_eq!(annotations.destructive_hint, Some(true));
     }
 
-    #[test]
-    fn test_apply_instance_tool_config_merges_defaults_with_call_precedence() {
-        let mut defaults = HashMap::new();
-        defaults.insert(
-            "odoo_search_read".to_string(),
+    #[tokio::test]
+    async fn multi_read_runs_lookups_and_keys_results_by_name() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/res.partner/read"))
+            .and(body_partial_json(
+                json!({"ids": [7], "context": {"lang": "fr_FR"}}),
+            ))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!([{"id": 7, "name": "Acme"}])),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/sale.order/search_read"))
+            .and(body_partial_json(json!({
+                "domain": [["partner_id", "=", 7]],
+                "limit": 5,
+                "context": {"lang": "en_US"}
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{"id": 40}, {"id": 41}])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/crm.lead/search_read"))
+            .respond_with(ResponseTemplate::new(403).set_body_json(json!({
+                "name": "odoo.exceptions.AccessError",
+                "message": "You are not allowed to access 'Lead/Opportunity'"
+            })))
+            .mount(&server)
+            .await;
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
+        let mut tool = make_tool("odoo_multi_read", "multi_read");
+        for key in ["instance", "requests", "context"] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+
+        let result = call_tool(
+            &pool,
+            &tool,
             json!({
-                "limit": 20,
-                "context": {
-                    "allowed_company_ids": [1],
-                    "lang": "en_US"
-                },
-                "fields": ["name"]
+                "instance": "school-prod",
+                "context": {"lang": "fr_FR"},
+                "requests": {
+                    "partner": {"model": "res.partner", "ids": [7], "fields": ["name"]},
+                    "orders": {
+                        "model": "sale.order",
+                        "domain": [["partner_id", "=", 7]],
+                        "limit": 5,
+                        "context": {"lang": "en_US"}
+                    },
+                    "leads": {"model": "crm.lead", "domain": []}
+                }
             }),
+            &CallContext::default(),
+        )
+        .await
+        .unwrap();
+        let payload: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(payload["count"], 3);
+        assert_eq!(payload["failed"], 1);
+        assert_eq!(
+            payload["results"]["partner"]["records"],
+            json!([{"id": 7, "name": "Acme"}])
         );
+        assert_eq!(payload["results"]["orders"]["count"], 2);
+        assert_eq!(payload["results"]["leads"]["model"], "crm.lead");
+        assert!(payload["results"]["leads"]["error"].is_string());
+
+        let too_many: Map<String, Value> = (0..=MAX_MULTI_READ_SPECS)
+            .map(|i| (format!("p{i}"), json!({"model": "res.partner", "ids": [i]})))
+            .collect();
+        let err = call_tool(
+            &pool,
+            &tool,
+            json!({"instance": "school-prod", "requests": too_many}),
+            &CallContext::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("between 1 and 10"), "{err}");
+    }
 
-        let pool = make_pool(Some(InstanceToolConfig {
-            disabled_tools: Vec::new(),
-            disabled_packs: Vec::new(),
-            defaults,
-            execute_allowlist: Vec::new(),
-        }));
+    #[tokio::test]
+    #[allow(clippy::await_holding_lock)] // The process environment requires serialized tests.
+    async fn payslip_amounts_need_an_admin_token() {
+        use crate::mcp::auth_tokens::{TokenGrant, TokenScope};
+        use std::collections::BTreeSet;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let _env_lock = crate::TEST_ENV_MUTEX.lock().unwrap();
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/hr.payslip/read_group"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {"payslip_run_id": [3, "March"], "state": "done", "__count": 1}
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/hr.payslip/search_read"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {"id": 11, "employee_id": [5, "Ana"], "payslip_run_id": [3, "March"], "state": "done", "net_wage": 2500.0}
+            ])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/hr.payslip.line/search_read"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {"slip_id": [11, "Ana"], "code": "NET", "name": "Net", "total": 2500.0}
+            ])))
+            .mount(&server)
+            .await;
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
+        let mut tool = make_tool("odoo_payslip_summary", "payslip_summary");
+        for key in ["instance", "includeAmounts"] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let read = CallContext {
+            grant: Some(TokenGrant {
+                name: "hr-bot".into(),
+                scopes: BTreeSet::from([TokenScope::Read]),
+                tenant: None,
+            }),
+            ..CallContext::default()
+        };
+        let admin = CallContext {
+            grant: Some(TokenGrant::admin("ops")),
+            ..CallContext::default()
+        };
+        let amounts = json!({"instance": "school-prod", "includeAmounts": true});
+        // SAFETY: serialized by TEST_ENV_MUTEX.
+        unsafe { std::env::set_var(packs::payroll::PAYROLL_AMOUNTS_ENV, "true") };
 
-        let merged = pool
-            .apply_instance_tool_config(
-                "school-prod",
-                &make_tool("odoo_search_read", "search_read"),
-                json!({
-                    "instance": "school-prod",
-                    "context": {
-                        "lang": "id_ID"
-                    },
-                    "fields": ["display_name"],
-                    "offset": 5
-                }),
-            )
-            .unwrap();
+        let error = call_tool(&pool, &tool, amounts.clone(), &read)
+            .await
+            .unwrap_err();
+        assert!(
+            error.to_string().contains("lacks the 'admin' scope"),
+            "{error}"
+        );
 
-        assert_eq!(merged["instance"], json!("school-prod"));
-        assert_eq!(merged["limit"], json!(20));
-        assert_eq!(merged["offset"], json!(5));
-        assert_eq!(merged["context"]["allowed_company_ids"], json!([1]));
-        assert_eq!(merged["context"]["lang"], json!("id_ID"));
-        assert_eq!(merged["fields"], json!(["display_name"]));
+        let counts = call_tool(&pool, &tool, json!({"instance": "school-prod"}), &read)
+            .await
+            .unwrap();
+        let counts: Value =
+            serde_json::from_str(counts["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(counts["amountsIncluded"], json!(false));
+        assert_eq!(counts["total"], 1);
+
+        let result = call_tool(&pool, &tool, amounts, &admin).await;
+        // SAFETY: serialized by TEST_ENV_MUTEX.
+        unsafe { std::env::remove_var(packs::payroll::PAYROLL_AMOUNTS_ENV) };
+        let result: Value =
+            serde_json::from_str(result.unwrap()["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(result["amountsIncluded"], json!(true));
+        assert_eq!(result["payslips"][0]["netWage"], json!(2500.0));
+        assert_eq!(result["payslips"][0]["lines"][0]["code"], "NET");
     }
 
-    #[test]
-    fn read_only_instance_rejects_mutating_tools() {
+    #[tokio::test]
+    async fn format_renders_results_as_markdown_or_csv() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/res.partner/search_read"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {"id": 7, "name": "Azure Interior", "country_id": [233, "United States"]}
+            ])))
+            .mount(&server)
+            .await;
         let pool = make_pool(None);
         pool.env
             .write()
@@ -2103,79 +8207,365 @@ mod tests {
             .instances
             .get_mut("school-prod")
             .unwrap()
-            .read_only = true;
+            .url = server.uri();
+        let mut tool = make_tool("odoo_search_read", "search_read");
+        for key in ["instance", "model"] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let ctx = CallContext::default();
+        let call = |format: &str| json!({"instance": "school-prod", "model": "res.partner", "format": format});
 
-        let error = pool
-            .apply_instance_tool_config(
-                "school-prod",
-                &make_tool("odoo_create", "create"),
-                json!({}),
-            )
+        let result = call_tool(&pool, &tool, call("markdown"), &ctx)
+            .await
+            .unwrap();
+        assert_eq!(
+            result["content"][0]["text"],
+            "- **count**: 1\n\n### records\n\n| id | country_id | name |\n| --- | --- | --- |\n| 7 | United States | Azure Interior |\n"
+        );
+        let result = call_tool(&pool, &tool, call("csv"), &ctx).await.unwrap();
+        assert_eq!(
+            result["content"][0]["text"],
+            "id,country_id,name\r\n7,United States,Azure Interior\r\n"
+        );
+        let err = call_tool(&pool, &tool, call("yaml"), &ctx)
+            .await
             .unwrap_err();
-
-        assert!(error.to_string().contains("read-only instance"));
-        assert!(pool.all_instances_read_only());
-        assert!(pool.instance_is_read_only("school-prod"));
+        assert!(err.to_string().contains("'format' must be one of"));
     }
 
-    #[test]
-    fn execute_is_denied_unless_model_and_method_are_allowlisted() {
-        let pool = make_pool(Some(InstanceToolConfig {
-            disabled_tools: Vec::new(),
-            disabled_packs: Vec::new(),
-            defaults: HashMap::new(),
-            execute_allowlist: vec![crate::odoo::config::ExecuteAllowlistEntry {
-                model: "sale.order".to_string(),
-                methods: vec!["action_confirm".to_string()],
-            }],
-        }));
+    #[tokio::test]
+    async fn settings_are_read_and_applied_through_execute() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/res.config.settings/fields_get"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": {"type": "integer", "string": "ID", "readonly": true},
+                "group_multi_currency": {"type": "boolean", "string": "Multi-Currencies"},
+                "company_currency_id": {"type": "many2one", "string": "Currency", "readonly": true},
+                "auth_signup_uninvited": {"type": "selection", "string": "Customer Account"}
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/res.config.settings/default_get"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "group_multi_currency": true,
+                "company_currency_id": 1,
+                "auth_signup_uninvited": "b2b"
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/res.config.settings/create"))
+            .and(body_partial_json(json!({
+                "vals_list": [{"group_multi_currency": true, "auth_signup_uninvited": "b2c"}]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([31])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/res.config.settings/execute"))
+            .and(body_partial_json(json!({"ids": [31]})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(true)))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
+        let ctx = CallContext::default();
 
-        assert!(pool.execute_allowed("school-prod", "sale.order", "action_confirm"));
-        assert!(!pool.execute_allowed("school-prod", "sale.order", "unlink"));
-        assert!(!pool.execute_allowed("school-prod", "res.partner", "action_confirm"));
+        let mut tool = make_tool("odoo_settings", "settings_get");
+        for key in ["instance", "fields", "search"] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let result = call_tool(
+            &pool,
+            &tool,
+            json!({"instance": "school-prod", "search": "currenc"}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+        let body: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(body["count"], 2);
+        assert_eq!(body["settings"][1]["field"], "group_multi_currency");
+        assert_eq!(body["settings"][1]["value"], true);
+
+        let mut tool = make_tool("odoo_settings_apply", "settings_apply");
+        for key in ["instance", "values"] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let err = call_tool(
+            &pool,
+            &tool,
+            json!({"instance": "school-prod", "values": {"company_currency_id": 2, "bogus": 1}}),
+            &ctx,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("bogus is not a settings field"));
+        assert!(err.to_string().contains("company_currency_id is read-only"));
+
+        let result = call_tool(
+            &pool,
+            &tool,
+            json!({
+                "instance": "school-prod",
+                "values": {"group_multi_currency": true, "auth_signup_uninvited": "b2c"}
+            }),
+            &ctx,
+        )
+        .await
+        .unwrap();
+        let body: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(body["applied"]["group_multi_currency"], true);
+        // The mock keeps answering b2b, as a setter that ignored the change would.
+        assert_eq!(body["differs"], json!(["auth_signup_uninvited"]));
+        assert!(body.get("action").is_none());
     }
 
-    #[test]
-    fn execute_is_denied_when_allowlist_is_empty() {
-        let without_tool_config = make_pool(None);
-        assert!(!without_tool_config.execute_allowed(
-            "school-prod",
-            "sale.order",
-            "action_confirm"
-        ));
+    #[tokio::test]
+    async fn search_read_pages_with_next_cursor() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/res.partner/search_read"))
+            .and(body_partial_json(json!({"offset": 2, "limit": 2})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{"id": 9}])))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/json/2/res.partner/search_read"))
+            .and(body_partial_json(
+                json!({"limit": 2, "domain": [["customer_rank", ">", 0]]}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{"id": 3}, {"id": 5}])))
+            .mount(&server)
+            .await;
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
+        let mut tool = make_tool("odoo_search_read", "search_read");
+        for key in ["instance", "model", "domain", "limit", "offset", "order"] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let ctx = CallContext::default();
+        let page = |result: Value| -> Value {
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap()
+        };
 
-        let empty_allowlist = make_pool(Some(InstanceToolConfig {
-            disabled_tools: Vec::new(),
-            disabled_packs: Vec::new(),
-            defaults: HashMap::new(),
-            execute_allowlist: Vec::new(),
-        }));
-        assert!(!empty_allowlist.execute_allowed("school-prod", "sale.order", "action_confirm"));
+        let first = page(
+            call_tool(
+                &pool,
+                &tool,
+                json!({
+                    "instance": "school-prod",
+                    "model": "res.partner",
+                    "domain": [["customer_rank", ">", 0]],
+                    "limit": 2
+                }),
+                &ctx,
+            )
+            .await
+            .unwrap(),
+        );
+        assert_eq!(first["count"], 2);
+        let cursor = first["nextCursor"].as_str().unwrap().to_string();
+
+        let second = page(
+            call_tool(&pool, &tool, json!({ "cursor": cursor }), &ctx)
+                .await
+                .unwrap(),
+        );
+        assert_eq!(second["records"], json!([{"id": 9}]));
+        assert!(second.get("nextCursor").is_none(), "short page is the last");
     }
 
     #[tokio::test]
-    async fn direct_call_denies_tool_with_missing_module() {
-        let pool = make_pool(None);
-        pool.module_snapshots
-            .success(
-                "school-prod",
-                Some("19".into()),
-                "community".into(),
-                BTreeSet::from(["base".into()]),
+    async fn send_email_queues_a_template_or_raw_mail() {
+        use wiremock::matchers::{body_partial_json, method, path_regex};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/mail\.template/send_mail"))
+            .and(body_partial_json(
+                json!({"ids": [12], "res_id": 5, "force_send": false}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(301)))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/mail\.mail/create"))
+            .and(body_partial_json(json!({"vals_list": [{
+                "subject": "Delivery update",
+                "model": "sale.order",
+                "res_id": 5,
+                "recipient_ids": [[6, 0, [3]]]
+            }]})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([302])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/mail\.mail/read"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!([{"id": 301, "state": "outgoing"}])),
             )
+            .mount(&server)
             .await;
-        let mut tool = make_tool("stock_tool", "search");
-        tool.required_modules = vec!["stock".into()];
-        tool.op.map.insert("instance".into(), "/instance".into());
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
+        let mut tool = make_tool("odoo_send_email", "send_email");
+        for key in [
+            "instance",
+            "model",
+            "id",
+            "templateId",
+            "subject",
+            "body",
+            "partnerIds",
+        ] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
 
-        let error = call_tool(
+        let result = call_tool(
             &pool,
             &tool,
-            json!({"instance": "school-prod", "model": "stock.quant"}),
+            json!({"instance": "school-prod", "model": "sale.order", "id": 5, "templateId": 12}),
+            &CallContext::default(),
         )
         .await
-        .unwrap_err();
+        .unwrap();
+        let sent: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(sent["mailId"], 301);
+        assert_eq!(sent["state"], "outgoing");
+
+        let raw = json!({
+            "instance": "school-prod",
+            "model": "sale.order",
+            "id": 5,
+            "subject": "Delivery update",
+            "body": "<p>Your order ships today.</p>",
+            "partnerIds": [3]
+        });
+        let result = call_tool(&pool, &tool, raw.clone(), &CallContext::default())
+            .await
+            .unwrap();
+        let sent: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(sent["mailId"], 302);
 
-        assert!(error.to_string().contains("missing_modules (stock)"));
+        let mut missing = raw;
+        missing.as_object_mut().unwrap().remove("partnerIds");
+        let error = call_tool(&pool, &tool, missing, &CallContext::default())
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("emailTo"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn activities_are_scheduled_and_completed() {
+        use wiremock::matchers::{body_partial_json, method, path_regex};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/crm\.lead/activity_schedule"))
+            .and(body_partial_json(json!({
+                "ids": [12],
+                "act_type_xmlid": "mail.mail_activity_data_call",
+                "summary": "Call back",
+                "date_deadline": "2026-11-02",
+                "user_id": 4
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([31])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path_regex(r"/json/2/mail\.activity/action_feedback"))
+            .and(body_partial_json(
+                json!({"ids": [31], "feedback": "Reached"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(null)))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let pool = make_pool(None);
+        pool.env
+            .write()
+            .unwrap()
+            .instances
+            .get_mut("school-prod")
+            .unwrap()
+            .url = server.uri();
+        let mut tool = make_tool("odoo_activity_schedule", "activity_schedule");
+        for key in [
+            "instance",
+            "action",
+            "model",
+            "id",
+            "activityType",
+            "summary",
+            "dateDeadline",
+            "userId",
+            "activityId",
+            "feedback",
+        ] {
+            tool.op.map.insert(key.into(), format!("/{key}"));
+        }
+        let args = json!({
+            "instance": "school-prod",
+            "model": "crm.lead",
+            "id": 12,
+            "activityType": "mail.mail_activity_data_call",
+            "summary": "Call back",
+            "dateDeadline": "2026-11-02",
+            "userId": 4
+        });
+        let result = call_tool(&pool, &tool, args, &CallContext::default())
+            .await
+            .unwrap();
+        let scheduled: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(scheduled["activityId"], 31);
+
+        let args = json!({
+            "instance": "school-prod",
+            "action": "done",
+            "activityId": 31,
+            "feedback": "Reached"
+        });
+        call_tool(&pool, &tool, args, &CallContext::default())
+            .await
+            .unwrap();
     }
 }
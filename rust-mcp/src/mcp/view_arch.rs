@@ -0,0 +1,212 @@
+//! Field extraction from view arches for `odoo_view_definition`.
+//!
+//! Odoo returns a view's arch as an XML string. Only the `<field>` elements that belong to the
+//! view's own model are needed here, so this is a small tag scanner rather than an XML parser:
+//! fields nested inside another field (inline one2many list or form views) describe the
+//! related model and are skipped.
+
+use std::collections::BTreeMap;
+
+use serde_json::{Map, Value, json};
+
+/// One `<field>` element of a view with its attributes, entities decoded.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ViewField {
+    pub name: String,
+    pub attrs: BTreeMap<String, String>,
+}
+
+/// The tag starting at `text` (which begins with `<`) and the text after it. `>` inside quoted
+/// attribute values does not end the tag.
+fn split_tag(text: &str) -> Option<(&str, &str)> {
+    let mut quote = None;
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '>') => return Some((&text[..=i], &text[i + 1..])),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn decode_entities(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn parse_attrs(mut text: &str) -> BTreeMap<String, String> {
+    let mut attrs = BTreeMap::new();
+    loop {
+        text = text.trim_start();
+        let Some(eq) = text.find('=') else {
+            return attrs;
+        };
+        let name = text[..eq].trim().to_string();
+        text = text[eq + 1..].trim_start();
+        let Some(quote) = text.chars().next().filter(|c| matches!(c, '"' | '\'')) else {
+            return attrs;
+        };
+        let Some(end) = text[1..].find(quote) else {
+            return attrs;
+        };
+        attrs.insert(name, decode_entities(&text[1..end + 1]));
+        text = &text[end + 2..];
+    }
+}
+
+/// The `<field>` elements of `arch` that are not nested inside another field, in order.
+pub fn top_level_fields(arch: &str) -> Vec<ViewField> {
+    let mut fields = Vec::new();
+    let mut depth = 0usize;
+    let mut rest = arch;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start..];
+        if let Some(after) = rest.strip_prefix("<!--") {
+            rest = after.find("-->").map_or("", |end| &after[end + 3..]);
+            continue;
+        }
+        let Some((tag, after)) = split_tag(rest) else {
+            break;
+        };
+        rest = after;
+        let body = &tag[1..tag.len() - 1];
+        if let Some(closing) = body.strip_prefix('/') {
+            if closing.trim() == "field" {
+                depth = depth.saturating_sub(1);
+            }
+            continue;
+        }
+        let self_closing = body.ends_with('/');
+        let body = body.trim_end_matches('/');
+        let (element, attrs) = body.split_once(char::is_whitespace).unwrap_or((body, ""));
+        if element != "field" {
+            continue;
+        }
+        if depth == 0 {
+            let mut attrs = parse_attrs(attrs);
+            if let Some(name) = attrs.remove("name") {
+                fields.push(ViewField { name, attrs });
+            }
+        }
+        if !self_closing {
+            depth += 1;
+        }
+    }
+    fields
+}
+
+/// A view modifier: `true`/`false` for constant values, otherwise the condition expression.
+fn modifier(value: &str) -> Value {
+    match value.trim() {
+        "1" | "True" | "true" => json!(true),
+        "0" | "False" | "false" | "" => json!(false),
+        expr => json!(expr),
+    }
+}
+
+/// What a client needs to know about one view field: its label and type from `fields` (the
+/// model's field definitions), and whether the view makes it required, read-only or invisible.
+/// View attributes win over the field definition; conditions are returned as expressions.
+pub fn field_summary(field: &ViewField, fields: &Value) -> Value {
+    let definition = &fields[&field.name];
+    let attr = |name: &str| field.attrs.get(name).map(|v| modifier(v));
+    let mut summary = Map::new();
+    summary.insert("name".into(), json!(field.name));
+    summary.insert(
+        "string".into(),
+        field
+            .attrs
+            .get("string")
+            .map(|s| json!(s))
+            .unwrap_or_else(|| definition["string"].clone()),
+    );
+    summary.insert("type".into(), definition["type"].clone());
+    if let Some(relation) = definition.get("relation") {
+        summary.insert("relation".into(), relation.clone());
+    }
+    for key in ["required", "readonly"] {
+        let value = attr(key).unwrap_or_else(|| json!(definition[key].as_bool() == Some(true)));
+        summary.insert(key.into(), value);
+    }
+    let invisible = attr("invisible")
+        .filter(|v| v != &json!(false))
+        .or_else(|| attr("column_invisible"))
+        .unwrap_or(json!(false));
+    summary.insert("invisible".into(), invisible);
+    // Odoo 16 and older express conditional modifiers in a separate `attrs` dict.
+    if let Some(attrs) = field.attrs.get("attrs") {
+        summary.insert("attrs".into(), json!(attrs));
+    }
+    Value::Object(summary)
+}
+
+/// Names among `summaries` a create call must fill: always required and not always hidden.
+pub fn required_fields(summaries: &[Value]) -> Vec<String> {
+    summaries
+        .iter()
+        .filter(|f| f["required"] == json!(true) && f["invisible"] != json!(true))
+        .filter_map(|f| f["name"].as_str().map(str::to_string))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARCH: &str = r#"<form string="Order">
+        <!-- <field name="commented"/> -->
+        <field name="partner_id" required="1" options="{'no_create': True}"/>
+        <field name="date_order" readonly="state != 'draft'"/>
+        <field name="note" invisible="1" string="Terms &amp; conditions"/>
+        <field name="order_line">
+            <list editable="bottom"><field name="product_id" required="1"/></list>
+        </field>
+        <field name="company_id" column_invisible="True"/>
+    </form>"#;
+
+    #[test]
+    fn scans_top_level_fields_only() {
+        let fields = top_level_fields(ARCH);
+        let names: Vec<_> = fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "partner_id",
+                "date_order",
+                "note",
+                "order_line",
+                "company_id"
+            ]
+        );
+        assert_eq!(fields[0].attrs["options"], "{'no_create': True}");
+        assert_eq!(fields[2].attrs["string"], "Terms & conditions");
+    }
+
+    #[test]
+    fn summaries_combine_view_and_model_modifiers() {
+        let fields = json!({
+            "partner_id": {"string": "Customer", "type": "many2one", "relation": "res.partner"},
+            "date_order": {"string": "Order Date", "type": "datetime", "required": true},
+            "note": {"string": "Note", "type": "html"},
+            "order_line": {"string": "Lines", "type": "one2many", "relation": "sale.order.line"},
+            "company_id": {"string": "Company", "type": "many2one", "required": true},
+        });
+        let summaries: Vec<Value> = top_level_fields(ARCH)
+            .iter()
+            .map(|f| field_summary(f, &fields))
+            .collect();
+        assert_eq!(summaries[0]["required"], true);
+        assert_eq!(summaries[0]["relation"], "res.partner");
+        assert_eq!(summaries[1]["readonly"], "state != 'draft'");
+        assert_eq!(summaries[2]["invisible"], true);
+        assert_eq!(summaries[2]["string"], "Terms & conditions");
+        assert_eq!(summaries[4]["invisible"], true);
+        assert_eq!(required_fields(&summaries), ["partner_id", "date_order"]);
+    }
+}
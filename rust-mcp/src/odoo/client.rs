@@ -1,11 +1,19 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use reqwest::StatusCode;
 use reqwest::header::{AUTHORIZATION, CONTENT_TYPE, HeaderMap, HeaderValue, USER_AGENT};
 use serde_json::{Value, json};
+use tracing::Instrument;
 use url::Url;
 
 use super::config::OdooInstanceConfig;
-use super::types::{OdooError, OdooErrorBody, OdooResult};
+use super::deadline;
+use super::faults::{self, FaultInjector};
+use super::health::{self, HealthDepth};
+use super::locale;
+use super::throttle::{self, UpstreamThrottle};
+use super::types::{BinaryContent, OdooError, OdooErrorBody, OdooResult, ReportFormat};
+use crate::telemetry;
 
 #[derive(Clone, Copy)]
 enum RetryMode {
@@ -13,6 +21,31 @@ enum RetryMode {
     Never,
 }
 
+/// Read a response body chunk by chunk, failing as soon as it grows past `max_bytes`.
+pub(crate) async fn read_body(
+    mut resp: reqwest::Response,
+    max_bytes: Option<usize>,
+) -> OdooResult<Vec<u8>> {
+    let limit = max_bytes.unwrap_or(usize::MAX);
+    let too_large = || {
+        OdooError::InvalidResponse(format!(
+            "Response exceeds the configured {limit}-byte limit"
+        ))
+    };
+    let declared = resp.content_length().unwrap_or(0);
+    if declared > limit as u64 {
+        return Err(too_large());
+    }
+    let mut body = Vec::with_capacity(declared as usize);
+    while let Some(chunk) = resp.chunk().await.map_err(deadline::transport_error)? {
+        if body.len() + chunk.len() > limit {
+            return Err(too_large());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
 #[derive(Clone)]
 pub struct OdooHttpClient {
     base_url: Url, // e.g. https://mycompany.example.com
@@ -20,6 +53,16 @@ pub struct OdooHttpClient {
     api_key: String,
     http: reqwest::Client,
     max_retries: usize,
+    /// Per-attempt timeout; a request deadline may shorten it.
+    timeout: Duration,
+    /// Pacing after Odoo answered 429.
+    throttle: UpstreamThrottle,
+    /// Test-only failures injected into calls (`ODOO_FAULT_INJECTION`).
+    faults: Option<FaultInjector>,
+    user_agent: HeaderValue,
+    /// `context.lang` for requests that do not set one.
+    lang: Option<String>,
+    translate_errors: bool,
 }
 
 impl OdooHttpClient {
@@ -42,6 +85,7 @@ impl OdooHttpClient {
         let http = reqwest::Client::builder()
             .timeout(timeout)
             .cookie_store(true)
+            .default_headers(cfg.default_headers()?)
             .build()?;
 
         Ok(Self {
@@ -50,6 +94,12 @@ impl OdooHttpClient {
             api_key,
             http,
             max_retries,
+            timeout,
+            throttle: UpstreamThrottle::default(),
+            faults: FaultInjector::from_env(),
+            user_agent: HeaderValue::from_str(&telemetry::user_agent())?,
+            lang: cfg.lang.clone(),
+            translate_errors: cfg.translate_errors,
         })
     }
 
@@ -64,7 +114,7 @@ impl OdooHttpClient {
             CONTENT_TYPE,
             HeaderValue::from_static("application/json; charset=utf-8"),
         );
-        headers.insert(USER_AGENT, HeaderValue::from_static("odoo-mcp-rust/0.1"));
+        headers.insert(USER_AGENT, self.user_agent.clone());
         if let Some(db) = &self.db
             && !db.trim().is_empty()
         {
@@ -94,10 +144,15 @@ impl OdooHttpClient {
                 .unwrap_or_else(|| text.to_string())
         };
 
-        OdooError::Api {
+        let err = OdooError::Api {
             status: status.as_u16(),
             message,
             body: parsed_err,
+        };
+        if self.translate_errors {
+            locale::translate_error(err)
+        } else {
+            err
         }
     }
 
@@ -126,9 +181,12 @@ impl OdooHttpClient {
         &self,
         model: &str,
         method: &str,
-        body: Value,
+        mut body: Value,
         retry_mode: RetryMode,
     ) -> OdooResult<Value> {
+        if let Some(lang) = &self.lang {
+            locale::apply_lang(&mut body, lang);
+        }
         let url = self
             .endpoint(model, method)
             .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
@@ -143,19 +201,39 @@ impl OdooHttpClient {
             RetryMode::Never => 0,
         };
         for attempt in 0..=max_retries {
-            let resp = self
-                .http
-                .post(url.clone())
-                .headers(headers.clone())
-                .json(&body)
-                .send()
-                .await;
+            let span = telemetry::odoo_http_span("POST", &url, attempt as u32);
+            self.throttle.acquire().await?;
+            let timeout = deadline::attempt_timeout(self.timeout, last_err.as_ref())?;
+            let fault = match faults::before_send(self.faults.as_ref(), timeout).await {
+                Ok(fault) => fault,
+                Err(err) => {
+                    last_err = Some(err);
+                    if attempt < max_retries {
+                        deadline::backoff(attempt, last_err.as_ref()).await?;
+                    }
+                    continue;
+                }
+            };
+            let resp = telemetry::propagate(
+                self.http
+                    .post(url.clone())
+                    .timeout(timeout)
+                    .headers(headers.clone())
+                    .json(&body),
+            )
+            .send()
+            .instrument(span.clone())
+            .await;
+            telemetry::record_response(&span, &resp);
 
             match resp {
                 Ok(r) => {
                     let status = r.status();
+                    let retry_after = throttle::retry_after(r.headers());
                     let text = r.text().await.unwrap_or_default();
+                    let text = faults::after_receive(fault, text);
                     if status.is_success() {
+                        self.throttle.on_success();
                         let v: Value = serde_json::from_str(&text).map_err(|e| {
                             OdooError::InvalidResponse(format!(
                                 "Failed to parse JSON response: {e}. Body: {text}"
@@ -167,7 +245,9 @@ impl OdooHttpClient {
                     let err = self.build_api_error(status, &url, &text);
 
                     // Retry on 5xx and 429; do not retry auth/4xx.
-                    if status.is_server_error() || status.as_u16() == 429 {
+                    if status == StatusCode::TOO_MANY_REQUESTS {
+                        last_err = Some(self.throttle.on_throttled(retry_after, Instant::now()));
+                    } else if status.is_server_error() {
                         last_err = Some(err);
                     } else {
                         return Err(err);
@@ -175,14 +255,12 @@ impl OdooHttpClient {
                 }
                 Err(e) => {
                     // Retry on transport-level errors.
-                    last_err = Some(OdooError::Http(e));
+                    last_err = Some(deadline::transport_error(e));
                 }
             }
 
             if attempt < max_retries {
-                // Exponential backoff: 250ms, 500ms, 1s, 2s...
-                let backoff_ms = 250u64.saturating_mul(2u64.saturating_pow(attempt as u32));
-                tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
+                deadline::backoff(attempt, last_err.as_ref()).await?;
             }
         }
 
@@ -192,32 +270,80 @@ impl OdooHttpClient {
     }
 
     pub async fn download_report_pdf(&self, report_name: &str, ids: &[i64]) -> OdooResult<Vec<u8>> {
+        self.download_report(report_name, ids, ReportFormat::Pdf)
+            .await
+            .map(|content| content.bytes)
+    }
+
+    /// Render a report for `ids` through the web controller of `format`.
+    pub async fn download_report(
+        &self,
+        report_name: &str,
+        ids: &[i64],
+        format: ReportFormat,
+    ) -> OdooResult<BinaryContent> {
         let mut url = self.base_url.clone();
         let ids_csv = ids
             .iter()
             .map(|i| i.to_string())
             .collect::<Vec<_>>()
             .join(",");
-        url.set_path(&format!("/report/pdf/{report_name}/{ids_csv}"));
+        url.set_path(&format!(
+            "/report/{}/{report_name}/{ids_csv}",
+            format.converter()
+        ));
+        self.download(url, None).await
+    }
+
+    /// Download the binary content of an `ir.attachment` through `/web/content`, failing once
+    /// the body grows past `max_bytes`.
+    pub async fn download_attachment(
+        &self,
+        id: i64,
+        max_bytes: usize,
+    ) -> OdooResult<BinaryContent> {
+        let mut url = self.base_url.clone();
+        url.set_path(&format!("/web/content/{id}"));
+        url.set_query(Some("download=true"));
+        self.download(url, Some(max_bytes)).await
+    }
 
+    async fn download(&self, url: Url, max_bytes: Option<usize>) -> OdooResult<BinaryContent> {
         let headers = self
             .headers()
             .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
 
         let mut last_err: Option<OdooError> = None;
         for attempt in 0..=self.max_retries {
-            let resp = self
-                .http
-                .get(url.clone())
-                .headers(headers.clone())
-                .send()
-                .await;
+            let span = telemetry::odoo_http_span("GET", &url, attempt as u32);
+            self.throttle.acquire().await?;
+            let timeout = deadline::attempt_timeout(self.timeout, last_err.as_ref())?;
+            let resp = telemetry::propagate(
+                self.http
+                    .get(url.clone())
+                    .timeout(timeout)
+                    .headers(headers.clone()),
+            )
+            .send()
+            .instrument(span.clone())
+            .await;
+            telemetry::record_response(&span, &resp);
             match resp {
                 Ok(r) => {
                     let status = r.status();
+                    let retry_after = throttle::retry_after(r.headers());
                     if status.is_success() {
-                        let bytes = r.bytes().await.map_err(OdooError::Http)?;
-                        return Ok(bytes.to_vec());
+                        self.throttle.on_success();
+                        let content_type = r
+                            .headers()
+                            .get(CONTENT_TYPE)
+                            .and_then(|v| v.to_str().ok())
+                            .map(str::to_owned);
+                        let bytes = read_body(r, max_bytes).await?;
+                        return Ok(BinaryContent {
+                            bytes,
+                            content_type,
+                        });
                     }
 
                     let text = r.text().await.unwrap_or_default();
@@ -233,20 +359,21 @@ impl OdooHttpClient {
                         body: parsed_err,
                     };
 
-                    if status.is_server_error() || status.as_u16() == 429 {
+                    if status == StatusCode::TOO_MANY_REQUESTS {
+                        last_err = Some(self.throttle.on_throttled(retry_after, Instant::now()));
+                    } else if status.is_server_error() {
                         last_err = Some(err);
                     } else {
                         return Err(err);
                     }
                 }
                 Err(e) => {
-                    last_err = Some(OdooError::Http(e));
+                    last_err = Some(deadline::transport_error(e));
                 }
             }
 
             if attempt < self.max_retries {
-                let backoff_ms = 250u64.saturating_mul(2u64.saturating_pow(attempt as u32));
-                tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
+                deadline::backoff(attempt, last_err.as_ref()).await?;
             }
         }
 
@@ -386,6 +513,27 @@ impl OdooHttpClient {
         })
     }
 
+    /// Create several records in one `create(vals_list)` call; ids come back in input order.
+    pub async fn create_many(
+        &self,
+        model: &str,
+        vals_list: Vec<Value>,
+        context: Option<Value>,
+    ) -> OdooResult<Vec<i64>> {
+        let mut body = json!({ "vals_list": vals_list });
+        if let Some(ctx) = context {
+            body["context"] = ctx;
+        }
+        let v = self
+            .post_json2_raw(model, "create", body, RetryMode::Never)
+            .await?;
+        serde_json::from_value(v.clone()).map_err(|e| {
+            OdooError::InvalidResponse(format!(
+                "Expected created ids (array) from create: {e}. Got: {v}"
+            ))
+        })
+    }
+
     pub async fn write(
         &self,
         model: &str,
@@ -632,12 +780,34 @@ impl OdooHttpClient {
             .await
     }
 
+    /// Pacing state after Odoo answered 429, shared by clones of this client.
+    pub fn throttle(&self) -> &UpstreamThrottle {
+        &self.throttle
+    }
+
     pub async fn health_probe(&self) -> OdooResult<()> {
         self.search_count("ir.model", Some(json!([])), None)
             .await
             .map(|_| ())
     }
 
+    /// Probe at `depth` (see [`super::health`]); `standard` also returns the server version.
+    pub async fn health_probe_at(&self, depth: HealthDepth) -> OdooResult<Option<String>> {
+        match depth {
+            HealthDepth::Shallow => {
+                health::probe_reachable(&self.http, &self.base_url, &self.user_agent)
+                    .await
+                    .map(|_| None)
+            }
+            HealthDepth::Standard => {
+                health::probe_version(&self.http, &self.base_url, &self.user_agent)
+                    .await
+                    .map(Some)
+            }
+            HealthDepth::Deep => self.health_probe().await.map(|_| None),
+        }
+    }
+
     /// Health check: perform a minimal operation to verify Odoo is reachable.
     /// Uses search_count on ir.model with empty domain as a cheap probe.
     pub async fn health_check(&self) -> bool {
@@ -664,6 +834,11 @@ mod tests {
             tool_config: None,
             read_only: false,
             tags: Vec::new(),
+            headers: HashMap::new(),
+            proxy_auth: None,
+            lang: None,
+            translate_errors: false,
+            health_depth: None,
             aliases: Vec::new(),
             extra: HashMap::new(),
         }
@@ -722,6 +897,11 @@ mod tests {
             tool_config: None,
             read_only: false,
             tags: Vec::new(),
+            headers: HashMap::new(),
+            proxy_auth: None,
+            lang: None,
+            translate_errors: false,
+            health_depth: None,
             aliases: Vec::new(),
             extra: HashMap::new(),
         };
@@ -751,6 +931,11 @@ mod tests {
             tool_config: None,
             read_only: false,
             tags: Vec::new(),
+            headers: HashMap::new(),
+            proxy_auth: None,
+            lang: None,
+            translate_errors: false,
+            health_depth: None,
             aliases: Vec::new(),
             extra: HashMap::new(),
         };
@@ -831,6 +1016,11 @@ mod tests {
             tool_config: None,
             read_only: false,
             tags: Vec::new(),
+            headers: HashMap::new(),
+            proxy_auth: None,
+            lang: None,
+            translate_errors: false,
+            health_depth: None,
             aliases: Vec::new(),
             extra: HashMap::new(),
         };
@@ -853,4 +1043,36 @@ mod tests {
             other => panic!("expected API error, got {other:?}"),
         }
     }
+
+    #[tokio::test]
+    async fn injected_faults_go_through_retries_and_parsing() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!(5)))
+            .mount(&server)
+            .await;
+        let mut client = OdooHttpClient::new(&make_config(&server.uri(), Some("key"))).unwrap();
+        let faults = |error_percent, truncate_percent| FaultInjector {
+            error_percent,
+            delay_percent: 0.0,
+            delay: Duration::ZERO,
+            truncate_percent,
+        };
+
+        client.faults = Some(faults(100.0, 0.0));
+        let err = client
+            .search_count("res.partner", None, None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Injected fault"));
+        assert!(server.received_requests().await.unwrap().is_empty());
+
+        client.faults = Some(faults(0.0, 100.0));
+        let err = client.fields_get("res.partner", None).await.unwrap_err();
+        assert!(matches!(err, OdooError::InvalidResponse(_)));
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+    }
 }
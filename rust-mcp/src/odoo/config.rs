@@ -1,9 +1,23 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+use base64::Engine;
+use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderName, HeaderValue, PROXY_AUTHORIZATION};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use super::health::HealthDepthConfig;
+
+/// Headers the Odoo clients set themselves; instance `headers` may not override them.
+const RESERVED_HEADERS: &[&str] = &[
+    "authorization",
+    "content-type",
+    "content-length",
+    "host",
+    "user-agent",
+    "x-odoo-database",
+];
+
 fn is_false(value: &bool) -> bool {
     !value
 }
@@ -18,6 +32,49 @@ pub enum OdooAuthMode {
     Password,
 }
 
+/// Header carrying [`ProxyAuthConfig`] credentials.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProxyAuthHeader {
+    /// What a basic-auth protected reverse proxy (nginx `auth_basic`, Traefik `basicAuth`)
+    /// checks. Only usable with password (JSON-RPC) auth, since JSON-2 sends the API key in it.
+    #[default]
+    Authorization,
+    ProxyAuthorization,
+}
+
+impl ProxyAuthHeader {
+    fn name(self) -> HeaderName {
+        match self {
+            Self::Authorization => AUTHORIZATION,
+            Self::ProxyAuthorization => PROXY_AUTHORIZATION,
+        }
+    }
+}
+
+/// HTTP basic-auth credentials for a proxy in front of Odoo, sent on every request before
+/// (and independently of) the Odoo login.
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProxyAuthConfig {
+    pub username: String,
+    pub password: String,
+    #[serde(default, skip_serializing_if = "is_default_proxy_header")]
+    pub header: ProxyAuthHeader,
+}
+
+fn is_default_proxy_header(header: &ProxyAuthHeader) -> bool {
+    *header == ProxyAuthHeader::default()
+}
+
+impl std::fmt::Debug for ProxyAuthConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProxyAuthConfig")
+            .field("username", &self.username)
+            .field("header", &self.header)
+            .finish_non_exhaustive()
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct InstanceToolConfig {
     #[serde(
@@ -26,6 +83,13 @@ pub struct InstanceToolConfig {
         skip_serializing_if = "Vec::is_empty"
     )]
     pub disabled_tools: Vec<String>,
+    /// When non-empty, the only tools permitted on this instance.
+    #[serde(
+        default,
+        rename = "allowedTools",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub allowed_tools: Vec<String>,
     #[serde(
         default,
         rename = "disabledPacks",
@@ -50,8 +114,12 @@ pub struct ExecuteAllowlistEntry {
 }
 
 impl InstanceToolConfig {
+    /// Whether `tool_name` is listed in `disabledTools` or missing from a non-empty
+    /// `allowedTools`. Entries may use `*` as a wildcard (`odoo_search*`).
     pub fn is_tool_disabled(&self, tool_name: &str) -> bool {
-        self.disabled_tools.iter().any(|name| name == tool_name)
+        let matches = |pattern: &String| tool_name_matches(pattern, tool_name);
+        self.disabled_tools.iter().any(matches)
+            || (!self.allowed_tools.is_empty() && !self.allowed_tools.iter().any(matches))
     }
 
     pub fn tool_defaults(&self, tool_name: &str) -> Option<&Value> {
@@ -60,12 +128,34 @@ impl InstanceToolConfig {
 
     pub fn is_empty(&self) -> bool {
         self.disabled_tools.is_empty()
+            && self.allowed_tools.is_empty()
             && self.disabled_packs.is_empty()
             && self.defaults.is_empty()
             && self.execute_allowlist.is_empty()
     }
 }
 
+/// Match a tool name against an `allowedTools`/`disabledTools` entry, where `*` stands for any
+/// run of characters.
+pub fn tool_name_matches(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else {
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
 /// Protocol to use for Odoo communication.
 #[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -120,6 +210,33 @@ pub struct OdooInstanceConfig {
     /// Manual labels used by Config UI search and grouping.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tags: Vec<String>,
+    /// Extra HTTP headers sent with every request to this instance, e.g. the service token
+    /// headers of a Cloudflare Access or API gateway in front of Odoo.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// Basic auth for a proxy in front of Odoo (`proxyAuth`; `proxy_auth` is also accepted).
+    #[serde(
+        default,
+        rename = "proxyAuth",
+        alias = "proxy_auth",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub proxy_auth: Option<ProxyAuthConfig>,
+    /// Language sent as `context.lang` with every request that does not set one, e.g. `en_US`,
+    /// so errors (and translated field values) come back in it whatever the database default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+    /// Rewrite well-known localized Odoo errors into English (see `odoo::locale`).
+    #[serde(default, rename = "translateErrors", skip_serializing_if = "is_false")]
+    pub translate_errors: bool,
+    /// Probe depth for `/health` and `/readyz` (see `odoo::health`); the
+    /// `ODOO_HEALTH_DEPTH` / `ODOO_READYZ_DEPTH` defaults apply when unset.
+    #[serde(
+        default,
+        rename = "healthDepth",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub health_depth: Option<HealthDepthConfig>,
     /// Deprecated legacy input field. Still accepted so older configs remain readable,
     /// but it is ignored at runtime and omitted from any normalized output.
     #[serde(default, skip_serializing)]
@@ -161,6 +278,50 @@ impl OdooInstanceConfig {
         OdooAuthMode::ApiKey
     }
 
+    /// The instance's extra `headers` as a header map, rejecting invalid names or values and
+    /// headers the clients set themselves.
+    pub fn extra_headers(&self) -> anyhow::Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            let header = HeaderName::from_bytes(name.trim().as_bytes())
+                .map_err(|_| anyhow::anyhow!("Invalid header name '{name}'"))?;
+            if RESERVED_HEADERS.contains(&header.as_str()) {
+                anyhow::bail!("Header '{name}' is set by the Odoo client and cannot be overridden");
+            }
+            let mut value = HeaderValue::from_str(value.trim())
+                .map_err(|_| anyhow::anyhow!("Invalid value for header '{name}'"))?;
+            value.set_sensitive(true);
+            headers.insert(header, value);
+        }
+        Ok(headers)
+    }
+
+    /// Headers every request to the instance carries: [`Self::extra_headers`] and the
+    /// `proxyAuth` credentials. Proxy auth in `Authorization` is refused for JSON-2 instances,
+    /// whose API key travels in that header.
+    pub fn default_headers(&self) -> anyhow::Result<HeaderMap> {
+        let mut headers = self.extra_headers()?;
+        if let Some(proxy) = &self.proxy_auth {
+            if proxy.username.trim().is_empty() {
+                anyhow::bail!("proxyAuth.username must not be empty");
+            }
+            if proxy.header == ProxyAuthHeader::Authorization
+                && self.auth_mode() == OdooAuthMode::ApiKey
+            {
+                anyhow::bail!(
+                    "proxyAuth cannot use the Authorization header with API key (JSON-2) auth; \
+                     set \"header\": \"proxy-authorization\" if the proxy accepts it"
+                );
+            }
+            let credentials = base64::engine::general_purpose::STANDARD
+                .encode(format!("{}:{}", proxy.username, proxy.password));
+            let mut value = HeaderValue::from_str(&format!("Basic {credentials}"))?;
+            value.set_sensitive(true);
+            headers.insert(proxy.header.name(), value);
+        }
+        Ok(headers)
+    }
+
     pub fn username_looks_like_url(&self) -> bool {
         let Some(username) = self.username.as_deref() else {
             return false;
@@ -258,6 +419,15 @@ pub fn detect_runtime_instances_source() -> RuntimeInstancesSource {
     }
 }
 
+fn env_flag(name: &str) -> bool {
+    std::env::var(name).is_ok_and(|v| {
+        matches!(
+            v.trim().to_ascii_lowercase().as_str(),
+            "1" | "true" | "yes" | "y" | "on"
+        )
+    })
+}
+
 pub fn load_odoo_env() -> anyhow::Result<OdooEnvConfig> {
     let mut instances = HashMap::new();
     let mut instances_json_path: Option<String> = None;
@@ -326,6 +496,33 @@ pub fn load_odoo_env() -> anyhow::Result<OdooEnvConfig> {
         let username = std::env::var("ODOO_USERNAME").ok();
         let password = std::env::var("ODOO_PASSWORD").ok();
         let version = std::env::var("ODOO_VERSION").ok();
+        let proxy_auth = match (
+            std::env::var("ODOO_PROXY_AUTH_USERNAME"),
+            std::env::var("ODOO_PROXY_AUTH_PASSWORD"),
+        ) {
+            (Ok(username), Ok(password)) if !username.trim().is_empty() => Some(ProxyAuthConfig {
+                username,
+                password,
+                header: match std::env::var("ODOO_PROXY_AUTH_HEADER") {
+                    Ok(header) if !header.trim().is_empty() => {
+                        serde_json::from_value(Value::String(header.trim().to_lowercase()))
+                            .map_err(|_| {
+                                anyhow::anyhow!(
+                                    "ODOO_PROXY_AUTH_HEADER must be 'authorization' or 'proxy-authorization'"
+                                )
+                            })?
+                    }
+                    _ => ProxyAuthHeader::default(),
+                },
+            }),
+            _ => None,
+        };
+        let headers = match std::env::var("ODOO_EXTRA_HEADERS") {
+            Ok(raw) if !raw.trim().is_empty() => serde_json::from_str(&raw).map_err(|e| {
+                anyhow::anyhow!("ODOO_EXTRA_HEADERS must be a JSON object of strings: {e}")
+            })?,
+            _ => HashMap::new(),
+        };
 
         // Accept if we have URL + (api_key OR (username + password))
         let has_api_key = api_key
@@ -362,8 +559,15 @@ pub fn load_odoo_env() -> anyhow::Result<OdooEnvConfig> {
                         .ok()
                         .and_then(|v| v.parse().ok()),
                     tool_config: None,
-                    read_only: false,
+                    read_only: env_flag("ODOO_READ_ONLY"),
                     tags: Vec::new(),
+                    headers,
+                    proxy_auth,
+                    lang: std::env::var("ODOO_LANG")
+                        .ok()
+                        .filter(|lang| !lang.trim().is_empty()),
+                    translate_errors: env_flag("ODOO_TRANSLATE_ERRORS"),
+                    health_depth: None,
                     aliases: Vec::new(),
                     extra: HashMap::new(),
                 },
@@ -461,6 +665,11 @@ pub fn load_odoo_env() -> anyhow::Result<OdooEnvConfig> {
         }
     }
 
+    for (name, cfg) in &instances {
+        cfg.default_headers()
+            .map_err(|e| anyhow::anyhow!("Invalid headers for instance '{name}': {e}"))?;
+    }
+
     Ok(OdooEnvConfig { instances })
 }
 
@@ -540,6 +749,11 @@ mod tests {
             tool_config: None,
             read_only: false,
             tags: Vec::new(),
+            headers: HashMap::new(),
+            proxy_auth: None,
+            lang: None,
+            translate_errors: false,
+            health_depth: None,
             aliases: Vec::new(),
             extra: HashMap::new(),
         };
@@ -561,6 +775,11 @@ mod tests {
             tool_config: None,
             read_only: false,
             tags: Vec::new(),
+            headers: HashMap::new(),
+            proxy_auth: None,
+            lang: None,
+            translate_errors: false,
+            health_depth: None,
             aliases: Vec::new(),
             extra: HashMap::new(),
         };
@@ -582,6 +801,11 @@ mod tests {
             tool_config: None,
             read_only: false,
             tags: Vec::new(),
+            headers: HashMap::new(),
+            proxy_auth: None,
+            lang: None,
+            translate_errors: false,
+            health_depth: None,
             aliases: Vec::new(),
             extra: HashMap::new(),
         };
@@ -603,6 +827,11 @@ mod tests {
             tool_config: None,
             read_only: false,
             tags: Vec::new(),
+            headers: HashMap::new(),
+            proxy_auth: None,
+            lang: None,
+            translate_errors: false,
+            health_depth: None,
             aliases: Vec::new(),
             extra: HashMap::new(),
         };
@@ -694,6 +923,11 @@ mod tests {
             tool_config: None,
             read_only: false,
             tags: Vec::new(),
+            headers: HashMap::new(),
+            proxy_auth: None,
+            lang: None,
+            translate_errors: false,
+            health_depth: None,
             aliases: Vec::new(),
             extra: HashMap::new(),
         };
@@ -715,6 +949,11 @@ mod tests {
             tool_config: None,
             read_only: false,
             tags: Vec::new(),
+            headers: HashMap::new(),
+            proxy_auth: None,
+            lang: None,
+            translate_errors: false,
+            health_depth: None,
             aliases: Vec::new(),
             extra: HashMap::new(),
         };
@@ -760,6 +999,127 @@ mod tests {
         assert_eq!(source.path, Some(file_path));
     }
 
+    #[test]
+    fn test_tool_allowlist_and_denylist() {
+        assert!(tool_name_matches("odoo_search", "odoo_search"));
+        assert!(!tool_name_matches("odoo_search", "odoo_search_read"));
+        assert!(tool_name_matches("odoo_search*", "odoo_search_read"));
+        assert!(tool_name_matches("*_count", "odoo_count"));
+        assert!(tool_name_matches("odoo_*_read", "odoo_search_read"));
+        assert!(!tool_name_matches("odoo_*_read", "odoo_read"));
+        assert!(tool_name_matches("*", "anything"));
+
+        let config: InstanceToolConfig = serde_json::from_value(serde_json::json!({
+            "allowedTools": ["odoo_search*", "odoo_read", "odoo_count"],
+            "disabledTools": ["odoo_search_read"]
+        }))
+        .unwrap();
+        assert!(!config.is_tool_disabled("odoo_search"));
+        assert!(!config.is_tool_disabled("odoo_count"));
+        assert!(config.is_tool_disabled("odoo_search_read"));
+        assert!(config.is_tool_disabled("odoo_create"));
+        assert!(!InstanceToolConfig::default().is_tool_disabled("odoo_create"));
+    }
+
+    #[test]
+    fn test_load_odoo_env_reads_read_only_flags() {
+        let _env_lock = TEST_ENV_MUTEX.lock().unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("instances.json");
+        std::fs::write(
+            &file_path,
+            r#"{"prod":{"url":"http://prod","apiKey":"k","readOnly":true},"dev":{"url":"http://dev","apiKey":"k"}}"#,
+        )
+        .unwrap();
+        let _instances_json = EnvGuard::set(
+            "ODOO_INSTANCES_JSON",
+            Some(file_path.to_string_lossy().as_ref()),
+        );
+
+        let env = load_odoo_env().unwrap();
+        assert!(env.instances["prod"].read_only);
+        assert!(!env.instances["dev"].read_only);
+
+        // A missing instances file falls back to the single-instance env vars.
+        std::fs::remove_file(&file_path).unwrap();
+        let _url = EnvGuard::set("ODOO_URL", Some("http://single"));
+        let _api_key = EnvGuard::set("ODOO_API_KEY", Some("k"));
+        let _read_only = EnvGuard::set("ODOO_READ_ONLY", Some("true"));
+
+        let env = load_odoo_env().unwrap();
+        assert!(env.instances["default"].read_only);
+    }
+
+    #[test]
+    fn test_load_odoo_env_validates_instance_headers() {
+        let _env_lock = TEST_ENV_MUTEX.lock().unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("instances.json");
+        std::fs::write(
+            &file_path,
+            r#"{"prod":{"url":"http://prod","apiKey":"k","headers":{"CF-Access-Client-Id":"id","CF-Access-Client-Secret":"s"}}}"#,
+        )
+        .unwrap();
+        let _instances_json = EnvGuard::set(
+            "ODOO_INSTANCES_JSON",
+            Some(file_path.to_string_lossy().as_ref()),
+        );
+
+        let env = load_odoo_env().unwrap();
+        let headers = env.instances["prod"].extra_headers().unwrap();
+        assert_eq!(headers["cf-access-client-id"], "id");
+        assert!(headers["cf-access-client-secret"].is_sensitive());
+
+        std::fs::write(
+            &file_path,
+            r#"{"prod":{"url":"http://prod","apiKey":"k","headers":{"X-Odoo-Database":"other"}}}"#,
+        )
+        .unwrap();
+        let err = load_odoo_env().unwrap_err().to_string();
+        assert!(err.contains("Invalid headers for instance 'prod'"), "{err}");
+
+        // The single-instance env vars take headers as a JSON object.
+        std::fs::remove_file(&file_path).unwrap();
+        let _url = EnvGuard::set("ODOO_URL", Some("http://single"));
+        let _api_key = EnvGuard::set("ODOO_API_KEY", Some("k"));
+        let _headers = EnvGuard::set("ODOO_EXTRA_HEADERS", Some(r#"{"X-Api-Gateway-Key":"g"}"#));
+        let env = load_odoo_env().unwrap();
+        assert_eq!(env.instances["default"].headers["X-Api-Gateway-Key"], "g");
+    }
+
+    #[test]
+    fn test_proxy_auth_headers_depend_on_auth_mode() {
+        let config = |value: serde_json::Value| -> OdooInstanceConfig {
+            serde_json::from_value(value).unwrap()
+        };
+        let legacy = config(serde_json::json!({
+            "url": "https://staging.example.com",
+            "db": "staging",
+            "version": "17",
+            "username": "admin",
+            "password": "admin",
+            "proxy_auth": {"username": "gate", "password": "s3cret"}
+        }));
+        let headers = legacy.default_headers().unwrap();
+        // base64("gate:s3cret")
+        assert_eq!(headers[AUTHORIZATION], "Basic Z2F0ZTpzM2NyZXQ=");
+        assert!(!format!("{legacy:?}").contains("s3cret"));
+
+        let mut modern = config(serde_json::json!({
+            "url": "https://staging.example.com",
+            "apiKey": "k",
+            "proxyAuth": {"username": "gate", "password": "s3cret"}
+        }));
+        let err = modern.default_headers().unwrap_err().to_string();
+        assert!(err.contains("proxy-authorization"), "{err}");
+        modern.proxy_auth.as_mut().unwrap().header = ProxyAuthHeader::ProxyAuthorization;
+        let headers = modern.default_headers().unwrap();
+        assert_eq!(headers[PROXY_AUTHORIZATION], "Basic Z2F0ZTpzM2NyZXQ=");
+        assert!(headers.get(AUTHORIZATION).is_none());
+        let value = serde_json::to_value(&modern).unwrap();
+        assert_eq!(value["proxyAuth"]["header"], "proxy-authorization");
+    }
+
     #[test]
     fn test_instance_config_omits_aliases_when_serialized() {
         let config = OdooInstanceConfig {
@@ -775,6 +1135,11 @@ mod tests {
             tool_config: None,
             read_only: false,
             tags: vec!["prod".to_string()],
+            headers: HashMap::new(),
+            proxy_auth: None,
+            lang: None,
+            translate_errors: false,
+            health_depth: None,
             aliases: vec!["legacy-alias".to_string()],
             extra: HashMap::new(),
         };
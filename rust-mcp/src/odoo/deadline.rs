@@ -0,0 +1,129 @@
+//! Deadline of the MCP request an Odoo call is serving.
+//!
+//! The MCP handler runs each request inside [`scope`] with the earlier of the client's
+//! `_meta.timeoutMs` hint and the transport's request timeout. The HTTP clients cap each
+//! attempt's timeout at the time left and skip retries whose backoff would overrun it, failing
+//! with [`OdooError::DeadlineExceeded`] instead of a generic timeout.
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+use super::types::{OdooError, OdooResult};
+
+tokio::task_local! {
+    static DEADLINE: Instant;
+}
+
+/// Run `fut` with `deadline`; a deadline already in scope is only ever tightened.
+pub async fn scope<F: Future>(deadline: Option<Instant>, fut: F) -> F::Output {
+    let deadline = match (current(), deadline) {
+        (Some(outer), Some(inner)) => Some(outer.min(inner)),
+        (outer, inner) => outer.or(inner),
+    };
+    match deadline {
+        Some(deadline) => DEADLINE.scope(deadline, fut).await,
+        None => fut.await,
+    }
+}
+
+pub fn current() -> Option<Instant> {
+    DEADLINE.try_with(|deadline| *deadline).ok()
+}
+
+/// Deadline from a request's `_meta.timeoutMs`, counted from now.
+pub fn from_params(params: Option<&Value>) -> Option<Instant> {
+    let millis = params?.pointer("/_meta/timeoutMs")?.as_u64()?;
+    Some(Instant::now() + Duration::from_millis(millis))
+}
+
+fn exceeded(last_err: Option<&OdooError>) -> OdooError {
+    let last_error = match last_err {
+        Some(OdooError::DeadlineExceeded { last_error }) => last_error.clone(),
+        other => other.map(ToString::to_string),
+    };
+    OdooError::DeadlineExceeded { last_error }
+}
+
+/// Timeout for the next attempt: `default`, capped at the time left.
+pub fn attempt_timeout(default: Duration, last_err: Option<&OdooError>) -> OdooResult<Duration> {
+    let Some(deadline) = current() else {
+        return Ok(default);
+    };
+    match deadline.checked_duration_since(Instant::now()) {
+        Some(left) if !left.is_zero() => Ok(default.min(left)),
+        _ => Err(exceeded(last_err)),
+    }
+}
+
+/// A transport error, reported as the deadline when a capped timeout cut the attempt short.
+pub fn transport_error(err: reqwest::Error) -> OdooError {
+    if err.is_timeout() && current().is_some_and(|deadline| deadline <= Instant::now()) {
+        exceeded(None)
+    } else {
+        OdooError::Http(err)
+    }
+}
+
+/// Wait out the exponential backoff (250ms, 500ms, 1s, ...) before retrying `attempt`, or fail
+/// when no time would be left for another attempt afterwards.
+pub async fn backoff(attempt: usize, last_err: Option<&OdooError>) -> OdooResult<()> {
+    let delay = Duration::from_millis(250u64.saturating_mul(2u64.saturating_pow(attempt as u32)));
+    if current().is_some_and(|deadline| Instant::now() + delay >= deadline) {
+        return Err(exceeded(last_err));
+    }
+    tokio::time::sleep(delay).await;
+    Ok(())
+}
+
+/// Resolves when the deadline passes; never without one.
+pub async fn expired() {
+    match current() {
+        Some(deadline) => tokio::time::sleep_until(deadline.into()).await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn nested_scopes_keep_the_earlier_deadline() {
+        let soon = Instant::now() + Duration::from_secs(1);
+        let later = soon + Duration::from_secs(60);
+        assert_eq!(current(), None);
+        let inner = scope(Some(soon), scope(Some(later), async { current() })).await;
+        assert_eq!(inner, Some(soon));
+        let inherited = scope(Some(soon), scope(None, async { current() })).await;
+        assert_eq!(inherited, Some(soon));
+    }
+
+    #[tokio::test]
+    async fn retries_stop_when_the_backoff_would_overrun() {
+        assert!(backoff(0, None).await.is_ok());
+        let deadline = Instant::now() + Duration::from_millis(100);
+        scope(Some(deadline), async {
+            let last = OdooError::InvalidResponse("HTTP 503".into());
+            let err = backoff(0, Some(&last)).await.unwrap_err();
+            assert!(err.to_string().contains("HTTP 503"), "{err}");
+            let timeout = attempt_timeout(Duration::from_secs(30), None).unwrap();
+            assert!(timeout <= Duration::from_millis(100));
+        })
+        .await;
+        let spent = scope(Some(Instant::now()), async {
+            attempt_timeout(Duration::from_secs(30), None)
+        })
+        .await;
+        assert!(matches!(spent, Err(OdooError::DeadlineExceeded { .. })));
+    }
+
+    #[test]
+    fn timeout_hint_comes_from_meta() {
+        assert!(from_params(Some(&json!({"_meta": {"timeoutMs": 500}}))).is_some());
+        assert!(from_params(Some(&json!({"name": "x"}))).is_none());
+        assert!(from_params(None).is_none());
+    }
+}
@@ -0,0 +1,208 @@
+//! Fault injection for testing agents against an unreliable Odoo. Never enable in production.
+//!
+//! With `ODOO_FAULT_INJECTION=true`, every attempt of an upstream JSON-2 or JSON-RPC call rolls
+//! once against the configured percentages:
+//!
+//! - `ODOO_FAULT_ERROR_PERCENT`: the request is not sent and fails as an HTTP 503, so it goes
+//!   through the normal retry and backoff path.
+//! - `ODOO_FAULT_DELAY_PERCENT`: the request waits `ODOO_FAULT_DELAY_MS` (default 2000) first;
+//!   a delay reaching the attempt timeout fails the attempt as an HTTP 504 after the timeout.
+//! - `ODOO_FAULT_TRUNCATE_PERCENT`: the response body is cut in half before parsing.
+//!
+//! Binary downloads (reports, attachments) are not affected.
+
+use std::time::Duration;
+
+use rand::Rng;
+use tracing::{debug, warn};
+
+use super::types::{OdooError, OdooResult};
+
+pub const FAULT_INJECTION_ENV: &str = "ODOO_FAULT_INJECTION";
+const DEFAULT_DELAY: Duration = Duration::from_millis(2000);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Fault {
+    Error,
+    Delay,
+    Truncate,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FaultInjector {
+    pub error_percent: f64,
+    pub delay_percent: f64,
+    pub delay: Duration,
+    pub truncate_percent: f64,
+}
+
+fn percent_from_env(name: &str) -> f64 {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .filter(|p| p.is_finite())
+        .map(|p| p.clamp(0.0, 100.0))
+        .unwrap_or(0.0)
+}
+
+impl FaultInjector {
+    /// The injector configured by `ODOO_FAULT_*`, or `None` unless `ODOO_FAULT_INJECTION=true`
+    /// and at least one percentage is set.
+    pub fn from_env() -> Option<Self> {
+        let enabled = std::env::var(FAULT_INJECTION_ENV)
+            .map(|v| v.trim().eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        if !enabled {
+            return None;
+        }
+        let injector = Self {
+            error_percent: percent_from_env("ODOO_FAULT_ERROR_PERCENT"),
+            delay_percent: percent_from_env("ODOO_FAULT_DELAY_PERCENT"),
+            delay: std::env::var("ODOO_FAULT_DELAY_MS")
+                .ok()
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(DEFAULT_DELAY),
+            truncate_percent: percent_from_env("ODOO_FAULT_TRUNCATE_PERCENT"),
+        };
+        if injector.error_percent + injector.delay_percent + injector.truncate_percent == 0.0 {
+            return None;
+        }
+        warn!(
+            error_percent = injector.error_percent,
+            delay_percent = injector.delay_percent,
+            delay_ms = injector.delay.as_millis() as u64,
+            truncate_percent = injector.truncate_percent,
+            "Fault injection is enabled for Odoo calls; do not use this in production"
+        );
+        Some(injector)
+    }
+
+    /// Map a roll in `[0, 100)` to a fault; the percentages are stacked in a fixed order.
+    fn pick(&self, roll: f64) -> Option<Fault> {
+        let mut upper = self.error_percent;
+        if roll < upper {
+            return Some(Fault::Error);
+        }
+        upper += self.delay_percent;
+        if roll < upper {
+            return Some(Fault::Delay);
+        }
+        upper += self.truncate_percent;
+        (roll < upper).then_some(Fault::Truncate)
+    }
+
+    pub fn roll(&self) -> Option<Fault> {
+        self.pick(rand::rng().random_range(0.0..100.0))
+    }
+}
+
+/// Roll for one attempt before it is sent. Injected errors and delays are applied here; the
+/// returned fault is passed to [`after_receive`].
+pub async fn before_send(
+    injector: Option<&FaultInjector>,
+    timeout: Duration,
+) -> OdooResult<Option<Fault>> {
+    let Some(injector) = injector else {
+        return Ok(None);
+    };
+    let fault = injector.roll();
+    match fault {
+        Some(Fault::Error) => {
+            debug!("Injecting an upstream error");
+            Err(OdooError::Api {
+                status: 503,
+                message: "Injected fault: upstream error".to_string(),
+                body: None,
+            })
+        }
+        Some(Fault::Delay) if injector.delay >= timeout => {
+            debug!("Injecting an upstream timeout");
+            tokio::time::sleep(timeout).await;
+            Err(OdooError::Api {
+                status: 504,
+                message: format!(
+                    "Injected fault: no response within {}ms",
+                    timeout.as_millis()
+                ),
+                body: None,
+            })
+        }
+        Some(Fault::Delay) => {
+            debug!(
+                delay_ms = injector.delay.as_millis() as u64,
+                "Injecting a delay"
+            );
+            tokio::time::sleep(injector.delay).await;
+            Ok(fault)
+        }
+        _ => Ok(fault),
+    }
+}
+
+/// The response body as the caller sees it: cut in half for [`Fault::Truncate`].
+pub fn after_receive(fault: Option<Fault>, text: String) -> String {
+    if fault != Some(Fault::Truncate) {
+        return text;
+    }
+    debug!("Injecting a truncated response");
+    let mut end = text.len() / 2;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text[..end].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn injector(error: f64, delay: f64, truncate: f64) -> FaultInjector {
+        FaultInjector {
+            error_percent: error,
+            delay_percent: delay,
+            delay: Duration::from_millis(10),
+            truncate_percent: truncate,
+        }
+    }
+
+    #[test]
+    fn rolls_map_onto_stacked_percentages() {
+        let faults = injector(10.0, 20.0, 5.0);
+        assert_eq!(faults.pick(0.0), Some(Fault::Error));
+        assert_eq!(faults.pick(9.9), Some(Fault::Error));
+        assert_eq!(faults.pick(10.0), Some(Fault::Delay));
+        assert_eq!(faults.pick(34.9), Some(Fault::Truncate));
+        assert_eq!(faults.pick(35.0), None);
+        assert_eq!(injector(0.0, 0.0, 100.0).roll(), Some(Fault::Truncate));
+    }
+
+    #[tokio::test]
+    async fn delays_past_the_timeout_fail_the_attempt() {
+        let slow = injector(0.0, 100.0, 0.0);
+        assert_eq!(
+            before_send(Some(&slow), Duration::from_secs(1))
+                .await
+                .unwrap(),
+            Some(Fault::Delay)
+        );
+        let err = before_send(Some(&slow), Duration::from_millis(5))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, OdooError::Api { status: 504, .. }));
+        assert!(matches!(
+            before_send(Some(&injector(100.0, 0.0, 0.0)), Duration::from_secs(1)).await,
+            Err(OdooError::Api { status: 503, .. })
+        ));
+    }
+
+    #[test]
+    fn truncation_keeps_char_boundaries() {
+        assert_eq!(
+            after_receive(Some(Fault::Truncate), "{\"a\":1}".into()),
+            "{\"a"
+        );
+        assert_eq!(after_receive(Some(Fault::Truncate), "éé".into()), "é");
+        assert_eq!(after_receive(None, "{}".into()), "{}");
+    }
+}
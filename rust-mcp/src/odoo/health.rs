@@ -0,0 +1,201 @@
+//! How deep a health check probes an Odoo instance.
+//!
+//! - `shallow`: any HTTP answer below 500 from the instance URL. Checks the network path and
+//!   the reverse proxy only.
+//! - `standard`: `/web/webclient/version_info`, answered by Odoo itself without logging in.
+//! - `deep`: an authenticated `search_count` on `ir.model`. With password auth (Odoo < 19)
+//!   this logs in, taking a session on the server.
+//!
+//! `/health` and `/readyz` each take a depth from `ODOO_HEALTH_DEPTH` (default `deep`) and
+//! `ODOO_READYZ_DEPTH` (default `standard`); an instance's `healthDepth` overrides either,
+//! as one depth for both endpoints or as `{"health": ..., "readyz": ...}`.
+
+use std::str::FromStr;
+
+use reqwest::Url;
+use reqwest::header::{HeaderValue, USER_AGENT};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use super::types::{OdooError, OdooResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthDepth {
+    Shallow,
+    Standard,
+    Deep,
+}
+
+impl HealthDepth {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Shallow => "shallow",
+            Self::Standard => "standard",
+            Self::Deep => "deep",
+        }
+    }
+}
+
+impl FromStr for HealthDepth {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "shallow" => Ok(Self::Shallow),
+            "standard" => Ok(Self::Standard),
+            "deep" => Ok(Self::Deep),
+            other => Err(format!(
+                "Unknown health depth '{other}' (expected shallow, standard or deep)"
+            )),
+        }
+    }
+}
+
+/// HTTP endpoint a health check is made for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthEndpoint {
+    Health,
+    Readyz,
+}
+
+impl HealthEndpoint {
+    /// Depth for instances without their own `healthDepth`.
+    pub fn default_depth(self) -> HealthDepth {
+        let (var, fallback) = match self {
+            Self::Health => ("ODOO_HEALTH_DEPTH", HealthDepth::Deep),
+            Self::Readyz => ("ODOO_READYZ_DEPTH", HealthDepth::Standard),
+        };
+        match std::env::var(var) {
+            Ok(value) if !value.trim().is_empty() => value.parse().unwrap_or_else(|e| {
+                tracing::warn!("{var}: {e}; using {}", fallback.as_str());
+                fallback
+            }),
+            _ => fallback,
+        }
+    }
+}
+
+/// An instance's `healthDepth`: one depth, or one per endpoint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum HealthDepthConfig {
+    All(HealthDepth),
+    PerEndpoint(EndpointDepths),
+}
+
+/// Per-endpoint `healthDepth`. Unknown keys are rejected so a typo is not silently ignored.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EndpointDepths {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health: Option<HealthDepth>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub readyz: Option<HealthDepth>,
+}
+
+impl HealthDepthConfig {
+    pub fn for_endpoint(&self, endpoint: HealthEndpoint) -> Option<HealthDepth> {
+        match (self, endpoint) {
+            (Self::All(depth), _) => Some(*depth),
+            (Self::PerEndpoint(depths), HealthEndpoint::Health) => depths.health,
+            (Self::PerEndpoint(depths), HealthEndpoint::Readyz) => depths.readyz,
+        }
+    }
+}
+
+/// Depth to probe an instance with for `endpoint`.
+pub fn depth_for(config: Option<&HealthDepthConfig>, endpoint: HealthEndpoint) -> HealthDepth {
+    config
+        .and_then(|config| config.for_endpoint(endpoint))
+        .unwrap_or_else(|| endpoint.default_depth())
+}
+
+/// `shallow` probe: the instance URL answers HTTP without a server error.
+pub async fn probe_reachable(
+    http: &reqwest::Client,
+    base_url: &Url,
+    user_agent: &HeaderValue,
+) -> OdooResult<()> {
+    let response = http
+        .get(base_url.clone())
+        .header(USER_AGENT, user_agent.clone())
+        .send()
+        .await?;
+    let status = response.status();
+    if status.is_server_error() {
+        return Err(OdooError::Api {
+            status: status.as_u16(),
+            message: format!("{base_url} answered {status}"),
+            body: None,
+        });
+    }
+    Ok(())
+}
+
+/// `standard` probe: the Odoo server version from `/web/webclient/version_info`, which needs
+/// no login.
+pub async fn probe_version(
+    http: &reqwest::Client,
+    base_url: &Url,
+    user_agent: &HeaderValue,
+) -> OdooResult<String> {
+    let url = base_url
+        .join("/web/webclient/version_info")
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let response = http
+        .post(url)
+        .header(USER_AGENT, user_agent.clone())
+        .json(&json!({"jsonrpc": "2.0", "method": "call", "params": {}}))
+        .send()
+        .await?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(OdooError::Api {
+            status: status.as_u16(),
+            message: format!("version_info answered {status}"),
+            body: None,
+        });
+    }
+    let body: Value = response.json().await?;
+    body.pointer("/result/server_version")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| {
+            OdooError::InvalidResponse("version_info did not return a server_version".to_string())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn instance_depth_overrides_endpoint_defaults() {
+        let all: HealthDepthConfig = serde_json::from_value(json!("shallow")).unwrap();
+        assert_eq!(
+            depth_for(Some(&all), HealthEndpoint::Health),
+            HealthDepth::Shallow
+        );
+        assert_eq!(
+            depth_for(Some(&all), HealthEndpoint::Readyz),
+            HealthDepth::Shallow
+        );
+
+        let split: HealthDepthConfig =
+            serde_json::from_value(json!({"health": "standard"})).unwrap();
+        assert_eq!(
+            depth_for(Some(&split), HealthEndpoint::Health),
+            HealthDepth::Standard
+        );
+        assert_eq!(
+            split.for_endpoint(HealthEndpoint::Readyz),
+            None,
+            "falls back to ODOO_READYZ_DEPTH"
+        );
+
+        assert!(serde_json::from_value::<HealthDepthConfig>(json!("ping")).is_err());
+        assert!(serde_json::from_value::<HealthDepthConfig>(json!({"helth": "deep"})).is_err());
+        assert_eq!("Deep".parse::<HealthDepth>(), Ok(HealthDepth::Deep));
+    }
+}
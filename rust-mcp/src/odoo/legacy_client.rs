@@ -1,13 +1,22 @@
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use reqwest::StatusCode;
 use reqwest::header::{CONTENT_TYPE, HeaderMap, HeaderValue, USER_AGENT};
 use serde_json::{Value, json};
 use tokio::sync::RwLock;
+use tracing::Instrument;
 use url::Url;
 
+use super::client::read_body;
 use super::config::OdooInstanceConfig;
-use super::types::{OdooError, OdooErrorBody, OdooResult};
+use super::deadline;
+use super::faults::{self, FaultInjector};
+use super::health::{self, HealthDepth};
+use super::locale;
+use super::throttle::{self, UpstreamThrottle};
+use super::types::{BinaryContent, OdooError, OdooErrorBody, OdooResult, ReportFormat};
+use crate::telemetry;
 
 #[derive(Clone, Copy)]
 enum RetryMode {
@@ -25,6 +34,16 @@ pub struct OdooLegacyClient {
     password: String,
     http: reqwest::Client,
     max_retries: usize,
+    /// Per-attempt timeout; a request deadline may shorten it.
+    timeout: Duration,
+    /// Pacing after Odoo answered 429.
+    throttle: UpstreamThrottle,
+    /// Test-only failures injected into calls (`ODOO_FAULT_INJECTION`).
+    faults: Option<FaultInjector>,
+    user_agent: HeaderValue,
+    /// `context.lang` for calls that do not set one.
+    lang: Option<String>,
+    translate_errors: bool,
     /// Cached user ID after authentication
     uid: Arc<RwLock<Option<i64>>>,
 }
@@ -53,6 +72,7 @@ impl OdooLegacyClient {
         let http = reqwest::Client::builder()
             .timeout(timeout)
             .cookie_store(true)
+            .default_headers(cfg.default_headers()?)
             .build()?;
 
         Ok(Self {
@@ -62,6 +82,12 @@ impl OdooLegacyClient {
             password,
             http,
             max_retries,
+            timeout,
+            throttle: UpstreamThrottle::default(),
+            faults: FaultInjector::from_env(),
+            user_agent: HeaderValue::from_str(&telemetry::user_agent())?,
+            lang: cfg.lang.clone(),
+            translate_errors: cfg.translate_errors,
             uid: Arc::new(RwLock::new(None)),
         })
     }
@@ -72,10 +98,18 @@ impl OdooLegacyClient {
             CONTENT_TYPE,
             HeaderValue::from_static("application/json; charset=utf-8"),
         );
-        headers.insert(USER_AGENT, HeaderValue::from_static("odoo-mcp-rust/0.1"));
+        headers.insert(USER_AGENT, self.user_agent.clone());
         headers
     }
 
+    fn api_error(&self, err: OdooError) -> OdooError {
+        if self.translate_errors {
+            locale::translate_error(err)
+        } else {
+            err
+        }
+    }
+
     fn jsonrpc_endpoint(&self) -> Url {
         let mut url = self.base_url.clone();
         url.set_path("/jsonrpc");
@@ -115,20 +149,40 @@ impl OdooLegacyClient {
             RetryMode::Never => 0,
         };
         for attempt in 0..=max_retries {
-            let resp = self
-                .http
-                .post(url.clone())
-                .headers(headers.clone())
-                .json(&body)
-                .send()
-                .await;
+            let span = telemetry::odoo_http_span("POST", &url, attempt as u32);
+            self.throttle.acquire().await?;
+            let timeout = deadline::attempt_timeout(self.timeout, last_err.as_ref())?;
+            let fault = match faults::before_send(self.faults.as_ref(), timeout).await {
+                Ok(fault) => fault,
+                Err(err) => {
+                    last_err = Some(err);
+                    if attempt < max_retries {
+                        deadline::backoff(attempt, last_err.as_ref()).await?;
+                    }
+                    continue;
+                }
+            };
+            let resp = telemetry::propagate(
+                self.http
+                    .post(url.clone())
+                    .timeout(timeout)
+                    .headers(headers.clone())
+                    .json(&body),
+            )
+            .send()
+            .instrument(span.clone())
+            .await;
+            telemetry::record_response(&span, &resp);
 
             match resp {
                 Ok(r) => {
                     let status = r.status();
+                    let retry_after = throttle::retry_after(r.headers());
                     let text = r.text().await.unwrap_or_default();
+                    let text = faults::after_receive(fault, text);
 
                     if status.is_success() {
+                        self.throttle.on_success();
                         let v: Value = serde_json::from_str(&text).map_err(|e| {
                             OdooError::InvalidResponse(format!(
                                 "Failed to parse JSON-RPC response: {e}. Body: {text}"
@@ -145,11 +199,15 @@ impl OdooLegacyClient {
                                 .unwrap_or("Unknown JSON-RPC error")
                                 .to_string();
 
-                            return Err(OdooError::Api {
+                            // `data` carries the exception class name and its message.
+                            let body = error
+                                .get("data")
+                                .and_then(|d| serde_json::from_value(d.clone()).ok());
+                            return Err(self.api_error(OdooError::Api {
                                 status: 400,
                                 message,
-                                body: None,
-                            });
+                                body,
+                            }));
                         }
 
                         // Extract result. Some Odoo button/action methods return Python None
@@ -164,26 +222,27 @@ impl OdooLegacyClient {
                         .as_ref()
                         .and_then(|b| b.message.clone())
                         .unwrap_or_else(|| text.clone());
-                    let err = OdooError::Api {
+                    let err = self.api_error(OdooError::Api {
                         status: status.as_u16(),
                         message,
                         body: parsed_err,
-                    };
+                    });
 
-                    if status.is_server_error() || status.as_u16() == 429 {
+                    if status == StatusCode::TOO_MANY_REQUESTS {
+                        last_err = Some(self.throttle.on_throttled(retry_after, Instant::now()));
+                    } else if status.is_server_error() {
                         last_err = Some(err);
                     } else {
                         return Err(err);
                     }
                 }
                 Err(e) => {
-                    last_err = Some(OdooError::Http(e));
+                    last_err = Some(deadline::transport_error(e));
                 }
             }
 
             if attempt < max_retries {
-                let backoff_ms = 250u64.saturating_mul(2u64.saturating_pow(attempt as u32));
-                tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
+                deadline::backoff(attempt, last_err.as_ref()).await?;
             }
         }
 
@@ -250,6 +309,10 @@ impl OdooLegacyClient {
 
         // execute_kw always expects 7 arguments: [db, uid, password, model, method, args, kwargs]
         // kwargs must be an object (even if empty) for proper Odoo execution
+        let mut kwargs = kwargs.unwrap_or_else(|| json!({}));
+        if let Some(lang) = &self.lang {
+            locale::apply_lang(&mut kwargs, lang);
+        }
         let call_args = vec![
             json!(self.db),
             json!(uid),
@@ -257,7 +320,7 @@ impl OdooLegacyClient {
             json!(model),
             json!(method),
             args,
-            kwargs.unwrap_or_else(|| json!({})),
+            kwargs,
         ];
 
         self.jsonrpc_call("object", "execute_kw", json!(call_args), retry_mode)
@@ -385,6 +448,33 @@ impl OdooLegacyClient {
         })
     }
 
+    /// Create several records in one `create(vals_list)` call; ids come back in input order.
+    pub async fn create_many(
+        &self,
+        model: &str,
+        vals_list: Vec<Value>,
+        context: Option<Value>,
+    ) -> OdooResult<Vec<i64>> {
+        let count = vals_list.len();
+        let result = self
+            .execute_kw(
+                model,
+                "create",
+                json!([vals_list]),
+                Self::kwargs_with_context(None, context),
+                RetryMode::Never,
+            )
+            .await?;
+        // Models without multi-create support answer a single id for a one-record list.
+        match result {
+            Value::Number(_) if count == 1 => serde_json::from_value(json!([result])),
+            _ => serde_json::from_value(result),
+        }
+        .map_err(|e| {
+            OdooError::InvalidResponse(format!("Expected created ids (array) from create: {e}"))
+        })
+    }
+
     pub async fn write(
         &self,
         model: &str,
@@ -481,16 +571,47 @@ impl OdooLegacyClient {
     }
 
     pub async fn download_report_pdf(&self, report_name: &str, ids: &[i64]) -> OdooResult<Vec<u8>> {
-        let _uid = self.authenticate().await?;
+        self.download_report(report_name, ids, ReportFormat::Pdf)
+            .await
+            .map(|content| content.bytes)
+    }
 
-        // For legacy Odoo, we use the web controller for reports
+    /// Render a report for `ids` through the web controller of `format`.
+    pub async fn download_report(
+        &self,
+        report_name: &str,
+        ids: &[i64],
+        format: ReportFormat,
+    ) -> OdooResult<BinaryContent> {
         let mut url = self.base_url.clone();
         let ids_csv = ids
             .iter()
             .map(|i| i.to_string())
             .collect::<Vec<_>>()
             .join(",");
-        url.set_path(&format!("/report/pdf/{report_name}/{ids_csv}"));
+        url.set_path(&format!(
+            "/report/{}/{report_name}/{ids_csv}",
+            format.converter()
+        ));
+        self.download(url, None).await
+    }
+
+    /// Download the binary content of an `ir.attachment` through `/web/content`, failing once
+    /// the body grows past `max_bytes`.
+    pub async fn download_attachment(
+        &self,
+        id: i64,
+        max_bytes: usize,
+    ) -> OdooResult<BinaryContent> {
+        let mut url = self.base_url.clone();
+        url.set_path(&format!("/web/content/{id}"));
+        url.set_query(Some("download=true"));
+        self.download(url, Some(max_bytes)).await
+    }
+
+    /// GET a web controller URL after opening a web session.
+    async fn download(&self, url: Url, max_bytes: Option<usize>) -> OdooResult<BinaryContent> {
+        let _uid = self.authenticate().await?;
 
         // We need to authenticate via session first
         // This is a simplified approach - in production you might need session cookies
@@ -514,31 +635,59 @@ impl OdooLegacyClient {
                 "id": 1
             });
 
-            let session_resp = self
-                .http
-                .post(session_url)
-                .headers(self.headers())
-                .json(&session_body)
-                .send()
-                .await;
+            let span = telemetry::odoo_http_span("POST", &session_url, attempt as u32);
+            self.throttle.acquire().await?;
+            let timeout = deadline::attempt_timeout(self.timeout, last_err.as_ref())?;
+            let session_resp = telemetry::propagate(
+                self.http
+                    .post(session_url.clone())
+                    .timeout(timeout)
+                    .headers(self.headers())
+                    .json(&session_body),
+            )
+            .send()
+            .instrument(span.clone())
+            .await;
+            telemetry::record_response(&span, &session_resp);
 
             if let Err(e) = session_resp {
-                last_err = Some(OdooError::Http(e));
+                last_err = Some(deadline::transport_error(e));
                 if attempt < self.max_retries {
-                    let backoff_ms = 250u64.saturating_mul(2u64.saturating_pow(attempt as u32));
-                    tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
+                    deadline::backoff(attempt, last_err.as_ref()).await?;
                 }
                 continue;
             }
 
             // Now fetch the report
-            let resp = self.http.get(url.clone()).send().await;
+            let span = telemetry::odoo_http_span("GET", &url, attempt as u32);
+            self.throttle.acquire().await?;
+            let timeout = deadline::attempt_timeout(self.timeout, last_err.as_ref())?;
+            let resp = telemetry::propagate(
+                self.http
+                    .get(url.clone())
+                    .timeout(timeout)
+                    .header(USER_AGENT, self.user_agent.clone()),
+            )
+            .send()
+            .instrument(span.clone())
+            .await;
+            telemetry::record_response(&span, &resp);
             match resp {
                 Ok(r) => {
                     let status = r.status();
+                    let retry_after = throttle::retry_after(r.headers());
                     if status.is_success() {
-                        let bytes = r.bytes().await.map_err(OdooError::Http)?;
-                        return Ok(bytes.to_vec());
+                        self.throttle.on_success();
+                        let content_type = r
+                            .headers()
+                            .get(CONTENT_TYPE)
+                            .and_then(|v| v.to_str().ok())
+                            .map(str::to_owned);
+                        let bytes = read_body(r, max_bytes).await?;
+                        return Ok(BinaryContent {
+                            bytes,
+                            content_type,
+                        });
                     }
 
                     let text = r.text().await.unwrap_or_default();
@@ -548,20 +697,21 @@ impl OdooLegacyClient {
                         body: None,
                     };
 
-                    if status.is_server_error() || status.as_u16() == 429 {
+                    if status == StatusCode::TOO_MANY_REQUESTS {
+                        last_err = Some(self.throttle.on_throttled(retry_after, Instant::now()));
+                    } else if status.is_server_error() {
                         last_err = Some(err);
                     } else {
                         return Err(err);
                     }
                 }
                 Err(e) => {
-                    last_err = Some(OdooError::Http(e));
+                    last_err = Some(deadline::transport_error(e));
                 }
             }
 
             if attempt < self.max_retries {
-                let backoff_ms = 250u64.saturating_mul(2u64.saturating_pow(attempt as u32));
-                tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
+                deadline::backoff(attempt, last_err.as_ref()).await?;
             }
         }
 
@@ -707,12 +857,34 @@ impl OdooLegacyClient {
         .await
     }
 
+    /// Pacing state after Odoo answered 429, shared by clones of this client.
+    pub fn throttle(&self) -> &UpstreamThrottle {
+        &self.throttle
+    }
+
     pub async fn health_probe(&self) -> OdooResult<()> {
         self.search_count("ir.model", Some(json!([])), None)
             .await
             .map(|_| ())
     }
 
+    /// Probe at `depth` (see [`super::health`]); `standard` also returns the server version.
+    pub async fn health_probe_at(&self, depth: HealthDepth) -> OdooResult<Option<String>> {
+        match depth {
+            HealthDepth::Shallow => {
+                health::probe_reachable(&self.http, &self.base_url, &self.user_agent)
+                    .await
+                    .map(|_| None)
+            }
+            HealthDepth::Standard => {
+                health::probe_version(&self.http, &self.base_url, &self.user_agent)
+                    .await
+                    .map(Some)
+            }
+            HealthDepth::Deep => self.health_probe().await.map(|_| None),
+        }
+    }
+
     /// Health check: perform a minimal operation to verify Odoo is reachable.
     /// Uses search_count on ir.model with empty domain as a cheap probe.
     pub async fn health_check(&self) -> bool {
@@ -744,6 +916,11 @@ mod tests {
             tool_config: None,
             read_only: false,
             tags: Vec::new(),
+            headers: HashMap::new(),
+            proxy_auth: None,
+            lang: None,
+            translate_errors: false,
+            health_depth: None,
             aliases: Vec::new(),
             extra: HashMap::new(),
         }
@@ -832,6 +1009,11 @@ mod tests {
             tool_config: None,
             read_only: false,
             tags: Vec::new(),
+            headers: HashMap::new(),
+            proxy_auth: None,
+            lang: None,
+            translate_errors: false,
+            health_depth: None,
             aliases: Vec::new(),
             extra: HashMap::new(),
         };
@@ -0,0 +1,193 @@
+//! Request language and English error text for instances whose database speaks another language.
+//!
+//! Odoo translates messages into the language of the request context, falling back to the
+//! user's language. An instance's `lang` is sent as `context.lang` with every request that does
+//! not choose one, so Odoo answers in it directly. Some errors are raised before the context
+//! applies or by code that ignores it; with `translateErrors` the clients rewrite the ones they
+//! recognize: messages from [`KNOWN_MESSAGES`] get their English text in front of the original,
+//! other errors of a known exception class get an English label naming the class.
+
+use serde_json::{Value, json};
+
+use super::types::{OdooError, OdooErrorBody};
+
+/// English text of common Odoo messages, with lowercase fragments of their translations.
+const KNOWN_MESSAGES: &[(&str, &[&str])] = &[
+    (
+        "Record does not exist or has been deleted.",
+        &[
+            "n'existe pas ou a été supprimé",
+            "no existe o ha sido eliminado",
+            "existiert nicht oder wurde gelöscht",
+            "bestaat niet of is verwijderd",
+            "non esiste o è stato eliminato",
+            "não existe ou foi excluído",
+            "tidak ada atau telah dihapus",
+        ],
+    ),
+    (
+        "The operation cannot be completed: a mandatory field is not set, or another model requires the record being deleted.",
+        &[
+            "l'opération ne peut pas être effectuée",
+            "no se puede completar la operación",
+            "der vorgang kann nicht abgeschlossen werden",
+            "de bewerking kan niet worden voltooid",
+            "l'operazione non può essere completata",
+            "a operação não pode ser concluída",
+            "operasi tidak dapat diselesaikan",
+        ],
+    ),
+    (
+        "You are not allowed to access this document.",
+        &[
+            "vous n'êtes pas autorisé à accéder",
+            "no tiene permitido acceder",
+            "sie dürfen nicht auf",
+            "u heeft geen toegang",
+            "non hai i permessi per accedere",
+            "você não tem permissão para acessar",
+            "anda tidak diizinkan untuk mengakses",
+        ],
+    ),
+    (
+        "Access Denied",
+        &[
+            "accès refusé",
+            "acceso denegado",
+            "zugriff verweigert",
+            "toegang geweigerd",
+            "accesso negato",
+            "acesso negado",
+            "akses ditolak",
+        ],
+    ),
+];
+
+/// English label of Odoo's user-facing exception classes, by class name.
+const EXCEPTION_LABELS: &[(&str, &str)] = &[
+    ("AccessDenied", "Access denied"),
+    ("AccessError", "Access error"),
+    ("MissingError", "Missing record"),
+    ("ValidationError", "Validation error"),
+    ("UserError", "User error"),
+    ("RedirectWarning", "Warning"),
+    ("CacheMiss", "Missing value"),
+];
+
+/// Set `context.lang` in a JSON-2 body or JSON-RPC kwargs unless the caller chose a language.
+pub fn apply_lang(params: &mut Value, lang: &str) {
+    let Some(params) = params.as_object_mut() else {
+        return;
+    };
+    let context = params.entry("context").or_insert_with(|| json!({}));
+    if let Some(context) = context.as_object_mut() {
+        context
+            .entry("lang")
+            .or_insert_with(|| Value::String(lang.to_string()));
+    }
+}
+
+/// `message` in English when it is a known localized message or `name` a known exception class.
+pub fn translate_message(message: &str, name: Option<&str>) -> Option<String> {
+    let lower = message.to_lowercase();
+    if let Some((english, _)) = KNOWN_MESSAGES
+        .iter()
+        .find(|(_, fragments)| fragments.iter().any(|f| lower.contains(f)))
+    {
+        return Some(format!("{english} ({message})"));
+    }
+    let class = name?.rsplit('.').next()?;
+    let (_, label) = EXCEPTION_LABELS.iter().find(|(c, _)| *c == class)?;
+    if lower.starts_with(&label.to_lowercase()) {
+        return None;
+    }
+    Some(format!("{label}: {message}"))
+}
+
+/// `error` with its message translated by [`translate_message`]; the original text stays in the
+/// error body.
+pub fn translate_error(error: OdooError) -> OdooError {
+    match error {
+        OdooError::Api {
+            status,
+            message,
+            body,
+        } => {
+            let name = body.as_ref().and_then(|body| body.name.as_deref());
+            match translate_message(&message, name) {
+                Some(translated) => OdooError::Api {
+                    status,
+                    body: Some(body.unwrap_or_else(|| OdooErrorBody {
+                        name: None,
+                        message: Some(message),
+                        arguments: Vec::new(),
+                        context: Value::Null,
+                        debug: None,
+                    })),
+                    message: translated,
+                },
+                None => OdooError::Api {
+                    status,
+                    message,
+                    body,
+                },
+            }
+        }
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lang_is_added_without_overriding_the_caller() {
+        let mut body = json!({"ids": [1]});
+        apply_lang(&mut body, "en_US");
+        assert_eq!(body["context"], json!({"lang": "en_US"}));
+
+        let mut body = json!({"context": {"lang": "fr_FR", "tz": "Europe/Paris"}});
+        apply_lang(&mut body, "en_US");
+        assert_eq!(body["context"]["lang"], "fr_FR");
+    }
+
+    #[test]
+    fn known_messages_and_exception_classes_are_translated() {
+        assert_eq!(
+            translate_message(
+                "L'enregistrement n'existe pas ou a été supprimé.\n(Enregistrement : sale.order(5,))",
+                Some("odoo.exceptions.MissingError"),
+            )
+            .unwrap(),
+            "Record does not exist or has been deleted. (L'enregistrement n'existe pas ou a été supprimé.\n(Enregistrement : sale.order(5,)))"
+        );
+        assert_eq!(
+            translate_message(
+                "Tanggal akhir harus setelah tanggal mulai",
+                Some("odoo.exceptions.ValidationError"),
+            )
+            .unwrap(),
+            "Validation error: Tanggal akhir harus setelah tanggal mulai"
+        );
+        assert_eq!(
+            translate_message("Something odd", Some("builtins.KeyError")),
+            None
+        );
+        assert_eq!(translate_message("Something odd", None), None);
+    }
+
+    #[test]
+    fn translated_errors_keep_the_original_message() {
+        let error = translate_error(OdooError::Api {
+            status: 400,
+            message: "Akses ditolak".to_string(),
+            body: None,
+        });
+        let OdooError::Api { message, body, .. } = error else {
+            panic!("expected an API error");
+        };
+        assert_eq!(message, "Access Denied (Akses ditolak)");
+        assert_eq!(body.unwrap().message.as_deref(), Some("Akses ditolak"));
+    }
+}
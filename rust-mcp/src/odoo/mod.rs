@@ -1,5 +1,10 @@
 pub mod client;
 pub mod config;
+pub mod deadline;
+pub mod faults;
+pub mod health;
 pub mod legacy_client;
+pub mod locale;
+pub mod throttle;
 pub mod types;
 pub mod unified_client;
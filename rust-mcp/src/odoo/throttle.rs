@@ -0,0 +1,228 @@
+//! Adaptive pacing of requests to an Odoo instance that answers HTTP 429.
+//!
+//! Every client carries an [`UpstreamThrottle`]. A 429 from Odoo or a reverse proxy starts a
+//! cool-down taken from `Retry-After` (seconds or an HTTP date, default 1s) and paces the client
+//! at a request rate that halves on each further 429 and creeps back up with every success
+//! until pacing is lifted. Requests wait for their slot before being sent. When the wait would
+//! run past the request deadline or [`MAX_WAIT`], the call fails with
+//! [`OdooError::UpstreamThrottled`] instead of a raw HTTP error.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use serde::Serialize;
+
+use super::deadline;
+use super::types::{OdooError, OdooResult};
+
+/// Longest a request waits for the throttle before giving up.
+pub const MAX_WAIT: Duration = Duration::from_secs(10);
+const DEFAULT_COOL_DOWN: Duration = Duration::from_secs(1);
+/// Requests per second after the first 429.
+const INITIAL_RATE: f64 = 4.0;
+const MIN_RATE: f64 = 0.25;
+/// Rate gained per successful request; pacing is lifted once it reaches [`LIFT_RATE`].
+const RATE_STEP: f64 = 0.25;
+const LIFT_RATE: f64 = 16.0;
+
+/// `Retry-After` as a delay from now, from delta-seconds or an HTTP date.
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = DateTime::parse_from_rfc2822(value)
+        .ok()?
+        .with_timezone(&Utc);
+    Some((at - Utc::now()).to_std().unwrap_or(Duration::ZERO))
+}
+
+#[derive(Debug, Default)]
+struct State {
+    cool_down_until: Option<Instant>,
+    /// Requests per second while pacing; `None` when not throttled.
+    rate: Option<f64>,
+    next_slot: Option<Instant>,
+    throttled_total: u64,
+}
+
+/// Throttle state reported by `/health`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThrottleStatus {
+    /// Whether requests are currently paced or held back.
+    pub throttled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_in_secs: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_per_sec: Option<f64>,
+    /// 429 responses seen since the client was created.
+    pub throttled_total: u64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct UpstreamThrottle {
+    state: Arc<Mutex<State>>,
+}
+
+impl UpstreamThrottle {
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Record a 429 and return the error describing it.
+    pub fn on_throttled(&self, retry_after: Option<Duration>, now: Instant) -> OdooError {
+        let cool_down = retry_after.unwrap_or(DEFAULT_COOL_DOWN);
+        let mut state = self.lock();
+        state.throttled_total += 1;
+        state.cool_down_until = Some(now + cool_down);
+        state.rate = Some(
+            state
+                .rate
+                .map_or(INITIAL_RATE, |rate| (rate / 2.0).max(MIN_RATE)),
+        );
+        tracing::warn!(
+            retry_after_ms = cool_down.as_millis() as u64,
+            rate_per_sec = state.rate,
+            "Odoo throttled the request (HTTP 429)"
+        );
+        OdooError::UpstreamThrottled {
+            retry_after_secs: cool_down.as_secs_f64().ceil() as u64,
+        }
+    }
+
+    /// Record a successful response, easing the pacing.
+    pub fn on_success(&self) {
+        let mut state = self.lock();
+        if let Some(rate) = state.rate {
+            let rate = rate + RATE_STEP;
+            state.rate = (rate < LIFT_RATE).then_some(rate);
+            if state.rate.is_none() {
+                state.next_slot = None;
+            }
+        }
+    }
+
+    /// Reserve the next send slot, returning how long to wait for it.
+    fn reserve(&self, now: Instant) -> Duration {
+        let mut state = self.lock();
+        let Some(rate) = state.rate else {
+            return Duration::ZERO;
+        };
+        let earliest = [state.cool_down_until, state.next_slot]
+            .into_iter()
+            .flatten()
+            .fold(now, Instant::max);
+        state.next_slot = Some(earliest + Duration::from_secs_f64(1.0 / rate));
+        earliest - now
+    }
+
+    /// Wait for a send slot, or fail when it is further away than the deadline or [`MAX_WAIT`].
+    pub async fn acquire(&self) -> OdooResult<()> {
+        let now = Instant::now();
+        let wait = self.reserve(now);
+        if wait.is_zero() {
+            return Ok(());
+        }
+        let past_deadline = deadline::current().is_some_and(|deadline| now + wait >= deadline);
+        if wait > MAX_WAIT || past_deadline {
+            return Err(OdooError::UpstreamThrottled {
+                retry_after_secs: wait.as_secs_f64().ceil() as u64,
+            });
+        }
+        tokio::time::sleep(wait).await;
+        Ok(())
+    }
+
+    pub fn status(&self, now: Instant) -> ThrottleStatus {
+        let state = self.lock();
+        let retry_in = state
+            .cool_down_until
+            .and_then(|until| until.checked_duration_since(now))
+            .filter(|left| !left.is_zero());
+        ThrottleStatus {
+            throttled: state.rate.is_some(),
+            retry_in_secs: retry_in.map(|left| left.as_secs_f64().ceil() as u64),
+            rate_per_sec: state.rate,
+            throttled_total: state.throttled_total,
+        }
+    }
+
+    /// Whether the cool-down after a 429 is still running.
+    pub fn is_cooling_down(&self, now: Instant) -> bool {
+        self.lock().cool_down_until.is_some_and(|until| until > now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn retry_after_accepts_seconds_and_dates() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("7"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(7)));
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(retry_after(&headers), Some(Duration::ZERO));
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("soon"));
+        assert_eq!(retry_after(&headers), None);
+    }
+
+    #[test]
+    fn rate_halves_on_throttling_and_recovers_with_successes() {
+        let throttle = UpstreamThrottle::default();
+        let now = Instant::now();
+        assert_eq!(throttle.reserve(now), Duration::ZERO);
+
+        let error = throttle.on_throttled(Some(Duration::from_secs(2)), now);
+        assert_eq!(
+            error.to_string(),
+            "Odoo is throttling requests (HTTP 429); retry in 2s"
+        );
+        assert_eq!(throttle.status(now).rate_per_sec, Some(INITIAL_RATE));
+        assert_eq!(throttle.status(now).retry_in_secs, Some(2));
+        assert!(throttle.is_cooling_down(now));
+        // The cool-down comes first, then slots are spaced at the paced rate.
+        assert_eq!(throttle.reserve(now), Duration::from_secs(2));
+        assert_eq!(throttle.reserve(now), Duration::from_millis(2250));
+
+        throttle.on_throttled(None, now);
+        assert_eq!(throttle.status(now).rate_per_sec, Some(INITIAL_RATE / 2.0));
+        assert_eq!(throttle.status(now).throttled_total, 2);
+
+        for _ in 0..100 {
+            throttle.on_success();
+        }
+        let later = now + Duration::from_secs(5);
+        assert!(!throttle.status(later).throttled);
+        assert_eq!(throttle.reserve(later), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn acquire_gives_up_past_the_deadline() {
+        let throttle = UpstreamThrottle::default();
+        throttle.on_throttled(Some(Duration::from_secs(5)), Instant::now());
+        let result = deadline::scope(
+            Some(Instant::now() + Duration::from_secs(1)),
+            throttle.acquire(),
+        )
+        .await;
+        assert!(matches!(
+            result,
+            Err(OdooError::UpstreamThrottled {
+                retry_after_secs: 5
+            })
+        ));
+
+        throttle.on_throttled(Some(Duration::from_secs(60)), Instant::now());
+        assert!(throttle.acquire().await.is_err());
+    }
+}
@@ -26,10 +26,83 @@ pub enum OdooError {
 
     #[error("Invalid response: {0}")]
     InvalidResponse(String),
+
+    #[error("Request cancelled")]
+    Cancelled,
+
+    /// The request's deadline passed, or would pass before another attempt could finish.
+    #[error(
+        "Deadline exceeded{}",
+        .last_error.as_ref().map(|e| format!(" (last attempt: {e})")).unwrap_or_default()
+    )]
+    DeadlineExceeded { last_error: Option<String> },
+
+    /// Odoo or a proxy in front of it answered 429 and the client is still backing off.
+    #[error("Odoo is throttling requests (HTTP 429); retry in {retry_after_secs}s")]
+    UpstreamThrottled { retry_after_secs: u64 },
+
+    /// Tool arguments that do not match the tool's inputSchema, one entry per violation.
+    #[error("Invalid arguments: {}", .0.join("; "))]
+    InvalidArguments(Vec<String>),
 }
 
 pub type OdooResult<T> = Result<T, OdooError>;
 
+/// Body of a binary download from an Odoo web controller.
+#[derive(Debug, Clone)]
+pub struct BinaryContent {
+    pub bytes: Vec<u8>,
+    /// `Content-Type` of the response, when Odoo sent one.
+    pub content_type: Option<String>,
+}
+
+/// Output format of a report download, named after `ir.actions.report.report_type`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    Pdf,
+    Html,
+    Text,
+    /// Spreadsheet reports of the OCA `report_xlsx` module.
+    Xlsx,
+}
+
+impl ReportFormat {
+    /// Accepts the `report_type` names (`qweb-pdf`, ...) and their short forms (`pdf`, ...).
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "qweb-pdf" | "pdf" => Some(Self::Pdf),
+            "qweb-html" | "html" => Some(Self::Html),
+            "qweb-text" | "text" => Some(Self::Text),
+            "xlsx" => Some(Self::Xlsx),
+            _ => None,
+        }
+    }
+
+    pub fn report_type(self) -> &'static str {
+        match self {
+            Self::Pdf => "qweb-pdf",
+            Self::Html => "qweb-html",
+            Self::Text => "qweb-text",
+            Self::Xlsx => "xlsx",
+        }
+    }
+
+    /// The converter segment of Odoo's `/report/<converter>/<report_name>/<ids>` route.
+    pub fn converter(self) -> &'static str {
+        match self {
+            Self::Pdf => "pdf",
+            Self::Html => "html",
+            Self::Text => "text",
+            Self::Xlsx => "xlsx",
+        }
+    }
+
+    /// Whether the report is text the caller can read directly rather than a binary file.
+    pub fn is_text(self) -> bool {
+        matches!(self, Self::Html | Self::Text)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -63,6 +136,18 @@ mod tests {
         assert!(err.to_string().contains("Unauthorized"));
     }
 
+    #[test]
+    fn test_report_format_parse() {
+        assert_eq!(ReportFormat::parse("qweb-html"), Some(ReportFormat::Html));
+        assert_eq!(ReportFormat::parse(" PDF "), Some(ReportFormat::Pdf));
+        assert_eq!(ReportFormat::parse("xlsx").unwrap().converter(), "xlsx");
+        assert_eq!(
+            ReportFormat::parse("text").unwrap().report_type(),
+            "qweb-text"
+        );
+        assert_eq!(ReportFormat::parse("docx"), None);
+    }
+
     #[test]
     fn test_odoo_error_display_invalid_response() {
         let err = OdooError::InvalidResponse("missing field".to_string());
@@ -3,8 +3,10 @@ use serde_json::Value;
 
 use super::client::OdooHttpClient;
 use super::config::{OdooAuthMode, OdooInstanceConfig};
+use super::health::HealthDepth;
 use super::legacy_client::OdooLegacyClient;
-use super::types::OdooResult;
+use super::throttle::UpstreamThrottle;
+use super::types::{BinaryContent, OdooResult, ReportFormat};
 
 /// Trait for Odoo client operations, enabling mockability for testing.
 #[async_trait]
@@ -70,6 +72,13 @@ pub trait OdooClientTrait: Send + Sync {
 
     async fn download_report_pdf(&self, report_name: &str, ids: &[i64]) -> OdooResult<Vec<u8>>;
 
+    async fn download_report(
+        &self,
+        report_name: &str,
+        ids: &[i64],
+        format: ReportFormat,
+    ) -> OdooResult<BinaryContent>;
+
     async fn read_group(
         &self,
         model: &str,
@@ -166,6 +175,14 @@ impl OdooClient {
         matches!(self, OdooClient::Legacy(_))
     }
 
+    /// Pacing state after the instance answered 429.
+    pub fn throttle(&self) -> &UpstreamThrottle {
+        match self {
+            OdooClient::Modern(c) => c.throttle(),
+            OdooClient::Legacy(c) => c.throttle(),
+        }
+    }
+
     pub async fn search(
         &self,
         model: &str,
@@ -228,6 +245,18 @@ impl OdooClient {
         }
     }
 
+    pub async fn create_many(
+        &self,
+        model: &str,
+        vals_list: Vec<Value>,
+        context: Option<Value>,
+    ) -> OdooResult<Vec<i64>> {
+        match self {
+            OdooClient::Modern(c) => c.create_many(model, vals_list, context).await,
+            OdooClient::Legacy(c) => c.create_many(model, vals_list, context).await,
+        }
+    }
+
     pub async fn write(
         &self,
         model: &str,
@@ -293,6 +322,29 @@ impl OdooClient {
         }
     }
 
+    pub async fn download_report(
+        &self,
+        report_name: &str,
+        ids: &[i64],
+        format: ReportFormat,
+    ) -> OdooResult<BinaryContent> {
+        match self {
+            OdooClient::Modern(c) => c.download_report(report_name, ids, format).await,
+            OdooClient::Legacy(c) => c.download_report(report_name, ids, format).await,
+        }
+    }
+
+    pub async fn download_attachment(
+        &self,
+        id: i64,
+        max_bytes: usize,
+    ) -> OdooResult<BinaryContent> {
+        match self {
+            OdooClient::Modern(c) => c.download_attachment(id, max_bytes).await,
+            OdooClient::Legacy(c) => c.download_attachment(id, max_bytes).await,
+        }
+    }
+
     pub async fn read_group(
         &self,
         model: &str,
@@ -407,6 +459,13 @@ impl OdooClient {
         }
     }
 
+    pub async fn health_probe_at(&self, depth: HealthDepth) -> OdooResult<Option<String>> {
+        match self {
+            OdooClient::Modern(c) => c.health_probe_at(depth).await,
+            OdooClient::Legacy(c) => c.health_probe_at(depth).await,
+        }
+    }
+
     pub async fn health_check(&self) -> bool {
         self.health_probe().await.is_ok()
     }
@@ -497,6 +556,15 @@ impl OdooClientTrait for OdooClient {
         self.download_report_pdf(report_name, ids).await
     }
 
+    async fn download_report(
+        &self,
+        report_name: &str,
+        ids: &[i64],
+        format: ReportFormat,
+    ) -> OdooResult<BinaryContent> {
+        self.download_report(report_name, ids, format).await
+    }
+
     async fn read_group(
         &self,
         model: &str,
@@ -614,6 +682,11 @@ mod tests {
             tool_config: None,
             read_only: false,
             tags: Vec::new(),
+            headers: HashMap::new(),
+            proxy_auth: None,
+            lang: None,
+            translate_errors: false,
+            health_depth: None,
             aliases: Vec::new(),
             extra: HashMap::new(),
         };
@@ -633,6 +706,11 @@ mod tests {
             tool_config: None,
             read_only: false,
             tags: Vec::new(),
+            headers: HashMap::new(),
+            proxy_auth: None,
+            lang: None,
+            translate_errors: false,
+            health_depth: None,
             aliases: Vec::new(),
             extra: HashMap::new(),
         };
@@ -657,6 +735,11 @@ mod tests {
             tool_config: None,
             read_only: false,
             tags: Vec::new(),
+            headers: HashMap::new(),
+            proxy_auth: None,
+            lang: None,
+            translate_errors: false,
+            health_depth: None,
             aliases: Vec::new(),
             extra: HashMap::new(),
         };
@@ -679,6 +762,11 @@ mod tests {
             tool_config: None,
             read_only: false,
             tags: Vec::new(),
+            headers: HashMap::new(),
+            proxy_auth: None,
+            lang: None,
+            translate_errors: false,
+            health_depth: None,
             aliases: Vec::new(),
             extra: HashMap::new(),
         };
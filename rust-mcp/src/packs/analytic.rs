@@ -0,0 +1,542 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::odoo::types::{OdooError, OdooResult};
+use crate::odoo::unified_client::OdooClient;
+use crate::packs::{many2one_id, many2one_name, resolve_by_name};
+
+const LINE_MODEL: &str = "account.analytic.line";
+const ACCOUNT_MODEL: &str = "account.analytic.account";
+const PLAN_MODEL: &str = "account.analytic.plan";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyticRollupOptions {
+    /// Period start (YYYY-MM-DD, inclusive).
+    pub date_from: String,
+    /// Period end (YYYY-MM-DD, inclusive).
+    pub date_to: String,
+    /// Restrict to one analytic plan (Odoo 16+) or analytic group (Odoo 14/15), with children.
+    pub plan_id: Option<i64>,
+    pub plan: Option<String>,
+    pub account_ids: Option<Vec<i64>>,
+    pub company_id: Option<i64>,
+    /// List the documents behind this analytic account's total.
+    pub drill_down_account_id: Option<i64>,
+    /// Maximum analytic lines read for the drill-down (default 500).
+    pub drill_down_limit: Option<i64>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountTotal {
+    pub account_id: i64,
+    pub account: Option<String>,
+    pub code: Option<String>,
+    pub plan_id: Option<i64>,
+    pub plan: Option<String>,
+    pub amount: f64,
+    pub quantity: f64,
+    pub line_count: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanTotal {
+    pub plan_id: Option<i64>,
+    pub plan: Option<String>,
+    pub amount: f64,
+    pub line_count: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentTotal {
+    /// `account.move` id, or `None` for lines without an accounting entry (e.g. timesheets).
+    pub move_id: Option<i64>,
+    pub document: String,
+    pub amount: f64,
+    pub line_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DrillDown {
+    pub account_id: i64,
+    pub line_count: usize,
+    /// True when more lines matched than `drillDownLimit`.
+    pub truncated: bool,
+    pub documents: Vec<DocumentTotal>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyticRollup {
+    pub date_from: String,
+    pub date_to: String,
+    /// Analytic line columns that were aggregated (`account_id`, plus `x_plan*_id` on Odoo 17+).
+    pub plan_columns: Vec<String>,
+    pub total: f64,
+    pub line_count: i64,
+    pub plans: Vec<PlanTotal>,
+    pub accounts: Vec<AccountTotal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub drill_down: Option<DrillDown>,
+}
+
+/// How the analytic model is laid out on the connected Odoo version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Layout {
+    /// Many2one columns on `account.analytic.line` pointing at analytic accounts.
+    columns: Vec<String>,
+    /// `plan_id` (16+) or `group_id` (14/15) on `account.analytic.account`, if any.
+    plan_field: Option<&'static str>,
+    plan_model: &'static str,
+    /// `move_line_id` (16+) or `move_id` (14/15), both pointing at `account.move.line`.
+    move_line_field: Option<&'static str>,
+}
+
+fn detect_layout(line_fields: &Value, account_fields: &Value) -> Layout {
+    let mut columns = vec!["account_id".to_string()];
+    let mut extra: Vec<String> = line_fields
+        .as_object()
+        .map(|fields| {
+            fields
+                .iter()
+                .filter(|(name, spec)| {
+                    name.starts_with("x_plan")
+                        && name.ends_with("_id")
+                        && spec.get("relation").and_then(Value::as_str) == Some(ACCOUNT_MODEL)
+                })
+                .map(|(name, _)| name.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+    extra.sort();
+    columns.extend(extra);
+
+    let (plan_field, plan_model) = if account_fields.get("plan_id").is_some() {
+        (Some("plan_id"), PLAN_MODEL)
+    } else if account_fields.get("group_id").is_some() {
+        (Some("group_id"), "account.analytic.group")
+    } else {
+        (None, PLAN_MODEL)
+    };
+    let move_line_field = ["move_line_id", "move_id"]
+        .into_iter()
+        .find(|name| line_fields.get(*name).is_some());
+    Layout {
+        columns,
+        plan_field,
+        plan_model,
+        move_line_field,
+    }
+}
+
+/// Prefix-notation OR of `terms`; a single term is returned as is.
+fn any_of(terms: Vec<Value>) -> Vec<Value> {
+    let mut domain = vec![json!("|"); terms.len().saturating_sub(1)];
+    domain.extend(terms);
+    domain
+}
+
+fn base_domain(
+    options: &AnalyticRollupOptions,
+    layout: &Layout,
+    plan_id: Option<i64>,
+) -> Vec<Value> {
+    let mut domain = vec![
+        json!(["date", ">=", options.date_from]),
+        json!(["date", "<=", options.date_to]),
+    ];
+    if let Some(company_id) = options.company_id {
+        domain.push(json!(["company_id", "=", company_id]));
+    }
+    if let Some(ids) = options.account_ids.as_ref() {
+        domain.extend(any_of(
+            layout
+                .columns
+                .iter()
+                .map(|col| json!([col, "in", ids]))
+                .collect(),
+        ));
+    }
+    if let (Some(plan_id), Some(plan_field)) = (plan_id, layout.plan_field) {
+        domain.extend(any_of(
+            layout
+                .columns
+                .iter()
+                .map(|col| json!([format!("{col}.{plan_field}"), "child_of", plan_id]))
+                .collect(),
+        ));
+    }
+    domain
+}
+
+fn sum_of(row: &Value, key: &str) -> f64 {
+    row.get(key).and_then(Value::as_f64).unwrap_or(0.0)
+}
+
+fn count_of(row: &Value) -> i64 {
+    row.get("__count").and_then(Value::as_i64).unwrap_or(0)
+}
+
+/// Fold `read_group` rows of one column into per-account totals.
+fn fold_accounts(rows: &Value, column: &str, totals: &mut BTreeMap<i64, AccountTotal>) {
+    for row in rows.as_array().map(Vec::as_slice).unwrap_or(&[]) {
+        let Some(account_id) = row.get(column).and_then(many2one_id) else {
+            continue;
+        };
+        let total = totals.entry(account_id).or_insert_with(|| AccountTotal {
+            account_id,
+            account: row.get(column).and_then(many2one_name),
+            code: None,
+            plan_id: None,
+            plan: None,
+            amount: 0.0,
+            quantity: 0.0,
+            line_count: 0,
+        });
+        total.amount += sum_of(row, "amount");
+        total.quantity += sum_of(row, "unit_amount");
+        total.line_count += count_of(row);
+    }
+}
+
+fn fold_plans(accounts: &[AccountTotal]) -> Vec<PlanTotal> {
+    let mut plans: Vec<PlanTotal> = Vec::new();
+    for account in accounts {
+        match plans.iter_mut().find(|p| p.plan_id == account.plan_id) {
+            Some(plan) => {
+                plan.amount += account.amount;
+                plan.line_count += account.line_count;
+            }
+            None => plans.push(PlanTotal {
+                plan_id: account.plan_id,
+                plan: account.plan.clone(),
+                amount: account.amount,
+                line_count: account.line_count,
+            }),
+        }
+    }
+    plans.sort_by(|a, b| a.plan.cmp(&b.plan));
+    plans
+}
+
+/// Group drill-down lines by accounting entry; `moves` maps move line ids to their move.
+fn fold_documents(
+    lines: &[Value],
+    move_line_field: Option<&str>,
+    moves: &BTreeMap<i64, (i64, String)>,
+) -> Vec<DocumentTotal> {
+    let mut documents: Vec<DocumentTotal> = Vec::new();
+    for line in lines {
+        let entry = move_line_field
+            .and_then(|field| line.get(field))
+            .and_then(many2one_id)
+            .and_then(|id| moves.get(&id));
+        let (move_id, document) = match entry {
+            Some((move_id, name)) => (Some(*move_id), name.clone()),
+            None => (None, "Without accounting entry".to_string()),
+        };
+        let amount = sum_of(line, "amount");
+        match documents.iter_mut().find(|d| d.move_id == move_id) {
+            Some(doc) => {
+                doc.amount += amount;
+                doc.line_count += 1;
+            }
+            None => documents.push(DocumentTotal {
+                move_id,
+                document,
+                amount,
+                line_count: 1,
+            }),
+        }
+    }
+    documents.sort_by(|a, b| b.amount.abs().total_cmp(&a.amount.abs()));
+    documents
+}
+
+pub async fn analytic_rollup(
+    client: &OdooClient,
+    options: AnalyticRollupOptions,
+) -> OdooResult<AnalyticRollup> {
+    let context = options.context.clone();
+    let line_fields = client.fields_get(LINE_MODEL, context.clone()).await?;
+    let account_fields = client.fields_get(ACCOUNT_MODEL, context.clone()).await?;
+    let layout = detect_layout(&line_fields, &account_fields);
+
+    let plan_id = match (options.plan_id, options.plan.as_deref()) {
+        (Some(id), _) => Some(id),
+        (None, Some(name)) => {
+            Some(resolve_by_name(client, layout.plan_model, "name", name, vec![]).await?)
+        }
+        (None, None) => None,
+    };
+    if plan_id.is_some() && layout.plan_field.is_none() {
+        return Err(OdooError::InvalidResponse(
+            "This Odoo version has neither analytic plans nor analytic groups".into(),
+        ));
+    }
+    let domain = base_domain(&options, &layout, plan_id);
+
+    let totals = client
+        .read_group(
+            LINE_MODEL,
+            Some(Value::Array(domain.clone())),
+            vec!["amount:sum".to_string()],
+            Vec::new(),
+            None,
+            None,
+            None,
+            Some(false),
+            context.clone(),
+        )
+        .await?;
+    let totals_row = totals
+        .as_array()
+        .and_then(|rows| rows.first())
+        .cloned()
+        .unwrap_or_default();
+
+    let mut by_account = BTreeMap::new();
+    for column in &layout.columns {
+        let rows = client
+            .read_group(
+                LINE_MODEL,
+                Some(Value::Array(domain.clone())),
+                vec!["amount:sum".to_string(), "unit_amount:sum".to_string()],
+                vec![column.clone()],
+                None,
+                None,
+                None,
+                Some(false),
+                context.clone(),
+            )
+            .await?;
+        fold_accounts(&rows, column, &mut by_account);
+    }
+
+    let mut account_read_fields = vec!["name".to_string(), "code".to_string()];
+    account_read_fields.extend(layout.plan_field.map(str::to_string));
+    let account_rows = if by_account.is_empty() {
+        Value::Array(Vec::new())
+    } else {
+        client
+            .read(
+                ACCOUNT_MODEL,
+                by_account.keys().copied().collect(),
+                Some(account_read_fields),
+                context.clone(),
+            )
+            .await?
+    };
+    for row in account_rows.as_array().map(Vec::as_slice).unwrap_or(&[]) {
+        let Some(total) = row
+            .get("id")
+            .and_then(Value::as_i64)
+            .and_then(|id| by_account.get_mut(&id))
+        else {
+            continue;
+        };
+        total.code = row
+            .get("code")
+            .and_then(Value::as_str)
+            .filter(|code| !code.is_empty())
+            .map(String::from);
+        if let Some(plan) = layout.plan_field.and_then(|field| row.get(field)) {
+            total.plan_id = many2one_id(plan);
+            total.plan = many2one_name(plan);
+        }
+    }
+
+    let mut accounts: Vec<AccountTotal> = by_account.into_values().collect();
+    accounts.sort_by(|a, b| b.amount.abs().total_cmp(&a.amount.abs()));
+
+    let drill_down = match options.drill_down_account_id {
+        Some(account_id) => Some(drill_down(client, &layout, &options, domain, account_id).await?),
+        None => None,
+    };
+
+    Ok(AnalyticRollup {
+        date_from: options.date_from,
+        date_to: options.date_to,
+        plans: fold_plans(&accounts),
+        plan_columns: layout.columns,
+        total: sum_of(&totals_row, "amount"),
+        line_count: count_of(&totals_row),
+        accounts,
+        drill_down,
+    })
+}
+
+async fn drill_down(
+    client: &OdooClient,
+    layout: &Layout,
+    options: &AnalyticRollupOptions,
+    mut domain: Vec<Value>,
+    account_id: i64,
+) -> OdooResult<DrillDown> {
+    domain.extend(any_of(
+        layout
+            .columns
+            .iter()
+            .map(|col| json!([col, "=", account_id]))
+            .collect(),
+    ));
+    let limit = options.drill_down_limit.unwrap_or(500);
+    let mut fields = vec!["amount".to_string()];
+    fields.extend(layout.move_line_field.map(str::to_string));
+    let lines = client
+        .search_read(
+            LINE_MODEL,
+            Some(Value::Array(domain)),
+            Some(fields),
+            Some(limit + 1),
+            None,
+            Some("date desc, id desc".to_string()),
+            options.context.clone(),
+        )
+        .await?;
+    let mut lines = lines.as_array().cloned().unwrap_or_default();
+    let truncated = lines.len() as i64 > limit;
+    lines.truncate(limit.max(0) as usize);
+
+    let move_line_ids: Vec<i64> = layout
+        .move_line_field
+        .map(|field| {
+            let mut ids: Vec<i64> = lines
+                .iter()
+                .filter_map(|line| line.get(field).and_then(many2one_id))
+                .collect();
+            ids.sort_unstable();
+            ids.dedup();
+            ids
+        })
+        .unwrap_or_default();
+    let mut moves = BTreeMap::new();
+    if !move_line_ids.is_empty() {
+        let rows = client
+            .read(
+                "account.move.line",
+                move_line_ids,
+                Some(vec!["move_id".to_string()]),
+                options.context.clone(),
+            )
+            .await?;
+        for row in rows.as_array().map(Vec::as_slice).unwrap_or(&[]) {
+            let entry = row.get("move_id");
+            if let (Some(id), Some(move_id), Some(name)) = (
+                row.get("id").and_then(Value::as_i64),
+                entry.and_then(many2one_id),
+                entry.and_then(many2one_name),
+            ) {
+                moves.insert(id, (move_id, name));
+            }
+        }
+    }
+
+    Ok(DrillDown {
+        account_id,
+        line_count: lines.len(),
+        truncated,
+        documents: fold_documents(&lines, layout.move_line_field, &moves),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_layout_covers_plan_generations() {
+        let v15 = detect_layout(
+            &json!({"account_id": {}, "move_id": {"relation": "account.move.line"}}),
+            &json!({"group_id": {}}),
+        );
+        assert_eq!(v15.columns, vec!["account_id"]);
+        assert_eq!(v15.plan_field, Some("group_id"));
+        assert_eq!(v15.move_line_field, Some("move_id"));
+
+        let v17 = detect_layout(
+            &json!({
+                "account_id": {}, "move_line_id": {},
+                "x_plan3_id": {"relation": ACCOUNT_MODEL},
+                "x_plan2_id": {"relation": ACCOUNT_MODEL},
+                "x_plan_note": {"relation": "res.partner"}
+            }),
+            &json!({"plan_id": {}}),
+        );
+        assert_eq!(v17.columns, vec!["account_id", "x_plan2_id", "x_plan3_id"]);
+        assert_eq!(v17.plan_field, Some("plan_id"));
+        assert_eq!(v17.move_line_field, Some("move_line_id"));
+    }
+
+    #[test]
+    fn any_of_prefixes_or_operators() {
+        assert_eq!(any_of(vec![json!(1)]), vec![json!(1)]);
+        assert_eq!(
+            any_of(vec![json!(1), json!(2), json!(3)]),
+            vec![json!("|"), json!("|"), json!(1), json!(2), json!(3)]
+        );
+    }
+
+    #[test]
+    fn fold_accounts_merges_columns_and_plans() {
+        let mut totals = BTreeMap::new();
+        fold_accounts(
+            &json!([
+                {"account_id": [1, "Project A"], "amount": -100.0, "unit_amount": 2.0, "__count": 2},
+                {"account_id": false, "amount": 5.0, "__count": 1}
+            ]),
+            "account_id",
+            &mut totals,
+        );
+        fold_accounts(
+            &json!([{"x_plan2_id": [1, "Project A"], "amount": -50.0, "__count": 1}]),
+            "x_plan2_id",
+            &mut totals,
+        );
+        let mut accounts: Vec<AccountTotal> = totals.into_values().collect();
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0].amount, -150.0);
+        assert_eq!(accounts[0].line_count, 3);
+
+        accounts[0].plan_id = Some(9);
+        accounts[0].plan = Some("Projects".into());
+        assert_eq!(
+            fold_plans(&accounts),
+            vec![PlanTotal {
+                plan_id: Some(9),
+                plan: Some("Projects".into()),
+                amount: -150.0,
+                line_count: 3
+            }]
+        );
+    }
+
+    #[test]
+    fn fold_documents_groups_by_move() {
+        let moves = BTreeMap::from([
+            (10, (1, "INV/2026/0001".to_string())),
+            (11, (1, "INV/2026/0001".to_string())),
+        ]);
+        let documents = fold_documents(
+            &[
+                json!({"amount": 100.0, "move_line_id": [10, "x"]}),
+                json!({"amount": 20.0, "move_line_id": [11, "y"]}),
+                json!({"amount": -300.0, "move_line_id": false}),
+            ],
+            Some("move_line_id"),
+            &moves,
+        );
+        assert_eq!(documents.len(), 2);
+        assert_eq!(documents[0].move_id, None);
+        assert_eq!(documents[1].document, "INV/2026/0001");
+        assert_eq!(documents[1].amount, 120.0);
+        assert_eq!(documents[1].line_count, 2);
+    }
+}
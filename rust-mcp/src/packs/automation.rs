@@ -0,0 +1,456 @@
+//! Automated actions (`base.automation`).
+//!
+//! Up to Odoo 16 a rule inherits a single server action, so `state` and `code` sit on the
+//! rule itself; from 17 it points at `action_server_ids`. Fields are picked from
+//! `fields_get` so both layouts read the same way.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::odoo::types::{OdooError, OdooResult};
+use crate::odoo::unified_client::OdooClient;
+use crate::packs::{existing_fields, many2one_id, many2one_name, resolve_by_name};
+
+const RULE_MODEL: &str = "base.automation";
+const ACTION_MODEL: &str = "ir.actions.server";
+const FIELD_MODEL: &str = "ir.model.fields";
+
+const RULE_FIELDS: &[&str] = &[
+    "name",
+    "model_id",
+    "model_name",
+    "trigger",
+    "active",
+    "last_run",
+    "filter_pre_domain",
+    "filter_domain",
+    "trigger_field_ids",
+    "trg_date_id",
+    "trg_date_range",
+    "trg_date_range_type",
+    "action_server_ids",
+    "action_server_id",
+];
+
+/// What a server action does; `update_*`/`value` are the Odoo 17+ "update record" fields.
+const ACTION_FIELDS: &[&str] = &[
+    "name",
+    "sequence",
+    "state",
+    "code",
+    "crud_model_id",
+    "update_field_id",
+    "value",
+    "template_id",
+    "activity_type_id",
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutomationListOptions {
+    /// Only rules on this model (technical name, e.g. "sale.order").
+    pub model: Option<String>,
+    /// Only enabled (true) or disabled (false) rules; both when omitted.
+    pub active: Option<bool>,
+    /// Trigger code, e.g. "on_create_or_write" or "on_time".
+    pub trigger: Option<String>,
+    /// Only rules whose name contains this text.
+    pub search: Option<String>,
+    pub limit: Option<i64>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutomationRuleRef {
+    pub id: Option<i64>,
+    /// Rule name (case-insensitive exact match) when no id is given.
+    pub name: Option<String>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutomationToggleOptions {
+    #[serde(flatten)]
+    pub rule: AutomationRuleRef,
+    pub active: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutomationRule {
+    pub id: i64,
+    pub name: String,
+    pub model: Option<String>,
+    pub trigger: Option<String>,
+    pub active: bool,
+    pub last_run: Option<String>,
+    /// Domain the record must match before the change (write triggers only).
+    pub before_domain: Option<String>,
+    /// Domain the record must match to fire the rule.
+    pub domain: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub trigger_field_ids: Vec<i64>,
+    /// Date field and delay for time-based rules, e.g. "2 day after Deadline".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_condition: Option<String>,
+    pub action_ids: Vec<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutomationRules {
+    pub count: usize,
+    pub rules: Vec<AutomationRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutomationAction {
+    pub id: i64,
+    pub name: String,
+    pub state: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// Remaining non-empty action fields (target model, field updated, template...).
+    #[serde(skip_serializing_if = "serde_json::Map::is_empty")]
+    pub details: serde_json::Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutomationRuleDetail {
+    pub rule: AutomationRule,
+    pub trigger_fields: Vec<String>,
+    pub actions: Vec<AutomationAction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutomationToggle {
+    pub id: i64,
+    pub name: String,
+    pub active: bool,
+    pub changed: bool,
+}
+
+/// Caller context plus `active_test: false`, so disabled rules are found too.
+fn rule_context(context: Option<Value>) -> Value {
+    let mut context = match context {
+        Some(Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    };
+    context.insert("active_test".to_string(), json!(false));
+    Value::Object(context)
+}
+
+fn text(row: &Value, key: &str) -> Option<String> {
+    row.get(key)
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+}
+
+fn ids(row: &Value, key: &str) -> Vec<i64> {
+    row.get(key)
+        .and_then(|v| v.as_array())
+        .map(|items| items.iter().filter_map(|v| v.as_i64()).collect())
+        .unwrap_or_default()
+}
+
+fn parse_rule(row: &Value) -> Option<AutomationRule> {
+    let mut action_ids = ids(row, "action_server_ids");
+    if action_ids.is_empty() {
+        action_ids.extend(row.get("action_server_id").and_then(many2one_id));
+    }
+    let model_id = row.get("model_id").unwrap_or(&Value::Null);
+    let time_condition = row.get("trg_date_id").and_then(many2one_name).map(|field| {
+        let range = row
+            .get("trg_date_range")
+            .and_then(|v| v.as_i64())
+            .unwrap_or(0);
+        let (delay, unit) = (range.abs(), text(row, "trg_date_range_type"));
+        let side = if range < 0 { "before" } else { "after" };
+        format!(
+            "{delay} {} {side} {field}",
+            unit.unwrap_or_else(|| "day".to_string())
+        )
+    });
+    Some(AutomationRule {
+        id: row.get("id")?.as_i64()?,
+        name: text(row, "name").unwrap_or_default(),
+        model: text(row, "model_name").or_else(|| many2one_name(model_id)),
+        trigger: text(row, "trigger"),
+        active: row.get("active").and_then(|v| v.as_bool()).unwrap_or(true),
+        last_run: text(row, "last_run"),
+        before_domain: text(row, "filter_pre_domain"),
+        domain: text(row, "filter_domain"),
+        trigger_field_ids: ids(row, "trigger_field_ids"),
+        time_condition,
+        action_ids,
+    })
+}
+
+async fn rule_fields(client: &OdooClient, context: &Value) -> OdooResult<Vec<String>> {
+    let available = client.fields_get(RULE_MODEL, Some(context.clone())).await?;
+    Ok(existing_fields(&available, RULE_FIELDS))
+}
+
+async fn resolve_rule(client: &OdooClient, rule: &AutomationRuleRef) -> OdooResult<i64> {
+    match (rule.id, rule.name.as_deref()) {
+        (Some(id), _) => Ok(id),
+        (None, Some(name)) => {
+            resolve_by_name(
+                client,
+                RULE_MODEL,
+                "name",
+                name,
+                vec![
+                    json!("|"),
+                    json!(["active", "=", true]),
+                    json!(["active", "=", false]),
+                ],
+            )
+            .await
+        }
+        (None, None) => Err(OdooError::InvalidResponse(
+            "Give the automation rule's id or name".to_string(),
+        )),
+    }
+}
+
+async fn read_rule(
+    client: &OdooClient,
+    id: i64,
+    fields: Vec<String>,
+    context: &Value,
+) -> OdooResult<AutomationRule> {
+    let rows = client
+        .read(RULE_MODEL, vec![id], Some(fields), Some(context.clone()))
+        .await?;
+    rows.as_array()
+        .and_then(|rows| rows.first())
+        .and_then(parse_rule)
+        .ok_or_else(|| OdooError::InvalidResponse(format!("{RULE_MODEL} {id} not found")))
+}
+
+pub async fn list_rules(
+    client: &OdooClient,
+    options: AutomationListOptions,
+) -> OdooResult<AutomationRules> {
+    let context = rule_context(options.context);
+    let mut domain = Vec::new();
+    if let Some(model) = options.model.as_deref() {
+        domain.push(json!(["model_id.model", "=", model]));
+    }
+    if let Some(active) = options.active {
+        domain.push(json!(["active", "=", active]));
+    }
+    if let Some(trigger) = options.trigger.as_deref() {
+        domain.push(json!(["trigger", "=", trigger]));
+    }
+    if let Some(search) = options.search.as_deref() {
+        domain.push(json!(["name", "ilike", search]));
+    }
+    let fields = rule_fields(client, &context).await?;
+    let rows = client
+        .search_read(
+            RULE_MODEL,
+            Some(Value::Array(domain)),
+            Some(fields),
+            Some(options.limit.unwrap_or(100)),
+            None,
+            Some("model_id asc, id asc".to_string()),
+            Some(context),
+        )
+        .await?;
+    let rules: Vec<AutomationRule> = rows
+        .as_array()
+        .map(|rows| rows.iter().filter_map(parse_rule).collect())
+        .unwrap_or_default();
+    Ok(AutomationRules {
+        count: rules.len(),
+        rules,
+    })
+}
+
+/// One rule with its trigger field names and the server actions it runs, in order.
+pub async fn inspect_rule(
+    client: &OdooClient,
+    options: AutomationRuleRef,
+) -> OdooResult<AutomationRuleDetail> {
+    let context = rule_context(options.context.clone());
+    let id = resolve_rule(client, &options).await?;
+    let fields = rule_fields(client, &context).await?;
+    let rule = read_rule(client, id, fields, &context).await?;
+
+    let trigger_fields = if rule.trigger_field_ids.is_empty() {
+        Vec::new()
+    } else {
+        let rows = client
+            .read(
+                FIELD_MODEL,
+                rule.trigger_field_ids.clone(),
+                Some(vec!["name".to_string()]),
+                Some(context.clone()),
+            )
+            .await?;
+        rows.as_array()
+            .map(|rows| rows.iter().filter_map(|row| text(row, "name")).collect())
+            .unwrap_or_default()
+    };
+
+    let actions = if rule.action_ids.is_empty() {
+        Vec::new()
+    } else {
+        let available = client
+            .fields_get(ACTION_MODEL, Some(context.clone()))
+            .await?;
+        let rows = client
+            .read(
+                ACTION_MODEL,
+                rule.action_ids.clone(),
+                Some(existing_fields(&available, ACTION_FIELDS)),
+                Some(context),
+            )
+            .await?;
+        let mut rows = rows.as_array().cloned().unwrap_or_default();
+        rows.sort_by_key(|row| row.get("sequence").and_then(|v| v.as_i64()).unwrap_or(0));
+        rows.iter().filter_map(parse_action).collect()
+    };
+
+    Ok(AutomationRuleDetail {
+        rule,
+        trigger_fields,
+        actions,
+    })
+}
+
+fn parse_action(row: &Value) -> Option<AutomationAction> {
+    let details = row
+        .as_object()?
+        .iter()
+        .filter(|(key, _)| !matches!(key.as_str(), "id" | "name" | "state" | "code" | "sequence"))
+        .filter(|(_, value)| !matches!(value, Value::Bool(false) | Value::Null))
+        .filter(|(_, value)| value.as_str() != Some(""))
+        .map(|(key, value)| {
+            let value = many2one_name(value)
+                .map(Value::String)
+                .unwrap_or(value.clone());
+            (key.clone(), value)
+        })
+        .collect();
+    Some(AutomationAction {
+        id: row.get("id")?.as_i64()?,
+        name: text(row, "name").unwrap_or_default(),
+        state: text(row, "state"),
+        code: text(row, "code"),
+        details,
+    })
+}
+
+/// Enable or disable a rule; leaves it untouched when it is already in that state.
+pub async fn set_rule_active(
+    client: &OdooClient,
+    options: AutomationToggleOptions,
+) -> OdooResult<AutomationToggle> {
+    let context = rule_context(options.rule.context.clone());
+    let id = resolve_rule(client, &options.rule).await?;
+    let fields = vec!["name".to_string(), "active".to_string()];
+    let rule = read_rule(client, id, fields, &context).await?;
+    let changed = rule.active != options.active;
+    if changed {
+        client
+            .write(
+                RULE_MODEL,
+                vec![id],
+                json!({"active": options.active}),
+                Some(context),
+            )
+            .await?;
+    }
+    Ok(AutomationToggle {
+        id,
+        name: rule.name,
+        active: options.active,
+        changed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_rule_handles_single_and_multi_action_layouts() {
+        let v16 = json!({
+            "id": 3,
+            "name": "Escalate late tasks",
+            "model_id": [41, "Task"],
+            "model_name": "project.task",
+            "trigger": "on_time",
+            "active": false,
+            "last_run": "2024-05-01 06:00:00",
+            "filter_domain": "[('stage_id.fold', '=', False)]",
+            "filter_pre_domain": false,
+            "trg_date_id": [812, "Deadline"],
+            "trg_date_range": -2,
+            "trg_date_range_type": "day",
+            "action_server_id": [90, "Escalate late tasks"]
+        });
+        let rule = parse_rule(&v16).unwrap();
+        assert_eq!(rule.model.as_deref(), Some("project.task"));
+        assert!(!rule.active);
+        assert_eq!(rule.before_domain, None);
+        assert_eq!(
+            rule.time_condition.as_deref(),
+            Some("2 day before Deadline")
+        );
+        assert_eq!(rule.action_ids, vec![90]);
+
+        let v17 = json!({
+            "id": 4,
+            "name": "Tag VIP orders",
+            "model_id": [12, "Sales Order"],
+            "trigger": "on_create_or_write",
+            "active": true,
+            "trigger_field_ids": [301, 302],
+            "action_server_ids": [91, 92]
+        });
+        let rule = parse_rule(&v17).unwrap();
+        assert_eq!(rule.model.as_deref(), Some("Sales Order"));
+        assert_eq!(rule.trigger_field_ids, vec![301, 302]);
+        assert_eq!(rule.time_condition, None);
+        assert_eq!(rule.action_ids, vec![91, 92]);
+    }
+
+    #[test]
+    fn parse_action_keeps_only_set_details() {
+        let action = parse_action(&json!({
+            "id": 91,
+            "name": "Set priority",
+            "sequence": 5,
+            "state": "object_write",
+            "code": "",
+            "update_field_id": [77, "Priority"],
+            "value": "1",
+            "template_id": false
+        }))
+        .unwrap();
+        assert_eq!(action.code, None);
+        assert_eq!(
+            Value::Object(action.details),
+            json!({"update_field_id": "Priority", "value": "1"})
+        );
+    }
+
+    #[test]
+    fn rule_context_keeps_caller_keys() {
+        assert_eq!(
+            rule_context(Some(json!({"lang": "fr_FR"}))),
+            json!({"lang": "fr_FR", "active_test": false})
+        );
+        assert_eq!(rule_context(None), json!({"active_test": false}));
+    }
+}
@@ -0,0 +1,243 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::odoo::types::{OdooError, OdooResult};
+use crate::odoo::unified_client::OdooClient;
+use crate::packs::{many2one_id, many2one_name, resolve_by_name};
+
+const MOVE_LINE_MODEL: &str = "account.move.line";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreditExposureOptions {
+    /// Partner by id or by name; contacts are rolled up to their company.
+    pub partner_id: Option<i64>,
+    pub partner: Option<String>,
+    pub company_id: Option<i64>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreditExposure {
+    pub partner_id: i64,
+    pub partner: Option<String>,
+    /// Open receivable items (posted, unreconciled), in company currency.
+    pub total_receivable: f64,
+    pub overdue: f64,
+    pub not_due: f64,
+    pub overdue_item_count: i64,
+    pub oldest_due_date: Option<String>,
+    /// Confirmed sales not invoiced yet (Odoo 16+ with sale installed).
+    pub uninvoiced_orders: Option<f64>,
+    /// Receivable plus uninvoiced orders.
+    pub exposure: f64,
+    pub credit_limit: Option<f64>,
+    pub remaining_credit: Option<f64>,
+    pub over_limit: bool,
+}
+
+/// Account domain selecting receivable accounts: `account_type` since 16, `internal_type` before.
+fn receivable_domain(account_fields: &Value) -> Value {
+    if account_fields.get("account_type").is_some() {
+        json!(["account_id.account_type", "=", "asset_receivable"])
+    } else {
+        json!(["account_id.internal_type", "=", "receivable"])
+    }
+}
+
+/// Sum of `amount_residual` and the row count of a single-group `read_group` answer.
+fn group_totals(rows: &Value) -> (f64, i64) {
+    let row = rows.get(0);
+    let amount = row
+        .and_then(|r| r.get("amount_residual"))
+        .and_then(Value::as_f64)
+        .unwrap_or(0.0);
+    let count = row
+        .and_then(|r| r.get("__count").or_else(|| r.get("amount_residual_count")))
+        .and_then(Value::as_i64)
+        .unwrap_or(0);
+    (amount, count)
+}
+
+/// Exposure, remaining credit and whether the limit is exceeded.
+fn finish(
+    receivable: f64,
+    uninvoiced: Option<f64>,
+    credit_limit: Option<f64>,
+) -> (f64, Option<f64>, bool) {
+    let exposure = receivable + uninvoiced.unwrap_or(0.0);
+    let remaining = credit_limit.map(|limit| limit - exposure);
+    (exposure, remaining, remaining.is_some_and(|r| r < 0.0))
+}
+
+pub async fn credit_exposure(
+    client: &OdooClient,
+    options: CreditExposureOptions,
+) -> OdooResult<CreditExposure> {
+    let ctx = options.context.clone();
+    let partner_id = match (options.partner_id, options.partner.as_deref()) {
+        (Some(id), _) => id,
+        (None, Some(name)) => resolve_by_name(client, "res.partner", "name", name, vec![]).await?,
+        (None, None) => {
+            return Err(OdooError::InvalidResponse(
+                "Pass 'partnerId' or 'partner'".to_string(),
+            ));
+        }
+    };
+
+    let partner_fields = client.fields_get("res.partner", ctx.clone()).await?;
+    let mut wanted = vec!["display_name".to_string()];
+    for field in ["credit_limit", "credit_to_invoice"] {
+        if partner_fields.get(field).is_some() {
+            wanted.push(field.to_string());
+        }
+    }
+    let mut partner_wanted = wanted.clone();
+    partner_wanted.push("commercial_partner_id".to_string());
+    let rows = client
+        .read(
+            "res.partner",
+            vec![partner_id],
+            Some(partner_wanted),
+            ctx.clone(),
+        )
+        .await?;
+    let partner = rows
+        .get(0)
+        .cloned()
+        .ok_or_else(|| OdooError::InvalidResponse(format!("Partner {partner_id} not found")))?;
+    let commercial_id = partner
+        .get("commercial_partner_id")
+        .and_then(many2one_id)
+        .unwrap_or(partner_id);
+    // Credit limit and to-invoice amounts live on the commercial partner.
+    let commercial = if commercial_id == partner_id {
+        partner.clone()
+    } else {
+        client
+            .read(
+                "res.partner",
+                vec![commercial_id],
+                Some(wanted),
+                ctx.clone(),
+            )
+            .await?
+            .get(0)
+            .cloned()
+            .unwrap_or_default()
+    };
+
+    let account_fields = client.fields_get("account.account", ctx.clone()).await?;
+    let mut domain = vec![
+        json!(["partner_id", "child_of", commercial_id]),
+        receivable_domain(&account_fields),
+        json!(["reconciled", "=", false]),
+        json!(["parent_state", "=", "posted"]),
+    ];
+    if let Some(company_id) = options.company_id {
+        domain.push(json!(["company_id", "=", company_id]));
+    }
+    let sum = |domain: Vec<Value>| {
+        client.read_group(
+            MOVE_LINE_MODEL,
+            Some(Value::Array(domain)),
+            vec!["amount_residual:sum".to_string()],
+            vec![],
+            None,
+            None,
+            None,
+            Some(false),
+            ctx.clone(),
+        )
+    };
+    let (total_receivable, _) = group_totals(&sum(domain.clone()).await?);
+    let today = Utc::now().date_naive().to_string();
+    let mut overdue_domain = domain.clone();
+    overdue_domain.push(json!(["date_maturity", "<", today]));
+    let (overdue, overdue_item_count) = group_totals(&sum(overdue_domain.clone()).await?);
+
+    let oldest = client
+        .search_read(
+            MOVE_LINE_MODEL,
+            Some(Value::Array(overdue_domain)),
+            Some(vec!["date_maturity".to_string()]),
+            Some(1),
+            None,
+            Some("date_maturity asc".to_string()),
+            ctx,
+        )
+        .await?;
+    let oldest_due_date = oldest
+        .get(0)
+        .and_then(|row| row.get("date_maturity"))
+        .and_then(Value::as_str)
+        .map(String::from);
+
+    let uninvoiced_orders = commercial.get("credit_to_invoice").and_then(Value::as_f64);
+    let credit_limit = commercial
+        .get("credit_limit")
+        .and_then(Value::as_f64)
+        .filter(|limit| *limit > 0.0);
+    let (exposure, remaining_credit, over_limit) =
+        finish(total_receivable, uninvoiced_orders, credit_limit);
+
+    Ok(CreditExposure {
+        partner_id: commercial_id,
+        partner: commercial
+            .get("display_name")
+            .and_then(Value::as_str)
+            .map(String::from)
+            .or_else(|| partner.get("commercial_partner_id").and_then(many2one_name)),
+        total_receivable,
+        overdue,
+        not_due: total_receivable - overdue,
+        overdue_item_count,
+        oldest_due_date,
+        uninvoiced_orders,
+        exposure,
+        credit_limit,
+        remaining_credit,
+        over_limit,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn receivable_domain_follows_account_layout() {
+        assert_eq!(
+            receivable_domain(&json!({"account_type": {}})),
+            json!(["account_id.account_type", "=", "asset_receivable"])
+        );
+        assert_eq!(
+            receivable_domain(&json!({"internal_type": {}})),
+            json!(["account_id.internal_type", "=", "receivable"])
+        );
+    }
+
+    #[test]
+    fn group_totals_reads_count_variants() {
+        assert_eq!(
+            group_totals(&json!([{"amount_residual": 150.5, "__count": 3}])),
+            (150.5, 3)
+        );
+        assert_eq!(group_totals(&json!([])), (0.0, 0));
+    }
+
+    #[test]
+    fn finish_computes_remaining_credit() {
+        assert_eq!(
+            finish(800.0, Some(300.0), Some(1000.0)),
+            (1100.0, Some(-100.0), true)
+        );
+        assert_eq!(
+            finish(800.0, None, Some(1000.0)),
+            (800.0, Some(200.0), false)
+        );
+        assert_eq!(finish(800.0, None, None), (800.0, None, false));
+    }
+}
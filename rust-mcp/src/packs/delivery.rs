@@ -0,0 +1,327 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+use crate::odoo::types::{OdooError, OdooResult};
+use crate::odoo::unified_client::OdooClient;
+use crate::packs::{many2one_id, many2one_name, resolve_by_name};
+
+const PICKING_MODEL: &str = "stock.picking";
+const CARRIER_MODEL: &str = "delivery.carrier";
+const RATE_WIZARD_MODEL: &str = "choose.delivery.carrier";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackingOptions {
+    pub picking_ids: Option<Vec<i64>>,
+    /// Source document, e.g. the sale order name "S00042".
+    pub origin: Option<String>,
+    pub partner_id: Option<i64>,
+    /// Only pickings that already have a tracking reference.
+    pub only_tracked: Option<bool>,
+    pub limit: Option<i64>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendToShipperOptions {
+    pub picking_id: i64,
+    /// Regenerate even if the picking already has a tracking reference.
+    pub force: Option<bool>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateOptions {
+    pub order_id: i64,
+    /// Carrier by id or by name; defaults to the order's carrier.
+    pub carrier_id: Option<i64>,
+    pub carrier: Option<String>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryTracking {
+    pub picking_id: i64,
+    pub name: String,
+    pub origin: Option<String>,
+    pub state: Option<String>,
+    pub partner: Option<String>,
+    pub carrier_id: Option<i64>,
+    pub carrier: Option<String>,
+    pub tracking_ref: Option<String>,
+    pub tracking_url: Option<String>,
+    pub date_done: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackingList {
+    pub count: usize,
+    pub deliveries: Vec<DeliveryTracking>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShipmentSent {
+    pub picking_id: i64,
+    pub previous_tracking_ref: Option<String>,
+    pub tracking: DeliveryTracking,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShippingRate {
+    pub order_id: i64,
+    pub carrier_id: i64,
+    pub price: Option<f64>,
+    pub message: Option<String>,
+}
+
+const TRACKING_FIELDS: &[&str] = &[
+    "name",
+    "origin",
+    "state",
+    "partner_id",
+    "carrier_id",
+    "carrier_tracking_ref",
+    "carrier_tracking_url",
+    "date_done",
+];
+
+/// Odoo returns `false` for empty char fields.
+fn text(row: &Value, key: &str) -> Option<String> {
+    row.get(key)
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+}
+
+fn parse_tracking(row: &Value) -> Option<DeliveryTracking> {
+    Some(DeliveryTracking {
+        picking_id: row.get("id")?.as_i64()?,
+        name: text(row, "name").unwrap_or_default(),
+        origin: text(row, "origin"),
+        state: text(row, "state"),
+        partner: row.get("partner_id").and_then(many2one_name),
+        carrier_id: row.get("carrier_id").and_then(many2one_id),
+        carrier: row.get("carrier_id").and_then(many2one_name),
+        tracking_ref: text(row, "carrier_tracking_ref"),
+        tracking_url: text(row, "carrier_tracking_url"),
+        date_done: text(row, "date_done"),
+    })
+}
+
+async fn read_tracking(
+    client: &OdooClient,
+    picking_id: i64,
+    context: Option<Value>,
+) -> OdooResult<DeliveryTracking> {
+    let rows = client
+        .read(
+            PICKING_MODEL,
+            vec![picking_id],
+            Some(TRACKING_FIELDS.iter().map(|f| f.to_string()).collect()),
+            context,
+        )
+        .await?;
+    rows.as_array()
+        .and_then(|rows| rows.first())
+        .and_then(parse_tracking)
+        .ok_or_else(|| OdooError::InvalidResponse(format!("Picking {picking_id} not found")))
+}
+
+pub async fn delivery_tracking(
+    client: &OdooClient,
+    options: TrackingOptions,
+) -> OdooResult<TrackingList> {
+    let mut domain = vec![json!(["picking_type_code", "=", "outgoing"])];
+    if let Some(ids) = options.picking_ids.as_ref() {
+        domain.push(json!(["id", "in", ids]));
+    }
+    if let Some(origin) = options.origin.as_deref() {
+        domain.push(json!(["origin", "ilike", origin]));
+    }
+    if let Some(partner_id) = options.partner_id {
+        domain.push(json!(["partner_id", "child_of", partner_id]));
+    }
+    if options.only_tracked.unwrap_or(false) {
+        domain.push(json!(["carrier_tracking_ref", "!=", false]));
+    }
+
+    let rows = client
+        .search_read(
+            PICKING_MODEL,
+            Some(Value::Array(domain)),
+            Some(TRACKING_FIELDS.iter().map(|f| f.to_string()).collect()),
+            Some(options.limit.unwrap_or(50)),
+            None,
+            Some("id desc".to_string()),
+            options.context,
+        )
+        .await?;
+    let deliveries: Vec<DeliveryTracking> = rows
+        .as_array()
+        .map(|rows| rows.iter().filter_map(parse_tracking).collect())
+        .unwrap_or_default();
+    Ok(TrackingList {
+        count: deliveries.len(),
+        deliveries,
+    })
+}
+
+/// Guard against re-sending shipments Odoo would reject or duplicate.
+fn check_sendable(tracking: &DeliveryTracking, force: bool) -> OdooResult<()> {
+    if tracking.carrier_id.is_none() {
+        return Err(OdooError::InvalidResponse(format!(
+            "Picking {} has no carrier",
+            tracking.name
+        )));
+    }
+    if tracking.state.as_deref() != Some("done") {
+        return Err(OdooError::InvalidResponse(format!(
+            "Picking {} must be done before it is sent to the shipper",
+            tracking.name
+        )));
+    }
+    if tracking.tracking_ref.is_some() && !force {
+        return Err(OdooError::InvalidResponse(format!(
+            "Picking {} already has tracking reference {}; pass force to regenerate the label",
+            tracking.name,
+            tracking.tracking_ref.as_deref().unwrap_or_default()
+        )));
+    }
+    Ok(())
+}
+
+/// Re-run the carrier integration (`send_to_shipper`) to generate a label and tracking ref.
+pub async fn send_to_shipper(
+    client: &OdooClient,
+    options: SendToShipperOptions,
+) -> OdooResult<ShipmentSent> {
+    let before = read_tracking(client, options.picking_id, options.context.clone()).await?;
+    check_sendable(&before, options.force.unwrap_or(false))?;
+
+    client
+        .call_named(
+            PICKING_MODEL,
+            "send_to_shipper",
+            Some(vec![options.picking_id]),
+            Map::new(),
+            options.context.clone(),
+        )
+        .await?;
+
+    let tracking = read_tracking(client, options.picking_id, options.context).await?;
+    Ok(ShipmentSent {
+        picking_id: options.picking_id,
+        previous_tracking_ref: before.tracking_ref,
+        tracking,
+    })
+}
+
+/// Ask the carrier for a price through the "Add shipping" wizard (`choose.delivery.carrier`).
+///
+/// The wizard is transient; the sale order itself is not modified.
+pub async fn shipping_rate(client: &OdooClient, options: RateOptions) -> OdooResult<ShippingRate> {
+    let carrier_id = match (options.carrier_id, options.carrier.as_deref()) {
+        (Some(id), _) => id,
+        (None, Some(name)) => resolve_by_name(client, CARRIER_MODEL, "name", name, vec![]).await?,
+        (None, None) => {
+            let order = client
+                .read(
+                    "sale.order",
+                    vec![options.order_id],
+                    Some(vec!["carrier_id".to_string()]),
+                    options.context.clone(),
+                )
+                .await?;
+            order
+                .as_array()
+                .and_then(|rows| rows.first())
+                .and_then(|row| row.get("carrier_id"))
+                .and_then(many2one_id)
+                .ok_or_else(|| {
+                    OdooError::InvalidResponse(format!(
+                        "Sale order {} has no carrier; pass 'carrierId' or 'carrier'",
+                        options.order_id
+                    ))
+                })?
+        }
+    };
+
+    let wizard_id = client
+        .create(
+            RATE_WIZARD_MODEL,
+            json!({ "order_id": options.order_id, "carrier_id": carrier_id }),
+            options.context.clone(),
+        )
+        .await?;
+    client
+        .call_named(
+            RATE_WIZARD_MODEL,
+            "update_price",
+            Some(vec![wizard_id]),
+            Map::new(),
+            options.context.clone(),
+        )
+        .await?;
+    let rows = client
+        .read(
+            RATE_WIZARD_MODEL,
+            vec![wizard_id],
+            Some(vec![
+                "delivery_price".to_string(),
+                "delivery_message".to_string(),
+            ]),
+            options.context,
+        )
+        .await?;
+    let row = rows
+        .as_array()
+        .and_then(|rows| rows.first())
+        .cloned()
+        .unwrap_or_default();
+    Ok(ShippingRate {
+        order_id: options.order_id,
+        carrier_id,
+        price: row.get("delivery_price").and_then(|v| v.as_f64()),
+        message: text(&row, "delivery_message"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracking(state: &str, tracking_ref: Option<&str>) -> DeliveryTracking {
+        parse_tracking(&json!({
+            "id": 7, "name": "WH/OUT/00007", "state": state, "carrier_id": [1, "DHL"],
+            "carrier_tracking_ref": tracking_ref.map(Value::from).unwrap_or(json!(false)),
+            "origin": false, "partner_id": [3, "Azure Interior"]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_tracking_treats_false_as_missing() {
+        let parsed = tracking("done", None);
+        assert_eq!(parsed.tracking_ref, None);
+        assert_eq!(parsed.origin, None);
+        assert_eq!(parsed.carrier.as_deref(), Some("DHL"));
+    }
+
+    #[test]
+    fn check_sendable_requires_done_and_respects_force() {
+        assert!(check_sendable(&tracking("done", None), false).is_ok());
+        assert!(check_sendable(&tracking("assigned", None), false).is_err());
+        assert!(check_sendable(&tracking("done", Some("1Z999")), false).is_err());
+        assert!(check_sendable(&tracking("done", Some("1Z999")), true).is_ok());
+
+        let mut no_carrier = tracking("done", None);
+        no_carrier.carrier_id = None;
+        assert!(check_sendable(&no_carrier, true).is_err());
+    }
+}
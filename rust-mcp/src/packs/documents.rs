@@ -0,0 +1,349 @@
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+use crate::odoo::types::{OdooError, OdooResult};
+use crate::odoo::unified_client::OdooClient;
+use crate::packs::{many2one_id, many2one_name, resolve_by_name};
+
+const DOCUMENT_MODEL: &str = "documents.document";
+/// Workspaces were a dedicated model up to Odoo 17; Odoo 18 made them folder-type documents.
+const LEGACY_FOLDER_MODEL: &str = "documents.folder";
+const TAG_MODEL: &str = "documents.tag";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListWorkspacesOptions {
+    /// Only workspaces whose name contains this text.
+    pub name: Option<String>,
+    pub limit: Option<i64>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadDocumentOptions {
+    pub name: String,
+    /// File content, base64-encoded.
+    pub content_base64: String,
+    pub mimetype: Option<String>,
+    /// Target workspace by id or by name.
+    pub workspace_id: Option<i64>,
+    pub workspace: Option<String>,
+    /// Tag names; each must match exactly one `documents.tag`.
+    pub tags: Option<Vec<String>>,
+    pub partner_id: Option<i64>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Signer {
+    pub partner_id: i64,
+    /// `sign.item.role` id; defaults to the template's first role.
+    pub role_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignRequestOptions {
+    /// Existing sign template to send.
+    pub template_id: Option<i64>,
+    /// Document to turn into a sign template when no template is given.
+    pub document_id: Option<i64>,
+    pub signers: Vec<Signer>,
+    pub subject: Option<String>,
+    pub message: Option<String>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Workspace {
+    pub id: i64,
+    pub name: String,
+    pub parent_id: Option<i64>,
+    pub parent: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceList {
+    pub model: String,
+    pub count: usize,
+    pub workspaces: Vec<Workspace>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadedDocument {
+    pub document_id: i64,
+    pub workspace_id: i64,
+    pub tag_ids: Vec<i64>,
+    pub size_bytes: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignRequestResult {
+    pub sign_request_id: i64,
+    pub template_id: i64,
+    pub signer_count: usize,
+}
+
+pub async fn list_workspaces(
+    client: &OdooClient,
+    options: ListWorkspacesOptions,
+) -> OdooResult<WorkspaceList> {
+    let limit = Some(options.limit.unwrap_or(200));
+    let name_filter = options
+        .name
+        .as_ref()
+        .map(|name| json!(["name", "ilike", name]));
+
+    let legacy_domain = Value::Array(name_filter.iter().cloned().collect());
+    let legacy = client
+        .search_read(
+            LEGACY_FOLDER_MODEL,
+            Some(legacy_domain),
+            Some(vec!["name".to_string(), "parent_folder_id".to_string()]),
+            limit,
+            None,
+            Some("name asc".to_string()),
+            options.context.clone(),
+        )
+        .await;
+    let (model, rows, parent_field) = match legacy {
+        Ok(rows) => (LEGACY_FOLDER_MODEL, rows, "parent_folder_id"),
+        Err(OdooError::Api { .. }) => {
+            let mut domain = vec![json!(["type", "=", "folder"])];
+            domain.extend(name_filter);
+            let rows = client
+                .search_read(
+                    DOCUMENT_MODEL,
+                    Some(Value::Array(domain)),
+                    Some(vec!["name".to_string(), "folder_id".to_string()]),
+                    limit,
+                    None,
+                    Some("name asc".to_string()),
+                    options.context,
+                )
+                .await?;
+            (DOCUMENT_MODEL, rows, "folder_id")
+        }
+        Err(e) => return Err(e),
+    };
+
+    let workspaces = parse_workspaces(&rows, parent_field);
+    Ok(WorkspaceList {
+        model: model.to_string(),
+        count: workspaces.len(),
+        workspaces,
+    })
+}
+
+fn parse_workspaces(rows: &Value, parent_field: &str) -> Vec<Workspace> {
+    rows.as_array()
+        .map(|rows| {
+            rows.iter()
+                .filter_map(|row| {
+                    Some(Workspace {
+                        id: row.get("id")?.as_i64()?,
+                        name: row
+                            .get("name")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or_default()
+                            .to_string(),
+                        parent_id: row.get(parent_field).and_then(many2one_id),
+                        parent: row.get(parent_field).and_then(many2one_name),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+async fn resolve_workspace(client: &OdooClient, name: &str) -> OdooResult<i64> {
+    match resolve_by_name(client, LEGACY_FOLDER_MODEL, "name", name, vec![]).await {
+        Err(OdooError::Api { .. }) => {
+            resolve_by_name(
+                client,
+                DOCUMENT_MODEL,
+                "name",
+                name,
+                vec![json!(["type", "=", "folder"])],
+            )
+            .await
+        }
+        other => other,
+    }
+}
+
+pub async fn upload_document(
+    client: &OdooClient,
+    options: UploadDocumentOptions,
+) -> OdooResult<UploadedDocument> {
+    if options.name.trim().is_empty() {
+        return Err(OdooError::InvalidResponse(
+            "Document 'name' must not be empty".to_string(),
+        ));
+    }
+    let size_bytes = base64::engine::general_purpose::STANDARD
+        .decode(options.content_base64.trim())
+        .map_err(|e| {
+            OdooError::InvalidResponse(format!("'contentBase64' is not valid base64: {e}"))
+        })?
+        .len();
+
+    let workspace_id = match (options.workspace_id, options.workspace.as_deref()) {
+        (Some(id), _) => id,
+        (None, Some(name)) => resolve_workspace(client, name).await?,
+        (None, None) => {
+            return Err(OdooError::InvalidResponse(
+                "Provide 'workspaceId' or 'workspace'".to_string(),
+            ));
+        }
+    };
+
+    let mut tag_ids = Vec::new();
+    for tag in options.tags.unwrap_or_default() {
+        tag_ids.push(resolve_by_name(client, TAG_MODEL, "name", &tag, vec![]).await?);
+    }
+
+    let mut values = Map::new();
+    values.insert("name".into(), json!(options.name));
+    values.insert("datas".into(), json!(options.content_base64.trim()));
+    values.insert("folder_id".into(), json!(workspace_id));
+    if !tag_ids.is_empty() {
+        values.insert("tag_ids".into(), json!([[6, 0, tag_ids]]));
+    }
+    if let Some(mimetype) = options.mimetype {
+        values.insert("mimetype".into(), json!(mimetype));
+    }
+    if let Some(partner_id) = options.partner_id {
+        values.insert("partner_id".into(), json!(partner_id));
+    }
+
+    let document_id = client
+        .create(DOCUMENT_MODEL, Value::Object(values), options.context)
+        .await?;
+    Ok(UploadedDocument {
+        document_id,
+        workspace_id,
+        tag_ids,
+        size_bytes,
+    })
+}
+
+pub async fn request_signature(
+    client: &OdooClient,
+    options: SignRequestOptions,
+) -> OdooResult<SignRequestResult> {
+    if options.signers.is_empty() {
+        return Err(OdooError::InvalidResponse(
+            "At least one signer is required".to_string(),
+        ));
+    }
+
+    let template_id = match (options.template_id, options.document_id) {
+        (Some(id), _) => id,
+        (None, Some(document_id)) => {
+            let rows = client
+                .read(
+                    DOCUMENT_MODEL,
+                    vec![document_id],
+                    Some(vec!["attachment_id".to_string()]),
+                    options.context.clone(),
+                )
+                .await?;
+            let attachment_id = rows
+                .as_array()
+                .and_then(|rows| rows.first())
+                .and_then(|row| row.get("attachment_id"))
+                .and_then(many2one_id)
+                .ok_or_else(|| {
+                    OdooError::InvalidResponse(format!(
+                        "Document {document_id} has no attachment to sign"
+                    ))
+                })?;
+            client
+                .create(
+                    "sign.template",
+                    json!({"attachment_id": attachment_id}),
+                    options.context.clone(),
+                )
+                .await?
+        }
+        (None, None) => {
+            return Err(OdooError::InvalidResponse(
+                "Provide 'templateId' or 'documentId'".to_string(),
+            ));
+        }
+    };
+
+    let default_role = if options.signers.iter().any(|s| s.role_id.is_none()) {
+        client
+            .search(
+                "sign.item.role",
+                Some(json!([])),
+                Some(1),
+                None,
+                Some("sequence asc, id asc".to_string()),
+                options.context.clone(),
+            )
+            .await?
+            .first()
+            .copied()
+    } else {
+        None
+    };
+
+    let items: Vec<Value> = options
+        .signers
+        .iter()
+        .map(|signer| {
+            json!([0, 0, {
+                "partner_id": signer.partner_id,
+                "role_id": signer.role_id.or(default_role),
+            }])
+        })
+        .collect();
+
+    let mut values = Map::new();
+    values.insert("template_id".into(), json!(template_id));
+    values.insert("request_item_ids".into(), Value::Array(items));
+    if let Some(subject) = options.subject {
+        values.insert("subject".into(), json!(subject.clone()));
+        values.insert("reference".into(), json!(subject));
+    }
+    if let Some(message) = options.message {
+        values.insert("message".into(), json!(message));
+    }
+
+    let sign_request_id = client
+        .create("sign.request", Value::Object(values), options.context)
+        .await?;
+    Ok(SignRequestResult {
+        sign_request_id,
+        template_id,
+        signer_count: options.signers.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_workspaces_reads_parent_field() {
+        let rows = json!([
+            {"id": 1, "name": "Finance", "parent_folder_id": false},
+            {"id": 2, "name": "Invoices", "parent_folder_id": [1, "Finance"]}
+        ]);
+        let workspaces = parse_workspaces(&rows, "parent_folder_id");
+        assert_eq!(workspaces.len(), 2);
+        assert_eq!(workspaces[0].parent_id, None);
+        assert_eq!(workspaces[1].parent_id, Some(1));
+        assert_eq!(workspaces[1].parent.as_deref(), Some("Finance"));
+    }
+}
@@ -0,0 +1,452 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+use crate::odoo::types::{OdooError, OdooResult};
+use crate::odoo::unified_client::OdooClient;
+use crate::packs::many2one_name;
+
+const EVENT_MODEL: &str = "event.event";
+const REGISTRATION_MODEL: &str = "event.registration";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListEventsOptions {
+    /// Only events whose name contains this text.
+    pub name: Option<String>,
+    /// Only events starting on or after this date (YYYY-MM-DD). Defaults to upcoming events.
+    pub date_from: Option<String>,
+    pub date_to: Option<String>,
+    pub limit: Option<i64>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Attendee {
+    pub name: String,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub partner_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterAttendeesOptions {
+    pub event_id: i64,
+    pub attendees: Vec<Attendee>,
+    /// `event.event.ticket` id applied to every registration.
+    pub ticket_id: Option<i64>,
+    /// Register attendees even if their email is already registered for the event.
+    pub allow_duplicates: Option<bool>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportAttendeesOptions {
+    pub event_id: i64,
+    /// Registration states to include; defaults to all but cancelled.
+    pub states: Option<Vec<String>>,
+    /// "json" (default) or "csv".
+    pub format: Option<String>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventSummary {
+    pub id: i64,
+    pub name: String,
+    pub date_begin: Option<String>,
+    pub date_end: Option<String>,
+    pub seats_max: Option<i64>,
+    pub seats_taken: i64,
+    pub organizer: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventList {
+    pub count: usize,
+    pub events: Vec<EventSummary>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedAttendee {
+    pub name: String,
+    pub email: Option<String>,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistrationResult {
+    pub event_id: i64,
+    pub registration_ids: Vec<i64>,
+    pub skipped: Vec<SkippedAttendee>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttendeeRow {
+    pub registration_id: i64,
+    pub name: String,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub partner: Option<String>,
+    pub ticket: Option<String>,
+    pub state: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttendeeExport {
+    pub event_id: i64,
+    pub count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attendees: Option<Vec<AttendeeRow>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub csv: Option<String>,
+}
+
+fn text(row: &Value, key: &str) -> Option<String> {
+    row.get(key).and_then(|v| v.as_str()).map(String::from)
+}
+
+fn normalize_email(email: &str) -> String {
+    email.trim().to_ascii_lowercase()
+}
+
+/// Split attendees into those to register and those skipped as duplicates, both against
+/// existing registrations and within the request itself.
+fn partition_attendees(
+    attendees: Vec<Attendee>,
+    existing_emails: &[String],
+    allow_duplicates: bool,
+) -> (Vec<Attendee>, Vec<SkippedAttendee>) {
+    let mut seen: Vec<String> = existing_emails.iter().map(|e| normalize_email(e)).collect();
+    let mut keep = Vec::new();
+    let mut skipped = Vec::new();
+    for attendee in attendees {
+        if attendee.name.trim().is_empty() {
+            skipped.push(SkippedAttendee {
+                name: attendee.name,
+                email: attendee.email,
+                reason: "missing name".to_string(),
+            });
+            continue;
+        }
+        if let Some(email) = attendee.email.as_deref().map(normalize_email)
+            && !email.is_empty()
+        {
+            if !allow_duplicates && seen.contains(&email) {
+                skipped.push(SkippedAttendee {
+                    name: attendee.name,
+                    email: attendee.email,
+                    reason: "email already registered".to_string(),
+                });
+                continue;
+            }
+            seen.push(email);
+        }
+        keep.push(attendee);
+    }
+    (keep, skipped)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn attendees_csv(rows: &[AttendeeRow]) -> String {
+    let mut out = String::from("registration_id,name,email,phone,partner,ticket,state\n");
+    for row in rows {
+        let cells = [
+            row.registration_id.to_string(),
+            row.name.clone(),
+            row.email.clone().unwrap_or_default(),
+            row.phone.clone().unwrap_or_default(),
+            row.partner.clone().unwrap_or_default(),
+            row.ticket.clone().unwrap_or_default(),
+            row.state.clone().unwrap_or_default(),
+        ];
+        let line: Vec<String> = cells.iter().map(|c| csv_field(c)).collect();
+        out.push_str(&line.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+pub async fn list_events(client: &OdooClient, options: ListEventsOptions) -> OdooResult<EventList> {
+    let mut domain = Vec::new();
+    if let Some(name) = options.name.as_deref() {
+        domain.push(json!(["name", "ilike", name]));
+    }
+    match options.date_from.as_deref() {
+        Some(from) => domain.push(json!(["date_begin", ">=", from])),
+        None => domain.push(json!([
+            "date_end",
+            ">=",
+            chrono::Utc::now().format("%Y-%m-%d").to_string()
+        ])),
+    }
+    if let Some(to) = options.date_to.as_deref() {
+        domain.push(json!(["date_begin", "<=", to]));
+    }
+
+    let rows = client
+        .search_read(
+            EVENT_MODEL,
+            Some(Value::Array(domain)),
+            Some(vec![
+                "name".to_string(),
+                "date_begin".to_string(),
+                "date_end".to_string(),
+                "seats_max".to_string(),
+                "seats_taken".to_string(),
+                "organizer_id".to_string(),
+            ]),
+            Some(options.limit.unwrap_or(50)),
+            None,
+            Some("date_begin asc".to_string()),
+            options.context,
+        )
+        .await?;
+    let events: Vec<EventSummary> = rows
+        .as_array()
+        .map(|rows| {
+            rows.iter()
+                .filter_map(|row| {
+                    Some(EventSummary {
+                        id: row.get("id")?.as_i64()?,
+                        name: text(row, "name").unwrap_or_default(),
+                        date_begin: text(row, "date_begin"),
+                        date_end: text(row, "date_end"),
+                        // 0 means unlimited seats in Odoo.
+                        seats_max: row
+                            .get("seats_max")
+                            .and_then(|v| v.as_i64())
+                            .filter(|n| *n > 0),
+                        seats_taken: row.get("seats_taken").and_then(|v| v.as_i64()).unwrap_or(0),
+                        organizer: row.get("organizer_id").and_then(many2one_name),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(EventList {
+        count: events.len(),
+        events,
+    })
+}
+
+pub async fn register_attendees(
+    client: &OdooClient,
+    options: RegisterAttendeesOptions,
+) -> OdooResult<RegistrationResult> {
+    if options.attendees.is_empty() {
+        return Err(OdooError::InvalidResponse(
+            "'attendees' must not be empty".to_string(),
+        ));
+    }
+
+    let existing = client
+        .search_read(
+            REGISTRATION_MODEL,
+            Some(json!([
+                ["event_id", "=", options.event_id],
+                ["state", "!=", "cancel"],
+                ["email", "!=", false]
+            ])),
+            Some(vec!["email".to_string()]),
+            None,
+            None,
+            None,
+            options.context.clone(),
+        )
+        .await?;
+    let existing_emails: Vec<String> = existing
+        .as_array()
+        .map(|rows| rows.iter().filter_map(|row| text(row, "email")).collect())
+        .unwrap_or_default();
+
+    let (attendees, skipped) = partition_attendees(
+        options.attendees,
+        &existing_emails,
+        options.allow_duplicates.unwrap_or(false),
+    );
+
+    let mut registration_ids = Vec::new();
+    for attendee in attendees {
+        let mut values = Map::new();
+        values.insert("event_id".into(), json!(options.event_id));
+        values.insert("name".into(), json!(attendee.name));
+        if let Some(email) = attendee.email {
+            values.insert("email".into(), json!(email.trim()));
+        }
+        if let Some(phone) = attendee.phone {
+            values.insert("phone".into(), json!(phone));
+        }
+        if let Some(partner_id) = attendee.partner_id {
+            values.insert("partner_id".into(), json!(partner_id));
+        }
+        if let Some(ticket_id) = options.ticket_id {
+            values.insert("event_ticket_id".into(), json!(ticket_id));
+        }
+        registration_ids.push(
+            client
+                .create(
+                    REGISTRATION_MODEL,
+                    Value::Object(values),
+                    options.context.clone(),
+                )
+                .await?,
+        );
+    }
+
+    Ok(RegistrationResult {
+        event_id: options.event_id,
+        registration_ids,
+        skipped,
+    })
+}
+
+pub async fn export_attendees(
+    client: &OdooClient,
+    options: ExportAttendeesOptions,
+) -> OdooResult<AttendeeExport> {
+    let csv = match options.format.as_deref().unwrap_or("json") {
+        "json" => false,
+        "csv" => true,
+        other => {
+            return Err(OdooError::InvalidResponse(format!(
+                "Unknown format '{other}' (expected json or csv)"
+            )));
+        }
+    };
+    let mut domain = vec![json!(["event_id", "=", options.event_id])];
+    match options.states.filter(|s| !s.is_empty()) {
+        Some(states) => domain.push(json!(["state", "in", states])),
+        None => domain.push(json!(["state", "!=", "cancel"])),
+    }
+
+    let rows = client
+        .search_read(
+            REGISTRATION_MODEL,
+            Some(Value::Array(domain)),
+            Some(vec![
+                "name".to_string(),
+                "email".to_string(),
+                "phone".to_string(),
+                "partner_id".to_string(),
+                "event_ticket_id".to_string(),
+                "state".to_string(),
+            ]),
+            None,
+            None,
+            Some("name asc".to_string()),
+            options.context,
+        )
+        .await?;
+    let attendees: Vec<AttendeeRow> = rows
+        .as_array()
+        .map(|rows| {
+            rows.iter()
+                .filter_map(|row| {
+                    Some(AttendeeRow {
+                        registration_id: row.get("id")?.as_i64()?,
+                        name: text(row, "name").unwrap_or_default(),
+                        email: text(row, "email"),
+                        phone: text(row, "phone"),
+                        partner: row.get("partner_id").and_then(many2one_name),
+                        ticket: row.get("event_ticket_id").and_then(many2one_name),
+                        state: text(row, "state"),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let count = attendees.len();
+    Ok(if csv {
+        AttendeeExport {
+            event_id: options.event_id,
+            count,
+            attendees: None,
+            csv: Some(attendees_csv(&attendees)),
+        }
+    } else {
+        AttendeeExport {
+            event_id: options.event_id,
+            count,
+            attendees: Some(attendees),
+            csv: None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attendee(name: &str, email: Option<&str>) -> Attendee {
+        Attendee {
+            name: name.to_string(),
+            email: email.map(String::from),
+            ..Attendee::default()
+        }
+    }
+
+    #[test]
+    fn partition_skips_existing_and_repeated_emails() {
+        let existing = vec!["ana@example.com".to_string()];
+        let (keep, skipped) = partition_attendees(
+            vec![
+                attendee("Ana", Some(" ANA@example.com ")),
+                attendee("Ben", Some("ben@example.com")),
+                attendee("Ben again", Some("Ben@Example.com")),
+                attendee("No email", None),
+                attendee("", Some("blank@example.com")),
+            ],
+            &existing,
+            false,
+        );
+        let kept: Vec<_> = keep.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(kept, vec!["Ben", "No email"]);
+        assert_eq!(skipped.len(), 3);
+        assert_eq!(skipped[2].reason, "missing name");
+    }
+
+    #[test]
+    fn partition_allows_duplicates_when_requested() {
+        let existing = vec!["ana@example.com".to_string()];
+        let (keep, skipped) = partition_attendees(
+            vec![attendee("Ana", Some("ana@example.com"))],
+            &existing,
+            true,
+        );
+        assert_eq!(keep.len(), 1);
+        assert!(skipped.is_empty());
+    }
+
+    #[test]
+    fn attendees_csv_quotes_special_characters() {
+        let rows = vec![AttendeeRow {
+            registration_id: 3,
+            name: "Doe, Jane".to_string(),
+            email: Some("jane@example.com".to_string()),
+            phone: None,
+            partner: None,
+            ticket: Some("VIP \"Gold\"".to_string()),
+            state: Some("open".to_string()),
+        }];
+        let csv = attendees_csv(&rows);
+        assert!(csv.starts_with("registration_id,name,"));
+        assert!(csv.contains("3,\"Doe, Jane\",jane@example.com,,,\"VIP \"\"Gold\"\"\",open"));
+    }
+}
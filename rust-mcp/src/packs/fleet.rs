@@ -0,0 +1,322 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+use crate::odoo::types::{OdooError, OdooResult};
+use crate::odoo::unified_client::OdooClient;
+use crate::packs::{many2one_id, many2one_name, resolve_by_name};
+
+const VEHICLE_MODEL: &str = "fleet.vehicle";
+const ODOMETER_MODEL: &str = "fleet.vehicle.odometer";
+const SERVICE_MODEL: &str = "fleet.vehicle.log.services";
+const SERVICE_TYPE_MODEL: &str = "fleet.service.type";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListVehiclesOptions {
+    /// Substring match on the license plate.
+    pub license_plate: Option<String>,
+    pub driver_id: Option<i64>,
+    /// Vehicle status name (e.g. "Registered").
+    pub state: Option<String>,
+    pub limit: Option<i64>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogOdometerOptions {
+    /// Target vehicle by id or by exact license plate.
+    pub vehicle_id: Option<i64>,
+    pub license_plate: Option<String>,
+    pub value: f64,
+    /// Reading date (YYYY-MM-DD); Odoo defaults to today.
+    pub date: Option<String>,
+    /// Accept a reading lower than the last one (e.g. after a counter replacement).
+    pub allow_decrease: Option<bool>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateServiceOptions {
+    pub vehicle_id: Option<i64>,
+    pub license_plate: Option<String>,
+    /// Service type name (`fleet.service.type`).
+    pub service_type: String,
+    pub amount: Option<f64>,
+    pub date: Option<String>,
+    pub vendor_id: Option<i64>,
+    pub description: Option<String>,
+    pub odometer: Option<f64>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Vehicle {
+    pub id: i64,
+    pub name: String,
+    pub license_plate: Option<String>,
+    pub model: Option<String>,
+    pub driver_id: Option<i64>,
+    pub driver: Option<String>,
+    pub state: Option<String>,
+    pub odometer: f64,
+    pub odometer_unit: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VehicleList {
+    pub count: usize,
+    pub vehicles: Vec<Vehicle>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OdometerLog {
+    pub odometer_id: i64,
+    pub vehicle_id: i64,
+    pub value: f64,
+    pub previous_value: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceLog {
+    pub service_id: i64,
+    pub vehicle_id: i64,
+    pub service_type_id: i64,
+}
+
+fn parse_vehicle(row: &Value) -> Option<Vehicle> {
+    let text = |key: &str| row.get(key).and_then(|v| v.as_str()).map(String::from);
+    Some(Vehicle {
+        id: row.get("id")?.as_i64()?,
+        name: text("name").unwrap_or_default(),
+        license_plate: text("license_plate"),
+        model: row.get("model_id").and_then(many2one_name),
+        driver_id: row.get("driver_id").and_then(many2one_id),
+        driver: row.get("driver_id").and_then(many2one_name),
+        state: row.get("state_id").and_then(many2one_name),
+        odometer: row.get("odometer").and_then(|v| v.as_f64()).unwrap_or(0.0),
+        odometer_unit: text("odometer_unit"),
+    })
+}
+
+/// Reject odometer readings that go backwards unless explicitly allowed.
+fn check_odometer(previous: Option<f64>, value: f64, allow_decrease: bool) -> OdooResult<()> {
+    if value < 0.0 {
+        return Err(OdooError::InvalidResponse(
+            "Odometer 'value' must not be negative".to_string(),
+        ));
+    }
+    match previous {
+        Some(previous) if value < previous && !allow_decrease => {
+            Err(OdooError::InvalidResponse(format!(
+                "Odometer reading {value} is lower than the last reading {previous}; pass allowDecrease to record it anyway"
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// First candidate field present in a `fields_get` result; field names drift across versions.
+fn pick_field<'a>(fields: &Value, candidates: &[&'a str]) -> Option<&'a str> {
+    candidates
+        .iter()
+        .copied()
+        .find(|name| fields.get(*name).is_some())
+}
+
+async fn resolve_vehicle(
+    client: &OdooClient,
+    vehicle_id: Option<i64>,
+    license_plate: Option<&str>,
+) -> OdooResult<i64> {
+    match (vehicle_id, license_plate) {
+        (Some(id), _) => Ok(id),
+        (None, Some(plate)) => {
+            resolve_by_name(client, VEHICLE_MODEL, "license_plate", plate, vec![]).await
+        }
+        (None, None) => Err(OdooError::InvalidResponse(
+            "Provide 'vehicleId' or 'licensePlate'".to_string(),
+        )),
+    }
+}
+
+pub async fn list_vehicles(
+    client: &OdooClient,
+    options: ListVehiclesOptions,
+) -> OdooResult<VehicleList> {
+    let mut domain = Vec::new();
+    if let Some(plate) = options.license_plate.as_deref() {
+        domain.push(json!(["license_plate", "ilike", plate]));
+    }
+    if let Some(driver_id) = options.driver_id {
+        domain.push(json!(["driver_id", "=", driver_id]));
+    }
+    if let Some(state) = options.state.as_deref() {
+        domain.push(json!(["state_id.name", "=ilike", state]));
+    }
+
+    let rows = client
+        .search_read(
+            VEHICLE_MODEL,
+            Some(Value::Array(domain)),
+            Some(vec![
+                "name".to_string(),
+                "license_plate".to_string(),
+                "model_id".to_string(),
+                "driver_id".to_string(),
+                "state_id".to_string(),
+                "odometer".to_string(),
+                "odometer_unit".to_string(),
+            ]),
+            Some(options.limit.unwrap_or(100)),
+            None,
+            Some("license_plate asc".to_string()),
+            options.context,
+        )
+        .await?;
+    let vehicles: Vec<Vehicle> = rows
+        .as_array()
+        .map(|rows| rows.iter().filter_map(parse_vehicle).collect())
+        .unwrap_or_default();
+    Ok(VehicleList {
+        count: vehicles.len(),
+        vehicles,
+    })
+}
+
+pub async fn log_odometer(
+    client: &OdooClient,
+    options: LogOdometerOptions,
+) -> OdooResult<OdometerLog> {
+    let vehicle_id =
+        resolve_vehicle(client, options.vehicle_id, options.license_plate.as_deref()).await?;
+
+    let last = client
+        .search_read(
+            ODOMETER_MODEL,
+            Some(json!([["vehicle_id", "=", vehicle_id]])),
+            Some(vec!["value".to_string()]),
+            Some(1),
+            None,
+            Some("value desc".to_string()),
+            options.context.clone(),
+        )
+        .await?;
+    let previous_value = last
+        .as_array()
+        .and_then(|rows| rows.first())
+        .and_then(|row| row.get("value"))
+        .and_then(|v| v.as_f64());
+    check_odometer(
+        previous_value,
+        options.value,
+        options.allow_decrease.unwrap_or(false),
+    )?;
+
+    let mut values = Map::new();
+    values.insert("vehicle_id".into(), json!(vehicle_id));
+    values.insert("value".into(), json!(options.value));
+    if let Some(date) = options.date {
+        values.insert("date".into(), json!(date));
+    }
+    let odometer_id = client
+        .create(ODOMETER_MODEL, Value::Object(values), options.context)
+        .await?;
+    Ok(OdometerLog {
+        odometer_id,
+        vehicle_id,
+        value: options.value,
+        previous_value,
+    })
+}
+
+pub async fn create_service(
+    client: &OdooClient,
+    options: CreateServiceOptions,
+) -> OdooResult<ServiceLog> {
+    let vehicle_id =
+        resolve_vehicle(client, options.vehicle_id, options.license_plate.as_deref()).await?;
+    let service_type_id = resolve_by_name(
+        client,
+        SERVICE_TYPE_MODEL,
+        "name",
+        &options.service_type,
+        vec![],
+    )
+    .await?;
+    let fields = client
+        .fields_get(SERVICE_MODEL, options.context.clone())
+        .await?;
+
+    let mut values = Map::new();
+    values.insert("vehicle_id".into(), json!(vehicle_id));
+    values.insert("service_type_id".into(), json!(service_type_id));
+    if let Some(amount) = options.amount {
+        let field = pick_field(&fields, &["amount", "cost_amount"]).unwrap_or("amount");
+        values.insert(field.into(), json!(amount));
+    }
+    if let Some(date) = options.date {
+        values.insert("date".into(), json!(date));
+    }
+    if let Some(vendor_id) = options.vendor_id {
+        values.insert("vendor_id".into(), json!(vendor_id));
+    }
+    if let Some(description) = options.description {
+        let field = pick_field(&fields, &["description", "notes"]).unwrap_or("description");
+        values.insert(field.into(), json!(description));
+    }
+    if let Some(odometer) = options.odometer {
+        values.insert("odometer".into(), json!(odometer));
+    }
+
+    let service_id = client
+        .create(SERVICE_MODEL, Value::Object(values), options.context)
+        .await?;
+    Ok(ServiceLog {
+        service_id,
+        vehicle_id,
+        service_type_id,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_odometer_rejects_backwards_readings() {
+        assert!(check_odometer(None, 10.0, false).is_ok());
+        assert!(check_odometer(Some(100.0), 150.0, false).is_ok());
+        assert!(check_odometer(Some(100.0), 50.0, false).is_err());
+        assert!(check_odometer(Some(100.0), 50.0, true).is_ok());
+        assert!(check_odometer(None, -1.0, true).is_err());
+    }
+
+    #[test]
+    fn pick_field_prefers_first_present_candidate() {
+        let fields = json!({"cost_amount": {}, "notes": {}});
+        assert_eq!(
+            pick_field(&fields, &["amount", "cost_amount"]),
+            Some("cost_amount")
+        );
+        assert_eq!(pick_field(&fields, &["description"]), None);
+    }
+
+    #[test]
+    fn parse_vehicle_reads_many2ones() {
+        let row = json!({
+            "id": 4, "name": "Audi/A3/1-ABC-123", "license_plate": "1-ABC-123",
+            "model_id": [2, "Audi/A3"], "driver_id": [8, "Ana"], "state_id": [1, "Registered"],
+            "odometer": 42000.0, "odometer_unit": "kilometers"
+        });
+        let vehicle = parse_vehicle(&row).unwrap();
+        assert_eq!(vehicle.driver_id, Some(8));
+        assert_eq!(vehicle.state.as_deref(), Some("Registered"));
+        assert_eq!(vehicle.odometer, 42000.0);
+    }
+}
@@ -0,0 +1,365 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+use crate::odoo::types::{OdooError, OdooResult};
+use crate::odoo::unified_client::OdooClient;
+use crate::packs::{many2one_id, many2one_name, resolve_by_name};
+
+const TICKET_MODEL: &str = "helpdesk.ticket";
+const TEAM_MODEL: &str = "helpdesk.team";
+const STAGE_MODEL: &str = "helpdesk.stage";
+const SLA_STATUS_MODEL: &str = "helpdesk.sla.status";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateTicketOptions {
+    pub name: String,
+    pub description: Option<String>,
+    pub partner_id: Option<i64>,
+    pub partner_name: Option<String>,
+    pub partner_email: Option<String>,
+    pub team: Option<String>,
+    pub stage: Option<String>,
+    pub user: Option<String>,
+    pub priority: Option<String>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssignTicketOptions {
+    pub ticket_id: i64,
+    pub team: Option<String>,
+    pub stage: Option<String>,
+    pub user: Option<String>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplyOptions {
+    pub ticket_id: i64,
+    pub body: String,
+    /// Post an internal note instead of a customer-visible reply.
+    pub internal: Option<bool>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlaStatusOptions {
+    pub ticket_ids: Option<Vec<i64>>,
+    pub team: Option<String>,
+    /// Only return SLA lines in this state: "ongoing", "reached", or "failed".
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TicketAssignment {
+    pub ticket_id: i64,
+    pub team_id: Option<i64>,
+    pub stage_id: Option<i64>,
+    pub user_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TicketReply {
+    pub ticket_id: i64,
+    pub message_id: Option<i64>,
+    pub internal: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlaLine {
+    pub ticket_id: Option<i64>,
+    pub ticket: Option<String>,
+    pub sla: Option<String>,
+    pub status: String,
+    pub deadline: Option<String>,
+    pub reached_datetime: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlaStatusReport {
+    pub total: usize,
+    pub by_status: BTreeMap<String, usize>,
+    pub lines: Vec<SlaLine>,
+}
+
+async fn resolve_team(client: &OdooClient, team: &str) -> OdooResult<i64> {
+    resolve_by_name(client, TEAM_MODEL, "name", team, vec![]).await
+}
+
+async fn resolve_stage(client: &OdooClient, stage: &str, team_id: Option<i64>) -> OdooResult<i64> {
+    let scope = team_id
+        .map(|id| vec![json!(["team_ids", "in", [id]])])
+        .unwrap_or_default();
+    resolve_by_name(client, STAGE_MODEL, "name", stage, scope).await
+}
+
+async fn resolve_user(client: &OdooClient, user: &str) -> OdooResult<i64> {
+    match resolve_by_name(client, "res.users", "login", user, vec![]).await {
+        Ok(id) => Ok(id),
+        Err(_) => resolve_by_name(client, "res.users", "name", user, vec![]).await,
+    }
+}
+
+pub async fn create_ticket(
+    client: &OdooClient,
+    options: CreateTicketOptions,
+) -> OdooResult<TicketAssignment> {
+    if options.name.trim().is_empty() {
+        return Err(OdooError::InvalidResponse(
+            "Ticket 'name' must not be empty".to_string(),
+        ));
+    }
+
+    let mut values = Map::new();
+    values.insert("name".into(), json!(options.name));
+    if let Some(description) = options.description {
+        values.insert("description".into(), json!(description));
+    }
+    if let Some(partner_id) = options.partner_id {
+        values.insert("partner_id".into(), json!(partner_id));
+    }
+    if let Some(partner_name) = options.partner_name {
+        values.insert("partner_name".into(), json!(partner_name));
+    }
+    if let Some(partner_email) = options.partner_email {
+        values.insert("partner_email".into(), json!(partner_email));
+    }
+    if let Some(priority) = options.priority {
+        values.insert("priority".into(), json!(priority));
+    }
+
+    let team_id = match options.team.as_deref() {
+        Some(team) => Some(resolve_team(client, team).await?),
+        None => None,
+    };
+    let stage_id = match options.stage.as_deref() {
+        Some(stage) => Some(resolve_stage(client, stage, team_id).await?),
+        None => None,
+    };
+    let user_id = match options.user.as_deref() {
+        Some(user) => Some(resolve_user(client, user).await?),
+        None => None,
+    };
+    if let Some(id) = team_id {
+        values.insert("team_id".into(), json!(id));
+    }
+    if let Some(id) = stage_id {
+        values.insert("stage_id".into(), json!(id));
+    }
+    if let Some(id) = user_id {
+        values.insert("user_id".into(), json!(id));
+    }
+
+    let ticket_id = client
+        .create(TICKET_MODEL, Value::Object(values), options.context)
+        .await?;
+    Ok(TicketAssignment {
+        ticket_id,
+        team_id,
+        stage_id,
+        user_id,
+    })
+}
+
+pub async fn assign_ticket(
+    client: &OdooClient,
+    options: AssignTicketOptions,
+) -> OdooResult<TicketAssignment> {
+    if options.team.is_none() && options.stage.is_none() && options.user.is_none() {
+        return Err(OdooError::InvalidResponse(
+            "Provide at least one of 'team', 'stage', or 'user'".to_string(),
+        ));
+    }
+
+    let team_id = match options.team.as_deref() {
+        Some(team) => Some(resolve_team(client, team).await?),
+        None => None,
+    };
+    let stage_id = match options.stage.as_deref() {
+        Some(stage) => {
+            // Stages are team-scoped; fall back to the ticket's current team.
+            let scope_team = match team_id {
+                Some(id) => Some(id),
+                None => {
+                    let rows = client
+                        .read(
+                            TICKET_MODEL,
+                            vec![options.ticket_id],
+                            Some(vec!["team_id".to_string()]),
+                            options.context.clone(),
+                        )
+                        .await?;
+                    rows.as_array()
+                        .and_then(|rows| rows.first())
+                        .and_then(|row| row.get("team_id"))
+                        .and_then(many2one_id)
+                }
+            };
+            Some(resolve_stage(client, stage, scope_team).await?)
+        }
+        None => None,
+    };
+    let user_id = match options.user.as_deref() {
+        Some(user) => Some(resolve_user(client, user).await?),
+        None => None,
+    };
+
+    let mut values = Map::new();
+    if let Some(id) = team_id {
+        values.insert("team_id".into(), json!(id));
+    }
+    if let Some(id) = stage_id {
+        values.insert("stage_id".into(), json!(id));
+    }
+    if let Some(id) = user_id {
+        values.insert("user_id".into(), json!(id));
+    }
+    client
+        .write(
+            TICKET_MODEL,
+            vec![options.ticket_id],
+            Value::Object(values),
+            options.context,
+        )
+        .await?;
+
+    Ok(TicketAssignment {
+        ticket_id: options.ticket_id,
+        team_id,
+        stage_id,
+        user_id,
+    })
+}
+
+pub async fn reply(client: &OdooClient, options: ReplyOptions) -> OdooResult<TicketReply> {
+    if options.body.trim().is_empty() {
+        return Err(OdooError::InvalidResponse(
+            "Reply 'body' must not be empty".to_string(),
+        ));
+    }
+    let internal = options.internal.unwrap_or(false);
+    let mut params = Map::new();
+    params.insert("body".into(), json!(options.body));
+    params.insert("message_type".into(), json!("comment"));
+    params.insert(
+        "subtype_xmlid".into(),
+        json!(if internal {
+            "mail.mt_note"
+        } else {
+            "mail.mt_comment"
+        }),
+    );
+    let result = client
+        .call_named(
+            TICKET_MODEL,
+            "message_post",
+            Some(vec![options.ticket_id]),
+            params,
+            options.context,
+        )
+        .await?;
+    Ok(TicketReply {
+        ticket_id: options.ticket_id,
+        message_id: many2one_id(&result),
+        internal,
+    })
+}
+
+pub async fn sla_status(
+    client: &OdooClient,
+    options: SlaStatusOptions,
+) -> OdooResult<SlaStatusReport> {
+    let mut domain = Vec::new();
+    if let Some(ids) = options.ticket_ids.filter(|ids| !ids.is_empty()) {
+        domain.push(json!(["ticket_id", "in", ids]));
+    }
+    if let Some(team) = options.team.as_deref() {
+        let team_id = resolve_team(client, team).await?;
+        domain.push(json!(["ticket_id.team_id", "=", team_id]));
+    }
+    if let Some(status) = options.status.as_deref() {
+        if !matches!(status, "ongoing" | "reached" | "failed") {
+            return Err(OdooError::InvalidResponse(format!(
+                "Unknown SLA status '{status}' (expected ongoing, reached, or failed)"
+            )));
+        }
+        domain.push(json!(["status", "=", status]));
+    }
+
+    let rows = client
+        .search_read(
+            SLA_STATUS_MODEL,
+            Some(Value::Array(domain)),
+            Some(vec![
+                "ticket_id".to_string(),
+                "sla_id".to_string(),
+                "status".to_string(),
+                "deadline".to_string(),
+                "reached_datetime".to_string(),
+            ]),
+            Some(options.limit.unwrap_or(200)),
+            None,
+            Some("deadline asc".to_string()),
+            options.context,
+        )
+        .await?;
+    Ok(summarize_sla(
+        rows.as_array().map(Vec::as_slice).unwrap_or(&[]),
+    ))
+}
+
+fn summarize_sla(rows: &[Value]) -> SlaStatusReport {
+    let text = |row: &Value, key: &str| row.get(key).and_then(|v| v.as_str()).map(String::from);
+    let lines: Vec<SlaLine> = rows
+        .iter()
+        .map(|row| SlaLine {
+            ticket_id: row.get("ticket_id").and_then(many2one_id),
+            ticket: row.get("ticket_id").and_then(many2one_name),
+            sla: row.get("sla_id").and_then(many2one_name),
+            status: text(row, "status").unwrap_or_else(|| "unknown".to_string()),
+            deadline: text(row, "deadline"),
+            reached_datetime: text(row, "reached_datetime"),
+        })
+        .collect();
+    let mut by_status = BTreeMap::new();
+    for line in &lines {
+        *by_status.entry(line.status.clone()).or_insert(0) += 1;
+    }
+    SlaStatusReport {
+        total: lines.len(),
+        by_status,
+        lines,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarize_sla_counts_by_status() {
+        let rows = vec![
+            json!({"ticket_id": [1, "Printer"], "sla_id": [3, "4h"], "status": "failed", "deadline": "2026-01-01 10:00:00", "reached_datetime": false}),
+            json!({"ticket_id": [2, "VPN"], "sla_id": [3, "4h"], "status": "ongoing", "deadline": "2026-01-02 10:00:00", "reached_datetime": false}),
+            json!({"ticket_id": [3, "Mail"], "sla_id": [3, "4h"], "status": "ongoing", "deadline": false, "reached_datetime": false}),
+        ];
+        let report = summarize_sla(&rows);
+        assert_eq!(report.total, 3);
+        assert_eq!(report.by_status.get("ongoing"), Some(&2));
+        assert_eq!(report.by_status.get("failed"), Some(&1));
+        assert_eq!(report.lines[0].ticket.as_deref(), Some("Printer"));
+        assert_eq!(report.lines[2].deadline, None);
+    }
+}
@@ -0,0 +1,328 @@
+use std::collections::BTreeMap;
+
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::odoo::types::{OdooError, OdooResult};
+use crate::odoo::unified_client::OdooClient;
+use crate::packs::{many2one_id, many2one_name};
+
+const LAYER_MODEL: &str = "stock.valuation.layer";
+
+const DEFAULT_AGING_BUCKETS: &[i64] = &[30, 60, 90, 180];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InventoryValuationOptions {
+    /// Value stock as of this date (YYYY-MM-DD); defaults to today.
+    pub as_of_date: Option<String>,
+    pub company_id: Option<i64>,
+    /// Only products in these categories (children included).
+    pub category_ids: Option<Vec<i64>>,
+    /// Upper bounds in days of the aging buckets (default 30, 60, 90, 180).
+    pub aging_buckets: Option<Vec<i64>>,
+    /// Maximum open valuation layers read for aging (default 5000).
+    pub aging_limit: Option<i64>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryValuation {
+    pub category_id: Option<i64>,
+    pub category: Option<String>,
+    pub quantity: f64,
+    pub value: f64,
+    pub product_count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgingBucket {
+    pub label: String,
+    pub min_days: i64,
+    pub max_days: Option<i64>,
+    pub quantity: f64,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StockAging {
+    /// Aging uses the remaining quantity of incoming layers, i.e. today's stock.
+    pub as_of: String,
+    pub layer_count: usize,
+    /// True when more open layers exist than `agingLimit`.
+    pub truncated: bool,
+    pub buckets: Vec<AgingBucket>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InventoryValuation {
+    pub as_of_date: String,
+    pub total_quantity: f64,
+    pub total_value: f64,
+    pub categories: Vec<CategoryValuation>,
+    pub aging: StockAging,
+}
+
+/// Empty buckets for the given upper bounds: `0-30`, `31-60`, ..., `181+`.
+fn make_buckets(bounds: &[i64]) -> Vec<AgingBucket> {
+    let mut bounds: Vec<i64> = bounds.iter().copied().filter(|b| *b > 0).collect();
+    bounds.sort_unstable();
+    bounds.dedup();
+    let mut buckets = Vec::new();
+    let mut min_days = 0;
+    for max_days in bounds {
+        buckets.push(AgingBucket {
+            label: format!("{min_days}-{max_days}"),
+            min_days,
+            max_days: Some(max_days),
+            quantity: 0.0,
+            value: 0.0,
+        });
+        min_days = max_days + 1;
+    }
+    buckets.push(AgingBucket {
+        label: format!("{min_days}+"),
+        min_days,
+        max_days: None,
+        quantity: 0.0,
+        value: 0.0,
+    });
+    buckets
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .map(|dt| dt.date())
+        .or_else(|_| NaiveDate::parse_from_str(value, "%Y-%m-%d"))
+        .ok()
+}
+
+/// Spread open layers (`remaining_qty`/`remaining_value`) into buckets by receipt age.
+fn fold_aging(layers: &[Value], today: NaiveDate, buckets: &mut [AgingBucket]) {
+    for layer in layers {
+        let Some(received) = layer
+            .get("create_date")
+            .and_then(Value::as_str)
+            .and_then(parse_date)
+        else {
+            continue;
+        };
+        let age = (today - received).num_days().max(0);
+        let Some(bucket) = buckets
+            .iter_mut()
+            .find(|b| age >= b.min_days && b.max_days.is_none_or(|max| age <= max))
+        else {
+            continue;
+        };
+        bucket.quantity += layer
+            .get("remaining_qty")
+            .and_then(Value::as_f64)
+            .unwrap_or(0.0);
+        bucket.value += layer
+            .get("remaining_value")
+            .and_then(Value::as_f64)
+            .unwrap_or(0.0);
+    }
+}
+
+/// Fold per-product `read_group` rows into categories using `product -> category`.
+fn fold_categories(
+    rows: &Value,
+    categories: &BTreeMap<i64, (Option<i64>, Option<String>)>,
+) -> Vec<CategoryValuation> {
+    let mut out: Vec<CategoryValuation> = Vec::new();
+    for row in rows.as_array().map(Vec::as_slice).unwrap_or(&[]) {
+        let Some(product_id) = row.get("product_id").and_then(many2one_id) else {
+            continue;
+        };
+        let (category_id, category) = categories.get(&product_id).cloned().unwrap_or_default();
+        let quantity = row.get("quantity").and_then(Value::as_f64).unwrap_or(0.0);
+        let value = row.get("value").and_then(Value::as_f64).unwrap_or(0.0);
+        match out.iter_mut().find(|c| c.category_id == category_id) {
+            Some(entry) => {
+                entry.quantity += quantity;
+                entry.value += value;
+                entry.product_count += 1;
+            }
+            None => out.push(CategoryValuation {
+                category_id,
+                category,
+                quantity,
+                value,
+                product_count: 1,
+            }),
+        }
+    }
+    out.sort_by(|a, b| b.value.total_cmp(&a.value));
+    out
+}
+
+pub async fn inventory_valuation(
+    client: &OdooClient,
+    options: InventoryValuationOptions,
+) -> OdooResult<InventoryValuation> {
+    let today = Utc::now().date_naive();
+    let as_of = match options.as_of_date.as_deref() {
+        Some(date) => NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| {
+            OdooError::InvalidResponse(format!("asOfDate must be YYYY-MM-DD, got '{date}'"))
+        })?,
+        None => today,
+    };
+
+    let mut domain = Vec::new();
+    if let Some(company_id) = options.company_id {
+        domain.push(json!(["company_id", "=", company_id]));
+    }
+    if let Some(ids) = options.category_ids.as_ref() {
+        domain.push(json!(["product_id.categ_id", "child_of", ids]));
+    }
+
+    let mut valuation_domain = domain.clone();
+    valuation_domain.push(json!(["create_date", "<=", format!("{as_of} 23:59:59")]));
+    let rows = client
+        .read_group(
+            LAYER_MODEL,
+            Some(Value::Array(valuation_domain)),
+            vec!["quantity:sum".to_string(), "value:sum".to_string()],
+            vec!["product_id".to_string()],
+            None,
+            None,
+            None,
+            Some(false),
+            options.context.clone(),
+        )
+        .await?;
+
+    let product_ids: Vec<i64> = rows
+        .as_array()
+        .map(|rows| {
+            rows.iter()
+                .filter_map(|row| row.get("product_id").and_then(many2one_id))
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut categories = BTreeMap::new();
+    if !product_ids.is_empty() {
+        let products = client
+            .read(
+                "product.product",
+                product_ids,
+                Some(vec!["categ_id".to_string()]),
+                options.context.clone(),
+            )
+            .await?;
+        for product in products.as_array().map(Vec::as_slice).unwrap_or(&[]) {
+            if let Some(id) = product.get("id").and_then(Value::as_i64) {
+                let categ = product.get("categ_id");
+                categories.insert(
+                    id,
+                    (categ.and_then(many2one_id), categ.and_then(many2one_name)),
+                );
+            }
+        }
+    }
+    let categories = fold_categories(&rows, &categories);
+
+    let aging_limit = options.aging_limit.unwrap_or(5000);
+    let mut aging_domain = domain;
+    aging_domain.push(json!(["remaining_qty", ">", 0]));
+    let layers = client
+        .search_read(
+            LAYER_MODEL,
+            Some(Value::Array(aging_domain)),
+            Some(vec![
+                "create_date".to_string(),
+                "remaining_qty".to_string(),
+                "remaining_value".to_string(),
+            ]),
+            Some(aging_limit + 1),
+            None,
+            Some("create_date asc, id asc".to_string()),
+            options.context,
+        )
+        .await?;
+    let mut layers = layers.as_array().cloned().unwrap_or_default();
+    let truncated = layers.len() as i64 > aging_limit;
+    layers.truncate(aging_limit.max(0) as usize);
+    let mut buckets = make_buckets(
+        options
+            .aging_buckets
+            .as_deref()
+            .unwrap_or(DEFAULT_AGING_BUCKETS),
+    );
+    fold_aging(&layers, today, &mut buckets);
+
+    Ok(InventoryValuation {
+        as_of_date: as_of.to_string(),
+        total_quantity: categories.iter().map(|c| c.quantity).sum(),
+        total_value: categories.iter().map(|c| c.value).sum(),
+        categories,
+        aging: StockAging {
+            as_of: today.to_string(),
+            layer_count: layers.len(),
+            truncated,
+            buckets,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn make_buckets_sorts_bounds_and_adds_open_bucket() {
+        let labels: Vec<String> = make_buckets(&[60, 30, 30, 0])
+            .into_iter()
+            .map(|b| b.label)
+            .collect();
+        assert_eq!(labels, vec!["0-30", "31-60", "61+"]);
+    }
+
+    #[test]
+    fn fold_aging_uses_receipt_age() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 31).unwrap();
+        let mut buckets = make_buckets(&[30, 90]);
+        fold_aging(
+            &[
+                json!({"create_date": "2026-03-30 08:00:00", "remaining_qty": 5.0, "remaining_value": 50.0}),
+                json!({"create_date": "2026-01-15 08:00:00", "remaining_qty": 2.0, "remaining_value": 30.0}),
+                json!({"create_date": "2025-06-01 08:00:00", "remaining_qty": 1.0, "remaining_value": 7.5}),
+                json!({"create_date": false, "remaining_qty": 9.0}),
+            ],
+            today,
+            &mut buckets,
+        );
+        assert_eq!(
+            buckets.iter().map(|b| b.value).collect::<Vec<_>>(),
+            vec![50.0, 30.0, 7.5]
+        );
+        assert_eq!(buckets[0].quantity, 5.0);
+    }
+
+    #[test]
+    fn fold_categories_merges_products() {
+        let categories = BTreeMap::from([
+            (1, (Some(10), Some("All / Saleable".to_string()))),
+            (2, (Some(10), Some("All / Saleable".to_string()))),
+            (3, (Some(11), Some("All / Raw".to_string()))),
+        ]);
+        let folded = fold_categories(
+            &json!([
+                {"product_id": [1, "Desk"], "quantity": 4.0, "value": 400.0},
+                {"product_id": [2, "Chair"], "quantity": 10.0, "value": 250.0},
+                {"product_id": [3, "Screw"], "quantity": 1000.0, "value": 20.0}
+            ]),
+            &categories,
+        );
+        assert_eq!(folded.len(), 2);
+        assert_eq!(folded[0].category.as_deref(), Some("All / Saleable"));
+        assert_eq!(folded[0].value, 650.0);
+        assert_eq!(folded[0].product_count, 2);
+    }
+}
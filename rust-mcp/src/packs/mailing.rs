@@ -0,0 +1,328 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::odoo::types::OdooResult;
+use crate::odoo::unified_client::OdooClient;
+use crate::packs::existing_fields;
+
+const MAILING_MODEL: &str = "mailing.mailing";
+const LIST_MODEL: &str = "mailing.list";
+
+/// Per-mailing counters; names are stable from Odoo 13 on, but some only exist in later versions.
+const STAT_FIELDS: &[&str] = &[
+    "expected",
+    "sent",
+    "delivered",
+    "opened",
+    "clicked",
+    "replied",
+    "bounced",
+    "failed",
+];
+
+/// Contact counters on `mailing.list`; the email/opt-out splits arrived in Odoo 15.
+const LIST_COUNT_FIELDS: &[&str] = &[
+    "contact_count",
+    "contact_count_email",
+    "contact_count_opt_out",
+    "contact_count_blacklisted",
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MailingStatsOptions {
+    /// Restrict to these `mailing.mailing` ids.
+    pub mailing_ids: Option<Vec<i64>>,
+    /// Mailing state (default "done"; pass "all" for every state).
+    pub state: Option<String>,
+    /// Only mailings sent on or after this date (YYYY-MM-DD).
+    pub date_from: Option<String>,
+    pub date_to: Option<String>,
+    /// "mail" (default) or "sms".
+    pub mailing_type: Option<String>,
+    pub limit: Option<i64>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MailingListSizesOptions {
+    /// Only lists whose name contains this text.
+    pub name: Option<String>,
+    pub limit: Option<i64>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MailingCounters {
+    pub expected: i64,
+    pub sent: i64,
+    pub delivered: i64,
+    pub opened: i64,
+    pub clicked: i64,
+    pub replied: i64,
+    pub bounced: i64,
+    pub failed: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MailingRates {
+    pub open_rate: f64,
+    pub click_rate: f64,
+    pub bounce_rate: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MailingStats {
+    pub id: i64,
+    pub subject: String,
+    pub state: Option<String>,
+    pub sent_date: Option<String>,
+    pub counters: MailingCounters,
+    pub rates: MailingRates,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MailingStatsReport {
+    pub count: usize,
+    pub totals: MailingCounters,
+    pub rates: MailingRates,
+    pub mailings: Vec<MailingStats>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MailingListSize {
+    pub id: i64,
+    pub name: String,
+    pub contact_count: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email_count: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opt_out_count: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blacklisted_count: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MailingListSizes {
+    pub count: usize,
+    pub total_contacts: i64,
+    pub lists: Vec<MailingListSize>,
+}
+
+fn parse_counters(row: &Value) -> MailingCounters {
+    let n = |key: &str| row.get(key).and_then(|v| v.as_i64()).unwrap_or(0);
+    MailingCounters {
+        expected: n("expected"),
+        sent: n("sent"),
+        delivered: n("delivered"),
+        opened: n("opened"),
+        clicked: n("clicked"),
+        replied: n("replied"),
+        bounced: n("bounced"),
+        failed: n("failed"),
+    }
+}
+
+fn add_counters(total: &mut MailingCounters, c: &MailingCounters) {
+    total.expected += c.expected;
+    total.sent += c.sent;
+    total.delivered += c.delivered;
+    total.opened += c.opened;
+    total.clicked += c.clicked;
+    total.replied += c.replied;
+    total.bounced += c.bounced;
+    total.failed += c.failed;
+}
+
+/// Percentages as Odoo shows them: opens/clicks over delivered, bounces over sent.
+fn rates(c: &MailingCounters) -> MailingRates {
+    let pct = |part: i64, whole: i64| {
+        if whole > 0 {
+            (part as f64 * 10000.0 / whole as f64).round() / 100.0
+        } else {
+            0.0
+        }
+    };
+    MailingRates {
+        open_rate: pct(c.opened, c.delivered),
+        click_rate: pct(c.clicked, c.delivered),
+        bounce_rate: pct(c.bounced, c.sent),
+    }
+}
+
+pub async fn mailing_stats(
+    client: &OdooClient,
+    options: MailingStatsOptions,
+) -> OdooResult<MailingStatsReport> {
+    let mut domain = vec![json!([
+        "mailing_type",
+        "=",
+        options.mailing_type.as_deref().unwrap_or("mail")
+    ])];
+    match options.state.as_deref() {
+        Some("all") => {}
+        state => domain.push(json!(["state", "=", state.unwrap_or("done")])),
+    }
+    if let Some(ids) = options.mailing_ids.as_ref() {
+        domain.push(json!(["id", "in", ids]));
+    }
+    if let Some(date) = options.date_from.as_deref() {
+        domain.push(json!(["sent_date", ">=", date]));
+    }
+    if let Some(date) = options.date_to.as_deref() {
+        domain.push(json!(["sent_date", "<=", format!("{date} 23:59:59")]));
+    }
+
+    let available = client
+        .fields_get(MAILING_MODEL, options.context.clone())
+        .await?;
+    let mut fields = vec![
+        "subject".to_string(),
+        "state".to_string(),
+        "sent_date".to_string(),
+    ];
+    fields.extend(existing_fields(&available, STAT_FIELDS));
+
+    let rows = client
+        .search_read(
+            MAILING_MODEL,
+            Some(Value::Array(domain)),
+            Some(fields),
+            Some(options.limit.unwrap_or(50)),
+            None,
+            Some("sent_date desc, id desc".to_string()),
+            options.context,
+        )
+        .await?;
+
+    let mut totals = MailingCounters::default();
+    let mut mailings = Vec::new();
+    for row in rows.as_array().map(Vec::as_slice).unwrap_or(&[]) {
+        let Some(id) = row.get("id").and_then(|v| v.as_i64()) else {
+            continue;
+        };
+        let text = |key: &str| row.get(key).and_then(|v| v.as_str()).map(String::from);
+        let counters = parse_counters(row);
+        add_counters(&mut totals, &counters);
+        mailings.push(MailingStats {
+            id,
+            subject: text("subject").unwrap_or_default(),
+            state: text("state"),
+            sent_date: text("sent_date"),
+            rates: rates(&counters),
+            counters,
+        });
+    }
+
+    Ok(MailingStatsReport {
+        count: mailings.len(),
+        rates: rates(&totals),
+        totals,
+        mailings,
+    })
+}
+
+pub async fn mailing_list_sizes(
+    client: &OdooClient,
+    options: MailingListSizesOptions,
+) -> OdooResult<MailingListSizes> {
+    let mut domain = Vec::new();
+    if let Some(name) = options.name.as_deref() {
+        domain.push(json!(["name", "ilike", name]));
+    }
+    let available = client
+        .fields_get(LIST_MODEL, options.context.clone())
+        .await?;
+    let mut fields = vec!["name".to_string()];
+    fields.extend(existing_fields(&available, LIST_COUNT_FIELDS));
+
+    let rows = client
+        .search_read(
+            LIST_MODEL,
+            Some(Value::Array(domain)),
+            Some(fields),
+            Some(options.limit.unwrap_or(100)),
+            None,
+            Some("name asc".to_string()),
+            options.context,
+        )
+        .await?;
+
+    let lists: Vec<MailingListSize> = rows
+        .as_array()
+        .map(|rows| {
+            rows.iter()
+                .filter_map(|row| {
+                    let count = |key: &str| row.get(key).and_then(|v| v.as_i64());
+                    Some(MailingListSize {
+                        id: row.get("id")?.as_i64()?,
+                        name: row
+                            .get("name")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or_default()
+                            .to_string(),
+                        contact_count: count("contact_count").unwrap_or(0),
+                        email_count: count("contact_count_email"),
+                        opt_out_count: count("contact_count_opt_out"),
+                        blacklisted_count: count("contact_count_blacklisted"),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(MailingListSizes {
+        count: lists.len(),
+        total_contacts: lists.iter().map(|l| l.contact_count).sum(),
+        lists,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn existing_fields_drops_unknown_counters() {
+        let fields = json!({"sent": {}, "opened": {}, "subject": {}});
+        assert_eq!(
+            existing_fields(&fields, STAT_FIELDS),
+            vec!["sent".to_string(), "opened".to_string()]
+        );
+    }
+
+    #[test]
+    fn rates_use_delivered_and_sent_denominators() {
+        let counters = parse_counters(&json!({
+            "sent": 200, "delivered": 190, "opened": 57, "clicked": 19, "bounced": 10
+        }));
+        let rates = rates(&counters);
+        assert_eq!(rates.open_rate, 30.0);
+        assert_eq!(rates.click_rate, 10.0);
+        assert_eq!(rates.bounce_rate, 5.0);
+        assert_eq!(super::rates(&MailingCounters::default()).open_rate, 0.0);
+    }
+
+    #[test]
+    fn add_counters_sums_every_field() {
+        let mut total = MailingCounters::default();
+        add_counters(
+            &mut total,
+            &parse_counters(&json!({"sent": 3, "failed": 1})),
+        );
+        add_counters(
+            &mut total,
+            &parse_counters(&json!({"sent": 2, "replied": 4})),
+        );
+        assert_eq!(total.sent, 5);
+        assert_eq!(total.failed, 1);
+        assert_eq!(total.replied, 4);
+    }
+}
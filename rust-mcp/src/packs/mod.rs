@@ -0,0 +1,109 @@
+//! Domain tool packs.
+//!
+//! Each submodule backs the tools of one `pack` in tools.json. Pack functions take a
+//! resolved [`OdooClient`] plus a typed options struct and return a serializable report,
+//! mirroring the layout of [`crate::cleanup`]. The tool dispatch calls them through one
+//! generic wrapper, so adding a pack tool only takes a dispatch arm naming the function.
+
+pub mod analytic;
+pub mod automation;
+pub mod credit;
+pub mod delivery;
+pub mod documents;
+pub mod event;
+pub mod fleet;
+pub mod helpdesk;
+pub mod inventory;
+pub mod mailing;
+pub mod order;
+pub mod partner;
+pub mod payroll;
+pub mod pricing;
+pub mod quality;
+pub mod reconciliation;
+pub mod subscription;
+pub mod survey;
+pub mod timesheet;
+pub mod users;
+
+use serde_json::{Value, json};
+
+use crate::odoo::types::{OdooError, OdooResult};
+use crate::odoo::unified_client::OdooClient;
+
+/// Resolve a record id from a human-readable name.
+///
+/// Matches `field =ilike name` (case-insensitive exact match) within `extra_domain`.
+/// Fails when nothing matches or when the name is ambiguous.
+pub async fn resolve_by_name(
+    client: &OdooClient,
+    model: &str,
+    field: &str,
+    name: &str,
+    extra_domain: Vec<Value>,
+) -> OdooResult<i64> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(OdooError::InvalidResponse(format!(
+            "Empty name given for {model}"
+        )));
+    }
+    let mut domain = vec![json!([field, "=ilike", name])];
+    domain.extend(extra_domain);
+    let ids = client
+        .search(model, Some(Value::Array(domain)), Some(2), None, None, None)
+        .await?;
+    match ids.as_slice() {
+        [id] => Ok(*id),
+        [] => Err(OdooError::InvalidResponse(format!(
+            "No {model} record found with {field} '{name}'"
+        ))),
+        _ => Err(OdooError::InvalidResponse(format!(
+            "Name '{name}' matches several {model} records; use a more specific name"
+        ))),
+    }
+}
+
+/// Keep the candidate fields that exist in a `fields_get` result.
+pub fn existing_fields(fields: &Value, candidates: &[&str]) -> Vec<String> {
+    candidates
+        .iter()
+        .filter(|name| fields.get(**name).is_some())
+        .map(|name| name.to_string())
+        .collect()
+}
+
+/// Extract the id from a many2one value (`[id, "display name"]`, `id`, or `false`).
+pub fn many2one_id(value: &Value) -> Option<i64> {
+    match value {
+        Value::Array(items) => items.first().and_then(|v| v.as_i64()),
+        Value::Number(n) => n.as_i64(),
+        _ => None,
+    }
+}
+
+/// Extract the display name from a many2one value (`[id, "display name"]`).
+pub fn many2one_name(value: &Value) -> Option<String> {
+    value
+        .as_array()
+        .and_then(|items| items.get(1))
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn many2one_helpers_handle_odoo_shapes() {
+        assert_eq!(many2one_id(&json!([7, "Support"])), Some(7));
+        assert_eq!(many2one_id(&json!(7)), Some(7));
+        assert_eq!(many2one_id(&json!(false)), None);
+        assert_eq!(
+            many2one_name(&json!([7, "Support"])),
+            Some("Support".to_string())
+        );
+        assert_eq!(many2one_name(&json!(false)), None);
+    }
+}
@@ -0,0 +1,303 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::odoo::types::{OdooError, OdooResult};
+use crate::odoo::unified_client::OdooClient;
+use crate::packs::partner::Section;
+use crate::packs::resolve_by_name;
+
+const ORDER_MODEL: &str = "sale.order";
+
+const ORDER_FIELDS: &[&str] = &[
+    "name",
+    "state",
+    "partner_id",
+    "user_id",
+    "date_order",
+    "currency_id",
+    "amount_untaxed",
+    "amount_tax",
+    "amount_total",
+    "invoice_status",
+    // Only with stock / account installed; filtered through fields_get.
+    "picking_ids",
+    "invoice_ids",
+];
+
+/// Characters of each chatter message body kept in the summary.
+const MESSAGE_PREVIEW_CHARS: usize = 200;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Order360Options {
+    /// Sale order by id or by reference (e.g. "S00042").
+    pub order_id: Option<i64>,
+    pub order: Option<String>,
+    /// Chatter messages to include (default 10).
+    pub message_limit: Option<i64>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderPayment {
+    pub invoice: Option<String>,
+    pub name: Option<String>,
+    pub journal: Option<String>,
+    pub date: Option<String>,
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Order360 {
+    pub order: Value,
+    pub lines: Section,
+    pub deliveries: Section,
+    pub invoices: Section,
+    pub payments: Vec<OrderPayment>,
+    pub messages: Section,
+}
+
+fn fields(names: &[&str]) -> Option<Vec<String>> {
+    Some(names.iter().map(|f| f.to_string()).collect())
+}
+
+fn ids(order: &Value, key: &str) -> Vec<i64> {
+    order
+        .get(key)
+        .and_then(Value::as_array)
+        .map(|ids| ids.iter().filter_map(Value::as_i64).collect())
+        .unwrap_or_default()
+}
+
+/// Payments listed in `invoice_payments_widget`: a JSON string up to 16, an object since 17.
+fn parse_payments(invoice: &Value) -> Vec<OrderPayment> {
+    let widget = match invoice.get("invoice_payments_widget") {
+        Some(Value::String(raw)) => serde_json::from_str(raw).unwrap_or(Value::Null),
+        Some(value) => value.clone(),
+        None => Value::Null,
+    };
+    let invoice_name = invoice
+        .get("name")
+        .and_then(Value::as_str)
+        .map(String::from);
+    widget
+        .get("content")
+        .and_then(Value::as_array)
+        .map(|content| {
+            content
+                .iter()
+                .map(|p| OrderPayment {
+                    invoice: invoice_name.clone(),
+                    name: p
+                        .get("ref")
+                        .or_else(|| p.get("name"))
+                        .and_then(Value::as_str)
+                        .map(String::from),
+                    journal: p
+                        .get("journal_name")
+                        .and_then(Value::as_str)
+                        .map(String::from),
+                    date: p.get("date").and_then(Value::as_str).map(String::from),
+                    amount: p.get("amount").and_then(Value::as_f64).unwrap_or(0.0),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Plain-text preview of an HTML message body.
+fn preview(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => {
+                in_tag = false;
+                text.push(' ');
+            }
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match text.char_indices().nth(MESSAGE_PREVIEW_CHARS) {
+        Some((cut, _)) => format!("{}…", &text[..cut]),
+        None => text,
+    }
+}
+
+async fn read_section(
+    client: &OdooClient,
+    model: &str,
+    ids: Vec<i64>,
+    wanted: &[&str],
+    context: Option<Value>,
+) -> OdooResult<Value> {
+    if ids.is_empty() {
+        return Ok(json!([]));
+    }
+    client.read(model, ids, fields(wanted), context).await
+}
+
+pub async fn order_360(client: &OdooClient, options: Order360Options) -> OdooResult<Order360> {
+    let ctx = options.context.clone();
+    let order_id = match (options.order_id, options.order.as_deref()) {
+        (Some(id), _) => id,
+        (None, Some(name)) => resolve_by_name(client, ORDER_MODEL, "name", name, vec![]).await?,
+        (None, None) => {
+            return Err(OdooError::InvalidResponse(
+                "Pass 'orderId' or 'order'".to_string(),
+            ));
+        }
+    };
+
+    let available = client.fields_get(ORDER_MODEL, ctx.clone()).await?;
+    let wanted: Vec<String> = ORDER_FIELDS
+        .iter()
+        .filter(|f| available.get(**f).is_some())
+        .map(|f| f.to_string())
+        .collect();
+    let rows = client
+        .read(ORDER_MODEL, vec![order_id], Some(wanted), ctx.clone())
+        .await?;
+    let mut order = rows
+        .get(0)
+        .cloned()
+        .ok_or_else(|| OdooError::InvalidResponse(format!("Sale order {order_id} not found")))?;
+    let picking_ids = ids(&order, "picking_ids");
+    let invoice_ids = ids(&order, "invoice_ids");
+    // The sections below expand these; keep the order header small.
+    if let Some(order) = order.as_object_mut() {
+        order.remove("picking_ids");
+        order.remove("invoice_ids");
+    }
+
+    let (lines, deliveries, invoices, messages) = futures::join!(
+        client.search_read(
+            "sale.order.line",
+            Some(json!([["order_id", "=", order_id]])),
+            fields(&[
+                "product_id",
+                "name",
+                "product_uom_qty",
+                "qty_delivered",
+                "qty_invoiced",
+                "price_unit",
+                "discount",
+                "price_subtotal",
+            ]),
+            None,
+            None,
+            Some("sequence, id".to_string()),
+            ctx.clone(),
+        ),
+        read_section(
+            client,
+            "stock.picking",
+            picking_ids,
+            &[
+                "name",
+                "state",
+                "picking_type_id",
+                "scheduled_date",
+                "date_done"
+            ],
+            ctx.clone(),
+        ),
+        read_section(
+            client,
+            "account.move",
+            invoice_ids,
+            &[
+                "name",
+                "move_type",
+                "state",
+                "invoice_date",
+                "invoice_date_due",
+                "amount_total",
+                "amount_residual",
+                "payment_state",
+                "invoice_payments_widget",
+            ],
+            ctx.clone(),
+        ),
+        client.search_read(
+            "mail.message",
+            Some(json!([
+                ["model", "=", ORDER_MODEL],
+                ["res_id", "=", order_id],
+                ["message_type", "!=", "notification"]
+            ])),
+            fields(&["date", "author_id", "subject", "body", "message_type"]),
+            Some(options.message_limit.unwrap_or(10)),
+            None,
+            Some("date desc".to_string()),
+            ctx,
+        ),
+    );
+
+    let mut invoices: Section = invoices.into();
+    let payments = invoices.records.iter().flat_map(parse_payments).collect();
+    for invoice in &mut invoices.records {
+        if let Some(invoice) = invoice.as_object_mut() {
+            invoice.remove("invoice_payments_widget");
+        }
+    }
+    let mut messages: Section = messages.into();
+    for message in &mut messages.records {
+        if let Some(message) = message.as_object_mut()
+            && let Some(body) = message.remove("body")
+        {
+            message.insert(
+                "preview".to_string(),
+                json!(preview(body.as_str().unwrap_or_default())),
+            );
+        }
+    }
+
+    Ok(Order360 {
+        order,
+        lines: lines.into(),
+        deliveries: deliveries.into(),
+        invoices,
+        payments,
+        messages,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_payments_handles_string_and_object_widgets() {
+        let payment = json!({"content": [
+            {"name": "Customer Payment", "ref": "PBNK1/2026/0004", "journal_name": "Bank",
+             "date": "2026-03-02", "amount": 120.0}
+        ]});
+        let legacy =
+            json!({"name": "INV/2026/0001", "invoice_payments_widget": payment.to_string()});
+        let modern = json!({"name": "INV/2026/0001", "invoice_payments_widget": payment});
+        assert_eq!(parse_payments(&legacy), parse_payments(&modern));
+        assert_eq!(
+            parse_payments(&modern)[0].name.as_deref(),
+            Some("PBNK1/2026/0004")
+        );
+        // Unpaid invoices carry `false`.
+        assert!(parse_payments(&json!({"invoice_payments_widget": false})).is_empty());
+    }
+
+    #[test]
+    fn preview_strips_html_and_truncates() {
+        assert_eq!(
+            preview("<p>Customer asked for <b>faster</b> delivery.</p>"),
+            "Customer asked for faster delivery."
+        );
+        let long = preview(&"word ".repeat(100));
+        assert!(long.ends_with('…'));
+        assert_eq!(long.chars().count(), MESSAGE_PREVIEW_CHARS + 1);
+    }
+}
@@ -0,0 +1,246 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::odoo::types::{OdooError, OdooResult};
+use crate::odoo::unified_client::OdooClient;
+use crate::packs::{many2one_id, resolve_by_name};
+
+const PARTNER_FIELDS: &[&str] = &[
+    "display_name",
+    "is_company",
+    "commercial_partner_id",
+    "email",
+    "phone",
+    "city",
+    "country_id",
+    "user_id",
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Partner360Options {
+    /// Partner by id or by name.
+    pub partner_id: Option<i64>,
+    pub partner: Option<String>,
+    /// Records per section (default 10).
+    pub limit: Option<i64>,
+    pub context: Option<Value>,
+}
+
+/// One part of the 360 view. A section whose app is not installed (or not readable) carries
+/// the error instead of failing the whole call.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Section {
+    pub count: usize,
+    pub records: Vec<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Partner360 {
+    pub partner: Value,
+    pub open_orders: Section,
+    pub open_invoices: Section,
+    pub tickets: Section,
+    pub activities: Section,
+    pub messages: Section,
+}
+
+impl From<OdooResult<Value>> for Section {
+    fn from(result: OdooResult<Value>) -> Self {
+        match result {
+            Ok(rows) => {
+                let records = rows.as_array().cloned().unwrap_or_default();
+                Section {
+                    count: records.len(),
+                    records,
+                    error: None,
+                }
+            }
+            Err(e) => Section {
+                error: Some(e.to_string()),
+                ..Section::default()
+            },
+        }
+    }
+}
+
+fn fields(names: &[&str]) -> Option<Vec<String>> {
+    Some(names.iter().map(|f| f.to_string()).collect())
+}
+
+/// Domains per section; documents use the whole company, chatter only the partner records.
+fn section_domains(partner_ids: &[i64], commercial_id: i64) -> [Value; 5] {
+    [
+        json!([
+            ["partner_id", "child_of", commercial_id],
+            ["state", "in", ["draft", "sent", "sale"]],
+            ["invoice_status", "!=", "invoiced"]
+        ]),
+        json!([
+            ["partner_id", "child_of", commercial_id],
+            ["move_type", "in", ["out_invoice", "out_refund"]],
+            ["state", "=", "posted"],
+            ["payment_state", "in", ["not_paid", "partial"]]
+        ]),
+        json!([
+            ["partner_id", "child_of", commercial_id],
+            ["stage_id.fold", "=", false]
+        ]),
+        json!([
+            ["res_model", "=", "res.partner"],
+            ["res_id", "in", partner_ids]
+        ]),
+        json!([
+            ["model", "=", "res.partner"],
+            ["res_id", "in", partner_ids],
+            ["message_type", "!=", "notification"]
+        ]),
+    ]
+}
+
+pub async fn partner_360(
+    client: &OdooClient,
+    options: Partner360Options,
+) -> OdooResult<Partner360> {
+    let ctx = options.context.clone();
+    let partner_id = match (options.partner_id, options.partner.as_deref()) {
+        (Some(id), _) => id,
+        (None, Some(name)) => resolve_by_name(client, "res.partner", "name", name, vec![]).await?,
+        (None, None) => {
+            return Err(OdooError::InvalidResponse(
+                "Pass 'partnerId' or 'partner'".to_string(),
+            ));
+        }
+    };
+    let rows = client
+        .read(
+            "res.partner",
+            vec![partner_id],
+            fields(PARTNER_FIELDS),
+            ctx.clone(),
+        )
+        .await?;
+    let partner = rows
+        .get(0)
+        .cloned()
+        .ok_or_else(|| OdooError::InvalidResponse(format!("Partner {partner_id} not found")))?;
+    let commercial_id = partner
+        .get("commercial_partner_id")
+        .and_then(many2one_id)
+        .unwrap_or(partner_id);
+    let mut partner_ids = vec![partner_id];
+    if commercial_id != partner_id {
+        partner_ids.push(commercial_id);
+    }
+
+    let limit = Some(options.limit.unwrap_or(10));
+    let [orders, invoices, tickets, activities, messages] =
+        section_domains(&partner_ids, commercial_id);
+    let (orders, invoices, tickets, activities, messages) = futures::join!(
+        client.search_read(
+            "sale.order",
+            Some(orders),
+            fields(&[
+                "name",
+                "state",
+                "date_order",
+                "amount_total",
+                "invoice_status",
+                "user_id",
+            ]),
+            limit,
+            None,
+            Some("date_order desc".to_string()),
+            ctx.clone(),
+        ),
+        client.search_read(
+            "account.move",
+            Some(invoices),
+            fields(&[
+                "name",
+                "move_type",
+                "invoice_date",
+                "invoice_date_due",
+                "amount_total",
+                "amount_residual",
+                "payment_state",
+            ]),
+            limit,
+            None,
+            Some("invoice_date_due asc".to_string()),
+            ctx.clone(),
+        ),
+        client.search_read(
+            "helpdesk.ticket",
+            Some(tickets),
+            fields(&["name", "stage_id", "priority", "user_id", "create_date"]),
+            limit,
+            None,
+            Some("priority desc, create_date desc".to_string()),
+            ctx.clone(),
+        ),
+        client.search_read(
+            "mail.activity",
+            Some(activities),
+            fields(&[
+                "summary",
+                "activity_type_id",
+                "date_deadline",
+                "user_id",
+                "res_name",
+            ]),
+            limit,
+            None,
+            Some("date_deadline asc".to_string()),
+            ctx.clone(),
+        ),
+        client.search_read(
+            "mail.message",
+            Some(messages),
+            fields(&["date", "author_id", "subject", "body", "message_type"]),
+            limit,
+            None,
+            Some("date desc".to_string()),
+            ctx,
+        ),
+    );
+
+    Ok(Partner360 {
+        partner,
+        open_orders: orders.into(),
+        open_invoices: invoices.into(),
+        tickets: tickets.into(),
+        activities: activities.into(),
+        messages: messages.into(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn section_keeps_errors_local() {
+        let ok: Section = Ok(json!([{"id": 1}, {"id": 2}])).into();
+        assert_eq!(ok.count, 2);
+        assert!(ok.error.is_none());
+
+        let missing: Section = Err(OdooError::InvalidResponse(
+            "Object helpdesk.ticket doesn't exist".into(),
+        ))
+        .into();
+        assert_eq!(missing.count, 0);
+        assert!(missing.error.unwrap().contains("helpdesk.ticket"));
+    }
+
+    #[test]
+    fn section_domains_scope_documents_to_company() {
+        let [orders, _, _, activities, _] = section_domains(&[7, 3], 3);
+        assert_eq!(orders[0], json!(["partner_id", "child_of", 3]));
+        assert_eq!(activities[1], json!(["res_id", "in", [7, 3]]));
+    }
+}
@@ -0,0 +1,261 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::mcp::registry::env_truthy;
+use crate::odoo::types::{OdooError, OdooResult};
+use crate::odoo::unified_client::OdooClient;
+use crate::packs::{many2one_id, many2one_name};
+
+const PAYSLIP_MODEL: &str = "hr.payslip";
+const PAYSLIP_LINE_MODEL: &str = "hr.payslip.line";
+
+/// Elevated scope required (in addition to `includeAmounts`) before any amount leaves the server.
+pub const PAYROLL_AMOUNTS_ENV: &str = "ODOO_ENABLE_PAYROLL_AMOUNTS";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PayslipSummaryOptions {
+    /// `hr.payslip.run` ids; all batches when omitted.
+    pub batch_ids: Option<Vec<i64>>,
+    /// Only payslips whose period starts on or after this date (YYYY-MM-DD).
+    pub date_from: Option<String>,
+    /// Only payslips whose period ends on or before this date (YYYY-MM-DD).
+    pub date_to: Option<String>,
+    /// Also return per-payslip net amounts and salary lines. Requires the elevated scope.
+    pub include_amounts: Option<bool>,
+    /// Maximum payslips returned in the amounts detail.
+    pub limit: Option<i64>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchSummary {
+    pub batch_id: Option<i64>,
+    pub batch: Option<String>,
+    pub total: i64,
+    pub by_status: BTreeMap<String, i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PayslipLineAmount {
+    pub code: Option<String>,
+    pub name: Option<String>,
+    pub total: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PayslipAmounts {
+    pub payslip_id: i64,
+    pub employee: Option<String>,
+    pub batch_id: Option<i64>,
+    pub state: Option<String>,
+    pub net_wage: Option<f64>,
+    pub lines: Vec<PayslipLineAmount>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PayslipSummary {
+    pub total: i64,
+    pub by_status: BTreeMap<String, i64>,
+    pub batches: Vec<BatchSummary>,
+    pub amounts_included: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payslips: Option<Vec<PayslipAmounts>>,
+}
+
+/// Fold `read_group(["payslip_run_id", "state"], lazy=False)` rows into per-batch counts.
+fn aggregate_batches(rows: &Value) -> Vec<BatchSummary> {
+    let mut batches: Vec<BatchSummary> = Vec::new();
+    for row in rows.as_array().map(Vec::as_slice).unwrap_or(&[]) {
+        let batch_id = row.get("payslip_run_id").and_then(many2one_id);
+        let state = row
+            .get("state")
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown")
+            .to_string();
+        let count = row.get("__count").and_then(|v| v.as_i64()).unwrap_or(0);
+        let index = match batches.iter().position(|b| b.batch_id == batch_id) {
+            Some(index) => index,
+            None => {
+                batches.push(BatchSummary {
+                    batch_id,
+                    batch: row.get("payslip_run_id").and_then(many2one_name),
+                    total: 0,
+                    by_status: BTreeMap::new(),
+                });
+                batches.len() - 1
+            }
+        };
+        let batch = &mut batches[index];
+        batch.total += count;
+        *batch.by_status.entry(state).or_insert(0) += count;
+    }
+    batches
+}
+
+fn parse_lines(rows: &Value) -> HashMap<i64, Vec<PayslipLineAmount>> {
+    let mut by_slip: HashMap<i64, Vec<PayslipLineAmount>> = HashMap::new();
+    for row in rows.as_array().map(Vec::as_slice).unwrap_or(&[]) {
+        let Some(slip_id) = row.get("slip_id").and_then(many2one_id) else {
+            continue;
+        };
+        let text = |key: &str| row.get(key).and_then(|v| v.as_str()).map(String::from);
+        by_slip.entry(slip_id).or_default().push(PayslipLineAmount {
+            code: text("code"),
+            name: text("name"),
+            total: row.get("total").and_then(|v| v.as_f64()).unwrap_or(0.0),
+        });
+    }
+    by_slip
+}
+
+pub async fn payslip_summary(
+    client: &OdooClient,
+    options: PayslipSummaryOptions,
+) -> OdooResult<PayslipSummary> {
+    let amounts_included = options.include_amounts.unwrap_or(false);
+    if amounts_included && !env_truthy(PAYROLL_AMOUNTS_ENV) {
+        return Err(OdooError::InvalidResponse(format!(
+            "Payslip amounts require the elevated payroll scope ({PAYROLL_AMOUNTS_ENV}=true)"
+        )));
+    }
+
+    let mut domain = Vec::new();
+    if let Some(ids) = options.batch_ids.as_ref().filter(|ids| !ids.is_empty()) {
+        domain.push(json!(["payslip_run_id", "in", ids]));
+    }
+    if let Some(from) = options.date_from.as_deref() {
+        domain.push(json!(["date_from", ">=", from]));
+    }
+    if let Some(to) = options.date_to.as_deref() {
+        domain.push(json!(["date_to", "<=", to]));
+    }
+
+    let rows = client
+        .read_group(
+            PAYSLIP_MODEL,
+            Some(Value::Array(domain.clone())),
+            vec!["state".to_string()],
+            vec!["payslip_run_id".to_string(), "state".to_string()],
+            None,
+            None,
+            None,
+            Some(false),
+            options.context.clone(),
+        )
+        .await?;
+    let batches = aggregate_batches(&rows);
+    let mut by_status = BTreeMap::new();
+    for batch in &batches {
+        for (state, count) in &batch.by_status {
+            *by_status.entry(state.clone()).or_insert(0) += count;
+        }
+    }
+
+    let payslips = if amounts_included {
+        let slips = client
+            .search_read(
+                PAYSLIP_MODEL,
+                Some(Value::Array(domain)),
+                Some(vec![
+                    "employee_id".to_string(),
+                    "payslip_run_id".to_string(),
+                    "state".to_string(),
+                    "net_wage".to_string(),
+                ]),
+                Some(options.limit.unwrap_or(100)),
+                None,
+                Some("id asc".to_string()),
+                options.context.clone(),
+            )
+            .await?;
+        let slips = slips.as_array().cloned().unwrap_or_default();
+        let ids: Vec<i64> = slips
+            .iter()
+            .filter_map(|s| s.get("id").and_then(|v| v.as_i64()))
+            .collect();
+        let lines = client
+            .search_read(
+                PAYSLIP_LINE_MODEL,
+                Some(json!([["slip_id", "in", ids]])),
+                Some(vec![
+                    "slip_id".to_string(),
+                    "code".to_string(),
+                    "name".to_string(),
+                    "total".to_string(),
+                ]),
+                None,
+                None,
+                Some("slip_id asc, sequence asc".to_string()),
+                options.context,
+            )
+            .await?;
+        let mut lines = parse_lines(&lines);
+        Some(
+            slips
+                .iter()
+                .filter_map(|slip| {
+                    let payslip_id = slip.get("id")?.as_i64()?;
+                    Some(PayslipAmounts {
+                        payslip_id,
+                        employee: slip.get("employee_id").and_then(many2one_name),
+                        batch_id: slip.get("payslip_run_id").and_then(many2one_id),
+                        state: slip.get("state").and_then(|v| v.as_str()).map(String::from),
+                        net_wage: slip.get("net_wage").and_then(|v| v.as_f64()),
+                        lines: lines.remove(&payslip_id).unwrap_or_default(),
+                    })
+                })
+                .collect(),
+        )
+    } else {
+        None
+    };
+
+    Ok(PayslipSummary {
+        total: batches.iter().map(|b| b.total).sum(),
+        by_status,
+        batches,
+        amounts_included,
+        payslips,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregate_batches_counts_states_per_batch() {
+        let rows = json!([
+            {"payslip_run_id": [1, "June 2026"], "state": "draft", "__count": 3},
+            {"payslip_run_id": [1, "June 2026"], "state": "done", "__count": 7},
+            {"payslip_run_id": false, "state": "draft", "__count": 2}
+        ]);
+        let batches = aggregate_batches(&rows);
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].batch.as_deref(), Some("June 2026"));
+        assert_eq!(batches[0].total, 10);
+        assert_eq!(batches[0].by_status.get("done"), Some(&7));
+        assert_eq!(batches[1].batch_id, None);
+        assert_eq!(batches[1].total, 2);
+    }
+
+    #[test]
+    fn parse_lines_groups_by_payslip() {
+        let rows = json!([
+            {"slip_id": [5, "Slip"], "code": "BASIC", "name": "Basic", "total": 3000.0},
+            {"slip_id": [5, "Slip"], "code": "NET", "name": "Net", "total": 2400.0},
+            {"slip_id": false, "code": "X", "name": "Orphan", "total": 1.0}
+        ]);
+        let lines = parse_lines(&rows);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[&5].len(), 2);
+        assert_eq!(lines[&5][1].code.as_deref(), Some("NET"));
+    }
+}
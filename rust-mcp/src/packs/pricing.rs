@@ -0,0 +1,658 @@
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+use crate::odoo::types::{OdooError, OdooResult};
+use crate::odoo::unified_client::OdooClient;
+use crate::packs::{many2one_id, many2one_name, resolve_by_name};
+
+const PRICELIST_MODEL: &str = "product.pricelist";
+const ITEM_MODEL: &str = "product.pricelist.item";
+
+const ITEM_FIELDS: &[&str] = &[
+    "name",
+    "applied_on",
+    "product_id",
+    "product_tmpl_id",
+    "categ_id",
+    "min_quantity",
+    "date_start",
+    "date_end",
+    "compute_price",
+    "fixed_price",
+    "percent_price",
+    "base",
+    "base_pricelist_id",
+    "price_discount",
+    "price_surcharge",
+    "price_round",
+    "price_min_margin",
+    "price_max_margin",
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceSimulationOptions {
+    /// Product variant by id or by name.
+    pub product_id: Option<i64>,
+    pub product: Option<String>,
+    pub partner_id: Option<i64>,
+    /// Defaults to the partner's pricelist.
+    pub pricelist_id: Option<i64>,
+    pub quantity: Option<f64>,
+    /// Pricing date (YYYY-MM-DD); defaults to today.
+    pub date: Option<String>,
+    /// Past sale order lines to show for the product (default 10, 0 to skip).
+    pub history_limit: Option<i64>,
+    pub context: Option<Value>,
+}
+
+const EARLIER_RULE: &str = "an earlier rule applies";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleCandidate {
+    pub rule_id: i64,
+    pub name: Option<String>,
+    pub applied_on: Option<String>,
+    pub compute_price: Option<String>,
+    pub min_quantity: f64,
+    pub date_start: Option<String>,
+    pub date_end: Option<String>,
+    pub applies: bool,
+    /// Why the rule does not apply.
+    pub skipped_because: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceHistoryLine {
+    pub order: Option<String>,
+    pub partner: Option<String>,
+    pub date: Option<String>,
+    pub quantity: f64,
+    pub price_unit: f64,
+    pub discount: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceSimulation {
+    pub product_id: i64,
+    pub product: Option<String>,
+    pub pricelist_id: i64,
+    pub pricelist: Option<String>,
+    pub partner_id: Option<i64>,
+    pub quantity: f64,
+    pub date: String,
+    pub list_price: f64,
+    pub price: Option<f64>,
+    pub rule_id: Option<i64>,
+    /// `server` when Odoo computed the price, `emulated` when the rules were evaluated here.
+    pub source: String,
+    /// Rules of the pricelist in the order Odoo tries them; the first applicable one fires.
+    pub candidates: Vec<RuleCandidate>,
+    pub history: Vec<PriceHistoryLine>,
+    pub notes: Vec<String>,
+}
+
+/// What the rule matching needs to know about the product.
+#[derive(Debug, Clone, Default)]
+struct ProductFacts {
+    id: i64,
+    template_id: Option<i64>,
+    /// The product category and all its parents.
+    category_ids: Vec<i64>,
+    list_price: f64,
+    standard_price: f64,
+}
+
+fn text(row: &Value, key: &str) -> Option<String> {
+    row.get(key)
+        .and_then(Value::as_str)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+}
+
+fn number(row: &Value, key: &str) -> f64 {
+    row.get(key).and_then(Value::as_f64).unwrap_or(0.0)
+}
+
+/// Why `item` does not apply to the product/quantity/date, or `None` if it does.
+fn skip_reason(item: &Value, product: &ProductFacts, quantity: f64, date: &str) -> Option<String> {
+    let min_quantity = number(item, "min_quantity");
+    if min_quantity > quantity {
+        return Some(format!("minimum quantity {min_quantity}"));
+    }
+    // Datetimes in 17+, dates before; comparing the date part works for both.
+    if let Some(start) = text(item, "date_start")
+        && start.get(..10).unwrap_or(&start) > date
+    {
+        return Some(format!("starts {start}"));
+    }
+    if let Some(end) = text(item, "date_end")
+        && end.get(..10).unwrap_or(&end) < date
+    {
+        return Some(format!("ended {end}"));
+    }
+    let matches = match text(item, "applied_on").as_deref() {
+        Some("0_product_variant") => {
+            item.get("product_id").and_then(many2one_id) == Some(product.id)
+        }
+        Some("1_product") => {
+            item.get("product_tmpl_id").and_then(many2one_id) == product.template_id
+        }
+        Some("2_product_category") => item
+            .get("categ_id")
+            .and_then(many2one_id)
+            .is_some_and(|id| product.category_ids.contains(&id)),
+        _ => true,
+    };
+    (!matches).then(|| "other product".to_string())
+}
+
+/// Price from a rule, following `product.pricelist.item._compute_price`.
+///
+/// `None` when the rule is based on another pricelist, which is not followed here.
+fn rule_price(item: &Value, product: &ProductFacts) -> Option<f64> {
+    let compute_price = text(item, "compute_price");
+    if compute_price.as_deref() == Some("fixed") {
+        return Some(number(item, "fixed_price"));
+    }
+    let base = match text(item, "base").as_deref() {
+        Some("standard_price") => product.standard_price,
+        Some("pricelist") => return None,
+        _ => product.list_price,
+    };
+    if compute_price.as_deref() == Some("percentage") {
+        return Some(base - base * number(item, "percent_price") / 100.0);
+    }
+    let mut price = base - base * number(item, "price_discount") / 100.0;
+    let rounding = number(item, "price_round");
+    if rounding > 0.0 {
+        price = (price / rounding).round() * rounding;
+    }
+    price += number(item, "price_surcharge");
+    let min_margin = number(item, "price_min_margin");
+    if min_margin != 0.0 {
+        price = price.max(base + min_margin);
+    }
+    let max_margin = number(item, "price_max_margin");
+    if max_margin != 0.0 {
+        price = price.min(base + max_margin);
+    }
+    Some(price)
+}
+
+fn candidates(
+    items: &[Value],
+    product: &ProductFacts,
+    quantity: f64,
+    date: &str,
+) -> Vec<RuleCandidate> {
+    let mut fired = false;
+    items
+        .iter()
+        .filter_map(|item| {
+            let reason = skip_reason(item, product, quantity, date);
+            let applies = reason.is_none() && !fired;
+            fired |= applies;
+            Some(RuleCandidate {
+                rule_id: item.get("id")?.as_i64()?,
+                name: text(item, "name"),
+                applied_on: text(item, "applied_on"),
+                compute_price: text(item, "compute_price"),
+                min_quantity: number(item, "min_quantity"),
+                date_start: text(item, "date_start"),
+                date_end: text(item, "date_end"),
+                applies,
+                skipped_because: match reason {
+                    Some(reason) => Some(reason),
+                    None if !applies => Some(EARLIER_RULE.to_string()),
+                    None => None,
+                },
+            })
+        })
+        .collect()
+}
+
+/// Trust Odoo's choice of rule over the local matching, keeping `skipped_because` in line:
+/// rules the local matching ruled out keep their reason, the others are explained by where
+/// Odoo's rule sits in the order.
+fn apply_server_choice(candidates: &mut [RuleCandidate], rule_id: Option<i64>) {
+    let fired = candidates.iter().position(|c| Some(c.rule_id) == rule_id);
+    for (index, candidate) in candidates.iter_mut().enumerate() {
+        candidate.applies = Some(index) == fired;
+        let locally_eligible = candidate
+            .skipped_because
+            .as_deref()
+            .is_none_or(|reason| reason == EARLIER_RULE);
+        if candidate.applies {
+            candidate.skipped_because = None;
+        } else if locally_eligible {
+            candidate.skipped_because = Some(match fired {
+                Some(fired) if fired < index => EARLIER_RULE.to_string(),
+                _ => "not applied by Odoo".to_string(),
+            });
+        }
+    }
+}
+
+async fn read_product(
+    client: &OdooClient,
+    product_id: i64,
+    context: Option<Value>,
+) -> OdooResult<(ProductFacts, Option<String>)> {
+    let rows = client
+        .read(
+            "product.product",
+            vec![product_id],
+            Some(vec![
+                "display_name".to_string(),
+                "product_tmpl_id".to_string(),
+                "categ_id".to_string(),
+                "lst_price".to_string(),
+                "standard_price".to_string(),
+            ]),
+            context.clone(),
+        )
+        .await?;
+    let row = rows
+        .as_array()
+        .and_then(|rows| rows.first())
+        .cloned()
+        .ok_or_else(|| OdooError::InvalidResponse(format!("Product {product_id} not found")))?;
+
+    let mut category_ids = Vec::new();
+    if let Some(categ_id) = row.get("categ_id").and_then(many2one_id) {
+        let categories = client
+            .read(
+                "product.category",
+                vec![categ_id],
+                Some(vec!["parent_path".to_string()]),
+                context,
+            )
+            .await?;
+        category_ids = categories
+            .get(0)
+            .and_then(|c| c.get("parent_path"))
+            .and_then(Value::as_str)
+            .map(|path| path.split('/').filter_map(|id| id.parse().ok()).collect())
+            .unwrap_or_else(|| vec![categ_id]);
+    }
+    Ok((
+        ProductFacts {
+            id: product_id,
+            template_id: row.get("product_tmpl_id").and_then(many2one_id),
+            category_ids,
+            list_price: number(&row, "lst_price"),
+            standard_price: number(&row, "standard_price"),
+        },
+        text(&row, "display_name"),
+    ))
+}
+
+fn with_context(context: Option<Value>, extra: Value) -> Value {
+    let mut merged = match context {
+        Some(Value::Object(map)) => map,
+        _ => Map::new(),
+    };
+    if let Value::Object(extra) = extra {
+        merged.extend(extra);
+    }
+    Value::Object(merged)
+}
+
+/// `price_rule_get` is public up to Odoo 16; 17+ only has private methods and returns an error.
+async fn server_price(
+    client: &OdooClient,
+    pricelist_id: i64,
+    product_id: i64,
+    quantity: f64,
+    partner_id: Option<i64>,
+    context: Value,
+) -> Option<(f64, Option<i64>)> {
+    let mut params = Map::new();
+    params.insert("prod_id".to_string(), json!(product_id));
+    params.insert("qty".to_string(), json!(quantity));
+    params.insert("partner".to_string(), json!(partner_id.unwrap_or_default()));
+    let result = client
+        .call_named(
+            PRICELIST_MODEL,
+            "price_rule_get",
+            Some(vec![pricelist_id]),
+            params,
+            Some(context),
+        )
+        .await
+        .ok()?;
+    let price = result.get(0)?.as_f64()?;
+    Some((
+        price,
+        result.get(1).and_then(Value::as_i64).filter(|id| *id > 0),
+    ))
+}
+
+async fn price_history(
+    client: &OdooClient,
+    product_id: i64,
+    partner_id: Option<i64>,
+    limit: i64,
+    context: Option<Value>,
+) -> OdooResult<Vec<PriceHistoryLine>> {
+    let mut domain = vec![
+        json!(["product_id", "=", product_id]),
+        json!(["state", "in", ["sale", "done"]]),
+    ];
+    if let Some(partner_id) = partner_id {
+        domain.push(json!(["order_partner_id", "child_of", partner_id]));
+    }
+    let rows = client
+        .search_read(
+            "sale.order.line",
+            Some(Value::Array(domain)),
+            Some(vec![
+                "order_id".to_string(),
+                "order_partner_id".to_string(),
+                "create_date".to_string(),
+                "product_uom_qty".to_string(),
+                "price_unit".to_string(),
+                "discount".to_string(),
+            ]),
+            Some(limit),
+            None,
+            Some("id desc".to_string()),
+            context,
+        )
+        .await?;
+    Ok(rows
+        .as_array()
+        .map(|rows| {
+            rows.iter()
+                .map(|row| PriceHistoryLine {
+                    order: row.get("order_id").and_then(many2one_name),
+                    partner: row.get("order_partner_id").and_then(many2one_name),
+                    date: text(row, "create_date"),
+                    quantity: number(row, "product_uom_qty"),
+                    price_unit: number(row, "price_unit"),
+                    discount: number(row, "discount"),
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+pub async fn price_simulation(
+    client: &OdooClient,
+    options: PriceSimulationOptions,
+) -> OdooResult<PriceSimulation> {
+    let product_id = match (options.product_id, options.product.as_deref()) {
+        (Some(id), _) => id,
+        (None, Some(name)) => {
+            resolve_by_name(client, "product.product", "name", name, vec![]).await?
+        }
+        (None, None) => {
+            return Err(OdooError::InvalidResponse(
+                "Pass 'productId' or 'product'".to_string(),
+            ));
+        }
+    };
+    let quantity = options.quantity.unwrap_or(1.0);
+    let date = match options.date.as_deref() {
+        Some(date) => NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| {
+            OdooError::InvalidResponse(format!("date must be YYYY-MM-DD, got '{date}'"))
+        })?,
+        None => Utc::now().date_naive(),
+    }
+    .to_string();
+
+    let pricelist_id = match (options.pricelist_id, options.partner_id) {
+        (Some(id), _) => id,
+        (None, Some(partner_id)) => {
+            let partner = client
+                .read(
+                    "res.partner",
+                    vec![partner_id],
+                    Some(vec!["property_product_pricelist".to_string()]),
+                    options.context.clone(),
+                )
+                .await?;
+            partner
+                .get(0)
+                .and_then(|p| p.get("property_product_pricelist"))
+                .and_then(many2one_id)
+                .ok_or_else(|| {
+                    OdooError::InvalidResponse(format!(
+                        "Partner {partner_id} has no pricelist; pass 'pricelistId'"
+                    ))
+                })?
+        }
+        (None, None) => {
+            return Err(OdooError::InvalidResponse(
+                "Pass 'pricelistId' or 'partnerId'".to_string(),
+            ));
+        }
+    };
+
+    let (product, product_name) = read_product(client, product_id, options.context.clone()).await?;
+    let pricelist = client
+        .read(
+            PRICELIST_MODEL,
+            vec![pricelist_id],
+            Some(vec!["display_name".to_string()]),
+            options.context.clone(),
+        )
+        .await?;
+    let items = client
+        .search_read(
+            ITEM_MODEL,
+            Some(json!([["pricelist_id", "=", pricelist_id]])),
+            Some(ITEM_FIELDS.iter().map(|f| f.to_string()).collect()),
+            None,
+            None,
+            // product.pricelist.item._order
+            Some("applied_on, min_quantity desc, categ_id desc, id desc".to_string()),
+            options.context.clone(),
+        )
+        .await?;
+    let items = items.as_array().cloned().unwrap_or_default();
+    let mut candidates = candidates(&items, &product, quantity, &date);
+    let mut notes = Vec::new();
+
+    let server_context = with_context(options.context.clone(), json!({ "date": date }));
+    let (price, rule_id, source) = match server_price(
+        client,
+        pricelist_id,
+        product_id,
+        quantity,
+        options.partner_id,
+        server_context,
+    )
+    .await
+    {
+        Some((price, rule_id)) => {
+            apply_server_choice(&mut candidates, rule_id);
+            (Some(price), rule_id, "server")
+        }
+        None => {
+            let fired = candidates
+                .iter()
+                .find(|c| c.applies)
+                .and_then(|c| items.iter().find(|i| i["id"].as_i64() == Some(c.rule_id)));
+            let price = match fired {
+                Some(item) => {
+                    let price = rule_price(item, &product);
+                    if price.is_none() {
+                        notes.push(
+                            "The rule is based on another pricelist, which is not evaluated"
+                                .to_string(),
+                        );
+                    }
+                    price
+                }
+                None => Some(product.list_price),
+            };
+            notes.push(
+                "Price evaluated from the pricelist rules; currency conversion and UoM are not applied"
+                    .to_string(),
+            );
+            (price, fired.and_then(|i| i["id"].as_i64()), "emulated")
+        }
+    };
+
+    let history_limit = options.history_limit.unwrap_or(10);
+    let history = if history_limit > 0 {
+        match price_history(
+            client,
+            product_id,
+            options.partner_id,
+            history_limit,
+            options.context,
+        )
+        .await
+        {
+            Ok(history) => history,
+            Err(e) => {
+                notes.push(format!("No sale history: {e}"));
+                Vec::new()
+            }
+        }
+    } else {
+        Vec::new()
+    };
+
+    Ok(PriceSimulation {
+        product_id,
+        product: product_name,
+        pricelist_id,
+        pricelist: pricelist.get(0).and_then(|p| text(p, "display_name")),
+        partner_id: options.partner_id,
+        quantity,
+        date,
+        list_price: product.list_price,
+        price,
+        rule_id,
+        source: source.to_string(),
+        candidates,
+        history,
+        notes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn product() -> ProductFacts {
+        ProductFacts {
+            id: 5,
+            template_id: Some(50),
+            category_ids: vec![1, 8],
+            list_price: 100.0,
+            standard_price: 60.0,
+        }
+    }
+
+    #[test]
+    fn candidates_fire_first_applicable_rule() {
+        let items = [
+            json!({"id": 1, "applied_on": "0_product_variant", "product_id": [6, "Other"], "min_quantity": 0}),
+            json!({"id": 2, "applied_on": "1_product", "product_tmpl_id": [50, "Desk"], "min_quantity": 10}),
+            json!({"id": 3, "applied_on": "2_product_category", "categ_id": [8, "Office"], "min_quantity": 0,
+                   "date_end": "2026-01-31 23:59:59"}),
+            json!({"id": 4, "applied_on": "2_product_category", "categ_id": [1, "All"], "min_quantity": 0}),
+            json!({"id": 5, "applied_on": "3_global", "min_quantity": 0}),
+        ];
+        let result = candidates(&items, &product(), 5.0, "2026-03-01");
+        let fired: Vec<i64> = result
+            .iter()
+            .filter(|c| c.applies)
+            .map(|c| c.rule_id)
+            .collect();
+        assert_eq!(fired, vec![4]);
+        assert_eq!(result[0].skipped_because.as_deref(), Some("other product"));
+        assert_eq!(
+            result[1].skipped_because.as_deref(),
+            Some("minimum quantity 10")
+        );
+        assert!(
+            result[2]
+                .skipped_because
+                .as_deref()
+                .unwrap()
+                .starts_with("ended")
+        );
+        assert_eq!(
+            result[4].skipped_because.as_deref(),
+            Some("an earlier rule applies")
+        );
+    }
+
+    #[test]
+    fn server_choice_recomputes_skip_reasons() {
+        let items = [
+            json!({"id": 1, "applied_on": "1_product", "product_tmpl_id": [50, "Desk"], "min_quantity": 10}),
+            json!({"id": 2, "applied_on": "2_product_category", "categ_id": [8, "Office"], "min_quantity": 0}),
+            json!({"id": 3, "applied_on": "2_product_category", "categ_id": [1, "All"], "min_quantity": 0}),
+            json!({"id": 4, "applied_on": "3_global", "min_quantity": 0}),
+        ];
+        let mut result = candidates(&items, &product(), 5.0, "2026-03-01");
+        assert!(result[1].applies);
+
+        // Odoo picked a rule the local matching had shadowed.
+        apply_server_choice(&mut result, Some(3));
+        let reasons: Vec<(bool, Option<&str>)> = result
+            .iter()
+            .map(|c| (c.applies, c.skipped_because.as_deref()))
+            .collect();
+        assert_eq!(
+            reasons,
+            vec![
+                (false, Some("minimum quantity 10")),
+                (false, Some("not applied by Odoo")),
+                (true, None),
+                (false, Some(EARLIER_RULE)),
+            ]
+        );
+
+        apply_server_choice(&mut result, None);
+        assert!(result.iter().all(|c| !c.applies));
+        assert_eq!(
+            result[2].skipped_because.as_deref(),
+            Some("not applied by Odoo")
+        );
+        assert_eq!(
+            result[0].skipped_because.as_deref(),
+            Some("minimum quantity 10")
+        );
+    }
+
+    #[test]
+    fn rule_price_follows_odoo_formula() {
+        let p = product();
+        assert_eq!(
+            rule_price(&json!({"compute_price": "fixed", "fixed_price": 42.0}), &p),
+            Some(42.0)
+        );
+        assert_eq!(
+            rule_price(
+                &json!({"compute_price": "percentage", "percent_price": 15.0}),
+                &p
+            ),
+            Some(85.0)
+        );
+        // Cost + 20%, rounded to 5, minus 0.01, at least 5 over cost.
+        let formula = json!({
+            "compute_price": "formula", "base": "standard_price", "price_discount": -20.0,
+            "price_round": 5.0, "price_surcharge": -0.01, "price_min_margin": 5.0
+        });
+        assert!((rule_price(&formula, &p).unwrap() - 69.99).abs() < 1e-9);
+        assert_eq!(
+            rule_price(
+                &json!({"compute_price": "formula", "base": "pricelist"}),
+                &p
+            ),
+            None
+        );
+    }
+}
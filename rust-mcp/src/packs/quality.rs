@@ -0,0 +1,344 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+use crate::odoo::types::{OdooError, OdooResult};
+use crate::odoo::unified_client::OdooClient;
+use crate::packs::{many2one_id, many2one_name, resolve_by_name};
+
+const EQUIPMENT_MODEL: &str = "maintenance.equipment";
+const REQUEST_MODEL: &str = "maintenance.request";
+const CHECK_MODEL: &str = "quality.check";
+const ALERT_MODEL: &str = "quality.alert";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EquipmentLookupOptions {
+    pub serial_no: String,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceRequestOptions {
+    pub name: String,
+    pub description: Option<String>,
+    /// Target equipment by id or by serial number.
+    pub equipment_id: Option<i64>,
+    pub serial_no: Option<String>,
+    /// "corrective" (default) or "preventive".
+    pub maintenance_type: Option<String>,
+    /// "0" (very low) to "3" (high).
+    pub priority: Option<String>,
+    pub team: Option<String>,
+    pub schedule_date: Option<String>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QualityCheckOptions {
+    pub product_id: Option<i64>,
+    pub point_id: Option<i64>,
+    pub team: Option<String>,
+    pub picking_id: Option<i64>,
+    pub production_id: Option<i64>,
+    pub note: Option<String>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QualityAlertOptions {
+    pub name: String,
+    pub description: Option<String>,
+    pub product_id: Option<i64>,
+    pub team: Option<String>,
+    pub priority: Option<String>,
+    /// Equipment serial number; requires the quality/maintenance bridge module.
+    pub serial_no: Option<String>,
+    pub check_id: Option<i64>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Equipment {
+    pub id: i64,
+    pub name: String,
+    pub serial_no: Option<String>,
+    pub category: Option<String>,
+    pub location: Option<String>,
+    pub team_id: Option<i64>,
+    pub team: Option<String>,
+    pub open_requests: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedRecord {
+    pub model: String,
+    pub id: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub equipment_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub team_id: Option<i64>,
+}
+
+fn text(row: &Value, key: &str) -> Option<String> {
+    row.get(key).and_then(|v| v.as_str()).map(String::from)
+}
+
+fn parse_equipment(row: &Value) -> Option<Equipment> {
+    Some(Equipment {
+        id: row.get("id")?.as_i64()?,
+        name: text(row, "name").unwrap_or_default(),
+        serial_no: text(row, "serial_no"),
+        category: row.get("category_id").and_then(many2one_name),
+        location: text(row, "location"),
+        team_id: row.get("maintenance_team_id").and_then(many2one_id),
+        team: row.get("maintenance_team_id").and_then(many2one_name),
+        open_requests: row
+            .get("maintenance_open_count")
+            .and_then(|v| v.as_i64())
+            .unwrap_or(0),
+    })
+}
+
+async fn find_equipment(
+    client: &OdooClient,
+    serial_no: &str,
+    context: Option<Value>,
+) -> OdooResult<Equipment> {
+    let serial_no = serial_no.trim();
+    if serial_no.is_empty() {
+        return Err(OdooError::InvalidResponse(
+            "'serialNo' must not be empty".to_string(),
+        ));
+    }
+    let rows = client
+        .search_read(
+            EQUIPMENT_MODEL,
+            Some(json!([["serial_no", "=", serial_no]])),
+            Some(vec![
+                "name".to_string(),
+                "serial_no".to_string(),
+                "category_id".to_string(),
+                "location".to_string(),
+                "maintenance_team_id".to_string(),
+                "maintenance_open_count".to_string(),
+            ]),
+            Some(2),
+            None,
+            None,
+            context,
+        )
+        .await?;
+    match rows.as_array().map(Vec::as_slice).unwrap_or(&[]) {
+        [row] => parse_equipment(row).ok_or_else(|| {
+            OdooError::InvalidResponse(format!("Malformed equipment record for '{serial_no}'"))
+        }),
+        [] => Err(OdooError::InvalidResponse(format!(
+            "No equipment found with serial number '{serial_no}'"
+        ))),
+        _ => Err(OdooError::InvalidResponse(format!(
+            "Serial number '{serial_no}' matches several equipment records"
+        ))),
+    }
+}
+
+pub async fn lookup_equipment(
+    client: &OdooClient,
+    options: EquipmentLookupOptions,
+) -> OdooResult<Equipment> {
+    find_equipment(client, &options.serial_no, options.context).await
+}
+
+pub async fn create_maintenance_request(
+    client: &OdooClient,
+    options: MaintenanceRequestOptions,
+) -> OdooResult<CreatedRecord> {
+    if options.name.trim().is_empty() {
+        return Err(OdooError::InvalidResponse(
+            "Request 'name' must not be empty".to_string(),
+        ));
+    }
+    let maintenance_type = options.maintenance_type.as_deref().unwrap_or("corrective");
+    if !matches!(maintenance_type, "corrective" | "preventive") {
+        return Err(OdooError::InvalidResponse(format!(
+            "Unknown maintenanceType '{maintenance_type}' (expected corrective or preventive)"
+        )));
+    }
+
+    let equipment = match (options.equipment_id, options.serial_no.as_deref()) {
+        (Some(id), _) => Some((id, None)),
+        (None, Some(serial_no)) => {
+            let equipment = find_equipment(client, serial_no, options.context.clone()).await?;
+            Some((equipment.id, equipment.team_id))
+        }
+        (None, None) => None,
+    };
+    // An explicit team wins; otherwise inherit the equipment's maintenance team.
+    let team_id = match options.team.as_deref() {
+        Some(team) => {
+            Some(resolve_by_name(client, "maintenance.team", "name", team, vec![]).await?)
+        }
+        None => equipment.and_then(|(_, team_id)| team_id),
+    };
+
+    let mut values = Map::new();
+    values.insert("name".into(), json!(options.name));
+    values.insert("maintenance_type".into(), json!(maintenance_type));
+    if let Some(description) = options.description {
+        values.insert("description".into(), json!(description));
+    }
+    if let Some((id, _)) = equipment {
+        values.insert("equipment_id".into(), json!(id));
+    }
+    if let Some(id) = team_id {
+        values.insert("maintenance_team_id".into(), json!(id));
+    }
+    if let Some(priority) = options.priority {
+        values.insert("priority".into(), json!(priority));
+    }
+    if let Some(date) = options.schedule_date {
+        values.insert("schedule_date".into(), json!(date));
+    }
+
+    let id = client
+        .create(REQUEST_MODEL, Value::Object(values), options.context)
+        .await?;
+    Ok(CreatedRecord {
+        model: REQUEST_MODEL.to_string(),
+        id,
+        equipment_id: equipment.map(|(id, _)| id),
+        team_id,
+    })
+}
+
+async fn resolve_quality_team(client: &OdooClient, team: Option<&str>) -> OdooResult<Option<i64>> {
+    match team {
+        Some(team) => Ok(Some(
+            resolve_by_name(client, "quality.alert.team", "name", team, vec![]).await?,
+        )),
+        None => Ok(None),
+    }
+}
+
+pub async fn create_quality_check(
+    client: &OdooClient,
+    options: QualityCheckOptions,
+) -> OdooResult<CreatedRecord> {
+    if options.point_id.is_none() && options.product_id.is_none() {
+        return Err(OdooError::InvalidResponse(
+            "Provide 'pointId' or 'productId'".to_string(),
+        ));
+    }
+    let team_id = resolve_quality_team(client, options.team.as_deref()).await?;
+
+    let mut values = Map::new();
+    for (field, value) in [
+        ("point_id", options.point_id),
+        ("product_id", options.product_id),
+        ("team_id", team_id),
+        ("picking_id", options.picking_id),
+        ("production_id", options.production_id),
+    ] {
+        if let Some(id) = value {
+            values.insert(field.into(), json!(id));
+        }
+    }
+    if let Some(note) = options.note {
+        values.insert("note".into(), json!(note));
+    }
+
+    let id = client
+        .create(CHECK_MODEL, Value::Object(values), options.context)
+        .await?;
+    Ok(CreatedRecord {
+        model: CHECK_MODEL.to_string(),
+        id,
+        equipment_id: None,
+        team_id,
+    })
+}
+
+pub async fn create_quality_alert(
+    client: &OdooClient,
+    options: QualityAlertOptions,
+) -> OdooResult<CreatedRecord> {
+    if options.name.trim().is_empty() {
+        return Err(OdooError::InvalidResponse(
+            "Alert 'name' must not be empty".to_string(),
+        ));
+    }
+    let team_id = resolve_quality_team(client, options.team.as_deref()).await?;
+    let equipment_id = match options.serial_no.as_deref() {
+        Some(serial_no) => Some(
+            find_equipment(client, serial_no, options.context.clone())
+                .await?
+                .id,
+        ),
+        None => None,
+    };
+
+    let mut values = Map::new();
+    values.insert("name".into(), json!(options.name));
+    if let Some(description) = options.description {
+        values.insert("description".into(), json!(description));
+    }
+    if let Some(priority) = options.priority {
+        values.insert("priority".into(), json!(priority));
+    }
+    for (field, value) in [
+        ("product_id", options.product_id),
+        ("team_id", team_id),
+        ("equipment_id", equipment_id),
+        ("check_id", options.check_id),
+    ] {
+        if let Some(id) = value {
+            values.insert(field.into(), json!(id));
+        }
+    }
+
+    let id = client
+        .create(ALERT_MODEL, Value::Object(values), options.context)
+        .await?;
+    Ok(CreatedRecord {
+        model: ALERT_MODEL.to_string(),
+        id,
+        equipment_id,
+        team_id,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_equipment_reads_team_and_open_requests() {
+        let row = json!({
+            "id": 12,
+            "name": "CNC Mill",
+            "serial_no": "SN-0042",
+            "category_id": [2, "Machines"],
+            "location": "Hall B",
+            "maintenance_team_id": [1, "Internal Maintenance"],
+            "maintenance_open_count": 3
+        });
+        let equipment = parse_equipment(&row).unwrap();
+        assert_eq!(equipment.serial_no.as_deref(), Some("SN-0042"));
+        assert_eq!(equipment.category.as_deref(), Some("Machines"));
+        assert_eq!(equipment.team_id, Some(1));
+        assert_eq!(equipment.open_requests, 3);
+    }
+
+    #[test]
+    fn parse_equipment_tolerates_missing_optionals() {
+        let row = json!({"id": 5, "name": "Press", "serial_no": false, "category_id": false});
+        let equipment = parse_equipment(&row).unwrap();
+        assert_eq!(equipment.serial_no, None);
+        assert_eq!(equipment.team, None);
+        assert_eq!(equipment.open_requests, 0);
+    }
+}
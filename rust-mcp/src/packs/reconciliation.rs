@@ -0,0 +1,511 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+use crate::odoo::types::{OdooError, OdooResult};
+use crate::odoo::unified_client::OdooClient;
+use crate::packs::{many2one_id, many2one_name};
+
+const STATEMENT_LINE_MODEL: &str = "account.bank.statement.line";
+const MOVE_LINE_MODEL: &str = "account.move.line";
+
+/// Candidates scoring below this are not proposed.
+const MIN_SCORE: u32 = 30;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestOptions {
+    pub journal_id: Option<i64>,
+    pub statement_line_ids: Option<Vec<i64>>,
+    pub date_from: Option<String>,
+    pub date_to: Option<String>,
+    /// Statement lines to analyse (default 20).
+    pub limit: Option<i64>,
+    /// Candidates proposed per statement line (default 3).
+    pub max_candidates: Option<usize>,
+    /// Relative amount tolerance for near matches (default 0.01 = 1%).
+    pub amount_tolerance: Option<f64>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmOptions {
+    pub statement_line_id: i64,
+    /// Open journal item (`account.move.line`) proposed by the suggest tool.
+    pub move_line_id: i64,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatementLine {
+    pub id: i64,
+    pub date: Option<String>,
+    pub label: Option<String>,
+    pub amount: f64,
+    pub partner_id: Option<i64>,
+    pub partner: Option<String>,
+    pub journal: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Candidate {
+    pub move_line_id: i64,
+    pub entry: Option<String>,
+    pub reference: Option<String>,
+    pub date: Option<String>,
+    pub partner: Option<String>,
+    pub amount_residual: f64,
+    pub score: u32,
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineSuggestions {
+    pub statement_line: StatementLine,
+    pub candidates: Vec<Candidate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Suggestions {
+    pub count: usize,
+    pub lines: Vec<LineSuggestions>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Confirmation {
+    pub statement_line_id: i64,
+    pub move_line_id: i64,
+    pub reconciled: bool,
+}
+
+/// Odoo returns `false` for empty char fields.
+fn text(row: &Value, key: &str) -> Option<String> {
+    row.get(key)
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.trim().is_empty())
+        .map(String::from)
+}
+
+fn parse_statement_line(row: &Value) -> Option<StatementLine> {
+    Some(StatementLine {
+        id: row.get("id")?.as_i64()?,
+        date: text(row, "date"),
+        label: text(row, "payment_ref"),
+        amount: row.get("amount").and_then(|v| v.as_f64()).unwrap_or(0.0),
+        partner_id: row.get("partner_id").and_then(many2one_id),
+        partner: row.get("partner_id").and_then(many2one_name),
+        journal: row.get("journal_id").and_then(many2one_name),
+    })
+}
+
+fn days_apart(a: Option<&str>, b: Option<&str>) -> Option<i64> {
+    let parse = |d: &str| NaiveDate::parse_from_str(d.get(..10)?, "%Y-%m-%d").ok();
+    Some((parse(a?)? - parse(b?)?).num_days().abs())
+}
+
+/// Score an open journal item against a statement line; higher is a better match.
+fn score_candidate(line: &StatementLine, row: &Value, tolerance: f64) -> Option<Candidate> {
+    let residual = row.get("amount_residual")?.as_f64()?;
+    let mut score = 0;
+    let mut reasons = Vec::new();
+
+    let diff = (residual - line.amount).abs();
+    if diff < 0.005 {
+        score += 50;
+        reasons.push("exact amount".to_string());
+    } else if diff <= line.amount.abs() * tolerance {
+        score += 30;
+        reasons.push(format!("amount within {:.1}%", tolerance * 100.0));
+    }
+
+    let partner_id = row.get("partner_id").and_then(many2one_id);
+    if partner_id.is_some() && partner_id == line.partner_id {
+        score += 30;
+        reasons.push("same partner".to_string());
+    }
+
+    let label = line.label.as_deref().unwrap_or("").to_lowercase();
+    let move_name = row.get("move_id").and_then(many2one_name);
+    let reference = text(row, "ref");
+    let referenced = [move_name.as_deref(), reference.as_deref()]
+        .into_iter()
+        .flatten()
+        .any(|r| r.len() >= 3 && label.contains(&r.to_lowercase()));
+    if referenced {
+        score += 30;
+        reasons.push("reference in label".to_string());
+    }
+
+    let date = text(row, "date_maturity").or_else(|| text(row, "date"));
+    match days_apart(line.date.as_deref(), date.as_deref()) {
+        Some(days) if days <= 7 => {
+            score += 10;
+            reasons.push("date within a week".to_string());
+        }
+        Some(days) if days <= 30 => {
+            score += 5;
+            reasons.push("date within a month".to_string());
+        }
+        _ => {}
+    }
+
+    Some(Candidate {
+        move_line_id: row.get("id")?.as_i64()?,
+        entry: move_name,
+        reference,
+        date,
+        partner: row.get("partner_id").and_then(many2one_name),
+        amount_residual: residual,
+        score,
+        reasons,
+    })
+}
+
+fn rank_candidates(
+    line: &StatementLine,
+    rows: &Value,
+    tolerance: f64,
+    max: usize,
+) -> Vec<Candidate> {
+    let mut candidates: Vec<Candidate> = rows
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or(&[])
+        .iter()
+        .filter_map(|row| score_candidate(line, row, tolerance))
+        .filter(|c| c.score >= MIN_SCORE)
+        .collect();
+    candidates.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then(a.move_line_id.cmp(&b.move_line_id))
+    });
+    candidates.truncate(max);
+    candidates
+}
+
+/// List unreconciled statement lines and propose open receivable/payable items to match.
+pub async fn suggest_matches(
+    client: &OdooClient,
+    options: SuggestOptions,
+) -> OdooResult<Suggestions> {
+    let mut domain = vec![json!(["is_reconciled", "=", false])];
+    if let Some(journal_id) = options.journal_id {
+        domain.push(json!(["journal_id", "=", journal_id]));
+    }
+    if let Some(ids) = options.statement_line_ids.as_ref() {
+        domain.push(json!(["id", "in", ids]));
+    }
+    if let Some(date) = options.date_from.as_deref() {
+        domain.push(json!(["date", ">=", date]));
+    }
+    if let Some(date) = options.date_to.as_deref() {
+        domain.push(json!(["date", "<=", date]));
+    }
+    let rows = client
+        .search_read(
+            STATEMENT_LINE_MODEL,
+            Some(Value::Array(domain)),
+            Some(vec![
+                "date".to_string(),
+                "payment_ref".to_string(),
+                "amount".to_string(),
+                "partner_id".to_string(),
+                "journal_id".to_string(),
+                "move_id".to_string(),
+            ]),
+            Some(options.limit.unwrap_or(20)),
+            None,
+            Some("date asc, id asc".to_string()),
+            options.context.clone(),
+        )
+        .await?;
+
+    let tolerance = options.amount_tolerance.unwrap_or(0.01).max(0.0);
+    let max = options.max_candidates.unwrap_or(3);
+    let mut lines = Vec::new();
+    for row in rows.as_array().map(Vec::as_slice).unwrap_or(&[]) {
+        let Some(line) = parse_statement_line(row) else {
+            continue;
+        };
+        let own_move = row.get("move_id").and_then(many2one_id).unwrap_or(0);
+        let slack = line.amount.abs() * tolerance + 0.005;
+        let mut candidate_domain = vec![
+            json!(["reconciled", "=", false]),
+            json!(["account_id.reconcile", "=", true]),
+            json!(["parent_state", "=", "posted"]),
+            json!(["move_id", "!=", own_move]),
+        ];
+        // Same partner with any amount, or any partner with a matching amount.
+        if let Some(partner_id) = line.partner_id {
+            candidate_domain.push(json!("|"));
+            candidate_domain.push(json!(["partner_id", "=", partner_id]));
+            candidate_domain.push(json!("&"));
+        }
+        candidate_domain.push(json!(["amount_residual", ">=", line.amount - slack]));
+        candidate_domain.push(json!(["amount_residual", "<=", line.amount + slack]));
+
+        let candidates = client
+            .search_read(
+                MOVE_LINE_MODEL,
+                Some(Value::Array(candidate_domain)),
+                Some(vec![
+                    "move_id".to_string(),
+                    "ref".to_string(),
+                    "date".to_string(),
+                    "date_maturity".to_string(),
+                    "partner_id".to_string(),
+                    "amount_residual".to_string(),
+                ]),
+                Some(50),
+                None,
+                Some("date_maturity asc, id asc".to_string()),
+                options.context.clone(),
+            )
+            .await?;
+        lines.push(LineSuggestions {
+            candidates: rank_candidates(&line, &candidates, tolerance, max),
+            statement_line: line,
+        });
+    }
+
+    Ok(Suggestions {
+        count: lines.len(),
+        lines,
+    })
+}
+
+fn first_row(rows: &Value) -> Option<&Value> {
+    rows.as_array().and_then(|rows| rows.first())
+}
+
+/// Reconcile a statement line with one open journal item.
+///
+/// Odoo 14/15 expose `account.bank.statement.line.reconcile`; from Odoo 16 (detected by the
+/// `internal_index` field) the statement move is reset to draft, its suspense line is moved to
+/// the counterpart account and partner, reposted, and reconciled with the journal item.
+pub async fn confirm_match(
+    client: &OdooClient,
+    options: ConfirmOptions,
+) -> OdooResult<Confirmation> {
+    let ctx = options.context.clone();
+    let line = client
+        .read(
+            STATEMENT_LINE_MODEL,
+            vec![options.statement_line_id],
+            Some(vec![
+                "move_id".to_string(),
+                "journal_id".to_string(),
+                "is_reconciled".to_string(),
+            ]),
+            ctx.clone(),
+        )
+        .await?;
+    let line = first_row(&line).cloned().ok_or_else(|| {
+        OdooError::InvalidResponse(format!(
+            "Statement line {} not found",
+            options.statement_line_id
+        ))
+    })?;
+    if line.get("is_reconciled").and_then(|v| v.as_bool()) == Some(true) {
+        return Err(OdooError::InvalidResponse(format!(
+            "Statement line {} is already reconciled",
+            options.statement_line_id
+        )));
+    }
+    let counterpart = client
+        .read(
+            MOVE_LINE_MODEL,
+            vec![options.move_line_id],
+            Some(vec![
+                "account_id".to_string(),
+                "partner_id".to_string(),
+                "reconciled".to_string(),
+            ]),
+            ctx.clone(),
+        )
+        .await?;
+    let counterpart = first_row(&counterpart).cloned().ok_or_else(|| {
+        OdooError::InvalidResponse(format!("Journal item {} not found", options.move_line_id))
+    })?;
+    if counterpart.get("reconciled").and_then(|v| v.as_bool()) == Some(true) {
+        return Err(OdooError::InvalidResponse(format!(
+            "Journal item {} is already reconciled",
+            options.move_line_id
+        )));
+    }
+
+    let fields = client.fields_get(STATEMENT_LINE_MODEL, ctx.clone()).await?;
+    if fields.get("internal_index").is_none() {
+        let mut params = Map::new();
+        params.insert(
+            "lines_vals_list".into(),
+            json!([{ "id": options.move_line_id }]),
+        );
+        client
+            .call_named(
+                STATEMENT_LINE_MODEL,
+                "reconcile",
+                Some(vec![options.statement_line_id]),
+                params,
+                ctx.clone(),
+            )
+            .await?;
+    } else {
+        let move_id = line.get("move_id").and_then(many2one_id).ok_or_else(|| {
+            OdooError::InvalidResponse("Statement line has no journal entry".to_string())
+        })?;
+        let journal_id = line.get("journal_id").and_then(many2one_id).unwrap_or(0);
+        let journal = client
+            .read(
+                "account.journal",
+                vec![journal_id],
+                Some(vec!["suspense_account_id".to_string()]),
+                ctx.clone(),
+            )
+            .await?;
+        let suspense_account = first_row(&journal)
+            .and_then(|j| j.get("suspense_account_id"))
+            .and_then(many2one_id)
+            .ok_or_else(|| {
+                OdooError::InvalidResponse("Bank journal has no suspense account".to_string())
+            })?;
+        let suspense = client
+            .search(
+                MOVE_LINE_MODEL,
+                Some(json!([
+                    ["move_id", "=", move_id],
+                    ["account_id", "=", suspense_account]
+                ])),
+                None,
+                None,
+                None,
+                ctx.clone(),
+            )
+            .await?;
+        let [suspense_line] = suspense.as_slice() else {
+            return Err(OdooError::InvalidResponse(format!(
+                "Expected one suspense line on the statement entry, found {}",
+                suspense.len()
+            )));
+        };
+
+        let mut values = Map::new();
+        values.insert(
+            "account_id".into(),
+            json!(counterpart.get("account_id").and_then(many2one_id)),
+        );
+        if let Some(partner_id) = counterpart.get("partner_id").and_then(many2one_id) {
+            values.insert("partner_id".into(), json!(partner_id));
+        }
+        client
+            .call_named(
+                "account.move",
+                "button_draft",
+                Some(vec![move_id]),
+                Map::new(),
+                ctx.clone(),
+            )
+            .await?;
+        client
+            .write(
+                MOVE_LINE_MODEL,
+                vec![*suspense_line],
+                Value::Object(values),
+                ctx.clone(),
+            )
+            .await?;
+        client
+            .call_named(
+                "account.move",
+                "action_post",
+                Some(vec![move_id]),
+                Map::new(),
+                ctx.clone(),
+            )
+            .await?;
+        client
+            .call_named(
+                MOVE_LINE_MODEL,
+                "reconcile",
+                Some(vec![*suspense_line, options.move_line_id]),
+                Map::new(),
+                ctx.clone(),
+            )
+            .await?;
+    }
+
+    let after = client
+        .read(
+            STATEMENT_LINE_MODEL,
+            vec![options.statement_line_id],
+            Some(vec!["is_reconciled".to_string()]),
+            ctx,
+        )
+        .await?;
+    Ok(Confirmation {
+        statement_line_id: options.statement_line_id,
+        move_line_id: options.move_line_id,
+        reconciled: first_row(&after)
+            .and_then(|r| r.get("is_reconciled"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statement_line() -> StatementLine {
+        parse_statement_line(&json!({
+            "id": 1, "date": "2026-03-10", "payment_ref": "Payment INV/2026/00042",
+            "amount": 1150.0, "partner_id": [7, "Deco Addict"], "journal_id": [3, "Bank"]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn score_candidate_combines_heuristics() {
+        let row = json!({
+            "id": 90, "move_id": [40, "INV/2026/00042"], "ref": false,
+            "date": "2026-03-01", "date_maturity": "2026-03-08",
+            "partner_id": [7, "Deco Addict"], "amount_residual": 1150.0
+        });
+        let candidate = score_candidate(&statement_line(), &row, 0.01).unwrap();
+        assert_eq!(candidate.score, 120);
+        assert_eq!(candidate.reasons.len(), 4);
+    }
+
+    #[test]
+    fn rank_candidates_drops_weak_matches_and_sorts() {
+        let rows = json!([
+            {"id": 1, "move_id": [1, "BILL/1"], "partner_id": [9, "Other"],
+             "amount_residual": 1149.0, "date": "2025-01-01"},
+            {"id": 2, "move_id": [2, "INV/9"], "partner_id": [7, "Deco Addict"],
+             "amount_residual": 1150.0, "date": "2026-03-09"},
+            {"id": 3, "move_id": [3, "INV/8"], "partner_id": [9, "Other"],
+             "amount_residual": 500.0, "date": "2026-03-09"}
+        ]);
+        let ranked = rank_candidates(&statement_line(), &rows, 0.01, 3);
+        assert_eq!(
+            ranked.iter().map(|c| c.move_line_id).collect::<Vec<_>>(),
+            vec![2, 1]
+        );
+    }
+
+    #[test]
+    fn days_apart_handles_datetimes_and_missing_values() {
+        assert_eq!(
+            days_apart(Some("2026-03-10"), Some("2026-03-01 12:00:00")),
+            Some(9)
+        );
+        assert_eq!(days_apart(None, Some("2026-03-01")), None);
+    }
+}
@@ -0,0 +1,553 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+use crate::odoo::types::{OdooError, OdooResult};
+use crate::odoo::unified_client::OdooClient;
+use crate::packs::{many2one_id, many2one_name};
+
+/// Where subscriptions live: `sale.subscription` up to Odoo 15, subscription
+/// `sale.order`s (with a `subscription_state` selection) from Odoo 16.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Flavor {
+    model: &'static str,
+    stage_field: &'static str,
+    end_field: &'static str,
+    next_invoice_field: &'static str,
+}
+
+const LEGACY: Flavor = Flavor {
+    model: "sale.subscription",
+    stage_field: "stage_id",
+    end_field: "date",
+    next_invoice_field: "recurring_next_date",
+};
+
+const ORDERS: Flavor = Flavor {
+    model: "sale.order",
+    stage_field: "subscription_state",
+    end_field: "end_date",
+    next_invoice_field: "next_invoice_date",
+};
+
+/// `sale.order.subscription_state` values (Odoo 16+).
+const ORDER_STATES: &[&str] = &[
+    "1_draft",
+    "2_renewal",
+    "3_progress",
+    "4_paused",
+    "5_renewed",
+    "6_churn",
+    "7_upsell",
+];
+
+impl Flavor {
+    fn base_domain(&self) -> Vec<Value> {
+        if *self == ORDERS {
+            vec![json!(["is_subscription", "=", true])]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn active_domain(&self) -> Value {
+        if *self == ORDERS {
+            json!(["subscription_state", "in", ["3_progress", "4_paused"]])
+        } else {
+            json!(["stage_category", "=", "progress"])
+        }
+    }
+
+    fn churned_domain(&self) -> Value {
+        if *self == ORDERS {
+            json!(["subscription_state", "=", "6_churn"])
+        } else {
+            json!(["stage_category", "=", "closed"])
+        }
+    }
+
+    fn stage_domain(&self, stage: &str) -> OdooResult<Value> {
+        if *self == ORDERS {
+            let state = order_state_code(stage).ok_or_else(|| {
+                OdooError::InvalidResponse(format!(
+                    "Unknown subscription stage '{stage}' (expected one of {})",
+                    ORDER_STATES.join(", ")
+                ))
+            })?;
+            Ok(json!(["subscription_state", "=", state]))
+        } else {
+            Ok(json!(["stage_id.name", "=ilike", stage]))
+        }
+    }
+}
+
+/// Accept either the raw selection value ("3_progress") or its suffix ("progress").
+fn order_state_code(stage: &str) -> Option<&'static str> {
+    let stage = stage.trim().to_ascii_lowercase().replace(' ', "_");
+    ORDER_STATES
+        .iter()
+        .copied()
+        .find(|code| *code == stage || code.split_once('_').is_some_and(|(_, s)| s == stage))
+}
+
+async fn detect_flavor(client: &OdooClient, context: Option<Value>) -> OdooResult<Flavor> {
+    match client
+        .search_count(LEGACY.model, Some(json!([])), context)
+        .await
+    {
+        Ok(_) => Ok(LEGACY),
+        Err(e) if is_missing_model(&e, LEGACY.model) => Ok(ORDERS),
+        Err(e) => Err(e),
+    }
+}
+
+/// Whether `err` is Odoo refusing `model` because it is not installed: "Object ... doesn't
+/// exist" or a `KeyError` from the legacy API, "the model ... does not exist" from JSON-2.
+/// Access, connection and server errors do not qualify.
+fn is_missing_model(err: &OdooError, model: &str) -> bool {
+    let OdooError::Api { message, body, .. } = err else {
+        return false;
+    };
+    let body_message = body.as_ref().and_then(|b| b.message.as_deref());
+    let key_error = body
+        .as_ref()
+        .and_then(|b| b.name.as_deref())
+        .is_some_and(|name| name.ends_with("KeyError"));
+    [Some(message.as_str()), body_message]
+        .into_iter()
+        .flatten()
+        .filter(|text| text.contains(model))
+        .any(|text| key_error || text.contains("does not exist") || text.contains("doesn't exist"))
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListSubscriptionsOptions {
+    /// Stage name (Odoo <= 15) or `subscription_state` value such as "progress" (Odoo 16+).
+    pub stage: Option<String>,
+    pub partner_id: Option<i64>,
+    pub limit: Option<i64>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionMetricsOptions {
+    /// Inclusive start of the churn window (YYYY-MM-DD).
+    pub date_from: String,
+    /// Inclusive end of the churn window (YYYY-MM-DD).
+    pub date_to: String,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenewalInvoiceOptions {
+    pub subscription_ids: Vec<i64>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Subscription {
+    pub id: i64,
+    pub name: String,
+    pub partner_id: Option<i64>,
+    pub partner: Option<String>,
+    pub stage: Option<String>,
+    pub mrr: f64,
+    pub next_invoice_date: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionList {
+    pub model: String,
+    pub count: usize,
+    pub subscriptions: Vec<Subscription>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StageMetrics {
+    pub stage: Option<String>,
+    pub count: i64,
+    pub mrr: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionMetrics {
+    pub model: String,
+    pub date_from: String,
+    pub date_to: String,
+    pub active_count: i64,
+    pub mrr: f64,
+    pub churned_count: i64,
+    pub churned_mrr: f64,
+    /// Churned subscriptions over (active + churned) in the window.
+    pub churn_rate: f64,
+    pub by_stage: Vec<StageMetrics>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenewalInvoiceResult {
+    pub model: String,
+    pub subscription_ids: Vec<i64>,
+    pub invoice_ids: Vec<i64>,
+}
+
+fn stage_label(value: &Value) -> Option<String> {
+    many2one_name(value).or_else(|| value.as_str().map(String::from))
+}
+
+fn parse_stage_groups(rows: &Value, stage_field: &str) -> Vec<StageMetrics> {
+    rows.as_array()
+        .map(|rows| {
+            rows.iter()
+                .map(|row| StageMetrics {
+                    stage: row.get(stage_field).and_then(stage_label),
+                    count: row.get("__count").and_then(|v| v.as_i64()).unwrap_or(0),
+                    mrr: row
+                        .get("recurring_monthly")
+                        .and_then(|v| v.as_f64())
+                        .unwrap_or(0.0),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn churn_rate(active: i64, churned: i64) -> f64 {
+    let base = active + churned;
+    if base == 0 {
+        0.0
+    } else {
+        churned as f64 / base as f64
+    }
+}
+
+fn collect_invoice_ids(rows: &Value) -> Vec<i64> {
+    let mut ids: Vec<i64> = rows
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or(&[])
+        .iter()
+        .filter_map(|row| row.get("invoice_ids").and_then(|v| v.as_array()))
+        .flatten()
+        .filter_map(|v| v.as_i64())
+        .collect();
+    ids.sort_unstable();
+    ids.dedup();
+    ids
+}
+
+pub async fn list_subscriptions(
+    client: &OdooClient,
+    options: ListSubscriptionsOptions,
+) -> OdooResult<SubscriptionList> {
+    let flavor = detect_flavor(client, options.context.clone()).await?;
+    let mut domain = flavor.base_domain();
+    if let Some(stage) = options.stage.as_deref() {
+        domain.push(flavor.stage_domain(stage)?);
+    }
+    if let Some(partner_id) = options.partner_id {
+        domain.push(json!(["partner_id", "=", partner_id]));
+    }
+
+    let rows = client
+        .search_read(
+            flavor.model,
+            Some(Value::Array(domain)),
+            Some(vec![
+                "name".to_string(),
+                "partner_id".to_string(),
+                flavor.stage_field.to_string(),
+                "recurring_monthly".to_string(),
+                flavor.next_invoice_field.to_string(),
+            ]),
+            Some(options.limit.unwrap_or(100)),
+            None,
+            Some("id desc".to_string()),
+            options.context,
+        )
+        .await?;
+    let subscriptions: Vec<Subscription> = rows
+        .as_array()
+        .map(|rows| {
+            rows.iter()
+                .filter_map(|row| {
+                    Some(Subscription {
+                        id: row.get("id")?.as_i64()?,
+                        name: row
+                            .get("name")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or_default()
+                            .to_string(),
+                        partner_id: row.get("partner_id").and_then(many2one_id),
+                        partner: row.get("partner_id").and_then(many2one_name),
+                        stage: row.get(flavor.stage_field).and_then(stage_label),
+                        mrr: row
+                            .get("recurring_monthly")
+                            .and_then(|v| v.as_f64())
+                            .unwrap_or(0.0),
+                        next_invoice_date: row
+                            .get(flavor.next_invoice_field)
+                            .and_then(|v| v.as_str())
+                            .map(String::from),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(SubscriptionList {
+        model: flavor.model.to_string(),
+        count: subscriptions.len(),
+        subscriptions,
+    })
+}
+
+async fn sum_mrr(
+    client: &OdooClient,
+    flavor: Flavor,
+    domain: Vec<Value>,
+    context: Option<Value>,
+) -> OdooResult<(i64, f64)> {
+    let rows = client
+        .read_group(
+            flavor.model,
+            Some(Value::Array(domain)),
+            vec!["recurring_monthly:sum".to_string()],
+            Vec::new(),
+            None,
+            None,
+            None,
+            Some(false),
+            context,
+        )
+        .await?;
+    let row = rows.as_array().and_then(|rows| rows.first());
+    Ok((
+        row.and_then(|r| r.get("__count"))
+            .and_then(|v| v.as_i64())
+            .unwrap_or(0),
+        row.and_then(|r| r.get("recurring_monthly"))
+            .and_then(|v| v.as_f64())
+            .unwrap_or(0.0),
+    ))
+}
+
+pub async fn subscription_metrics(
+    client: &OdooClient,
+    options: SubscriptionMetricsOptions,
+) -> OdooResult<SubscriptionMetrics> {
+    let flavor = detect_flavor(client, options.context.clone()).await?;
+
+    let by_stage = client
+        .read_group(
+            flavor.model,
+            Some(Value::Array(flavor.base_domain())),
+            vec!["recurring_monthly:sum".to_string()],
+            vec![flavor.stage_field.to_string()],
+            None,
+            None,
+            None,
+            Some(false),
+            options.context.clone(),
+        )
+        .await?;
+
+    let mut active_domain = flavor.base_domain();
+    active_domain.push(flavor.active_domain());
+    let (active_count, mrr) =
+        sum_mrr(client, flavor, active_domain, options.context.clone()).await?;
+
+    let mut churn_domain = flavor.base_domain();
+    churn_domain.push(flavor.churned_domain());
+    churn_domain.push(json!([flavor.end_field, ">=", options.date_from]));
+    churn_domain.push(json!([flavor.end_field, "<=", options.date_to]));
+    let (churned_count, churned_mrr) =
+        sum_mrr(client, flavor, churn_domain, options.context).await?;
+
+    Ok(SubscriptionMetrics {
+        model: flavor.model.to_string(),
+        date_from: options.date_from,
+        date_to: options.date_to,
+        active_count,
+        mrr,
+        churned_count,
+        churned_mrr,
+        churn_rate: churn_rate(active_count, churned_count),
+        by_stage: parse_stage_groups(&by_stage, flavor.stage_field),
+    })
+}
+
+pub async fn create_renewal_invoices(
+    client: &OdooClient,
+    options: RenewalInvoiceOptions,
+) -> OdooResult<RenewalInvoiceResult> {
+    if options.subscription_ids.is_empty() {
+        return Err(OdooError::InvalidResponse(
+            "'subscriptionIds' must not be empty".to_string(),
+        ));
+    }
+    let flavor = detect_flavor(client, options.context.clone()).await?;
+    let ids = options.subscription_ids;
+
+    if flavor == LEGACY {
+        client
+            .call_named(
+                flavor.model,
+                "recurring_invoice",
+                Some(ids.clone()),
+                Map::new(),
+                options.context.clone(),
+            )
+            .await?;
+    } else {
+        // Subscription orders invoice through the standard "Create Invoice" wizard.
+        let mut context = options
+            .context
+            .clone()
+            .and_then(|c| c.as_object().cloned())
+            .unwrap_or_default();
+        context.insert("active_model".into(), json!(flavor.model));
+        context.insert("active_ids".into(), json!(ids));
+        let context = Some(Value::Object(context));
+        let wizard_id = client
+            .create(
+                "sale.advance.payment.inv",
+                json!({
+                    "advance_payment_method": "delivered",
+                    "sale_order_ids": [[6, 0, ids]],
+                }),
+                context.clone(),
+            )
+            .await?;
+        client
+            .call_named(
+                "sale.advance.payment.inv",
+                "create_invoices",
+                Some(vec![wizard_id]),
+                Map::new(),
+                context,
+            )
+            .await?;
+    }
+
+    let invoice_ids = if flavor == LEGACY {
+        // Legacy subscriptions link invoices through their invoice lines.
+        client
+            .search(
+                "account.move",
+                Some(json!([["invoice_line_ids.subscription_id", "in", ids]])),
+                None,
+                None,
+                Some("id asc".to_string()),
+                options.context,
+            )
+            .await?
+    } else {
+        let rows = client
+            .read(
+                flavor.model,
+                ids.clone(),
+                Some(vec!["invoice_ids".to_string()]),
+                options.context,
+            )
+            .await?;
+        collect_invoice_ids(&rows)
+    };
+
+    Ok(RenewalInvoiceResult {
+        model: flavor.model.to_string(),
+        subscription_ids: ids,
+        invoice_ids,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::odoo::types::OdooErrorBody;
+
+    fn api_error(status: u16, message: &str, name: Option<&str>) -> OdooError {
+        OdooError::Api {
+            status,
+            message: message.to_string(),
+            body: name.map(|name| OdooErrorBody {
+                name: Some(name.to_string()),
+                message: Some(message.to_string()),
+                arguments: vec![],
+                context: Value::Null,
+                debug: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn only_a_missing_model_selects_the_order_flavor() {
+        let model = LEGACY.model;
+        assert!(is_missing_model(
+            &api_error(404, "the model 'sale.subscription' does not exist", None),
+            model
+        ));
+        assert!(is_missing_model(
+            &api_error(400, "Object sale.subscription doesn't exist", None),
+            model
+        ));
+        assert!(is_missing_model(
+            &api_error(200, "'sale.subscription'", Some("builtins.KeyError")),
+            model
+        ));
+
+        assert!(!is_missing_model(
+            &api_error(403, "Access Denied", Some("odoo.exceptions.AccessDenied")),
+            model
+        ));
+        assert!(!is_missing_model(
+            &api_error(500, "Internal Server Error", None),
+            model
+        ));
+        assert!(!is_missing_model(
+            &api_error(404, "Object helpdesk.ticket doesn't exist", None),
+            model
+        ));
+        assert!(!is_missing_model(&OdooError::Cancelled, model));
+    }
+
+    #[test]
+    fn order_state_code_accepts_value_or_suffix() {
+        assert_eq!(order_state_code("3_progress"), Some("3_progress"));
+        assert_eq!(order_state_code("Progress"), Some("3_progress"));
+        assert_eq!(order_state_code("churn"), Some("6_churn"));
+        assert_eq!(order_state_code("closed"), None);
+    }
+
+    #[test]
+    fn parse_stage_groups_handles_both_flavors() {
+        let legacy =
+            json!([{"stage_id": [2, "In Progress"], "__count": 4, "recurring_monthly": 400.0}]);
+        let groups = parse_stage_groups(&legacy, "stage_id");
+        assert_eq!(groups[0].stage.as_deref(), Some("In Progress"));
+        assert_eq!(groups[0].mrr, 400.0);
+
+        let orders =
+            json!([{"subscription_state": "3_progress", "__count": 2, "recurring_monthly": 90.5}]);
+        let groups = parse_stage_groups(&orders, "subscription_state");
+        assert_eq!(groups[0].stage.as_deref(), Some("3_progress"));
+        assert_eq!(groups[0].count, 2);
+    }
+
+    #[test]
+    fn collect_invoice_ids_dedups_across_orders() {
+        let rows = json!([{"id": 1, "invoice_ids": [9, 4]}, {"id": 2, "invoice_ids": [4]}]);
+        assert_eq!(collect_invoice_ids(&rows), vec![4, 9]);
+    }
+
+    #[test]
+    fn churn_rate_is_zero_without_subscriptions() {
+        assert_eq!(churn_rate(0, 0), 0.0);
+        assert_eq!(churn_rate(3, 1), 0.25);
+    }
+}
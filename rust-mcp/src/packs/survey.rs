@@ -0,0 +1,319 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::odoo::types::{OdooError, OdooResult};
+use crate::odoo::unified_client::OdooClient;
+use crate::packs::{many2one_id, many2one_name};
+
+const SURVEY_MODEL: &str = "survey.survey";
+const QUESTION_MODEL: &str = "survey.question";
+const INPUT_MODEL: &str = "survey.user_input";
+const LINE_MODEL: &str = "survey.user_input.line";
+
+/// Question types answered with free text (Odoo 14+ naming).
+const TEXT_TYPES: &[&str] = &["char_box", "text_box"];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SurveyResultsOptions {
+    pub survey_id: i64,
+    /// Include partially filled responses; by default only completed ones are counted.
+    pub include_partial: Option<bool>,
+    /// Free-text answers returned per text question (default 5, 0 disables).
+    pub sample_size: Option<i64>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChoiceCount {
+    pub answer_id: Option<i64>,
+    pub answer: Option<String>,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuestionResult {
+    pub question_id: i64,
+    pub title: String,
+    pub question_type: Option<String>,
+    /// Non-skipped answer lines; multiple-choice questions count one line per ticked answer.
+    pub answer_count: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub choices: Option<Vec<ChoiceCount>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub average: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub samples: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SurveyResults {
+    pub survey_id: i64,
+    pub title: String,
+    pub responses: i64,
+    pub questions: Vec<QuestionResult>,
+}
+
+/// Fold `read_group(["question_id", "suggested_answer_id"], lazy=False)` rows per question.
+fn fold_choices(rows: &Value) -> HashMap<i64, Vec<ChoiceCount>> {
+    let mut choices: HashMap<i64, Vec<ChoiceCount>> = HashMap::new();
+    for row in rows.as_array().map(Vec::as_slice).unwrap_or(&[]) {
+        let Some(question_id) = row.get("question_id").and_then(many2one_id) else {
+            continue;
+        };
+        choices.entry(question_id).or_default().push(ChoiceCount {
+            answer_id: row.get("suggested_answer_id").and_then(many2one_id),
+            answer: row.get("suggested_answer_id").and_then(many2one_name),
+            count: row.get("__count").and_then(|v| v.as_i64()).unwrap_or(0),
+        });
+    }
+    for counts in choices.values_mut() {
+        counts.sort_by_key(|c| std::cmp::Reverse(c.count));
+    }
+    choices
+}
+
+/// Fold `read_group(["question_id"])` rows into `(count, numeric aggregate)` per question.
+///
+/// Lazy grouping reports the count under `question_id_count`, non-lazy under `__count`.
+fn fold_per_question(rows: &Value, aggregate: Option<&str>) -> HashMap<i64, (i64, Option<f64>)> {
+    rows.as_array()
+        .map(Vec::as_slice)
+        .unwrap_or(&[])
+        .iter()
+        .filter_map(|row| {
+            let question_id = row.get("question_id").and_then(many2one_id)?;
+            let count = row
+                .get("question_id_count")
+                .or_else(|| row.get("__count"))
+                .and_then(|v| v.as_i64())
+                .unwrap_or(0);
+            let value = aggregate.and_then(|field| row.get(field)?.as_f64());
+            Some((question_id, (count, value)))
+        })
+        .collect()
+}
+
+fn text_answer(row: &Value) -> Option<String> {
+    ["value_char_box", "value_text_box"]
+        .iter()
+        .filter_map(|field| row.get(*field).and_then(|v| v.as_str()))
+        .map(str::trim)
+        .find(|text| !text.is_empty())
+        .map(String::from)
+}
+
+/// Aggregate survey answers per question without returning individual responses.
+///
+/// Choice questions get per-answer counts, numerical questions an average, and text
+/// questions a handful of the most recent answers. Targets the Odoo 14+ answer model.
+pub async fn survey_results(
+    client: &OdooClient,
+    options: SurveyResultsOptions,
+) -> OdooResult<SurveyResults> {
+    let survey = client
+        .read(
+            SURVEY_MODEL,
+            vec![options.survey_id],
+            Some(vec!["title".to_string()]),
+            options.context.clone(),
+        )
+        .await?;
+    let title = survey
+        .as_array()
+        .and_then(|rows| rows.first())
+        .map(|row| {
+            row.get("title")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string()
+        })
+        .ok_or_else(|| {
+            OdooError::InvalidResponse(format!("Survey {} not found", options.survey_id))
+        })?;
+
+    let questions = client
+        .search_read(
+            QUESTION_MODEL,
+            Some(json!([
+                ["survey_id", "=", options.survey_id],
+                ["is_page", "=", false]
+            ])),
+            Some(vec!["title".to_string(), "question_type".to_string()]),
+            None,
+            None,
+            Some("sequence asc, id asc".to_string()),
+            options.context.clone(),
+        )
+        .await?;
+
+    let mut input_domain = vec![json!(["survey_id", "=", options.survey_id])];
+    let mut line_domain = vec![
+        json!(["user_input_id.survey_id", "=", options.survey_id]),
+        json!(["skipped", "=", false]),
+    ];
+    if !options.include_partial.unwrap_or(false) {
+        input_domain.push(json!(["state", "=", "done"]));
+        line_domain.push(json!(["user_input_id.state", "=", "done"]));
+    }
+    let responses = client
+        .search_count(
+            INPUT_MODEL,
+            Some(Value::Array(input_domain)),
+            options.context.clone(),
+        )
+        .await?;
+
+    let with = |extra: Value| {
+        let mut domain = line_domain.clone();
+        domain.push(extra);
+        Some(Value::Array(domain))
+    };
+    let counts = client
+        .read_group(
+            LINE_MODEL,
+            Some(Value::Array(line_domain.clone())),
+            vec!["question_id".to_string()],
+            vec!["question_id".to_string()],
+            None,
+            None,
+            None,
+            None,
+            options.context.clone(),
+        )
+        .await?;
+    let counts = fold_per_question(&counts, None);
+    let choices = client
+        .read_group(
+            LINE_MODEL,
+            with(json!(["answer_type", "=", "suggestion"])),
+            vec!["suggested_answer_id".to_string()],
+            vec!["question_id".to_string(), "suggested_answer_id".to_string()],
+            None,
+            None,
+            None,
+            Some(false),
+            options.context.clone(),
+        )
+        .await?;
+    let mut choices = fold_choices(&choices);
+    let numeric = client
+        .read_group(
+            LINE_MODEL,
+            with(json!(["answer_type", "=", "numerical_box"])),
+            vec!["value_numerical_box:avg".to_string()],
+            vec!["question_id".to_string()],
+            None,
+            None,
+            None,
+            None,
+            options.context.clone(),
+        )
+        .await?;
+    let numeric = fold_per_question(&numeric, Some("value_numerical_box"));
+
+    let sample_size = options.sample_size.unwrap_or(5).max(0);
+    let mut results = Vec::new();
+    for row in questions.as_array().map(Vec::as_slice).unwrap_or(&[]) {
+        let Some(question_id) = row.get("id").and_then(|v| v.as_i64()) else {
+            continue;
+        };
+        let question_type = row
+            .get("question_type")
+            .and_then(|v| v.as_str())
+            .map(String::from);
+        let is_text = question_type
+            .as_deref()
+            .is_some_and(|ty| TEXT_TYPES.contains(&ty));
+        let samples = if is_text && sample_size > 0 {
+            let lines = client
+                .search_read(
+                    LINE_MODEL,
+                    with(json!(["question_id", "=", question_id])),
+                    Some(vec![
+                        "value_char_box".to_string(),
+                        "value_text_box".to_string(),
+                    ]),
+                    Some(sample_size),
+                    None,
+                    Some("id desc".to_string()),
+                    options.context.clone(),
+                )
+                .await?;
+            Some(
+                lines
+                    .as_array()
+                    .map(|rows| rows.iter().filter_map(text_answer).collect())
+                    .unwrap_or_default(),
+            )
+        } else {
+            None
+        };
+        results.push(QuestionResult {
+            question_id,
+            title: row
+                .get("title")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            question_type,
+            answer_count: counts.get(&question_id).map(|(n, _)| *n).unwrap_or(0),
+            choices: choices.remove(&question_id),
+            average: numeric.get(&question_id).and_then(|(_, avg)| *avg),
+            samples,
+        });
+    }
+
+    Ok(SurveyResults {
+        survey_id: options.survey_id,
+        title,
+        responses,
+        questions: results,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fold_choices_groups_and_sorts_by_count() {
+        let rows = json!([
+            {"question_id": [1, "Rating"], "suggested_answer_id": [10, "Good"], "__count": 2},
+            {"question_id": [1, "Rating"], "suggested_answer_id": [11, "Great"], "__count": 5},
+            {"question_id": [2, "Channel"], "suggested_answer_id": false, "__count": 1}
+        ]);
+        let choices = fold_choices(&rows);
+        assert_eq!(choices[&1][0].answer.as_deref(), Some("Great"));
+        assert_eq!(choices[&1][1].count, 2);
+        assert_eq!(choices[&2][0].answer_id, None);
+    }
+
+    #[test]
+    fn fold_per_question_reads_lazy_counts_and_averages() {
+        let rows = json!([
+            {"question_id": [3, "Age"], "question_id_count": 4, "value_numerical_box": 31.5},
+            {"question_id": false, "question_id_count": 9}
+        ]);
+        let folded = fold_per_question(&rows, Some("value_numerical_box"));
+        assert_eq!(folded.len(), 1);
+        assert_eq!(folded[&3], (4, Some(31.5)));
+    }
+
+    #[test]
+    fn text_answer_skips_empty_values() {
+        assert_eq!(
+            text_answer(&json!({"value_char_box": false, "value_text_box": " Loved it "})),
+            Some("Loved it".to_string())
+        );
+        assert_eq!(
+            text_answer(&json!({"value_char_box": "", "value_text_box": false})),
+            None
+        );
+    }
+}
@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::odoo::types::{OdooError, OdooResult};
+use crate::odoo::unified_client::OdooClient;
+use crate::packs::{many2one_id, many2one_name};
+
+const LINE_MODEL: &str = "account.analytic.line";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimesheetSummaryOptions {
+    /// Inclusive start date (YYYY-MM-DD).
+    pub date_from: String,
+    /// Inclusive end date (YYYY-MM-DD).
+    pub date_to: String,
+    /// Any of "project", "employee", "week". Defaults to ["project", "employee"].
+    pub group_by: Option<Vec<String>>,
+    pub project_ids: Option<Vec<i64>>,
+    pub employee_ids: Option<Vec<i64>>,
+    /// When set, employees logging fewer hours than `workdays * expectedHoursPerDay`
+    /// are reported as under-logged.
+    pub expected_hours_per_day: Option<f64>,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimesheetGroup {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub employee_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub employee: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub week: Option<String>,
+    pub hours: f64,
+    pub entries: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingTimesheet {
+    pub employee_id: i64,
+    pub employee: String,
+    pub logged_hours: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_hours: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimesheetSummary {
+    pub date_from: String,
+    pub date_to: String,
+    pub group_by: Vec<String>,
+    pub workdays: i64,
+    pub total_hours: f64,
+    pub groups: Vec<TimesheetGroup>,
+    pub missing: Vec<MissingTimesheet>,
+}
+
+fn parse_date(label: &str, value: &str) -> OdooResult<NaiveDate> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").map_err(|_| {
+        OdooError::InvalidResponse(format!("'{label}' must be a date in YYYY-MM-DD format"))
+    })
+}
+
+/// Monday-to-Friday days in the inclusive range.
+fn count_workdays(from: NaiveDate, to: NaiveDate) -> i64 {
+    from.iter_days()
+        .take_while(|d| *d <= to)
+        .filter(|d| !matches!(d.weekday(), Weekday::Sat | Weekday::Sun))
+        .count() as i64
+}
+
+fn groupby_field(key: &str) -> OdooResult<&'static str> {
+    match key {
+        "project" => Ok("project_id"),
+        "employee" => Ok("employee_id"),
+        "week" => Ok("date:week"),
+        other => Err(OdooError::InvalidResponse(format!(
+            "Unknown groupBy '{other}' (expected project, employee, or week)"
+        ))),
+    }
+}
+
+fn parse_groups(rows: &Value) -> Vec<TimesheetGroup> {
+    rows.as_array()
+        .map(|rows| {
+            rows.iter()
+                .map(|row| TimesheetGroup {
+                    project_id: row.get("project_id").and_then(many2one_id),
+                    project: row.get("project_id").and_then(many2one_name),
+                    employee_id: row.get("employee_id").and_then(many2one_id),
+                    employee: row.get("employee_id").and_then(many2one_name),
+                    week: row
+                        .get("date:week")
+                        .and_then(|v| v.as_str())
+                        .map(String::from),
+                    hours: row
+                        .get("unit_amount")
+                        .and_then(|v| v.as_f64())
+                        .unwrap_or(0.0),
+                    entries: row.get("__count").and_then(|v| v.as_i64()).unwrap_or(0),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Employees with no hours (or fewer than expected) in the range.
+fn find_missing(
+    employees: &[(i64, String)],
+    logged: &HashMap<i64, f64>,
+    expected_hours: Option<f64>,
+) -> Vec<MissingTimesheet> {
+    employees
+        .iter()
+        .filter_map(|(id, name)| {
+            let logged_hours = logged.get(id).copied().unwrap_or(0.0);
+            let short = match expected_hours {
+                Some(expected) => logged_hours + f64::EPSILON < expected,
+                None => logged_hours <= 0.0,
+            };
+            short.then(|| MissingTimesheet {
+                employee_id: *id,
+                employee: name.clone(),
+                logged_hours,
+                expected_hours,
+            })
+        })
+        .collect()
+}
+
+pub async fn timesheet_summary(
+    client: &OdooClient,
+    options: TimesheetSummaryOptions,
+) -> OdooResult<TimesheetSummary> {
+    let from = parse_date("dateFrom", &options.date_from)?;
+    let to = parse_date("dateTo", &options.date_to)?;
+    if to < from {
+        return Err(OdooError::InvalidResponse(
+            "'dateTo' must not be before 'dateFrom'".to_string(),
+        ));
+    }
+    let group_by = options
+        .group_by
+        .filter(|g| !g.is_empty())
+        .unwrap_or_else(|| vec!["project".to_string(), "employee".to_string()]);
+    let groupby_fields = group_by
+        .iter()
+        .map(|g| groupby_field(g).map(String::from))
+        .collect::<OdooResult<Vec<_>>>()?;
+
+    let mut domain = vec![
+        json!(["date", ">=", from.to_string()]),
+        json!(["date", "<=", to.to_string()]),
+        json!(["project_id", "!=", false]),
+    ];
+    if let Some(ids) = options.project_ids.as_ref().filter(|ids| !ids.is_empty()) {
+        domain.push(json!(["project_id", "in", ids]));
+    }
+    if let Some(ids) = options.employee_ids.as_ref().filter(|ids| !ids.is_empty()) {
+        domain.push(json!(["employee_id", "in", ids]));
+    }
+
+    let rows = client
+        .read_group(
+            LINE_MODEL,
+            Some(Value::Array(domain.clone())),
+            vec!["unit_amount:sum".to_string()],
+            groupby_fields,
+            None,
+            None,
+            None,
+            Some(false),
+            options.context.clone(),
+        )
+        .await?;
+    let groups = parse_groups(&rows);
+    let total_hours = groups.iter().map(|g| g.hours).sum();
+
+    // Per-employee totals over the whole range drive missing-timesheet detection.
+    let per_employee = client
+        .read_group(
+            LINE_MODEL,
+            Some(Value::Array(domain)),
+            vec!["unit_amount:sum".to_string()],
+            vec!["employee_id".to_string()],
+            None,
+            None,
+            None,
+            Some(false),
+            options.context.clone(),
+        )
+        .await?;
+    let logged: HashMap<i64, f64> = parse_groups(&per_employee)
+        .into_iter()
+        .filter_map(|g| Some((g.employee_id?, g.hours)))
+        .collect();
+
+    let mut employee_domain = vec![json!(["active", "=", true])];
+    if let Some(ids) = options.employee_ids.as_ref().filter(|ids| !ids.is_empty()) {
+        employee_domain.push(json!(["id", "in", ids]));
+    }
+    let employees = client
+        .search_read(
+            "hr.employee",
+            Some(Value::Array(employee_domain)),
+            Some(vec!["name".to_string()]),
+            None,
+            None,
+            Some("name asc".to_string()),
+            options.context,
+        )
+        .await?;
+    let employees: Vec<(i64, String)> = employees
+        .as_array()
+        .map(|rows| {
+            rows.iter()
+                .filter_map(|row| {
+                    Some((
+                        row.get("id")?.as_i64()?,
+                        row.get("name")?.as_str()?.to_string(),
+                    ))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let workdays = count_workdays(from, to);
+    let expected = options
+        .expected_hours_per_day
+        .map(|per_day| per_day * workdays as f64);
+
+    Ok(TimesheetSummary {
+        date_from: from.to_string(),
+        date_to: to.to_string(),
+        group_by,
+        workdays,
+        total_hours,
+        groups,
+        missing: find_missing(&employees, &logged, expected),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_workdays_skips_weekends() {
+        let from = NaiveDate::from_ymd_opt(2026, 6, 1).unwrap(); // Monday
+        let to = NaiveDate::from_ymd_opt(2026, 6, 14).unwrap(); // Sunday
+        assert_eq!(count_workdays(from, to), 10);
+        assert_eq!(count_workdays(to, to), 0);
+    }
+
+    #[test]
+    fn parse_groups_reads_read_group_rows() {
+        let rows = json!([
+            {"project_id": [3, "Website"], "employee_id": [9, "Ana"], "date:week": "W23 2026", "unit_amount": 12.5, "__count": 4}
+        ]);
+        let groups = parse_groups(&rows);
+        assert_eq!(groups[0].project.as_deref(), Some("Website"));
+        assert_eq!(groups[0].employee_id, Some(9));
+        assert_eq!(groups[0].week.as_deref(), Some("W23 2026"));
+        assert_eq!(groups[0].hours, 12.5);
+        assert_eq!(groups[0].entries, 4);
+    }
+
+    #[test]
+    fn find_missing_flags_absent_and_under_logged_employees() {
+        let employees = vec![(1, "Ana".to_string()), (2, "Ben".to_string())];
+        let logged = HashMap::from([(1, 40.0)]);
+
+        let missing = find_missing(&employees, &logged, None);
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].employee_id, 2);
+
+        let missing = find_missing(&employees, &logged, Some(80.0));
+        assert_eq!(missing.len(), 2);
+    }
+
+    #[test]
+    fn groupby_rejects_unknown_keys() {
+        assert_eq!(groupby_field("week").unwrap(), "date:week");
+        assert!(groupby_field("task").is_err());
+    }
+}
@@ -0,0 +1,479 @@
+//! User administration: create users, reset passwords, archive or restore them and manage
+//! their group membership. Every call needs `confirm: true`, since these change who can log
+//! in and what they can do.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+use crate::odoo::types::{OdooError, OdooResult};
+use crate::odoo::unified_client::OdooClient;
+use crate::packs::resolve_by_name;
+
+const USER_MODEL: &str = "res.users";
+const GROUP_MODEL: &str = "res.groups";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateUserOptions {
+    pub name: String,
+    pub login: String,
+    pub email: Option<String>,
+    /// Initial password; without one the user sets it through an invitation or reset.
+    pub password: Option<String>,
+    /// Groups added on top of the defaults for new users (see [`GroupRef`]).
+    #[serde(default)]
+    pub groups: Vec<String>,
+    pub company_id: Option<i64>,
+    #[serde(default)]
+    pub confirm: bool,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetPasswordOptions {
+    pub user_id: Option<i64>,
+    pub login: Option<String>,
+    /// Set this password directly; without one a reset email is sent (needs auth_signup).
+    pub password: Option<String>,
+    #[serde(default)]
+    pub confirm: bool,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetActiveOptions {
+    pub user_id: Option<i64>,
+    pub login: Option<String>,
+    pub active: bool,
+    #[serde(default)]
+    pub confirm: bool,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserGroupsOptions {
+    pub user_id: Option<i64>,
+    pub login: Option<String>,
+    #[serde(default)]
+    pub add: Vec<String>,
+    #[serde(default)]
+    pub remove: Vec<String>,
+    #[serde(default)]
+    pub confirm: bool,
+    pub context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserChange {
+    pub id: i64,
+    pub login: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active: Option<bool>,
+    /// `set` when a password was written, `email` when a reset link was sent.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password_reset: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub groups_added: Vec<i64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub groups_removed: Vec<i64>,
+}
+
+/// How a group is named: by XML id (`base.group_system`), by full name
+/// (`Sales / Administrator`, category then group) or by its bare name when that is unique.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GroupRef<'a> {
+    XmlId { module: &'a str, name: &'a str },
+    FullName { category: &'a str, name: &'a str },
+    Name(&'a str),
+}
+
+impl<'a> GroupRef<'a> {
+    pub fn parse(value: &'a str) -> Self {
+        let value = value.trim();
+        if let Some((category, name)) = value.rsplit_once(" / ") {
+            return Self::FullName {
+                category: category.trim(),
+                name: name.trim(),
+            };
+        }
+        match value.split_once('.') {
+            Some((module, name))
+                if !module.is_empty()
+                    && !name.is_empty()
+                    && !name.contains('.')
+                    && !value.contains(char::is_whitespace) =>
+            {
+                Self::XmlId { module, name }
+            }
+            _ => Self::Name(value),
+        }
+    }
+}
+
+fn require_confirm(confirm: bool, action: &str) -> OdooResult<()> {
+    if confirm {
+        Ok(())
+    } else {
+        Err(OdooError::InvalidResponse(format!(
+            "Refusing to {action} without 'confirm': true"
+        )))
+    }
+}
+
+/// `context` with `active_test` off, so archived users can be found.
+fn with_inactive(context: Option<&Value>) -> Value {
+    let mut context = context
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+    context.insert("active_test".into(), json!(false));
+    Value::Object(context)
+}
+
+async fn resolve_user(
+    client: &OdooClient,
+    user_id: Option<i64>,
+    login: Option<&str>,
+    context: Option<&Value>,
+) -> OdooResult<(i64, String)> {
+    let domain = match (user_id, login.map(str::trim)) {
+        (Some(id), _) => json!([["id", "=", id]]),
+        (None, Some(login)) if !login.is_empty() => json!([["login", "=", login]]),
+        _ => {
+            return Err(OdooError::InvalidResponse(
+                "Provide 'userId' or 'login'".to_string(),
+            ));
+        }
+    };
+    let rows = client
+        .search_read(
+            USER_MODEL,
+            Some(domain),
+            Some(vec!["login".to_string()]),
+            Some(1),
+            None,
+            None,
+            Some(with_inactive(context)),
+        )
+        .await?;
+    let row = rows
+        .as_array()
+        .and_then(|rows| rows.first())
+        .ok_or_else(|| {
+            OdooError::InvalidResponse(match (user_id, login) {
+                (Some(id), _) => format!("No user with id {id}"),
+                (None, login) => format!("No user with login '{}'", login.unwrap_or_default()),
+            })
+        })?;
+    let id = row.get("id").and_then(Value::as_i64).unwrap_or_default();
+    let login = row
+        .get("login")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    Ok((id, login))
+}
+
+async fn resolve_group(client: &OdooClient, group: &str) -> OdooResult<i64> {
+    match GroupRef::parse(group) {
+        GroupRef::XmlId { module, name } => {
+            let rows = client
+                .search_read(
+                    "ir.model.data",
+                    Some(json!([
+                        ["module", "=", module],
+                        ["name", "=", name],
+                        ["model", "=", GROUP_MODEL]
+                    ])),
+                    Some(vec!["res_id".to_string()]),
+                    Some(1),
+                    None,
+                    None,
+                    None,
+                )
+                .await?;
+            rows.as_array()
+                .and_then(|rows| rows.first())
+                .and_then(|row| row.get("res_id"))
+                .and_then(Value::as_i64)
+                .ok_or_else(|| {
+                    OdooError::InvalidResponse(format!("No group with XML id '{group}'"))
+                })
+        }
+        GroupRef::FullName { category, name } => {
+            resolve_by_name(
+                client,
+                GROUP_MODEL,
+                "name",
+                name,
+                vec![json!(["category_id.name", "=ilike", category])],
+            )
+            .await
+        }
+        GroupRef::Name(name) => resolve_by_name(client, GROUP_MODEL, "name", name, vec![]).await,
+    }
+}
+
+async fn resolve_groups(client: &OdooClient, groups: &[String]) -> OdooResult<Vec<i64>> {
+    let mut ids = Vec::with_capacity(groups.len());
+    for group in groups {
+        ids.push(resolve_group(client, group).await?);
+    }
+    Ok(ids)
+}
+
+/// The user's group field: `group_ids` from Odoo 19, `groups_id` before.
+async fn groups_field(client: &OdooClient) -> OdooResult<&'static str> {
+    let renamed = client
+        .search_count(
+            "ir.model.fields",
+            Some(json!([
+                ["model", "=", USER_MODEL],
+                ["name", "=", "group_ids"]
+            ])),
+            None,
+        )
+        .await?;
+    Ok(if renamed > 0 {
+        "group_ids"
+    } else {
+        "groups_id"
+    })
+}
+
+/// many2many commands linking (4) `add` and unlinking (3) `remove`.
+fn group_commands(add: &[i64], remove: &[i64]) -> Value {
+    let link = add.iter().map(|id| json!([4, id]));
+    let unlink = remove.iter().map(|id| json!([3, id]));
+    Value::Array(link.chain(unlink).collect())
+}
+
+pub async fn create_user(
+    client: &OdooClient,
+    options: CreateUserOptions,
+) -> OdooResult<UserChange> {
+    require_confirm(options.confirm, "create a user")?;
+    let login = options.login.trim();
+    if options.name.trim().is_empty() || login.is_empty() {
+        return Err(OdooError::InvalidResponse(
+            "'name' and 'login' must not be empty".to_string(),
+        ));
+    }
+    let groups = resolve_groups(client, &options.groups).await?;
+
+    let mut values = Map::new();
+    values.insert("name".into(), json!(options.name.trim()));
+    values.insert("login".into(), json!(login));
+    if let Some(email) = options.email {
+        values.insert("email".into(), json!(email));
+    }
+    if let Some(password) = &options.password {
+        values.insert("password".into(), json!(password));
+    }
+    if let Some(company_id) = options.company_id {
+        values.insert("company_id".into(), json!(company_id));
+        values.insert("company_ids".into(), json!([[4, company_id]]));
+    }
+    if !groups.is_empty() {
+        values.insert(
+            groups_field(client).await?.into(),
+            group_commands(&groups, &[]),
+        );
+    }
+
+    let id = client
+        .create(USER_MODEL, Value::Object(values), options.context)
+        .await?;
+    Ok(UserChange {
+        id,
+        login: login.to_string(),
+        active: Some(true),
+        password_reset: options.password.map(|_| "set".to_string()),
+        groups_added: groups,
+        ..Default::default()
+    })
+}
+
+pub async fn reset_password(
+    client: &OdooClient,
+    options: ResetPasswordOptions,
+) -> OdooResult<UserChange> {
+    require_confirm(options.confirm, "reset a password")?;
+    let (id, login) = resolve_user(
+        client,
+        options.user_id,
+        options.login.as_deref(),
+        options.context.as_ref(),
+    )
+    .await?;
+    let method = match options.password {
+        Some(password) if !password.is_empty() => {
+            client
+                .write(
+                    USER_MODEL,
+                    vec![id],
+                    json!({ "password": password }),
+                    options.context,
+                )
+                .await?;
+            "set"
+        }
+        Some(_) => {
+            return Err(OdooError::InvalidResponse(
+                "'password' must not be empty".to_string(),
+            ));
+        }
+        None => {
+            client
+                .call_named(
+                    USER_MODEL,
+                    "action_reset_password",
+                    Some(vec![id]),
+                    Map::new(),
+                    options.context,
+                )
+                .await?;
+            "email"
+        }
+    };
+    Ok(UserChange {
+        id,
+        login,
+        password_reset: Some(method.to_string()),
+        ..Default::default()
+    })
+}
+
+pub async fn set_active(client: &OdooClient, options: SetActiveOptions) -> OdooResult<UserChange> {
+    let action = if options.active {
+        "reactivate a user"
+    } else {
+        "archive a user"
+    };
+    require_confirm(options.confirm, action)?;
+    let (id, login) = resolve_user(
+        client,
+        options.user_id,
+        options.login.as_deref(),
+        options.context.as_ref(),
+    )
+    .await?;
+    client
+        .write(
+            USER_MODEL,
+            vec![id],
+            json!({ "active": options.active }),
+            Some(with_inactive(options.context.as_ref())),
+        )
+        .await?;
+    Ok(UserChange {
+        id,
+        login,
+        active: Some(options.active),
+        ..Default::default()
+    })
+}
+
+pub async fn update_groups(
+    client: &OdooClient,
+    options: UserGroupsOptions,
+) -> OdooResult<UserChange> {
+    require_confirm(options.confirm, "change group membership")?;
+    if options.add.is_empty() && options.remove.is_empty() {
+        return Err(OdooError::InvalidResponse(
+            "Provide groups to 'add' or 'remove'".to_string(),
+        ));
+    }
+    let (id, login) = resolve_user(
+        client,
+        options.user_id,
+        options.login.as_deref(),
+        options.context.as_ref(),
+    )
+    .await?;
+    let added = resolve_groups(client, &options.add).await?;
+    let removed = resolve_groups(client, &options.remove).await?;
+    if let Some(both) = added.iter().find(|id| removed.contains(id)) {
+        return Err(OdooError::InvalidResponse(format!(
+            "Group {both} is both added and removed"
+        )));
+    }
+    let mut values = Map::new();
+    values.insert(
+        groups_field(client).await?.into(),
+        group_commands(&added, &removed),
+    );
+    client
+        .write(USER_MODEL, vec![id], Value::Object(values), options.context)
+        .await?;
+    Ok(UserChange {
+        id,
+        login,
+        groups_added: added,
+        groups_removed: removed,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn group_refs_parse_xml_ids_full_names_and_names() {
+        assert_eq!(
+            GroupRef::parse("base.group_system"),
+            GroupRef::XmlId {
+                module: "base",
+                name: "group_system"
+            }
+        );
+        assert_eq!(
+            GroupRef::parse("Sales / Administrator"),
+            GroupRef::FullName {
+                category: "Sales",
+                name: "Administrator"
+            }
+        );
+        assert_eq!(
+            GroupRef::parse("Technical / Access to export feature"),
+            GroupRef::FullName {
+                category: "Technical",
+                name: "Access to export feature"
+            }
+        );
+        assert_eq!(
+            GroupRef::parse("Internal User"),
+            GroupRef::Name("Internal User")
+        );
+        assert_eq!(GroupRef::parse("v2.0 beta"), GroupRef::Name("v2.0 beta"));
+    }
+
+    #[test]
+    fn group_commands_link_then_unlink() {
+        assert_eq!(
+            group_commands(&[7, 8], &[3]),
+            json!([[4, 7], [4, 8], [3, 3]])
+        );
+    }
+
+    #[test]
+    fn unconfirmed_changes_are_refused() {
+        let err = require_confirm(false, "archive a user").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Refusing to archive a user without 'confirm': true")
+        );
+        assert!(require_confirm(true, "archive a user").is_ok());
+    }
+
+    #[test]
+    fn inactive_users_are_visible_to_lookups() {
+        let context = with_inactive(Some(&json!({"lang": "en_US"})));
+        assert_eq!(context, json!({"lang": "en_US", "active_test": false}));
+    }
+}
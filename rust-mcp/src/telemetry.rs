@@ -0,0 +1,534 @@
+//! Request ids and optional OTLP trace export.
+//!
+//! Every MCP request runs in an `mcp.request` span with a request id (the caller's
+//! `X-Request-Id` over HTTP, otherwise a fresh one). Tool calls add a `call_tool` child span
+//! and every HTTP call to Odoo an `odoo.http` grandchild; the request id is forwarded to Odoo
+//! in the `X-Request-Id` header so server logs on both sides line up. Odoo requests also carry
+//! `X-MCP-Session` (a digest of the session id) and `X-MCP-Tool` so proxies and Odoo logs can
+//! attribute traffic to agent sessions, and a `User-Agent` from `ODOO_USER_AGENT`.
+//!
+//! Export is off unless `OTEL_EXPORTER_OTLP_ENDPOINT` (or `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT`)
+//! is set. Spans are batched and posted as OTLP/HTTP JSON, which every OpenTelemetry collector
+//! accepts on port 4318; gRPC is not supported.
+
+use std::future::Future;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::RequestBuilder;
+use serde_json::{Value, json};
+use sha2::Digest;
+use tokio::sync::mpsc;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Span, Subscriber, debug, info_span, warn};
+use tracing_subscriber::Layer;
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+pub const SESSION_HEADER: &str = "X-MCP-Session";
+pub const TOOL_HEADER: &str = "X-MCP-Tool";
+
+/// Spans buffered for export; further spans are dropped while the collector is unreachable.
+const QUEUE_CAPACITY: usize = 4096;
+const MAX_BATCH: usize = 512;
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+/// Request ids longer than this (or with non-visible characters) are replaced.
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+    static SESSION_TAG: String;
+    static TOOL_NAME: String;
+}
+
+/// `User-Agent` of requests to Odoo: `ODOO_USER_AGENT`, or `odoo-rust-mcp/<version>`.
+pub fn user_agent() -> String {
+    std::env::var("ODOO_USER_AGENT")
+        .ok()
+        .map(|ua| ua.trim().to_string())
+        .filter(|ua| !ua.is_empty() && reqwest::header::HeaderValue::from_str(ua).is_ok())
+        .unwrap_or_else(|| concat!("odoo-rust-mcp/", env!("CARGO_PKG_VERSION")).to_string())
+}
+
+/// Tag sent for a session: the first 16 hex digits of the SHA-256 of its id, so Odoo logs can be
+/// matched against the audit trail without exposing the id itself.
+pub fn session_tag(session: &str) -> String {
+    let digest = format!("{:x}", sha2::Sha256::digest(session.as_bytes()));
+    digest[..16].to_string()
+}
+
+/// Run `fut` on behalf of `session` ("stdio" or an HTTP session id).
+pub async fn with_session<F: Future>(session: &str, fut: F) -> F::Output {
+    SESSION_TAG.scope(session_tag(session), fut).await
+}
+
+/// Run `fut` on behalf of the tool called `tool`.
+pub async fn with_tool<F: Future>(tool: &str, fut: F) -> F::Output {
+    TOOL_NAME.scope(tool.to_string(), fut).await
+}
+
+pub fn new_request_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// Request id of the MCP request the current task is serving.
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// Run `fut` with `request_id` as the current request id; `None` leaves it unchanged.
+pub async fn scope<F: Future>(request_id: Option<String>, fut: F) -> F::Output {
+    match request_id {
+        Some(id) => REQUEST_ID.scope(id, fut).await,
+        None => fut.await,
+    }
+}
+
+/// A usable inbound `X-Request-Id`, if the caller sent one.
+pub fn request_id_from_headers(headers: &axum::http::HeaderMap) -> Option<String> {
+    let id = headers.get(REQUEST_ID_HEADER)?.to_str().ok()?.trim();
+    let usable = !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LEN
+        && id.chars().all(|c| c.is_ascii_graphic());
+    usable.then(|| id.to_string())
+}
+
+/// Add the current request id, session tag and tool name to an outgoing Odoo request.
+pub fn propagate(mut builder: RequestBuilder) -> RequestBuilder {
+    if let Some(id) = current_request_id() {
+        builder = builder.header(REQUEST_ID_HEADER, id);
+    }
+    if let Ok(tag) = SESSION_TAG.try_with(Clone::clone) {
+        builder = builder.header(SESSION_HEADER, tag);
+    }
+    if let Ok(tool) = TOOL_NAME.try_with(Clone::clone) {
+        builder = builder.header(TOOL_HEADER, tool);
+    }
+    builder
+}
+
+/// Span for one HTTP call to Odoo; retries each get their own.
+pub fn odoo_http_span(method: &'static str, url: &url::Url, attempt: u32) -> Span {
+    info_span!(
+        "odoo.http",
+        http.request.method = method,
+        url.full = %url,
+        attempt,
+        http.response.status_code = tracing::field::Empty,
+        error = tracing::field::Empty
+    )
+}
+
+/// Record the outcome of a call started under [`odoo_http_span`].
+pub fn record_response(span: &Span, response: &reqwest::Result<reqwest::Response>) {
+    match response {
+        Ok(r) => {
+            span.record("http.response.status_code", r.status().as_u16());
+            if !r.status().is_success() {
+                span.record("error", r.status().to_string());
+            }
+        }
+        Err(e) => {
+            span.record("error", e.to_string());
+        }
+    }
+}
+
+/// Where and how to export spans.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OtlpConfig {
+    /// Full traces URL, e.g. `http://localhost:4318/v1/traces`.
+    pub endpoint: String,
+    pub service_name: String,
+    pub headers: Vec<(String, String)>,
+}
+
+impl OtlpConfig {
+    /// Standard OpenTelemetry variables: `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT` (used as is) or
+    /// `OTEL_EXPORTER_OTLP_ENDPOINT` (`/v1/traces` appended), `OTEL_SERVICE_NAME` and
+    /// `OTEL_EXPORTER_OTLP_HEADERS` (`key=value,key=value`).
+    pub fn from_env() -> Option<Self> {
+        let var = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let endpoint = var("OTEL_EXPORTER_OTLP_TRACES_ENDPOINT").or_else(|| {
+            var("OTEL_EXPORTER_OTLP_ENDPOINT")
+                .map(|base| format!("{}/v1/traces", base.trim_end_matches('/')))
+        })?;
+        Some(Self {
+            endpoint,
+            service_name: var("OTEL_SERVICE_NAME").unwrap_or_else(|| "odoo-rust-mcp".to_string()),
+            headers: var("OTEL_EXPORTER_OTLP_HEADERS")
+                .map(|raw| parse_headers(&raw))
+                .unwrap_or_default(),
+        })
+    }
+}
+
+fn parse_headers(raw: &str) -> Vec<(String, String)> {
+    raw.split(',')
+        .filter_map(|pair| {
+            let (key, value) = pair.split_once('=')?;
+            let key = key.trim();
+            (!key.is_empty()).then(|| (key.to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+/// A finished span, ready for export.
+#[derive(Debug, Clone)]
+struct SpanRecord {
+    trace_id: [u8; 16],
+    span_id: [u8; 8],
+    parent_id: Option<[u8; 8]>,
+    name: &'static str,
+    start: SystemTime,
+    end: SystemTime,
+    attributes: Vec<(&'static str, Value)>,
+}
+
+impl SpanRecord {
+    fn kind(&self) -> u8 {
+        // SPAN_KIND_INTERNAL = 1, SERVER = 2, CLIENT = 3.
+        match self.name {
+            "mcp.request" => 2,
+            "odoo.http" => 3,
+            _ => 1,
+        }
+    }
+
+    fn to_otlp(&self) -> Value {
+        let failed = self.attributes.iter().any(|(key, _)| *key == "error");
+        let mut span = json!({
+            "traceId": hex::encode(self.trace_id),
+            "spanId": hex::encode(self.span_id),
+            "name": self.name,
+            "kind": self.kind(),
+            "startTimeUnixNano": unix_nanos(self.start).to_string(),
+            "endTimeUnixNano": unix_nanos(self.end).to_string(),
+            "attributes": self
+                .attributes
+                .iter()
+                .map(|(key, value)| otlp_attribute(key, value))
+                .collect::<Vec<_>>(),
+            // STATUS_CODE_UNSET = 0, ERROR = 2.
+            "status": {"code": if failed { 2 } else { 0 }},
+        });
+        if let Some(parent) = self.parent_id {
+            span["parentSpanId"] = json!(hex::encode(parent));
+        }
+        span
+    }
+}
+
+fn unix_nanos(time: SystemTime) -> u128 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+}
+
+fn otlp_attribute(key: &str, value: &Value) -> Value {
+    let value = match value {
+        Value::Bool(b) => json!({"boolValue": b}),
+        // OTLP/JSON encodes 64-bit integers as strings.
+        Value::Number(n) if n.is_i64() || n.is_u64() => json!({"intValue": n.to_string()}),
+        Value::Number(n) => json!({"doubleValue": n.as_f64()}),
+        Value::String(s) => json!({"stringValue": s}),
+        other => json!({"stringValue": other.to_string()}),
+    };
+    json!({"key": key, "value": value})
+}
+
+/// OTLP `ExportTraceServiceRequest` body for one batch.
+fn export_body(service_name: &str, spans: &[SpanRecord]) -> Value {
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [otlp_attribute("service.name", &json!(service_name))]
+            },
+            "scopeSpans": [{
+                "scope": {"name": "odoo-rust-mcp", "version": env!("CARGO_PKG_VERSION")},
+                "spans": spans.iter().map(SpanRecord::to_otlp).collect::<Vec<_>>(),
+            }]
+        }]
+    })
+}
+
+/// In-progress span state kept in the span's extensions.
+struct SpanData {
+    trace_id: [u8; 16],
+    span_id: [u8; 8],
+    parent_id: Option<[u8; 8]>,
+    start: SystemTime,
+    attributes: Vec<(&'static str, Value)>,
+}
+
+struct FieldVisitor<'a>(&'a mut Vec<(&'static str, Value)>);
+
+impl FieldVisitor<'_> {
+    fn set(&mut self, field: &Field, value: Value) {
+        match self.0.iter_mut().find(|(key, _)| *key == field.name()) {
+            Some(slot) => slot.1 = value,
+            None => self.0.push((field.name(), value)),
+        }
+    }
+}
+
+impl Visit for FieldVisitor<'_> {
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.set(field, json!(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.set(field, json!(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.set(field, json!(value));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.set(field, json!(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.set(field, json!(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.set(field, json!(format!("{value:?}")));
+    }
+}
+
+/// The OTLP layer when export is configured, limited to this crate's spans.
+///
+/// Must be called from within a Tokio runtime.
+pub fn layer_from_env<S>() -> Option<impl Layer<S>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let config = OtlpConfig::from_env()?;
+    Some(
+        OtlpLayer::spawn(config)
+            .with_filter(filter_fn(|meta| meta.target().starts_with("rust_mcp"))),
+    )
+}
+
+/// Tracing layer that turns this crate's spans into OTLP spans.
+pub struct OtlpLayer {
+    spans: mpsc::Sender<SpanRecord>,
+}
+
+impl OtlpLayer {
+    /// Start the exporter task. Must be called from within a Tokio runtime.
+    pub fn spawn(config: OtlpConfig) -> Self {
+        let (tx, rx) = mpsc::channel(QUEUE_CAPACITY);
+        tokio::spawn(export_loop(config, rx));
+        Self { spans: tx }
+    }
+}
+
+impl<S> Layer<S> for OtlpLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let parent = span.parent().and_then(|parent| {
+            let extensions = parent.extensions();
+            extensions
+                .get::<SpanData>()
+                .map(|data| (data.trace_id, data.span_id))
+        });
+        let mut attributes = Vec::new();
+        attrs.record(&mut FieldVisitor(&mut attributes));
+        let (trace_id, parent_id) = match parent {
+            Some((trace_id, parent_id)) => (trace_id, Some(parent_id)),
+            None => (rand::random(), None),
+        };
+        span.extensions_mut().insert(SpanData {
+            trace_id,
+            span_id: rand::random(),
+            parent_id,
+            start: SystemTime::now(),
+            attributes,
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id)
+            && let Some(data) = span.extensions_mut().get_mut::<SpanData>()
+        {
+            values.record(&mut FieldVisitor(&mut data.attributes));
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(data) = span.extensions_mut().remove::<SpanData>() else {
+            return;
+        };
+        let record = SpanRecord {
+            trace_id: data.trace_id,
+            span_id: data.span_id,
+            parent_id: data.parent_id,
+            name: span.name(),
+            start: data.start,
+            end: SystemTime::now(),
+            attributes: data.attributes,
+        };
+        // A full queue means the collector is behind or down; tracing must not block requests.
+        let _ = self.spans.try_send(record);
+    }
+}
+
+async fn export_loop(config: OtlpConfig, mut rx: mpsc::Receiver<SpanRecord>) {
+    let http = reqwest::Client::new();
+    let mut batch = Vec::with_capacity(MAX_BATCH);
+    let mut ticker = tokio::time::interval(FLUSH_INTERVAL);
+    let mut failing = false;
+    loop {
+        let closed = tokio::select! {
+            span = rx.recv() => match span {
+                Some(span) => {
+                    batch.push(span);
+                    if batch.len() < MAX_BATCH {
+                        continue;
+                    }
+                    false
+                }
+                None => true,
+            },
+            _ = ticker.tick() => false,
+        };
+        if !batch.is_empty() {
+            let mut request = http
+                .post(&config.endpoint)
+                .json(&export_body(&config.service_name, &batch));
+            for (key, value) in &config.headers {
+                request = request.header(key, value);
+            }
+            let outcome = request.send().await.and_then(|r| r.error_for_status());
+            match outcome {
+                Ok(_) => failing = false,
+                // Warn once per outage rather than once per batch.
+                Err(e) if !failing => {
+                    failing = true;
+                    warn!("OTLP export to {} failed: {}", config.endpoint, e);
+                }
+                Err(e) => debug!("OTLP export still failing: {}", e),
+            }
+            batch.clear();
+        }
+        if closed {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn headers_are_parsed_leniently() {
+        assert_eq!(
+            parse_headers("authorization=Bearer abc, x-team = ops,broken,=x"),
+            vec![
+                ("authorization".to_string(), "Bearer abc".to_string()),
+                ("x-team".to_string(), "ops".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn inbound_request_ids_are_validated() {
+        let mut headers = axum::http::HeaderMap::new();
+        assert_eq!(request_id_from_headers(&headers), None);
+        headers.insert(REQUEST_ID_HEADER, "abc-123".parse().unwrap());
+        assert_eq!(
+            request_id_from_headers(&headers).as_deref(),
+            Some("abc-123")
+        );
+        headers.insert(REQUEST_ID_HEADER, "x".repeat(200).parse().unwrap());
+        assert_eq!(request_id_from_headers(&headers), None);
+    }
+
+    #[tokio::test]
+    async fn request_id_is_task_scoped() {
+        assert_eq!(current_request_id(), None);
+        let seen = scope(Some("r1".to_string()), async { current_request_id() }).await;
+        assert_eq!(seen.as_deref(), Some("r1"));
+        assert_eq!(scope(None, async { current_request_id() }).await, None);
+    }
+
+    #[tokio::test]
+    async fn odoo_requests_carry_session_and_tool_tags() {
+        let tag = session_tag("3f2a-session");
+        assert_eq!(tag.len(), 16);
+        assert_ne!(tag, session_tag("other-session"));
+
+        let request = with_session(
+            "3f2a-session",
+            with_tool("odoo_search", async {
+                propagate(reqwest::Client::new().get("http://odoo.test"))
+                    .build()
+                    .unwrap()
+            }),
+        )
+        .await;
+        assert_eq!(request.headers()[SESSION_HEADER], tag.as_str());
+        assert_eq!(request.headers()[TOOL_HEADER], "odoo_search");
+
+        let untagged = propagate(reqwest::Client::new().get("http://odoo.test"))
+            .build()
+            .unwrap();
+        assert!(untagged.headers().get(SESSION_HEADER).is_none());
+    }
+
+    #[test]
+    fn child_spans_share_the_trace_and_export_as_otlp() {
+        let (tx, mut rx) = mpsc::channel(16);
+        let subscriber = tracing_subscriber::registry().with(OtlpLayer { spans: tx });
+        tracing::subscriber::with_default(subscriber, || {
+            let request = tracing::info_span!("mcp.request", rpc.method = "tools/call");
+            let _request = request.enter();
+            let http = tracing::info_span!(
+                "odoo.http",
+                http.response.status_code = tracing::field::Empty
+            );
+            http.record("http.response.status_code", 200);
+        });
+
+        let child = rx.try_recv().unwrap();
+        let root = rx.try_recv().unwrap();
+        assert_eq!(child.name, "odoo.http");
+        assert_eq!(child.trace_id, root.trace_id);
+        assert_eq!(child.parent_id, Some(root.span_id));
+        assert_eq!(root.parent_id, None);
+
+        let body = export_body("svc", &[child, root]);
+        let spans = &body["resourceSpans"][0]["scopeSpans"][0]["spans"];
+        assert_eq!(spans[0]["kind"], 3);
+        assert_eq!(spans[1]["kind"], 2);
+        assert_eq!(spans[0]["parentSpanId"], spans[1]["spanId"]);
+        assert_eq!(
+            spans[0]["attributes"][0],
+            json!({"key": "http.response.status_code", "value": {"intValue": "200"}})
+        );
+        assert_eq!(
+            spans[1]["attributes"][0]["value"]["stringValue"],
+            "tools/call"
+        );
+    }
+}
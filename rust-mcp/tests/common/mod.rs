@@ -303,6 +303,23 @@ pub fn minimal_tools_json() -> &'static str {
                 "op": {
                     "type": "search"
                 }
+            },
+            {
+                "name": "odoo_connect",
+                "description": "Connect this MCP session to an Odoo instance",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "name": {"type": "string"},
+                        "url": {"type": "string"},
+                        "apiKey": {"type": "string"}
+                    },
+                    "required": ["name", "url"]
+                },
+                "op": {
+                    "type": "connect",
+                    "map": {"name": "/name", "url": "/url", "apiKey": "/apiKey"}
+                }
             }
         ]
     }"#
@@ -0,0 +1,83 @@
+//! Integration tests for HTTPS and mutual TLS on the HTTP transport.
+
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+
+use axum::Router;
+use axum::extract::ConnectInfo;
+use axum::routing::get;
+use rust_mcp::mcp::tls::{TlsSettings, serve_tls};
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/tls")
+        .join(name)
+}
+
+/// Start a TLS server answering `/` with the client address; returns its port.
+async fn start(settings: TlsSettings) -> u16 {
+    let app = Router::new().route(
+        "/",
+        get(|ConnectInfo(addr): ConnectInfo<SocketAddr>| async move { addr.ip().to_string() }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move { serve_tls(listener, app, &settings).await });
+    port
+}
+
+fn client(identity: bool) -> reqwest::Client {
+    let ca = std::fs::read(fixture("ca.pem")).unwrap();
+    let mut builder = reqwest::Client::builder()
+        .use_rustls_tls()
+        .add_root_certificate(reqwest::Certificate::from_pem(&ca).unwrap());
+    if identity {
+        let mut pem = std::fs::read(fixture("client.pem")).unwrap();
+        pem.extend(std::fs::read(fixture("client.key")).unwrap());
+        builder = builder.identity(reqwest::Identity::from_pem(&pem).unwrap());
+    }
+    builder.build().unwrap()
+}
+
+#[tokio::test]
+async fn test_https_serves_requests_with_connect_info() {
+    let port = start(TlsSettings {
+        cert_path: fixture("server.pem"),
+        key_path: fixture("server.key"),
+        client_ca_path: None,
+    })
+    .await;
+
+    let body = client(false)
+        .get(format!("https://localhost:{port}/"))
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert_eq!(body, "127.0.0.1");
+
+    // Plain HTTP on the TLS port does not get through.
+    assert!(
+        reqwest::get(format!("http://localhost:{port}/"))
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_mtls_requires_client_certificate() {
+    let port = start(TlsSettings {
+        cert_path: fixture("server.pem"),
+        key_path: fixture("server.key"),
+        client_ca_path: Some(fixture("ca.pem")),
+    })
+    .await;
+    let url = format!("https://localhost:{port}/");
+
+    assert!(client(false).get(&url).send().await.is_err());
+
+    let response = client(true).get(&url).send().await.unwrap();
+    assert!(response.status().is_success());
+}
@@ -6,12 +6,21 @@ use axum::http::{HeaderName, HeaderValue};
 use axum_test::TestServer;
 use common::{minimal_prompts_json, minimal_server_json, minimal_tools_json};
 use rust_mcp::mcp::McpOdooHandler;
-use rust_mcp::mcp::http::{AuthConfig, create_app};
+use rust_mcp::mcp::audit::SqliteAuditLog;
+use rust_mcp::mcp::auth_tokens::{ApiToken, TokenScope};
+use rust_mcp::mcp::http::{AuthConfig, SecurityConfig, create_app_with_security};
+use rust_mcp::mcp::rate_limit::{Quota, RateLimitConfig};
 use rust_mcp::mcp::registry::Registry;
+use rust_mcp::mcp::relay::{self, RelayConfig};
+use rust_mcp::mcp::request_signing::{RequestSigner, RequestVerifier, SignedRequest};
+use rust_mcp::mcp::tenants::Tenant;
 use rust_mcp::mcp::tools::OdooClientPool;
 use serde_json::json;
+use std::collections::BTreeSet;
 use std::sync::Arc;
+use std::time::Duration;
 use tempfile::TempDir;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::sync::Mutex;
 
 const MCP_SESSION_HEADER: &str = "mcp-session-id";
@@ -20,6 +29,51 @@ static TEST_ENV_LOCK: Mutex<()> = Mutex::const_new(());
 
 /// Setup test environment and create a test server.
 async fn setup_test_server(with_auth: bool) -> (TestServer, TempDir) {
+    let auth = if with_auth {
+        AuthConfig::new(true, Some("test_token".to_string()))
+    } else {
+        AuthConfig::disabled()
+    };
+    setup_test_server_with_auth(auth).await
+}
+
+async fn setup_test_server_with_auth(auth: AuthConfig) -> (TestServer, TempDir) {
+    setup_test_server_with_config(auth, SecurityConfig::default()).await
+}
+
+async fn setup_test_server_with_config(
+    auth: AuthConfig,
+    security: SecurityConfig,
+) -> (TestServer, TempDir) {
+    setup_test_server_with(auth, security, |handler| handler).await
+}
+
+async fn setup_test_server_with(
+    auth: AuthConfig,
+    security: SecurityConfig,
+    configure: impl FnOnce(McpOdooHandler) -> McpOdooHandler,
+) -> (TestServer, TempDir) {
+    let (app, temp_dir) = setup_test_app(auth, security, configure).await;
+    let server = TestServer::new(app.into_make_service()).unwrap();
+
+    (server, temp_dir)
+}
+
+/// Serve the app on a local port, for tests that read long-lived SSE streams.
+async fn setup_listening_server(auth: AuthConfig, security: SecurityConfig) -> (String, TempDir) {
+    let (app, temp_dir) = setup_test_app(auth, security, |handler| handler).await;
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    (base, temp_dir)
+}
+
+async fn setup_test_app(
+    auth: AuthConfig,
+    security: SecurityConfig,
+    configure: impl FnOnce(McpOdooHandler) -> McpOdooHandler,
+) -> (axum::Router, TempDir) {
     let _lock = TEST_ENV_LOCK.lock().await;
     let temp_dir = TempDir::new().unwrap();
 
@@ -62,18 +116,11 @@ async fn setup_test_server(with_auth: bool) -> (TestServer, TempDir) {
     let pool = OdooClientPool::from_env().unwrap();
     let registry = Arc::new(Registry::from_env());
     registry.initial_load().await.unwrap();
-    let handler = Arc::new(McpOdooHandler::new(pool, registry));
-
-    let auth = if with_auth {
-        AuthConfig::new(true, Some("test_token".to_string()))
-    } else {
-        AuthConfig::disabled()
-    };
+    let handler = Arc::new(configure(McpOdooHandler::new(pool, registry)));
 
-    let app = create_app(handler, auth);
-    let server = TestServer::new(app.into_make_service()).unwrap();
+    let app = create_app_with_security(handler, auth, security);
 
-    (server, temp_dir)
+    (app, temp_dir)
 }
 
 // ============================================================================
@@ -290,6 +337,887 @@ async fn test_mcp_post_authorized_with_correct_token() {
     response.assert_status_ok();
 }
 
+#[tokio::test]
+async fn test_named_tokens_authenticate_until_expiry() {
+    let token = |name: &str, secret: &str, expires_at| ApiToken {
+        name: name.to_string(),
+        token: secret.to_string(),
+        expires_at,
+        scopes: BTreeSet::from([TokenScope::Read]),
+        tenant: None,
+    };
+    let expired = chrono::Utc::now() - chrono::Duration::minutes(1);
+    let (server, _temp) = setup_test_server_with_auth(AuthConfig::with_tokens(vec![
+        token("bi", "bi_secret", None),
+        token("old", "old_secret", Some(expired)),
+    ]))
+    .await;
+    let initialize = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "initialize",
+        "params": {}
+    });
+
+    server
+        .post("/mcp")
+        .add_header(
+            HeaderName::from_static(AUTH_HEADER),
+            HeaderValue::from_static("Bearer bi_secret"),
+        )
+        .json(&initialize)
+        .await
+        .assert_status_ok();
+
+    let response = server
+        .post("/mcp")
+        .add_header(
+            HeaderName::from_static(AUTH_HEADER),
+            HeaderValue::from_static("Bearer old_secret"),
+        )
+        .json(&initialize)
+        .await;
+    response.assert_status_unauthorized();
+    assert_eq!(
+        response.json::<serde_json::Value>()["error_description"],
+        "The access token has expired"
+    );
+}
+
+#[tokio::test]
+async fn test_rate_limit_per_token_returns_429() {
+    let security = SecurityConfig {
+        rate_limit: RateLimitConfig {
+            per_token: Some(Quota {
+                per_minute: 1,
+                burst: 2,
+            }),
+            per_ip: None,
+        },
+        ..Default::default()
+    };
+    let (server, _temp) = setup_test_server_with_config(
+        AuthConfig::new(true, Some("test_token".to_string())),
+        security,
+    )
+    .await;
+    let initialize = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "initialize",
+        "params": {}
+    });
+    let post = || {
+        server
+            .post("/mcp")
+            .add_header(
+                HeaderName::from_static(AUTH_HEADER),
+                HeaderValue::from_static("Bearer test_token"),
+            )
+            .json(&initialize)
+    };
+
+    post().await.assert_status_ok();
+    post().await.assert_status_ok();
+    let response = post().await;
+    response.assert_status(axum::http::StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(response.header("retry-after"), "60");
+
+    // Health checks are not rate limited
+    server.get("/health").await.assert_status_ok();
+}
+
+#[tokio::test]
+async fn test_rate_limit_charges_each_batch_element() {
+    let security = SecurityConfig {
+        rate_limit: RateLimitConfig {
+            per_token: Some(Quota {
+                per_minute: 1,
+                burst: 2,
+            }),
+            per_ip: None,
+        },
+        ..Default::default()
+    };
+    let (server, _temp) = setup_test_server_with_config(
+        AuthConfig::new(true, Some("test_token".to_string())),
+        security,
+    )
+    .await;
+    let list = |id: u64| json!({"jsonrpc": "2.0", "id": id, "method": "tools/list"});
+    let post = |body: serde_json::Value| {
+        server
+            .post("/mcp")
+            .add_header(
+                HeaderName::from_static(AUTH_HEADER),
+                HeaderValue::from_static("Bearer test_token"),
+            )
+            .json(&body)
+    };
+
+    // A batch larger than the burst is refused outright
+    let response = post(json!([list(1), list(2), list(3)])).await;
+    response.assert_status(axum::http::StatusCode::TOO_MANY_REQUESTS);
+    assert!(response.text().contains("burst of 2"));
+
+    // A batch of two uses up the whole bucket
+    post(json!([list(1), list(2)])).await.assert_status_ok();
+    post(list(3))
+        .await
+        .assert_status(axum::http::StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn test_max_body_bytes_rejects_large_requests() {
+    let security = SecurityConfig {
+        max_body_bytes: Some(256),
+        ..Default::default()
+    };
+    let (server, _temp) = setup_test_server_with_config(AuthConfig::disabled(), security).await;
+
+    server
+        .post("/mcp")
+        .json(&json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}))
+        .await
+        .assert_status_ok();
+
+    let response = server
+        .post("/mcp")
+        .json(&json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "initialize",
+            "params": {"padding": "x".repeat(1024)}
+        }))
+        .await;
+    response.assert_status(axum::http::StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[tokio::test]
+async fn test_audit_log_records_tool_calls() {
+    let token = |name: &str, secret: &str, scope| ApiToken {
+        name: name.to_string(),
+        token: secret.to_string(),
+        expires_at: None,
+        scopes: BTreeSet::from([scope]),
+        tenant: None,
+    };
+    let auth = AuthConfig::with_tokens(vec![
+        token("agent", "agent_secret", TokenScope::Read),
+        token("auditor", "auditor_secret", TokenScope::Admin),
+    ]);
+    let log = Arc::new(SqliteAuditLog::open_in_memory().unwrap());
+    let (server, _temp) = setup_test_server_with(auth, SecurityConfig::default(), |handler| {
+        handler.with_audit_log(log)
+    })
+    .await;
+    let bearer = |secret: &str| HeaderValue::from_str(&format!("Bearer {secret}")).unwrap();
+
+    server
+        .post("/mcp")
+        .add_header(HeaderName::from_static(AUTH_HEADER), bearer("agent_secret"))
+        .json(&json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": {
+                "name": "no_such_tool",
+                "arguments": {"instance": "default", "model": "res.partner", "ids": [5]}
+            }
+        }))
+        .await
+        .assert_status_ok();
+
+    server
+        .get("/audit")
+        .add_header(HeaderName::from_static(AUTH_HEADER), bearer("agent_secret"))
+        .await
+        .assert_status(axum::http::StatusCode::FORBIDDEN);
+
+    let response = server
+        .get("/audit?tool=no_such_tool")
+        .add_header(
+            HeaderName::from_static(AUTH_HEADER),
+            bearer("auditor_secret"),
+        )
+        .await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    let entries = body["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["token"], "agent");
+    assert_eq!(entries[0]["model"], "res.partner");
+    assert_eq!(entries[0]["ids"], json!([5]));
+    assert_eq!(entries[0]["outcome"], "unknown_tool");
+}
+
+#[tokio::test]
+async fn test_inspector_returns_recent_redacted_requests() {
+    let (server, _temp) = setup_test_server(false).await;
+
+    let init_resp = server
+        .post("/mcp")
+        .json(&json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}))
+        .await;
+    let session_id = init_resp
+        .headers()
+        .get("mcp-session-id")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+    let session_header = || {
+        (
+            HeaderName::from_static(MCP_SESSION_HEADER),
+            HeaderValue::from_str(&session_id).unwrap(),
+        )
+    };
+    let (name, value) = session_header();
+    server
+        .post("/mcp")
+        .add_header(name, value)
+        .json(&json!({"jsonrpc": "2.0", "method": "notifications/initialized"}))
+        .await;
+    let (name, value) = session_header();
+    server
+        .post("/mcp")
+        .add_header(name, value)
+        .json(&json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": {
+                "name": "no_such_tool",
+                "arguments": {"instance": "default", "password": "hunter2"}
+            }
+        }))
+        .await
+        .assert_status_ok();
+
+    let response = server
+        .get(&format!("/inspector?session={session_id}"))
+        .await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    let requests = body["requests"].as_array().unwrap();
+    assert_eq!(requests[0]["method"], "tools/call");
+    assert_eq!(requests[0]["id"], 2);
+    assert_eq!(requests[0]["params"]["arguments"]["password"], "[redacted]");
+    assert_eq!(requests[0]["result"]["isError"], true);
+
+    let response = server.get("/inspector?session=unknown").await;
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["requests"], json!([]));
+}
+
+#[tokio::test]
+async fn test_deprecated_features_are_flagged_and_summarized() {
+    let (server, _temp) = setup_test_server(false).await;
+
+    let response = server
+        .post("/mcp")
+        .json(&json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {"protocolVersion": "2024-11-05"}
+        }))
+        .await;
+    let body: serde_json::Value = response.json();
+    assert_eq!(
+        body["result"]["_meta"]["deprecations"][0]["code"],
+        "legacy-protocol-version"
+    );
+    let response = server
+        .post("/mcp")
+        .json(&json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}))
+        .await;
+    let body: serde_json::Value = response.json();
+    assert!(body["result"].get("_meta").is_none());
+
+    let response = server.get("/deprecations").await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    let deprecations = body["deprecations"].as_array().unwrap();
+    assert_eq!(deprecations.len(), 1);
+    assert_eq!(deprecations[0]["code"], "legacy-protocol-version");
+    assert_eq!(deprecations[0]["count"], 1);
+    assert!(deprecations[0]["replacement"].is_string());
+}
+
+#[tokio::test]
+async fn test_server_info_reports_redacted_configuration() {
+    let token = |name: &str, secret: &str, scope| ApiToken {
+        name: name.to_string(),
+        token: secret.to_string(),
+        expires_at: None,
+        scopes: BTreeSet::from([scope]),
+        tenant: None,
+    };
+    let auth = AuthConfig::with_tokens(vec![
+        token("agent", "agent_secret", TokenScope::Write),
+        token("ops", "ops_secret", TokenScope::Admin),
+    ]);
+    let security = SecurityConfig {
+        allowed_origins: Some(vec!["https://app.example.com".to_string()]),
+        ..Default::default()
+    };
+    let log = Arc::new(SqliteAuditLog::open_in_memory().unwrap());
+    let (server, _temp) =
+        setup_test_server_with(auth, security, |handler| handler.with_audit_log(log)).await;
+    let bearer = |secret: &str| HeaderValue::from_str(&format!("Bearer {secret}")).unwrap();
+
+    server
+        .get("/server-info")
+        .add_header(HeaderName::from_static(AUTH_HEADER), bearer("agent_secret"))
+        .await
+        .assert_status(axum::http::StatusCode::FORBIDDEN);
+
+    let response = server
+        .get("/server-info")
+        .add_header(HeaderName::from_static(AUTH_HEADER), bearer("ops_secret"))
+        .await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["transports"], json!(["http"]));
+    assert_eq!(body["http"]["auth"]["mode"], "tokens");
+    assert_eq!(body["http"]["auth"]["tokens"], 2);
+    assert_eq!(
+        body["http"]["allowedOrigins"],
+        json!(["https://app.example.com"])
+    );
+    assert_eq!(body["auditLog"], "sqlite");
+    assert!(body["instances"].is_array());
+    assert!(!body.to_string().contains("_secret"));
+}
+
+#[tokio::test]
+async fn test_server_info_lists_only_the_tenant_instances() {
+    let token = |name: &str, secret: &str, tenant: Option<&str>| ApiToken {
+        name: name.to_string(),
+        token: secret.to_string(),
+        expires_at: None,
+        scopes: BTreeSet::from([TokenScope::Admin]),
+        tenant: tenant.map(str::to_string),
+    };
+    let tenant = |name: &str, instance: &str| Tenant {
+        name: name.to_string(),
+        instances: vec![instance.to_string()],
+        calls_per_minute: None,
+    };
+    let auth = AuthConfig::with_tenants(
+        vec![
+            token("ops", "ops_secret", None),
+            token("finance-admin", "finance_secret", Some("finance")),
+            token("hr-admin", "hr_secret", Some("hr")),
+        ],
+        vec![tenant("finance", "default"), tenant("hr", "hr-prod")],
+    );
+    let (server, _temp) = setup_test_server_with_auth(auth).await;
+    let instances = |secret: &'static str| {
+        let request = server.get("/server-info").add_header(
+            HeaderName::from_static(AUTH_HEADER),
+            HeaderValue::from_str(&format!("Bearer {secret}")).unwrap(),
+        );
+        async move {
+            let response = request.await;
+            response.assert_status_ok();
+            let body: serde_json::Value = response.json();
+            body["instances"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|instance| instance["name"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        }
+    };
+
+    assert_eq!(instances("ops_secret").await, ["default"]);
+    assert_eq!(instances("finance_secret").await, ["default"]);
+    assert!(instances("hr_secret").await.is_empty());
+}
+
+/// Reads `data:` payloads of one SSE event type from a streaming response.
+struct SseReader {
+    response: reqwest::Response,
+    buffer: String,
+}
+
+impl SseReader {
+    async fn next(&mut self, event: &str) -> String {
+        loop {
+            if let Some(end) = self.buffer.find("\n\n") {
+                let frame: String = self.buffer.drain(..end + 2).collect();
+                let is_event = frame.lines().any(|line| line == format!("event: {event}"));
+                if let Some(data) = frame.lines().find_map(|line| line.strip_prefix("data: "))
+                    && is_event
+                {
+                    return data.to_string();
+                }
+                continue;
+            }
+            let chunk =
+                tokio::time::timeout(std::time::Duration::from_secs(5), self.response.chunk())
+                    .await
+                    .expect("SSE event in time")
+                    .unwrap()
+                    .expect("open stream");
+            self.buffer.push_str(&String::from_utf8_lossy(&chunk));
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_legacy_sse_sessions_match_streamable_http() {
+    let (base, _temp) = setup_listening_server(
+        AuthConfig::new(true, Some("test_token".to_string())),
+        SecurityConfig::default(),
+    )
+    .await;
+    let client = reqwest::Client::new();
+
+    let unauthorized = client.get(format!("{base}/sse")).send().await.unwrap();
+    assert_eq!(unauthorized.status(), 401);
+    let unknown = client
+        .post(format!("{base}/messages?sessionId=nope"))
+        .bearer_auth("test_token")
+        .json(&json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(unknown.status(), 404);
+
+    let stream = client
+        .get(format!("{base}/sse"))
+        .bearer_auth("test_token")
+        .send()
+        .await
+        .unwrap();
+    let mut events = SseReader {
+        response: stream,
+        buffer: String::new(),
+    };
+    let endpoint = format!("{base}{}", events.next("endpoint").await);
+    let post = |body: serde_json::Value| {
+        client
+            .post(&endpoint)
+            .bearer_auth("test_token")
+            .json(&body)
+            .send()
+    };
+
+    // Requests before initialize are refused, as on /mcp.
+    assert_eq!(
+        post(json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"}))
+            .await
+            .unwrap()
+            .status(),
+        202
+    );
+    let refused: serde_json::Value = serde_json::from_str(&events.next("message").await).unwrap();
+    assert_eq!(refused["id"], 1);
+    assert_eq!(refused["error"]["message"], "Server not initialized");
+
+    post(json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "method": "initialize",
+        "params": {"protocolVersion": "2025-03-26", "clientInfo": {"name": "cursor"}}
+    }))
+    .await
+    .unwrap();
+    let initialized: serde_json::Value =
+        serde_json::from_str(&events.next("message").await).unwrap();
+    assert_eq!(initialized["result"]["protocolVersion"], "2025-03-26");
+    assert_eq!(
+        initialized["result"]["_meta"]["deprecations"][0]["code"],
+        "legacy-sse-transport"
+    );
+
+    post(json!({"jsonrpc": "2.0", "id": 3, "method": "tools/list"}))
+        .await
+        .unwrap();
+    let tools: serde_json::Value = serde_json::from_str(&events.next("message").await).unwrap();
+    assert!(tools["result"]["tools"].is_array());
+
+    let summary: serde_json::Value = client
+        .get(format!("{base}/deprecations"))
+        .bearer_auth("test_token")
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(summary["deprecations"][0]["code"], "legacy-sse-transport");
+    assert_eq!(summary["deprecations"][0]["count"], 3);
+
+    // Disconnecting the stream ends the session.
+    drop(events);
+    let mut status = 0;
+    for _ in 0..50 {
+        status = post(json!({"jsonrpc": "2.0", "id": 4, "method": "tools/list"}))
+            .await
+            .unwrap()
+            .status()
+            .as_u16();
+        if status == 404 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    assert_eq!(status, 404);
+}
+
+#[tokio::test]
+async fn test_signature_for_one_route_is_rejected_on_another() {
+    const SECRET: &str = "relay-signing-secret-0123456789abcdef";
+    let security = SecurityConfig {
+        request_signing: Some(RequestVerifier::new(SECRET, Duration::from_secs(300)).unwrap()),
+        ..Default::default()
+    };
+    let (server, _temp) = setup_test_server_with_config(
+        AuthConfig::new(true, Some("test_token".to_string())),
+        security,
+    )
+    .await;
+    let signer = RequestSigner::new(SECRET, "MCP_RELAY_SIGNING_SECRET").unwrap();
+    let body = json!({"jsonrpc": "2.0", "id": 1, "method": "ping"}).to_string();
+    let post = |path: &str, signed_for: &str| {
+        let mut request = server
+            .post(path)
+            .add_header(
+                HeaderName::from_static(AUTH_HEADER),
+                HeaderValue::from_static("Bearer test_token"),
+            )
+            .text(body.clone())
+            .content_type("application/json");
+        for (name, value) in signer.headers(SignedRequest {
+            method: "POST",
+            target: signed_for,
+            body: body.as_bytes(),
+        }) {
+            request = request.add_header(
+                HeaderName::from_static(name),
+                HeaderValue::from_str(&value).unwrap(),
+            );
+        }
+        request
+    };
+
+    post("/mcp", "/mcp").await.assert_status_ok();
+    let response = post("/messages?sessionId=s1", "/mcp").await;
+    response.assert_status_unauthorized();
+    assert_eq!(
+        response.json::<serde_json::Value>()["error"],
+        "invalid_signature"
+    );
+}
+
+#[tokio::test]
+async fn test_relay_forwards_signed_requests() {
+    const SECRET: &str = "relay-signing-secret-0123456789abcdef";
+    let security = SecurityConfig {
+        request_signing: Some(RequestVerifier::new(SECRET, Duration::from_secs(300)).unwrap()),
+        ..Default::default()
+    };
+    let (base, _temp) = setup_listening_server(
+        AuthConfig::new(true, Some("test_token".to_string())),
+        security,
+    )
+    .await;
+
+    // Bearer token alone is not enough once signing is required.
+    let unsigned = reqwest::Client::new()
+        .post(format!("{base}/mcp"))
+        .bearer_auth("test_token")
+        .json(&json!({"jsonrpc": "2.0", "id": 1, "method": "ping"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(unsigned.status(), 401);
+    let body: serde_json::Value = unsigned.json().await.unwrap();
+    assert_eq!(body["error"], "invalid_signature");
+
+    let run_relay = |secret: &str| {
+        let config = RelayConfig::new(
+            &format!("{base}/mcp"),
+            Some("test_token".to_string()),
+            Some(RequestSigner::new(secret, "MCP_RELAY_SIGNING_SECRET").unwrap()),
+        )
+        .unwrap();
+        let (client_end, relay_end) = tokio::io::duplex(64 * 1024);
+        let (relay_in, relay_out) = tokio::io::split(relay_end);
+        let task = tokio::spawn(relay::run(config, relay_in, relay_out));
+        let (reader, writer) = tokio::io::split(client_end);
+        (task, BufReader::new(reader).lines(), writer)
+    };
+
+    let (task, mut lines, mut stdin) = run_relay(SECRET);
+    let initialize = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-11-05",
+            "capabilities": {},
+            "clientInfo": {"name": "desktop", "version": "1.0"}
+        }
+    });
+    for message in [
+        initialize.clone(),
+        json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+        json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"}),
+    ] {
+        stdin
+            .write_all(format!("{message}\n").as_bytes())
+            .await
+            .unwrap();
+    }
+    let first: serde_json::Value =
+        serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+    assert_eq!(first["id"], 1);
+    assert!(first["result"]["serverInfo"].is_object());
+    let second: serde_json::Value =
+        serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+    assert_eq!(second["id"], 2);
+    assert!(second["result"]["tools"].is_array());
+    stdin.shutdown().await.unwrap();
+    task.await.unwrap().unwrap();
+
+    // A relay with the wrong secret gets an error response for each request.
+    let (task, mut lines, mut stdin) = run_relay(&SECRET.replace('r', "R"));
+    stdin
+        .write_all(format!("{initialize}\n").as_bytes())
+        .await
+        .unwrap();
+    let rejected: serde_json::Value =
+        serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+    assert_eq!(rejected["id"], 1);
+    assert!(
+        rejected["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("HTTP 401")
+    );
+    stdin.shutdown().await.unwrap();
+    task.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn test_tenant_tokens_only_see_their_instances_and_audit_entries() {
+    let token = |name: &str, secret: &str, tenant: Option<&str>| ApiToken {
+        name: name.to_string(),
+        token: secret.to_string(),
+        expires_at: None,
+        scopes: BTreeSet::from([TokenScope::Admin]),
+        tenant: tenant.map(str::to_string),
+    };
+    let tenant = |name: &str, instance: &str| Tenant {
+        name: name.to_string(),
+        instances: vec![instance.to_string()],
+        calls_per_minute: None,
+    };
+    let auth = AuthConfig::with_tenants(
+        vec![
+            token("ops", "ops_secret", None),
+            token("finance-bot", "finance_secret", Some("finance")),
+            token("hr-bot", "hr_secret", Some("hr")),
+        ],
+        vec![tenant("finance", "default"), tenant("hr", "hr-prod")],
+    );
+    let log = Arc::new(SqliteAuditLog::open_in_memory().unwrap());
+    let (server, _temp) = setup_test_server_with(auth, SecurityConfig::default(), |handler| {
+        handler.with_audit_log(log)
+    })
+    .await;
+    let bearer = |secret: &str| HeaderValue::from_str(&format!("Bearer {secret}")).unwrap();
+    let rpc = |secret: &str, method: &str, params: serde_json::Value| {
+        server
+            .post("/mcp")
+            .add_header(HeaderName::from_static(AUTH_HEADER), bearer(secret))
+            .json(&json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params}))
+    };
+    let instances = |body: serde_json::Value| -> serde_json::Value {
+        serde_json::from_str(body["result"]["contents"][0]["text"].as_str().unwrap()).unwrap()
+    };
+
+    let read = json!({"uri": "odoo://instances"});
+    let all = instances(
+        rpc("ops_secret", "resources/read", read.clone())
+            .await
+            .json(),
+    );
+    assert_eq!(all, json!([{"name": "default"}]));
+    let finance = instances(
+        rpc("finance_secret", "resources/read", read.clone())
+            .await
+            .json(),
+    );
+    assert_eq!(finance, json!([{"name": "default"}]));
+    let hr = instances(rpc("hr_secret", "resources/read", read).await.json());
+    assert_eq!(hr, json!([]));
+
+    let call = json!({"name": "no_such_tool", "arguments": {"instance": "default"}});
+    rpc("finance_secret", "tools/call", call.clone())
+        .await
+        .assert_status_ok();
+    rpc("hr_secret", "tools/call", call)
+        .await
+        .assert_status_ok();
+
+    let response = server
+        .get("/audit")
+        .add_header(HeaderName::from_static(AUTH_HEADER), bearer("hr_secret"))
+        .await;
+    response.assert_status_ok();
+    let entries = response.json::<serde_json::Value>()["entries"].clone();
+    assert_eq!(entries.as_array().unwrap().len(), 1);
+    assert_eq!(entries[0]["token"], "hr-bot");
+    assert_eq!(entries[0]["tenant"], "hr");
+
+    let response = server
+        .get("/audit")
+        .add_header(HeaderName::from_static(AUTH_HEADER), bearer("ops_secret"))
+        .await;
+    let entries = response.json::<serde_json::Value>()["entries"].clone();
+    assert_eq!(entries.as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_tool_call_with_progress_token_streams_sse() {
+    let (server, _temp) = setup_test_server(false).await;
+    let call = |progress: bool| {
+        let mut params = json!({"name": "no_such_tool", "arguments": {}});
+        if progress {
+            params["_meta"] = json!({"progressToken": "p1"});
+        }
+        server
+            .post("/mcp")
+            .add_header(
+                HeaderName::from_static("accept"),
+                HeaderValue::from_static("application/json, text/event-stream"),
+            )
+            .json(&json!({"jsonrpc": "2.0", "id": 7, "method": "tools/call", "params": params}))
+    };
+
+    // Without a progress token the response stays plain JSON.
+    let response = call(false).await;
+    response.assert_status_ok();
+    assert_eq!(response.json::<serde_json::Value>()["id"], 7);
+
+    let response = call(true).await;
+    response.assert_status_ok();
+    assert!(
+        response
+            .header("content-type")
+            .to_str()
+            .unwrap()
+            .starts_with("text/event-stream")
+    );
+    let text = response.text();
+    let data: Vec<serde_json::Value> = text
+        .lines()
+        .filter_map(|line| line.strip_prefix("data: "))
+        .map(|data| serde_json::from_str(data).unwrap())
+        .collect();
+    let last = data.last().expect("response event");
+    assert_eq!(last["id"], 7);
+    assert_eq!(last["result"]["isError"], true);
+}
+
+#[tokio::test]
+async fn test_idle_sessions_expire() {
+    let security = SecurityConfig {
+        session_idle_ttl: Some(std::time::Duration::from_millis(300)),
+        ..Default::default()
+    };
+    let (server, _temp) = setup_test_server_with_config(AuthConfig::disabled(), security).await;
+
+    let init_resp = server
+        .post("/mcp")
+        .json(&json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}))
+        .await;
+    let session_id = init_resp
+        .headers()
+        .get("mcp-session-id")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+    let ping = |id: i64| {
+        server
+            .post("/mcp")
+            .add_header(
+                HeaderName::from_static(MCP_SESSION_HEADER),
+                HeaderValue::from_str(&session_id).unwrap(),
+            )
+            .json(&json!({"jsonrpc": "2.0", "id": id, "method": "ping"}))
+    };
+
+    // Requests keep the session alive
+    for id in 2..6 {
+        tokio::time::sleep(std::time::Duration::from_millis(150)).await;
+        ping(id).await.assert_status_ok();
+    }
+
+    tokio::time::sleep(std::time::Duration::from_millis(800)).await;
+    ping(6)
+        .await
+        .assert_status(axum::http::StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_admin_can_terminate_live_sessions() {
+    let mut directory = None;
+    let (server, _temp) = setup_test_server_with(
+        AuthConfig::disabled(),
+        SecurityConfig::default(),
+        |handler| {
+            directory = Some(handler.sessions().clone());
+            handler
+        },
+    )
+    .await;
+    let directory = directory.unwrap();
+
+    let init_resp = server
+        .post("/mcp")
+        .json(&json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {"clientInfo": {"name": "test-agent", "version": "0.1"}}
+        }))
+        .await;
+    let session_id = init_resp
+        .headers()
+        .get("mcp-session-id")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+    let sessions = directory.list();
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].id, session_id);
+    assert_eq!(
+        sessions[0].client_info.as_ref().unwrap()["name"],
+        "test-agent"
+    );
+
+    assert!(directory.terminate(&session_id));
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    server
+        .post("/mcp")
+        .add_header(
+            HeaderName::from_static(MCP_SESSION_HEADER),
+            HeaderValue::from_str(&session_id).unwrap(),
+        )
+        .json(&json!({"jsonrpc": "2.0", "id": 2, "method": "ping"}))
+        .await
+        .assert_status(axum::http::StatusCode::NOT_FOUND);
+}
+
 #[tokio::test]
 async fn test_mcp_post_unauthorized_invalid_scheme() {
     let (server, _temp) = setup_test_server(true).await;
@@ -420,6 +1348,34 @@ async fn test_session_required_for_tools_list() {
     response.assert_status_ok();
 }
 
+#[tokio::test]
+async fn test_sessionless_requests_cannot_connect_instances() {
+    let (server, _temp) = setup_test_server(false).await;
+
+    let response = server
+        .post("/mcp")
+        .json(&json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": {
+                "name": "odoo_connect",
+                "arguments": {"name": "acme", "url": "http://127.0.0.1:9", "apiKey": "k"}
+            }
+        }))
+        .await;
+
+    response.assert_status_ok();
+    let body = response.json::<serde_json::Value>();
+    assert_eq!(body["result"]["isError"], true);
+    assert!(
+        body["result"]["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("odoo_connect requires an MCP session")
+    );
+}
+
 #[tokio::test]
 async fn test_notification_returns_accepted() {
     let (server, _temp) = setup_test_server(false).await;
@@ -463,3 +1419,85 @@ async fn test_notification_returns_accepted() {
 
 // NOTE: Legacy messages endpoint test is commented out because
 // it requires SSE session which can cause test hangs.
+
+// ============================================================================
+// JSON-RPC Batch Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_batch_returns_array_of_responses() {
+    let (server, _temp) = setup_test_server(false).await;
+
+    let response = server
+        .post("/mcp")
+        .json(&json!([
+            {"jsonrpc": "2.0", "id": 1, "method": "ping"},
+            {"jsonrpc": "2.0", "method": "notifications/initialized"},
+            {"jsonrpc": "2.0", "id": "two", "method": "tools/list", "params": {}}
+        ]))
+        .await;
+
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    let items = body.as_array().expect("batch response must be an array");
+    assert_eq!(items.len(), 2, "notifications produce no response entry");
+    assert_eq!(items[0]["id"], 1);
+    assert_eq!(items[1]["id"], "two");
+    assert!(items[1]["result"]["tools"].is_array());
+}
+
+#[tokio::test]
+async fn test_batch_of_notifications_returns_accepted() {
+    let (server, _temp) = setup_test_server(false).await;
+
+    let response = server
+        .post("/mcp")
+        .json(&json!([
+            {"jsonrpc": "2.0", "method": "notifications/initialized"}
+        ]))
+        .await;
+
+    assert_eq!(response.status_code().as_u16(), 202);
+}
+
+#[tokio::test]
+async fn test_batch_rejects_initialize_and_empty_batch() {
+    let (server, _temp) = setup_test_server(false).await;
+
+    let response = server
+        .post("/mcp")
+        .json(&json!([
+            {"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}},
+            {"jsonrpc": "2.0", "id": 2, "method": "ping"}
+        ]))
+        .await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body[0]["id"], 1);
+    assert!(body[0]["error"].is_object());
+    assert!(body[1]["result"].is_object());
+
+    let response = server.post("/mcp").json(&json!([])).await;
+    assert_eq!(response.status_code().as_u16(), 400);
+}
+
+// ============================================================================
+// Server-to-client Response Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_client_response_is_accepted() {
+    let (server, _temp) = setup_test_server(false).await;
+
+    // A sampling answer (no method) must not be treated as a malformed request.
+    let response = server
+        .post("/mcp")
+        .json(&json!({
+            "jsonrpc": "2.0",
+            "id": "srv-1",
+            "result": {"role": "assistant", "content": {"type": "text", "text": "[]"}}
+        }))
+        .await;
+
+    assert_eq!(response.status_code().as_u16(), 202);
+}
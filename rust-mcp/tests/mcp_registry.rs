@@ -1,7 +1,7 @@
 //! Tests for MCP Registry and tool definitions
 #[cfg(test)]
 mod tests {
-    use rust_mcp::mcp::registry::{OpSpec, ToolDef};
+    use rust_mcp::mcp::registry::{OpSpec, ToolAnnotations, ToolDef};
     use serde_json::json;
     use std::collections::HashMap;
 
@@ -15,6 +15,8 @@ mod tests {
             op: OpSpec {
                 op_type: "search".to_string(),
                 map: Default::default(),
+                steps: Vec::new(),
+                pinned: Default::default(),
             },
             input_schema: json!({
                 "type": "object",
@@ -23,6 +25,8 @@ mod tests {
                 }
             }),
             guards: None,
+            annotations: Default::default(),
+            describe_fields: None,
         };
 
         assert_eq!(tool.name, "search_partners");
@@ -42,6 +46,8 @@ mod tests {
         let op = OpSpec {
             op_type: "search".to_string(),
             map,
+            steps: Vec::new(),
+            pinned: Default::default(),
         };
 
         assert_eq!(op.op_type, "search");
@@ -58,6 +64,8 @@ mod tests {
         let op = OpSpec {
             op_type: "create".to_string(),
             map: Default::default(),
+            steps: Vec::new(),
+            pinned: Default::default(),
         };
 
         assert_eq!(op.op_type, "create");
@@ -73,6 +81,8 @@ mod tests {
         let op = OpSpec {
             op_type: "read".to_string(),
             map,
+            steps: Vec::new(),
+            pinned: Default::default(),
         };
 
         assert_eq!(op.op_type, "read");
@@ -92,12 +102,16 @@ mod tests {
             op: OpSpec {
                 op_type: "admin_action".to_string(),
                 map: Default::default(),
+                steps: Vec::new(),
+                pinned: Default::default(),
             },
             input_schema: json!({"type": "object"}),
             guards: Some(ToolGuards {
                 requires_env_true: Some("ADMIN_MODE".to_string()),
                 requires_env_true_all: Vec::new(),
             }),
+            annotations: Default::default(),
+            describe_fields: None,
         };
 
         assert_eq!(tool.name, "admin_tool");
@@ -132,4 +146,54 @@ mod tests {
             Some(&"product.product".to_string())
         );
     }
+
+    #[test]
+    fn test_tool_annotations_derived_from_op() {
+        let tool: ToolDef = serde_json::from_value(json!({
+            "name": "odoo_search",
+            "description": "",
+            "inputSchema": {"type": "object"},
+            "op": {"type": "search"}
+        }))
+        .unwrap();
+        let annotations = tool.effective_annotations();
+        assert_eq!(annotations.read_only_hint, Some(true));
+        assert_eq!(annotations.destructive_hint, None);
+
+        assert_eq!(
+            ToolAnnotations::for_op("unlink").destructive_hint,
+            Some(true)
+        );
+        assert_eq!(
+            ToolAnnotations::for_op("deep_cleanup").destructive_hint,
+            Some(true)
+        );
+        let uninstall = ToolAnnotations::for_op("module_uninstall");
+        assert_eq!(uninstall.read_only_hint, Some(false));
+        assert_eq!(uninstall.destructive_hint, Some(true));
+        let create = ToolAnnotations::for_op("create");
+        assert_eq!(create.read_only_hint, Some(false));
+        assert_eq!(create.destructive_hint, Some(false));
+        assert_eq!(create.idempotent_hint, Some(false));
+    }
+
+    #[test]
+    fn test_tool_annotations_explicit_override() {
+        let tool: ToolDef = serde_json::from_value(json!({
+            "name": "odoo_workflow_action",
+            "description": "",
+            "inputSchema": {"type": "object"},
+            "op": {"type": "workflow_action"},
+            "annotations": {"destructiveHint": true, "openWorldHint": false}
+        }))
+        .unwrap();
+        let annotations = tool.effective_annotations();
+        assert_eq!(annotations.read_only_hint, Some(false));
+        assert_eq!(annotations.destructive_hint, Some(true));
+        assert_eq!(annotations.open_world_hint, Some(false));
+        assert_eq!(
+            serde_json::to_value(&annotations).unwrap()["readOnlyHint"],
+            json!(false)
+        );
+    }
 }
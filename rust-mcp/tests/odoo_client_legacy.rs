@@ -2,12 +2,13 @@
 
 mod common;
 
-use rust_mcp::odoo::config::OdooInstanceConfig;
+use rust_mcp::odoo::config::{OdooInstanceConfig, ProxyAuthConfig, ProxyAuthHeader};
+use rust_mcp::odoo::health::HealthDepth;
 use rust_mcp::odoo::legacy_client::OdooLegacyClient;
 use serde_json::json;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use wiremock::matchers::{method, path};
+use wiremock::matchers::{header, method, path};
 use wiremock::{Mock, MockServer, Respond, ResponseTemplate};
 
 fn create_legacy_config(url: &str) -> OdooInstanceConfig {
@@ -24,6 +25,11 @@ fn create_legacy_config(url: &str) -> OdooInstanceConfig {
         tool_config: None,
         read_only: false,
         tags: Vec::new(),
+        headers: HashMap::new(),
+        proxy_auth: None,
+        lang: None,
+        translate_errors: false,
+        health_depth: None,
         aliases: Vec::new(),
         extra: HashMap::new(),
     }
@@ -111,6 +117,170 @@ async fn test_legacy_authenticate_success() {
     assert_eq!(result, vec![1, 2, 3]);
 }
 
+#[tokio::test]
+async fn test_legacy_instance_headers_sent() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/jsonrpc"))
+        .and(header("x-gateway-key", "k-123"))
+        .respond_with(AuthThenDataResponder::new(json!([4])))
+        .mount(&server)
+        .await;
+
+    let mut config = create_legacy_config(&server.uri());
+    config
+        .headers
+        .insert("X-Gateway-Key".to_string(), "k-123".to_string());
+    let client = OdooLegacyClient::new(&config).unwrap();
+
+    let result = client
+        .search("res.partner", None, None, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(result, vec![4]);
+}
+
+#[tokio::test]
+async fn test_legacy_proxy_basic_auth_sent() {
+    let server = MockServer::start().await;
+
+    // A basic-auth proxy in front of Odoo: requests without the credentials never reach it.
+    Mock::given(method("POST"))
+        .and(path("/jsonrpc"))
+        .and(header("authorization", "Basic c3RhZ2luZzpvcGVu"))
+        .respond_with(AuthThenDataResponder::new(json!([9])))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/jsonrpc"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&server)
+        .await;
+
+    let mut config = create_legacy_config(&server.uri());
+    config.proxy_auth = Some(ProxyAuthConfig {
+        username: "staging".to_string(),
+        password: "open".to_string(),
+        header: ProxyAuthHeader::Authorization,
+    });
+    let client = OdooLegacyClient::new(&config).unwrap();
+
+    let result = client
+        .search("res.partner", None, None, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(result, vec![9]);
+}
+
+#[tokio::test]
+async fn test_legacy_lang_sent_and_errors_translated() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/jsonrpc"))
+        .and(|req: &wiremock::Request| {
+            let body: serde_json::Value = serde_json::from_slice(&req.body).unwrap_or_default();
+            body["params"]["method"] == "authenticate"
+        })
+        .respond_with(ResponseTemplate::new(200).set_body_json(jsonrpc_success(json!(2))))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/jsonrpc"))
+        .and(|req: &wiremock::Request| {
+            let body: serde_json::Value = serde_json::from_slice(&req.body).unwrap_or_default();
+            body["params"]["args"][6]["context"] == json!({"lang": "en_US"})
+        })
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "error": {
+                "code": 200,
+                "message": "Odoo Server Error",
+                "data": {
+                    "name": "odoo.exceptions.MissingError",
+                    "message": "Catatan tidak ada atau telah dihapus.",
+                    "arguments": ["Catatan tidak ada atau telah dihapus."]
+                }
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut config = create_legacy_config(&server.uri());
+    config.lang = Some("en_US".to_string());
+    config.translate_errors = true;
+    let client = OdooLegacyClient::new(&config).unwrap();
+
+    let err = client
+        .read("res.partner", vec![404], None, None)
+        .await
+        .unwrap_err();
+    match err {
+        rust_mcp::odoo::types::OdooError::Api { message, body, .. } => {
+            assert_eq!(
+                message,
+                "Record does not exist or has been deleted. (Catatan tidak ada atau telah dihapus.)"
+            );
+            assert_eq!(
+                body.unwrap().name.as_deref(),
+                Some("odoo.exceptions.MissingError")
+            );
+        }
+        other => panic!("expected an API error, got {other}"),
+    }
+}
+
+#[tokio::test]
+async fn test_legacy_shallow_and_standard_health_probes_skip_login() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/jsonrpc"))
+        .respond_with(AuthThenDataResponder::new(json!(42)))
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/web/webclient/version_info"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(jsonrpc_success(json!({
+                "server_version": "17.0",
+                "server_version_info": [17, 0, 0, "final", 0, ""]
+            }))),
+        )
+        .mount(&server)
+        .await;
+
+    let config = create_legacy_config(&server.uri());
+    let client = OdooLegacyClient::new(&config).unwrap();
+
+    // Unmatched GET / answers 404: the server is reachable.
+    assert_eq!(
+        client.health_probe_at(HealthDepth::Shallow).await.unwrap(),
+        None
+    );
+    assert_eq!(
+        client.health_probe_at(HealthDepth::Standard).await.unwrap(),
+        Some("17.0".to_string())
+    );
+    // Only the deep probe calls /jsonrpc: a login, then search_count.
+    assert_eq!(
+        client.health_probe_at(HealthDepth::Deep).await.unwrap(),
+        None
+    );
+
+    let down = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(502))
+        .mount(&down)
+        .await;
+    let client = OdooLegacyClient::new(&create_legacy_config(&down.uri())).unwrap();
+    assert!(client.health_probe_at(HealthDepth::Shallow).await.is_err());
+}
+
 #[tokio::test]
 async fn test_legacy_auth_failure() {
     let server = MockServer::start().await;
@@ -442,6 +612,11 @@ async fn test_legacy_network_error() {
         tool_config: None,
         read_only: false,
         tags: Vec::new(),
+        headers: HashMap::new(),
+        proxy_auth: None,
+        lang: None,
+        translate_errors: false,
+        health_depth: None,
         aliases: Vec::new(),
         extra: HashMap::new(),
     };
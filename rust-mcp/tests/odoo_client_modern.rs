@@ -5,9 +5,10 @@ mod common;
 use common::{MockOdooServer, responses};
 use rust_mcp::odoo::client::OdooHttpClient;
 use rust_mcp::odoo::config::OdooInstanceConfig;
+use rust_mcp::odoo::types::OdooError;
 use serde_json::json;
 use std::collections::HashMap;
-use wiremock::matchers::{header, method, path_regex};
+use wiremock::matchers::{body_partial_json, header, method, path_regex};
 use wiremock::{Mock, ResponseTemplate};
 
 fn create_config(url: &str) -> OdooInstanceConfig {
@@ -24,6 +25,11 @@ fn create_config(url: &str) -> OdooInstanceConfig {
         tool_config: None,
         read_only: false,
         tags: Vec::new(),
+        headers: HashMap::new(),
+        proxy_auth: None,
+        lang: None,
+        translate_errors: false,
+        health_depth: None,
         aliases: Vec::new(),
         extra: HashMap::new(),
     }
@@ -374,6 +380,87 @@ async fn test_api_error_500_server_error() {
     assert!(result.is_err());
 }
 
+#[tokio::test]
+async fn test_deadline_stops_retries_that_cannot_finish() {
+    let mock = MockOdooServer::start().await;
+    Mock::given(method("POST"))
+        .and(path_regex(r"/json/2/res\.partner/search"))
+        .respond_with(ResponseTemplate::new(503).set_body_string("busy"))
+        .expect(2)
+        .mount(&mock.server)
+        .await;
+
+    let client = OdooHttpClient::new(&create_config(&mock.uri())).unwrap();
+    let started = std::time::Instant::now();
+    // 250ms and 500ms backoffs fit in the budget only for the first retry.
+    let deadline = started + std::time::Duration::from_millis(600);
+    let result = rust_mcp::odoo::deadline::scope(
+        Some(deadline),
+        client.search("res.partner", None, None, None, None, None),
+    )
+    .await;
+
+    match result {
+        Err(OdooError::DeadlineExceeded { last_error }) => {
+            assert!(last_error.unwrap().contains("503"));
+        }
+        other => panic!("expected a deadline error, got {other:?}"),
+    }
+    assert!(started.elapsed() < std::time::Duration::from_millis(600));
+}
+
+#[tokio::test]
+async fn test_throttled_request_waits_for_retry_after() {
+    let mock = MockOdooServer::start().await;
+    Mock::given(method("POST"))
+        .and(path_regex(r"/json/2/res\.partner/search"))
+        .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "1"))
+        .up_to_n_times(1)
+        .mount(&mock.server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path_regex(r"/json/2/res\.partner/search"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([7])))
+        .mount(&mock.server)
+        .await;
+
+    let client = OdooHttpClient::new(&create_config(&mock.uri())).unwrap();
+    let started = std::time::Instant::now();
+    let ids = client
+        .search("res.partner", None, None, None, None, None)
+        .await
+        .unwrap();
+
+    assert_eq!(ids, vec![7]);
+    assert!(started.elapsed() >= std::time::Duration::from_secs(1));
+    let status = client.throttle().status(std::time::Instant::now());
+    assert!(status.throttled);
+    assert_eq!(status.throttled_total, 1);
+}
+
+#[tokio::test]
+async fn test_long_retry_after_fails_as_throttled() {
+    let mock = MockOdooServer::start().await;
+    Mock::given(method("POST"))
+        .and(path_regex(r"/json/2/res\.partner/search"))
+        .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "30"))
+        .expect(1)
+        .mount(&mock.server)
+        .await;
+
+    let client = OdooHttpClient::new(&create_config(&mock.uri())).unwrap();
+    let result = client
+        .search("res.partner", None, None, None, None, None)
+        .await;
+
+    match result {
+        Err(OdooError::UpstreamThrottled { retry_after_secs }) => {
+            assert_eq!(retry_after_secs, 30)
+        }
+        other => panic!("expected a throttling error, got {other:?}"),
+    }
+}
+
 // ============================================================================
 // Header Validation Tests
 // ============================================================================
@@ -401,6 +488,98 @@ async fn test_authorization_header_sent() {
     assert_eq!(result, vec![1, 2, 3]);
 }
 
+#[tokio::test]
+async fn test_instance_headers_sent() {
+    let mock = MockOdooServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path_regex(r"/json/2/res\.partner/search"))
+        .and(header("cf-access-client-id", "svc.access"))
+        .and(header("authorization", "bearer test_api_key"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([7])))
+        .mount(&mock.server)
+        .await;
+
+    let mut config = create_config(&mock.uri());
+    config
+        .headers
+        .insert("CF-Access-Client-Id".to_string(), "svc.access".to_string());
+    let client = OdooHttpClient::new(&config).unwrap();
+
+    let result = client
+        .search("res.partner", None, None, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(result, vec![7]);
+
+    config
+        .headers
+        .insert("Authorization".to_string(), "Basic abc".to_string());
+    let err = OdooHttpClient::new(&config).err().unwrap();
+    assert!(err.to_string().contains("cannot be overridden"));
+}
+
+#[tokio::test]
+async fn test_instance_lang_sent_and_errors_translated() {
+    let mock = MockOdooServer::start().await;
+
+    // A caller's own language wins over the instance default.
+    Mock::given(method("POST"))
+        .and(path_regex(r"/json/2/res\.partner/search"))
+        .and(body_partial_json(json!({"context": {"lang": "de_DE"}})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([3])))
+        .mount(&mock.server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path_regex(r"/json/2/res\.partner/write"))
+        .and(body_partial_json(json!({"context": {"lang": "en_US"}})))
+        .respond_with(ResponseTemplate::new(422).set_body_json(json!({
+            "name": "odoo.exceptions.ValidationError",
+            "message": "Tanggal akhir harus setelah tanggal mulai",
+            "arguments": [],
+            "context": {},
+            "debug": ""
+        })))
+        .expect(1)
+        .mount(&mock.server)
+        .await;
+
+    let mut config = create_config(&mock.uri());
+    config.lang = Some("en_US".to_string());
+    config.translate_errors = true;
+    let client = OdooHttpClient::new(&config).unwrap();
+
+    let ids = client
+        .search(
+            "res.partner",
+            None,
+            None,
+            None,
+            None,
+            Some(json!({"lang": "de_DE"})),
+        )
+        .await
+        .unwrap();
+    assert_eq!(ids, vec![3]);
+
+    let err = client
+        .write(
+            "res.partner",
+            vec![3],
+            json!({"date_end": "2020-01-01"}),
+            None,
+        )
+        .await
+        .unwrap_err();
+    match err {
+        OdooError::Api { message, .. } => assert_eq!(
+            message,
+            "Validation error: Tanggal akhir harus setelah tanggal mulai"
+        ),
+        other => panic!("expected an API error, got {other}"),
+    }
+}
+
 #[tokio::test]
 async fn test_content_type_header_sent() {
     let mock = MockOdooServer::start().await;
@@ -427,10 +606,87 @@ async fn test_content_type_header_sent() {
     assert_eq!(result, vec![1]);
 }
 
+#[tokio::test]
+async fn test_request_id_header_propagated() {
+    let mock = MockOdooServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path_regex(r"/json/2/res\.partner/search"))
+        .and(header("x-request-id", "req-42"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([7])))
+        .mount(&mock.server)
+        .await;
+
+    let config = create_config(&mock.uri());
+    let client = OdooHttpClient::new(&config).unwrap();
+
+    let result = rust_mcp::telemetry::scope(
+        Some("req-42".to_string()),
+        client.search("res.partner", None, None, None, None, None),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(result, vec![7]);
+}
+
+#[tokio::test]
+async fn test_session_and_tool_headers_propagated() {
+    let mock = MockOdooServer::start().await;
+    let session_tag = rust_mcp::telemetry::session_tag("session-1");
+
+    Mock::given(method("POST"))
+        .and(path_regex(r"/json/2/res\.partner/search"))
+        .and(header("x-mcp-session", session_tag.as_str()))
+        .and(header("x-mcp-tool", "odoo_search"))
+        .and(header(
+            "user-agent",
+            concat!("odoo-rust-mcp/", env!("CARGO_PKG_VERSION")),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([7])))
+        .mount(&mock.server)
+        .await;
+
+    let client = OdooHttpClient::new(&create_config(&mock.uri())).unwrap();
+    let result = rust_mcp::telemetry::with_session(
+        "session-1",
+        rust_mcp::telemetry::with_tool(
+            "odoo_search",
+            client.search("res.partner", None, None, None, None, None),
+        ),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(result, vec![7]);
+}
+
 // ============================================================================
 // Report Download Tests
 // ============================================================================
 
+#[tokio::test]
+async fn test_download_attachment_stops_at_the_size_limit() {
+    let mock = MockOdooServer::start().await;
+    Mock::given(method("GET"))
+        .and(wiremock::matchers::path("/web/content/9"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/plain")
+                .set_body_bytes(b"0123456789".to_vec()),
+        )
+        .mount(&mock.server)
+        .await;
+
+    let client = OdooHttpClient::new(&create_config(&mock.uri())).unwrap();
+    let content = client.download_attachment(9, 10).await.unwrap();
+    assert_eq!(content.bytes, b"0123456789");
+    assert_eq!(content.content_type.as_deref(), Some("text/plain"));
+
+    let error = client.download_attachment(9, 4).await.unwrap_err();
+    assert!(error.to_string().contains("4-byte limit"), "{error}");
+}
+
 #[tokio::test]
 async fn test_download_report_pdf() {
     let mock = MockOdooServer::start().await;
@@ -19,6 +19,11 @@ mod tests {
             tool_config: None,
             read_only: false,
             tags: Vec::new(),
+            headers: HashMap::new(),
+            proxy_auth: None,
+            lang: None,
+            translate_errors: false,
+            health_depth: None,
             aliases: Vec::new(),
             extra: HashMap::new(),
         };
@@ -65,6 +70,11 @@ mod tests {
             tool_config: None,
             read_only: false,
             tags: Vec::new(),
+            headers: HashMap::new(),
+            proxy_auth: None,
+            lang: None,
+            translate_errors: false,
+            health_depth: None,
             aliases: Vec::new(),
             extra: HashMap::new(),
         };
@@ -93,6 +103,11 @@ mod tests {
                 tool_config: None,
                 read_only: false,
                 tags: Vec::new(),
+                headers: HashMap::new(),
+                proxy_auth: None,
+                lang: None,
+                translate_errors: false,
+                health_depth: None,
                 aliases: Vec::new(),
                 extra: HashMap::new(),
             },
@@ -124,6 +139,11 @@ mod tests {
                 tool_config: None,
                 read_only: false,
                 tags: Vec::new(),
+                headers: HashMap::new(),
+                proxy_auth: None,
+                lang: None,
+                translate_errors: false,
+                health_depth: None,
                 aliases: Vec::new(),
                 extra: HashMap::new(),
             },
@@ -144,6 +164,11 @@ mod tests {
                 tool_config: None,
                 read_only: false,
                 tags: Vec::new(),
+                headers: HashMap::new(),
+                proxy_auth: None,
+                lang: None,
+                translate_errors: false,
+                health_depth: None,
                 aliases: Vec::new(),
                 extra: HashMap::new(),
             },
@@ -170,6 +195,11 @@ mod tests {
             tool_config: None,
             read_only: false,
             tags: Vec::new(),
+            headers: HashMap::new(),
+            proxy_auth: None,
+            lang: None,
+            translate_errors: false,
+            health_depth: None,
             aliases: Vec::new(),
             extra: HashMap::new(),
         };
@@ -192,6 +222,11 @@ mod tests {
             tool_config: None,
             read_only: false,
             tags: Vec::new(),
+            headers: HashMap::new(),
+            proxy_auth: None,
+            lang: None,
+            translate_errors: false,
+            health_depth: None,
             aliases: Vec::new(),
             extra: HashMap::new(),
         };
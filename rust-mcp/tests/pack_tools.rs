@@ -0,0 +1,753 @@
+//! Integration tests for the pack tools (`src/packs`), run through the tool dispatch against a
+//! mock Odoo 19 server with the shipped tool definitions.
+
+mod common;
+
+use common::MockOdooServer;
+use rust_mcp::mcp::registry::ToolDef;
+use rust_mcp::mcp::tools::{OdooClientPool, execute_op};
+use rust_mcp::odoo::types::OdooError;
+use serde_json::{Value, json};
+use tempfile::TempDir;
+use tokio::sync::Mutex;
+use wiremock::matchers::{body_partial_json, method, path_regex};
+use wiremock::{Mock, ResponseTemplate};
+
+static TEST_ENV_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// A pool whose only instance, "acme", is the mock server.
+async fn pool_for(mock: &MockOdooServer) -> OdooClientPool {
+    let _lock = TEST_ENV_LOCK.lock().await;
+    let temp_dir = TempDir::new().unwrap();
+    let instances = temp_dir.path().join("instances.json");
+    let config = json!({
+        "acme": {"url": mock.uri(), "db": "acme", "apiKey": "test_api_key", "version": "19"}
+    });
+    std::fs::write(&instances, config.to_string()).unwrap();
+
+    // SAFETY: Tests mutate process env under TEST_ENV_LOCK; the pool reads it once here.
+    unsafe {
+        std::env::set_var("ODOO_INSTANCES_JSON", &instances);
+    }
+    OdooClientPool::from_env().unwrap()
+}
+
+fn shipped_tool(name: &str) -> ToolDef {
+    let raw =
+        std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/config/tools.json")).unwrap();
+    let tools: Vec<ToolDef> =
+        serde_json::from_value(serde_json::from_str::<Value>(&raw).unwrap()["tools"].clone())
+            .unwrap();
+    tools
+        .into_iter()
+        .find(|tool| tool.name == name)
+        .unwrap_or_else(|| panic!("tool '{name}' is not in config/tools.json"))
+}
+
+/// Mount a `read_group` mock on `model` for requests whose body contains `body`.
+async fn mock_read_group(mock: &MockOdooServer, model: &str, body: Value, response: Value) {
+    Mock::given(method("POST"))
+        .and(path_regex(format!(
+            r"/json/2/{}/read_group",
+            model.replace('.', r"\.")
+        )))
+        .and(body_partial_json(body))
+        .respond_with(ResponseTemplate::new(200).set_body_json(response))
+        .mount(&mock.server)
+        .await;
+}
+
+/// Run the shipped tool `name` on "acme" and parse the report from its text content.
+async fn run_pack_tool(
+    mock: &MockOdooServer,
+    name: &str,
+    mut args: Value,
+) -> Result<Value, OdooError> {
+    let pool = pool_for(mock).await;
+    args["instance"] = json!("acme");
+    let result = execute_op(&pool, &shipped_tool(name).op, args).await?;
+    let text = result["content"][0]["text"].as_str().unwrap();
+    Ok(serde_json::from_str(text).unwrap())
+}
+
+// ============================================================================
+// Pack tools
+// ============================================================================
+
+#[tokio::test]
+async fn test_documents_list_workspaces() {
+    let mock = MockOdooServer::start().await;
+    mock.mock_search_read(
+        "documents.folder",
+        json!([
+            {"id": 1, "name": "Finance", "parent_folder_id": false},
+            {"id": 2, "name": "Invoices", "parent_folder_id": [1, "Finance"]}
+        ]),
+    )
+    .await;
+
+    let report = run_pack_tool(&mock, "odoo_documents_list_workspaces", json!({}))
+        .await
+        .unwrap();
+
+    assert_eq!(report["model"], "documents.folder");
+    assert_eq!(report["count"], 2);
+    assert_eq!(report["workspaces"][1]["parent"], "Finance");
+}
+
+#[tokio::test]
+async fn test_fleet_list_vehicles() {
+    let mock = MockOdooServer::start().await;
+    mock.mock_search_read(
+        "fleet.vehicle",
+        json!([{
+            "id": 7,
+            "name": "Van",
+            "license_plate": "B 1234 XY",
+            "model_id": [3, "Transit"],
+            "driver_id": [9, "Dana"],
+            "state_id": [1, "Registered"],
+            "odometer": 15200.0,
+            "odometer_unit": "kilometers"
+        }]),
+    )
+    .await;
+
+    let report = run_pack_tool(&mock, "odoo_fleet_list_vehicles", json!({}))
+        .await
+        .unwrap();
+
+    assert_eq!(report["count"], 1);
+    assert_eq!(report["vehicles"][0]["licensePlate"], "B 1234 XY");
+}
+
+#[tokio::test]
+async fn test_event_list() {
+    let mock = MockOdooServer::start().await;
+    mock.mock_search_read(
+        "event.event",
+        json!([{
+            "id": 4,
+            "name": "Open Day",
+            "date_begin": "2026-11-01 09:00:00",
+            "date_end": "2026-11-01 17:00:00",
+            "seats_max": 0,
+            "seats_taken": 12,
+            "organizer_id": [1, "Acme"]
+        }]),
+    )
+    .await;
+
+    let report = run_pack_tool(&mock, "odoo_event_list", json!({}))
+        .await
+        .unwrap();
+
+    assert_eq!(report["count"], 1);
+    assert_eq!(report["events"][0]["seatsTaken"], 12);
+    assert_eq!(report["events"][0]["seatsMax"], Value::Null);
+}
+
+#[tokio::test]
+async fn test_mailing_list_sizes() {
+    let mock = MockOdooServer::start().await;
+    mock.mock_fields_get(
+        "mailing.list",
+        json!({"name": {"type": "char"}, "contact_count": {"type": "integer"}}),
+    )
+    .await;
+    mock.mock_search_read(
+        "mailing.list",
+        json!([
+            {"id": 1, "name": "Newsletter", "contact_count": 120},
+            {"id": 2, "name": "Partners", "contact_count": 30}
+        ]),
+    )
+    .await;
+
+    let report = run_pack_tool(&mock, "odoo_mailing_list_sizes", json!({}))
+        .await
+        .unwrap();
+
+    assert_eq!(report["count"], 2);
+    assert_eq!(report["totalContacts"], 150);
+}
+
+#[tokio::test]
+async fn test_quality_equipment_lookup() {
+    let mock = MockOdooServer::start().await;
+    mock.mock_search_read(
+        "maintenance.equipment",
+        json!([{
+            "id": 5,
+            "name": "Press 01",
+            "serial_no": "SN-001",
+            "category_id": [2, "Presses"],
+            "location": "Hall A",
+            "maintenance_team_id": [1, "Internal"],
+            "maintenance_open_count": 1
+        }]),
+    )
+    .await;
+
+    let report = run_pack_tool(
+        &mock,
+        "odoo_equipment_lookup",
+        json!({"serialNo": "SN-001"}),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(report["id"], 5);
+    assert_eq!(report["serialNo"], "SN-001");
+
+    let error = run_pack_tool(&mock, "odoo_equipment_lookup", json!({"serialNo": " "}))
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("must not be empty"));
+}
+
+#[tokio::test]
+async fn test_helpdesk_sla_status() {
+    let mock = MockOdooServer::start().await;
+    mock.mock_search_read(
+        "helpdesk.sla.status",
+        json!([
+            {"id": 1, "ticket_id": [10, "Printer jam"], "sla_id": [1, "4h response"], "status": "failed", "deadline": "2026-10-01 10:00:00", "reached_datetime": false},
+            {"id": 2, "ticket_id": [11, "Login issue"], "sla_id": [1, "4h response"], "status": "reached", "deadline": "2026-10-01 12:00:00", "reached_datetime": "2026-10-01 11:00:00"}
+        ]),
+    )
+    .await;
+
+    let report = run_pack_tool(&mock, "odoo_helpdesk_sla_status", json!({}))
+        .await
+        .unwrap();
+
+    assert_eq!(report["total"], 2);
+    assert_eq!(report["byStatus"]["failed"], 1);
+    assert_eq!(report["lines"][0]["ticket"], "Printer jam");
+}
+
+#[tokio::test]
+async fn test_subscription_list() {
+    let mock = MockOdooServer::start().await;
+    mock.mock_search_count("sale.subscription", 1).await;
+    mock.mock_search_read(
+        "sale.subscription",
+        json!([{
+            "id": 12,
+            "name": "SUB012",
+            "partner_id": [3, "Deco Addict"],
+            "stage_id": [2, "In Progress"],
+            "recurring_monthly": 49.5,
+            "recurring_next_date": "2026-11-01"
+        }]),
+    )
+    .await;
+
+    let report = run_pack_tool(&mock, "odoo_subscription_list", json!({}))
+        .await
+        .unwrap();
+
+    assert_eq!(report["model"], "sale.subscription");
+    assert_eq!(report["count"], 1);
+    assert_eq!(report["subscriptions"][0]["mrr"], 49.5);
+    assert_eq!(report["subscriptions"][0]["nextInvoiceDate"], "2026-11-01");
+}
+
+#[tokio::test]
+async fn test_subscription_list_propagates_access_errors() {
+    let mock = MockOdooServer::start().await;
+    mock.mock_error("sale.subscription", "search_count", 403, "Access Denied")
+        .await;
+
+    let err = run_pack_tool(&mock, "odoo_subscription_list", json!({}))
+        .await
+        .unwrap_err();
+
+    assert!(err.to_string().contains("Access Denied"), "{err}");
+}
+
+#[tokio::test]
+async fn test_automation_list_rules() {
+    let mock = MockOdooServer::start().await;
+    mock.mock_fields_get(
+        "base.automation",
+        json!({"name": {}, "model_id": {}, "trigger": {}, "active": {}, "action_server_ids": {}}),
+    )
+    .await;
+    mock.mock_search_read(
+        "base.automation",
+        json!([{
+            "id": 3,
+            "name": "Escalate late tasks",
+            "model_id": [41, "Task"],
+            "trigger": "on_time",
+            "active": false,
+            "action_server_ids": [8, 9]
+        }]),
+    )
+    .await;
+
+    let report = run_pack_tool(&mock, "odoo_automation_rules", json!({}))
+        .await
+        .unwrap();
+
+    assert_eq!(report["count"], 1);
+    assert_eq!(report["rules"][0]["model"], "Task");
+    assert_eq!(report["rules"][0]["active"], false);
+    assert_eq!(report["rules"][0]["actionIds"], json!([8, 9]));
+}
+
+#[tokio::test]
+async fn test_users_set_active() {
+    let mock = MockOdooServer::start().await;
+    mock.mock_search_read("res.users", json!([{"id": 6, "login": "marc"}]))
+        .await;
+    mock.mock_write("res.users", true).await;
+
+    let report = run_pack_tool(
+        &mock,
+        "odoo_user_set_active",
+        json!({"login": "marc", "active": false, "confirm": true}),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(report["id"], 6);
+    assert_eq!(report["login"], "marc");
+    assert_eq!(report["active"], false);
+
+    let error = run_pack_tool(
+        &mock,
+        "odoo_user_set_active",
+        json!({"login": "marc", "active": false, "confirm": false}),
+    )
+    .await
+    .unwrap_err();
+    assert!(error.to_string().contains("confirm"));
+}
+
+#[tokio::test]
+async fn test_delivery_tracking() {
+    let mock = MockOdooServer::start().await;
+    mock.mock_search_read(
+        "stock.picking",
+        json!([{
+            "id": 21,
+            "name": "WH/OUT/00021",
+            "origin": "S00042",
+            "state": "done",
+            "partner_id": [3, "Deco Addict"],
+            "carrier_id": [1, "DHL"],
+            "carrier_tracking_ref": "1Z999",
+            "carrier_tracking_url": false,
+            "date_done": "2026-10-01 10:00:00"
+        }]),
+    )
+    .await;
+
+    let report = run_pack_tool(&mock, "odoo_delivery_tracking", json!({}))
+        .await
+        .unwrap();
+
+    assert_eq!(report["count"], 1);
+    assert_eq!(report["deliveries"][0]["trackingRef"], "1Z999");
+    assert_eq!(report["deliveries"][0]["trackingUrl"], Value::Null);
+}
+
+#[tokio::test]
+async fn test_timesheet_summary() {
+    let mock = MockOdooServer::start().await;
+    mock_read_group(
+        &mock,
+        "account.analytic.line",
+        json!({"groupby": ["project_id", "employee_id"]}),
+        json!([{"project_id": [3, "Website"], "employee_id": [1, "Ana"], "unit_amount": 12.5, "__count": 4}]),
+    )
+    .await;
+    mock_read_group(
+        &mock,
+        "account.analytic.line",
+        json!({"groupby": ["employee_id"]}),
+        json!([{"employee_id": [1, "Ana"], "unit_amount": 12.5, "__count": 4}]),
+    )
+    .await;
+    mock.mock_search_read(
+        "hr.employee",
+        json!([{"id": 1, "name": "Ana"}, {"id": 2, "name": "Ben"}]),
+    )
+    .await;
+
+    let report = run_pack_tool(
+        &mock,
+        "odoo_timesheet_summary",
+        json!({"dateFrom": "2026-10-05", "dateTo": "2026-10-09"}),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(report["workdays"], 5);
+    assert_eq!(report["totalHours"], 12.5);
+    assert_eq!(report["groups"][0]["project"], "Website");
+    assert_eq!(report["missing"][0]["employeeId"], 2);
+}
+
+#[tokio::test]
+async fn test_payroll_payslip_summary() {
+    let mock = MockOdooServer::start().await;
+    mock_read_group(
+        &mock,
+        "hr.payslip",
+        json!({"groupby": ["payslip_run_id", "state"]}),
+        json!([
+            {"payslip_run_id": [1, "October 2026"], "state": "draft", "__count": 3},
+            {"payslip_run_id": [1, "October 2026"], "state": "done", "__count": 7}
+        ]),
+    )
+    .await;
+
+    let report = run_pack_tool(&mock, "odoo_payslip_summary", json!({}))
+        .await
+        .unwrap();
+
+    assert_eq!(report["total"], 10);
+    assert_eq!(report["byStatus"]["done"], 7);
+    assert_eq!(report["amountsIncluded"], false);
+    assert_eq!(report["payslips"], Value::Null);
+}
+
+#[tokio::test]
+async fn test_survey_results() {
+    let mock = MockOdooServer::start().await;
+    mock.mock_read(
+        "survey.survey",
+        json!([{"id": 3, "title": "Customer feedback"}]),
+    )
+    .await;
+    mock.mock_search_read(
+        "survey.question",
+        json!([
+            {"id": 1, "title": "Rating", "question_type": "simple_choice"},
+            {"id": 2, "title": "Age", "question_type": "numerical_box"}
+        ]),
+    )
+    .await;
+    mock.mock_search_count("survey.user_input", 4).await;
+    mock_read_group(
+        &mock,
+        "survey.user_input.line",
+        json!({"fields": ["question_id"]}),
+        json!([
+            {"question_id": [1, "Rating"], "question_id_count": 4},
+            {"question_id": [2, "Age"], "question_id_count": 3}
+        ]),
+    )
+    .await;
+    mock_read_group(
+        &mock,
+        "survey.user_input.line",
+        json!({"fields": ["suggested_answer_id"]}),
+        json!([
+            {"question_id": [1, "Rating"], "suggested_answer_id": [10, "Good"], "__count": 1},
+            {"question_id": [1, "Rating"], "suggested_answer_id": [11, "Great"], "__count": 3}
+        ]),
+    )
+    .await;
+    mock_read_group(
+        &mock,
+        "survey.user_input.line",
+        json!({"fields": ["value_numerical_box:avg"]}),
+        json!([{"question_id": [2, "Age"], "question_id_count": 3, "value_numerical_box": 31.5}]),
+    )
+    .await;
+
+    let report = run_pack_tool(&mock, "odoo_survey_results", json!({"surveyId": 3}))
+        .await
+        .unwrap();
+
+    assert_eq!(report["title"], "Customer feedback");
+    assert_eq!(report["responses"], 4);
+    assert_eq!(report["questions"][0]["answerCount"], 4);
+    assert_eq!(report["questions"][0]["choices"][0]["answer"], "Great");
+    assert_eq!(report["questions"][1]["average"], 31.5);
+}
+
+#[tokio::test]
+async fn test_credit_exposure() {
+    let mock = MockOdooServer::start().await;
+    mock.mock_fields_get(
+        "res.partner",
+        json!({"display_name": {}, "credit_limit": {}}),
+    )
+    .await;
+    mock.mock_read(
+        "res.partner",
+        json!([{
+            "id": 3,
+            "display_name": "Deco Addict",
+            "credit_limit": 1000.0,
+            "commercial_partner_id": [3, "Deco Addict"]
+        }]),
+    )
+    .await;
+    mock.mock_fields_get("account.account", json!({"account_type": {}}))
+        .await;
+    mock_read_group(
+        &mock,
+        "account.move.line",
+        json!({"fields": ["amount_residual:sum"]}),
+        json!([{"amount_residual": 1200.0, "__count": 2}]),
+    )
+    .await;
+    mock.mock_search_read(
+        "account.move.line",
+        json!([{"id": 40, "date_maturity": "2026-08-31"}]),
+    )
+    .await;
+
+    let report = run_pack_tool(
+        &mock,
+        "odoo_partner_credit_exposure",
+        json!({"partnerId": 3}),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(report["partner"], "Deco Addict");
+    assert_eq!(report["totalReceivable"], 1200.0);
+    assert_eq!(report["oldestDueDate"], "2026-08-31");
+    assert_eq!(report["overLimit"], true);
+}
+
+#[tokio::test]
+async fn test_partner_360() {
+    let mock = MockOdooServer::start().await;
+    mock.mock_read(
+        "res.partner",
+        json!([{"id": 3, "name": "Deco Addict", "commercial_partner_id": [3, "Deco Addict"]}]),
+    )
+    .await;
+    mock.mock_search_read(
+        "sale.order",
+        json!([{"id": 42, "name": "S00042", "state": "sale"}]),
+    )
+    .await;
+    mock.mock_search_read("account.move", json!([])).await;
+    mock.mock_search_read("mail.activity", json!([])).await;
+    mock.mock_search_read("mail.message", json!([])).await;
+    mock.mock_error("helpdesk.ticket", "search_read", 404, "Model not found")
+        .await;
+
+    let report = run_pack_tool(&mock, "odoo_partner_360", json!({"partnerId": 3}))
+        .await
+        .unwrap();
+
+    assert_eq!(report["partner"]["name"], "Deco Addict");
+    assert_eq!(report["openOrders"]["count"], 1);
+    assert_eq!(report["openInvoices"]["count"], 0);
+    assert!(report["tickets"]["error"].is_string());
+}
+
+#[tokio::test]
+async fn test_order_360() {
+    let mock = MockOdooServer::start().await;
+    mock.mock_fields_get(
+        "sale.order",
+        json!({"name": {}, "state": {}, "picking_ids": {}, "invoice_ids": {}}),
+    )
+    .await;
+    mock.mock_read(
+        "sale.order",
+        json!([{"id": 42, "name": "S00042", "state": "sale", "picking_ids": [21], "invoice_ids": []}]),
+    )
+    .await;
+    mock.mock_search_read(
+        "sale.order.line",
+        json!([{"id": 1, "name": "Desk", "product_uom_qty": 2.0}]),
+    )
+    .await;
+    mock.mock_read(
+        "stock.picking",
+        json!([{"id": 21, "name": "WH/OUT/00021", "state": "done"}]),
+    )
+    .await;
+    mock.mock_search_read(
+        "mail.message",
+        json!([{"id": 7, "body": "<p>Shipped today</p>", "message_type": "comment"}]),
+    )
+    .await;
+
+    let report = run_pack_tool(&mock, "odoo_order_360", json!({"orderId": 42}))
+        .await
+        .unwrap();
+
+    assert_eq!(report["order"]["name"], "S00042");
+    assert!(report["order"].get("picking_ids").is_none());
+    assert_eq!(report["lines"]["count"], 1);
+    assert_eq!(report["deliveries"]["count"], 1);
+    assert_eq!(report["invoices"]["count"], 0);
+    assert_eq!(report["messages"]["records"][0]["preview"], "Shipped today");
+}
+
+#[tokio::test]
+async fn test_pricing_price_simulation() {
+    let mock = MockOdooServer::start().await;
+    mock.mock_read(
+        "product.product",
+        json!([{
+            "id": 6,
+            "display_name": "Desk",
+            "product_tmpl_id": [50, "Desk"],
+            "categ_id": false,
+            "lst_price": 100.0,
+            "standard_price": 60.0
+        }]),
+    )
+    .await;
+    mock.mock_read(
+        "product.pricelist",
+        json!([{"id": 1, "display_name": "Public Pricelist"}]),
+    )
+    .await;
+    mock.mock_search_read(
+        "product.pricelist.item",
+        json!([{
+            "id": 5,
+            "applied_on": "3_global",
+            "min_quantity": 0,
+            "compute_price": "fixed",
+            "fixed_price": 80.0
+        }]),
+    )
+    .await;
+
+    let report = run_pack_tool(
+        &mock,
+        "odoo_price_simulation",
+        json!({"productId": 6, "pricelistId": 1}),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(report["pricelist"], "Public Pricelist");
+    assert_eq!(report["source"], "emulated");
+    assert_eq!(report["price"], 80.0);
+    assert_eq!(report["ruleId"], 5);
+}
+
+#[tokio::test]
+async fn test_reconciliation_suggest_matches() {
+    let mock = MockOdooServer::start().await;
+    mock.mock_search_read(
+        "account.bank.statement.line",
+        json!([{
+            "id": 9,
+            "date": "2026-10-01",
+            "payment_ref": "INV/2026/00042",
+            "amount": 250.0,
+            "partner_id": [3, "Deco Addict"],
+            "journal_id": [7, "Bank"],
+            "move_id": [90, "BNK1/2026/00009"]
+        }]),
+    )
+    .await;
+    mock.mock_search_read(
+        "account.move.line",
+        json!([{
+            "id": 55,
+            "move_id": [42, "INV/2026/00042"],
+            "ref": false,
+            "date": "2026-09-15",
+            "date_maturity": "2026-10-15",
+            "partner_id": [3, "Deco Addict"],
+            "amount_residual": 250.0
+        }]),
+    )
+    .await;
+
+    let report = run_pack_tool(&mock, "odoo_bank_reconcile_suggest", json!({}))
+        .await
+        .unwrap();
+
+    assert_eq!(report["count"], 1);
+    assert_eq!(report["lines"][0]["statementLine"]["id"], 9);
+    assert_eq!(report["lines"][0]["candidates"][0]["moveLineId"], 55);
+}
+
+#[tokio::test]
+async fn test_analytic_rollup() {
+    let mock = MockOdooServer::start().await;
+    mock.mock_fields_get(
+        "account.analytic.line",
+        json!({"account_id": {}, "move_line_id": {}}),
+    )
+    .await;
+    mock.mock_fields_get("account.analytic.account", json!({"plan_id": {}}))
+        .await;
+    // Mounted first: `"groupby": []` below matches any groupby.
+    mock_read_group(
+        &mock,
+        "account.analytic.line",
+        json!({"groupby": ["account_id"]}),
+        json!([{"account_id": [1, "Project A"], "amount": -150.0, "unit_amount": 2.0, "__count": 3}]),
+    )
+    .await;
+    mock_read_group(
+        &mock,
+        "account.analytic.line",
+        json!({"groupby": []}),
+        json!([{"amount": -150.0, "__count": 3}]),
+    )
+    .await;
+    mock.mock_read(
+        "account.analytic.account",
+        json!([{"id": 1, "name": "Project A", "code": "PA", "plan_id": [2, "Projects"]}]),
+    )
+    .await;
+
+    let report = run_pack_tool(
+        &mock,
+        "odoo_analytic_rollup",
+        json!({"dateFrom": "2026-10-01", "dateTo": "2026-10-31"}),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(report["total"], -150.0);
+    assert_eq!(report["lineCount"], 3);
+    assert_eq!(report["accounts"][0]["code"], "PA");
+    assert_eq!(report["accounts"][0]["plan"], "Projects");
+}
+
+#[tokio::test]
+async fn test_inventory_valuation() {
+    let mock = MockOdooServer::start().await;
+    mock_read_group(
+        &mock,
+        "stock.valuation.layer",
+        json!({"groupby": ["product_id"]}),
+        json!([
+            {"product_id": [6, "Desk"], "quantity": 4.0, "value": 240.0, "__count": 2},
+            {"product_id": [7, "Chair"], "quantity": 10.0, "value": 150.0, "__count": 1}
+        ]),
+    )
+    .await;
+    mock.mock_read(
+        "product.product",
+        json!([
+            {"id": 6, "categ_id": [8, "Office"]},
+            {"id": 7, "categ_id": [8, "Office"]}
+        ]),
+    )
+    .await;
+    mock.mock_search_read("stock.valuation.layer", json!([]))
+        .await;
+
+    let report = run_pack_tool(&mock, "odoo_inventory_valuation", json!({}))
+        .await
+        .unwrap();
+
+    assert_eq!(report["totalValue"], 390.0);
+    assert_eq!(report["categories"][0]["category"], "Office");
+    assert_eq!(report["aging"]["truncated"], false);
+}
@@ -140,6 +140,7 @@ fn instances_with_overrides(odoo_url: &str) -> Value {
             "apiKey": "school-b-key",
             "version": "19",
             "toolConfig": {
+                "allowedTools": ["odoo_search*"],
                 "defaults": {
                     "odoo_search_read": {
                         "limit": 20,
@@ -208,7 +209,7 @@ async fn initialize_mcp_session(server: &TestServer) -> String {
         .to_string()
 }
 
-async fn tools_list(server: &TestServer, session_id: &str) -> Value {
+async fn tools_list(server: &TestServer, session_id: &str, params: Value) -> Value {
     let response = server
         .post("/mcp")
         .add_header(
@@ -219,7 +220,7 @@ async fn tools_list(server: &TestServer, session_id: &str) -> Value {
             "jsonrpc": "2.0",
             "id": 2,
             "method": "tools/list",
-            "params": {}
+            "params": params
         }))
         .await;
 
@@ -317,7 +318,7 @@ async fn test_per_instance_tool_config_hot_reloads_end_to_end() {
 
     let session_id = initialize_mcp_session(&mcp_server).await;
 
-    let tools_before = tools_list(&mcp_server, &session_id).await;
+    let tools_before = tools_list(&mcp_server, &session_id, json!({})).await;
     let tool_names_before = tools_before["result"]["tools"]
         .as_array()
         .unwrap()
@@ -345,7 +346,7 @@ async fn test_per_instance_tool_config_hot_reloads_end_to_end() {
         json!(20)
     );
 
-    let tools_after = tools_list(&mcp_server, &session_id).await;
+    let tools_after = tools_list(&mcp_server, &session_id, json!({})).await;
     let tool_names_after = tools_after["result"]["tools"]
         .as_array()
         .unwrap()
@@ -353,6 +354,16 @@ async fn test_per_instance_tool_config_hot_reloads_end_to_end() {
         .filter_map(|tool| tool.get("name").and_then(|value| value.as_str()))
         .collect::<Vec<_>>();
     assert!(tool_names_after.contains(&"odoo_search_read"));
+    for (instance, listed) in [("school-a", false), ("school-b", true)] {
+        let scoped = tools_list(&mcp_server, &session_id, json!({ "instance": instance })).await;
+        let names = scoped["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|tool| tool.get("name").and_then(|value| value.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(names.contains(&"odoo_search_read"), listed, "{instance}");
+    }
 
     let disabled_call = tools_call(
         &mcp_server,